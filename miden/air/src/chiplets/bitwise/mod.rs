@@ -3,7 +3,8 @@ use crate::utils::{are_equal, binary_not, is_binary, is_zero, EvaluationResult};
 use vm_core::chiplets::{
     bitwise::{NUM_DECOMP_BITS, NUM_SELECTORS, OP_CYCLE_LEN},
     BITWISE_A_COL_IDX, BITWISE_A_COL_RANGE, BITWISE_B_COL_IDX, BITWISE_B_COL_RANGE,
-    BITWISE_OUTPUT_COL_IDX, BITWISE_PREV_OUTPUT_COL_IDX, BITWISE_SELECTOR_COL_RANGE,
+    BITWISE_HIGH_LIMB_COL_IDX, BITWISE_OUTPUT_COL_IDX, BITWISE_PREV_OUTPUT_COL_IDX,
+    BITWISE_SELECTOR_COL_RANGE,
 };
 use winter_air::TransitionConstraintDegree;
 
@@ -14,7 +15,7 @@ pub mod tests;
 // ================================================================================================
 
 /// The number of transition constraints on the bitwise chiplet.
-pub const NUM_CONSTRAINTS: usize = 19;
+pub const NUM_CONSTRAINTS: usize = 24;
 
 // PERIODIC COLUMNS
 // ================================================================================================
@@ -63,6 +64,14 @@ pub fn get_transition_constraint_degrees() -> Vec<TransitionConstraintDegree> {
         TransitionConstraintDegree::with_cycles(3, vec![OP_CYCLE_LEN]),
         TransitionConstraintDegree::with_cycles(3, vec![OP_CYCLE_LEN]),
         TransitionConstraintDegree::new(6),
+        // High limb flag should be binary and remain the same throughout the cycle.
+        TransitionConstraintDegree::new(4),
+        TransitionConstraintDegree::with_cycles(3, vec![OP_CYCLE_LEN]),
+        // A cycle for the high limbs should be followed by a cycle for the low limbs of the same
+        // operation.
+        TransitionConstraintDegree::with_cycles(4, vec![OP_CYCLE_LEN]),
+        TransitionConstraintDegree::with_cycles(4, vec![OP_CYCLE_LEN]),
+        TransitionConstraintDegree::with_cycles(4, vec![OP_CYCLE_LEN]),
     ];
 
     degrees.into()
@@ -88,7 +97,10 @@ pub fn enforce_constraints<E: FieldElement>(
         enforce_input_decomposition(frame, periodic_values, &mut result[index..], bitwise_flag);
 
     // Enforce that the operation result is aggregated into the output column correctly.
-    enforce_output_aggregation(frame, periodic_values, &mut result[index..], bitwise_flag);
+    index += enforce_output_aggregation(frame, periodic_values, &mut result[index..], bitwise_flag);
+
+    // Enforce that operations on 64-bit values are computed in coupled cycles.
+    enforce_high_limb_flag(frame, periodic_values, &mut result[index..], bitwise_flag);
 }
 
 /// Constraint evaluation function to enforce that the Bitwise internal selector columns
@@ -244,6 +256,50 @@ fn enforce_output_aggregation<E: FieldElement>(
     constraint_offset
 }
 
+/// Enforces that operations on 64-bit values are computed in two coupled cycles, where the cycle
+/// for the high 32-bit limbs is immediately followed by the cycle for the low 32-bit limbs. This
+/// requires the following constraints:
+/// - The high limb flag must be binary and remain the same throughout the cycle.
+/// - When transitioning from a cycle for the high limbs, the next cycle must not be for the high
+///   limbs, and the operation selectors must stay the same.
+///
+/// The lookup for the 64-bit result is provided from the last row of the cycle for the low limbs,
+/// and the values of the high limbs are taken from the last row of the preceding cycle.
+fn enforce_high_limb_flag<E: FieldElement>(
+    frame: &EvaluationFrame<E>,
+    periodic_values: &[E],
+    result: &mut [E],
+    processor_flag: E,
+) -> usize {
+    let k1 = periodic_values[1];
+    let mut constraint_offset = 0;
+
+    // The high limb flag must be binary.
+    result[constraint_offset] = processor_flag * is_binary(frame.high_limb());
+    constraint_offset += 1;
+
+    // The high limb flag should stay the same for the entire cycle.
+    result[constraint_offset] = processor_flag * k1 * (frame.high_limb() - frame.high_limb_next());
+    constraint_offset += 1;
+
+    // When transitioning from the last row of a cycle for the high limbs, the next cycle must be
+    // for the low limbs of the same operation.
+    let pair_flag = processor_flag * binary_not(k1) * frame.high_limb();
+    result[constraint_offset] = pair_flag * frame.high_limb_next();
+    constraint_offset += 1;
+
+    for (idx, result) in result[constraint_offset..]
+        .iter_mut()
+        .enumerate()
+        .take(NUM_SELECTORS)
+    {
+        *result = pair_flag * (frame.selector(idx) - frame.selector_next(idx));
+    }
+    constraint_offset += NUM_SELECTORS;
+
+    constraint_offset
+}
+
 /// Calculates the result of bitwise AND applied to the decomposed values provided as a bit array.
 /// The result will be the AND of the first 4 bits in the provided array with the latter 4 bits.
 pub fn bitwise_and<E: FieldElement>(decomposed_values: &[E]) -> E {
@@ -313,6 +369,10 @@ trait EvaluationFrameExt<E: FieldElement> {
     fn output_prev_next(&self) -> E;
     /// Gets the value of the aggregated output in the current row.
     fn output(&self) -> E;
+    /// Gets the current value of the high limb flag.
+    fn high_limb(&self) -> E;
+    /// Gets the value of the high limb flag in the next row.
+    fn high_limb_next(&self) -> E;
 
     // --- Intermediate variables & helpers -------------------------------------------------------
     /// The aggregated value of the decomposed bits from `a` in the current row.
@@ -385,6 +445,14 @@ impl<E: FieldElement> EvaluationFrameExt<E> for &EvaluationFrame<E> {
     fn output(&self) -> E {
         self.current()[BITWISE_OUTPUT_COL_IDX]
     }
+    #[inline(always)]
+    fn high_limb(&self) -> E {
+        self.current()[BITWISE_HIGH_LIMB_COL_IDX]
+    }
+    #[inline(always)]
+    fn high_limb_next(&self) -> E {
+        self.next()[BITWISE_HIGH_LIMB_COL_IDX]
+    }
 
     // --- Intermediate variables & helpers -------------------------------------------------------
     #[inline(always)]
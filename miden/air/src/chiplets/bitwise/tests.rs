@@ -1,8 +1,8 @@
 use super::{
     enforce_constraints, get_periodic_values, EvaluationFrame, BITWISE_A_COL_IDX,
-    BITWISE_A_COL_RANGE, BITWISE_B_COL_IDX, BITWISE_B_COL_RANGE, BITWISE_OUTPUT_COL_IDX,
-    BITWISE_PREV_OUTPUT_COL_IDX, BITWISE_SELECTOR_COL_RANGE, NUM_CONSTRAINTS, NUM_DECOMP_BITS,
-    NUM_SELECTORS, OP_CYCLE_LEN,
+    BITWISE_A_COL_RANGE, BITWISE_B_COL_IDX, BITWISE_B_COL_RANGE, BITWISE_HIGH_LIMB_COL_IDX,
+    BITWISE_OUTPUT_COL_IDX, BITWISE_PREV_OUTPUT_COL_IDX, BITWISE_SELECTOR_COL_RANGE,
+    NUM_CONSTRAINTS, NUM_DECOMP_BITS, NUM_SELECTORS, OP_CYCLE_LEN,
 };
 use rand_utils::rand_value;
 use vm_core::{
@@ -57,6 +57,63 @@ fn test_bitwise_change_ops_fail() {
     assert_eq!(result[4..], expected[4..]);
 }
 
+/// Tests that the constraints on the high limb flag do not evaluate to zero if the flag changes
+/// within a cycle.
+#[test]
+fn test_bitwise_change_high_limb_fail() {
+    let expected = [Felt::ZERO; NUM_CONSTRAINTS];
+
+    let a = rand_value::<u32>();
+    let b = rand_value::<u32>();
+    let cycle_row: usize = rand_value::<u8>() as usize % (OP_CYCLE_LEN - 1);
+
+    // the flag is set for the entire cycle, so all constraints should evaluate to zero.
+    let mut frame = get_test_frame(BITWISE_AND, a, b, cycle_row);
+    frame.current_mut()[BITWISE_HIGH_LIMB_COL_IDX] = Felt::ONE;
+    frame.next_mut()[BITWISE_HIGH_LIMB_COL_IDX] = Felt::ONE;
+    let result = get_constraint_evaluation(frame, cycle_row);
+    assert_eq!(expected, result);
+
+    // the flag changes within the cycle, so that constraint should fail.
+    let mut frame = get_test_frame(BITWISE_AND, a, b, cycle_row);
+    frame.current_mut()[BITWISE_HIGH_LIMB_COL_IDX] = Felt::ONE;
+    let result = get_constraint_evaluation(frame, cycle_row);
+    assert_ne!(result[20], expected[20]);
+
+    // All other constraints should evaluate to zero.
+    assert_eq!(result[..20], expected[..20]);
+    assert_eq!(result[21..], expected[21..]);
+}
+
+/// Tests that the constraints on the transition from a cycle for the high limbs of an operation on
+/// 64-bit values require the next cycle to be for the low limbs of the same operation.
+#[test]
+fn test_bitwise_high_limb_transition() {
+    let expected = [Felt::ZERO; NUM_CONSTRAINTS];
+    // periodic values for the transition between cycles are returned for this row.
+    let last_row = OP_CYCLE_LEN;
+
+    // the cycle for the high limbs is followed by the cycle for the low limbs of the same operation.
+    let frame = get_cycle_transition_frame(BITWISE_XOR, BITWISE_XOR, Felt::ZERO);
+    let result = get_constraint_evaluation(frame, last_row);
+    assert_eq!(expected, result);
+
+    // the cycle for the high limbs is followed by another cycle for high limbs.
+    let frame = get_cycle_transition_frame(BITWISE_XOR, BITWISE_XOR, Felt::ONE);
+    let result = get_constraint_evaluation(frame, last_row);
+    assert_ne!(result[21], expected[21]);
+    assert_eq!(result[..21], expected[..21]);
+    assert_eq!(result[22..], expected[22..]);
+
+    // the cycle for the high limbs is followed by the cycle for the low limbs of an operation with
+    // a different first selector.
+    let frame = get_cycle_transition_frame(BITWISE_XOR, BITWISE_AND, Felt::ZERO);
+    let result = get_constraint_evaluation(frame, last_row);
+    assert_ne!(result[22], expected[22]);
+    assert_eq!(result[..22], expected[..22]);
+    assert_eq!(result[23..], expected[23..]);
+}
+
 // RANDOMIZED TESTS
 // ================================================================================================
 
@@ -159,6 +216,27 @@ pub fn get_test_frame(
     EvaluationFrame::<Felt>::from_rows(current, next)
 }
 
+/// Generates a frame for the transition from the last row of a cycle for the high limbs of an
+/// operation on 64-bit values to the first row of the next cycle. The inputs of both cycles are
+/// set to ZERO.
+fn get_cycle_transition_frame(
+    op_current: Selectors,
+    op_next: Selectors,
+    high_limb_next: Felt,
+) -> EvaluationFrame<Felt> {
+    let mut current = vec![Felt::ZERO; TRACE_WIDTH];
+    let mut next = vec![Felt::ZERO; TRACE_WIDTH];
+
+    for idx in 0..NUM_SELECTORS {
+        current[BITWISE_SELECTOR_COL_RANGE.start + idx] = op_current[idx];
+        next[BITWISE_SELECTOR_COL_RANGE.start + idx] = op_next[idx];
+    }
+    current[BITWISE_HIGH_LIMB_COL_IDX] = Felt::ONE;
+    next[BITWISE_HIGH_LIMB_COL_IDX] = high_limb_next;
+
+    EvaluationFrame::<Felt>::from_rows(current, next)
+}
+
 /// Generates the current and next rows for the provided inputs, current cycle row number, and the
 /// operations specified for each row, and returns an EvaluationFrame for testing. It only tests
 /// frames within a cycle.
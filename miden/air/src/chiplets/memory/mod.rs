@@ -1,8 +1,8 @@
 use super::{EvaluationFrame, FieldElement, Vec};
 use crate::utils::{binary_not, is_binary, EvaluationResult};
 use vm_core::chiplets::memory::{
-    ADDR_COL_IDX, CLK_COL_IDX, CTX_COL_IDX, D0_COL_IDX, D1_COL_IDX, D_INV_COL_IDX, NUM_ELEMENTS,
-    U_COL_RANGE, V_COL_RANGE,
+    ADDR_COL_IDX, CLK_COL_IDX, CTX_COL_IDX, D0_COL_IDX, D1_COL_IDX, D2_COL_IDX, D_INV_COL_IDX,
+    NUM_ELEMENTS, U_COL_RANGE, V_COL_RANGE,
 };
 use winter_air::TransitionConstraintDegree;
 
@@ -81,7 +81,8 @@ fn enforce_d_inv<E: FieldElement>(
 }
 
 /// A constraint evaluation function to enforce that the delta between two consecutive context IDs,
-/// addresses, or clock cycles is updated and decomposed into the `d1` and `d0` columns correctly.
+/// addresses, or clock cycles is updated and decomposed into the `d2`, `d1`, and `d0` columns
+/// correctly.
 fn enforce_delta<E: FieldElement>(
     frame: &EvaluationFrame<E>,
     result: &mut [E],
@@ -153,9 +154,12 @@ trait EvaluationFrameExt<E: FieldElement> {
     /// The next value of the lower 16-bits of the delta value being tracked between two consecutive
     /// context IDs, addresses, or clock cycles.
     fn d0_next(&self) -> E;
-    /// The next value of the upper 16-bits of the delta value being tracked between two consecutive
-    /// context IDs, addresses, or clock cycles.
+    /// The next value of the upper 16-bits of the lower 32-bit limb of the delta value being
+    /// tracked between two consecutive context IDs, addresses, or clock cycles.
     fn d1_next(&self) -> E;
+    /// The next value of the upper 16-bit limb of the delta value being tracked between two
+    /// consecutive context IDs, addresses, or clock cycles.
+    fn d2_next(&self) -> E;
     /// The next value of the column tracking the inverse delta used for constraint evaluations.
     fn d_inv_next(&self) -> E;
 
@@ -232,6 +236,10 @@ impl<E: FieldElement> EvaluationFrameExt<E> for &EvaluationFrame<E> {
         self.next()[D1_COL_IDX]
     }
     #[inline(always)]
+    fn d2_next(&self) -> E {
+        self.next()[D2_COL_IDX]
+    }
+    #[inline(always)]
     fn d_inv_next(&self) -> E {
         self.next()[D_INV_COL_IDX]
     }
@@ -280,7 +288,9 @@ impl<E: FieldElement> EvaluationFrameExt<E> for &EvaluationFrame<E> {
 
     #[inline(always)]
     fn delta_next(&self) -> E {
-        E::from(2_u32.pow(16)) * self.d1_next() + self.d0_next()
+        E::from(2_u64.pow(32)) * self.d2_next()
+            + E::from(2_u32.pow(16)) * self.d1_next()
+            + self.d0_next()
     }
 
     // --- Flags ----------------------------------------------------------------------------------
@@ -306,9 +316,12 @@ pub trait MemoryFrameExt<E: FieldElement> {
     /// The value of the lower 16-bits of the delta value being tracked between two consecutive
     /// context IDs, addresses, or clock cycles in the current row.
     fn memory_d0(&self) -> E;
-    /// The value of the upper 16-bits of the delta value being tracked between two consecutive
-    /// context IDs, addresses, or clock cycles in the current row.
+    /// The value of the upper 16-bits of the lower 32-bit limb of the delta value being tracked
+    /// between two consecutive context IDs, addresses, or clock cycles in the current row.
     fn memory_d1(&self) -> E;
+    /// The value of the upper 16-bit limb of the delta value being tracked between two consecutive
+    /// context IDs, addresses, or clock cycles in the current row.
+    fn memory_d2(&self) -> E;
 }
 
 impl<E: FieldElement> MemoryFrameExt<E> for &EvaluationFrame<E> {
@@ -322,4 +335,8 @@ impl<E: FieldElement> MemoryFrameExt<E> for &EvaluationFrame<E> {
     fn memory_d1(&self) -> E {
         self.current()[D1_COL_IDX]
     }
+    #[inline(always)]
+    fn memory_d2(&self) -> E {
+        self.current()[D2_COL_IDX]
+    }
 }
@@ -1,6 +1,6 @@
 use super::{
-    EvaluationFrame, ADDR_COL_IDX, CLK_COL_IDX, CTX_COL_IDX, D0_COL_IDX, D1_COL_IDX, D_INV_COL_IDX,
-    NUM_ELEMENTS, U_COL_RANGE, V_COL_RANGE,
+    EvaluationFrame, ADDR_COL_IDX, CLK_COL_IDX, CTX_COL_IDX, D0_COL_IDX, D1_COL_IDX, D2_COL_IDX,
+    D_INV_COL_IDX, NUM_ELEMENTS, U_COL_RANGE, V_COL_RANGE,
 };
 use crate::{chiplets::memory, Felt, FieldElement};
 use vm_core::TRACE_WIDTH;
@@ -117,6 +117,7 @@ fn get_test_frame(
     // Set the delta and delta inverse values. Treat the current row as if it's the first row.
     current[D0_COL_IDX] = Felt::ZERO;
     current[D1_COL_IDX] = Felt::ZERO;
+    current[D2_COL_IDX] = Felt::ZERO;
     current[D_INV_COL_IDX] = Felt::ZERO;
 
     // Set the delta in the next row according to the specified delta type.
@@ -126,7 +127,8 @@ fn get_test_frame(
         MemoryTestDeltaType::Address => delta_row[MemoryTestDeltaType::Address as usize],
     };
     next[D0_COL_IDX] = Felt::new(delta as u16 as u64);
-    next[D1_COL_IDX] = Felt::new(delta >> 16);
+    next[D1_COL_IDX] = Felt::new((delta >> 16) as u16 as u64);
+    next[D2_COL_IDX] = Felt::new(delta >> 32);
     next[D_INV_COL_IDX] = (Felt::new(delta)).inv();
 
     EvaluationFrame::<Felt>::from_rows(current, next)
@@ -140,7 +142,8 @@ fn get_test_frame(
 /// - When the delta type is Address, the context must remain unchanged but the clock can change.
 /// - When the delta type is Clock, both the context and address columns must remain unchanged.
 fn get_test_delta_row(delta_type: &MemoryTestDeltaType) -> Vec<u64> {
-    let delta_value = rand_value::<u32>() as u64;
+    // deltas can be up to 48 bits wide
+    let delta_value = rand_value::<u64>() >> 16;
     let mut row = vec![0; 3];
     let ctx_idx = MemoryTestDeltaType::Context as usize;
     let addr_idx = MemoryTestDeltaType::Address as usize;
@@ -6,6 +6,7 @@ mod bitwise;
 mod hasher;
 mod memory;
 pub use memory::MemoryFrameExt;
+mod padding;
 
 // CONSTANTS
 // ================================================================================================
@@ -48,6 +49,8 @@ pub fn get_transition_constraint_degrees() -> Vec<TransitionConstraintDegree> {
 
     degrees.append(&mut memory::get_transition_constraint_degrees());
 
+    degrees.append(&mut padding::get_transition_constraint_degrees());
+
     degrees
 }
 
@@ -57,6 +60,7 @@ pub fn get_transition_constraint_count() -> usize {
         + hasher::get_transition_constraint_count()
         + bitwise::get_transition_constraint_count()
         + memory::get_transition_constraint_count()
+        + padding::get_transition_constraint_count()
 }
 
 /// Returns the boundary assertions for the chiplets at the first step.
@@ -99,6 +103,14 @@ pub fn enforce_constraints<E: FieldElement<BaseField = Felt>>(
         &mut result[constraint_offset..],
         frame.memory_flag(false),
     );
+    constraint_offset += memory::get_transition_constraint_count();
+
+    // padding transition constraints
+    padding::enforce_constraints(
+        frame,
+        &mut result[constraint_offset..],
+        frame.padding_flag(),
+    );
 }
 
 // TRANSITION CONSTRAINT HELPERS
@@ -134,7 +146,8 @@ fn enforce_selectors<E: FieldElement>(frame: &EvaluationFrame<E>, result: &mut [
 // ================================================================================================
 
 /// Trait to allow easy access to column values and intermediate variables used in constraint
-/// calculations for the Chiplets module and its Hasher, Bitwise, and Memory chiplets.
+/// calculations for the Chiplets module, its Hasher, Bitwise, and Memory chiplets, and the padding
+/// segment.
 trait EvaluationFrameExt<E: FieldElement> {
     // --- Column accessors -----------------------------------------------------------------------
 
@@ -160,6 +173,9 @@ trait EvaluationFrameExt<E: FieldElement> {
     /// transition constraints with `include_last_row = false`, they will not be applied to the
     /// final row of the memory trace.
     fn memory_flag(&self, include_last_row: bool) -> E;
+
+    /// Flag to indicate whether the frame is in the padding segment of the Chiplets trace.
+    fn padding_flag(&self) -> E;
 }
 
 impl<E: FieldElement> EvaluationFrameExt<E> for &EvaluationFrame<E> {
@@ -191,6 +207,10 @@ impl<E: FieldElement> EvaluationFrameExt<E> for &EvaluationFrame<E> {
             self.s(0) * self.s(1) * binary_not(self.s_next(2))
         }
     }
+    #[inline(always)]
+    fn padding_flag(&self) -> E {
+        self.s(0) * self.s(1) * self.s(2)
+    }
 }
 
 // EXTERNAL ACCESSORS
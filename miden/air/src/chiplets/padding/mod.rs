@@ -0,0 +1,45 @@
+use super::{EvaluationFrame, FieldElement, Vec};
+use vm_core::chiplets::{PADDING_TRACE_OFFSET, PADDING_TRACE_WIDTH};
+use winter_air::TransitionConstraintDegree;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// The number of constraints on the padding segment of the chiplets trace.
+pub const NUM_CONSTRAINTS: usize = PADDING_TRACE_WIDTH;
+/// The degree of all constraints on the padding segment of the chiplets trace.
+pub const CONSTRAINT_DEGREE: usize = 4;
+
+// PADDING TRANSITION CONSTRAINTS
+// ================================================================================================
+
+/// Builds the transition constraint degrees for the padding segment.
+pub fn get_transition_constraint_degrees() -> Vec<TransitionConstraintDegree> {
+    (0..NUM_CONSTRAINTS)
+        .map(|_| TransitionConstraintDegree::new(CONSTRAINT_DEGREE))
+        .collect()
+}
+
+/// Returns the number of transition constraints for the padding segment.
+pub fn get_transition_constraint_count() -> usize {
+    NUM_CONSTRAINTS
+}
+
+/// Enforces that all non-selector columns of the padding segment are set to ZERO.
+///
+/// No chiplet flag is set in padding rows, and thus, constraints of the individual chiplets are
+/// trivially satisfied there. The constraints enforced here only ensure that the padding rows
+/// don't carry any data.
+pub fn enforce_constraints<E: FieldElement>(
+    frame: &EvaluationFrame<E>,
+    result: &mut [E],
+    padding_flag: E,
+) {
+    let row = &frame.current()[PADDING_TRACE_OFFSET..PADDING_TRACE_OFFSET + PADDING_TRACE_WIDTH];
+    for (result, &value) in result.iter_mut().zip(row) {
+        *result = padding_flag * value;
+    }
+}
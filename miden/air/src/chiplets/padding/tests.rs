@@ -0,0 +1,61 @@
+use super::{enforce_constraints, EvaluationFrame, NUM_CONSTRAINTS, PADDING_TRACE_OFFSET};
+use rand_utils::rand_value;
+use vm_core::{Felt, FieldElement, TRACE_WIDTH};
+
+// UNIT TESTS
+// ================================================================================================
+
+#[test]
+fn test_padding_row() {
+    let expected = [Felt::ZERO; NUM_CONSTRAINTS];
+
+    let frame = get_test_frame(None);
+    let result = get_constraint_evaluation(frame, Felt::ONE);
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn test_padding_row_with_data_fail() {
+    let expected = [Felt::ZERO; NUM_CONSTRAINTS];
+    let col_idx = rand_value::<u8>() as usize % NUM_CONSTRAINTS;
+
+    let frame = get_test_frame(Some(col_idx));
+
+    // Only the constraint on the non-zero column should fail.
+    let result = get_constraint_evaluation(frame, Felt::ONE);
+    assert_ne!(result[col_idx], expected[col_idx]);
+    assert_eq!(result[..col_idx], expected[..col_idx]);
+    assert_eq!(result[col_idx + 1..], expected[col_idx + 1..]);
+
+    // The constraints don't apply outside the padding segment.
+    let frame = get_test_frame(Some(col_idx));
+    let result = get_constraint_evaluation(frame, Felt::ZERO);
+    assert_eq!(expected, result);
+}
+
+// TEST HELPERS
+// ================================================================================================
+
+/// Returns the result of padding constraint evaluations on the provided frame with the specified
+/// padding flag.
+fn get_constraint_evaluation(frame: EvaluationFrame<Felt>, flag: Felt) -> [Felt; NUM_CONSTRAINTS] {
+    let mut result = [Felt::ZERO; NUM_CONSTRAINTS];
+
+    enforce_constraints(&frame, &mut result, flag);
+
+    result
+}
+
+/// Generates a frame where all padding columns of the current row are ZERO, except for the column
+/// at the specified index (relative to the start of the padding trace), which is set to a random
+/// non-zero value.
+fn get_test_frame(non_zero_col: Option<usize>) -> EvaluationFrame<Felt> {
+    let mut current = vec![Felt::ZERO; TRACE_WIDTH];
+    let next = vec![Felt::ZERO; TRACE_WIDTH];
+
+    if let Some(col_idx) = non_zero_col {
+        current[PADDING_TRACE_OFFSET + col_idx] = Felt::new(rand_value::<u32>() as u64 + 1);
+    }
+
+    EvaluationFrame::<Felt>::from_rows(current, next)
+}
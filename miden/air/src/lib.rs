@@ -6,7 +6,7 @@ extern crate alloc;
 
 use serde::{Deserialize, Serialize};
 use vm_core::{
-    chiplets::hasher::Digest,
+    chiplets::hasher::{self, Digest},
     utils::{collections::Vec, ByteWriter, Serializable},
     ExtensionOf, CLK_COL_IDX, FMP_COL_IDX, MIN_STACK_DEPTH, STACK_TRACE_OFFSET,
 };
@@ -44,6 +44,17 @@ impl ProcessorAir {
     pub fn last_step(&self) -> usize {
         self.trace_length() - self.context().num_transition_exemptions()
     }
+
+    /// Returns the smallest blowup factor which accommodates the degrees of the transition
+    /// constraints of the processor; the AIR cannot be instantiated with a smaller blowup factor.
+    pub fn min_blowup_factor() -> usize {
+        range::get_transition_constraint_degrees()
+            .iter()
+            .chain(chiplets::get_transition_constraint_degrees().iter())
+            .chain(range::get_aux_transition_constraint_degrees().iter())
+            .map(TransitionConstraintDegree::min_blowup_factor)
+            .fold(1, usize::max)
+    }
 }
 
 impl Air for ProcessorAir {
@@ -73,11 +84,18 @@ impl Air for ProcessorAir {
             chiplets::get_transition_constraint_count(),
         );
 
+        // an output commitment is asserted on top of the stack at the last step in place of the
+        // outputs it commits to
+        let stack_outputs = match &pub_inputs.output_commitment {
+            Some(commitment) => commitment.as_elements().to_vec(),
+            None => pub_inputs.stack_outputs,
+        };
+
         // Define the number of boundary constraints for the main execution trace segment.
         // TODO: determine dynamically
         let num_main_assertions = 2
             + pub_inputs.stack_inputs.len()
-            + pub_inputs.stack_outputs.len()
+            + stack_outputs.len()
             + range::NUM_ASSERTIONS
             + chiplets::NUM_ASSERTIONS;
 
@@ -100,7 +118,7 @@ impl Air for ProcessorAir {
         Self {
             context,
             stack_inputs: pub_inputs.stack_inputs,
-            stack_outputs: pub_inputs.stack_outputs,
+            stack_outputs,
             constraint_ranges,
         }
     }
@@ -225,11 +243,36 @@ impl Air for ProcessorAir {
 // PUBLIC INPUTS
 // ================================================================================================
 
+/// Tag which precedes the output commitment in serialized public inputs. Tags 1 and 2 are
+/// reserved: they preceded commitments to a seed of pseudo-random words and to the stack overflow
+/// values, neither of which the AIR constrained.
+const OUTPUT_COMMITMENT_TAG: u8 = 3;
+
+/// Size of the optional public input extension in bytes.
+#[cfg(feature = "pub-input-extension")]
+pub const PUB_INPUT_EXTENSION_SIZE: usize = 32;
+
+/// Tag which precedes the length-prefixed extension in serialized public inputs.
+#[cfg(feature = "pub-input-extension")]
+const EXTENSION_TAG: u8 = 4;
+
+/// Bit which is set in the flags of the elements of public inputs with an extension.
+#[cfg(feature = "pub-input-extension")]
+const EXTENSION_FLAG: u64 = 1 << EXTENSION_TAG;
+
+/// Number of elements public inputs are laid out as (see [PublicInputs::to_elements()]): the flags,
+/// the program hash, the number of stack inputs and the 16 stack inputs, the number of stack
+/// outputs and the 16 stack outputs, the output commitment, and the extension as 8 words.
+pub const NUM_PUB_INPUT_ELEMENTS: usize = 1 + 4 + (1 + MIN_STACK_DEPTH) * 2 + 4 + 8;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PublicInputs {
     program_hash: Digest,
     stack_inputs: Vec<Felt>,
     stack_outputs: Vec<Felt>,
+    output_commitment: Option<Digest>,
+    #[cfg(feature = "pub-input-extension")]
+    extension: Option<[u8; PUB_INPUT_EXTENSION_SIZE]>,
 }
 
 impl PublicInputs {
@@ -247,7 +290,135 @@ impl PublicInputs {
             program_hash,
             stack_inputs,
             stack_outputs,
+            output_commitment: None,
+            #[cfg(feature = "pub-input-extension")]
+            extension: None,
+        }
+    }
+
+    /// Returns the hash of the program the execution of which is attested by these inputs.
+    pub fn program_hash(&self) -> &Digest {
+        &self.program_hash
+    }
+
+    /// Returns the values at the top of the stack at the start of the execution, with the value
+    /// at the top of the stack in the first position.
+    pub fn stack_inputs(&self) -> &[Felt] {
+        &self.stack_inputs
+    }
+
+    /// Returns the values at the top of the stack at the end of the execution, with the value at
+    /// the top of the stack in the first position; the values are empty if these inputs are bound
+    /// to a commitment to the outputs instead.
+    pub fn stack_outputs(&self) -> &[Felt] {
+        &self.stack_outputs
+    }
+
+    /// Returns these public inputs bound to the specified commitment to the outputs of the
+    /// program.
+    ///
+    /// Programs which produce more outputs than fit on the stack, e.g., outputs derived from
+    /// values read from the advice provider, may hash their outputs and leave only the resulting
+    /// commitment on top of the stack. The commitment is then asserted against the top four stack
+    /// registers at the last step, with its first element at the top of the stack, so that the
+    /// verifier needs to know only the commitment to bind the outputs to the proof.
+    ///
+    /// # Panics
+    /// Panics if these public inputs include stack outputs, since the stack outputs would be
+    /// asserted against the same registers as the commitment.
+    pub fn with_output_commitment(mut self, commitment: Digest) -> Self {
+        assert!(
+            self.stack_outputs.is_empty(),
+            "stack outputs cannot be combined with an output commitment"
+        );
+        self.output_commitment = Some(commitment);
+        self
+    }
+
+    /// Returns the commitment to the outputs of the program, if any.
+    pub fn output_commitment(&self) -> Option<&Digest> {
+        self.output_commitment.as_ref()
+    }
+
+    /// Returns these public inputs extended with the specified bytes.
+    ///
+    /// The extension is an arbitrary commitment supplied by the party requesting the proof (e.g.,
+    /// a context or fee commitment supplied by an L1 contract). It is mixed into the seed of the
+    /// public coin, and thus, a proof generated against one extension does not verify against any
+    /// other extension, or against public inputs without an extension.
+    #[cfg(feature = "pub-input-extension")]
+    pub fn with_extension(mut self, extension: [u8; PUB_INPUT_EXTENSION_SIZE]) -> Self {
+        self.extension = Some(extension);
+        self
+    }
+
+    /// Returns the extension of these public inputs, if any.
+    #[cfg(feature = "pub-input-extension")]
+    pub fn extension(&self) -> Option<&[u8; PUB_INPUT_EXTENSION_SIZE]> {
+        self.extension.as_ref()
+    }
+
+    /// Returns the canonical hash of these public inputs, i.e., the hash of their elements (see
+    /// [PublicInputs::to_elements()]) computed with the hash function of the VM.
+    ///
+    /// Two public inputs have the same hash exactly if the AIR binds proofs to the same statement
+    /// for both of them. The hash is cheap to recompute in a recursive verifier or in a Miden
+    /// program (via the `hperm` operation), which makes it suitable to identify the statement of a
+    /// proof in external systems.
+    pub fn hash(&self) -> Digest {
+        hasher::hash_elements(&self.to_elements())
+    }
+}
+
+impl ToElements for PublicInputs {
+    /// Returns these public inputs laid out as [NUM_PUB_INPUT_ELEMENTS] field elements:
+    /// - the flags, in which bit 3 is set if there is an output commitment, and bit 4 is set if
+    ///   there is an extension;
+    /// - the program hash;
+    /// - the number of stack inputs, followed by the stack inputs with the top of the stack first;
+    /// - the number of stack outputs, followed by the stack outputs with the top of the stack
+    ///   first;
+    /// - the output commitment;
+    /// - the extension as 8 little-endian 32-bit words.
+    ///
+    /// Missing stack values, the missing commitment, and the missing extension are set to ZERO. The number of
+    /// stack values is included since the AIR asserts only the provided values, and thus, e.g.,
+    /// outputs `[1]` and `[1, 0]` bind a proof to different statements.
+    fn to_elements(&self) -> Vec<Felt> {
+        let flags = match self.output_commitment {
+            Some(_) => 1 << OUTPUT_COMMITMENT_TAG,
+            None => 0u64,
+        };
+        #[cfg(feature = "pub-input-extension")]
+        let flags = match self.extension {
+            Some(_) => flags | EXTENSION_FLAG,
+            None => flags,
+        };
+
+        let mut elements = Vec::with_capacity(NUM_PUB_INPUT_ELEMENTS);
+        elements.push(Felt::new(flags));
+        elements.extend_from_slice(self.program_hash.as_elements());
+        for values in [&self.stack_inputs, &self.stack_outputs] {
+            elements.push(Felt::new(values.len() as u64));
+            elements.extend_from_slice(values);
+            elements.resize(elements.len() + MIN_STACK_DEPTH - values.len(), Felt::ZERO);
+        }
+        match &self.output_commitment {
+            Some(commitment) => elements.extend_from_slice(commitment.as_elements()),
+            None => elements.extend_from_slice(&[Felt::ZERO; 4]),
         }
+
+        let mut extension_words = [Felt::ZERO; 8];
+        #[cfg(feature = "pub-input-extension")]
+        if let Some(extension) = &self.extension {
+            for (word, bytes) in extension_words.iter_mut().zip(extension.chunks(4)) {
+                *word = Felt::new(u32::from_le_bytes(bytes.try_into().unwrap()) as u64);
+            }
+        }
+        elements.extend_from_slice(&extension_words);
+
+        debug_assert_eq!(NUM_PUB_INPUT_ELEMENTS, elements.len());
+        elements
     }
 }
 
@@ -256,5 +427,74 @@ impl Serializable for PublicInputs {
         target.write(self.program_hash.as_elements());
         target.write(self.stack_inputs.as_slice());
         target.write(self.stack_outputs.as_slice());
+
+        // the commitment is prefixed with a tag so that it cannot be confused with an extension;
+        // public inputs without a commitment are serialized in the same way as before
+        if let Some(commitment) = &self.output_commitment {
+            target.write_u8(OUTPUT_COMMITMENT_TAG);
+            target.write(commitment.as_elements());
+        }
+
+        // public inputs without an extension are serialized in the same way regardless of whether
+        // the extension is supported, so that proofs without an extension remain compatible; the
+        // extension is tagged and prefixed with its length, so that its bytes cannot be confused
+        // with stack values or commitments
+        #[cfg(feature = "pub-input-extension")]
+        if let Some(extension) = &self.extension {
+            target.write_u8(EXTENSION_TAG);
+            target.write_u32(extension.len() as u32);
+            target.write_u8_slice(extension);
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{Digest, Felt, PublicInputs};
+    use vm_core::utils::{collections::Vec, Serializable};
+
+    /// Returns a digest whose elements are the provided value.
+    fn digest(value: u64) -> Digest {
+        Digest::new([Felt::new(value); 4])
+    }
+
+    /// Returns public inputs of the same program with the provided stack outputs.
+    fn with_outputs(outputs: &[u64]) -> PublicInputs {
+        let outputs = outputs.iter().map(|&value| Felt::new(value)).collect();
+        PublicInputs::new(digest(1), Vec::new(), outputs)
+    }
+
+    #[test]
+    fn commitments_serialize_to_distinct_bytes() {
+        let committed = with_outputs(&[]).with_output_commitment(digest(2));
+        let outputs = with_outputs(&[2, 2, 2, 2]);
+
+        assert_ne!(committed.to_bytes(), outputs.to_bytes());
+    }
+
+    #[cfg(feature = "pub-input-extension")]
+    #[test]
+    fn extension_serializes_to_distinct_bytes() {
+        use super::PUB_INPUT_EXTENSION_SIZE;
+
+        // an extension holding the serialized stack outputs of other public inputs
+        let outputs = with_outputs(&[2, 3, 4, 5]);
+        let mut extension = [0; PUB_INPUT_EXTENSION_SIZE];
+        extension.copy_from_slice(&outputs.to_bytes()[32..]);
+        let extended = with_outputs(&[]).with_extension(extension);
+        assert_ne!(outputs.to_bytes(), extended.to_bytes());
+
+        // an extension which starts with the tag and the elements of a commitment
+        let committed = with_outputs(&[]).with_output_commitment(digest(2));
+        let mut extension = [0; PUB_INPUT_EXTENSION_SIZE];
+        extension.copy_from_slice(&committed.to_bytes()[32..32 + PUB_INPUT_EXTENSION_SIZE]);
+        let extended = with_outputs(&[]).with_extension(extension);
+        assert_ne!(committed.to_bytes(), extended.to_bytes());
+
+        // public inputs without an extension are serialized as before
+        assert_eq!(32, with_outputs(&[]).to_bytes().len());
     }
 }
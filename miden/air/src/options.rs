@@ -50,6 +50,34 @@ impl ProofOptions {
         ))
     }
 
+    /// Returns the options of [ProofOptions::with_sha2()] with commitments made with RPO, the
+    /// hash function native to the Miden field.
+    pub fn with_rpo() -> Self {
+        Self(WinterProofOptions::new(
+            1,
+            8,
+            16,
+            HashFunction::Rp64_256,
+            FieldExtension::None,
+            8,
+            256,
+        ))
+    }
+
+    /// Returns the options of [ProofOptions::with_sha2()] with commitments made with Keccak-256,
+    /// so that they can be checked on EVM chains.
+    pub fn with_keccak() -> Self {
+        Self(WinterProofOptions::new(
+            1,
+            8,
+            16,
+            HashFunction::Keccak256,
+            FieldExtension::None,
+            8,
+            256,
+        ))
+    }
+
     pub fn with_128_bit_security() -> Self {
         Self(WinterProofOptions::new(
             27,
@@ -3,7 +3,7 @@ use crate::{
     utils::are_equal,
 };
 use vm_core::{
-    range::{P0_COL_IDX, P1_COL_IDX, Q_COL_IDX, S0_COL_IDX, S1_COL_IDX, T_COL_IDX, V_COL_IDX},
+    range::{B0_COL_IDX, B1_COL_IDX, M_COL_IDX, Q_COL_IDX, T_COL_IDX, V_COL_IDX},
     utils::collections::Vec,
     ExtensionOf,
 };
@@ -22,11 +22,11 @@ use super::{
 /// The number of boundary constraints required by the Range Checker
 pub const NUM_ASSERTIONS: usize = 2;
 /// The number of transition constraints required by the Range Checker.
-pub const NUM_CONSTRAINTS: usize = 7;
+pub const NUM_CONSTRAINTS: usize = 5;
 /// The degrees of the range checker's constraints, in the order they'll be added to the the result
 /// array when a transition is evaluated.
 pub const CONSTRAINT_DEGREES: [usize; NUM_CONSTRAINTS] = [
-    2, 2, 2, // Selector flags must be binary: t, s0, s1.
+    2, // Selector flag must be binary: t.
     3, // Constrain the row transitions in the 8-bit section of the table.
     2, // Transition from 8-bit to 16-bit section of range check table occurs at most once.
     3, 3, // Enforce values of column v before and after 8-bit to 16-bit transition.
@@ -39,7 +39,11 @@ pub const NUM_AUX_ASSERTIONS: usize = 4;
 /// The number of transition constraints required by multiset checks for the Range Checker.
 pub const NUM_AUX_CONSTRAINTS: usize = 2;
 /// The degrees of the Range Checker's auxiliary column constraints, used for multiset checks.
-pub const AUX_CONSTRAINT_DEGREES: [usize; NUM_AUX_CONSTRAINTS] = [8, 8];
+pub const AUX_CONSTRAINT_DEGREES: [usize; NUM_AUX_CONSTRAINTS] = [
+    3, // Running sum of the 8-bit and the 16-bit sections of the table: b0.
+    8, // Running sum of the range checker and the lookups of the stack and memory (three
+       // 16-bit limbs of the memory delta): b1.
+];
 
 // BOUNDARY CONSTRAINTS
 // ================================================================================================
@@ -62,14 +66,14 @@ pub fn get_assertions_last_step(result: &mut Vec<Assertion<Felt>>, step: usize)
 /// Returns the range checker's boundary assertions for auxiliary columns at the first step.
 pub fn get_aux_assertions_first_step<E: FieldElement>(result: &mut Vec<Assertion<E>>) {
     let step = 0;
-    result.push(Assertion::single(P0_COL_IDX, step, E::ONE));
-    result.push(Assertion::single(P1_COL_IDX, step, E::ONE));
+    result.push(Assertion::single(B0_COL_IDX, step, E::ZERO));
+    result.push(Assertion::single(B1_COL_IDX, step, E::ZERO));
 }
 
 /// Returns the range checker's boundary assertions for auxiliary columns at the last step.
 pub fn get_aux_assertions_last_step<E: FieldElement>(result: &mut Vec<Assertion<E>>, step: usize) {
-    result.push(Assertion::single(P0_COL_IDX, step, E::ONE));
-    result.push(Assertion::single(P1_COL_IDX, step, E::ONE));
+    result.push(Assertion::single(B0_COL_IDX, step, E::ZERO));
+    result.push(Assertion::single(B1_COL_IDX, step, E::ZERO));
 }
 
 // TRANSITION CONSTRAINTS
@@ -92,7 +96,7 @@ pub fn get_transition_constraint_count() -> usize {
 
 /// Enforces constraints for the range checker.
 pub fn enforce_constraints<E: FieldElement>(frame: &EvaluationFrame<E>, result: &mut [E]) {
-    // Constrain the selector flags.
+    // Constrain the selector flag.
     let mut index = enforce_flags(frame, result);
 
     // Constrain the row transitions in the 8-bit section of the table.
@@ -126,11 +130,11 @@ pub fn enforce_aux_constraints<F, E>(
     // Get the first random element for this segment.
     let alpha = aux_rand_elements.get_segment_elements(0)[0];
 
-    // Enforce p0.
-    let index = enforce_running_product_p0(main_frame, aux_frame, alpha, result);
+    // Enforce b0.
+    let index = enforce_running_sum_b0(main_frame, aux_frame, alpha, result);
 
-    // Enforce p1.
-    enforce_running_product_p1(main_frame, aux_frame, alpha, &mut result[index..]);
+    // Enforce b1.
+    enforce_running_sum_b1(main_frame, aux_frame, alpha, &mut result[index..]);
 }
 
 // TRANSITION CONSTRAINT HELPERS
@@ -138,13 +142,11 @@ pub fn enforce_aux_constraints<F, E>(
 
 // --- MAIN TRACE ---------------------------------------------------------------------------------
 
-/// Constrain the selector flags to binary values.
+/// Constrain the selector flag to binary values.
 fn enforce_flags<E: FieldElement>(frame: &EvaluationFrame<E>, result: &mut [E]) -> usize {
-    let constraint_count = 3;
+    let constraint_count = 1;
 
     result[0] = is_binary(frame.t());
-    result[1] = is_binary(frame.s0());
-    result[2] = is_binary(frame.s1());
 
     constraint_count
 }
@@ -177,14 +179,14 @@ fn enforce_16bit<E: FieldElement>(frame: &EvaluationFrame<E>, result: &mut [E])
 
 // --- AUXILIARY COLUMNS (FOR MULTISET CHECKS) ----------------------------------------------------
 
-/// Ensures that the running product auxiliary column `p0` is correctly built up during the 8-bit
+/// Ensures that the running sum auxiliary column `b0` is correctly built up during the 8-bit
 /// section of the range check table and then correctly reduced in the 16-bit section of the table.
 ///
-/// In the 8-bit section, when `t=0` the value of `z` is included in the running product at each
-/// step, and the constraint reduces to p0' = p0 * z.
-/// In the 16-bit section, when `t=1`, the running product is reduced by the difference between the
-/// current and next value, and the constraint reduces to p0' * (alpha + v' - v) = p0.
-fn enforce_running_product_p0<E, F>(
+/// In the 8-bit section, when `t=0`, the value is included in the running sum with its
+/// multiplicity, and the constraint reduces to b0' = b0 + m / (alpha + v).
+/// In the 16-bit section, when `t=1`, the difference between the current and next value is
+/// removed from the running sum, and the constraint reduces to b0' = b0 - 1 / (alpha + v' - v).
+fn enforce_running_sum_b0<E, F>(
     main_frame: &EvaluationFrame<F>,
     aux_frame: &EvaluationFrame<E>,
     alpha: E,
@@ -196,22 +198,24 @@ where
 {
     let mut constraint_offset = 0;
 
-    let z = get_z(main_frame, alpha);
-    let t = main_frame.t().into();
-    let p0_term = aux_frame.p0() * (z - z * t + t);
-    let p0_next_term = aux_frame.p0_next()
-        * ((alpha + main_frame.v_next().into() - main_frame.v().into()) * t - t + E::ONE);
-    result[constraint_offset] = p0_next_term - p0_term;
+    let t: E = main_frame.t().into();
+    let m: E = main_frame.m().into();
+    let v_alpha = alpha + main_frame.v().into();
+    let delta_alpha = alpha + main_frame.v_next().into() - main_frame.v().into();
+
+    let b0_term = aux_frame.change(B0_COL_IDX) * v_alpha * delta_alpha;
+    let lookups = binary_not(t) * m * delta_alpha - t * v_alpha;
+    result[constraint_offset] = are_equal(b0_term, lookups);
     constraint_offset += 1;
 
     constraint_offset
 }
 
-/// Ensures that the 16-bit running product is computed correctly in the column `p1`. It enforces
-/// that the value only changes during the 16-bit section of the table, where the value of `z` is
-/// included at each step, ensuring that the values in the 16-bit section are multiplied into `p1`
-/// 0, 1, 2, or 4 times, according to the selector flags.
-fn enforce_running_product_p1<E, F>(
+/// Ensures that the 16-bit running sum is computed correctly in the column `b1`. It enforces that
+/// the value only changes during the 16-bit section of the table, where the value is included in
+/// the running sum with its multiplicity `m`, and at rows where the Stack or the Memory chiplet
+/// perform lookups, where the inverses of the looked up values are removed from the running sum.
+fn enforce_running_sum_b1<E, F>(
     main_frame: &EvaluationFrame<F>,
     aux_frame: &EvaluationFrame<E>,
     alpha: E,
@@ -220,54 +224,38 @@ fn enforce_running_product_p1<E, F>(
     F: FieldElement<BaseField = Felt>,
     E: FieldElement<BaseField = Felt> + ExtensionOf<F>,
 {
-    // The running product column must enforce that the next step has the values from the range
-    // checker multiplied in (z) and the values from the stack (q) and the memory divided out. This
-    // is enforced by ensuring that p1_next multiplied by the stack and memory lookups at this step
-    // is equal to the combination of p1 and the range checker's values for this step.
-    let lookups = aux_frame.q() * get_memory_lookups(main_frame, alpha);
+    // The running sum column must enforce that the next step has the value from the range checker
+    // added in (m / (alpha + v)) and the values from the stack (q) and the memory removed. All
+    // terms are multiplied by the denominators of the range checker and the memory lookups, so
+    // that no inversions are needed to evaluate the constraint.
+    let (mem_numerator, mem_denominator) = get_memory_lookups(main_frame, alpha);
     let t: E = main_frame.t().into();
-    let range_checks = get_z(main_frame, alpha) * t - t + E::ONE;
+    let m: E = main_frame.m().into();
+    let v_alpha = alpha + main_frame.v().into();
 
-    result[0] = are_equal(aux_frame.p1_next() * lookups, aux_frame.p1() * range_checks);
+    let b1_term = (aux_frame.change(B1_COL_IDX) + aux_frame.q()) * v_alpha * mem_denominator;
+    let range_checks = t * m * mem_denominator - mem_numerator * v_alpha;
+
+    result[0] = are_equal(b1_term, range_checks);
 }
 
-/// The value to be included in the running product column for memory lookups at this row. These are
-/// only included for steps in the memory section of the trace (when the memory_flag is one).
-fn get_memory_lookups<E, F>(main_frame: &EvaluationFrame<F>, alpha: E) -> E
+/// The numerator and the denominator of the sum of the inverses of the memory lookups at this
+/// row. These are only included for steps in the memory section of the trace (when the
+/// memory_flag is one); otherwise, the numerator is ZERO and the denominator is ONE.
+fn get_memory_lookups<E, F>(main_frame: &EvaluationFrame<F>, alpha: E) -> (E, E)
 where
     F: FieldElement<BaseField = Felt>,
     E: FieldElement<BaseField = Felt> + ExtensionOf<F>,
 {
     let memory_flag: E = main_frame.chiplets_memory_flag().into();
-    let d0: E = main_frame.memory_d0().into();
-    let d1: E = main_frame.memory_d1().into();
+    let d0: E = alpha + main_frame.memory_d0().into();
+    let d1: E = alpha + main_frame.memory_d1().into();
+    let d2: E = alpha + main_frame.memory_d2().into();
 
-    E::ONE + memory_flag * ((d0 + alpha) * (d1 + alpha) - E::ONE)
-}
+    let numerator = memory_flag * (d1 * d2 + d0 * d2 + d0 * d1);
+    let denominator = E::ONE + memory_flag * (d0 * d1 * d2 - E::ONE);
 
-/// Returns the value `z` which is included in the running product columns at each step. `z` causes
-/// the row's value to be included 0, 1, 2, or 4 times, according to the row's selector flags row.
-fn get_z<E, F>(main_frame: &EvaluationFrame<F>, alpha: E) -> E
-where
-    F: FieldElement<BaseField = Felt>,
-    E: FieldElement<BaseField = Felt> + ExtensionOf<F>,
-{
-    // Get the selectors and the value from the main frame.
-    let s0: E = main_frame.s0().into();
-    let s1: E = main_frame.s1().into();
-    let v: E = main_frame.v().into();
-
-    // Define the flags.
-    let f0: E = binary_not(s0) * binary_not(s1);
-    let f1: E = s0 * binary_not(s1);
-    let f2: E = binary_not(s0) * s1;
-    let f3: E = s0 * s1;
-
-    // Compute z.
-    let v_alpha = v + alpha;
-    let v_alpha2 = v_alpha.square();
-    let v_alpha4 = v_alpha2.square();
-    f3 * v_alpha4 + f2 * v_alpha2 + f1 * v_alpha + f0
+    (numerator, denominator)
 }
 
 // RANGE CHECKER FRAME EXTENSION TRAIT
@@ -282,24 +270,12 @@ trait EvaluationFrameExt<E: FieldElement> {
     fn t(&self) -> E;
     /// The next value in column T.
     fn t_next(&self) -> E;
-    /// The current value in column s0.
-    fn s0(&self) -> E;
-    /// The current value in column s1.
-    fn s1(&self) -> E;
+    /// The current value in column M.
+    fn m(&self) -> E;
     /// The current value in column V.
     fn v(&self) -> E;
     /// The next value in column V.
     fn v_next(&self) -> E;
-    /// The current value in auxiliary column p0.
-    fn p0(&self) -> E;
-    /// The next value in auxiliary column p0.
-    fn p0_next(&self) -> E;
-    /// The current value in auxiliary column p1.
-    fn p1(&self) -> E;
-
-    /// The next value in auxiliary column p1.
-    fn p1_next(&self) -> E;
-
     /// The current value in auxiliary column q.
     fn q(&self) -> E;
 
@@ -330,13 +306,8 @@ impl<E: FieldElement> EvaluationFrameExt<E> for &EvaluationFrame<E> {
     }
 
     #[inline(always)]
-    fn s0(&self) -> E {
-        self.current()[S0_COL_IDX]
-    }
-
-    #[inline(always)]
-    fn s1(&self) -> E {
-        self.current()[S1_COL_IDX]
+    fn m(&self) -> E {
+        self.current()[M_COL_IDX]
     }
 
     #[inline(always)]
@@ -349,26 +320,6 @@ impl<E: FieldElement> EvaluationFrameExt<E> for &EvaluationFrame<E> {
         self.next()[V_COL_IDX]
     }
 
-    #[inline(always)]
-    fn p0(&self) -> E {
-        self.current()[P0_COL_IDX]
-    }
-
-    #[inline(always)]
-    fn p0_next(&self) -> E {
-        self.next()[P0_COL_IDX]
-    }
-
-    #[inline(always)]
-    fn p1(&self) -> E {
-        self.current()[P1_COL_IDX]
-    }
-
-    #[inline(always)]
-    fn p1_next(&self) -> E {
-        self.next()[P1_COL_IDX]
-    }
-
     #[inline(always)]
     fn q(&self) -> E {
         self.current()[Q_COL_IDX]
@@ -388,3 +339,85 @@ impl<E: FieldElement> EvaluationFrameExt<E> for &EvaluationFrame<E> {
         binary_not(self.t()) * self.t_next()
     }
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        enforce_aux_constraints, AuxTraceRandElements, EvaluationFrame, Felt, FieldElement, Vec,
+        AUX_CONSTRAINT_DEGREES, NUM_AUX_CONSTRAINTS,
+    };
+    use rand_utils::rand_value;
+    use vm_core::{AUX_TRACE_WIDTH, TRACE_WIDTH};
+
+    /// The auxiliary constraints are evaluated over frames in which every column is a random
+    /// polynomial of degree one in `x`, so the evaluations of each constraint are a polynomial in
+    /// `x` whose degree is the degree of the constraint. In particular, the lookups of the three
+    /// 16-bit limbs of memory deltas must fit into the declared degree of `b1`.
+    #[test]
+    fn aux_constraint_degrees() {
+        let max_degree = *AUX_CONSTRAINT_DEGREES.iter().max().unwrap();
+        let main_rows = random_linear_rows(2 * TRACE_WIDTH);
+        let aux_rows = random_linear_rows(2 * AUX_TRACE_WIDTH);
+        let mut aux_rand_elements = AuxTraceRandElements::new();
+        aux_rand_elements.add_segment_elements(vec![rand_value::<Felt>()]);
+
+        // evaluate the constraints at enough points to detect a degree above the declared one
+        let mut evaluations = vec![Vec::new(); NUM_AUX_CONSTRAINTS];
+        for x in 0..max_degree as u64 + 2 {
+            let x = Felt::new(x);
+            let main = evaluate_rows(&main_rows, x);
+            let aux = evaluate_rows(&aux_rows, x);
+            let main_frame = EvaluationFrame::from_rows(
+                main[..TRACE_WIDTH].to_vec(),
+                main[TRACE_WIDTH..].to_vec(),
+            );
+            let aux_frame = EvaluationFrame::from_rows(
+                aux[..AUX_TRACE_WIDTH].to_vec(),
+                aux[AUX_TRACE_WIDTH..].to_vec(),
+            );
+
+            let mut result = [Felt::ZERO; NUM_AUX_CONSTRAINTS];
+            enforce_aux_constraints(&main_frame, &aux_frame, &aux_rand_elements, &mut result);
+            for (evaluations, &value) in evaluations.iter_mut().zip(result.iter()) {
+                evaluations.push(value);
+            }
+        }
+
+        for (evaluations, &degree) in evaluations.iter().zip(AUX_CONSTRAINT_DEGREES.iter()) {
+            assert_eq!(degree, degree_of(evaluations));
+        }
+    }
+
+    // TEST HELPERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the coefficients `(a, b)` of random degree one polynomials `a + b * x`.
+    fn random_linear_rows(width: usize) -> Vec<(Felt, Felt)> {
+        (0..width)
+            .map(|_| (rand_value::<Felt>(), rand_value::<Felt>()))
+            .collect()
+    }
+
+    /// Evaluates the provided degree one polynomials at `x`.
+    fn evaluate_rows(rows: &[(Felt, Felt)], x: Felt) -> Vec<Felt> {
+        rows.iter().map(|&(a, b)| a + b * x).collect()
+    }
+
+    /// Returns the degree of the polynomial with the provided evaluations at 0, 1, 2, ...; this is
+    /// the order of the last finite difference of the evaluations which is not all zeros.
+    fn degree_of(evaluations: &[Felt]) -> usize {
+        let mut differences = evaluations.to_vec();
+        let mut degree = 0;
+        while differences.len() > 1 {
+            differences = differences.windows(2).map(|w| w[1] - w[0]).collect();
+            if differences.iter().all(|&d| d == Felt::ZERO) {
+                break;
+            }
+            degree += 1;
+        }
+        degree
+    }
+}
@@ -245,7 +245,7 @@ fn validate_and_drop_root(span_ops: &mut Vec<Operation>) {
     span_ops.push(Operation::Dup2);
     span_ops.push(Operation::Eq);
     span_ops.push(Operation::And);
-    span_ops.push(Operation::Assert);
+    span_ops.push(Operation::Assert(0));
 
     // drop one of the duplicate roots
     span_ops.push_many(Operation::Drop, 4);
@@ -1,32 +1,32 @@
 use vm_core::utils::PushMany;
 
 use super::{
-    super::validate_operation, parse_element_param, AssemblyError, Felt, FieldElement, Operation,
-    Token, Vec,
+    super::validate_operation, parse_element_param, parse_err_code, AssemblyError, Felt,
+    FieldElement, Operation, Token, Vec,
 };
 
 // ASSERTIONS AND TESTS
 // ================================================================================================
 
 /// Appends ASSERT operation to the span block.
+///
+/// The error code of the assertion may be provided as `assert.err=<code>`; it defaults to 0.
 pub(super) fn parse_assert(span_ops: &mut Vec<Operation>, op: &Token) -> Result<(), AssemblyError> {
-    if op.num_parts() > 1 {
-        return Err(AssemblyError::extra_param(op));
-    }
-    span_ops.push(Operation::Assert);
+    let err_code = parse_err_code(op, 1)?;
+    span_ops.push(Operation::Assert(err_code));
     Ok(())
 }
 
 /// Appends EQ ASSERT operation sequence to the span block.
+///
+/// The error code of the assertion may be provided as `assert_eq.err=<code>`; it defaults to 0.
 pub(super) fn parse_assert_eq(
     span_ops: &mut Vec<Operation>,
     op: &Token,
 ) -> Result<(), AssemblyError> {
-    if op.num_parts() > 1 {
-        return Err(AssemblyError::extra_param(op));
-    }
+    let err_code = parse_err_code(op, 1)?;
     span_ops.push(Operation::Eq);
-    span_ops.push(Operation::Assert);
+    span_ops.push(Operation::Assert(err_code));
     Ok(())
 }
 
@@ -438,7 +438,7 @@ pub(super) fn append_pow2_op(span_ops: &mut Vec<Operation>, checked_mode: bool)
         span_ops.push(Operation::U32div);
         span_ops.push(Operation::Swap);
         span_ops.push(Operation::Eqz);
-        span_ops.push(Operation::Assert);
+        span_ops.push(Operation::Assert(0));
     }
 
     span_ops.push(Operation::Push(Felt::new(2)));
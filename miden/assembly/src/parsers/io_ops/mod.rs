@@ -304,7 +304,7 @@ mod tests {
 
     #[test]
     fn loadw_invalid() {
-        test_parsew_base("loadw", "loadw.adv|local|mem");
+        test_parsew_base("loadw", "loadw.adv|local|mem|rand");
     }
 
     // TESTS FOR SAVING STACK VALUES WITHOUT REMOVING THEM (STORE)
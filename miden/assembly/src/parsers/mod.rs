@@ -103,6 +103,9 @@ fn parse_op_token(
         "u32checked_or" => u32_ops::parse_u32or(span_ops, op),
         "u32checked_xor" => u32_ops::parse_u32xor(span_ops, op),
         "u32checked_not" => u32_ops::parse_u32not(span_ops, op),
+        "u64checked_and" => u32_ops::parse_u64and(span_ops, op),
+        "u64checked_or" => u32_ops::parse_u64or(span_ops, op),
+        "u64checked_xor" => u32_ops::parse_u64xor(span_ops, op),
 
         "u32checked_shr" => u32_ops::parse_u32shr(span_ops, op, U32OpMode::Checked),
         "u32unchecked_shr" => u32_ops::parse_u32shr(span_ops, op, U32OpMode::Unchecked),
@@ -279,6 +282,30 @@ fn parse_u32_param(
     Ok(result)
 }
 
+/// Parses the optional error code parameter of an assertion at the specified op index, which is
+/// expected in the form `err=<code>`, with the code being a decimal u32 value. Returns 0 if the
+/// parameter is not provided.
+///
+/// # Errors
+/// Returns an AssemblyError if:
+/// - the parameter is not of the form `err=<code>`, or the code is not a u32 value.
+/// - any parameters follow the error code.
+fn parse_err_code(op: &Token, param_idx: usize) -> Result<u32, AssemblyError> {
+    if op.num_parts() <= param_idx {
+        return Ok(0);
+    }
+    if op.num_parts() > param_idx + 1 {
+        return Err(AssemblyError::extra_param(op));
+    }
+
+    match op.parts()[param_idx].strip_prefix("err=") {
+        Some(err_code) => err_code
+            .parse::<u32>()
+            .map_err(|_| AssemblyError::invalid_param(op, param_idx)),
+        None => Err(AssemblyError::invalid_param(op, param_idx)),
+    }
+}
+
 /// This is a helper function that appends a PUSH operation to the span block which puts the
 /// provided value parameter onto the stack.
 ///
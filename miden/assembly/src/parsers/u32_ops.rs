@@ -392,6 +392,37 @@ pub fn parse_u32xor(span_ops: &mut Vec<Operation>, op: &Token) -> Result<(), Ass
     Ok(())
 }
 
+/// Translates u64checked_and assembly instruction to VM operations.
+///
+/// Implemented as: `U64AND DROP DROP` (3 VM cycles).
+///
+/// `U64AND` overwrites the limbs of the first operand with the limbs of the result, and thus, the
+/// limbs of the second operand need to be dropped afterwards. We don't need to assert that all
+/// limbs are u32 values because the VM does these assertions implicitly for `U64AND` operation.
+pub fn parse_u64and(span_ops: &mut Vec<Operation>, op: &Token) -> Result<(), AssemblyError> {
+    parse_u64_bitwise_op(span_ops, op, Operation::U64and)
+}
+
+/// Translates u64checked_or assembly instruction to VM operations.
+///
+/// Implemented as: `U64OR DROP DROP` (3 VM cycles).
+///
+/// We don't need to assert that all limbs are u32 values because the VM does these assertions
+/// implicitly for `U64OR` operation.
+pub fn parse_u64or(span_ops: &mut Vec<Operation>, op: &Token) -> Result<(), AssemblyError> {
+    parse_u64_bitwise_op(span_ops, op, Operation::U64or)
+}
+
+/// Translates u64checked_xor assembly instruction to VM operations.
+///
+/// Implemented as: `U64XOR DROP DROP` (3 VM cycles).
+///
+/// We don't need to assert that all limbs are u32 values because the VM does these assertions
+/// implicitly for `U64XOR` operation.
+pub fn parse_u64xor(span_ops: &mut Vec<Operation>, op: &Token) -> Result<(), AssemblyError> {
+    parse_u64_bitwise_op(span_ops, op, Operation::U64xor)
+}
+
 /// Translates u32checked_not assembly instruction to VM operations.
 ///
 /// The operation is implemented as `PUSH(2^32 - 1) U32ASSERT2 SWAP U32SUB DROP`,
@@ -598,7 +629,7 @@ pub fn parse_u32rotr(
                 span_ops.push(Operation::Dup1);
                 span_ops.push(Operation::U32sub);
                 span_ops.push(Operation::Not);
-                span_ops.push(Operation::Assert);
+                span_ops.push(Operation::Assert(0));
                 span_ops.push(Operation::Incr);
                 span_ops.push(Operation::Dup1);
                 // If 32-b = 32, replace it with 0.
@@ -1020,7 +1051,7 @@ fn handle_arithmetic_operation(
 
     if assert_u32_res {
         span_ops.push(Operation::Eqz);
-        span_ops.push(Operation::Assert);
+        span_ops.push(Operation::Assert(0));
     } else if drop_high_bits {
         span_ops.push(Operation::Drop);
     }
@@ -1054,3 +1085,24 @@ fn handle_division(
 
     Ok(())
 }
+
+/// Handles u64checked_and, u64checked_or, and u64checked_xor operations. The specified 64-bit
+/// bitwise operation is followed by two DROP operations which remove the limbs of the second
+/// operand from the stack.
+fn parse_u64_bitwise_op(
+    span_ops: &mut Vec<Operation>,
+    op: &Token,
+    bitwise_op: Operation,
+) -> Result<(), AssemblyError> {
+    match op.num_parts() {
+        0 => return Err(AssemblyError::missing_param(op)),
+        1 => {
+            span_ops.push(bitwise_op);
+            span_ops.push(Operation::Drop);
+            span_ops.push(Operation::Drop);
+        }
+        _ => return Err(AssemblyError::extra_param(op)),
+    }
+
+    Ok(())
+}
@@ -39,6 +39,28 @@ fn span_and_simple_if() {
     assert_eq!(expected, format!("{}", program));
 }
 
+#[test]
+fn assertions_with_err_codes() {
+    let assembler = super::Assembler::default();
+    let source = "begin assert assert.err=7 assert_eq.err=42 end";
+    let program = assembler.compile(source).unwrap();
+    let expected = "begin span assert assert(7) eq assert(42) end end";
+    assert_eq!(expected, format!("{}", program));
+
+    // the error codes are not a part of the program hash
+    let source = "begin assert assert assert_eq end";
+    assert_eq!(program.hash(), assembler.compile(source).unwrap().hash());
+
+    for source in [
+        "begin assert.7 end",
+        "begin assert.err=-1 end",
+        "begin assert_eq.err=4294967296 end",
+        "begin assert.err=1.err=2 end",
+    ] {
+        assert!(assembler.compile(source).is_err());
+    }
+}
+
 // NESTED CONTROL BLOCKS
 // ================================================================================================
 
@@ -7,11 +7,16 @@ use super::{create_range, Felt, FieldElement, Range};
 pub const NUM_SELECTORS: usize = 2;
 
 /// Number of columns needed to record an execution trace of the bitwise helper.
-pub const TRACE_WIDTH: usize = NUM_SELECTORS + 12;
+pub const TRACE_WIDTH: usize = NUM_SELECTORS + 13;
 
 /// The number of rows required to compute an operation in the Bitwise chiplet.
 pub const OP_CYCLE_LEN: usize = 8;
 
+/// The number of rows required to compute an operation on 64-bit values in the Bitwise chiplet.
+/// Such operations are computed in two coupled cycles: one for the high 32-bit limbs of the inputs,
+/// followed by one for the low 32-bit limbs.
+pub const U64_OP_CYCLE_LEN: usize = 2 * OP_CYCLE_LEN;
+
 // --- OPERATION SELECTORS ------------------------------------------------------------------------
 
 /// Specifies a bitwise AND operation.
@@ -32,6 +37,21 @@ pub const BITWISE_XOR: Selectors = [Felt::ONE, Felt::ZERO];
 /// the chiplet and operation selectors [1, 0, 1, 0].
 pub const BITWISE_XOR_LABEL: Felt = Felt::new(6);
 
+/// Unique label for the bitwise AND operation on 64-bit values. Computed as 1 more than the binary
+/// composition of the chiplet and operation selectors followed by the high limb flag
+/// [1, 0, 0, 0, 1].
+pub const BITWISE_U64_AND_LABEL: Felt = Felt::new(18);
+
+/// Unique label for the bitwise OR operation on 64-bit values. Computed as 1 more than the binary
+/// composition of the chiplet and operation selectors followed by the high limb flag
+/// [1, 0, 0, 1, 1].
+pub const BITWISE_U64_OR_LABEL: Felt = Felt::new(26);
+
+/// Unique label for the bitwise XOR operation on 64-bit values. Computed as 1 more than the binary
+/// composition of the chiplet and operation selectors followed by the high limb flag
+/// [1, 0, 1, 0, 1].
+pub const BITWISE_U64_XOR_LABEL: Felt = Felt::new(22);
+
 // --- INPUT DECOMPOSITION ------------------------------------------------------------------------
 
 /// The number of bits decomposed per row per input parameter `a` or `b`.
@@ -61,6 +81,11 @@ pub const PREV_OUTPUT_COL_IDX: usize = B_COL_IDX + 1 + 2 * NUM_DECOMP_BITS;
 /// execution trace.
 pub const OUTPUT_COL_IDX: usize = PREV_OUTPUT_COL_IDX + 1;
 
+/// The index of the column containing the high limb flag within the bitwise chiplet execution
+/// trace. The flag is set to ONE in the cycle computing the high 32-bit limbs of an operation on
+/// 64-bit values, and to ZERO otherwise.
+pub const HIGH_LIMB_COL_IDX: usize = OUTPUT_COL_IDX + 1;
+
 // TYPE ALIASES
 // ================================================================================================
 
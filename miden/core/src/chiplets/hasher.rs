@@ -1,6 +1,7 @@
 //! TODO: add docs
 
 use super::{Felt, FieldElement, Word, HASHER_AUX_TRACE_OFFSET};
+use crate::{errors::HasherAddressError, StarkField};
 use core::ops::Range;
 use crypto::{ElementHasher, Hasher as HashFn};
 
@@ -104,6 +105,40 @@ pub const RETURN_STATE: Selectors = [Felt::ZERO, Felt::ZERO, Felt::ONE];
 /// binary composition of  the chiplet and operation selectors [0, 0, 0, 1]
 pub const RETURN_STATE_LABEL: u8 = 9;
 
+// --- Hash domains -------------------------------------------------------------------------------
+
+/// Index of the capacity element of the hasher state which is initialized with the domain of a
+/// hash, i.e., a value identifying the use-case of the hash.
+///
+/// Hashes computed in different domains start from different states, and thus, a hash computed
+/// for one use-case cannot be passed off as a hash computed for another one.
+pub const DOMAIN_IDX: usize = 1;
+
+/// Domain of the hashes of the operation batches of SPAN blocks.
+///
+/// This is ZERO so that the hashes of SPAN blocks are the same as the ones computed by the
+/// assembler with `hasher::hash_elements()`.
+pub const SPAN_HASH_DOMAIN: Felt = Felt::ZERO;
+
+/// Domain of the hashes of the children of JOIN, SPLIT, and LOOP blocks.
+///
+/// This is ZERO so that the hashes of control blocks are the same as the ones computed by the
+/// assembler with `hasher::merge()`.
+pub const CONTROL_BLOCK_DOMAIN: Felt = Felt::ZERO;
+
+/// Domain of the hashes of the nodes of Merkle paths.
+///
+/// This is ZERO so that the roots of Merkle paths are the same as the roots of the advice sets,
+/// which are computed with `hasher::merge()`.
+pub const MERKLE_DOMAIN: Felt = Felt::ZERO;
+
+/// Domain of the hashes of memory regions requested by programs.
+///
+/// Programs choose the values they hash, and thus, the domain of their hashes differs from the
+/// domains of the hashes of code blocks and Merkle paths; a program cannot compute the hash of a
+/// code block or of a Merkle node by hashing its preimage from memory.
+pub const USER_HASH_DOMAIN: Felt = Felt::new(1);
+
 // --- Column accessors in the auxiliary trace ----------------------------------------------------
 
 /// Index of the auxiliary trace column tracking the state of the sibling table.
@@ -124,6 +159,35 @@ pub fn hash_elements(elements: &[Felt]) -> Digest {
     Hasher::hash_elements(elements)
 }
 
+/// Returns a hash of two digests in the specified domain.
+///
+/// The result is the same as the one of [merge()] in the ZERO domain.
+pub fn merge_in_domain(values: &[Digest; 2], domain: Felt) -> Digest {
+    let words: [Word; 2] = [values[0].into(), values[1].into()];
+    let mut state = init_state_from_words_in_domain(&words[0], &words[1], domain);
+    apply_permutation(&mut state);
+    get_digest(&state).into()
+}
+
+/// Returns a hash of the provided list of field elements in the specified domain.
+///
+/// The elements are absorbed 8 at a time, and the last block is padded with ZEROs, in the same
+/// way as by the hasher chiplet; the result is the same as the one of [hash_elements()] in the
+/// ZERO domain.
+pub fn hash_elements_in_domain(elements: &[Felt], domain: Felt) -> Digest {
+    let mut state = [Felt::ZERO; STATE_WIDTH];
+    state[0] = Felt::new(elements.len() as u64);
+    state[DOMAIN_IDX] = domain;
+
+    for chunk in elements.chunks(RATE_LEN) {
+        let mut block = [Felt::ZERO; RATE_LEN];
+        block[..chunk.len()].copy_from_slice(chunk);
+        absorb_into_state(&mut state, &block);
+        apply_permutation(&mut state);
+    }
+    get_digest(&state).into()
+}
+
 /// Applies Rescue-XLIX round function to the provided state.
 ///
 /// The function takes sponge state as an input and applies a single Rescue-XLIX round to it. The
@@ -140,6 +204,53 @@ pub fn apply_permutation(state: &mut [Felt; STATE_WIDTH]) {
     Hasher::apply_permutation(state)
 }
 
+// ROW ADDRESSES
+// ================================================================================================
+
+/// Returns the address of the specified row of the hasher execution trace.
+///
+/// Row addresses start at ONE (rather than ZERO), and are incremented by ONE at every row, so that
+/// the address of the first code block hashed by the decoder is a non-zero value.
+#[inline(always)]
+pub fn row_to_addr(row: usize) -> Felt {
+    Felt::new(row as u64 + 1)
+}
+
+/// Returns the row of the hasher execution trace at the specified address.
+///
+/// # Errors
+/// Returns an error if the address is ZERO, or if the row does not fit into a `usize`.
+pub fn addr_to_row(addr: Felt) -> Result<usize, HasherAddressError> {
+    let addr = addr.as_int();
+    let row = addr.checked_sub(1).ok_or(HasherAddressError::ZeroAddress)?;
+    row.try_into()
+        .map_err(|_| HasherAddressError::AddressTooLarge(addr))
+}
+
+/// Returns the position of the row at the specified address within its hash cycle, i.e., a value
+/// in the range [0, HASH_CYCLE_LEN).
+///
+/// # Errors
+/// Returns an error if the address is not a valid row address.
+pub fn addr_to_cycle_row(addr: Felt) -> Result<usize, HasherAddressError> {
+    Ok(addr_to_row(addr)? % HASH_CYCLE_LEN)
+}
+
+/// Returns the position within its hash cycle of the row at the specified address, checking that
+/// the row can hold a lookup of the hasher, i.e., that it is the first or the last row of a hash
+/// cycle.
+///
+/// # Errors
+/// Returns an error if the address is not a valid row address, or if the row is neither the first
+/// nor the last row of a hash cycle.
+pub fn addr_to_lookup_cycle_row(addr: Felt) -> Result<usize, HasherAddressError> {
+    let cycle_row = addr_to_cycle_row(addr)?;
+    if cycle_row != 0 && cycle_row != HASH_CYCLE_LEN - 1 {
+        return Err(HasherAddressError::NotLookupRow(addr.as_int()));
+    }
+    Ok(cycle_row)
+}
+
 // HASHER STATE MUTATORS
 // ================================================================================================
 
@@ -147,9 +258,20 @@ pub fn apply_permutation(state: &mut [Felt; STATE_WIDTH]) {
 /// number of elements to be absorbed.
 #[inline(always)]
 pub fn init_state(init_values: &[Felt; RATE_LEN], num_elements: usize) -> [Felt; STATE_WIDTH] {
+    init_state_in_domain(init_values, num_elements, Felt::ZERO)
+}
+
+/// Initializes hasher state with the first 8 elements to be absorbed, the specified total number
+/// of elements to be absorbed, and the specified domain of the hash.
+#[inline(always)]
+pub fn init_state_in_domain(
+    init_values: &[Felt; RATE_LEN],
+    num_elements: usize,
+    domain: Felt,
+) -> [Felt; STATE_WIDTH] {
     [
         Felt::new(num_elements as u64),
-        Felt::ZERO,
+        domain,
         Felt::ZERO,
         Felt::ZERO,
         init_values[0],
@@ -167,9 +289,16 @@ pub fn init_state(init_values: &[Felt; RATE_LEN], num_elements: usize) -> [Felt;
 /// to be hashed is set to 8.
 #[inline(always)]
 pub fn init_state_from_words(w1: &Word, w2: &Word) -> [Felt; STATE_WIDTH] {
+    init_state_from_words_in_domain(w1, w2, Felt::ZERO)
+}
+
+/// Initializes hasher state with the elements from the provided words and the specified domain of
+/// the hash. The number of elements to be hashed is set to 8.
+#[inline(always)]
+pub fn init_state_from_words_in_domain(w1: &Word, w2: &Word, domain: Felt) -> [Felt; STATE_WIDTH] {
     [
         Felt::from(8_u8),
-        Felt::ZERO,
+        domain,
         Felt::ZERO,
         Felt::ZERO,
         w1[0],
@@ -203,3 +332,69 @@ pub fn get_digest(state: &[Felt; STATE_WIDTH]) -> [Felt; DIGEST_LEN] {
         .try_into()
         .expect("failed to get digest from hasher state")
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        addr_to_cycle_row, addr_to_lookup_cycle_row, addr_to_row, hash_elements,
+        hash_elements_in_domain, merge, merge_in_domain, row_to_addr, HasherAddressError,
+        HASH_CYCLE_LEN, USER_HASH_DOMAIN,
+    };
+    use crate::{utils::collections::Vec, Felt, FieldElement};
+
+    #[test]
+    fn row_addresses() {
+        for row in [0, 1, HASH_CYCLE_LEN - 1, HASH_CYCLE_LEN, 1000] {
+            assert_eq!(Ok(row), addr_to_row(row_to_addr(row)));
+        }
+        assert_eq!(Felt::new(1), row_to_addr(0));
+        assert_eq!(
+            Err(HasherAddressError::ZeroAddress),
+            addr_to_row(Felt::new(0))
+        );
+    }
+
+    #[test]
+    fn cycle_rows() {
+        // the first two hash cycles start at addresses 1 and 9, and end at addresses 8 and 16
+        assert_eq!(Ok(0), addr_to_cycle_row(Felt::new(9)));
+        assert_eq!(Ok(3), addr_to_cycle_row(Felt::new(12)));
+        assert_eq!(Ok(0), addr_to_lookup_cycle_row(Felt::new(1)));
+        assert_eq!(
+            Ok(HASH_CYCLE_LEN - 1),
+            addr_to_lookup_cycle_row(Felt::new(16))
+        );
+        assert_eq!(
+            Err(HasherAddressError::NotLookupRow(12)),
+            addr_to_lookup_cycle_row(Felt::new(12))
+        );
+        assert_eq!(
+            Err(HasherAddressError::ZeroAddress),
+            addr_to_lookup_cycle_row(Felt::new(0))
+        );
+    }
+
+    #[test]
+    fn hash_domains() {
+        // hashes in the ZERO domain are the same as the ones of the hash function
+        let elements: Vec<Felt> = (0..13).map(Felt::new).collect();
+        for len in [0, 1, 8, 13] {
+            assert_eq!(
+                hash_elements(&elements[..len]),
+                hash_elements_in_domain(&elements[..len], Felt::ZERO)
+            );
+        }
+        let digests = [hash_elements(&elements[..4]), hash_elements(&elements[4..])];
+        assert_eq!(merge(&digests), merge_in_domain(&digests, Felt::ZERO));
+
+        // the same values hash differently in different domains
+        assert_ne!(
+            hash_elements(&elements),
+            hash_elements_in_domain(&elements, USER_HASH_DOMAIN)
+        );
+        assert_ne!(merge(&digests), merge_in_domain(&digests, USER_HASH_DOMAIN));
+    }
+}
@@ -0,0 +1,192 @@
+use super::{bitwise, hasher, memory, Felt};
+
+// CHIPLET KIND
+// ================================================================================================
+
+/// Chiplets which send or receive labeled requests over the chiplets bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChipletKind {
+    /// The hash chiplet.
+    Hasher,
+    /// The bitwise chiplet.
+    Bitwise,
+    /// The memory chiplet.
+    Memory,
+}
+
+// LABEL INFO
+// ================================================================================================
+
+/// Metadata of a label which identifies an operation of a chiplet on the chiplets bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LabelInfo {
+    chiplet: ChipletKind,
+    name: &'static str,
+    value: Felt,
+}
+
+impl LabelInfo {
+    /// Returns metadata of the label with the specified value, which identifies the named
+    /// operation of the specified chiplet.
+    pub const fn new(chiplet: ChipletKind, name: &'static str, value: Felt) -> Self {
+        Self {
+            chiplet,
+            name,
+            value,
+        }
+    }
+
+    /// Returns the chiplet which executes the operation identified by this label.
+    pub const fn chiplet(&self) -> ChipletKind {
+        self.chiplet
+    }
+
+    /// Returns the name of the operation identified by this label.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the value of this label.
+    pub const fn value(&self) -> Felt {
+        self.value
+    }
+}
+
+// LABEL REGISTRY
+// ================================================================================================
+
+/// All labels used on the chiplets bus.
+///
+/// Labels are defined by the chiplets themselves, and they share a single namespace on the bus:
+/// requests with the same label are indistinguishable, regardless of the chiplet they are meant
+/// for. Every new label must be added here, so that it is checked against the labels of all other
+/// chiplets at compile time.
+pub const CHIPLET_LABELS: [LabelInfo; 13] = [
+    LabelInfo::new(
+        ChipletKind::Hasher,
+        "LINEAR_HASH",
+        Felt::new(hasher::LINEAR_HASH_LABEL as u64),
+    ),
+    LabelInfo::new(
+        ChipletKind::Hasher,
+        "MP_VERIFY",
+        Felt::new(hasher::MP_VERIFY_LABEL as u64),
+    ),
+    LabelInfo::new(
+        ChipletKind::Hasher,
+        "MR_UPDATE_OLD",
+        Felt::new(hasher::MR_UPDATE_OLD_LABEL as u64),
+    ),
+    LabelInfo::new(
+        ChipletKind::Hasher,
+        "MR_UPDATE_NEW",
+        Felt::new(hasher::MR_UPDATE_NEW_LABEL as u64),
+    ),
+    LabelInfo::new(
+        ChipletKind::Hasher,
+        "RETURN_HASH",
+        Felt::new(hasher::RETURN_HASH_LABEL as u64),
+    ),
+    LabelInfo::new(
+        ChipletKind::Hasher,
+        "RETURN_STATE",
+        Felt::new(hasher::RETURN_STATE_LABEL as u64),
+    ),
+    LabelInfo::new(ChipletKind::Bitwise, "AND", bitwise::BITWISE_AND_LABEL),
+    LabelInfo::new(ChipletKind::Bitwise, "OR", bitwise::BITWISE_OR_LABEL),
+    LabelInfo::new(ChipletKind::Bitwise, "XOR", bitwise::BITWISE_XOR_LABEL),
+    LabelInfo::new(
+        ChipletKind::Bitwise,
+        "U64_AND",
+        bitwise::BITWISE_U64_AND_LABEL,
+    ),
+    LabelInfo::new(
+        ChipletKind::Bitwise,
+        "U64_OR",
+        bitwise::BITWISE_U64_OR_LABEL,
+    ),
+    LabelInfo::new(
+        ChipletKind::Bitwise,
+        "U64_XOR",
+        bitwise::BITWISE_U64_XOR_LABEL,
+    ),
+    LabelInfo::new(ChipletKind::Memory, "MEMORY", memory::MEMORY_LABEL),
+];
+
+// no two operations may share a label, and no label may be ZERO
+const _: () = assert!(
+    labels_are_unique(&CHIPLET_LABELS),
+    "chiplet labels must be unique and non-zero"
+);
+
+/// Returns metadata of the specified label, or None if no chiplet uses it.
+pub fn label_info(value: Felt) -> Option<&'static LabelInfo> {
+    CHIPLET_LABELS.iter().find(|label| label.value == value)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if all of the provided labels are non-zero and pairwise distinct.
+///
+/// Field elements cannot be compared in a const context, and thus, labels are compared by their
+/// internal representation, which is unique for every element constructed via [Felt::new].
+const fn labels_are_unique(labels: &[LabelInfo]) -> bool {
+    let zero = Felt::new(0).inner();
+    let mut i = 0;
+    while i < labels.len() {
+        if labels[i].value.inner() == zero {
+            return false;
+        }
+        let mut j = i + 1;
+        while j < labels.len() {
+            if labels[i].value.inner() == labels[j].value.inner() {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        bitwise, hasher, label_info, labels_are_unique, ChipletKind, LabelInfo, CHIPLET_LABELS,
+    };
+    use crate::Felt;
+
+    #[test]
+    fn label_metadata() {
+        let label = label_info(bitwise::BITWISE_XOR_LABEL).unwrap();
+        assert_eq!(ChipletKind::Bitwise, label.chiplet());
+        assert_eq!("XOR", label.name());
+
+        let label = label_info(Felt::new(hasher::MP_VERIFY_LABEL as u64)).unwrap();
+        assert_eq!(ChipletKind::Hasher, label.chiplet());
+        assert_eq!("MP_VERIFY", label.name());
+
+        assert!(label_info(Felt::new(4)).is_none());
+    }
+
+    #[test]
+    fn label_collisions() {
+        assert!(labels_are_unique(&CHIPLET_LABELS));
+
+        // a new label colliding with the label of another chiplet is rejected
+        let mut labels = CHIPLET_LABELS.to_vec();
+        labels.push(LabelInfo::new(
+            ChipletKind::Memory,
+            "MEMORY_READ",
+            Felt::new(3),
+        ));
+        assert!(!labels_are_unique(&labels));
+
+        let labels = [LabelInfo::new(ChipletKind::Memory, "MEMORY", Felt::new(0))];
+        assert!(!labels_are_unique(&labels));
+    }
+}
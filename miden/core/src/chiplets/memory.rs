@@ -5,6 +5,20 @@ use super::{create_range, Felt, Range, MEMORY_TRACE_OFFSET};
 
 /// The number of elements accessible in one read or write memory access.
 pub const NUM_ELEMENTS: usize = 4;
+/// The maximum delta between two consecutive context IDs, addresses, or clock cycles in the memory
+/// trace. The delta is decomposed into three 16-bit limbs, each of which is range checked.
+///
+/// The delta is not split into two full 32-bit limbs: the range checker checks 16-bit values, so
+/// each 32-bit limb would be range checked as two 16-bit limbs anyway, and a 64-bit delta would
+/// admit every field element, including the wrap-around of a decreasing address. Capping the
+/// upper limb at 16 bits keeps the delta well below the field modulus at the cost of one lookup
+/// per memory row.
+///
+/// This is a partial implementation of 64-bit addresses: any field element can be used as an
+/// address, but only gaps of up to 48 bits between consecutive accessed addresses are supported,
+/// and accesses which would leave a wider gap are rejected by the processor. Address spaces with
+/// wider gaps would need a fourth limb, which is not implemented.
+pub const MAX_DELTA: u64 = (1 << 48) - 1;
 /// Column to hold the context ID of the current memory context.
 pub const CTX_COL_IDX: usize = MEMORY_TRACE_OFFSET;
 /// Column to hold the memory address.
@@ -22,12 +36,15 @@ pub const V_COL_RANGE: Range<usize> = create_range(U_COL_RANGE.end, NUM_ELEMENTS
 /// Column for the lower 16-bits of the delta between two consecutive context IDs, addresses, or
 /// clock cycles.
 pub const D0_COL_IDX: usize = V_COL_RANGE.end;
-/// Column for the upper 16-bits of the delta between two consecutive context IDs, addresses, or
-/// clock cycles.
+/// Column for the upper 16-bits of the lower 32-bit limb of the delta between two consecutive
+/// context IDs, addresses, or clock cycles.
 pub const D1_COL_IDX: usize = D0_COL_IDX + 1;
+/// Column for the upper 16-bit limb of the delta between two consecutive context IDs, addresses,
+/// or clock cycles (i.e., bits 32 through 47 of the delta).
+pub const D2_COL_IDX: usize = D1_COL_IDX + 1;
 /// Column for the inverse of the delta between two consecutive context IDs, addresses, or clock
 /// cycles, used to enforce that changes are correctly constrained.
-pub const D_INV_COL_IDX: usize = D1_COL_IDX + 1;
+pub const D_INV_COL_IDX: usize = D2_COL_IDX + 1;
 
 // --- OPERATION SELECTOR -----------------------------------------------------------------------
 
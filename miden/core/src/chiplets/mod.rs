@@ -1,11 +1,12 @@
 use super::{
-    utils::range as create_range, Felt, FieldElement, Word, CHIPLETS_OFFSET,
+    utils::range as create_range, Felt, FieldElement, Word, CHIPLETS_OFFSET, CHIPLETS_WIDTH,
     HASHER_AUX_TRACE_OFFSET,
 };
 use core::ops::Range;
 
 pub mod bitwise;
 pub mod hasher;
+pub mod labels;
 pub mod memory;
 
 // CONSTANTS
@@ -17,6 +18,21 @@ pub const NUM_HASHER_SELECTORS: usize = 1;
 pub const NUM_BITWISE_SELECTORS: usize = 2;
 /// The number of columns in the chiplets which are used as selectors for the memory chiplet.
 pub const NUM_MEMORY_SELECTORS: usize = 3;
+/// The number of columns in the chiplets which are used as selectors for the padding segment.
+pub const NUM_PADDING_SELECTORS: usize = 3;
+
+/// The values of the selector columns which identify rows of the hasher chiplet.
+pub const HASHER_SEGMENT_SELECTORS: [Felt; NUM_HASHER_SELECTORS] = [Felt::ZERO];
+/// The values of the selector columns which identify rows of the bitwise chiplet.
+pub const BITWISE_SEGMENT_SELECTORS: [Felt; NUM_BITWISE_SELECTORS] = [Felt::ONE, Felt::ZERO];
+/// The values of the selector columns which identify rows of the memory chiplet.
+pub const MEMORY_SEGMENT_SELECTORS: [Felt; NUM_MEMORY_SELECTORS] =
+    [Felt::ONE, Felt::ONE, Felt::ZERO];
+/// The values of the selector columns which identify rows of the padding segment. The padding
+/// segment always follows the last chiplet, and its selector prefix must not be a prefix of the
+/// selectors of any chiplet.
+pub const PADDING_SEGMENT_SELECTORS: [Felt; NUM_PADDING_SELECTORS] =
+    [Felt::ONE, Felt::ONE, Felt::ONE];
 
 /// The first column of the hash chiplet.
 pub const HASHER_TRACE_OFFSET: usize = CHIPLETS_OFFSET + NUM_HASHER_SELECTORS;
@@ -24,6 +40,11 @@ pub const HASHER_TRACE_OFFSET: usize = CHIPLETS_OFFSET + NUM_HASHER_SELECTORS;
 pub const BITWISE_TRACE_OFFSET: usize = CHIPLETS_OFFSET + NUM_BITWISE_SELECTORS;
 /// The first column of the memory chiplet.
 pub const MEMORY_TRACE_OFFSET: usize = CHIPLETS_OFFSET + NUM_MEMORY_SELECTORS;
+/// The first column of the padding segment.
+pub const PADDING_TRACE_OFFSET: usize = CHIPLETS_OFFSET + NUM_PADDING_SELECTORS;
+/// The number of columns in the padding segment which follow the padding selectors. All of these
+/// columns are set to ZERO.
+pub const PADDING_TRACE_WIDTH: usize = CHIPLETS_WIDTH - NUM_PADDING_SELECTORS;
 
 // --- GLOBALLY-INDEXED CHIPLET COLUMN ACCESSORS --------------------------------------------------
 
@@ -60,3 +81,5 @@ pub const BITWISE_B_COL_RANGE: Range<usize> = Range {
 pub const BITWISE_PREV_OUTPUT_COL_IDX: usize = BITWISE_TRACE_OFFSET + bitwise::PREV_OUTPUT_COL_IDX;
 /// The index within the main trace of the bitwise column containing the aggregated output value.
 pub const BITWISE_OUTPUT_COL_IDX: usize = BITWISE_TRACE_OFFSET + bitwise::OUTPUT_COL_IDX;
+/// The index within the main trace of the bitwise column containing the high limb flag.
+pub const BITWISE_HIGH_LIMB_COL_IDX: usize = BITWISE_TRACE_OFFSET + bitwise::HIGH_LIMB_COL_IDX;
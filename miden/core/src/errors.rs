@@ -20,6 +20,13 @@ pub enum AdviceSetError {
     NodeNotInSet(u64),
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HasherAddressError {
+    ZeroAddress,
+    AddressTooLarge(u64),
+    NotLookupRow(u64),
+}
+
 #[derive(Clone, Debug)]
 pub enum LibraryError {
     ModuleNotFound(String),
@@ -26,12 +26,17 @@ pub use advice::AdviceSet;
 ///    of elements and (2) a list of advice sets, which are used to provide nondeterministic
 ///    inputs for instructions which work with Merkle trees.
 ///
+/// Executions of programs from untrusted sources can be bounded by a gas limit: the processor
+/// charges the cost of every executed operation against the limit, and aborts the execution once
+/// the limit is exceeded.
+///
 /// TODO: add more detailed explanation.
 #[derive(Clone, Debug)]
 pub struct ProgramInputs {
     stack_init: Vec<Felt>,
     advice_tape: Vec<Felt>,
     advice_sets: BTreeMap<[u8; 32], AdviceSet>,
+    gas_limit: Option<u64>,
 }
 
 impl ProgramInputs {
@@ -92,6 +97,7 @@ impl ProgramInputs {
             stack_init: init_stack_elements,
             advice_tape: advice_tape_elements,
             advice_sets: advice_map,
+            gas_limit: None,
         })
     }
 
@@ -116,9 +122,17 @@ impl ProgramInputs {
             stack_init: Vec::new(),
             advice_tape: Vec::new(),
             advice_sets: BTreeMap::new(),
+            gas_limit: None,
         }
     }
 
+    /// Returns these [ProgramInputs] with the amount of gas executions against them may consume
+    /// set to the specified value.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -132,6 +146,17 @@ impl ProgramInputs {
         &self.advice_tape
     }
 
+    /// Returns a reference to the advice sets, keyed by their roots.
+    pub fn advice_sets(&self) -> &BTreeMap<[u8; 32], AdviceSet> {
+        &self.advice_sets
+    }
+
+    /// Returns the amount of gas executions against these inputs may consume, if a limit was
+    /// provided.
+    pub fn gas_limit(&self) -> Option<u64> {
+        self.gas_limit
+    }
+
     // DESTRUCTURING
     // --------------------------------------------------------------------------------------------
 
@@ -141,6 +166,7 @@ impl ProgramInputs {
             stack_init,
             advice_tape,
             advice_sets,
+            ..
         } = self;
 
         (stack_init, advice_tape, advice_sets)
@@ -13,8 +13,16 @@ pub enum Operation {
     /// Advances cycle counter, but does not change the state of user stack.
     Noop,
 
-    /// Pops the stack; if the popped value is not 1, execution fails.
-    Assert,
+    /// Pops the stack; if the popped value is not 1, execution fails with the specified error
+    /// code.
+    ///
+    /// The error code is only reported by the processor; it is not encoded in the operation
+    /// groups of the program, and thus, the hash of the program does not commit to it.
+    ///
+    /// Committing the error code in the outputs of a program is out of scope: a failed assertion
+    /// leaves no valid execution trace, and thus, there is no proof whose outputs could carry the
+    /// code. Failed preconditions are diagnosable only from the execution error.
+    Assert(u32),
 
     /// Pops an element off the stack, adds the current value of the `fmp` register to it, and
     /// pushes the result back onto the stack.
@@ -161,6 +169,27 @@ pub enum Operation {
     /// If either of the elements is greater than or equal to 2^32, execution fails.
     U32xor,
 
+    /// Interprets the top four elements of the stack as 32-bit limbs of two 64-bit values
+    /// [b_hi, b_lo, a_hi, a_lo], computes their binary AND, and overwrites the limbs of `a` with
+    /// the limbs of the result. The limbs of `b` are left on the stack.
+    ///
+    /// If any of the limbs is greater than or equal to 2^32, execution fails.
+    U64and,
+
+    /// Interprets the top four elements of the stack as 32-bit limbs of two 64-bit values
+    /// [b_hi, b_lo, a_hi, a_lo], computes their binary OR, and overwrites the limbs of `a` with
+    /// the limbs of the result. The limbs of `b` are left on the stack.
+    ///
+    /// If any of the limbs is greater than or equal to 2^32, execution fails.
+    U64or,
+
+    /// Interprets the top four elements of the stack as 32-bit limbs of two 64-bit values
+    /// [b_hi, b_lo, a_hi, a_lo], computes their binary XOR, and overwrites the limbs of `a` with
+    /// the limbs of the result. The limbs of `b` are left on the stack.
+    ///
+    /// If any of the limbs is greater than or equal to 2^32, execution fails.
+    U64xor,
+
     // ----- stack manipulation -------------------------------------------------------------------
     /// Pushes 0 onto the stack.
     Pad,
@@ -376,7 +405,7 @@ impl Operation {
             Self::FmpAdd    => 0b0000_0110,
             Self::MLoad     => 0b0000_0111,
             Self::Swap      => 0b0000_1000,
-            // <empty>      => 0b0000_1001,
+            Self::U64and    => 0b0000_1001,
             Self::MovUp2    => 0b0000_1010,
             Self::MovDn2    => 0b0000_1011,
             Self::MovUp3    => 0b0000_1100,
@@ -393,15 +422,15 @@ impl Operation {
             Self::MovUp7    => 0b0001_0110,
             Self::MovDn7    => 0b0001_0111,
             Self::SwapW     => 0b0001_1000,
-            // <empty>      => 0b0001_1001
+            Self::U64or     => 0b0001_1001,
             Self::MovUp8    => 0b0001_1010,
             Self::MovDn8    => 0b0001_1011,
             Self::SwapW2    => 0b0001_1100,
             Self::SwapW3    => 0b0001_1101,
             Self::SwapDW    => 0b0001_1110,
-            // <empty>      => 0b0001_1111
+            Self::U64xor    => 0b0001_1111,
 
-            Self::Assert    => 0b0010_0000,
+            Self::Assert(_) => 0b0010_0000,
             Self::Eq        => 0b0010_0001,
             Self::Add       => 0b0010_0010,
             Self::Mul       => 0b0010_0011,
@@ -493,7 +522,8 @@ impl fmt::Display for Operation {
         match self {
             // ----- system operations ------------------------------------------------------------
             Self::Noop => write!(f, "noop"),
-            Self::Assert => write!(f, "assert"),
+            Self::Assert(0) => write!(f, "assert"),
+            Self::Assert(err_code) => write!(f, "assert({})", err_code),
 
             Self::FmpAdd => write!(f, "fmpadd"),
             Self::FmpUpdate => write!(f, "fmpupdate"),
@@ -536,6 +566,10 @@ impl fmt::Display for Operation {
             Self::U32or => write!(f, "u32or"),
             Self::U32xor => write!(f, "u32xor"),
 
+            Self::U64and => write!(f, "u64and"),
+            Self::U64or => write!(f, "u64or"),
+            Self::U64xor => write!(f, "u64xor"),
+
             // ----- stack manipulation -----------------------------------------------------------
             Self::Drop => write!(f, "drop"),
             Self::Pad => write!(f, "pad"),
@@ -1,8 +1,11 @@
 use super::data::{InputFile, OutputFile, ProgramFile};
 use air::StarkField;
+use processor::ExecutionError;
+use std::fs;
 use std::path::PathBuf;
 use std::time::Instant;
 use structopt::StructOpt;
+use vm_core::MIN_STACK_DEPTH;
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "Run", about = "Run a miden program")]
@@ -19,6 +22,16 @@ pub struct RunCmd {
     /// Path to output file
     #[structopt(short = "o", long = "output", parse(from_os_str))]
     output_file: Option<PathBuf>,
+    /// Path to which the metadata of the trace columns is written as JSON
+    #[structopt(long = "trace-layout", parse(from_os_str))]
+    trace_layout_file: Option<PathBuf>,
+    /// Path to which the coverage of the code blocks of the program is written in lcov format
+    #[structopt(long = "coverage", parse(from_os_str))]
+    coverage_file: Option<PathBuf>,
+    /// Path to which the ledger of the chiplets bus is written as JSON
+    #[cfg(feature = "bus-ledger")]
+    #[structopt(long = "dump-bus", parse(from_os_str))]
+    bus_ledger_file: Option<PathBuf>,
 }
 
 impl RunCmd {
@@ -27,6 +40,14 @@ impl RunCmd {
         println!("Run program");
         println!("============================================================");
 
+        // only the top 16 stack values can be returned as outputs
+        if self.num_outputs > MIN_STACK_DEPTH {
+            return Err(format!(
+                "Invalid number of outputs - {:?}",
+                ExecutionError::TooManyStackOutputs(self.num_outputs)
+            ));
+        }
+
         // load program from file and compile
         let program = ProgramFile::read(&self.assembly_file)?;
 
@@ -36,9 +57,20 @@ impl RunCmd {
         print!("Executing program... ");
         let now = Instant::now();
 
-        // generate execution trace
-        let trace = processor::execute(&program, &input_data.get_program_inputs())
-            .map_err(|err| format!("Failed to generate exection trace = {:?}", err))?;
+        // generate execution trace; if the execution fails, it is repeated in debug mode to
+        // report the blocks in which it failed
+        let inputs = input_data.get_program_inputs();
+        let trace = match processor::execute(&program, &inputs) {
+            Ok(trace) => trace,
+            Err(err) => {
+                return Err(
+                    match processor::execute_with_call_stack(&program, &inputs) {
+                        Err(failure) => format!("Failed to generate exection trace = {}", failure),
+                        Ok(_) => format!("Failed to generate exection trace = {:?}", err),
+                    },
+                );
+            }
+        };
 
         println!("done ({} ms)", now.elapsed().as_millis());
 
@@ -51,6 +83,35 @@ impl RunCmd {
         // write outputs to file
         OutputFile::write(outputs, &self.output_file)?;
 
+        // write metadata of the trace columns to file
+        if let Some(path) = &self.trace_layout_file {
+            let metadata = trace.metadata(input_data.stack_init.len(), self.num_outputs);
+            fs::write(path, metadata.to_json())
+                .map_err(|err| format!("Failed to write trace layout file - {}", err))?;
+        }
+
+        // write the coverage of the program to file; the program is executed again, since the
+        // coverage is not recorded when the trace is generated
+        if let Some(path) = &self.coverage_file {
+            let report = processor::collect_coverage(&program, &inputs)
+                .map_err(|err| format!("Failed to collect coverage - {:?}", err))?;
+            fs::write(path, report.to_lcov())
+                .map_err(|err| format!("Failed to write coverage file - {}", err))?;
+            println!(
+                "Covered {} of {} operations; {} code blocks were never executed",
+                report.num_covered_ops(),
+                report.num_ops(),
+                report.uncovered_blocks().count()
+            );
+        }
+
+        // write the ledger of the chiplets bus to file
+        #[cfg(feature = "bus-ledger")]
+        if let Some(path) = &self.bus_ledger_file {
+            fs::write(path, trace.chiplets_bus().to_ledger_json())
+                .map_err(|err| format!("Failed to write bus ledger file - {}", err))?;
+        }
+
         Ok(())
     }
 }
@@ -6,10 +6,23 @@
 pub use air::{FieldExtension, HashFunction, ProofOptions};
 pub use assembly::{Assembler, AssemblyError};
 pub use processor::{
-    execute, execute_iter, AsmOpInfo, ExecutionError, ExecutionTrace, VmState, VmStateIterator,
+    analyze_loops, collect_coverage, dry_run, estimate_program, execute, execute_iter,
+    execute_with_call_stack, execute_with_options, validate_program, AbortReason, AsmOpInfo,
+    BlockCoverage, BlockKind, CallFrame, CallStack, CancellationToken, CoverageReport,
+    DryRunReport, ExecutionError, ExecutionEstimate, ExecutionFailure, ExecutionOptions,
+    ExecutionReport, ExecutionTrace, GasCosts, LoopReport, LoopStats, PaddingPolicy, ProgramLimits,
+    ValidationError, VmState, VmStateIterator, Watchdog,
 };
-pub use prover::{prove, StarkProof};
-pub use verifier::{verify, VerificationError};
+#[cfg(feature = "pub-input-extension")]
+pub use prover::prove_with_extension;
+pub use prover::{prove, prove_with_output_commitment, prove_with_padding, StarkProof};
+pub use verifier::{
+    hashers, output_commitment, prepare_for_aggregation, public_inputs_hash, verify, verify_any,
+    verify_bounded, verify_hybrid, verify_outputs, verify_with_output_commitment,
+    AggregationInputs, BoundedStackValues, ProofView, VerificationError, VerificationFailure,
+};
+#[cfg(feature = "pub-input-extension")]
+pub use verifier::{verify_with_extension, PUB_INPUT_EXTENSION_SIZE};
 pub use vm_core::{
     chiplets::hasher::Digest,
     errors::{AdviceSetError, InputError},
@@ -17,6 +17,7 @@ pub struct Cli {
 #[derive(StructOpt, Debug)]
 pub enum Actions {
     Analyze(tools::Analyze),
+    BusDiff(tools::BusDiff),
     Compile(cli::CompileCmd),
     Example(examples::ExampleOptions),
     Prove(cli::ProveCmd),
@@ -29,6 +30,7 @@ impl Cli {
     pub fn execute(&self) -> Result<(), String> {
         match &self.action {
             Actions::Analyze(analyze) => analyze.execute(),
+            Actions::BusDiff(bus_diff) => bus_diff.execute(),
             Actions::Compile(compile) => compile.execute(),
             Actions::Example(example) => example.execute(),
             Actions::Prove(prove) => prove.execute(),
@@ -0,0 +1,120 @@
+use serde_derive::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+// CLI
+// ================================================================================================
+
+/// Defines cli interface
+#[derive(StructOpt, Debug)]
+#[structopt(about = "Compare two chiplets bus ledgers dumped with `run --dump-bus`")]
+pub struct BusDiff {
+    /// Path to the expected bus ledger
+    #[structopt(parse(from_os_str))]
+    expected_file: PathBuf,
+    /// Path to the actual bus ledger
+    #[structopt(parse(from_os_str))]
+    actual_file: PathBuf,
+}
+
+/// Implements CLI execution logic
+impl BusDiff {
+    pub fn execute(&self) -> Result<(), String> {
+        let expected = BusLedger::read(&self.expected_file)?;
+        let actual = BusLedger::read(&self.actual_file)?;
+        if expected.alpha != actual.alpha {
+            return Err(format!(
+                "ledgers were reduced with different random elements ({} and {}), and thus, their \
+                 values cannot be compared",
+                expected.alpha, actual.alpha
+            ));
+        }
+
+        // counts of the entries of every kind and chiplet operation
+        let expected_counts = expected.counts();
+        let actual_counts = actual.counts();
+        let keys: BTreeSet<_> = expected_counts.keys().chain(actual_counts.keys()).collect();
+        for key in keys {
+            let expected_count = expected_counts.get(key).copied().unwrap_or(0);
+            let actual_count = actual_counts.get(key).copied().unwrap_or(0);
+            if expected_count != actual_count {
+                println!(
+                    "{} {} lookups: expected {}, found {}",
+                    key.1, key.0, expected_count, actual_count
+                );
+            }
+        }
+
+        match expected.first_divergence(&actual) {
+            None => {
+                println!("ledgers match ({} entries)", expected.entries.len());
+                Ok(())
+            }
+            Some(i) => {
+                println!("ledgers diverge at entry #{}:", i);
+                println!("  expected: {}", describe(expected.entries.get(i)));
+                println!("  actual:   {}", describe(actual.entries.get(i)));
+                Err("Bus ledgers differ".to_string())
+            }
+        }
+    }
+}
+
+// BUS LEDGER
+// ================================================================================================
+
+/// Ledger of the chiplets bus, as written by `ChipletsBus::to_ledger_json()`.
+#[derive(Deserialize, Debug)]
+struct BusLedger {
+    alpha: u64,
+    entries: Vec<LedgerEntry>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+struct LedgerEntry {
+    cycle: usize,
+    kind: String,
+    row: String,
+    value: u64,
+}
+
+impl BusLedger {
+    /// Reads the ledger from the specified file.
+    fn read(path: &Path) -> Result<Self, String> {
+        let ledger = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to open bus ledger {} - {}", path.display(), err))?;
+        serde_json::from_str(&ledger)
+            .map_err(|err| format!("Failed to parse bus ledger {} - {}", path.display(), err))
+    }
+
+    /// Returns the number of entries for every (kind, chiplet operation) pair.
+    fn counts(&self) -> BTreeMap<(&str, &str), usize> {
+        let mut counts = BTreeMap::new();
+        for entry in self.entries.iter() {
+            *counts
+                .entry((entry.kind.as_str(), entry.row.as_str()))
+                .or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns the index of the first entry which differs between this and the other ledger, or
+    /// None if the ledgers are the same.
+    fn first_divergence(&self, other: &Self) -> Option<usize> {
+        let num_entries = self.entries.len().max(other.entries.len());
+        (0..num_entries).find(|&i| self.entries.get(i) != other.entries.get(i))
+    }
+}
+
+/// Returns a description of the specified ledger entry.
+fn describe(entry: Option<&LedgerEntry>) -> String {
+    match entry {
+        Some(entry) => format!(
+            "{} of a {} lookup at cycle {} with value {}",
+            entry.kind, entry.row, entry.cycle, entry.value
+        ),
+        None => "no entry".to_string(),
+    }
+}
@@ -6,6 +6,9 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 use vm_core::{utils::collections::Vec, Operation, ProgramInputs};
 
+mod bus_diff;
+pub use bus_diff::BusDiff;
+
 // CLI
 // ================================================================================================
 
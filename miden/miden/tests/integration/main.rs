@@ -6,6 +6,8 @@ mod flow_control;
 mod operations;
 mod stdlib;
 
+use vm_core::StarkField;
+
 // TESTS
 // ================================================================================================
 
@@ -20,6 +22,273 @@ fn multi_output_program() {
     test.prove_and_verify(vec![1, 2, 3], 2, false);
 }
 
+#[test]
+fn verify_any_program_hash() {
+    let test = build_test!("begin mul movup.2 drop end", &[1, 2, 3]);
+    let program = test.compile();
+    let other_program = build_test!("begin push.1 drop end").compile();
+    let (outputs, proof) =
+        prover::prove(&program, &test.inputs, 2, &helpers::ProofOptions::default()).unwrap();
+
+    // the matching hash is returned regardless of its position in the list
+    let hashes = [other_program.hash(), program.hash()];
+    let result = miden::verify_any(&hashes, &[1, 2, 3], &outputs, proof.clone());
+    assert_eq!(Ok(program.hash()), result);
+
+    // verification fails if none of the hashes match
+    let hashes = [other_program.hash()];
+    assert!(miden::verify_any(&hashes, &[1, 2, 3], &outputs, proof.clone()).is_err());
+
+    // verification fails if no hashes are provided
+    assert_eq!(
+        Err(miden::VerificationError::NoProgramHashes),
+        miden::verify_any(&[], &[1, 2, 3], &outputs, proof)
+    );
+}
+
+#[test]
+fn reject_stack_overflow_outputs() {
+    let stack_inputs = (1..=16).collect::<Vec<u64>>();
+    let test = build_test!("begin push.17 push.18 end", &stack_inputs);
+    let program = test.compile();
+    let options = helpers::ProofOptions::default();
+    let (outputs, proof) = prover::prove(&program, &test.inputs, 16, &options).unwrap();
+    assert_eq!((3..=18).rev().collect::<Vec<u64>>(), outputs);
+
+    // values in the stack overflow table are not constrained by the AIR, and thus, they cannot
+    // be returned as outputs
+    assert!(prover::prove(&program, &test.inputs, 17, &options).is_err());
+
+    let mut overflow_outputs = outputs.clone();
+    overflow_outputs.push(2);
+    assert_eq!(
+        Err(miden::VerificationError::TooManyOutputValues(16, 17)),
+        miden::verify(program.hash(), &stack_inputs, &overflow_outputs, proof)
+    );
+}
+
+#[test]
+fn verify_output_commitment() {
+    // the program reads the commitment to its outputs from the advice tape and leaves it on top
+    // of the stack, with the first element of the commitment at the top
+    let outputs = (1..=100).collect::<Vec<u64>>();
+    let commitment = miden::output_commitment(&outputs).unwrap();
+    let mut advice_tape = commitment
+        .as_elements()
+        .iter()
+        .map(|e| e.as_int())
+        .collect::<Vec<u64>>();
+    advice_tape.reverse();
+    let test = build_test!("begin push.adv.4 end", &[], &advice_tape, vec![]);
+    let program = test.compile();
+    let options = helpers::ProofOptions::default();
+    let (proven_commitment, proof) =
+        miden::prove_with_output_commitment(&program, &test.inputs, &options).unwrap();
+    assert_eq!(commitment, proven_commitment);
+
+    // the proof verifies against the commitment and the outputs it commits to
+    let result =
+        miden::verify_with_output_commitment(program.hash(), &[], commitment, proof.clone());
+    assert!(result.is_ok());
+    assert!(miden::verify_outputs(program.hash(), &[], &outputs, proof.clone()).is_ok());
+
+    // the proof does not verify against different outputs or without the commitment
+    let mut other_outputs = outputs.clone();
+    other_outputs[99] = 0;
+    let result = miden::verify_outputs(program.hash(), &[], &other_outputs, proof.clone());
+    assert!(result.is_err());
+    assert!(miden::verify(program.hash(), &[], &[], proof).is_err());
+
+    // outputs which are not field elements have no commitment
+    assert_eq!(
+        Err(miden::VerificationError::OutputNotFieldElement(u64::MAX)),
+        miden::output_commitment(&[u64::MAX])
+    );
+}
+
+#[test]
+fn verify_hybrid_commitments() {
+    use miden::hashers::{DefaultSha2, Rp64_256, Sha2_256};
+    use vm_core::Felt;
+
+    let test = build_test!("begin mul movup.2 drop end", &[1, 2, 3]);
+    let program = test.compile();
+    let options = helpers::ProofOptions::with_sha2();
+    let (outputs, proof) = prover::prove(&program, &test.inputs, 2, &options).unwrap();
+
+    // a proof with trace commitments made with the declared hash function verifies in the same
+    // way as via verify()
+    let result = miden::verify_hybrid::<Sha2_256<Felt, DefaultSha2>>(
+        program.hash(),
+        &[1, 2, 3],
+        &outputs,
+        proof.clone(),
+    );
+    assert!(result.is_ok());
+
+    // the trace commitments of the proof were not made with RPO
+    let result =
+        miden::verify_hybrid::<Rp64_256>(program.hash(), &[1, 2, 3], &outputs, proof.clone());
+    assert!(result.is_err());
+
+    // trace commitments must have digests of the same size as the declared hash function
+    let options = helpers::ProofOptions::with_96_bit_security();
+    let (outputs, proof) = prover::prove(&program, &test.inputs, 2, &options).unwrap();
+    let err =
+        miden::verify_hybrid::<Rp64_256>(program.hash(), &[1, 2, 3], &outputs, proof).unwrap_err();
+    assert!(matches!(
+        err.failure(),
+        Some(miden::VerificationFailure::Deserialization(_))
+    ));
+}
+
+#[test]
+fn verification_failure_categories() {
+    let test = build_test!("begin mul movup.2 drop end", &[1, 2, 3]);
+    let program = test.compile();
+    let options = helpers::ProofOptions::default();
+    let (mut outputs, proof) = prover::prove(&program, &test.inputs, 2, &options).unwrap();
+
+    // wrong outputs change the boundary constraints, and thus, the out-of-domain evaluations
+    outputs[0] += 1;
+    let err = miden::verify(program.hash(), &[1, 2, 3], &outputs, proof.clone()).unwrap_err();
+    assert!(matches!(
+        err.failure(),
+        Some(miden::VerificationFailure::OodMismatch(_))
+    ));
+
+    // errors which are not raised by the STARK verifier have no category
+    let err = miden::verify(program.hash(), &[u64::MAX], &outputs, proof).unwrap_err();
+    assert_eq!(None, err.failure());
+}
+
+#[test]
+fn verify_rpo_proof() {
+    let test = build_test!("begin mul movup.2 drop end", &[1, 2, 3]);
+    let program = test.compile();
+    let options = helpers::ProofOptions::with_rpo();
+    let (outputs, proof) = prover::prove(&program, &test.inputs, 2, &options).unwrap();
+    assert_eq!(miden::HashFunction::Rp64_256, proof.options().hash_fn());
+
+    // the commitments of the proof are checked with RPO, both by the deserialized and by the
+    // serialized proof verifiers
+    let proof_bytes = proof.to_bytes();
+    assert!(miden::verify(program.hash(), &[1, 2, 3], &outputs, proof.clone()).is_ok());
+    assert!(miden::verify(program.hash(), &[1, 2, 4], &outputs, proof).is_err());
+    let result = miden::verify_bounded(program.hash(), &[1, 2, 3], &outputs, &proof_bytes);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn verify_bounded_proof() {
+    let test = build_test!("begin mul movup.2 drop end", &[1, 2, 3]);
+    let program = test.compile();
+    let options = helpers::ProofOptions::default();
+    let (outputs, proof) = prover::prove(&program, &test.inputs, 2, &options).unwrap();
+    let proof_bytes = proof.to_bytes();
+
+    // the parsed sections match the deserialized proof
+    let view = miden::ProofView::parse(&proof_bytes).unwrap();
+    assert_eq!(
+        proof.context.trace_layout().main_trace_width(),
+        view.main_trace_width()
+    );
+    assert_eq!(proof.context.trace_length(), view.trace_length());
+    assert_eq!(proof.pow_nonce, view.pow_nonce());
+
+    // the serialized proof verifies in the same way as the deserialized one
+    let result = miden::verify_bounded(program.hash(), &[1, 2, 3], &outputs, &proof_bytes);
+    assert!(result.is_ok());
+    let result = miden::verify_bounded(program.hash(), &[1, 2, 4], &outputs, &proof_bytes);
+    assert!(result.is_err());
+
+    // truncated proofs are rejected before they are deserialized
+    assert!(matches!(
+        miden::verify_bounded(program.hash(), &[1, 2, 3], &outputs, &proof_bytes[..20]),
+        Err(miden::VerificationError::MalformedProof(_))
+    ));
+
+    // outputs beyond the top 16 stack registers are not supported
+    assert_eq!(
+        Err(miden::VerificationError::TooManyOutputValues(16, 17)),
+        miden::verify_bounded(program.hash(), &[1, 2, 3], &[0; 17], &proof_bytes)
+    );
+}
+
+#[test]
+fn public_inputs_hash() {
+    let test = build_test!("begin mul movup.2 drop end", &[1, 2, 3]);
+    let program = test.compile();
+    let hash = miden::public_inputs_hash(program.hash(), &[1, 2, 3], &[6, 1]).unwrap();
+    let other_program = build_test!("begin add movup.2 drop end", &[1, 2, 3]).compile();
+
+    // the hash depends on every value of the statement
+    let other_hashes = [
+        miden::public_inputs_hash(program.hash(), &[1, 2, 4], &[6, 1]),
+        miden::public_inputs_hash(program.hash(), &[1, 2, 3], &[7, 1]),
+        miden::public_inputs_hash(other_program.hash(), &[1, 2, 3], &[6, 1]),
+    ];
+    for other_hash in other_hashes {
+        assert_ne!(hash, other_hash.unwrap());
+    }
+
+    // the AIR asserts only the provided stack values, and thus, padding the values with zeros
+    // changes the statement
+    let padded_hash = miden::public_inputs_hash(program.hash(), &[1, 2, 3], &[6, 1, 0]).unwrap();
+    assert_ne!(hash, padded_hash);
+
+    // outputs beyond the top 16 stack registers cannot be part of the statement
+    assert_eq!(
+        Err(miden::VerificationError::TooManyOutputValues(16, 17)),
+        miden::public_inputs_hash(program.hash(), &[], &[0; 17])
+    );
+
+    // values which are not field elements have no hash
+    assert_eq!(
+        Err(miden::VerificationError::InputNotFieldElement(u64::MAX)),
+        miden::public_inputs_hash(program.hash(), &[u64::MAX], &[])
+    );
+}
+
+#[cfg(feature = "pub-input-extension")]
+#[test]
+fn verify_with_pub_input_extension() {
+    let test = build_test!("begin mul movup.2 drop end", &[1, 2, 3]);
+    let program = test.compile();
+    let options = helpers::ProofOptions::default();
+    let extension = [7u8; miden::PUB_INPUT_EXTENSION_SIZE];
+    let (outputs, proof) =
+        miden::prove_with_extension(&program, &test.inputs, 2, &options, extension).unwrap();
+
+    // the proof verifies against the extension it was generated with
+    let result = miden::verify_with_extension(
+        program.hash(),
+        &[1, 2, 3],
+        &outputs,
+        extension,
+        proof.clone(),
+    );
+    assert!(result.is_ok());
+
+    // the proof does not verify against a different extension or without an extension
+    let other_extension = [8u8; miden::PUB_INPUT_EXTENSION_SIZE];
+    let result = miden::verify_with_extension(
+        program.hash(),
+        &[1, 2, 3],
+        &outputs,
+        other_extension,
+        proof.clone(),
+    );
+    assert!(result.is_err());
+    assert!(miden::verify(program.hash(), &[1, 2, 3], &outputs, proof).is_err());
+
+    // proofs without an extension do not verify against an extension
+    let (outputs, proof) = prover::prove(&program, &test.inputs, 2, &options).unwrap();
+    let result =
+        miden::verify_with_extension(program.hash(), &[1, 2, 3], &outputs, extension, proof);
+    assert!(result.is_err());
+}
+
 // MACROS TO BUILD TESTS
 // ================================================================================================
 
@@ -165,6 +165,44 @@ fn u32checked_not_fail() {
     test_input_out_of_bounds(asm_op);
 }
 
+#[test]
+fn u64checked_bitwise() {
+    let a = rand_value::<u64>();
+    let b = rand_value::<u64>();
+    let inputs = [a as u32 as u64, a >> 32, b as u32 as u64, b >> 32];
+
+    let ops: [(&str, fn(u64, u64) -> u64); 3] = [
+        ("u64checked_and", |a, b| a & b),
+        ("u64checked_or", |a, b| a | b),
+        ("u64checked_xor", |a, b| a ^ b),
+    ];
+
+    for (asm_op, op) in ops {
+        let c = op(a, b);
+
+        let test = build_op_test!(asm_op, &inputs);
+        test.expect_stack(&[c >> 32, c as u32 as u64]);
+
+        // --- test that the rest of the stack isn't affected -------------------------------------
+        let e = rand_value::<u32>() as u64;
+        let test = build_op_test!(asm_op, &[e, inputs[0], inputs[1], inputs[2], inputs[3]]);
+        test.expect_stack(&[c >> 32, c as u32 as u64, e]);
+    }
+}
+
+#[test]
+fn u64checked_bitwise_fail() {
+    for asm_op in ["u64checked_and", "u64checked_or", "u64checked_xor"] {
+        for limb_idx in 0..4 {
+            let mut inputs = [0; 4];
+            inputs[limb_idx] = U32_BOUND;
+
+            let test = build_op_test!(asm_op, &inputs);
+            test.expect_error(TestError::ExecutionError("NotU32Value"));
+        }
+    }
+}
+
 #[test]
 fn u32checked_shl() {
     // left shift: pops a from the stack and pushes (a * 2^b) mod 2^32 for a provided value b
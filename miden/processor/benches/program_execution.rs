@@ -22,6 +22,21 @@ fn program_execution(c: &mut Criterion) {
         bench.iter(|| execute(&program, &ProgramInputs::none()));
     });
 
+    group.bench_function("span_batches", |bench| {
+        // a single span of 200000 operations is hashed in about 2800 batches
+        let source = "
+            begin
+                repeat.200000
+                    swap
+                end
+            end";
+        let assembler = Assembler::default();
+        let program = assembler
+            .compile(source)
+            .expect("Failed to compile test source.");
+        bench.iter(|| execute(&program, &ProgramInputs::none()));
+    });
+
     group.finish();
 }
 
@@ -0,0 +1,271 @@
+use super::{CodeBlock, Digest, OpBatch, Program, Span, Vec, OP_BATCH_SIZE, OP_GROUP_SIZE};
+use core::fmt;
+use vm_core::chiplets::hasher::HASH_CYCLE_LEN;
+
+#[cfg(test)]
+mod tests;
+
+// BATCHING REPORT
+// ================================================================================================
+
+/// Report on how well the operations of the SPAN blocks of a program are packed into operation
+/// batches.
+///
+/// Every operation batch of a SPAN block is absorbed by the hasher chiplet with a single
+/// permutation, and every batch after the first one is started with a RESPAN operation. Poorly
+/// packed batches thus increase both the number of hasher rows and the number of decoder cycles
+/// needed to execute a program. The report lists the NOOPs the processor executes to satisfy the
+/// alignment rules of batches, and suggests how the operations could be reordered to reduce them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchingReport {
+    /// Reports on every SPAN block of the program, in execution order.
+    pub spans: Vec<SpanBatching>,
+}
+
+impl BatchingReport {
+    /// Returns the total number of NOOPs executed to pad operation batches.
+    pub fn num_padding_noops(&self) -> usize {
+        self.spans.iter().map(|span| span.num_padding_noops).sum()
+    }
+
+    /// Returns the total number of RESPAN operations which could be avoided if the operations of
+    /// every SPAN block were packed optimally.
+    pub fn num_avoidable_respans(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|span| span.num_avoidable_respans())
+            .sum()
+    }
+
+    /// Returns the total number of hasher chiplet rows which could be saved if the operations of
+    /// every SPAN block were packed optimally.
+    pub fn num_avoidable_hasher_rows(&self) -> usize {
+        self.num_avoidable_respans() * HASH_CYCLE_LEN
+    }
+
+    /// Returns an iterator over all suggestions of this report, together with the hash of the
+    /// SPAN block each suggestion applies to.
+    pub fn suggestions(&self) -> impl Iterator<Item = (Digest, &BatchingSuggestion)> {
+        self.spans
+            .iter()
+            .flat_map(|span| span.suggestions.iter().map(move |s| (span.hash, s)))
+    }
+
+    /// Returns true if no SPAN block of the program could be packed better.
+    pub fn is_well_packed(&self) -> bool {
+        self.spans.iter().all(|span| span.suggestions.is_empty())
+    }
+}
+
+/// Report on how well the operations of a single SPAN block are packed into operation batches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpanBatching {
+    /// Hash of the SPAN block.
+    pub hash: Digest,
+    /// Number of operations in the SPAN block.
+    pub num_ops: usize,
+    /// Number of operation batches the SPAN block is split into.
+    pub num_batches: usize,
+    /// Lower bound on the number of operation batches the operations of the SPAN block could be
+    /// split into if they were ordered optimally.
+    pub min_batches: usize,
+    /// Number of NOOPs executed to pad the operation batches of the SPAN block.
+    pub num_padding_noops: usize,
+    /// Suggestions on how to pack the operations of the SPAN block better.
+    pub suggestions: Vec<BatchingSuggestion>,
+}
+
+impl SpanBatching {
+    /// Returns the number of RESPAN operations which could be avoided if the operations of this
+    /// SPAN block were packed optimally.
+    pub fn num_avoidable_respans(&self) -> usize {
+        self.num_batches - self.min_batches
+    }
+}
+
+// BATCHING SUGGESTION
+// ================================================================================================
+
+/// Suggestion on how to reorder the operations of a SPAN block to pack them into batches better.
+///
+/// Batches and groups are identified by their indexes within the SPAN block and the batch
+/// respectively.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchingSuggestion {
+    /// An operation carrying an immediate value would have been the last operation in the
+    /// specified group of the specified batch, and thus, it was moved into the next group,
+    /// leaving the last slot of the group empty. Moving an operation without an immediate value
+    /// in front of it fills the slot.
+    MisalignedImmediate(usize, usize),
+    /// The specified group of the specified batch ends with an operation carrying an immediate
+    /// value, and thus, a NOOP is executed after it. Moving an operation without an immediate
+    /// value after it avoids the NOOP.
+    TrailingImmediate(usize, usize),
+    /// The specified batch contains a number of groups which is not a power of two, and thus,
+    /// the specified number of NOOPs is executed to pad it. Moving operations from the next
+    /// batch into this batch, or splitting the SPAN block at a different operation, avoids the
+    /// NOOPs.
+    PaddedGroups(usize, usize),
+    /// The SPAN block is split into more batches than needed, and the specified number of
+    /// RESPAN operations could be avoided. Grouping operations carrying immediate values
+    /// together, so that they fill whole groups with their values, reduces the number of
+    /// batches.
+    AvoidableRespans(usize),
+}
+
+impl fmt::Display for BatchingSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MisalignedImmediate(batch, group) => write!(
+                f,
+                "group {} of batch {} leaves its last slot empty for an operation with an immediate value; move an operation without an immediate value in front of it",
+                group, batch
+            ),
+            Self::TrailingImmediate(batch, group) => write!(
+                f,
+                "group {} of batch {} ends with an operation with an immediate value, which is followed by a NOOP; move an operation without an immediate value after it",
+                group, batch
+            ),
+            Self::PaddedGroups(batch, num_noops) => write!(
+                f,
+                "batch {} is padded with {} NOOP groups; move operations from the next batch into it",
+                batch, num_noops
+            ),
+            Self::AvoidableRespans(num_respans) => write!(
+                f,
+                "{} RESPAN operations could be avoided; group operations with immediate values together",
+                num_respans
+            ),
+        }
+    }
+}
+
+// BATCHING ANALYSIS
+// ================================================================================================
+
+/// Analyzes how well the operations of all SPAN blocks of the provided program are packed into
+/// operation batches.
+///
+/// Every SPAN block is analyzed once, regardless of how many times it is executed. The code block
+/// tree is traversed iteratively, and blocks which cannot be executed by the processor (e.g.,
+/// PROXY blocks) are skipped.
+pub fn analyze_batching(program: &Program) -> BatchingReport {
+    let mut spans = Vec::new();
+    let mut blocks = vec![program.root()];
+
+    while let Some(block) = blocks.pop() {
+        match block {
+            CodeBlock::Join(block) => {
+                // push the second child first so that blocks are visited in execution order
+                blocks.push(block.second());
+                blocks.push(block.first());
+            }
+            CodeBlock::Split(block) => {
+                blocks.push(block.on_false());
+                blocks.push(block.on_true());
+            }
+            CodeBlock::Loop(block) => blocks.push(block.body()),
+            CodeBlock::Span(block) => spans.push(analyze_span_block(block)),
+            _ => (),
+        }
+    }
+
+    BatchingReport { spans }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Analyzes how well the operations of the provided SPAN block are packed into batches.
+fn analyze_span_block(block: &Span) -> SpanBatching {
+    let op_batches = block.op_batches();
+    let mut num_ops = 0;
+    let mut num_imm_ops = 0;
+    let mut num_padding_noops = 0;
+    let mut suggestions = Vec::new();
+
+    for (batch_idx, batch) in op_batches.iter().enumerate() {
+        num_ops += batch.ops().len();
+        num_imm_ops += batch
+            .ops()
+            .iter()
+            .filter(|op| op.imm_value().is_some())
+            .count();
+        num_padding_noops += analyze_op_batch(batch, batch_idx, &mut suggestions);
+    }
+
+    // every operation takes a slot in a group, and every immediate value takes a whole group
+    let min_groups = (num_ops + OP_GROUP_SIZE - 1) / OP_GROUP_SIZE + num_imm_ops;
+    let min_batches = (min_groups + OP_BATCH_SIZE - 1) / OP_BATCH_SIZE;
+    let num_batches = op_batches.len();
+    if num_batches > min_batches {
+        suggestions.push(BatchingSuggestion::AvoidableRespans(
+            num_batches - min_batches,
+        ));
+    }
+
+    SpanBatching {
+        hash: block.hash(),
+        num_ops,
+        num_batches,
+        min_batches,
+        num_padding_noops,
+        suggestions,
+    }
+}
+
+/// Walks the groups of the provided batch in the same way as the processor executes them, adds
+/// suggestions for poorly packed groups to the provided list, and returns the number of NOOPs
+/// executed to pad the batch.
+fn analyze_op_batch(
+    batch: &OpBatch,
+    batch_idx: usize,
+    suggestions: &mut Vec<BatchingSuggestion>,
+) -> usize {
+    let ops = batch.ops();
+    let op_counts = batch.op_counts();
+    let mut num_noops = 0;
+    let mut op_offset = 0;
+    let mut group_idx = 0;
+
+    while group_idx < batch.num_groups() {
+        let group_ops = &ops[op_offset..op_offset + op_counts[group_idx]];
+        op_offset += group_ops.len();
+
+        // groups following an operation with an immediate value hold the value, and are skipped
+        let num_imm_ops = group_ops
+            .iter()
+            .filter(|op| op.imm_value().is_some())
+            .count();
+
+        if group_ops.len() == OP_GROUP_SIZE - 1 {
+            if let Some(next_op) = ops.get(op_offset) {
+                if next_op.imm_value().is_some() {
+                    suggestions.push(BatchingSuggestion::MisalignedImmediate(
+                        batch_idx, group_idx,
+                    ));
+                }
+            }
+        }
+        if let Some(last_op) = group_ops.last() {
+            if last_op.imm_value().is_some() {
+                suggestions.push(BatchingSuggestion::TrailingImmediate(batch_idx, group_idx));
+                num_noops += 1;
+            }
+        }
+
+        group_idx += 1 + num_imm_ops;
+    }
+
+    // the processor executes a number of groups which is a power of two
+    let num_padding_groups = batch.num_groups().next_power_of_two() - batch.num_groups();
+    if num_padding_groups > 0 {
+        suggestions.push(BatchingSuggestion::PaddedGroups(
+            batch_idx,
+            num_padding_groups,
+        ));
+        num_noops += num_padding_groups;
+    }
+
+    num_noops
+}
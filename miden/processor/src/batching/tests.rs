@@ -0,0 +1,93 @@
+use super::{analyze_batching, BatchingSuggestion, CodeBlock, Program};
+use vm_core::{chiplets::hasher::HASH_CYCLE_LEN, Felt, FieldElement, Operation};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn analyze_well_packed_span() {
+    // 72 operations without immediate values fill all 8 groups of a single batch
+    let program = Program::new(CodeBlock::new_span(vec![Operation::Add; 72]));
+    let report = analyze_batching(&program);
+
+    assert_eq!(1, report.spans.len());
+    assert_eq!(72, report.spans[0].num_ops);
+    assert_eq!(1, report.spans[0].num_batches);
+    assert_eq!(1, report.spans[0].min_batches);
+    assert_eq!(0, report.num_padding_noops());
+    assert!(report.is_well_packed());
+}
+
+#[test]
+fn analyze_misaligned_immediate() {
+    // the PUSH would be the 9th operation of the first group, and thus, it is moved into the
+    // second group, which it ends; its value takes the third group, and the fourth group is
+    // padding
+    let mut ops = vec![Operation::Add; 8];
+    ops.push(Operation::Push(Felt::ONE));
+    let program = Program::new(CodeBlock::new_span(ops));
+    let report = analyze_batching(&program);
+
+    let expected = vec![
+        BatchingSuggestion::MisalignedImmediate(0, 0),
+        BatchingSuggestion::TrailingImmediate(0, 1),
+        BatchingSuggestion::PaddedGroups(0, 1),
+    ];
+    assert_eq!(expected, report.spans[0].suggestions);
+    assert_eq!(2, report.num_padding_noops());
+    assert_eq!(0, report.num_avoidable_respans());
+}
+
+#[test]
+fn analyze_avoidable_respans() {
+    // every PUSH after the first one is aligned to the start of a group, which leaves no room
+    // for the value of the PUSH which ends the second batch
+    let mut ops = Vec::new();
+    for _ in 0..8 {
+        ops.extend_from_slice(&[Operation::Add; 8]);
+        ops.push(Operation::Push(Felt::ONE));
+    }
+    let program = Program::new(CodeBlock::new_span(ops));
+    let report = analyze_batching(&program);
+
+    let span = &report.spans[0];
+    assert_eq!(3, span.num_batches);
+    assert_eq!(2, span.min_batches);
+    assert!(span
+        .suggestions
+        .contains(&BatchingSuggestion::MisalignedImmediate(0, 0)));
+    assert!(span
+        .suggestions
+        .contains(&BatchingSuggestion::PaddedGroups(0, 1)));
+    assert!(span
+        .suggestions
+        .contains(&BatchingSuggestion::AvoidableRespans(1)));
+    assert_eq!(HASH_CYCLE_LEN, report.num_avoidable_hasher_rows());
+}
+
+#[test]
+fn analyze_control_blocks() {
+    // every SPAN block is analyzed once, in execution order
+    let first = CodeBlock::new_span(vec![Operation::Push(Felt::ONE)]);
+    let second = CodeBlock::new_span(vec![Operation::Add; 72]);
+    let third = CodeBlock::new_span(vec![Operation::Mul; 3]);
+    let hashes = [first.hash(), second.hash(), third.hash()];
+    let program = Program::new(CodeBlock::new_join([
+        CodeBlock::new_split(first, second),
+        CodeBlock::new_loop(third),
+    ]));
+    let report = analyze_batching(&program);
+
+    let span_hashes = report
+        .spans
+        .iter()
+        .map(|span| span.hash)
+        .collect::<Vec<_>>();
+    assert_eq!(hashes.to_vec(), span_hashes);
+
+    // the PUSH of the first span ends its group, and its value leaves the batch with 2 groups
+    assert_eq!(1, report.spans[0].num_padding_noops);
+    assert_eq!(0, report.spans[1].num_padding_noops);
+    assert_eq!(0, report.spans[2].num_padding_noops);
+    assert_eq!(1, report.suggestions().count());
+}
@@ -0,0 +1,215 @@
+use super::{
+    CodeBlock, Continuation, Digest, ExecutionError, ExecutionTrace, Process, Program,
+    ProgramInputs, Vec,
+};
+use core::fmt;
+use vm_core::utils::string::String;
+
+#[cfg(test)]
+mod tests;
+
+// CALL STACK
+// ================================================================================================
+
+/// Chain of code blocks which were being executed when an execution failed.
+///
+/// The frames are listed from the root of the program down to the block which failed, so that
+/// an error reported only by the clock cycle at which it occurred can be traced to its location
+/// in the program. If the program was compiled in debug mode and executed by a process in debug
+/// mode, the assembly instruction which was being executed is reported as well; the assembler
+/// does not record source positions, and thus, the instruction is the most precise location
+/// available.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallStack {
+    /// Blocks enclosing the failed one, starting with the root of the program and ending with the
+    /// block which failed.
+    pub frames: Vec<CallFrame>,
+    /// Clock cycle at which the execution failed.
+    pub clk: usize,
+    /// Assembly instruction which was being executed when the execution failed, if known.
+    pub asm_op: Option<String>,
+}
+
+impl CallStack {
+    /// Reconstructs the call stack of the specified process, which failed to execute the
+    /// specified continuation; the other continuations are the ones which were left to be
+    /// executed after it.
+    pub(super) fn new(
+        process: &Process,
+        failed: &Continuation,
+        continuations: &[Continuation],
+    ) -> Self {
+        // a continuation which ends a block is pending exactly while the children of the block
+        // are executed, so these continuations are the frames enclosing the failed one; pending
+        // starts of blocks are siblings of the enclosing frames, and are skipped
+        let mut frames: Vec<CallFrame> = continuations
+            .iter()
+            .filter_map(CallFrame::enclosing)
+            .collect();
+        frames.push(CallFrame::failed(failed));
+
+        let clk = process.system.clk();
+        let asm_op = if process.decoder.in_debug_mode() {
+            process
+                .decoder
+                .debug_info()
+                .assembly_ops()
+                .iter()
+                .rev()
+                .find(|(op_clk, _)| *op_clk <= clk)
+                .map(|(_, asm_op)| asm_op.op().clone())
+        } else {
+            None
+        };
+
+        Self {
+            frames,
+            clk,
+            asm_op,
+        }
+    }
+
+    /// Returns the block which failed.
+    pub fn failed_block(&self) -> &CallFrame {
+        self.frames.last().expect("call stack is empty")
+    }
+}
+
+impl fmt::Display for CallStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "execution failed at clk={}", self.clk)?;
+        if let Some(asm_op) = &self.asm_op {
+            write!(f, " in `{}`", asm_op)?;
+        }
+        for (depth, frame) in self.frames.iter().enumerate() {
+            write!(f, "\n  {:>3}: {}", depth, frame)?;
+        }
+        Ok(())
+    }
+}
+
+// CALL FRAME
+// ================================================================================================
+
+/// Type of a code block in a [CallStack].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockKind {
+    Join,
+    Split,
+    Loop,
+    Span,
+    Call,
+    Proxy,
+}
+
+impl fmt::Display for BlockKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Join => write!(f, "JOIN"),
+            Self::Split => write!(f, "SPLIT"),
+            Self::Loop => write!(f, "LOOP"),
+            Self::Span => write!(f, "SPAN"),
+            Self::Call => write!(f, "CALL"),
+            Self::Proxy => write!(f, "PROXY"),
+        }
+    }
+}
+
+/// A code block in a [CallStack].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallFrame {
+    /// Type of the block.
+    pub kind: BlockKind,
+    /// Hash of the block.
+    pub hash: Digest,
+}
+
+impl CallFrame {
+    /// Returns the frame of the block ended by the specified continuation, or None if the
+    /// continuation starts a block.
+    fn enclosing(continuation: &Continuation) -> Option<Self> {
+        match continuation {
+            Continuation::Start(_) => None,
+            _ => Some(Self::failed(continuation)),
+        }
+    }
+
+    /// Returns the frame of the block which the specified continuation starts or ends.
+    fn failed(continuation: &Continuation) -> Self {
+        let (kind, hash) = match continuation {
+            Continuation::Start(block) => match block {
+                CodeBlock::Join(block) => (BlockKind::Join, block.hash()),
+                CodeBlock::Split(block) => (BlockKind::Split, block.hash()),
+                CodeBlock::Loop(block) => (BlockKind::Loop, block.hash()),
+                CodeBlock::Span(block) => (BlockKind::Span, block.hash()),
+                CodeBlock::Call(block) => (BlockKind::Call, block.hash()),
+                CodeBlock::Proxy(block) => (BlockKind::Proxy, block.hash()),
+            },
+            Continuation::EndJoin(block) => (BlockKind::Join, block.hash()),
+            Continuation::EndSplit(block) => (BlockKind::Split, block.hash()),
+            Continuation::RepeatLoop(block) => (BlockKind::Loop, block.hash()),
+        };
+        Self { kind, hash }
+    }
+}
+
+impl fmt::Display for CallFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} 0x", self.kind)?;
+        for byte in self.hash.as_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+// EXECUTION FAILURE
+// ================================================================================================
+
+/// An [ExecutionError] together with the call stack at which it occurred.
+#[derive(Debug)]
+pub struct ExecutionFailure {
+    pub error: ExecutionError,
+    pub call_stack: CallStack,
+}
+
+impl fmt::Display for ExecutionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}\n{}", self.error, self.call_stack)
+    }
+}
+
+// EXECUTOR
+// ================================================================================================
+
+/// Returns an execution trace resulting from executing the provided program against the provided
+/// inputs, or the error and the call stack at which the execution failed.
+///
+/// The program is executed by a process in debug mode, so that the failed assembly instruction
+/// is reported if the program was compiled in debug mode. This makes the execution slower than
+/// [super::execute], and thus, this is meant to be used for locating failures rather than in
+/// production.
+///
+/// # Errors
+/// Returns an [ExecutionFailure] if the execution fails for the same reasons as [super::execute].
+pub fn execute_with_call_stack(
+    program: &Program,
+    inputs: &ProgramInputs,
+) -> Result<ExecutionTrace, ExecutionFailure> {
+    let mut process = Process::new_debug(inputs.clone());
+    if let Err(error) = process.execute(program) {
+        let call_stack = process
+            .call_stack
+            .take()
+            .expect("failed execution recorded no call stack");
+        return Err(ExecutionFailure { error, call_stack });
+    }
+
+    let trace = ExecutionTrace::new(process);
+    assert_eq!(
+        program.hash(),
+        trace.program_hash(),
+        "inconsistent program hash"
+    );
+    Ok(trace)
+}
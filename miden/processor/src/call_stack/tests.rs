@@ -0,0 +1,92 @@
+use super::{
+    super::{execute, ExecutionError, Process, Program, ProgramInputs},
+    execute_with_call_stack, BlockKind,
+};
+use miden_assembly::Assembler;
+use vm_core::{code_blocks::CodeBlock, Operation};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn call_stack_of_failed_span() {
+    let failing = CodeBlock::new_span(vec![Operation::Pad, Operation::Assert(0)]);
+    let split = CodeBlock::new_split(failing.clone(), CodeBlock::new_span(vec![Operation::Noop]));
+    let first = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let program = Program::new(CodeBlock::new_join([first, split.clone()]));
+
+    // the first child of the JOIN block is executed, and the assertion in the true branch of the
+    // SPLIT block fails
+    let inputs = ProgramInputs::new(&[1], &[], vec![]).unwrap();
+    let failure = execute_with_call_stack(&program, &inputs).unwrap_err();
+    let clk = match failure.error {
+        ExecutionError::FailedAssertion(clk, _) => clk,
+        error => panic!("unexpected error {:?}", error),
+    };
+
+    let call_stack = failure.call_stack;
+    assert_eq!(clk, call_stack.clk);
+    let frames: Vec<_> = call_stack
+        .frames
+        .iter()
+        .map(|frame| (frame.kind, frame.hash))
+        .collect();
+    assert_eq!(
+        vec![
+            (BlockKind::Join, program.hash()),
+            (BlockKind::Split, split.hash()),
+            (BlockKind::Span, failing.hash()),
+        ],
+        frames
+    );
+    assert_eq!(BlockKind::Span, call_stack.failed_block().kind);
+
+    // the program was not compiled in debug mode
+    assert_eq!(None, call_stack.asm_op);
+    assert!(call_stack
+        .to_string()
+        .starts_with(&format!("execution failed at clk={}\n", clk)));
+}
+
+#[test]
+fn call_stack_of_failed_control_block() {
+    let split = CodeBlock::new_split(
+        CodeBlock::new_span(vec![Operation::Noop]),
+        CodeBlock::new_span(vec![Operation::Noop]),
+    );
+    let first = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let program = Program::new(CodeBlock::new_join([first, split.clone()]));
+
+    // the condition of the SPLIT block is not binary, so the SPLIT block itself fails
+    let inputs = ProgramInputs::new(&[2], &[], vec![]).unwrap();
+    let mut process = Process::new(inputs.clone());
+    assert!(process.execute(&program).is_err());
+
+    let call_stack = process.call_stack().unwrap();
+    assert_eq!(2, call_stack.frames.len());
+    assert_eq!(BlockKind::Split, call_stack.failed_block().kind);
+    assert_eq!(split.hash(), call_stack.failed_block().hash);
+
+    // successful executions record no call stack
+    let inputs = ProgramInputs::new(&[1], &[], vec![]).unwrap();
+    let mut process = Process::new(inputs);
+    process.execute(&program).unwrap();
+    assert!(process.call_stack().is_none());
+}
+
+#[test]
+fn call_stack_with_asm_op() {
+    let source = "begin push.1 push.0 assert end";
+    let inputs = ProgramInputs::none();
+
+    // the failed instruction is reported only if the program was compiled in debug mode
+    let program = Assembler::new(true).compile(source).unwrap();
+    let failure = execute_with_call_stack(&program, &inputs).unwrap_err();
+    assert_eq!(Some("assert"), failure.call_stack.asm_op.as_deref());
+    assert!(failure.call_stack.to_string().contains(" in `assert`"));
+
+    let program = Assembler::new(false).compile(source).unwrap();
+    let failure = execute_with_call_stack(&program, &inputs).unwrap_err();
+    assert_eq!(None, failure.call_stack.asm_op);
+    assert!(execute(&program, &inputs).is_err());
+}
@@ -1,12 +1,17 @@
 use super::{
     ChipletsBus, ExecutionError, Felt, FieldElement, LookupTableRow, StarkField, TraceFragment,
-    Vec, BITWISE_AND_LABEL, BITWISE_OR_LABEL, BITWISE_XOR_LABEL,
+    Vec, BITWISE_AND_LABEL, BITWISE_OR_LABEL, BITWISE_XOR_LABEL, ONE, ZERO,
+};
+use crate::{
+    trace::reduce_bitwise_row,
+    utils::{get_trace_len, read_elements},
 };
-use crate::utils::get_trace_len;
 use vm_core::chiplets::bitwise::{
-    A_COL_IDX, BITWISE_AND, BITWISE_OR, BITWISE_XOR, B_COL_IDX, NUM_SELECTORS, OP_CYCLE_LEN,
+    A_COL_IDX, BITWISE_AND, BITWISE_OR, BITWISE_U64_AND_LABEL, BITWISE_U64_OR_LABEL,
+    BITWISE_U64_XOR_LABEL, BITWISE_XOR, B_COL_IDX, HIGH_LIMB_COL_IDX, NUM_SELECTORS, OP_CYCLE_LEN,
     OUTPUT_COL_IDX, PREV_OUTPUT_COL_IDX, TRACE_WIDTH,
 };
+use winterfell::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 #[cfg(test)]
 mod tests;
@@ -25,18 +30,18 @@ type Selectors = [Felt; NUM_SELECTORS];
 // BITWISE
 // ================================================================================================
 
-/// Helper for the VM that computes AND, OR, and XOR bitwise operations on 32-bit values.
-/// It also builds an execution trace of these operations.
+/// Helper for the VM that computes AND, OR, and XOR bitwise operations on 32-bit and 64-bit
+/// values. It also builds an execution trace of these operations.
 ///
 /// ## Bitwise operation execution trace (AND, OR, XOR)
-/// The execution trace for each operation consists of 8 rows and 14 columns. At a high level,
+/// The execution trace for each operation on 32-bit values consists of 8 rows and 15 columns. At a high level,
 /// we break input values into 4-bit limbs, apply the bitwise operation to these limbs at every
 /// row starting with the most significant limb, and accumulate the result in the result column.
 ///
 /// The layout of the table is illustrated below.
 ///
-///    s0    s1    a     b      a0     a1     a2     a3     b0     b1     b2     b3    zp     z     
-/// ├─────┴─────┴─────┴─────┴───────┴──────┴──────┴──────┴──────┴──────┴──────┴──────┴─────┴─────┤
+///    s0    s1    a     b      a0     a1     a2     a3     b0     b1     b2     b3    zp     z     h
+/// ├─────┴─────┴─────┴─────┴───────┴──────┴──────┴──────┴──────┴──────┴──────┴──────┴─────┴─────┴─────┤
 ///
 /// In the above, the meaning of the columns is as follows:
 /// - Selector columns s0 and s1 are used to specify the bitwise operator for each row.
@@ -54,7 +59,17 @@ type Selectors = [Felt; NUM_SELECTORS];
 ///   significant 4-bit limbs of the input values. With every subsequent row, the next most
 ///   significant 4-bit limb of the result is appended to it. Thus, by the 8th row, column `z`
 ///   contains the full result of the bitwise operation.
+/// - Column `h` contains the high limb flag, which is set to ONE throughout the cycle computing
+///   the high 32-bit limbs of an operation on 64-bit values, and to ZERO otherwise.
+///
+/// ## Operations on 64-bit values
+/// An operation on 64-bit values is computed in two coupled 8-row cycles for the same operator:
+/// first for the high 32-bit limbs of the inputs (with `h` set to ONE), and then for the low
+/// 32-bit limbs (with `h` set to ZERO). Instead of providing a lookup per cycle, the pair of cycles
+/// provides a single lookup for the 64-bit result from the last row of the second cycle. Thus,
+/// programs can request 64-bit bitwise operations without splitting them into 32-bit operations.
 ///
+#[derive(Clone)]
 pub struct Bitwise {
     trace: [Vec<Felt>; TRACE_WIDTH],
 }
@@ -84,6 +99,14 @@ impl Bitwise {
     // TRACE MUTATORS
     // --------------------------------------------------------------------------------------------
 
+    /// Reserves capacity for at least `num_rows` more rows in every column of the execution
+    /// trace.
+    pub fn reserve(&mut self, num_rows: usize) {
+        self.trace
+            .iter_mut()
+            .for_each(|column| column.reserve(num_rows));
+    }
+
     /// Computes a bitwise AND of `a` and `b` and returns the result. We assume that `a` and `b`
     /// are 32-bit values. If that's not the case, the result of the computation is undefined.
     ///
@@ -92,29 +115,7 @@ impl Bitwise {
     pub fn u32and(&mut self, a: Felt, b: Felt) -> Result<Felt, ExecutionError> {
         let a = assert_u32(a)?.as_int();
         let b = assert_u32(b)?.as_int();
-        let mut result = 0u64;
-
-        // append 8 rows to the trace, each row computing bitwise AND in 4 bit limbs starting with
-        // the most significant limb.
-        for bit_offset in (0..32).step_by(4).rev() {
-            // append the previous row's result to the column for previous output values
-            self.trace[PREV_OUTPUT_COL_IDX].push(Felt::new(result));
-            // shift a and b so that the next 4-bit limb is in the least significant position
-            let a = a >> bit_offset;
-            let b = b >> bit_offset;
-
-            // add a new row to the trace table and populate it with binary decomposition of the 4
-            // least significant bits of a and b.
-            self.add_bitwise_trace_row(BITWISE_AND, a, b);
-
-            // compute bitwise AND of the 4 least significant bits of a and b
-            let result_4_bit = (a & b) & 0xF;
-
-            // append the 4 bit result to the result accumulator, and save the current result into
-            // the output column in the trace.
-            result = (result << 4) | result_4_bit;
-            self.trace[OUTPUT_COL_IDX].push(Felt::new(result));
-        }
+        let result = self.append_op_cycle(BITWISE_AND, a, b, ZERO, |a, b| a & b);
 
         Ok(Felt::new(result))
     }
@@ -127,29 +128,7 @@ impl Bitwise {
     pub fn u32or(&mut self, a: Felt, b: Felt) -> Result<Felt, ExecutionError> {
         let a = assert_u32(a)?.as_int();
         let b = assert_u32(b)?.as_int();
-        let mut result = 0u64;
-
-        // append 8 rows to the trace, each row computing bitwise OR in 4 bit limbs starting with
-        // the most significant limb.
-        for bit_offset in (0..32).step_by(4).rev() {
-            // append the previous row's result to the column for previous output values
-            self.trace[PREV_OUTPUT_COL_IDX].push(Felt::new(result));
-            // shift a and b so that the next 4-bit limb is in the least significant position
-            let a = a >> bit_offset;
-            let b = b >> bit_offset;
-
-            // add a new row to the trace table and populate it with binary decomposition of the 4
-            // least significant bits of a and b.
-            self.add_bitwise_trace_row(BITWISE_OR, a, b);
-
-            // compute bitwise OR of the 4 least significant bits of a and b
-            let result_4_bit = (a | b) & 0xF;
-
-            // append the 4 bit result to the result accumulator, and save the current result into
-            // the output column in the trace.
-            result = (result << 4) | result_4_bit;
-            self.trace[OUTPUT_COL_IDX].push(Felt::new(result));
-        }
+        let result = self.append_op_cycle(BITWISE_OR, a, b, ZERO, |a, b| a | b);
 
         Ok(Felt::new(result))
     }
@@ -162,31 +141,45 @@ impl Bitwise {
     pub fn u32xor(&mut self, a: Felt, b: Felt) -> Result<Felt, ExecutionError> {
         let a = assert_u32(a)?.as_int();
         let b = assert_u32(b)?.as_int();
-        let mut result = 0u64;
-
-        // append 8 rows to the trace, each row computing bitwise XOR in 4 bit limbs starting with
-        // the most significant limb.
-        for bit_offset in (0..32).step_by(4).rev() {
-            // append the previous row's result to the column for previous output values
-            self.trace[PREV_OUTPUT_COL_IDX].push(Felt::new(result));
-            // shift a and b so that the next 4-bit limb is in the least significant position
-            let a = a >> bit_offset;
-            let b = b >> bit_offset;
+        let result = self.append_op_cycle(BITWISE_XOR, a, b, ZERO, |a, b| a ^ b);
 
-            // add a new row to the trace table and populate it with binary decomposition of the 4
-            // least significant bits of a and b.
-            self.add_bitwise_trace_row(BITWISE_XOR, a, b);
+        Ok(Felt::new(result))
+    }
 
-            // compute bitwise XOR of the 4 least significant bits of a and b
-            let result_4_bit = (a ^ b) & 0xF;
+    /// Computes a bitwise AND of 64-bit values `a` and `b` specified by their 32-bit limbs, and
+    /// returns the limbs of the result as (hi, lo).
+    ///
+    /// This also adds 16 rows to the internal execution trace table required for computing the
+    /// operation: 8 rows for the high limbs followed by 8 rows for the low limbs.
+    ///
+    /// # Errors
+    /// Returns an error if any of the limbs is not a 32-bit value.
+    pub fn u64and(&mut self, a: [Felt; 2], b: [Felt; 2]) -> Result<[Felt; 2], ExecutionError> {
+        self.append_u64_op(BITWISE_AND, a, b, |a, b| a & b)
+    }
 
-            // append the 4 bit result to the result accumulator, and save the current result into
-            // the output column in the trace.
-            result = (result << 4) | result_4_bit;
-            self.trace[OUTPUT_COL_IDX].push(Felt::new(result));
-        }
+    /// Computes a bitwise OR of 64-bit values `a` and `b` specified by their 32-bit limbs, and
+    /// returns the limbs of the result as (hi, lo).
+    ///
+    /// This also adds 16 rows to the internal execution trace table required for computing the
+    /// operation: 8 rows for the high limbs followed by 8 rows for the low limbs.
+    ///
+    /// # Errors
+    /// Returns an error if any of the limbs is not a 32-bit value.
+    pub fn u64or(&mut self, a: [Felt; 2], b: [Felt; 2]) -> Result<[Felt; 2], ExecutionError> {
+        self.append_u64_op(BITWISE_OR, a, b, |a, b| a | b)
+    }
 
-        Ok(Felt::new(result))
+    /// Computes a bitwise XOR of 64-bit values `a` and `b` specified by their 32-bit limbs, and
+    /// returns the limbs of the result as (hi, lo).
+    ///
+    /// This also adds 16 rows to the internal execution trace table required for computing the
+    /// operation: 8 rows for the high limbs followed by 8 rows for the low limbs.
+    ///
+    /// # Errors
+    /// Returns an error if any of the limbs is not a 32-bit value.
+    pub fn u64xor(&mut self, a: [Felt; 2], b: [Felt; 2]) -> Result<[Felt; 2], ExecutionError> {
+        self.append_u64_op(BITWISE_XOR, a, b, |a, b| a ^ b)
     }
 
     // EXECUTION TRACE GENERATION
@@ -207,27 +200,35 @@ impl Bitwise {
         debug_assert_eq!(self.trace_len(), trace.len(), "inconsistent trace lengths");
         debug_assert_eq!(TRACE_WIDTH, trace.width(), "inconsistent trace widths");
 
-        // provide the lookup data from the last row in each bitwise cycle
+        // provide the lookup data from the last row in each bitwise cycle. the cycle computing the
+        // high limbs of a 64-bit operation doesn't provide a lookup on its own; instead, the cycle
+        // for the low limbs which follows it provides a single lookup for the full operation.
         for row in ((OP_CYCLE_LEN - 1)..self.trace_len()).step_by(OP_CYCLE_LEN) {
+            if self.trace[HIGH_LIMB_COL_IDX][row] == ONE {
+                continue;
+            }
+
             let a = self.trace[A_COL_IDX][row];
             let b = self.trace[B_COL_IDX][row];
             let z = self.trace[OUTPUT_COL_IDX][row];
 
             // get the operation label.
             let op_selectors: Selectors = [self.trace[0][row], self.trace[1][row]];
-            let label = if op_selectors == BITWISE_AND {
-                BITWISE_AND_LABEL
-            } else if op_selectors == BITWISE_OR {
-                BITWISE_OR_LABEL
+            let is_u64 =
+                row >= OP_CYCLE_LEN && self.trace[HIGH_LIMB_COL_IDX][row - OP_CYCLE_LEN] == ONE;
+            let label = get_op_label(op_selectors, is_u64);
+
+            let lookup = if is_u64 {
+                let hi_row = row - OP_CYCLE_LEN;
+                BitwiseLookup::new_u64(
+                    label,
+                    [self.trace[A_COL_IDX][hi_row], a],
+                    [self.trace[B_COL_IDX][hi_row], b],
+                    [self.trace[OUTPUT_COL_IDX][hi_row], z],
+                )
             } else {
-                assert!(
-                    op_selectors == BITWISE_XOR,
-                    "Unrecognized operation selectors in Bitwise chiplet"
-                );
-                BITWISE_XOR_LABEL
+                BitwiseLookup::new(label, a, b, z)
             };
-
-            let lookup = BitwiseLookup::new(label, a, b, z);
             chiplets_bus.provide_bitwise_operation(lookup, bitwise_start_row + row);
         }
 
@@ -241,7 +242,64 @@ impl Bitwise {
     // HELPER METHODS
     // --------------------------------------------------------------------------------------------
 
-    /// Appends a new row to the trace table and populates the first 14 columns of trace as follows:
+    /// Computes the bitwise operation specified by `selectors` and `op` on 64-bit values `a` and `b`
+    /// specified by their 32-bit limbs (hi, lo), and returns the limbs of the result.
+    ///
+    /// The operation is computed in two coupled cycles: the cycle for the high limbs is marked
+    /// with the high limb flag, and is immediately followed by the cycle for the low limbs.
+    fn append_u64_op(
+        &mut self,
+        selectors: Selectors,
+        a: [Felt; 2],
+        b: [Felt; 2],
+        op: fn(u64, u64) -> u64,
+    ) -> Result<[Felt; 2], ExecutionError> {
+        let [a_hi, a_lo] = [assert_u32(a[0])?.as_int(), assert_u32(a[1])?.as_int()];
+        let [b_hi, b_lo] = [assert_u32(b[0])?.as_int(), assert_u32(b[1])?.as_int()];
+
+        let result_hi = self.append_op_cycle(selectors, a_hi, b_hi, ONE, op);
+        let result_lo = self.append_op_cycle(selectors, a_lo, b_lo, ZERO, op);
+
+        Ok([Felt::new(result_hi), Felt::new(result_lo)])
+    }
+
+    /// Appends 8 rows to the trace, each row computing the bitwise operation `op` on 4-bit limbs
+    /// of 32-bit values `a` and `b` starting with the most significant limb, and returns the
+    /// result.
+    fn append_op_cycle(
+        &mut self,
+        selectors: Selectors,
+        a: u64,
+        b: u64,
+        high_limb: Felt,
+        op: fn(u64, u64) -> u64,
+    ) -> u64 {
+        let mut result = 0u64;
+
+        for bit_offset in (0..32).step_by(4).rev() {
+            // append the previous row's result to the column for previous output values
+            self.trace[PREV_OUTPUT_COL_IDX].push(Felt::new(result));
+            // shift a and b so that the next 4-bit limb is in the least significant position
+            let a = a >> bit_offset;
+            let b = b >> bit_offset;
+
+            // add a new row to the trace table and populate it with binary decomposition of the 4
+            // least significant bits of a and b.
+            self.add_bitwise_trace_row(selectors, a, b, high_limb);
+
+            // compute the bitwise operation on the 4 least significant bits of a and b
+            let result_4_bit = op(a, b) & 0xF;
+
+            // append the 4 bit result to the result accumulator, and save the current result into
+            // the output column in the trace.
+            result = (result << 4) | result_4_bit;
+            self.trace[OUTPUT_COL_IDX].push(Felt::new(result));
+        }
+
+        result
+    }
+
+    /// Appends a new row to the trace table and populates the columns of trace as follows:
     /// - Columns 0 and 1 are set to the selector values for the bitwise operation being executed.
     /// - Column 2 is set to the current value of `a`.
     /// - Column 3 is set to the current value of `b`.
@@ -249,7 +307,8 @@ impl Bitwise {
     /// - Columns 8 to 11 are set to the 4 least-significant bits of `b`.
     /// - Column 12 and 13 are left for the output value and that of the previous row, which are
     ///   set elsewhere.
-    fn add_bitwise_trace_row(&mut self, selectors: Selectors, a: u64, b: u64) {
+    /// - Column 14 is set to the high limb flag.
+    fn add_bitwise_trace_row(&mut self, selectors: Selectors, a: u64, b: u64, high_limb: Felt) {
         self.trace[0].push(selectors[0]);
         self.trace[1].push(selectors[1]);
 
@@ -265,6 +324,8 @@ impl Bitwise {
         self.trace[9].push(Felt::new((b >> 1) & 1));
         self.trace[10].push(Felt::new((b >> 2) & 1));
         self.trace[11].push(Felt::new((b >> 3) & 1));
+
+        self.trace[HIGH_LIMB_COL_IDX].push(high_limb);
     }
 }
 
@@ -277,6 +338,37 @@ impl Default for Bitwise {
 // HELPER FUNCTIONS
 // --------------------------------------------------------------------------------------------
 
+/// Returns the label of the bitwise operation specified by the provided selectors. If `is_u64` is
+/// set to true, the label of the operation on 64-bit values is returned.
+///
+/// # Panics
+/// Panics if the selectors don't specify a bitwise operation.
+fn get_op_label(op_selectors: Selectors, is_u64: bool) -> Felt {
+    if op_selectors == BITWISE_AND {
+        if is_u64 {
+            BITWISE_U64_AND_LABEL
+        } else {
+            BITWISE_AND_LABEL
+        }
+    } else if op_selectors == BITWISE_OR {
+        if is_u64 {
+            BITWISE_U64_OR_LABEL
+        } else {
+            BITWISE_OR_LABEL
+        }
+    } else {
+        assert!(
+            op_selectors == BITWISE_XOR,
+            "Unrecognized operation selectors in Bitwise chiplet"
+        );
+        if is_u64 {
+            BITWISE_U64_XOR_LABEL
+        } else {
+            BITWISE_XOR_LABEL
+        }
+    }
+}
+
 pub fn assert_u32(value: Felt) -> Result<Felt, ExecutionError> {
     let val_u64 = value.as_int();
     if val_u64 > u32::MAX.into() {
@@ -288,28 +380,88 @@ pub fn assert_u32(value: Felt) -> Result<Felt, ExecutionError> {
 
 // BITWISE LOOKUPS
 // ================================================================================================
+
+/// A lookup of a bitwise operation.
+///
+/// Lookups of operations on 32-bit values set the high limbs of the inputs and the output to
+/// ZERO, and thus, reduce to the same values as before operations on 64-bit values were supported.
+/// Lookups of operations on 64-bit values carry both limbs of the inputs and the output, so that a
+/// single lookup covers the full operation.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct BitwiseLookup {
     op_id: Felt,
     a: Felt,
     b: Felt,
     z: Felt,
+    a_hi: Felt,
+    b_hi: Felt,
+    z_hi: Felt,
 }
 
 impl BitwiseLookup {
+    /// Returns a lookup of a bitwise operation on 32-bit values.
     pub fn new(op_id: Felt, a: Felt, b: Felt, z: Felt) -> Self {
-        Self { op_id, a, b, z }
+        Self {
+            op_id,
+            a,
+            b,
+            z,
+            a_hi: ZERO,
+            b_hi: ZERO,
+            z_hi: ZERO,
+        }
+    }
+
+    /// Returns a lookup of a bitwise operation on 64-bit values specified by their 32-bit limbs
+    /// (hi, lo).
+    pub fn new_u64(op_id: Felt, a: [Felt; 2], b: [Felt; 2], z: [Felt; 2]) -> Self {
+        Self {
+            op_id,
+            a: a[1],
+            b: b[1],
+            z: z[1],
+            a_hi: a[0],
+            b_hi: b[0],
+            z_hi: z[0],
+        }
     }
 }
 
 impl LookupTableRow for BitwiseLookup {
     /// Reduces this row to a single field element in the field specified by E. This requires
-    /// at least 5 alpha values.
+    /// at least 8 alpha values.
     fn to_value<E: FieldElement<BaseField = Felt>>(&self, alphas: &[E]) -> E {
-        alphas[0]
-            + alphas[1].mul_base(self.op_id)
-            + alphas[2].mul_base(self.a)
-            + alphas[3].mul_base(self.b)
-            + alphas[4].mul_base(self.z)
+        reduce_bitwise_row(
+            alphas,
+            self.op_id,
+            [self.a_hi, self.a],
+            [self.b_hi, self.b],
+            [self.z_hi, self.z],
+        )
+    }
+}
+
+impl Serializable for BitwiseLookup {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write(
+            &[
+                self.op_id, self.a, self.b, self.z, self.a_hi, self.b_hi, self.z_hi,
+            ][..],
+        );
+    }
+}
+
+impl Deserializable for BitwiseLookup {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let [op_id, a, b, z, a_hi, b_hi, z_hi] = read_elements(source)?;
+        Ok(Self {
+            op_id,
+            a,
+            b,
+            z,
+            a_hi,
+            b_hi,
+            z_hi,
+        })
     }
 }
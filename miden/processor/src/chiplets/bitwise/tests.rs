@@ -1,11 +1,12 @@
 use super::{
     super::bus::{ChipletsLookup, ChipletsLookupRow},
     Bitwise, BitwiseLookup, ChipletsBus, Felt, StarkField, TraceFragment, A_COL_IDX, BITWISE_AND,
-    BITWISE_AND_LABEL, BITWISE_OR, BITWISE_OR_LABEL, BITWISE_XOR, BITWISE_XOR_LABEL, B_COL_IDX,
-    OP_CYCLE_LEN, OUTPUT_COL_IDX, PREV_OUTPUT_COL_IDX, TRACE_WIDTH,
+    BITWISE_AND_LABEL, BITWISE_OR, BITWISE_OR_LABEL, BITWISE_U64_AND_LABEL, BITWISE_U64_XOR_LABEL,
+    BITWISE_XOR, BITWISE_XOR_LABEL, B_COL_IDX, HIGH_LIMB_COL_IDX, OP_CYCLE_LEN, OUTPUT_COL_IDX,
+    PREV_OUTPUT_COL_IDX, TRACE_WIDTH,
 };
 use rand_utils::rand_value;
-use vm_core::ZERO;
+use vm_core::{ONE, ZERO};
 
 #[test]
 fn bitwise_init() {
@@ -295,6 +296,75 @@ fn bitwise_multiple() {
     verify_bus(&chiplets_bus, 3, OP_CYCLE_LEN * 4 - 1, &bitwise_lookup);
 }
 
+#[test]
+fn bitwise_u64_xor() {
+    let mut bitwise = Bitwise::new();
+
+    let a = [rand_u32(), rand_u32()];
+    let b = [rand_u32(), rand_u32()];
+
+    let result = bitwise.u64xor(a, b).unwrap();
+    assert_eq!(a[0].as_int() ^ b[0].as_int(), result[0].as_int());
+    assert_eq!(a[1].as_int() ^ b[1].as_int(), result[1].as_int());
+
+    // --- check generated trace ----------------------------------------------
+    let (trace, chiplets_bus) = build_trace(bitwise, 2 * OP_CYCLE_LEN);
+
+    // make sure both cycles specify bitwise XOR, and only the first cycle is for the high limbs
+    for row in 0..2 * OP_CYCLE_LEN {
+        assert_eq!([trace[0][row], trace[1][row]], BITWISE_XOR);
+        let high_limb = if row < OP_CYCLE_LEN { ONE } else { ZERO };
+        assert_eq!(high_limb, trace[HIGH_LIMB_COL_IDX][row]);
+    }
+
+    // make sure the high limbs are computed in the first cycle and the low limbs in the second
+    check_decomposition(&trace, 0, a[0].as_int(), b[0].as_int());
+    check_decomposition(&trace, OP_CYCLE_LEN, a[1].as_int(), b[1].as_int());
+    assert_eq!(result[0], trace[OUTPUT_COL_IDX][OP_CYCLE_LEN - 1]);
+    assert_eq!(result[1], trace[OUTPUT_COL_IDX][2 * OP_CYCLE_LEN - 1]);
+
+    // make sure a single lookup for the full operation was sent from the last row of the pair
+    let bitwise_lookup = BitwiseLookup::new_u64(BITWISE_U64_XOR_LABEL, a, b, result);
+    verify_bus(&chiplets_bus, 0, 2 * OP_CYCLE_LEN - 1, &bitwise_lookup);
+    assert!(chiplets_bus.get_lookup_hint(OP_CYCLE_LEN - 1).is_none());
+}
+
+#[test]
+fn bitwise_u64_and_u32_mixed() {
+    let mut bitwise = Bitwise::new();
+
+    let a = [rand_u32(), rand_u32(), rand_u32()];
+    let b = [rand_u32(), rand_u32(), rand_u32()];
+
+    // first operation: 32-bit AND
+    let result0 = bitwise.u32and(a[0], b[0]).unwrap();
+    // second operation: 64-bit AND
+    let result1 = bitwise.u64and([a[1], a[2]], [b[1], b[2]]).unwrap();
+    // third operation: 32-bit AND on the same inputs as the low limbs of the 64-bit operation
+    let result2 = bitwise.u32and(a[2], b[2]).unwrap();
+    assert_eq!(result1[1], result2);
+
+    // --- check generated trace ----------------------------------------------
+    let (_, chiplets_bus) = build_trace(bitwise, 4 * OP_CYCLE_LEN);
+
+    // make sure the lookups were sent to the bus correctly
+    let bitwise_lookup = BitwiseLookup::new(BITWISE_AND_LABEL, a[0], b[0], result0);
+    verify_bus(&chiplets_bus, 0, OP_CYCLE_LEN - 1, &bitwise_lookup);
+
+    let bitwise_lookup =
+        BitwiseLookup::new_u64(BITWISE_U64_AND_LABEL, [a[1], a[2]], [b[1], b[2]], result1);
+    verify_bus(&chiplets_bus, 1, 3 * OP_CYCLE_LEN - 1, &bitwise_lookup);
+
+    let bitwise_lookup = BitwiseLookup::new(BITWISE_AND_LABEL, a[2], b[2], result2);
+    verify_bus(&chiplets_bus, 2, 4 * OP_CYCLE_LEN - 1, &bitwise_lookup);
+
+    // the 32-bit lookup and the lookup for the low limbs of the 64-bit operation are distinct
+    assert_ne!(
+        BitwiseLookup::new_u64(BITWISE_U64_AND_LABEL, [a[1], a[2]], [b[1], b[2]], result1),
+        BitwiseLookup::new(BITWISE_AND_LABEL, a[2], b[2], result2)
+    );
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
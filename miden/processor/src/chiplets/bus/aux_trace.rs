@@ -36,13 +36,7 @@ impl AuxTraceBuilder {
 // CHIPLETS LOOKUPS
 // ================================================================================================
 
-impl AuxColumnBuilder<ChipletsLookup, ChipletsLookupRow> for AuxTraceBuilder {
-    /// This method is required, but because it is only called inside `build_row_values` which is
-    /// overridden below, it is not used here and should not be called.
-    fn get_table_rows(&self) -> &[ChipletsLookupRow] {
-        unimplemented!()
-    }
-
+impl AuxColumnBuilder<ChipletsLookup> for AuxTraceBuilder {
     /// Returns hints which describe the [Chiplets] lookup requests and responses during program
     /// execution. Each update hint is accompanied by a clock cycle at which the update happened.
     ///
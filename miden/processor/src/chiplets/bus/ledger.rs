@@ -0,0 +1,96 @@
+use super::{
+    ChipletsBus, ChipletsLookup, ChipletsLookupRow, Felt, FieldElement, LookupTableRow, Vec,
+};
+use vm_core::{utils::string::String, StarkField, AUX_TRACE_RAND_ELEMENTS};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Fixed random element with which the values of the lookups in a bus ledger are reduced; the
+/// random elements passed to the lookups are the first powers of this element, and the element
+/// itself is included in the ledger.
+///
+/// Since the element is fixed, ledgers dumped by different versions of the VM for the same
+/// program and inputs can be compared value by value, but the values carry no soundness
+/// guarantees and must never be used to build an actual auxiliary trace.
+const LEDGER_ALPHA: u64 = 0x0123_4567_89ab_cdef;
+
+// BUS LEDGER
+// ================================================================================================
+
+impl ChipletsBus {
+    /// Returns the ledger of every lookup requested from and provided by the chiplets, serialized
+    /// as a JSON object.
+    ///
+    /// The ledger lists the lookups in the order of the cycles at which they were requested or
+    /// provided; a request and a response at the same cycle are listed as two entries, with the
+    /// request first. Every entry holds the cycle of the lookup, its kind (`request` or
+    /// `response`), the chiplet operation it belongs to, and its value reduced with the random
+    /// elements derived from [LEDGER_ALPHA]:
+    ///
+    /// ```text
+    /// {"alpha":<alpha>,"entries":[{"cycle":3,"kind":"request","row":"bitwise","value":<value>},...]}
+    /// ```
+    ///
+    /// Labels and kinds consist of ASCII identifiers only, and thus, no escaping is needed.
+    pub fn to_ledger_json(&self) -> String {
+        let alphas = ledger_alphas();
+        let entry = |cycle: usize, kind: &str, row: &ChipletsLookupRow| {
+            format!(
+                "{{\"cycle\":{},\"kind\":\"{}\",\"row\":\"{}\",\"value\":{}}}",
+                cycle,
+                kind,
+                row_label(row),
+                row.to_value(&alphas).as_int()
+            )
+        };
+
+        let mut entries = Vec::with_capacity(self.request_rows.len() + self.response_rows.len());
+        for (&cycle, &hint) in self.lookup_hints.iter() {
+            match hint {
+                ChipletsLookup::Request(request_idx) => {
+                    entries.push(entry(cycle, "request", &self.request_rows[request_idx]));
+                }
+                ChipletsLookup::Response(response_idx) => {
+                    entries.push(entry(cycle, "response", &self.response_rows[response_idx]));
+                }
+                ChipletsLookup::RequestAndResponse((request_idx, response_idx)) => {
+                    entries.push(entry(cycle, "request", &self.request_rows[request_idx]));
+                    entries.push(entry(cycle, "response", &self.response_rows[response_idx]));
+                }
+            }
+        }
+
+        format!(
+            "{{\"alpha\":{},\"entries\":[{}]}}",
+            LEDGER_ALPHA,
+            entries.join(",")
+        )
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the random elements with which the values of ledger entries are reduced.
+fn ledger_alphas() -> Vec<Felt> {
+    let alpha = Felt::new(LEDGER_ALPHA);
+    let mut alphas = Vec::with_capacity(AUX_TRACE_RAND_ELEMENTS);
+    let mut power = Felt::ONE;
+    for _ in 0..AUX_TRACE_RAND_ELEMENTS {
+        power *= alpha;
+        alphas.push(power);
+    }
+    alphas
+}
+
+/// Returns the label of the chiplet operation the specified lookup row belongs to.
+fn row_label(row: &ChipletsLookupRow) -> &'static str {
+    match row {
+        ChipletsLookupRow::Hasher(_) => "hasher",
+        ChipletsLookupRow::HasherMulti(_) => "hasher_multi",
+        ChipletsLookupRow::Bitwise(_) => "bitwise",
+        ChipletsLookupRow::Memory(_) => "memory",
+        ChipletsLookupRow::MemoryHash(..) => "memory_hash",
+    }
+}
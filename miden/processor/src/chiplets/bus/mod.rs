@@ -2,10 +2,27 @@ use super::{
     hasher::HasherLookup, BTreeMap, BitwiseLookup, Felt, FieldElement, LookupTableRow,
     MemoryLookup, Vec,
 };
+use vm_core::utils::string::ToString;
+use winterfell::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
 
 mod aux_trace;
 pub use aux_trace::AuxTraceBuilder;
 
+#[cfg(feature = "bus-ledger")]
+mod ledger;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the serialized form of [ChipletsBus]; it is incremented whenever the layout of the
+/// serialized bus changes.
+const CHIPLETS_BUS_VERSION: u8 = 1;
+
 // CHIPLETS BUS
 // ================================================================================================
 
@@ -18,8 +35,11 @@ pub use aux_trace::AuxTraceBuilder;
 /// lookup results provided by the chiplets must be multiplied in. To ensure that all lookups are
 /// attributed to the correct chiplet and operation, a unique chiplet operation label must be
 /// included in the lookup row value when it is computed.
-
-#[derive(Default)]
+///
+/// The bus can be serialized once the execution trace has been built, so that the `b_chip` column
+/// can be constructed in a separate process (or on another machine) once the random elements for
+/// the auxiliary trace are available; see [ChipletsBus::from_bytes()].
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub struct ChipletsBus {
     lookup_hints: BTreeMap<usize, ChipletsLookup>,
     request_rows: Vec<ChipletsLookupRow>,
@@ -39,28 +59,35 @@ impl ChipletsBus {
     /// can contain one or more lookups, while Bitwise and Memory requests will only contain a
     /// single lookup.
     fn request_lookup(&mut self, cycle: usize) {
-        // all requests are sent from the stack before responses are provided (during Chiplets trace
-        // finalization). requests are guaranteed not to share cycles with other requests, since
-        // only one operation will be executed at a time.
+        // requests are guaranteed not to share cycles with other requests, since only one
+        // operation will be executed at a time, but they might share a cycle with a response.
         let request_idx = self.request_rows.len();
-        self.lookup_hints
-            .insert(cycle, ChipletsLookup::Request(request_idx));
+        self.add_lookup_hint(cycle, ChipletsLookup::Request(request_idx));
     }
 
     /// Provides lookup data at the specified cycle, which is the row of the Chiplets execution
     /// trace that contains this lookup row.
     fn provide_lookup(&mut self, response_cycle: usize) {
         // results are guaranteed not to share cycles with other results, but they might share
-        // a cycle with a request which has already been sent.
+        // a cycle with a request.
         let response_idx = self.response_rows.len();
-        self.lookup_hints
-            .entry(response_cycle)
-            .and_modify(|lookup| {
-                if let ChipletsLookup::Request(request_idx) = *lookup {
-                    *lookup = ChipletsLookup::RequestAndResponse((request_idx, response_idx));
-                }
-            })
-            .or_insert_with(|| ChipletsLookup::Response(response_idx));
+        self.add_lookup_hint(response_cycle, ChipletsLookup::Response(response_idx));
+    }
+
+    /// Adds the specified hint at the specified cycle, merging it with the hint already added at
+    /// the cycle, if any.
+    ///
+    /// Requests are usually sent from the stack before responses are provided (during Chiplets
+    /// trace finalization), but chiplets may also provide responses before the requests for the
+    /// same cycle are sent. In either case, a request and a response at the same cycle are merged
+    /// into the same [ChipletsLookup::RequestAndResponse] hint, and thus, the contribution of the
+    /// cycle to the bus column does not depend on the order in which they are added.
+    fn add_lookup_hint(&mut self, cycle: usize, hint: ChipletsLookup) {
+        let hint = match self.lookup_hints.get(&cycle) {
+            Some(&existing) => existing.merge(hint, cycle),
+            None => hint,
+        };
+        self.lookup_hints.insert(cycle, hint);
     }
 
     // HASHER LOOKUPS
@@ -159,9 +186,38 @@ impl ChipletsBus {
         self.response_rows.push(ChipletsLookupRow::Memory(lookup));
     }
 
+    // MEMORY HASHING LOOKUPS
+    // --------------------------------------------------------------------------------------------
+
+    /// Requests the specified memory reads together with the lookups of the hash computation
+    /// which absorbs the read words at the specified `cycle`. The reads and the hash computation
+    /// are requested by a single operation, and thus, they are combined into a single request.
+    pub fn request_memory_hash(
+        &mut self,
+        memory_lookups: &[MemoryLookup],
+        hasher_lookups: &[HasherLookup],
+        cycle: usize,
+    ) {
+        self.request_lookup(cycle);
+        self.request_rows.push(ChipletsLookupRow::MemoryHash(
+            memory_lookups.to_vec(),
+            hasher_lookups.to_vec(),
+        ));
+    }
+
     // AUX TRACE BUILDER GENERATION
     // --------------------------------------------------------------------------------------------
 
+    /// Returns a bus holding the lookups described by the specified auxiliary trace builder.
+    pub(crate) fn from_aux_builder(aux_builder: &AuxTraceBuilder) -> Self {
+        Self {
+            lookup_hints: aux_builder.lookup_hints.iter().copied().collect(),
+            request_rows: aux_builder.request_rows.clone(),
+            response_rows: aux_builder.response_rows.clone(),
+            queued_requests: Vec::new(),
+        }
+    }
+
     /// Converts this [ChipletsBus] into an auxiliary trace builder which can be used to construct
     /// the auxiliary trace column describing the [Chiplets] lookups at every cycle.
     pub fn into_aux_builder(self) -> AuxTraceBuilder {
@@ -174,6 +230,28 @@ impl ChipletsBus {
         }
     }
 
+    // SERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a bus deserialized from the provided bytes, which are expected to have been
+    /// produced by [ChipletsBus::to_bytes()](Serializable::to_bytes).
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The bytes were serialized with a different version of the bus layout.
+    /// - The bytes do not describe a valid bus, i.e., an element is not a valid field element, a
+    ///   lookup hint refers to a request or a response row which does not exist, or two hints are
+    ///   placed at the same cycle.
+    /// - Not all of the provided bytes were consumed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(bytes);
+        let bus = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(bus)
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -190,6 +268,66 @@ impl ChipletsBus {
     }
 }
 
+impl Serializable for ChipletsBus {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(CHIPLETS_BUS_VERSION);
+
+        // rows are written before the hints, so that the hints can be checked against them
+        write_rows(target, &self.request_rows);
+        write_rows(target, &self.response_rows);
+        target.write_u64(self.queued_requests.len() as u64);
+        target.write(&self.queued_requests);
+
+        target.write_u64(self.lookup_hints.len() as u64);
+        for (&cycle, &hint) in self.lookup_hints.iter() {
+            target.write_u64(cycle as u64);
+            target.write(hint);
+        }
+    }
+}
+
+impl Deserializable for ChipletsBus {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let version = source.read_u8()?;
+        if version != CHIPLETS_BUS_VERSION {
+            return Err(DeserializationError::InvalidValue(
+                "unsupported chiplets bus version".to_string(),
+            ));
+        }
+
+        let request_rows = read_rows(source)?;
+        let response_rows = read_rows(source)?;
+        let num_queued_requests = source.read_u64()? as usize;
+        let queued_requests = HasherLookup::read_batch_from(source, num_queued_requests)?;
+
+        let num_hints = source.read_u64()?;
+        let mut lookup_hints = BTreeMap::new();
+        for _ in 0..num_hints {
+            let cycle = source.read_u64()? as usize;
+            let hint = ChipletsLookup::read_from(source)?;
+            if !hint.is_valid(request_rows.len(), response_rows.len()) {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "lookup hint at cycle {} refers to a row which does not exist",
+                    cycle
+                )));
+            }
+            if lookup_hints.insert(cycle, hint).is_some() {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "cycle {} contains more than one lookup hint",
+                    cycle
+                )));
+            }
+        }
+
+        Ok(Self {
+            lookup_hints,
+            request_rows,
+            response_rows,
+            queued_requests,
+        })
+    }
+}
+
 // CHIPLETS LOOKUPS
 // ================================================================================================
 
@@ -200,6 +338,76 @@ pub(super) enum ChipletsLookup {
     RequestAndResponse((usize, usize)),
 }
 
+impl ChipletsLookup {
+    /// Returns the hint combining this hint with the other hint at the specified cycle; the
+    /// request index always comes first in the combined hint.
+    ///
+    /// # Panics
+    /// Panics if both hints are requests or both hints are responses, or if either of them is
+    /// already combined.
+    fn merge(self, other: Self, cycle: usize) -> Self {
+        match (self, other) {
+            (Self::Request(request_idx), Self::Response(response_idx))
+            | (Self::Response(response_idx), Self::Request(request_idx)) => {
+                Self::RequestAndResponse((request_idx, response_idx))
+            }
+            (existing, hint) => panic!(
+                "lookup hint {:?} cannot be added at cycle {} which already contains hint {:?}",
+                hint, cycle, existing
+            ),
+        }
+    }
+
+    /// Returns true if the rows this hint refers to exist among the specified numbers of request
+    /// and response rows.
+    fn is_valid(&self, num_requests: usize, num_responses: usize) -> bool {
+        match *self {
+            Self::Request(request_idx) => request_idx < num_requests,
+            Self::Response(response_idx) => response_idx < num_responses,
+            Self::RequestAndResponse((request_idx, response_idx)) => {
+                request_idx < num_requests && response_idx < num_responses
+            }
+        }
+    }
+}
+
+impl Serializable for ChipletsLookup {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match *self {
+            Self::Request(request_idx) => {
+                target.write_u8(0);
+                target.write_u64(request_idx as u64);
+            }
+            Self::Response(response_idx) => {
+                target.write_u8(1);
+                target.write_u64(response_idx as u64);
+            }
+            Self::RequestAndResponse((request_idx, response_idx)) => {
+                target.write_u8(2);
+                target.write_u64(request_idx as u64);
+                target.write_u64(response_idx as u64);
+            }
+        }
+    }
+}
+
+impl Deserializable for ChipletsLookup {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::Request(source.read_u64()? as usize)),
+            1 => Ok(Self::Response(source.read_u64()? as usize)),
+            2 => {
+                let request_idx = source.read_u64()? as usize;
+                let response_idx = source.read_u64()? as usize;
+                Ok(Self::RequestAndResponse((request_idx, response_idx)))
+            }
+            _ => Err(DeserializationError::InvalidValue(
+                "unknown chiplets lookup hint".to_string(),
+            )),
+        }
+    }
+}
+
 // TODO: investigate alternative approaches, since this is heavy (e.g. read from execution trace)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(super) enum ChipletsLookupRow {
@@ -207,6 +415,7 @@ pub(super) enum ChipletsLookupRow {
     HasherMulti(Vec<HasherLookup>),
     Bitwise(BitwiseLookup),
     Memory(MemoryLookup),
+    MemoryHash(Vec<MemoryLookup>, Vec<HasherLookup>),
 }
 
 impl LookupTableRow for ChipletsLookupRow {
@@ -218,6 +427,102 @@ impl LookupTableRow for ChipletsLookupRow {
             ChipletsLookupRow::Hasher(row) => row.to_value(alphas),
             ChipletsLookupRow::Bitwise(row) => row.to_value(alphas),
             ChipletsLookupRow::Memory(row) => row.to_value(alphas),
+            ChipletsLookupRow::MemoryHash(memory_lookups, hasher_lookups) => {
+                let value = memory_lookups
+                    .iter()
+                    .fold(E::ONE, |acc, row| acc * row.to_value(alphas));
+                hasher_lookups
+                    .iter()
+                    .fold(value, |acc, row| acc * row.to_value(alphas))
+            }
+        }
+    }
+}
+
+impl Serializable for ChipletsLookupRow {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        match self {
+            ChipletsLookupRow::Hasher(row) => {
+                target.write_u8(0);
+                row.write_into(target);
+            }
+            ChipletsLookupRow::HasherMulti(lookups) => {
+                target.write_u8(1);
+                write_lookups(target, lookups);
+            }
+            ChipletsLookupRow::Bitwise(row) => {
+                target.write_u8(2);
+                row.write_into(target);
+            }
+            ChipletsLookupRow::Memory(row) => {
+                target.write_u8(3);
+                row.write_into(target);
+            }
+            ChipletsLookupRow::MemoryHash(memory_lookups, hasher_lookups) => {
+                target.write_u8(4);
+                write_lookups(target, memory_lookups);
+                write_lookups(target, hasher_lookups);
+            }
         }
     }
 }
+
+impl Deserializable for ChipletsLookupRow {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(ChipletsLookupRow::Hasher(HasherLookup::read_from(source)?)),
+            1 => Ok(ChipletsLookupRow::HasherMulti(read_lookups(source)?)),
+            2 => Ok(ChipletsLookupRow::Bitwise(BitwiseLookup::read_from(
+                source,
+            )?)),
+            3 => Ok(ChipletsLookupRow::Memory(MemoryLookup::read_from(source)?)),
+            4 => {
+                let memory_lookups = read_lookups(source)?;
+                let hasher_lookups = read_lookups(source)?;
+                Ok(ChipletsLookupRow::MemoryHash(
+                    memory_lookups,
+                    hasher_lookups,
+                ))
+            }
+            _ => Err(DeserializationError::InvalidValue(
+                "unknown chiplets lookup row".to_string(),
+            )),
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Writes the number of the specified rows followed by the rows themselves into the target.
+fn write_rows<W: ByteWriter>(target: &mut W, rows: &[ChipletsLookupRow]) {
+    target.write_u64(rows.len() as u64);
+    target.write(rows);
+}
+
+/// Reads rows written by [write_rows()] from the source.
+fn read_rows<R: ByteReader>(
+    source: &mut R,
+) -> Result<Vec<ChipletsLookupRow>, DeserializationError> {
+    let num_rows = source.read_u64()? as usize;
+    ChipletsLookupRow::read_batch_from(source, num_rows)
+}
+
+/// Writes the number of the specified lookups followed by the lookups themselves into the target;
+/// lookup rows hold at most a few lookups each.
+fn write_lookups<W: ByteWriter, L: Serializable>(target: &mut W, lookups: &[L]) {
+    debug_assert!(
+        lookups.len() <= u8::MAX as usize,
+        "too many lookups in a row"
+    );
+    target.write_u8(lookups.len() as u8);
+    target.write(lookups);
+}
+
+/// Reads lookups written by [write_lookups()] from the source.
+fn read_lookups<R: ByteReader, L: Deserializable>(
+    source: &mut R,
+) -> Result<Vec<L>, DeserializationError> {
+    let num_lookups = source.read_u8()? as usize;
+    L::read_batch_from(source, num_lookups)
+}
@@ -0,0 +1,171 @@
+use super::{BitwiseLookup, ChipletsBus, ChipletsLookup, Felt, MemoryLookup};
+use crate::{execute, Program, ProgramInputs};
+use vm_core::{chiplets::bitwise::BITWISE_AND_LABEL, code_blocks::CodeBlock, Operation, ONE, ZERO};
+use winterfell::{DeserializationError, Matrix, Serializable, Trace};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn request_and_response_order() {
+    let lookup = BitwiseLookup::new(BITWISE_AND_LABEL, Felt::new(5), Felt::new(3), Felt::new(1));
+    let cycle = 3;
+
+    // the request is sent before the response is provided
+    let mut request_first = ChipletsBus::default();
+    request_first.request_bitwise_operation(lookup, cycle);
+    request_first.provide_bitwise_operation(lookup, cycle);
+
+    // the response is provided before the request is sent
+    let mut response_first = ChipletsBus::default();
+    response_first.provide_bitwise_operation(lookup, cycle);
+    response_first.request_bitwise_operation(lookup, cycle);
+
+    let expected = ChipletsLookup::RequestAndResponse((0, 0));
+    assert_eq!(Some(&expected), request_first.get_lookup_hint(cycle));
+    assert_eq!(Some(&expected), response_first.get_lookup_hint(cycle));
+
+    // both orders contribute the same values to the bus column
+    let main_trace = Matrix::new(vec![vec![Felt::new(0); 8]]);
+    let alphas = (1..=16).map(Felt::new).collect::<Vec<_>>();
+    let request_first = request_first
+        .into_aux_builder()
+        .build_aux_columns(&main_trace, &alphas);
+    let response_first = response_first
+        .into_aux_builder()
+        .build_aux_columns(&main_trace, &alphas);
+    assert_eq!(request_first, response_first);
+}
+
+#[test]
+fn distinct_cycles() {
+    let lookup = BitwiseLookup::new(BITWISE_AND_LABEL, Felt::new(5), Felt::new(3), Felt::new(1));
+
+    let mut bus = ChipletsBus::default();
+    bus.provide_bitwise_operation(lookup, 7);
+    bus.request_bitwise_operation(lookup, 2);
+
+    assert_eq!(Some(&ChipletsLookup::Request(0)), bus.get_lookup_hint(2));
+    assert_eq!(Some(&ChipletsLookup::Response(0)), bus.get_lookup_hint(7));
+}
+
+#[test]
+#[should_panic]
+fn duplicate_requests() {
+    let lookup = BitwiseLookup::new(BITWISE_AND_LABEL, Felt::new(5), Felt::new(3), Felt::new(1));
+
+    let mut bus = ChipletsBus::default();
+    bus.request_bitwise_operation(lookup, 2);
+    bus.request_bitwise_operation(lookup, 2);
+}
+
+#[test]
+fn serialization_round_trip() {
+    let bitwise = BitwiseLookup::new(BITWISE_AND_LABEL, Felt::new(5), Felt::new(3), Felt::new(1));
+    let memory = MemoryLookup::new(Felt::new(2), 4, [ZERO; 4], [ONE; 4]);
+
+    let mut bus = ChipletsBus::default();
+    bus.request_bitwise_operation(bitwise, 1);
+    bus.request_memory_operation(memory, 4);
+    bus.provide_memory_operation(memory, 4);
+    bus.provide_bitwise_operation(bitwise, 9);
+
+    let bytes = bus.to_bytes();
+    assert_eq!(bus, ChipletsBus::from_bytes(&bytes).unwrap());
+
+    // bytes which are not consumed by the bus are rejected
+    let mut extended = bytes.clone();
+    extended.push(0);
+    assert!(matches!(
+        ChipletsBus::from_bytes(&extended),
+        Err(DeserializationError::UnconsumedBytes)
+    ));
+
+    // truncated bytes are rejected
+    assert!(ChipletsBus::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn serialization_invalid_hint() {
+    let lookup = BitwiseLookup::new(BITWISE_AND_LABEL, Felt::new(5), Felt::new(3), Felt::new(1));
+    let mut bus = ChipletsBus::default();
+    bus.request_bitwise_operation(lookup, 2);
+
+    // the hint of a request without a request row refers to a row which does not exist
+    bus.request_rows.clear();
+    assert!(matches!(
+        ChipletsBus::from_bytes(&bus.to_bytes()),
+        Err(DeserializationError::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn serialized_bus_builds_same_column() {
+    let program = Program::new(CodeBlock::new_span(vec![
+        Operation::Push(Felt::new(7)),
+        Operation::Push(Felt::new(3)),
+        Operation::U32and,
+        Operation::Pad,
+        Operation::MStoreW,
+        Operation::RpPerm,
+    ]));
+    let trace = execute(&program, &ProgramInputs::none()).unwrap();
+
+    // the bus is serialized by the process which executed the program, and the column is built
+    // by another process from the main trace and the deserialized bus
+    let bytes = trace.chiplets_bus().to_bytes();
+    let bus = ChipletsBus::from_bytes(&bytes).unwrap();
+
+    let alphas = (1..=16).map(Felt::new).collect::<Vec<_>>();
+    let expected = trace
+        .chiplets_bus()
+        .into_aux_builder()
+        .build_aux_columns(trace.main_segment(), &alphas);
+    let columns = bus
+        .into_aux_builder()
+        .build_aux_columns(trace.main_segment(), &alphas);
+    assert_eq!(expected, columns);
+}
+
+#[cfg(feature = "bus-ledger")]
+#[test]
+fn bus_ledger_lists_lookups_by_cycle() {
+    let and = BitwiseLookup::new(BITWISE_AND_LABEL, Felt::new(5), Felt::new(3), Felt::new(1));
+    let other = BitwiseLookup::new(BITWISE_AND_LABEL, Felt::new(6), Felt::new(3), Felt::new(2));
+
+    let mut bus = ChipletsBus::default();
+    bus.provide_bitwise_operation(and, 7);
+    bus.request_bitwise_operation(other, 3);
+    bus.provide_bitwise_operation(other, 3);
+    bus.request_bitwise_operation(and, 2);
+    let ledger = bus.to_ledger_json();
+
+    // entries are listed by cycle, with the request first when both share a cycle
+    let entries = ledger
+        .split("{\"cycle\":")
+        .skip(1)
+        .map(|entry| entry.split(",\"value\"").next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "2,\"kind\":\"request\",\"row\":\"bitwise\"",
+            "3,\"kind\":\"request\",\"row\":\"bitwise\"",
+            "3,\"kind\":\"response\",\"row\":\"bitwise\"",
+            "7,\"kind\":\"response\",\"row\":\"bitwise\"",
+        ],
+        entries
+    );
+
+    // a request and the matching response are reduced to the same value
+    let value = |i: usize| {
+        ledger
+            .split(",\"value\":")
+            .nth(i)
+            .unwrap()
+            .split('}')
+            .next()
+    };
+    assert_eq!(value(1), value(4));
+    assert_eq!(value(2), value(3));
+    assert_ne!(value(1), value(2));
+}
@@ -1,6 +1,7 @@
 use super::{Felt, FieldElement, StarkField, Vec, Word};
 use crate::trace::{AuxColumnBuilder, LookupTableRow};
-use winterfell::Matrix;
+use vm_core::chiplets::{hasher::DIGEST_LEN, HASHER_STATE_COL_RANGE};
+use winterfell::{math::batch_inversion, Matrix};
 
 // AUXILIARY TRACE BUILDER
 // ================================================================================================
@@ -10,10 +11,13 @@ use winterfell::Matrix;
 ///
 /// Currently, this manages construction of a single column representing the state of the sibling
 /// table (used in Merkle root update computation).
+///
+/// Rows of the sibling table are recorded as references into the hasher trace; the siblings
+/// themselves are read from the main trace only when the auxiliary column is built.
 #[derive(Debug, Clone, Default)]
 pub struct AuxTraceBuilder {
     pub(super) sibling_hints: Vec<(usize, SiblingTableUpdate)>,
-    pub(super) sibling_rows: Vec<SiblingTableRow>,
+    pub(super) sibling_rows: Vec<SiblingTableRowRef>,
 }
 
 impl AuxTraceBuilder {
@@ -35,13 +39,13 @@ impl AuxTraceBuilder {
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
-    /// Specifies that an entry for the provided sibling was added to the sibling table at the
-    /// specified step.
-    pub fn sibling_added(&mut self, step: usize, index: Felt, sibling: Word) {
+    /// Specifies that an entry for the sibling absorbed into the hasher state at the specified
+    /// step was added to the sibling table.
+    pub fn sibling_added(&mut self, step: usize, index: Felt) {
         let row_index = self.sibling_rows.len();
         let update = SiblingTableUpdate::SiblingAdded(row_index as u32);
         self.sibling_hints.push((step, update));
-        self.sibling_rows.push(SiblingTableRow::new(index, sibling));
+        self.sibling_rows.push(SiblingTableRowRef::new(step, index));
     }
 
     /// Specifies that an entry for a sibling was removed from the sibling table. The entry is
@@ -54,15 +58,7 @@ impl AuxTraceBuilder {
     }
 }
 
-impl AuxColumnBuilder<SiblingTableUpdate, SiblingTableRow> for AuxTraceBuilder {
-    /// Returns a list of rows which were added to and then removed from the sibling table.
-    ///
-    /// The order of the rows in the list is the same as the order in which the rows were added to
-    /// the table.
-    fn get_table_rows(&self) -> &[SiblingTableRow] {
-        &self.sibling_rows
-    }
-
+impl AuxColumnBuilder<SiblingTableUpdate> for AuxTraceBuilder {
     /// Returns hints which describe how the sibling table was updated during program execution.
     /// Each update hint is accompanied by a clock cycle at which the update happened.
     ///
@@ -89,6 +85,24 @@ impl AuxColumnBuilder<SiblingTableUpdate, SiblingTableRow> for AuxTraceBuilder {
             }
         }
     }
+
+    /// Builds the row values and inverse values used to build the auxiliary column.
+    ///
+    /// The rows which were added to and then removed from the sibling table are resolved from the
+    /// hasher trace in the order in which they were added to the table.
+    fn build_row_values<E>(&self, main_trace: &Matrix<Felt>, alphas: &[E]) -> (Vec<E>, Vec<E>)
+    where
+        E: FieldElement<BaseField = Felt>,
+    {
+        let row_values = self
+            .sibling_rows
+            .iter()
+            .map(|row| row.resolve(main_trace).to_value(alphas))
+            .collect::<Vec<_>>();
+        let inv_row_values = batch_inversion(&row_values);
+
+        (row_values, inv_row_values)
+    }
 }
 
 // SIBLING TABLE
@@ -118,6 +132,37 @@ impl SiblingTableRow {
     }
 }
 
+/// Describes where an entry of the sibling table can be found in the execution trace: the step at
+/// which its sibling was absorbed into the hasher state, and the index of the node at its depth.
+///
+/// At this step, the hasher state is initialized with the node and its sibling; when the least
+/// significant bit of the index is 0, the sibling is in the 3rd word of the hasher state, and when
+/// it is 1, the sibling is in the 2nd word.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SiblingTableRowRef {
+    step: usize,
+    index: Felt,
+}
+
+impl SiblingTableRowRef {
+    pub fn new(step: usize, index: Felt) -> Self {
+        Self { step, index }
+    }
+
+    /// Returns the sibling table row referenced by this entry, reading its sibling from the hasher
+    /// state columns of the provided main trace.
+    pub fn resolve(&self, main_trace: &Matrix<Felt>) -> SiblingTableRow {
+        let word_idx = if self.index.as_int() & 1 == 0 { 2 } else { 1 };
+        let first_col = HASHER_STATE_COL_RANGE.start + word_idx * DIGEST_LEN;
+
+        let mut sibling = [Felt::ZERO; DIGEST_LEN];
+        for (i, value) in sibling.iter_mut().enumerate() {
+            *value = main_trace.get(first_col + i, self.step);
+        }
+        SiblingTableRow::new(self.index, sibling)
+    }
+}
+
 impl LookupTableRow for SiblingTableRow {
     /// Reduces this row to a single field element in the field specified by E. This requires
     /// at least 6 alpha values.
@@ -1,8 +1,14 @@
 use super::{Felt, FieldElement, HasherState, LookupTableRow, StarkField};
+use crate::{
+    trace::{reduce_elements, reduce_hasher_row},
+    utils::read_elements,
+};
 use vm_core::chiplets::hasher::{
-    CAPACITY_LEN, DIGEST_RANGE, LINEAR_HASH_LABEL, MP_VERIFY_LABEL, MR_UPDATE_NEW_LABEL,
-    MR_UPDATE_OLD_LABEL, RETURN_HASH_LABEL, RETURN_STATE_LABEL, STATE_WIDTH,
+    addr_to_row, CAPACITY_LEN, DIGEST_RANGE, LINEAR_HASH_LABEL, MP_VERIFY_LABEL,
+    MR_UPDATE_NEW_LABEL, MR_UPDATE_OLD_LABEL, RETURN_HASH_LABEL, RETURN_STATE_LABEL, STATE_WIDTH,
 };
+use vm_core::utils::string::ToString;
+use winterfell::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 // CONSTANTS
 // ================================================================================================
@@ -58,23 +64,29 @@ impl HasherLookup {
 
     /// The cycle at which the lookup is provided by the hasher.
     pub(super) fn cycle(&self) -> usize {
-        // the hasher's addresses start from one instead of zero, so the cycle at which each lookup
-        // is provided is one less than its address
-        self.addr as usize - 1
+        // the lookup is provided at the row of the hasher trace at its address
+        addr_to_row(Felt::from(self.addr)).expect("invalid hasher lookup address")
     }
 
-    /// Returns the common header value which describes this hash operation. It is a combination of
-    /// the transition label, the row address, and the node index.
-    fn get_header_value<E: FieldElement<BaseField = Felt>>(&self, alphas: &[E]) -> E {
-        let transition_label = match self.context {
-            HasherLookupContext::Start => E::from(self.label) + E::from(16_u8),
-            _ => E::from(self.label) + E::from(32_u8),
-        };
+    /// Returns the state after the absorption of the next block described by this lookup, so that
+    /// the block can be absorbed into it in place.
+    ///
+    /// # Panics
+    /// Panics if this lookup does not describe the absorption of a block.
+    pub(super) fn absorbed_state_mut(&mut self) -> &mut HasherState {
+        match &mut self.context {
+            HasherLookupContext::Absorb(next_state) => next_state,
+            _ => panic!("hasher lookup does not describe an absorption"),
+        }
+    }
 
-        alphas[0]
-            + alphas[1].mul(transition_label)
-            + alphas[2].mul(E::from(self.addr))
-            + alphas[3].mul_base(self.index)
+    /// Returns the transition label of this hash operation, i.e., its label offset by 16 for the
+    /// first row of a hash cycle, and by 32 for the last one.
+    fn transition_label(&self) -> Felt {
+        match self.context {
+            HasherLookupContext::Start => Felt::from(self.label + 16_u8),
+            _ => Felt::from(self.label + 32_u8),
+        }
     }
 }
 
@@ -82,15 +94,14 @@ impl LookupTableRow for HasherLookup {
     /// Reduces this row to a single field element in the field specified by E. This requires
     /// at least 16 alpha values.
     fn to_value<E: FieldElement<BaseField = Felt>>(&self, alphas: &[E]) -> E {
-        let header = self.get_header_value(&alphas[..NUM_HEADER_ALPHAS]);
-        // computing the rest of the value requires an alpha for each element in the [HasherState]
-        let alphas = &alphas[NUM_HEADER_ALPHAS..(NUM_HEADER_ALPHAS + STATE_WIDTH)];
+        // computing the state value requires an alpha for each element in the [HasherState]
+        let state_alphas = &alphas[NUM_HEADER_ALPHAS..(NUM_HEADER_ALPHAS + STATE_WIDTH)];
 
-        match self.context {
+        let state_value = match self.context {
             HasherLookupContext::Start => {
                 if self.label == LINEAR_HASH_LABEL {
                     // include the entire state when initializing a linear hash.
-                    header + build_value(alphas, &self.state)
+                    reduce_elements(state_alphas, &self.state)
                 } else {
                     assert!(
                         self.label == MR_UPDATE_OLD_LABEL
@@ -103,11 +114,11 @@ impl LookupTableRow for HasherLookup {
                     // by the index bit will be the leaf node, and the value must be computed in the
                     // same way in both cases.
                     let bit = (self.index.as_int() >> 1) & 1;
-                    let left_word = build_value(&alphas[DIGEST_RANGE], &self.state[DIGEST_RANGE]);
-                    let right_word =
-                        build_value(&alphas[DIGEST_RANGE], &self.state[DIGEST_RANGE.end..]);
+                    let word_alphas = &state_alphas[DIGEST_RANGE];
+                    let left_word = reduce_elements(word_alphas, &self.state[DIGEST_RANGE]);
+                    let right_word = reduce_elements(word_alphas, &self.state[DIGEST_RANGE.end..]);
 
-                    header + E::from(1 - bit).mul(left_word) + E::from(bit).mul(right_word)
+                    E::from(1 - bit).mul(left_word) + E::from(bit).mul(right_word)
                 }
             }
             HasherLookupContext::Absorb(next_state) => {
@@ -117,39 +128,79 @@ impl LookupTableRow for HasherLookup {
                 );
                 // build the value from the delta of the hasher state's rate before and after the
                 // absorption of new elements.
-                let next_state_value =
-                    build_value(&alphas[CAPACITY_LEN..], &next_state[CAPACITY_LEN..]);
-                let state_value = build_value(&alphas[CAPACITY_LEN..], &self.state[CAPACITY_LEN..]);
+                let rate_alphas = &state_alphas[CAPACITY_LEN..];
+                let next_state_value = reduce_elements(rate_alphas, &next_state[CAPACITY_LEN..]);
+                let state_value = reduce_elements(rate_alphas, &self.state[CAPACITY_LEN..]);
 
-                header + next_state_value - state_value
+                next_state_value - state_value
             }
             HasherLookupContext::Return => {
                 if self.label == RETURN_STATE_LABEL {
                     // build the value from the result, which is the entire state
-                    header + build_value(alphas, &self.state)
+                    reduce_elements(state_alphas, &self.state)
                 } else {
                     assert!(
                         self.label == RETURN_HASH_LABEL,
                         "unrecognized hash operation"
                     );
                     // build the value from the result, which is the digest portion of the state
-                    header + build_value(&alphas[DIGEST_RANGE], &self.state[DIGEST_RANGE])
+                    reduce_elements(&state_alphas[DIGEST_RANGE], &self.state[DIGEST_RANGE])
                 }
             }
+        };
+
+        reduce_hasher_row(
+            alphas,
+            self.transition_label(),
+            Felt::from(self.addr),
+            self.index,
+            state_value,
+        )
+    }
+}
+
+impl Serializable for HasherLookup {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.label);
+        target.write(&self.state[..]);
+        target.write_u32(self.addr);
+        target.write(self.index);
+        match self.context {
+            HasherLookupContext::Start => target.write_u8(0),
+            HasherLookupContext::Absorb(next_state) => {
+                target.write_u8(1);
+                target.write(&next_state[..]);
+            }
+            HasherLookupContext::Return => target.write_u8(2),
         }
     }
 }
 
-// HELPER FUNCTIONS
-// ================================================================================================
+impl Deserializable for HasherLookup {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let label = source.read_u8()?;
+        let state = read_elements(source)?;
+        let addr = source.read_u32()?;
+        let index = Felt::read_from(source)?;
+        let context = match source.read_u8()? {
+            0 => HasherLookupContext::Start,
+            1 => HasherLookupContext::Absorb(read_elements(source)?),
+            2 => HasherLookupContext::Return,
+            _ => {
+                return Err(DeserializationError::InvalidValue(
+                    "unknown hasher lookup context".to_string(),
+                ))
+            }
+        };
+
+        // the cycle of a lookup is derived from its address
+        if let Err(err) = addr_to_row(Felt::from(addr)) {
+            return Err(DeserializationError::InvalidValue(format!(
+                "invalid hasher lookup address {}: {:?}",
+                addr, err
+            )));
+        }
 
-/// Reduces a slice of elements to a single field element in the field specified by E using a slice
-/// of alphas of matching length. This can be used to build the value for a single word or for an
-/// entire [HasherState].
-fn build_value<E: FieldElement<BaseField = Felt>>(alphas: &[E], elements: &[Felt]) -> E {
-    let mut value = E::ZERO;
-    for (&alpha, &element) in alphas.iter().zip(elements.iter()) {
-        value += alpha.mul_base(element);
+        Ok(Self::new(label, state, addr, index, context))
     }
-    value
 }
@@ -3,10 +3,11 @@ use super::{
     TraceFragment, Vec, Word, ZERO,
 };
 use vm_core::chiplets::hasher::{
-    absorb_into_state, get_digest, init_state, init_state_from_words, Selectors, LINEAR_HASH,
-    LINEAR_HASH_LABEL, MP_VERIFY, MP_VERIFY_LABEL, MR_UPDATE_NEW, MR_UPDATE_NEW_LABEL,
-    MR_UPDATE_OLD, MR_UPDATE_OLD_LABEL, RETURN_HASH, RETURN_HASH_LABEL, RETURN_STATE,
-    RETURN_STATE_LABEL, STATE_WIDTH, TRACE_WIDTH,
+    absorb_into_state, get_digest, init_state_from_words_in_domain, init_state_in_domain,
+    Selectors, CONTROL_BLOCK_DOMAIN, HASH_CYCLE_LEN, LINEAR_HASH, LINEAR_HASH_LABEL, MERKLE_DOMAIN,
+    MP_VERIFY, MP_VERIFY_LABEL, MR_UPDATE_NEW, MR_UPDATE_NEW_LABEL, MR_UPDATE_OLD,
+    MR_UPDATE_OLD_LABEL, RATE_LEN, RETURN_HASH, RETURN_HASH_LABEL, RETURN_STATE,
+    RETURN_STATE_LABEL, SPAN_HASH_DOMAIN, STATE_WIDTH, TRACE_WIDTH, USER_HASH_DOMAIN,
 };
 
 mod lookups;
@@ -22,6 +23,12 @@ pub use aux_trace::{AuxTraceBuilder, SiblingTableRow, SiblingTableUpdate};
 #[cfg(test)]
 mod tests;
 
+// CONSTANTS
+// ================================================================================================
+
+/// Number of field elements in a word of memory.
+const WORD_LEN: usize = 4;
+
 // HASH PROCESSOR
 // ================================================================================================
 
@@ -64,7 +71,7 @@ mod tests;
 /// - a vector of [HasherLookup]s, each of which specifies the data for one of the lookup rows which
 ///   are required for verification of the communication between the stack/decoder and the Hash
 ///   Chiplet via the Chiplets Bus.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Hasher {
     trace: HasherTrace,
     aux_trace: AuxTraceBuilder,
@@ -89,6 +96,14 @@ impl Hasher {
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
+    /// Reserves capacity for at least `num_permutations` more permutations of the hash function
+    /// in the execution trace, and for the lookups verifying them.
+    pub(super) fn reserve(&mut self, num_permutations: usize) {
+        self.trace.reserve(num_permutations * HASH_CYCLE_LEN);
+        // most permutations are verified with a lookup at the first and at the last row
+        self.lookups.reserve(2 * num_permutations);
+    }
+
     /// Records a HasherLookup with the specified data.
     ///
     /// When starting a hash operation, it should be called before any rows are recorded in the
@@ -112,6 +127,36 @@ impl Hasher {
             .push(HasherLookup::new(label, state, addr, index, context));
     }
 
+    /// Records the lookup for absorbing the provided block into the provided state of a
+    /// sequential hash, and absorbs the block into the state.
+    ///
+    /// The lookup is written in place: it is appended with the provided state as the state both
+    /// before and after the absorption, and the block is then absorbed into the latter, which is
+    /// copied back into the provided state. This should be called immediately after the
+    /// permutation preceding the absorption is appended to the trace.
+    fn append_absorb_lookup(
+        &mut self,
+        label: u8,
+        state: &mut HasherState,
+        block: &[Felt; RATE_LEN],
+    ) {
+        let addr = self.trace_len() as u32;
+        self.lookups.push(HasherLookup::new(
+            label,
+            *state,
+            addr,
+            ZERO,
+            HasherLookupContext::Absorb(*state),
+        ));
+        let next_state = self
+            .lookups
+            .last_mut()
+            .expect("no hasher lookups")
+            .absorbed_state_mut();
+        absorb_into_state(next_state, block);
+        *state = *next_state;
+    }
+
     /// Returns the index at which the next lookup will be appended.
     fn next_lookup_idx(&self) -> usize {
         self.lookups.len()
@@ -154,7 +199,8 @@ impl Hasher {
         (addr, state, lookups)
     }
 
-    /// Merges the provided words by computing hash(h1, h2) and returns the result. It also records
+    /// Merges the provided words by computing hash(h1, h2) in the domain of control blocks and
+    /// returns the result. It also records
     /// the execution trace of this computation as well as the lookups required for verifying its
     /// correctness so that they can be provided to the Chiplets Bus when the trace is finalized.
     ///
@@ -164,7 +210,7 @@ impl Hasher {
     pub(super) fn merge(&mut self, h1: Word, h2: Word) -> (Felt, Word, &[HasherLookup]) {
         let addr = self.trace.next_row_addr();
         let init_lookup_idx = self.next_lookup_idx();
-        let mut state = init_state_from_words(&h1, &h2);
+        let mut state = init_state_from_words_in_domain(&h1, &h2, CONTROL_BLOCK_DOMAIN);
 
         // add the lookup for the hash initialization.
         self.append_lookup(LINEAR_HASH_LABEL, state, ZERO, HasherLookupContext::Start);
@@ -194,6 +240,51 @@ impl Hasher {
         op_batches: &[OpBatch],
         num_op_groups: usize,
     ) -> (Felt, Word, &[HasherLookup]) {
+        let blocks = op_batches.iter().map(|batch| *batch.groups());
+        self.hash_sequential(blocks, num_op_groups, SPAN_HASH_DOMAIN)
+    }
+
+    /// Computes a sequential hash of the provided words read from memory and returns the result.
+    /// The words are absorbed two at a time, and the last block is padded with ZEROs if the
+    /// number of words is odd; thus, the result is the same as the hash of all elements of the
+    /// words computed by `hasher::hash_elements_in_domain()` in the domain of user hashes.
+    ///
+    /// The returned tuple also contains the row address of the execution trace at which the hash
+    /// computation started and the lookups required to verify the computation so that the correct
+    /// requests can be sent by the caller to the Chiplets Bus.
+    ///
+    /// # Panics
+    /// Panics if no words are provided.
+    pub(super) fn hash_memory(&mut self, words: &[Word]) -> (Felt, Word, &[HasherLookup]) {
+        let blocks = words.chunks(RATE_LEN / WORD_LEN).map(|chunk| {
+            let mut block = [ZERO; RATE_LEN];
+            for (i, word) in chunk.iter().enumerate() {
+                block[i * WORD_LEN..(i + 1) * WORD_LEN].copy_from_slice(word);
+            }
+            block
+        });
+        self.hash_sequential(blocks, words.len() * WORD_LEN, USER_HASH_DOMAIN)
+    }
+
+    /// Computes a sequential hash of the provided blocks of `num_elements` elements in total in
+    /// the specified domain, and records the execution trace of this computation and the lookups
+    /// required for verifying its correctness.
+    ///
+    /// The returned tuple contains the result, the row address of the execution trace at which
+    /// the hash computation started, and the lookups of the computation: one for the
+    /// initialization, one for absorbing every block after the first one, and one for the result.
+    ///
+    /// # Panics
+    /// Panics if no blocks are provided.
+    fn hash_sequential<I>(
+        &mut self,
+        mut blocks: I,
+        num_elements: usize,
+        domain: Felt,
+    ) -> (Felt, Word, &[HasherLookup])
+    where
+        I: ExactSizeIterator<Item = [Felt; RATE_LEN]>,
+    {
         const START: Selectors = LINEAR_HASH;
         const START_LABEL: u8 = LINEAR_HASH_LABEL;
         const RETURN: Selectors = RETURN_HASH;
@@ -207,55 +298,49 @@ impl Hasher {
         // 1st flag to ZERO.
         const CONTINUE: Selectors = [ZERO, LINEAR_HASH[1], LINEAR_HASH[2]];
 
+        let num_blocks = blocks.len();
+
+        // reserve the trace rows and the lookups of the whole computation at once: one
+        // permutation for every block, and one lookup for the initialization, for absorbing every
+        // block after the first one, and for the result
+        self.trace.reserve(num_blocks * HASH_CYCLE_LEN);
+        self.lookups.reserve(num_blocks + 1);
+
         let addr = self.trace.next_row_addr();
         let init_lookup_idx = self.next_lookup_idx();
 
-        // initialize the state and absorb the first operation batch into it
-        let mut state = init_state(op_batches[0].groups(), num_op_groups);
+        // initialize the state and absorb the first block into it
+        let first_block = blocks.next().expect("no blocks to hash");
+        let mut state = init_state_in_domain(&first_block, num_elements, domain);
 
         // add the lookup for the hash initialization.
         self.append_lookup(START_LABEL, state, ZERO, HasherLookupContext::Start);
 
-        let num_batches = op_batches.len();
-        if num_batches == 1 {
-            // if there is only one batch to hash, we need only one permutation
+        if num_blocks == 1 {
+            // if there is only one block to hash, we need only one permutation
             self.trace.append_permutation(&mut state, START, RETURN);
         } else {
-            // if there is more than one batch, we need to process the first, the last, and the
+            // if there is more than one block, we need to process the first, the last, and the
             // middle permutations a bit differently. Specifically, selector flags for the
             // permutations need to be set as follows:
-            // - first permutation: init linear hash on the first row, and absorb the next
-            //   operation batch on the last row.
-            // - middle permutations: continue hashing on the first row, and absorb the next
-            //   operation batch on the last row.
-            // - last permutation: continue hashing on the first row, and return the result
+            // - first permutation: init linear hash on the first row, and absorb the next block
             //   on the last row.
+            // - middle permutations: continue hashing on the first row, and absorb the next
+            //   block on the last row.
+            // - last permutation: continue hashing on the first row, and return the result on
+            //   the last row.
             self.trace.append_permutation(&mut state, START, ABSORB);
-            let mut last_state = state;
-
-            for batch in op_batches.iter().take(num_batches - 1).skip(1) {
-                absorb_into_state(&mut state, batch.groups());
-                // add the lookup for absorbing the next operation batch.
-                self.append_lookup(
-                    ABSORB_LABEL,
-                    last_state,
-                    ZERO,
-                    HasherLookupContext::Absorb(state),
-                );
-
-                self.trace.append_permutation(&mut state, CONTINUE, ABSORB);
-                last_state = state;
-            }
 
-            absorb_into_state(&mut state, op_batches[num_batches - 1].groups());
-            // add the lookup for absorbing the final operation batch.
-            self.append_lookup(
-                ABSORB_LABEL,
-                last_state,
-                ZERO,
-                HasherLookupContext::Absorb(state),
-            );
-            self.trace.append_permutation(&mut state, CONTINUE, RETURN);
+            for (i, block) in blocks.enumerate() {
+                // add the lookup for absorbing the next block, and absorb the block.
+                self.append_absorb_lookup(ABSORB_LABEL, &mut state, &block);
+
+                // the blocks after the first one are enumerated from zero
+                let is_last_block = i + 2 == num_blocks;
+                let end_selectors = if is_last_block { RETURN } else { ABSORB };
+                self.trace
+                    .append_permutation(&mut state, CONTINUE, end_selectors);
+            }
         }
 
         // add the lookup for the hash result.
@@ -387,19 +472,19 @@ impl Hasher {
         if path.len() == 1 {
             // handle path of length 1 separately because pattern for init and final selectors
             // is different from other cases
-            self.update_sibling_hints(context, index, path[0], depth);
+            self.update_sibling_hints(context, index, depth);
             self.verify_mp_leg(root, path[0], &mut index, main_selectors, RETURN_HASH)
         } else {
             // process the first node of the path; for this node, init and final selectors are
             // the same
             let sibling = path[0];
-            self.update_sibling_hints(context, index, sibling, depth);
+            self.update_sibling_hints(context, index, depth);
             root = self.verify_mp_leg(root, sibling, &mut index, main_selectors, main_selectors);
             depth -= 1;
 
             // process all other nodes, except for the last one
             for &sibling in &path[1..path.len() - 1] {
-                self.update_sibling_hints(context, index, sibling, depth);
+                self.update_sibling_hints(context, index, depth);
                 root =
                     self.verify_mp_leg(root, sibling, &mut index, part_selectors, main_selectors);
                 depth -= 1;
@@ -407,7 +492,7 @@ impl Hasher {
 
             // process the last node
             let sibling = path[path.len() - 1];
-            self.update_sibling_hints(context, index, sibling, depth);
+            self.update_sibling_hints(context, index, depth);
             self.verify_mp_leg(root, sibling, &mut index, part_selectors, RETURN_HASH)
         }
     }
@@ -475,18 +560,15 @@ impl Hasher {
     /// Records an update hint in the auxiliary trace builder to indicate whether the sibling was
     /// consumed as a part of computing the new or the old Merkle root. This is relevant only for
     /// the Merkle root update computation.
-    fn update_sibling_hints(
-        &mut self,
-        context: MerklePathContext,
-        index: u64,
-        sibling: Word,
-        depth: usize,
-    ) {
+    ///
+    /// This must be called before the permutation which absorbs the sibling is appended to the
+    /// trace, so that the sibling can be read from the first row of the permutation when the
+    /// auxiliary trace is built.
+    fn update_sibling_hints(&mut self, context: MerklePathContext, index: u64, depth: usize) {
         let step = self.trace.trace_len();
         match context {
             MerklePathContext::MrUpdateOld => {
-                self.aux_trace
-                    .sibling_added(step, Felt::new(index), sibling);
+                self.aux_trace.sibling_added(step, Felt::new(index));
             }
             MerklePathContext::MrUpdateNew => {
                 // we use node depth as row offset here because siblings are added to the table
@@ -539,15 +621,16 @@ impl MerklePathContext {
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Combines two words into a hasher state for Merkle path computation.
+/// Combines two words into a hasher state for Merkle path computation in the domain of Merkle
+/// paths.
 ///
 /// If index_bit = 0, the words are combined in the order (a, b), if index_bit = 1, the words are
 /// combined in the order (b, a), otherwise, the function panics.
 #[inline(always)]
 fn build_merge_state(a: &Word, b: &Word, index_bit: u64) -> HasherState {
     match index_bit {
-        0 => init_state_from_words(a, b),
-        1 => init_state_from_words(b, a),
+        0 => init_state_from_words_in_domain(a, b, MERKLE_DOMAIN),
+        1 => init_state_from_words_in_domain(b, a, MERKLE_DOMAIN),
         _ => panic!("index bit is not a binary value"),
     }
 }
@@ -1,7 +1,8 @@
 use super::{
-    init_state_from_words, AuxTraceBuilder, ChipletsBus, Felt, Hasher, HasherState, Selectors,
-    SiblingTableRow, SiblingTableUpdate, TraceFragment, Word, LINEAR_HASH, MP_VERIFY,
-    MR_UPDATE_NEW, MR_UPDATE_OLD, RETURN_HASH, RETURN_STATE, TRACE_WIDTH,
+    aux_trace::SiblingTableRowRef, init_state_from_words, AuxTraceBuilder, ChipletsBus, Felt,
+    Hasher, HasherLookup, HasherLookupContext, HasherState, Selectors, SiblingTableUpdate,
+    TraceFragment, Word, LINEAR_HASH, LINEAR_HASH_LABEL, MP_VERIFY, MR_UPDATE_NEW, MR_UPDATE_OLD,
+    RETURN_HASH, RETURN_HASH_LABEL, RETURN_STATE, TRACE_WIDTH,
 };
 use rand_utils::rand_array;
 use vm_core::{
@@ -78,6 +79,72 @@ fn hasher_permute() {
     assert!(aux_hints.sibling_rows.is_empty());
 }
 
+#[test]
+fn hasher_hash_memory_lookups() {
+    // hash 6 words, i.e., 3 blocks, after one unrelated permutation
+    let mut hasher = Hasher::default();
+    hasher.permute(rand_array());
+    let words: Vec<Word> = (0..6).map(|_| rand_array()).collect();
+    let (addr, result, lookups) = hasher.hash_memory(&words);
+    let lookups = lookups.to_vec();
+
+    // the computation starts after the first permutation
+    assert_eq!(Felt::new(9), addr);
+    let elements: Vec<Felt> = words.iter().flatten().copied().collect();
+    let expected_result: Word =
+        hasher::hash_elements_in_domain(&elements, hasher::USER_HASH_DOMAIN).into();
+    assert_eq!(expected_result, result);
+    assert_ne!(Word::from(hasher::hash_elements(&elements)), result);
+
+    // compute the expected lookups: one for the initialization, one for absorbing each of the
+    // last two blocks at the last row of the preceding permutation, and one for the result
+    let blocks: Vec<[Felt; 8]> = elements
+        .chunks(8)
+        .map(|chunk| chunk.try_into().unwrap())
+        .collect();
+    let mut state =
+        hasher::init_state_in_domain(&blocks[0], elements.len(), hasher::USER_HASH_DOMAIN);
+    let mut expected = vec![HasherLookup::new(
+        LINEAR_HASH_LABEL,
+        state,
+        9,
+        ZERO,
+        HasherLookupContext::Start,
+    )];
+    for (i, block) in blocks.iter().enumerate().skip(1) {
+        state = apply_permutation(state);
+        let last_state = state;
+        hasher::absorb_into_state(&mut state, block);
+        expected.push(HasherLookup::new(
+            LINEAR_HASH_LABEL,
+            last_state,
+            8 * (i as u32 + 1),
+            ZERO,
+            HasherLookupContext::Absorb(state),
+        ));
+    }
+    state = apply_permutation(state);
+    expected.push(HasherLookup::new(
+        RETURN_HASH_LABEL,
+        state,
+        32,
+        ZERO,
+        HasherLookupContext::Return,
+    ));
+    assert_eq!(expected, lookups);
+
+    // make sure the trace is correct
+    let (trace, _) = build_trace(hasher, 32);
+    check_row_addr_trace(&trace);
+    check_selector_trace(&trace, 8, LINEAR_HASH, LINEAR_HASH);
+    check_selector_trace(
+        &trace,
+        24,
+        [ZERO, LINEAR_HASH[1], LINEAR_HASH[2]],
+        RETURN_HASH,
+    );
+}
+
 // MERKLE TREE TESTS
 // ================================================================================================
 
@@ -214,9 +281,11 @@ fn hasher_update_merkle_root() {
     ];
     assert_eq!(expected_sibling_hints, aux_hints.sibling_hints);
 
+    // siblings are absorbed into the hasher state at the first row of the permutations which
+    // compute the old roots
     let expected_sibling_rows = vec![
-        SiblingTableRow::new(ZERO, path0[0]),
-        SiblingTableRow::new(ONE, path1[0]),
+        SiblingTableRowRef::new(0, ZERO),
+        SiblingTableRowRef::new(16, ONE),
     ];
     assert_eq!(expected_sibling_rows, aux_hints.sibling_rows);
 
@@ -283,17 +352,17 @@ fn hasher_update_merkle_root() {
 
     let expected_sibling_rows = vec![
         // first update
-        SiblingTableRow::new(Felt::new(3), path3[0]),
-        SiblingTableRow::new(Felt::new(3 >> 1), path3[1]),
-        SiblingTableRow::new(Felt::new(3 >> 2), path3[2]),
+        SiblingTableRowRef::new(0, Felt::new(3)),
+        SiblingTableRowRef::new(8, Felt::new(3 >> 1)),
+        SiblingTableRowRef::new(16, Felt::new(3 >> 2)),
         // second update
-        SiblingTableRow::new(Felt::new(6), path6[0]),
-        SiblingTableRow::new(Felt::new(6 >> 1), path6[1]),
-        SiblingTableRow::new(Felt::new(6 >> 2), path6[2]),
+        SiblingTableRowRef::new(48, Felt::new(6)),
+        SiblingTableRowRef::new(56, Felt::new(6 >> 1)),
+        SiblingTableRowRef::new(64, Felt::new(6 >> 2)),
         // third update
-        SiblingTableRow::new(Felt::new(3), path3_2[0]),
-        SiblingTableRow::new(Felt::new(3 >> 1), path3_2[1]),
-        SiblingTableRow::new(Felt::new(3 >> 2), path3_2[2]),
+        SiblingTableRowRef::new(96, Felt::new(3)),
+        SiblingTableRowRef::new(104, Felt::new(3 >> 1)),
+        SiblingTableRowRef::new(112, Felt::new(3 >> 2)),
     ];
     assert_eq!(expected_sibling_rows, aux_hints.sibling_rows);
 }
@@ -369,7 +438,7 @@ fn check_merkle_path(
 /// by 1 with every row.
 fn check_row_addr_trace(trace: &[Vec<Felt>]) {
     for (i, &addr) in trace[3].iter().enumerate() {
-        assert_eq!(Felt::new(i as u64 + 1), addr);
+        assert_eq!(hasher::row_to_addr(i), addr);
     }
 }
 
@@ -1,5 +1,5 @@
 use super::{Felt, HasherState, Selectors, TraceFragment, Vec, STATE_WIDTH, TRACE_WIDTH, ZERO};
-use vm_core::chiplets::hasher::{apply_round, NUM_ROUNDS};
+use vm_core::chiplets::hasher::{apply_round, row_to_addr, NUM_ROUNDS};
 
 // HASHER TRACE
 // ================================================================================================
@@ -11,7 +11,7 @@ use vm_core::chiplets::hasher::{apply_round, NUM_ROUNDS};
 /// - 1 row address column.
 /// - 12 columns describing hasher state.
 /// - 1 node index column used for Merkle path related computations.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct HasherTrace {
     selectors: [Vec<Felt>; 3],
     row_addr: Vec<Felt>,
@@ -28,18 +28,24 @@ impl HasherTrace {
         self.row_addr.len()
     }
 
-    /// Returns next row address. The address is equal to the current trace length + 1.
-    ///
-    /// The above means that row addresses start at ONE (rather than ZERO), and are incremented by
-    /// ONE at every row. Starting at ONE is needed for the decoder so that the address of the
-    /// first code block is a non-zero value.
+    /// Returns next row address, i.e., the address of the row at the current trace length; see
+    /// [row_to_addr()] for how rows are mapped to addresses.
     pub fn next_row_addr(&self) -> Felt {
-        Felt::new(self.trace_len() as u64 + 1)
+        row_to_addr(self.trace_len())
     }
 
     // TRACE MUTATORS
     // --------------------------------------------------------------------------------------------
 
+    /// Reserves capacity for at least `num_rows` more rows in every column of this trace.
+    pub fn reserve(&mut self, num_rows: usize) {
+        self.selectors
+            .iter_mut()
+            .chain(self.hasher_state.iter_mut())
+            .chain([&mut self.row_addr, &mut self.node_index])
+            .for_each(|column| column.reserve(num_rows));
+    }
+
     /// Appends 8 rows to the execution trace describing a single permutation of the hash function.
     ///
     /// The initial state of the hasher is provided via the `state` parameter. All subsequent
@@ -1,14 +1,22 @@
 use crate::{
     range::RangeChecker,
-    trace::LookupTableRow,
-    utils::{split_element_u32_into_u16, split_u32_into_u16},
+    trace::{reduce_memory_row, LookupTableRow},
+    utils::{read_elements, split_element_u48_into_u16, split_u48_into_u16},
+    ExecutionError,
 };
 
 use super::{
     BTreeMap, ChipletsBus, Felt, FieldElement, RangeInclusive, StarkField, TraceFragment, Vec,
     Word, ONE, ZERO,
 };
-use vm_core::chiplets::memory::MEMORY_LABEL;
+use vm_core::chiplets::memory::MAX_DELTA;
+use winterfell::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+
+#[cfg(feature = "std")]
+use std::sync::Arc;
 
 #[cfg(test)]
 mod tests;
@@ -19,6 +27,20 @@ mod tests;
 /// Initial value of every memory cell.
 const INIT_MEM_VALUE: Word = [ZERO; 4];
 
+/// Number of low bits of an address which select the address within its memory page; a page
+/// holds the traces of 2^8 consecutive addresses.
+const PAGE_BITS: u32 = 8;
+
+// TYPE ALIASES
+// ================================================================================================
+
+/// Accesses of a single address as (clock cycle, value after the access) pairs, sorted by clock
+/// cycle.
+type AddrTrace = Vec<(Felt, Word)>;
+
+/// Traces of the accessed addresses of a single memory page, sorted by address.
+type Page = BTreeMap<u64, AddrTrace>;
+
 // RANDOM ACCESS MEMORY
 // ================================================================================================
 
@@ -36,13 +58,13 @@ const INIT_MEM_VALUE: Word = [ZERO; 4];
 /// ## Execution trace
 /// The layout of the memory access trace is shown below.
 ///
-///   ctx   addr   clk   u0   u1   u2   u3   v0   v1   v2   v3   d0   d1   d_inv
-/// ├─────┴──────┴─────┴────┴────┴────┴────┴────┴────┴────┴────┴────┴────┴───────┤
+///   ctx   addr   clk   u0   u1   u2   u3   v0   v1   v2   v3   d0   d1   d2   d_inv
+/// ├─────┴──────┴─────┴────┴────┴────┴────┴────┴────┴────┴────┴────┴────┴────┴───────┤
 ///
 /// In the above, the meaning of the columns is as follows:
 /// - `ctx` contains context ID. Currently, context ID is always set to ZERO.
 /// - `addr` contains memory address. Values in this column must increase monotonically for a
-///   given context but there can be gaps between two consecutive values of up to 2^32. Also,
+///   given context but there can be gaps between two consecutive values of up to 2^48. Also,
 ///   two consecutive values can be the same.
 /// - `clk` contains clock cycle at which a memory operation happened. Values in this column must
 ///   increase monotonically for a given context and memory address but there can be gaps between
@@ -52,8 +74,12 @@ const INIT_MEM_VALUE: Word = [ZERO; 4];
 /// - Columns `v0`, `v1`, `v2`, `v3` contain field elements stored at a given context/address/clock
 ///   cycle after the memory operation. Notice that for a READ operation `u0` = `v0`, `u1` = `v1`
 ///   etc.
-/// - Columns `d0` and `d1` contain lower and upper 16 bits of the delta between two consecutive
-///   context IDs, addresses, or clock cycles. Specifically:
+/// - Columns `d0`, `d1`, and `d2` contain 16-bit limbs of the delta between two consecutive
+///   context IDs, addresses, or clock cycles. The delta is split into a lower 32-bit limb, which is
+///   further split into `d0` (lower 16 bits) and `d1` (upper 16 bits), and an upper limb, which
+///   is placed into `d2` and must fit into 16 bits. Each limb is range checked, and since a delta
+///   cannot exceed 2^48, a wrap-around in the field cannot be passed off as a valid delta.
+///   Specifically:
 ///   - When the context changes, these columns contain (`new_ctx` - `old_ctx`).
 ///   - When the context remains the same but the address changes, these columns contain
 ///     (`new_addr` - `old-addr`).
@@ -62,13 +88,26 @@ const INIT_MEM_VALUE: Word = [ZERO; 4];
 /// - `d_inv` contains the inverse of the delta between two consecutive context IDs, addresses, or
 ///   clock cycles computed as described above.
 ///
-/// For the first row of the trace, values in `d0`, `d1`, and `d_inv` are set to zeros.
+/// For the first row of the trace, values in `d0`, `d1`, `d2`, and `d_inv` are set to zeros.
+///
+/// Because of the limit on the delta, a memory access is rejected if it would introduce a gap of
+/// more than 2^48 - 1 between the accessed address and the nearest previously accessed address.
+///
+/// ## Forking
+/// The access trace is split into pages of 2^[PAGE_BITS] consecutive addresses, and the pages are
+/// shared between clones of the memory: cloning the memory copies only the map of pages, and a
+/// page is copied on the first access to it after the memory was cloned. Thus, snapshots and
+/// speculative executions fork the memory at a cost proportional to the number of accessed
+/// pages rather than to the size of the trace, and a backtracked execution copies only the pages
+/// it accessed.
+#[derive(Clone)]
 pub struct Memory {
     /// Current clock cycle of the VM.
     clk: u64,
 
-    /// Memory access trace sorted first by address and then by clock cycle.
-    trace: BTreeMap<u64, Vec<(Felt, Word)>>,
+    /// Memory access trace sorted first by address and then by clock cycle, split into pages
+    /// keyed by the page index of their addresses; pages are never empty.
+    pages: BTreeMap<u64, Arc<Page>>,
 
     /// Total number of entries in the trace; tracked separately so that we don't have to sum up
     /// length of all vectors in the trace map all the time.
@@ -82,7 +121,7 @@ impl Memory {
     pub fn new() -> Self {
         Self {
             clk: 0,
-            trace: BTreeMap::new(),
+            pages: BTreeMap::new(),
             num_trace_rows: 0,
         }
     }
@@ -98,7 +137,7 @@ impl Memory {
 
     /// Returns the address and clock cycle of the first trace row, or None if the trace is empty.
     fn get_first_row_info(&self) -> Option<(Felt, Felt)> {
-        match self.trace.iter().next() {
+        match self.addr_traces().next() {
             Some((&addr, addr_trace)) => {
                 let clk = addr_trace[0].0;
                 Some((Felt::new(addr), clk))
@@ -112,7 +151,7 @@ impl Memory {
     /// Unlike read() that modifies the underlying map, get_value() only attempts to read
     /// or return None when no value exists.
     pub fn get_value(&self, addr: u64) -> Option<Word> {
-        match self.trace.get(&addr) {
+        match self.addr_trace(addr) {
             Some(addr_trace) => addr_trace.last().map(|(_, value)| *value),
             None => None,
         }
@@ -140,7 +179,12 @@ impl Memory {
         // end of the current cycle.
         let search_step = clk - 1;
 
-        for (&addr, addr_trace) in self.trace.range(range) {
+        let pages = (range.start() >> PAGE_BITS)..=(range.end() >> PAGE_BITS);
+        let addr_traces = self
+            .pages
+            .range(pages)
+            .flat_map(|(_, page)| page.range(range.clone()));
+        for (&addr, addr_trace) in addr_traces {
             match addr_trace.binary_search_by(|(x, _)| x.as_int().cmp(&search_step)) {
                 Ok(i) => data.push((addr, addr_trace[i].1)),
                 Err(i) => {
@@ -157,6 +201,40 @@ impl Memory {
         data
     }
 
+    // ADDRESS VALIDATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that accessing the specified address would not make the memory trace invalid.
+    ///
+    /// Addresses in the trace are sorted, and the delta between two consecutive addresses must be
+    /// at most [MAX_DELTA]. If the address has not been accessed before, the gaps between it and
+    /// its nearest previously accessed neighbors are checked. Since the gaps between previously
+    /// accessed addresses are already valid, inserting an address between them cannot make these
+    /// gaps invalid.
+    ///
+    /// # Errors
+    /// Returns an error if the address is further than [MAX_DELTA] from its nearest neighbors.
+    pub fn validate_addr(&self, addr: Felt) -> Result<(), ExecutionError> {
+        let addr = addr.as_int();
+        if self.addr_trace(addr).is_some() {
+            return Ok(());
+        }
+
+        if let Some(prev_addr) = self.prev_addr(addr) {
+            if addr - prev_addr > MAX_DELTA {
+                return Err(ExecutionError::MemoryAddressOutOfRange(addr));
+            }
+        }
+
+        if let Some(next_addr) = self.next_addr(addr) {
+            if next_addr - addr > MAX_DELTA {
+                return Err(ExecutionError::MemoryAddressOutOfRange(addr));
+            }
+        }
+
+        Ok(())
+    }
+
     // STATE ACCESSORS AND MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -171,7 +249,7 @@ impl Memory {
         // look up the previous value in the appropriate address trace and add (clk, prev_value)
         // to it; if this is the first time we access this address, create address trace for it
         // with entry (clk, [ZERO, 4]). in both cases, return the last value in the address trace.
-        self.trace
+        self.page_mut(addr.as_int())
             .entry(addr.as_int())
             .and_modify(|addr_trace| {
                 let last_value = addr_trace.last().expect("empty address trace").1;
@@ -190,7 +268,7 @@ impl Memory {
 
         // add a tuple (clk, value) to the appropriate address trace; if this is the first time
         // we access this address, initialize address trace.
-        self.trace
+        self.page_mut(addr.as_int())
             .entry(addr.as_int())
             .and_modify(|addr_trace| addr_trace.push((clk, value)))
             .or_insert_with(|| vec![(clk, value)]);
@@ -220,7 +298,7 @@ impl Memory {
 
         let mut row = memory_start_row;
         // op range check index
-        for (&addr, addr_trace) in self.trace.iter() {
+        for (&addr, addr_trace) in self.addr_traces() {
             // when we start a new address, we set the previous value to all zeros. the effect of
             // this is that memory is always initialized to zero.
             for (clk, _) in addr_trace {
@@ -233,8 +311,8 @@ impl Memory {
                     clk - prev_clk - 1
                 };
 
-                let (delta_hi, delta_lo) = split_u32_into_u16(delta);
-                range.add_mem_checks(row, &[delta_lo, delta_hi]);
+                let (delta_hi, delta_mid, delta_lo) = split_u48_into_u16(delta);
+                range.add_mem_checks(row, &[delta_lo, delta_mid, delta_hi]);
 
                 // update values for the next iteration of the loop
                 prev_addr = addr;
@@ -262,14 +340,14 @@ impl Memory {
         };
 
         // iterate through addresses in ascending order, and write trace row for each memory access
-        // into the trace. we expect the trace to be 14 columns wide.
+        // into the trace. we expect the trace to be 15 columns wide.
         let mut i = 0;
-        for (addr, addr_trace) in self.trace {
+        for (&addr, addr_trace) in self.addr_traces() {
             // when we start a new address, we set the previous value to all zeros. the effect of
             // this is that memory is always initialized to zero.
             let addr = Felt::new(addr);
             let mut prev_value = INIT_MEM_VALUE;
-            for (clk, value) in addr_trace {
+            for &(clk, value) in addr_trace {
                 trace.set(i, 0, ZERO); // ctx
                 trace.set(i, 1, addr);
                 trace.set(i, 2, clk);
@@ -289,11 +367,12 @@ impl Memory {
                     clk - prev_clk - ONE
                 };
 
-                let (delta_hi, delta_lo) = split_element_u32_into_u16(delta);
+                let (delta_hi, delta_mid, delta_lo) = split_element_u48_into_u16(delta);
                 trace.set(i, 11, delta_lo);
-                trace.set(i, 12, delta_hi);
+                trace.set(i, 12, delta_mid);
+                trace.set(i, 13, delta_hi);
                 // TODO: switch to batch inversion to improve efficiency.
-                trace.set(i, 13, delta.inv());
+                trace.set(i, 14, delta.inv());
 
                 // provide the memory access data to the chiplets bus.
                 let memory_lookup = MemoryLookup::new(addr, clk.as_int(), prev_value, value);
@@ -308,13 +387,81 @@ impl Memory {
         }
     }
 
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the trace of the specified address, or None if the address hasn't been accessed
+    /// previously.
+    fn addr_trace(&self, addr: u64) -> Option<&AddrTrace> {
+        self.pages
+            .get(&(addr >> PAGE_BITS))
+            .and_then(|page| page.get(&addr))
+    }
+
+    /// Returns the traces of all accessed addresses, sorted by address.
+    fn addr_traces(&self) -> impl Iterator<Item = (&u64, &AddrTrace)> {
+        self.pages.values().flat_map(|page| page.iter())
+    }
+
+    /// Returns the page holding the specified address for modification; the page is created if
+    /// no address in it has been accessed previously, and copied if it is shared with a clone of
+    /// this memory.
+    fn page_mut(&mut self, addr: u64) -> &mut Page {
+        Arc::make_mut(self.pages.entry(addr >> PAGE_BITS).or_default())
+    }
+
+    /// Returns the largest accessed address which is smaller than the specified address.
+    fn prev_addr(&self, addr: u64) -> Option<u64> {
+        let page_idx = addr >> PAGE_BITS;
+        let in_page = self
+            .pages
+            .get(&page_idx)
+            .and_then(|page| page.range(..addr).next_back());
+        match in_page {
+            Some((&prev_addr, _)) => Some(prev_addr),
+            None => self
+                .pages
+                .range(..page_idx)
+                .next_back()
+                .and_then(|(_, page)| page.keys().next_back().copied()),
+        }
+    }
+
+    /// Returns the smallest accessed address which is greater than the specified address, which
+    /// hasn't been accessed previously.
+    fn next_addr(&self, addr: u64) -> Option<u64> {
+        let page_idx = addr >> PAGE_BITS;
+        let in_page = self
+            .pages
+            .get(&page_idx)
+            .and_then(|page| page.range(addr..).next());
+        match in_page {
+            Some((&next_addr, _)) => Some(next_addr),
+            None => self
+                .pages
+                .range(page_idx + 1..)
+                .next()
+                .and_then(|(_, page)| page.keys().next().copied()),
+        }
+    }
+
     // TEST HELPERS
     // --------------------------------------------------------------------------------------------
 
     /// Returns current size of the memory (in words).
     #[cfg(test)]
     pub fn size(&self) -> usize {
-        self.trace.len()
+        self.pages.values().map(|page| page.len()).sum()
+    }
+
+    /// Returns true if the page holding the specified address is shared with the other memory.
+    #[cfg(test)]
+    pub fn shares_page_with(&self, other: &Self, addr: u64) -> bool {
+        let page_idx = addr >> PAGE_BITS;
+        match (self.pages.get(&page_idx), other.pages.get(&page_idx)) {
+            (Some(page), Some(other_page)) => Arc::ptr_eq(page, other_page),
+            _ => false,
+        }
     }
 }
 
@@ -353,27 +500,35 @@ impl LookupTableRow for MemoryLookup {
     /// Reduces this row to a single field element in the field specified by E. This requires
     /// at least 13 alpha values.
     fn to_value<E: FieldElement<BaseField = Felt>>(&self, alphas: &[E]) -> E {
-        let old_word_value = self
-            .old_word
-            .iter()
-            .enumerate()
-            .fold(E::ZERO, |acc, (j, element)| {
-                acc + alphas[j + 5].mul_base(*element)
-            });
-        let new_word_value = self
-            .new_word
-            .iter()
-            .enumerate()
-            .fold(E::ZERO, |acc, (j, element)| {
-                acc + alphas[j + 9].mul_base(*element)
-            });
-
-        alphas[0]
-            + alphas[1].mul_base(MEMORY_LABEL)
-            + alphas[2].mul_base(self.ctx)
-            + alphas[3].mul_base(self.addr)
-            + alphas[4].mul_base(Felt::new(self.clk))
-            + old_word_value
-            + new_word_value
+        reduce_memory_row(
+            alphas,
+            self.ctx,
+            self.addr,
+            Felt::new(self.clk),
+            &self.old_word,
+            &self.new_word,
+        )
+    }
+}
+
+impl Serializable for MemoryLookup {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write(self.ctx);
+        target.write(self.addr);
+        target.write_u64(self.clk);
+        target.write(&self.old_word[..]);
+        target.write(&self.new_word[..]);
+    }
+}
+
+impl Deserializable for MemoryLookup {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(Self {
+            ctx: Felt::read_from(source)?,
+            addr: Felt::read_from(source)?,
+            clk: source.read_u64()?,
+            old_word: read_elements(source)?,
+            new_word: read_elements(source)?,
+        })
     }
 }
@@ -1,6 +1,7 @@
 use super::{
     super::bus::{ChipletsLookup, ChipletsLookupRow},
-    ChipletsBus, Felt, FieldElement, Memory, MemoryLookup, StarkField, TraceFragment, ONE, ZERO,
+    ChipletsBus, ExecutionError, Felt, FieldElement, Memory, MemoryLookup, StarkField,
+    TraceFragment, MAX_DELTA, ONE, ZERO,
 };
 use vm_core::MEMORY_TRACE_WIDTH;
 
@@ -244,6 +245,151 @@ fn mem_get_values_at() {
     assert_eq!(vec![(2_u64, value4)], mem.get_values_at(0..=4, 4));
 }
 
+#[test]
+fn mem_large_address_gap() {
+    let mut mem = Memory::new();
+
+    // write a value into address 0; clk = 1
+    mem.advance_clock();
+    let addr0 = Felt::new(0);
+    let value1 = [ONE, ZERO, ZERO, ZERO];
+    mem.write(addr0, value1);
+
+    // write a value into an address which is more than 2^32 away; clk = 2
+    mem.advance_clock();
+    let addr_far = Felt::new(1 << 40);
+    let value2 = [ZERO, ONE, ZERO, ZERO];
+    mem.write(addr_far, value2);
+
+    // check generated trace; the address delta occupies all three delta limbs
+    let (trace, chiplets_bus) = build_trace(mem, 2);
+
+    let mut prev_row = [ZERO; MEMORY_TRACE_WIDTH];
+    let memory_access = MemoryLookup::new(addr0, 1, [ZERO; 4], value1);
+    prev_row = verify_memory_access(&trace, &chiplets_bus, 0, &memory_access, prev_row);
+
+    let memory_access = MemoryLookup::new(addr_far, 2, [ZERO; 4], value2);
+    let row = verify_memory_access(&trace, &chiplets_bus, 1, &memory_access, prev_row);
+    assert_eq!([ZERO, ZERO, Felt::new(1 << 8)], row[11..14]);
+}
+
+#[test]
+fn mem_validate_addr() {
+    let mut mem = Memory::new();
+
+    // any address is valid when memory is empty
+    let addr = Felt::new(MAX_DELTA + 1);
+    assert!(mem.validate_addr(addr).is_ok());
+    mem.write(addr, [ONE; 4]);
+
+    // addresses which are at most MAX_DELTA away from the accessed address are valid
+    assert!(mem.validate_addr(addr).is_ok());
+    assert!(mem.validate_addr(Felt::new(1)).is_ok());
+    assert!(mem.validate_addr(Felt::new(2 * MAX_DELTA + 1)).is_ok());
+
+    // addresses which are further away are not
+    let result = mem.validate_addr(ZERO);
+    assert!(matches!(
+        result,
+        Err(ExecutionError::MemoryAddressOutOfRange(0))
+    ));
+    let result = mem.validate_addr(Felt::new(2 * MAX_DELTA + 2));
+    assert!(matches!(
+        result,
+        Err(ExecutionError::MemoryAddressOutOfRange(a)) if a == 2 * MAX_DELTA + 2
+    ));
+
+    // an address between two accessed addresses is valid as long as the gap is valid
+    mem.write(Felt::new(2 * MAX_DELTA + 1), [ONE; 4]);
+    assert!(mem.validate_addr(Felt::new(MAX_DELTA + 2)).is_ok());
+}
+
+#[test]
+fn mem_fork() {
+    let mut mem = Memory::new();
+
+    // write values into two addresses in different pages; clk = 1, 2
+    let addr0 = Felt::new(0);
+    let addr_far = Felt::new(1 << 20);
+    let value1 = [ONE, ZERO, ZERO, ZERO];
+    mem.advance_clock();
+    mem.write(addr0, value1);
+    mem.advance_clock();
+    mem.write(addr_far, value1);
+
+    // a fork shares all pages with the original memory until they are accessed
+    let mut fork = mem.clone();
+    assert!(fork.shares_page_with(&mem, addr0.as_int()));
+    assert!(fork.shares_page_with(&mem, addr_far.as_int()));
+
+    // writing into the fork copies only the page of the written address; clk = 3
+    let value2 = [ZERO, ONE, ZERO, ZERO];
+    fork.advance_clock();
+    fork.write(addr0, value2);
+    assert!(!fork.shares_page_with(&mem, addr0.as_int()));
+    assert!(fork.shares_page_with(&mem, addr_far.as_int()));
+
+    // the original memory is not affected by the accesses of the fork
+    assert_eq!(value2, fork.get_value(addr0.as_int()).unwrap());
+    assert_eq!(value1, mem.get_value(addr0.as_int()).unwrap());
+    assert_eq!(3, fork.trace_len());
+    assert_eq!(2, mem.trace_len());
+
+    // reading from the fork copies the page as well, since the read is recorded in the trace;
+    // clk = 4
+    fork.advance_clock();
+    assert_eq!(value1, fork.read(addr_far));
+    assert!(!fork.shares_page_with(&mem, addr_far.as_int()));
+    assert_eq!(2, mem.trace_len());
+
+    // addresses in pages which were created by the fork are valid if their gaps are valid
+    let addr_mid = Felt::new(1 << 10);
+    assert!(fork.validate_addr(addr_mid).is_ok());
+    fork.write(addr_mid, value2);
+    assert_eq!(3, fork.size());
+    assert_eq!(2, mem.size());
+
+    // the trace of the fork is sorted by address across pages
+    let (trace, chiplets_bus) = build_trace(fork, 5);
+    let mut prev_row = [ZERO; MEMORY_TRACE_WIDTH];
+    let memory_access = MemoryLookup::new(addr0, 1, [ZERO; 4], value1);
+    prev_row = verify_memory_access(&trace, &chiplets_bus, 0, &memory_access, prev_row);
+    let memory_access = MemoryLookup::new(addr0, 3, value1, value2);
+    prev_row = verify_memory_access(&trace, &chiplets_bus, 1, &memory_access, prev_row);
+    let memory_access = MemoryLookup::new(addr_mid, 4, [ZERO; 4], value2);
+    prev_row = verify_memory_access(&trace, &chiplets_bus, 2, &memory_access, prev_row);
+    let memory_access = MemoryLookup::new(addr_far, 2, [ZERO; 4], value1);
+    prev_row = verify_memory_access(&trace, &chiplets_bus, 3, &memory_access, prev_row);
+    let memory_access = MemoryLookup::new(addr_far, 4, value1, value1);
+    verify_memory_access(&trace, &chiplets_bus, 4, &memory_access, prev_row);
+}
+
+#[test]
+fn mem_validate_addr_across_pages() {
+    let mut mem = Memory::new();
+
+    // the nearest accessed addresses of a new address are found in other pages
+    let addr = 1 << 50;
+    mem.write(Felt::new(addr), [ONE; 4]);
+    assert!(mem.validate_addr(Felt::new(addr - MAX_DELTA)).is_ok());
+    assert!(mem.validate_addr(Felt::new(addr + MAX_DELTA)).is_ok());
+    assert!(matches!(
+        mem.validate_addr(Felt::new(addr - MAX_DELTA - 1)),
+        Err(ExecutionError::MemoryAddressOutOfRange(a)) if a == addr - MAX_DELTA - 1
+    ));
+    assert!(matches!(
+        mem.validate_addr(Felt::new(addr + MAX_DELTA + 1)),
+        Err(ExecutionError::MemoryAddressOutOfRange(a)) if a == addr + MAX_DELTA + 1
+    ));
+
+    // an address in a new page between two accessed addresses is valid as long as the gaps are
+    // valid
+    let prev_addr = addr - MAX_DELTA;
+    mem.write(Felt::new(prev_addr), [ONE; 4]);
+    assert!(mem.validate_addr(Felt::new(prev_addr + (1 << 40))).is_ok());
+    assert!(mem.validate_addr(Felt::new(prev_addr - MAX_DELTA)).is_ok());
+}
+
 // HELPER STRUCT & FUNCTIONS
 // ================================================================================================
 
@@ -301,10 +447,11 @@ fn build_trace_row(
             row[2] - prev_row[2] - ONE
         };
 
-        let (hi, lo) = super::split_element_u32_into_u16(delta);
+        let (hi, mid, lo) = super::split_element_u48_into_u16(delta);
         row[11] = lo;
-        row[12] = hi;
-        row[13] = delta.inv();
+        row[12] = mid;
+        row[13] = hi;
+        row[14] = delta.inv();
     }
 
     row
@@ -2,12 +2,23 @@ use super::{
     BTreeMap, ChipletsTrace, Felt, FieldElement, RangeChecker, StarkField, TraceFragment, Vec,
     Word, CHIPLETS_WIDTH, ONE, ZERO,
 };
-use crate::{trace::LookupTableRow, ExecutionError};
+use crate::{
+    trace::{fragment_checksum, LookupTableRow},
+    ExecutionError, ExecutionEstimate,
+};
 use core::ops::RangeInclusive;
 use vm_core::{
-    chiplets::bitwise::{BITWISE_AND_LABEL, BITWISE_OR_LABEL, BITWISE_XOR_LABEL},
-    chiplets::hasher::{Digest, HasherState},
+    chiplets::bitwise::{
+        BITWISE_AND_LABEL, BITWISE_OR_LABEL, BITWISE_U64_AND_LABEL, BITWISE_U64_OR_LABEL,
+        BITWISE_U64_XOR_LABEL, BITWISE_XOR_LABEL, TRACE_WIDTH as BITWISE_TRACE_WIDTH,
+    },
+    chiplets::hasher::{Digest, HasherState, TRACE_WIDTH as HASHER_TRACE_WIDTH},
+    chiplets::{
+        BITWISE_SEGMENT_SELECTORS, HASHER_SEGMENT_SELECTORS, MEMORY_SEGMENT_SELECTORS,
+        PADDING_SEGMENT_SELECTORS, PADDING_TRACE_WIDTH,
+    },
     code_blocks::OpBatch,
+    MEMORY_TRACE_WIDTH,
 };
 
 mod bitwise;
@@ -20,12 +31,21 @@ pub use hasher::{AuxTraceBuilder as HasherAuxTraceBuilder, SiblingTableRow};
 mod memory;
 use memory::{Memory, MemoryLookup};
 
+mod padding;
+use padding::Padding;
+
 mod bus;
 pub use bus::{AuxTraceBuilder, ChipletsBus};
 
 #[cfg(test)]
 mod tests;
 
+// CONSTANTS
+// ================================================================================================
+
+/// The number of segments stacked in the chiplets trace, including the padding segment.
+const NUM_SEGMENTS: usize = 4;
+
 // CHIPLETS MODULE OF HASHER, BITWISE, AND MEMORY CHIPLETS
 // ================================================================================================
 
@@ -43,25 +63,28 @@ mod tests;
 /// the `trace_len` of the bitwise chiplet.
 /// - column 0: selector column with values set to ONE
 /// - column 1: selector column with values set to ZERO
-/// - columns 2-15: execution trace of bitwise chiplet
-/// - column 16-17: unused columns padded with ZERO
+/// - columns 2-16: execution trace of bitwise chiplet
+/// - column 17: unused column padded with ZERO
 ///
 /// * Memory segment: contains the trace and selectors for the memory chiplet *
 /// This segment begins at the end of the bitwise segment and fills the next rows of the trace for
 /// the `trace_len` of the memory chiplet.
 /// - column 0-1: selector columns with values set to ONE
 /// - column 2: selector column with values set to ZERO
-/// - columns 3-16: execution trace of memory chiplet
-/// - column 17: unused column padded with ZERO
+/// - columns 3-17: execution trace of memory chiplet
 ///
-/// * Padding segment: unused *
+/// * Padding segment: contains the padding selectors and no chiplet trace *
 /// This segment begins at the end of the memory segment and fills the rest of the execution trace
 /// minus the number of random rows. When it finishes, the execution trace should have exactly
 /// enough rows remaining for the specified number of random rows.
 /// - columns 0-2: selector columns with values set to ONE
-/// - columns 3-17: unused columns padded with ZERO
+/// - columns 3-17: unused columns set to ZERO
+///
+/// Each segment is described by its length, its selector prefix, and the width of its chiplet
+/// trace, and the trace is built from these descriptions. Thus, adding a chiplet requires only
+/// adding its segment before the padding segment without changing how padding is handled.
 ///
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Chiplets {
     /// Current clock cycle of the VM.
     clk: usize,
@@ -82,11 +105,41 @@ impl Chiplets {
         self.hasher.trace_len() + self.bitwise.trace_len() + self.memory.trace_len() + 1
     }
 
+    /// Returns the number of rows in the execution trace of the Hash chiplet.
+    pub fn hasher_trace_len(&self) -> usize {
+        self.hasher.trace_len()
+    }
+
+    /// Returns the number of rows in the execution trace of the Bitwise chiplet.
+    pub fn bitwise_trace_len(&self) -> usize {
+        self.bitwise.trace_len()
+    }
+
+    /// Returns the number of rows in the execution trace of the Memory chiplet.
+    pub fn memory_trace_len(&self) -> usize {
+        self.memory.trace_len()
+    }
+
     /// Returns the index of the first row of the [Memory] execution trace.
     pub fn memory_start(&self) -> usize {
         self.hasher.trace_len() + self.bitwise.trace_len()
     }
 
+    // CAPACITY
+    // --------------------------------------------------------------------------------------------
+
+    /// Reserves capacity in the execution traces of the Hash and Bitwise chiplets for the
+    /// operations in the provided estimate, so that the traces are not repeatedly reallocated
+    /// during execution.
+    ///
+    /// The Memory chiplet is not pre-allocated, since its trace is built from the memory state
+    /// only when the execution completes.
+    pub fn reserve(&mut self, estimate: &ExecutionEstimate) {
+        self.hasher
+            .reserve(estimate.num_permutations + estimate.num_merkle_legs);
+        self.bitwise.reserve(estimate.bitwise_trace_len());
+    }
+
     // HASH CHIPLET ACCESSORS FOR OPERATIONS
     // --------------------------------------------------------------------------------------------
 
@@ -253,6 +306,45 @@ impl Chiplets {
         Ok(result)
     }
 
+    /// Requests a bitwise AND of 64-bit values `a` and `b` specified by their 32-bit limbs (hi, lo)
+    /// from the Bitwise chiplet and returns the limbs of the result.
+    ///
+    /// The operation is requested via a single lookup covering both limbs of the result.
+    pub fn u64and(&mut self, a: [Felt; 2], b: [Felt; 2]) -> Result<[Felt; 2], ExecutionError> {
+        let result = self.bitwise.u64and(a, b)?;
+
+        let bitwise_lookup = BitwiseLookup::new_u64(BITWISE_U64_AND_LABEL, a, b, result);
+        self.bus.request_bitwise_operation(bitwise_lookup, self.clk);
+
+        Ok(result)
+    }
+
+    /// Requests a bitwise OR of 64-bit values `a` and `b` specified by their 32-bit limbs (hi, lo)
+    /// from the Bitwise chiplet and returns the limbs of the result.
+    ///
+    /// The operation is requested via a single lookup covering both limbs of the result.
+    pub fn u64or(&mut self, a: [Felt; 2], b: [Felt; 2]) -> Result<[Felt; 2], ExecutionError> {
+        let result = self.bitwise.u64or(a, b)?;
+
+        let bitwise_lookup = BitwiseLookup::new_u64(BITWISE_U64_OR_LABEL, a, b, result);
+        self.bus.request_bitwise_operation(bitwise_lookup, self.clk);
+
+        Ok(result)
+    }
+
+    /// Requests a bitwise XOR of 64-bit values `a` and `b` specified by their 32-bit limbs (hi, lo)
+    /// from the Bitwise chiplet and returns the limbs of the result.
+    ///
+    /// The operation is requested via a single lookup covering both limbs of the result.
+    pub fn u64xor(&mut self, a: [Felt; 2], b: [Felt; 2]) -> Result<[Felt; 2], ExecutionError> {
+        let result = self.bitwise.u64xor(a, b)?;
+
+        let bitwise_lookup = BitwiseLookup::new_u64(BITWISE_U64_XOR_LABEL, a, b, result);
+        self.bus.request_bitwise_operation(bitwise_lookup, self.clk);
+
+        Ok(result)
+    }
+
     // MEMORY CHIPLET ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -260,7 +352,12 @@ impl Chiplets {
     ///
     /// If the specified address hasn't been previously written to, four ZERO elements are
     /// returned. This effectively implies that memory is initialized to ZERO.
-    pub fn read_mem(&mut self, addr: Felt) -> Word {
+    ///
+    /// # Errors
+    /// Returns an error if the address is too far away from all previously accessed addresses.
+    pub fn read_mem(&mut self, addr: Felt) -> Result<Word, ExecutionError> {
+        self.memory.validate_addr(addr)?;
+
         // read the word from memory
         let value = self.memory.read(addr);
 
@@ -268,12 +365,17 @@ impl Chiplets {
         let memory_lookup = MemoryLookup::new(addr, self.clk as u64, value, value);
         self.bus.request_memory_operation(memory_lookup, self.clk);
 
-        value
+        Ok(value)
     }
 
     /// Writes the provided element to memory at the specified address leaving the remaining 3
     /// elements of the word previously stored at that address unchanged.
-    pub fn write_mem_single(&mut self, addr: Felt, value: Felt) -> Word {
+    ///
+    /// # Errors
+    /// Returns an error if the address is too far away from all previously accessed addresses.
+    pub fn write_mem_single(&mut self, addr: Felt, value: Felt) -> Result<Word, ExecutionError> {
+        self.memory.validate_addr(addr)?;
+
         let old_word = self.memory.get_old_value(addr);
         let word = [value, old_word[1], old_word[2], old_word[3]];
 
@@ -283,11 +385,16 @@ impl Chiplets {
         let memory_lookup = MemoryLookup::new(addr, self.clk as u64, old_word, word);
         self.bus.request_memory_operation(memory_lookup, self.clk);
 
-        old_word
+        Ok(old_word)
     }
 
     /// Writes the provided word (4 elements) to memory at the specified address.
-    pub fn write_mem(&mut self, addr: Felt, word: Word) -> Word {
+    ///
+    /// # Errors
+    /// Returns an error if the address is too far away from all previously accessed addresses.
+    pub fn write_mem(&mut self, addr: Felt, word: Word) -> Result<Word, ExecutionError> {
+        self.memory.validate_addr(addr)?;
+
         let old_word = self.memory.get_old_value(addr);
         self.memory.write(addr, word);
 
@@ -295,7 +402,60 @@ impl Chiplets {
         let memory_lookup = MemoryLookup::new(addr, self.clk as u64, old_word, word);
         self.bus.request_memory_operation(memory_lookup, self.clk);
 
-        old_word
+        Ok(old_word)
+    }
+
+    // MEMORY HASHING
+    // --------------------------------------------------------------------------------------------
+
+    /// Requests a sequential hash of `len` consecutive words of memory starting at the specified
+    /// address in the specified context.
+    ///
+    /// The words are read from the Memory chiplet and absorbed into the Hash chiplet two words at
+    /// a time. The memory reads and the lookups of the hash computation are sent to the Chiplets
+    /// Bus as a single request at the current cycle, so that the traces of both chiplets are
+    /// consistent with the request.
+    ///
+    /// The returned tuple contains the row address of the execution trace at which the hash
+    /// computation started and the hash of all elements of the words.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The context is not the root context, which is the only context currently supported by
+    ///   the Memory chiplet.
+    /// - The region is empty or extends beyond the largest valid address.
+    /// - The first address of the region is too far away from all previously accessed addresses.
+    pub fn hash_memory(
+        &mut self,
+        ctx: u32,
+        addr: Felt,
+        len: usize,
+    ) -> Result<(Felt, Word), ExecutionError> {
+        if ctx != 0 {
+            return Err(ExecutionError::InvalidMemoryContext(ctx));
+        }
+        let start = addr.as_int();
+        let end = start.checked_add(len as u64);
+        if len == 0 || end.map_or(true, |end| end > Felt::MODULUS) {
+            return Err(ExecutionError::InvalidMemoryRange(start, len));
+        }
+        // the remaining addresses of the region follow the first one without gaps, and thus,
+        // only the first address needs to be validated
+        self.memory.validate_addr(addr)?;
+
+        let mut words = Vec::with_capacity(len);
+        let mut memory_lookups = Vec::with_capacity(len);
+        for word_addr in (start..start + len as u64).map(Felt::new) {
+            let value = self.memory.read(word_addr);
+            memory_lookups.push(MemoryLookup::new(word_addr, self.clk as u64, value, value));
+            words.push(value);
+        }
+
+        let (hasher_addr, digest, hasher_lookups) = self.hasher.hash_memory(&words);
+        self.bus
+            .request_memory_hash(&memory_lookups, hasher_lookups, self.clk);
+
+        Ok((hasher_addr, digest))
     }
 
     /// Returns a word located at the specified address, or None if the address hasn't been
@@ -392,82 +552,111 @@ impl Chiplets {
             mut bus,
         } = self;
 
-        // allocate fragments to be filled with the respective execution traces of each chiplet
-        let mut hasher_fragment = TraceFragment::new(CHIPLETS_WIDTH);
-        let mut bitwise_fragment = TraceFragment::new(CHIPLETS_WIDTH);
-        let mut memory_fragment = TraceFragment::new(CHIPLETS_WIDTH);
+        // the padding segment fills all rows after the memory chiplet
+        let padding = Padding::new(trace_len - memory_start - memory.trace_len());
+
+        // describe the segments of the trace in the order in which they are stacked. each segment
+        // starts with its selector prefix, and its chiplet trace fills the columns which follow the
+        // selectors; any columns of the segment not covered by the chiplet trace are ZERO.
+        let segments: [(usize, &[Felt], usize); NUM_SEGMENTS] = [
+            (
+                hasher.trace_len(),
+                &HASHER_SEGMENT_SELECTORS,
+                HASHER_TRACE_WIDTH,
+            ),
+            (
+                bitwise.trace_len(),
+                &BITWISE_SEGMENT_SELECTORS,
+                BITWISE_TRACE_WIDTH,
+            ),
+            (
+                memory.trace_len(),
+                &MEMORY_SEGMENT_SELECTORS,
+                MEMORY_TRACE_WIDTH,
+            ),
+            (
+                padding.trace_len(),
+                &PADDING_SEGMENT_SELECTORS,
+                PADDING_TRACE_WIDTH,
+            ),
+        ];
+        debug_assert_eq!(
+            trace_len,
+            segments.iter().map(|(len, _, _)| len).sum::<usize>(),
+            "inconsistent segment lengths"
+        );
+
+        // allocate fragments to be filled with the respective execution traces of each segment
+        let mut fragments = [(); NUM_SEGMENTS].map(|_| TraceFragment::new(CHIPLETS_WIDTH));
 
-        // set the selectors and padding as required by each column and segment
-        // and add the hasher, bitwise, and memory segments to their respective fragments
-        // so they can be filled with the chiplet traces
+        // set the selectors of each segment, and add the parts of each column covered by a chiplet
+        // trace to the fragment of that chiplet so that they can be filled with the chiplet traces
         for (column_num, column) in trace.iter_mut().enumerate() {
-            match column_num {
-                0 => {
-                    // set the selector value for the hasher segment to ZERO
-                    column.resize(hasher.trace_len(), Felt::ZERO);
-                    // set the selector value for all other segments ONE
-                    column.resize(trace_len, Felt::ONE);
-                }
-                1 => {
-                    // initialize hasher segment and set bitwise segment selector value to ZERO
-                    column.resize(hasher.trace_len() + bitwise.trace_len(), Felt::ZERO);
-                    // set selector value for all other segments to ONE
-                    column.resize(trace_len, Felt::ONE);
-                    // add hasher segment to the hasher fragment to be filled from the hasher trace
-                    hasher_fragment.push_column_slice(column, hasher.trace_len());
-                }
-                2 => {
-                    // initialize hasher and bitwise segments and set memory segment selector to ZERO
-                    column.resize(
-                        hasher.trace_len() + bitwise.trace_len() + memory.trace_len(),
-                        Felt::ZERO,
-                    );
-                    // set selector value for the final segment to ONE
-                    column.resize(trace_len, Felt::ONE);
-                    // add hasher segment to the hasher fragment to be filled from the hasher trace
-                    let rest_of_column =
-                        hasher_fragment.push_column_slice(column, hasher.trace_len());
-                    // add bitwise segment to the bitwise fragment to be filled from the bitwise trace
-                    bitwise_fragment.push_column_slice(rest_of_column, bitwise.trace_len());
-                }
-                16 => {
-                    // initialize hasher & memory segments and bitwise, padding segments with ZERO
-                    column.resize(trace_len, Felt::ZERO);
-                    // add hasher segment to the hasher fragment to be filled from the hasher trace
-                    let rest_of_column =
-                        hasher_fragment.push_column_slice(column, hasher.trace_len());
-                    // split the column to skip bitwise which have already been padded.
-                    let (_, rest_of_column) = rest_of_column.split_at_mut(bitwise.trace_len());
-                    // add memory segment to the memory fragment to be filled from the memory trace
-                    memory_fragment.push_column_slice(rest_of_column, memory.trace_len());
-                }
-                17 => {
-                    // initialize hasher segment and pad bitwise, memory, padding segments with ZERO
-                    column.resize(trace_len, Felt::ZERO);
-                    // add hasher segment to the hasher fragment to be filled from the hasher trace
-                    hasher_fragment.push_column_slice(column, hasher.trace_len());
-                }
-                _ => {
-                    // initialize hasher, bitwise, memory segments and pad the rest with ZERO
-                    column.resize(trace_len, Felt::ZERO);
-                    // add hasher segment to the hasher fragment to be filled from the hasher trace
-                    let rest_of_column =
-                        hasher_fragment.push_column_slice(column, hasher.trace_len());
-                    // add bitwise segment to the bitwise fragment to be filled from the bitwise trace
-                    let rest_of_column =
-                        bitwise_fragment.push_column_slice(rest_of_column, bitwise.trace_len());
-                    // add memory segment to the memory fragment to be filled from the memory trace
-                    memory_fragment.push_column_slice(rest_of_column, memory.trace_len());
+            column.resize(trace_len, ZERO);
+            let mut rest_of_column = column.as_mut_slice();
+            for ((len, selectors, width), fragment) in segments.iter().zip(fragments.iter_mut()) {
+                if column_num < selectors.len() {
+                    let (segment, rest) = rest_of_column.split_at_mut(*len);
+                    segment.fill(selectors[column_num]);
+                    rest_of_column = rest;
+                } else if column_num < selectors.len() + width {
+                    rest_of_column = fragment.push_column_slice(rest_of_column, *len);
+                } else {
+                    // the column is not used by this segment and has already been padded with ZERO
+                    rest_of_column = &mut rest_of_column[*len..];
                 }
             }
         }
 
+        let [mut hasher_fragment, mut bitwise_fragment, mut memory_fragment, mut padding_fragment] =
+            fragments;
+
         // fill the fragments with the execution trace from each chiplet
         // TODO: this can be parallelized to fill the traces in multiple threads
         let hasher_aux_builder = hasher.fill_trace(&mut hasher_fragment, &mut bus);
         bitwise.fill_trace(&mut bitwise_fragment, &mut bus, bitwise_start);
         memory.fill_trace(&mut memory_fragment, &mut bus, memory_start);
+        padding.fill_trace(&mut padding_fragment);
+
+        // checksum the fragments as filled, and check them against the segments of the assembled
+        // trace, so that a chiplet which wrote outside of its fragment, or a fragment which was
+        // taken from the wrong rows or columns, fails here rather than as a constraint failure
+        let checksums = [
+            hasher_fragment.checksum(),
+            bitwise_fragment.checksum(),
+            memory_fragment.checksum(),
+            padding_fragment.checksum(),
+        ];
+        check_segment_checksums(trace, &segments, &checksums);
 
         (hasher_aux_builder, bus.into_aux_builder())
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Checks that the parts of the provided chiplets trace covered by the chiplet trace of every
+/// segment have the checksums of the fragments the segments were filled through.
+///
+/// # Panics
+/// Panics if the checksum of any segment does not match the checksum of its fragment.
+fn check_segment_checksums(
+    trace: &[Vec<Felt>; CHIPLETS_WIDTH],
+    segments: &[(usize, &[Felt], usize); NUM_SEGMENTS],
+    checksums: &[Felt; NUM_SEGMENTS],
+) {
+    let names = ["hasher", "bitwise", "memory", "padding"];
+    let mut start = 0;
+    for (((len, selectors, width), checksum), name) in segments.iter().zip(checksums).zip(names) {
+        let columns = &trace[selectors.len()..selectors.len() + width];
+        let segment_checksum =
+            fragment_checksum(columns.iter().map(|column| &column[start..start + len]));
+        assert_eq!(
+            *checksum, segment_checksum,
+            "{} segment of the chiplets trace does not match its fragment",
+            name
+        );
+        start += len;
+    }
+}
@@ -0,0 +1,57 @@
+use super::{TraceFragment, ZERO};
+use vm_core::chiplets::PADDING_TRACE_WIDTH;
+
+// PADDING SEGMENT
+// ================================================================================================
+
+/// Padding segment of the chiplets trace.
+///
+/// The padding segment follows the last chiplet and fills the remainder of the chiplets trace.
+/// Its rows are identified by the padding selector prefix, and all other columns are set to ZERO.
+/// Since none of the chiplet flags are set in these rows, the padding segment trivially satisfies
+/// all chiplet constraints, and the only constraints which apply to it are the ones requiring its
+/// non-selector columns to be ZERO.
+///
+/// Padding rows are also what makes adding chiplets easy: a new chiplet only needs to be stacked
+/// before the padding segment with its own selector prefix, and the padding rules stay the same.
+#[derive(Debug)]
+pub struct Padding {
+    trace_len: usize,
+}
+
+impl Padding {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new padding segment with the specified number of rows.
+    pub fn new(trace_len: usize) -> Self {
+        Self { trace_len }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of rows in the padding segment.
+    pub fn trace_len(&self) -> usize {
+        self.trace_len
+    }
+
+    // EXECUTION TRACE GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Fills the provided trace fragment with the padding rows. The fragment is expected to cover
+    /// all non-selector columns of the padding segment.
+    pub fn fill_trace(self, trace: &mut TraceFragment) {
+        // make sure fragment dimensions are consistent with the dimensions of this trace
+        debug_assert_eq!(self.trace_len(), trace.len(), "inconsistent trace lengths");
+        debug_assert_eq!(
+            PADDING_TRACE_WIDTH,
+            trace.width(),
+            "inconsistent trace widths"
+        );
+
+        for column in trace.columns() {
+            column.fill(ZERO);
+        }
+    }
+}
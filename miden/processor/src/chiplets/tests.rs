@@ -157,8 +157,10 @@ fn validate_bitwise_trace(chiplets: &ChipletsTrace, start: usize, end: usize) {
         // the expected start of the bitwise trace should hold the expected bitwise op selectors
         assert_eq!(BITWISE_OR, [chiplets[2][row], chiplets[3][row]]);
 
-        // the final columns should be padded
+        // the high limb flag should not be set for operations on 32-bit values
         assert_eq!(Felt::ZERO, chiplets[16][row]);
+
+        // the final column should be padded
         assert_eq!(Felt::ZERO, chiplets[17][row]);
     }
 }
@@ -177,8 +179,12 @@ fn validate_memory_trace(chiplets: &ChipletsTrace, start: usize, end: usize, add
         assert_eq!(Felt::ZERO, chiplets[3][row]);
         assert_eq!(Felt::new(addr), chiplets[4][row]);
 
-        // the final column should be padded
-        assert_eq!(Felt::ZERO, chiplets[17][row]);
+        // the delta limbs and the delta inverse should be zero in the first row of the memory trace
+        if row == start {
+            chiplets[14..].iter().for_each(|column| {
+                assert_eq!(Felt::ZERO, column[row]);
+            });
+        }
     }
 }
 
@@ -0,0 +1,275 @@
+use super::{BTreeMap, BlockKind, CodeBlock, Digest, ExecutionError, Operation, Process, Program};
+use super::{ProgramInputs, StarkField, Vec, Word};
+use core::fmt::{self, Write};
+use vm_core::utils::{collections::BTreeSet, string::String};
+
+#[cfg(test)]
+mod tests;
+
+// COVERAGE REPORT
+// ================================================================================================
+
+/// Report on the code blocks and operations of a program executed against a set of inputs.
+///
+/// Every block which can be reached in the code block tree of the program is reported once, in
+/// the order in which the tree is traversed by the processor (i.e., the first child of a JOIN
+/// block before the second one, and the true branch of a SPLIT block before the false one). A
+/// block which occurs several times in the tree (e.g., a procedure inlined at several call sites)
+/// is identified by its hash, and thus, is reported once, with the executions of all its
+/// occurrences.
+///
+/// Blocks which were never executed are either code which the inputs did not exercise, or dead
+/// code which inflates the hash of the program, and the trace of every proof of it, without ever
+/// being executed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Hash of the program.
+    pub program_hash: Digest,
+    /// Reachable blocks of the program, in traversal order.
+    pub blocks: Vec<BlockCoverage>,
+}
+
+impl CoverageReport {
+    /// Returns the blocks of the program which were never executed.
+    pub fn uncovered_blocks(&self) -> impl Iterator<Item = &BlockCoverage> {
+        self.blocks.iter().filter(|block| !block.is_covered())
+    }
+
+    /// Returns the number of operations in the SPAN blocks of the program.
+    pub fn num_ops(&self) -> usize {
+        self.blocks.iter().map(|block| block.ops.len()).sum()
+    }
+
+    /// Returns the number of operations in the SPAN blocks of the program which were executed at
+    /// least once.
+    pub fn num_covered_ops(&self) -> usize {
+        self.blocks
+            .iter()
+            .filter(|block| block.is_covered())
+            .map(|block| block.ops.len())
+            .sum()
+    }
+
+    /// Returns this report in the lcov tracefile format, with the program as the single source
+    /// file of the report.
+    ///
+    /// The program has no source lines, and thus, the lines of the report are numbered from 1 in
+    /// traversal order: every block takes one line, reported as a function named after the kind
+    /// and the hash of the block, and is followed by one line for every one of its operations,
+    /// reported with the number of executions of the block. The branches of SPLIT blocks are
+    /// reported as branches of the line of the block.
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        self.write_lcov(&mut lcov)
+            .expect("writing to a string cannot fail");
+        lcov
+    }
+
+    fn write_lcov<W: Write>(&self, target: &mut W) -> fmt::Result {
+        writeln!(target, "TN:")?;
+        writeln!(target, "SF:{}", hex(&self.program_hash))?;
+
+        let mut line = 1;
+        let mut lines = Vec::new();
+        let mut branches = Vec::new();
+        for block in self.blocks.iter() {
+            let name = format!("{}_{}", block.kind, hex(&block.hash));
+            writeln!(target, "FN:{},{}", line, name)?;
+            writeln!(target, "FNDA:{},{}", block.num_executions, name)?;
+            if let Some(taken) = block.branches {
+                branches.push((line, taken));
+            }
+            for _ in block.ops.iter() {
+                line += 1;
+                lines.push((line, block.num_executions));
+            }
+            line += 1;
+        }
+        let num_covered_blocks = self.blocks.len() - self.uncovered_blocks().count();
+        writeln!(target, "FNF:{}", self.blocks.len())?;
+        writeln!(target, "FNH:{}", num_covered_blocks)?;
+
+        for &(line, taken) in branches.iter() {
+            for (branch, num_taken) in taken.iter().enumerate() {
+                writeln!(target, "BRDA:{},0,{},{}", line, branch, num_taken)?;
+            }
+        }
+        let num_taken = branches
+            .iter()
+            .flat_map(|(_, taken)| taken.iter())
+            .filter(|&&num_taken| num_taken > 0)
+            .count();
+        writeln!(target, "BRF:{}", branches.len() * 2)?;
+        writeln!(target, "BRH:{}", num_taken)?;
+
+        for &(line, num_executions) in lines.iter() {
+            writeln!(target, "DA:{},{}", line, num_executions)?;
+        }
+        writeln!(target, "LF:{}", self.num_ops())?;
+        writeln!(target, "LH:{}", self.num_covered_ops())?;
+        writeln!(target, "end_of_record")
+    }
+}
+
+/// Executions of a single code block of a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockCoverage {
+    /// Type of the block.
+    pub kind: BlockKind,
+    /// Hash of the block.
+    pub hash: Digest,
+    /// Number of times the execution of the block was started.
+    pub num_executions: usize,
+    /// Number of times the true and the false branch of the block were taken, if it is a SPLIT
+    /// block.
+    pub branches: Option<[usize; 2]>,
+    /// Operations of the block, if it is a SPAN block, excluding the NOOPs the processor executes
+    /// to align the operations in batches; every operation is executed whenever the block is.
+    pub ops: Vec<Operation>,
+}
+
+impl BlockCoverage {
+    /// Returns true if the block was executed at least once.
+    pub fn is_covered(&self) -> bool {
+        self.num_executions > 0
+    }
+}
+
+// COVERAGE COLLECTION
+// ================================================================================================
+
+/// Executes the provided program against the provided inputs, and returns a report on which of
+/// its code blocks and operations were executed.
+///
+/// The execution trace of the program is not built. Blocks which are not executed by any of a
+/// set of inputs the program is tested with are candidates for removal before the program is
+/// proven and recursively verified, since every block contributes to the hash of the program.
+///
+/// # Errors
+/// Returns an error if the execution fails for the same reasons as [super::execute].
+pub fn collect_coverage(
+    program: &Program,
+    inputs: &ProgramInputs,
+) -> Result<CoverageReport, ExecutionError> {
+    let mut process = Process::new(inputs.clone());
+    process.coverage = Some(CoverageTracker::default());
+    process.execute(program)?;
+    assert_eq!(
+        program.hash(),
+        process.decoder.program_hash().into(),
+        "inconsistent program hash"
+    );
+
+    let tracker = process
+        .coverage
+        .take()
+        .expect("coverage tracker was removed");
+    Ok(tracker.into_report(program))
+}
+
+// COVERAGE TRACKER
+// ================================================================================================
+
+/// Records the executions of the code blocks of a process.
+#[derive(Debug, Default)]
+pub(super) struct CoverageTracker {
+    blocks: BTreeMap<[u64; 4], BlockCounts>,
+}
+
+/// Executions of a code block recorded by a [CoverageTracker].
+#[derive(Debug, Default)]
+struct BlockCounts {
+    num_executions: usize,
+    branches: [usize; 2],
+}
+
+impl CoverageTracker {
+    /// Records that the execution of the specified block starts.
+    pub fn start(&mut self, block: &CodeBlock) {
+        self.blocks
+            .entry(key(block.hash()))
+            .or_default()
+            .num_executions += 1;
+    }
+
+    /// Records that the SPLIT block with the specified hash takes its true or its false branch.
+    pub fn branch(&mut self, hash: Digest, condition: bool) {
+        let branch = if condition { 0 } else { 1 };
+        self.blocks.entry(key(hash)).or_default().branches[branch] += 1;
+    }
+
+    /// Returns the report on the blocks of the provided program recorded by this tracker.
+    fn into_report(self, program: &Program) -> CoverageReport {
+        let mut blocks = Vec::new();
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![program.root()];
+
+        while let Some(block) = pending.pop() {
+            if !visited.insert(key(block.hash())) {
+                continue;
+            }
+            let (kind, ops) = match block {
+                CodeBlock::Join(block) => {
+                    // push the second child first so that blocks are visited in execution order
+                    pending.push(block.second());
+                    pending.push(block.first());
+                    (BlockKind::Join, Vec::new())
+                }
+                CodeBlock::Split(block) => {
+                    pending.push(block.on_false());
+                    pending.push(block.on_true());
+                    (BlockKind::Split, Vec::new())
+                }
+                CodeBlock::Loop(block) => {
+                    pending.push(block.body());
+                    (BlockKind::Loop, Vec::new())
+                }
+                CodeBlock::Span(block) => {
+                    let ops = block
+                        .op_batches()
+                        .iter()
+                        .flat_map(|batch| batch.ops().iter().copied())
+                        .collect();
+                    (BlockKind::Span, ops)
+                }
+                CodeBlock::Call(_) => (BlockKind::Call, Vec::new()),
+                CodeBlock::Proxy(_) => (BlockKind::Proxy, Vec::new()),
+            };
+
+            let counts = self.blocks.get(&key(block.hash()));
+            blocks.push(BlockCoverage {
+                kind,
+                hash: block.hash(),
+                num_executions: counts.map_or(0, |counts| counts.num_executions),
+                branches: match kind {
+                    BlockKind::Split => Some(counts.map_or([0, 0], |counts| counts.branches)),
+                    _ => None,
+                },
+                ops,
+            });
+        }
+
+        CoverageReport {
+            program_hash: program.hash(),
+            blocks,
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the key of the block with the specified hash.
+fn key(hash: Digest) -> [u64; 4] {
+    let word: Word = hash.into();
+    word.map(|element| element.as_int())
+}
+
+/// Returns the specified hash as a hexadecimal string prefixed with `0x`.
+fn hex(hash: &Digest) -> String {
+    let mut hex = String::from("0x");
+    for byte in hash.as_bytes() {
+        write!(hex, "{:02x}", byte).expect("writing to a string cannot fail");
+    }
+    hex
+}
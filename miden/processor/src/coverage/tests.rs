@@ -0,0 +1,91 @@
+use super::{
+    super::{BlockKind, Program, ProgramInputs},
+    collect_coverage,
+};
+use vm_core::{code_blocks::CodeBlock, Operation};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn coverage_of_untaken_branch() {
+    let on_true = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let on_false = CodeBlock::new_span(vec![
+        Operation::Pad,
+        Operation::Pad,
+        Operation::Drop,
+        Operation::Drop,
+    ]);
+    let program = Program::new(CodeBlock::new_split(on_true.clone(), on_false.clone()));
+
+    // the split block is entered with ONE on top of the stack
+    let inputs = ProgramInputs::new(&[1], &[], vec![]).unwrap();
+    let report = collect_coverage(&program, &inputs).unwrap();
+    assert_eq!(program.hash(), report.program_hash);
+
+    let kinds: Vec<BlockKind> = report.blocks.iter().map(|block| block.kind).collect();
+    assert_eq!(
+        vec![BlockKind::Split, BlockKind::Span, BlockKind::Span],
+        kinds
+    );
+    assert_eq!(Some([1, 0]), report.blocks[0].branches);
+    assert_eq!(1, report.blocks[1].num_executions);
+    assert_eq!(None, report.blocks[1].branches);
+
+    // the false branch is never executed
+    let uncovered: Vec<_> = report.uncovered_blocks().collect();
+    assert_eq!(1, uncovered.len());
+    assert_eq!(on_false.hash(), uncovered[0].hash);
+    assert_eq!(6, report.num_ops());
+    assert_eq!(2, report.num_covered_ops());
+}
+
+#[test]
+fn coverage_of_repeated_blocks() {
+    // the same SPAN block occurs twice in the tree and is executed by both occurrences
+    let span = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let program = Program::new(CodeBlock::new_join([span.clone(), span.clone()]));
+
+    let inputs = ProgramInputs::new(&[], &[], vec![]).unwrap();
+    let report = collect_coverage(&program, &inputs).unwrap();
+    assert_eq!(2, report.blocks.len());
+    assert_eq!(span.hash(), report.blocks[1].hash);
+    assert_eq!(2, report.blocks[1].num_executions);
+    assert_eq!(vec![Operation::Pad, Operation::Drop], report.blocks[1].ops);
+    assert_eq!(0, report.uncovered_blocks().count());
+}
+
+#[test]
+fn coverage_of_loop_body() {
+    let body = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let program = Program::new(CodeBlock::new_loop(body));
+
+    // the loop is entered with ONE on top of the stack and repeated once
+    let inputs = ProgramInputs::new(&[0, 1, 1], &[], vec![]).unwrap();
+    let report = collect_coverage(&program, &inputs).unwrap();
+    assert_eq!(1, report.blocks[0].num_executions);
+    assert_eq!(2, report.blocks[1].num_executions);
+}
+
+#[test]
+fn coverage_lcov_report() {
+    let on_true = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let on_false = CodeBlock::new_span(vec![
+        Operation::Pad,
+        Operation::Pad,
+        Operation::Drop,
+        Operation::Drop,
+    ]);
+    let program = Program::new(CodeBlock::new_split(on_true, on_false));
+    let inputs = ProgramInputs::new(&[1], &[], vec![]).unwrap();
+    let lcov = collect_coverage(&program, &inputs).unwrap().to_lcov();
+
+    // the split block is on line 1, and the spans on lines 2 and 5, followed by their operations
+    assert!(lcov.starts_with("TN:\nSF:0x"));
+    assert!(lcov.contains("\nFN:1,SPLIT_0x"));
+    assert!(lcov.contains("\nFN:5,SPAN_0x"));
+    assert!(lcov.contains("\nFNF:3\nFNH:2\n"));
+    assert!(lcov.contains("\nBRDA:1,0,0,1\nBRDA:1,0,1,0\nBRF:2\nBRH:1\n"));
+    assert!(lcov.contains("\nDA:3,1\nDA:4,1\nDA:6,0\nDA:7,0\nDA:8,0\nDA:9,0\nLF:6\nLH:2\n"));
+    assert!(lcov.ends_with("end_of_record\n"));
+}
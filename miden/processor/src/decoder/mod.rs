@@ -250,6 +250,7 @@ impl Process {
 /// - An instance of [DebugInfo] which is only populated in debug mode. This debug_info instance
 ///   includes operations executed by the VM and AsmOp decorators. AsmOp decorators are populated
 ///   only when both the processor and assembler are in debug mode.
+#[derive(Clone)]
 pub struct Decoder {
     block_stack: BlockStack,
     span_context: Option<SpanContext>,
@@ -633,6 +634,7 @@ impl Default for Decoder {
 // ================================================================================================
 
 /// Keeps track of code blocks which are currently being executed by the VM.
+#[derive(Clone)]
 struct BlockStack {
     blocks: Vec<BlockInfo>,
 }
@@ -782,6 +784,7 @@ impl BlockType {
 ///   encoded as opcodes (7 bits) appended one after another into a single field element, with the
 ///   next operation to be executed located at the least significant position.
 /// - Number of operation groups left to be executed in the entire SPAN block.
+#[derive(Clone)]
 struct SpanContext {
     group_ops_left: Felt,
     num_groups_left: Felt,
@@ -836,6 +839,7 @@ pub fn build_op_group(ops: &[Operation]) -> Felt {
 // DEBUG INFO
 // ================================================================================================
 
+#[derive(Clone)]
 pub struct DebugInfo {
     in_debug_mode: bool,
     operations: Vec<Operation>,
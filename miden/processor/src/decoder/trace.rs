@@ -3,9 +3,8 @@ use super::{
     MIN_TRACE_LEN, NUM_HASHER_COLUMNS, NUM_OP_BATCH_FLAGS, NUM_OP_BITS, ONE, OP_BATCH_1_GROUPS,
     OP_BATCH_2_GROUPS, OP_BATCH_4_GROUPS, OP_BATCH_8_GROUPS, OP_BATCH_SIZE, ZERO,
 };
-use crate::utils::get_trace_len;
 use core::ops::Range;
-use vm_core::utils::new_array_vec;
+use vm_core::utils::{collections::BTreeMap, new_array_vec};
 
 // CONSTANTS
 // ================================================================================================
@@ -34,10 +33,15 @@ pub const USER_OP_HELPERS: Range<usize> = Range {
 ///   group.
 /// - 3 columns for keeping track of operation batch flags.
 /// - 1 column used for op flag degree reduction (to support degree 5 operations).
+///
+/// The hasher state columns are not populated during execution: rows of control blocks only
+/// reference the words they contain (see [HasherTrace]), and the columns are built when the
+/// trace is converted into a vector of columns.
+#[derive(Clone)]
 pub struct DecoderTrace {
     addr_trace: Vec<Felt>,
     op_bits_trace: [Vec<Felt>; NUM_OP_BITS],
-    hasher_trace: [Vec<Felt>; NUM_HASHER_COLUMNS],
+    hasher_trace: HasherTrace,
     in_span_trace: Vec<Felt>,
     group_count_trace: Vec<Felt>,
     op_idx_trace: Vec<Felt>,
@@ -53,7 +57,7 @@ impl DecoderTrace {
         Self {
             addr_trace: Vec::with_capacity(MIN_TRACE_LEN),
             op_bits_trace: new_array_vec(MIN_TRACE_LEN),
-            hasher_trace: new_array_vec(MIN_TRACE_LEN),
+            hasher_trace: HasherTrace::new(),
             group_count_trace: Vec::with_capacity(MIN_TRACE_LEN),
             in_span_trace: Vec::with_capacity(MIN_TRACE_LEN),
             op_idx_trace: Vec::with_capacity(MIN_TRACE_LEN),
@@ -73,9 +77,7 @@ impl DecoderTrace {
     /// Returns the contents of the first 4 registers of the hasher state at the last row.
     pub fn program_hash(&self) -> [Felt; DIGEST_LEN] {
         let mut result = [ZERO; DIGEST_LEN];
-        for (i, element) in result.iter_mut().enumerate() {
-            *element = self.last_hasher_value(i);
-        }
+        result.copy_from_slice(&self.hasher_trace.last_state()[..DIGEST_LEN]);
         result
     }
 
@@ -101,16 +103,7 @@ impl DecoderTrace {
     pub fn append_block_start(&mut self, parent_addr: Felt, op: Operation, h1: Word, h2: Word) {
         self.addr_trace.push(parent_addr);
         self.append_opcode(op);
-
-        self.hasher_trace[0].push(h1[0]);
-        self.hasher_trace[1].push(h1[1]);
-        self.hasher_trace[2].push(h1[2]);
-        self.hasher_trace[3].push(h1[3]);
-
-        self.hasher_trace[4].push(h2[0]);
-        self.hasher_trace[5].push(h2[1]);
-        self.hasher_trace[6].push(h2[2]);
-        self.hasher_trace[7].push(h2[3]);
+        self.hasher_trace.push_block_start(h1, h2);
 
         self.in_span_trace.push(ZERO);
         self.group_count_trace.push(ZERO);
@@ -144,16 +137,8 @@ impl DecoderTrace {
 
         self.addr_trace.push(block_addr);
         self.append_opcode(Operation::End);
-
-        self.hasher_trace[0].push(block_hash[0]);
-        self.hasher_trace[1].push(block_hash[1]);
-        self.hasher_trace[2].push(block_hash[2]);
-        self.hasher_trace[3].push(block_hash[3]);
-
-        self.hasher_trace[4].push(is_loop_body);
-        self.hasher_trace[5].push(is_loop);
-        self.hasher_trace[6].push(ZERO);
-        self.hasher_trace[7].push(ZERO);
+        self.hasher_trace
+            .push_block_end(block_hash, is_loop_body == ONE, is_loop == ONE);
 
         self.in_span_trace.push(ZERO);
 
@@ -184,10 +169,7 @@ impl DecoderTrace {
         self.addr_trace.push(loop_addr);
         self.append_opcode(Operation::Repeat);
 
-        let last_row = get_trace_len(&self.hasher_trace) - 1;
-        for column in self.hasher_trace.iter_mut() {
-            column.push(column[last_row]);
-        }
+        self.hasher_trace.push_repeat();
 
         self.in_span_trace.push(ZERO);
         self.group_count_trace.push(ZERO);
@@ -218,9 +200,7 @@ impl DecoderTrace {
     ) {
         self.addr_trace.push(parent_addr);
         self.append_opcode(Operation::Span);
-        for (i, &op_group) in first_op_batch.iter().enumerate() {
-            self.hasher_trace[i].push(op_group);
-        }
+        self.hasher_trace.push_state(*first_op_batch);
 
         self.in_span_trace.push(ZERO);
         self.group_count_trace.push(num_op_groups);
@@ -246,9 +226,7 @@ impl DecoderTrace {
     pub fn append_respan(&mut self, op_batch: &[Felt; OP_BATCH_SIZE]) {
         self.addr_trace.push(self.last_addr());
         self.append_opcode(Operation::Respan);
-        for (i, &op_group) in op_batch.iter().enumerate() {
-            self.hasher_trace[i].push(op_group);
-        }
+        self.hasher_trace.push_state(*op_batch);
 
         let group_count = self.last_group_count();
         self.in_span_trace.push(ZERO);
@@ -288,12 +266,10 @@ impl DecoderTrace {
         self.addr_trace.push(span_addr);
         self.append_opcode(op);
 
-        self.hasher_trace[0].push(group_ops_left);
-        self.hasher_trace[1].push(parent_addr);
-
-        for idx in USER_OP_HELPERS {
-            self.hasher_trace[idx].push(ZERO);
-        }
+        let mut hasher_state = [ZERO; NUM_HASHER_COLUMNS];
+        hasher_state[0] = group_ops_left;
+        hasher_state[1] = parent_addr;
+        self.hasher_trace.push_state(hasher_state);
 
         self.in_span_trace.push(ONE);
         self.group_count_trace.push(num_groups_left);
@@ -322,16 +298,9 @@ impl DecoderTrace {
         self.addr_trace.push(self.last_addr());
         self.append_opcode(Operation::End);
 
-        self.hasher_trace[0].push(span_hash[0]);
-        self.hasher_trace[1].push(span_hash[1]);
-        self.hasher_trace[2].push(span_hash[2]);
-        self.hasher_trace[3].push(span_hash[3]);
-
         // we don't need to set is_loop here because we know we are not in a loop block
-        self.hasher_trace[4].push(is_loop_body);
-        self.hasher_trace[5].push(ZERO);
-        self.hasher_trace[6].push(ZERO);
-        self.hasher_trace[7].push(ZERO);
+        self.hasher_trace
+            .push_block_end(span_hash, is_loop_body == ONE, false);
 
         self.in_span_trace.push(ZERO);
 
@@ -358,6 +327,8 @@ impl DecoderTrace {
     /// - The first 4 columns of the hasher state, where the unfilled rows are filled with the
     ///   values from the last filled row. This is done so that the hash of the program is
     ///   propagated to the last row.
+    ///
+    /// The hasher state columns are built here from the rows recorded during execution.
     pub fn into_vec(mut self, trace_len: usize, num_rand_rows: usize) -> Vec<Vec<Felt>> {
         let own_len = self.trace_len();
         // make sure that only the duplicate rows will be overwritten with random values
@@ -383,7 +354,8 @@ impl DecoderTrace {
 
         // for unfilled rows of hasher state columns, copy over values from the last row for the
         // first 4 columns, and pad the other 4 columns with ZEROs
-        for (i, mut column) in self.hasher_trace.into_iter().enumerate() {
+        let hasher_columns = self.hasher_trace.into_columns(trace_len);
+        for (i, mut column) in hasher_columns.into_iter().enumerate() {
             debug_assert_eq!(own_len, column.len());
             if i < 4 {
                 let last_value = *column.last().expect("no last hasher trace value");
@@ -441,19 +413,11 @@ impl DecoderTrace {
         *self.group_count_trace.last().expect("no group count")
     }
 
-    /// Returns the last value in the specified hasher column.
-    fn last_hasher_value(&self, idx: usize) -> Felt {
-        debug_assert!(idx < NUM_HASHER_COLUMNS, "invalid hasher register index");
-        *self.hasher_trace[idx].last().expect("no last hasher value")
-    }
-
     /// Returns a reference to the last value in the helper register at the specified index.
     fn last_helper_mut(&mut self, idx: usize) -> &mut Felt {
         debug_assert!(idx < USER_OP_HELPERS.len(), "invalid helper register index");
 
-        self.hasher_trace[USER_OP_HELPERS.start + idx]
-            .last_mut()
-            .expect("no last helper value")
+        &mut self.hasher_trace.last_state_mut()[USER_OP_HELPERS.start + idx]
     }
 
     /// Populates op_bits registers for the next row with the opcode of the provided operation.
@@ -498,14 +462,183 @@ impl DecoderTrace {
             column.push(ZERO);
         }
         self.in_span_trace.push(ZERO);
-        for column in self.hasher_trace.iter_mut() {
-            column.push(ZERO);
-        }
+        self.hasher_trace.push_state([ZERO; NUM_HASHER_COLUMNS]);
         self.group_count_trace.push(ZERO);
         self.op_idx_trace.push(ZERO);
     }
 }
 
+// HASHER STATE COLUMNS
+// ================================================================================================
+
+/// Hasher state columns of the decoder trace, recorded row by row during execution.
+///
+/// Only the rows of SPAN blocks (SPAN, RESPAN, and user operation rows) store their hasher state
+/// explicitly. The rows of control blocks reference the words they contain in a table shared by
+/// all rows, in which every distinct word is stored once, and REPEAT rows store no state at all,
+/// since they copy the state of the previous row. Thus, control blocks executed many times, e.g.,
+/// in the body of a loop, add only a few bytes per row to the trace, rather than the full state.
+///
+/// The columns are built from the recorded rows by [HasherTrace::into_columns].
+#[derive(Clone)]
+struct HasherTrace {
+    /// Kind of the hasher state at every row of the trace.
+    rows: Vec<HasherRow>,
+    /// Hasher states of the rows of kind [HasherRow::State], in the order of the rows.
+    states: Vec<[Felt; NUM_HASHER_COLUMNS]>,
+    /// Hasher states of the rows of kind [HasherRow::Control], in the order of the rows.
+    control_rows: Vec<ControlRow>,
+    /// Words referenced by the rows of control blocks.
+    words: Vec<Word>,
+    /// Indexes of the words in the word table, keyed by the integer values of their elements.
+    word_indexes: BTreeMap<[u64; DIGEST_LEN], u32>,
+}
+
+/// Kind of the hasher state at a single row of the decoder trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HasherRow {
+    /// The state is stored explicitly.
+    State,
+    /// The state is described by a [ControlRow].
+    Control,
+    /// The state is a copy of the state at the previous row.
+    Repeat,
+}
+
+/// Hasher state at a row of a control block; words are given by their indexes in the word table.
+#[derive(Clone, Copy, Debug)]
+enum ControlRow {
+    /// Start of a control block, with the state set to [h1, h2].
+    Start(u32, u32),
+    /// End of a block, with the state set to [block_hash, is_loop_body, is_loop, 0, 0].
+    End(u32, bool, bool),
+}
+
+impl HasherTrace {
+    /// Initializes a blank [HasherTrace].
+    fn new() -> Self {
+        Self {
+            rows: Vec::with_capacity(MIN_TRACE_LEN),
+            states: Vec::with_capacity(MIN_TRACE_LEN),
+            control_rows: Vec::new(),
+            words: Vec::new(),
+            word_indexes: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the hasher state at the last row.
+    fn last_state(&self) -> [Felt; NUM_HASHER_COLUMNS] {
+        // a REPEAT row copies the state of the last row of another kind, and since no row of
+        // another kind follows that row, its state is the last stored state of its kind
+        match self
+            .rows
+            .iter()
+            .rev()
+            .find(|&&row| row != HasherRow::Repeat)
+        {
+            Some(HasherRow::State) => *self.states.last().expect("no last hasher state"),
+            Some(HasherRow::Control) => {
+                self.resolve(self.control_rows.last().expect("no last control row"))
+            }
+            _ => panic!("no last hasher state"),
+        }
+    }
+
+    /// Returns a mutable reference to the hasher state at the last row.
+    ///
+    /// # Panics
+    /// Panics if the state at the last row is not stored explicitly.
+    fn last_state_mut(&mut self) -> &mut [Felt; NUM_HASHER_COLUMNS] {
+        assert_eq!(
+            Some(&HasherRow::State),
+            self.rows.last(),
+            "hasher state at the last row is not stored explicitly"
+        );
+        self.states.last_mut().expect("no last hasher state")
+    }
+
+    /// Appends a row with the hasher state set to [h1, h2].
+    fn push_block_start(&mut self, h1: Word, h2: Word) {
+        let row = ControlRow::Start(self.word_index(h1), self.word_index(h2));
+        self.control_rows.push(row);
+        self.rows.push(HasherRow::Control);
+    }
+
+    /// Appends a row with the hasher state set to [block_hash, is_loop_body, is_loop, 0, 0].
+    fn push_block_end(&mut self, block_hash: Word, is_loop_body: bool, is_loop: bool) {
+        let row = ControlRow::End(self.word_index(block_hash), is_loop_body, is_loop);
+        self.control_rows.push(row);
+        self.rows.push(HasherRow::Control);
+    }
+
+    /// Appends a row with the hasher state copied from the previous row.
+    fn push_repeat(&mut self) {
+        assert!(!self.rows.is_empty(), "no hasher state to repeat");
+        self.rows.push(HasherRow::Repeat);
+    }
+
+    /// Appends a row with the specified hasher state.
+    fn push_state(&mut self, state: [Felt; NUM_HASHER_COLUMNS]) {
+        self.states.push(state);
+        self.rows.push(HasherRow::State);
+    }
+
+    /// Returns the hasher state columns, each allocated for the specified number of rows.
+    fn into_columns(self, capacity: usize) -> [Vec<Felt>; NUM_HASHER_COLUMNS] {
+        let mut columns: [Vec<Felt>; NUM_HASHER_COLUMNS] = new_array_vec(capacity);
+        let mut states = self.states.iter();
+        let mut control_rows = self.control_rows.iter();
+
+        let mut state = [ZERO; NUM_HASHER_COLUMNS];
+        for row in self.rows.iter() {
+            match row {
+                HasherRow::State => state = *states.next().expect("no hasher state"),
+                HasherRow::Control => {
+                    state = self.resolve(control_rows.next().expect("no control row"))
+                }
+                HasherRow::Repeat => (),
+            }
+            for (column, &value) in columns.iter_mut().zip(state.iter()) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+
+    /// Returns the hasher state described by the specified control row.
+    fn resolve(&self, row: &ControlRow) -> [Felt; NUM_HASHER_COLUMNS] {
+        let mut state = [ZERO; NUM_HASHER_COLUMNS];
+        match *row {
+            ControlRow::Start(h1, h2) => {
+                state[..DIGEST_LEN].copy_from_slice(&self.words[h1 as usize]);
+                state[DIGEST_LEN..].copy_from_slice(&self.words[h2 as usize]);
+            }
+            ControlRow::End(block_hash, is_loop_body, is_loop) => {
+                state[..DIGEST_LEN].copy_from_slice(&self.words[block_hash as usize]);
+                state[DIGEST_LEN] = if is_loop_body { ONE } else { ZERO };
+                state[DIGEST_LEN + 1] = if is_loop { ONE } else { ZERO };
+            }
+        }
+        state
+    }
+
+    /// Returns the index of the specified word in the word table, adding the word to the table
+    /// if it is not there yet.
+    fn word_index(&mut self, word: Word) -> u32 {
+        let key = [
+            word[0].as_int(),
+            word[1].as_int(),
+            word[2].as_int(),
+            word[3].as_int(),
+        ];
+        let words = &mut self.words;
+        *self.word_indexes.entry(key).or_insert_with(|| {
+            words.push(word);
+            (words.len() - 1) as u32
+        })
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -0,0 +1,90 @@
+use super::{ExecutionError, Felt, PaddingPolicy, Process, Program, ProgramInputs, Vec};
+use vm_core::MIN_STACK_DEPTH;
+
+#[cfg(test)]
+mod tests;
+
+// DRY-RUN REPORT
+// ================================================================================================
+
+/// Outputs and resource usage of a program executed without materializing its execution trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DryRunReport {
+    /// State of the stack at the end of the execution, including the values in the overflow
+    /// table, with the value at the top of the stack in the first position.
+    pub stack_outputs: Vec<Felt>,
+    /// Number of VM cycles executed by the program.
+    pub num_cycles: usize,
+    /// Number of rows in the execution trace of the Hash chiplet.
+    pub hasher_len: usize,
+    /// Number of rows in the execution trace of the Bitwise chiplet.
+    pub bitwise_len: usize,
+    /// Number of rows in the execution trace of the Memory chiplet.
+    pub memory_len: usize,
+    /// Number of rows in the execution trace of the range checker, including the range checks
+    /// required by the chiplets.
+    pub range_checker_len: usize,
+    /// Length of the execution trace, padded according to the default [PaddingPolicy], which a
+    /// full execution of the program would generate.
+    pub trace_len: usize,
+    /// Amount of gas consumed by the execution, if a gas limit was provided with the inputs.
+    pub gas_used: Option<u64>,
+}
+
+impl DryRunReport {
+    /// Returns the number of rows in the execution traces of all chiplets, including the
+    /// mandatory padding row which follows the Memory chiplet.
+    pub fn chiplets_len(&self) -> usize {
+        self.hasher_len + self.bitwise_len + self.memory_len + 1
+    }
+
+    /// Returns the top 16 values of the stack at the end of the execution.
+    pub fn stack_top(&self) -> &[Felt] {
+        &self.stack_outputs[..MIN_STACK_DEPTH]
+    }
+}
+
+// DRY RUN
+// ================================================================================================
+
+/// Executes the provided program against the provided inputs, and returns the outputs and the
+/// resource usage of the execution without building its execution trace.
+///
+/// This is meant for rapid iteration on programs before committing to a full prove run: the
+/// trace columns of the components are neither pre-allocated nor padded, random rows are not
+/// injected, the components are not combined into the main trace, and the hints for the
+/// auxiliary trace are not computed. The reported lengths are the same as those of the trace
+/// generated by [super::execute] for the same program and inputs.
+///
+/// # Errors
+/// Returns an error if the execution fails for the same reasons as [super::execute].
+pub fn dry_run(program: &Program, inputs: &ProgramInputs) -> Result<DryRunReport, ExecutionError> {
+    let mut process = Process::new(inputs.clone());
+    process.execute(program)?;
+    assert_eq!(
+        program.hash(),
+        process.decoder.program_hash().into(),
+        "inconsistent program hash"
+    );
+
+    let stack_outputs = process.stack.get_values(None);
+    let gas_used = process.gas_used();
+    let (system, _, _, mut range, chiplets) = process.to_components();
+    let num_cycles = system.clk();
+
+    // the range checker holds the range checks of the chiplets only once the trace is built
+    chiplets.append_range_checks(&mut range);
+    let range_checker_len = range.trace_len();
+
+    let max_len = num_cycles.max(range_checker_len).max(chiplets.trace_len());
+    Ok(DryRunReport {
+        stack_outputs,
+        num_cycles,
+        hasher_len: chiplets.hasher_trace_len(),
+        bitwise_len: chiplets.bitwise_trace_len(),
+        memory_len: chiplets.memory_trace_len(),
+        range_checker_len,
+        trace_len: PaddingPolicy::default().trace_len(max_len),
+        gas_used,
+    })
+}
@@ -0,0 +1,61 @@
+use super::{
+    super::{execute, Program, ProgramInputs},
+    dry_run,
+};
+use vm_core::{
+    chiplets::bitwise::OP_CYCLE_LEN, code_blocks::CodeBlock, Felt, Operation, StarkField,
+};
+use winterfell::Trace;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn dry_run_matches_execution() {
+    let program = build_program();
+    let inputs = ProgramInputs::new(&[3, 5], &[], vec![]).unwrap();
+    let trace = execute(&program, &inputs).unwrap();
+    let report = dry_run(&program, &inputs).unwrap();
+
+    // the outputs and the trace length are the same as those of the full execution
+    assert_eq!(&trace.last_stack_state()[..], report.stack_top());
+    assert_eq!(trace.length(), report.trace_len);
+
+    // the bitwise operation is executed in a single cycle of the bitwise chiplet, and the
+    // memory chiplet holds a row for the write and one for the read
+    assert_eq!(OP_CYCLE_LEN, report.bitwise_len);
+    assert_eq!(2, report.memory_len);
+    assert!(report.hasher_len > 0);
+    assert!(report.num_cycles > 0);
+    assert!(report.chiplets_len() > report.hasher_len + report.bitwise_len + report.memory_len);
+
+    // 3 XOR 5 is on top of the stack, followed by the value read back from memory
+    assert_eq!(6, report.stack_outputs[0].as_int());
+    assert_eq!(6, report.stack_outputs[1].as_int());
+}
+
+#[test]
+fn dry_run_fails_as_execution() {
+    let program = Program::new(CodeBlock::new_span(vec![
+        Operation::Push(Felt::new(2)),
+        Operation::Assert(0),
+    ]));
+    let inputs = ProgramInputs::none();
+    assert!(execute(&program, &inputs).is_err());
+    assert!(dry_run(&program, &inputs).is_err());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a program which XORs the two values on top of the stack, stores the result at memory
+/// address 0, and reads it back.
+fn build_program() -> Program {
+    Program::new(CodeBlock::new_span(vec![
+        Operation::U32xor,
+        Operation::Pad,
+        Operation::MStore,
+        Operation::Pad,
+        Operation::MLoad,
+    ]))
+}
@@ -1,4 +1,4 @@
-use super::{AdviceSetError, CodeBlock, Felt};
+use super::{AdviceSetError, CodeBlock, ExecutionReport, Felt, Operation};
 use winterfell::ProverError;
 
 // EXECUTION ERROR
@@ -11,13 +11,31 @@ pub enum ExecutionError {
     AdviceSetUpdateFailed(AdviceSetError),
     DivideByZero(usize),
     EmptyAdviceTape(usize),
-    FailedAssertion(usize),
+    ExecutionAborted(ExecutionReport),
+    FailedAssertion(usize, u32),
     InvalidFmpValue(Felt, Felt),
+    InvalidMemoryContext(u32),
+    InvalidMemoryRange(u64, usize),
     InvalidPowerOfTwo(Felt),
+    InvalidProgram(ValidationError),
+    MemoryAddressOutOfRange(u64),
     NotBinaryValue(Felt),
     NotU32Value(Felt),
+    OutOfGas(u64, u64),
     ProverError(ProverError),
     TooManyStackOutputs(usize),
     UnexecutableCodeBlock(CodeBlock),
     UnsupportedCodeBlock(CodeBlock),
 }
+
+// VALIDATION ERROR
+// ================================================================================================
+
+#[derive(Debug)]
+pub enum ValidationError {
+    DisallowedOperation(Operation),
+    NestingTooDeep(usize),
+    TooManySpanBatches(usize),
+    TooManyTotalBatches(usize),
+    UnsupportedCodeBlock(CodeBlock),
+}
@@ -0,0 +1,153 @@
+use super::{CodeBlock, Operation, Program, ProgramInputs, Span};
+use vm_core::chiplets::{bitwise::OP_CYCLE_LEN, hasher::HASH_CYCLE_LEN};
+
+#[cfg(test)]
+mod tests;
+
+// EXECUTION ESTIMATE
+// ================================================================================================
+
+/// Estimate of the resources consumed by executing a program, derived from the structure of the
+/// program without executing it.
+///
+/// The estimate is used to pre-allocate the execution traces of the chiplets, so that the
+/// columns of large traces are not repeatedly reallocated as the program executes. Since the
+/// number of loop iterations and the branches taken by conditionals are not known before the
+/// program is executed, the estimate counts every loop body once and takes the more expensive
+/// branch of every conditional. Thus, it is a lower bound for programs containing loops.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionEstimate {
+    /// Number of VM cycles needed to execute the program.
+    pub num_cycles: usize,
+    /// Number of hash function permutations computed by the hasher, excluding Merkle path
+    /// computations.
+    pub num_permutations: usize,
+    /// Number of Merkle path nodes (legs) computed by the hasher.
+    pub num_merkle_legs: usize,
+    /// Number of 8-row cycles computed by the bitwise chiplet.
+    pub num_bitwise_cycles: usize,
+    /// Number of memory accesses.
+    pub num_memory_accesses: usize,
+}
+
+impl ExecutionEstimate {
+    /// Returns the estimated number of rows in the execution trace of the hasher.
+    pub fn hasher_trace_len(&self) -> usize {
+        (self.num_permutations + self.num_merkle_legs) * HASH_CYCLE_LEN
+    }
+
+    /// Returns the estimated number of rows in the execution trace of the bitwise chiplet.
+    pub fn bitwise_trace_len(&self) -> usize {
+        self.num_bitwise_cycles * OP_CYCLE_LEN
+    }
+
+    /// Adds the resources of the other estimate to this estimate.
+    fn add(&mut self, other: &Self) {
+        self.num_cycles += other.num_cycles;
+        self.num_permutations += other.num_permutations;
+        self.num_merkle_legs += other.num_merkle_legs;
+        self.num_bitwise_cycles += other.num_bitwise_cycles;
+        self.num_memory_accesses += other.num_memory_accesses;
+    }
+
+    /// Returns an estimate in which every resource is the maximum of the two estimates.
+    fn max(&self, other: &Self) -> Self {
+        Self {
+            num_cycles: self.num_cycles.max(other.num_cycles),
+            num_permutations: self.num_permutations.max(other.num_permutations),
+            num_merkle_legs: self.num_merkle_legs.max(other.num_merkle_legs),
+            num_bitwise_cycles: self.num_bitwise_cycles.max(other.num_bitwise_cycles),
+            num_memory_accesses: self.num_memory_accesses.max(other.num_memory_accesses),
+        }
+    }
+}
+
+// PROGRAM ESTIMATION
+// ================================================================================================
+
+/// Returns an estimate of the resources consumed by executing the provided program against the
+/// provided inputs.
+///
+/// Merkle path operations are assumed to operate on paths as deep as the deepest advice set in
+/// the inputs. Blocks which cannot be executed by the processor (e.g., PROXY blocks) are not
+/// counted.
+pub fn estimate_program(program: &Program, inputs: &ProgramInputs) -> ExecutionEstimate {
+    let merkle_depth = inputs
+        .advice_sets()
+        .values()
+        .map(|set| set.depth() as usize)
+        .max()
+        .unwrap_or(0);
+
+    estimate_code_block(program.root(), merkle_depth)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns an estimate of the resources consumed by executing the specified code block.
+///
+/// The code block tree is traversed recursively in the same way as the processor executes it,
+/// and thus, the depth of the recursion is the same as during execution.
+fn estimate_code_block(block: &CodeBlock, merkle_depth: usize) -> ExecutionEstimate {
+    // every control block is hashed with a single permutation, and is started and ended with one
+    // cycle each
+    let mut estimate = ExecutionEstimate {
+        num_cycles: 2,
+        num_permutations: 1,
+        ..Default::default()
+    };
+
+    match block {
+        CodeBlock::Join(block) => {
+            estimate.add(&estimate_code_block(block.first(), merkle_depth));
+            estimate.add(&estimate_code_block(block.second(), merkle_depth));
+        }
+        CodeBlock::Split(block) => {
+            let on_true = estimate_code_block(block.on_true(), merkle_depth);
+            let on_false = estimate_code_block(block.on_false(), merkle_depth);
+            estimate.add(&on_true.max(&on_false));
+        }
+        CodeBlock::Loop(block) => estimate.add(&estimate_code_block(block.body(), merkle_depth)),
+        CodeBlock::Span(block) => return estimate_span_block(block, merkle_depth),
+        _ => return ExecutionEstimate::default(),
+    }
+
+    estimate
+}
+
+/// Returns an estimate of the resources consumed by executing the specified SPAN block.
+fn estimate_span_block(block: &Span, merkle_depth: usize) -> ExecutionEstimate {
+    let num_batches = block.op_batches().len();
+
+    // the span is hashed with one permutation per operation batch; executing it takes one cycle
+    // per operation, plus one cycle for SPAN, each RESPAN, and END
+    let mut estimate = ExecutionEstimate {
+        num_cycles: num_batches + 1,
+        num_permutations: num_batches,
+        ..Default::default()
+    };
+
+    for op in block.op_batches().iter().flat_map(|batch| batch.ops()) {
+        estimate.num_cycles += 1;
+        match op {
+            Operation::RpPerm => estimate.num_permutations += 1,
+            Operation::MpVerify => estimate.num_merkle_legs += merkle_depth,
+            // the old and the new roots are computed separately
+            Operation::MrUpdate(_) => estimate.num_merkle_legs += 2 * merkle_depth,
+            Operation::U32and | Operation::U32or | Operation::U32xor => {
+                estimate.num_bitwise_cycles += 1
+            }
+            // the high and the low limbs are computed in separate cycles
+            Operation::U64and | Operation::U64or | Operation::U64xor => {
+                estimate.num_bitwise_cycles += 2
+            }
+            Operation::MLoad | Operation::MLoadW | Operation::MStore | Operation::MStoreW => {
+                estimate.num_memory_accesses += 1
+            }
+            _ => (),
+        }
+    }
+
+    estimate
+}
@@ -0,0 +1,97 @@
+use super::{estimate_program, CodeBlock, ExecutionEstimate, Operation, Program, ProgramInputs};
+use crate::Process;
+use vm_core::{chiplets::hasher::HASH_CYCLE_LEN, AdviceSet, Felt, FieldElement};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn estimate_span_block() {
+    let program = Program::new(CodeBlock::new_span(vec![
+        Operation::RpPerm,
+        Operation::U32and,
+        Operation::U64xor,
+        Operation::MLoadW,
+        Operation::MStore,
+    ]));
+    let estimate = estimate_program(&program, &ProgramInputs::none());
+
+    let expected = ExecutionEstimate {
+        num_cycles: 7,
+        num_permutations: 2,
+        num_merkle_legs: 0,
+        num_bitwise_cycles: 3,
+        num_memory_accesses: 2,
+    };
+    assert_eq!(expected, estimate);
+    assert_eq!(2 * HASH_CYCLE_LEN, estimate.hasher_trace_len());
+    assert_eq!(24, estimate.bitwise_trace_len());
+}
+
+#[test]
+fn estimate_control_blocks() {
+    let on_true = CodeBlock::new_span(vec![Operation::RpPerm, Operation::RpPerm]);
+    let on_false = CodeBlock::new_span(vec![Operation::U32xor, Operation::U32xor]);
+    let body = CodeBlock::new_span(vec![Operation::MLoad]);
+    let program = Program::new(CodeBlock::new_join([
+        CodeBlock::new_split(on_true, on_false),
+        CodeBlock::new_loop(body),
+    ]));
+    let estimate = estimate_program(&program, &ProgramInputs::none());
+
+    // JOIN, SPLIT, and LOOP are hashed with one permutation each, and every SPAN is hashed with
+    // one permutation; the more expensive resource of each SPLIT branch is counted
+    assert_eq!(3 + 2 + 2, estimate.num_permutations);
+    assert_eq!(2, estimate.num_bitwise_cycles);
+    assert_eq!(1, estimate.num_memory_accesses);
+    assert_eq!(2 * 3 + 4 + 3, estimate.num_cycles);
+}
+
+#[test]
+fn estimate_merkle_ops() {
+    let leaves = (0..8)
+        .map(|i| [Felt::new(i), Felt::ZERO, Felt::ZERO, Felt::ZERO])
+        .collect();
+    let tree = AdviceSet::new_merkle_tree(leaves).unwrap();
+    let inputs = ProgramInputs::new(&[], &[], vec![tree]).unwrap();
+
+    let program = Program::new(CodeBlock::new_span(vec![
+        Operation::MpVerify,
+        Operation::MrUpdate(false),
+    ]));
+    let estimate = estimate_program(&program, &inputs);
+    assert_eq!(3 + 2 * 3, estimate.num_merkle_legs);
+
+    // without advice sets, Merkle paths are assumed to be empty
+    let estimate = estimate_program(&program, &ProgramInputs::none());
+    assert_eq!(0, estimate.num_merkle_legs);
+}
+
+#[test]
+fn estimate_matches_chiplets_trace() {
+    let span = CodeBlock::new_span(vec![
+        Operation::Pad,
+        Operation::Pad,
+        Operation::U32and,
+        Operation::Drop,
+        Operation::RpPerm,
+        Operation::Pad,
+        Operation::Pad,
+        Operation::Pad,
+        Operation::Pad,
+        Operation::U64or,
+    ]);
+    let program = Program::new(CodeBlock::new_join([span.clone(), span]));
+    let inputs = ProgramInputs::none();
+    let estimate = estimate_program(&program, &inputs);
+
+    let mut process = Process::new(inputs);
+    process.reserve(&estimate);
+    process.execute(&program).unwrap();
+
+    // for programs without loops, the estimate of the hasher and bitwise traces is exact
+    assert_eq!(
+        estimate.hasher_trace_len() + estimate.bitwise_trace_len(),
+        process.chiplets.memory_start()
+    );
+}
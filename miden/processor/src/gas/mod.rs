@@ -0,0 +1,188 @@
+use super::{BTreeMap, ExecutionError, Operation};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Default amount of gas charged for executing a single operation.
+pub const DEFAULT_OP_COST: u64 = 1;
+
+/// Default amount of gas charged for every row added to the execution trace of a chiplet.
+pub const DEFAULT_CHIPLET_ROW_COST: u64 = 1;
+
+// GAS COSTS
+// ================================================================================================
+
+/// Table of the amounts of gas charged for executing operations.
+///
+/// Every executed operation is charged its own cost, plus the cost of the rows it adds to the
+/// execution traces of the Hash, Bitwise, and Memory chiplets. Control flow operations are
+/// charged as the operations they are executed as (i.e., `NOOP` or `DROP`), while the hashing of
+/// code blocks is charged through the rows it adds to the Hash chiplet. By default, every
+/// operation and every chiplet row costs one unit of gas, so that the consumed gas approximates
+/// the length of the execution trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasCosts {
+    default_op_cost: u64,
+    op_costs: BTreeMap<u8, u64>,
+    hasher_row_cost: u64,
+    bitwise_row_cost: u64,
+    memory_row_cost: u64,
+}
+
+impl GasCosts {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a cost table which charges the default costs for all operations and chiplet rows.
+    pub fn new() -> Self {
+        Self {
+            default_op_cost: DEFAULT_OP_COST,
+            op_costs: BTreeMap::new(),
+            hasher_row_cost: DEFAULT_CHIPLET_ROW_COST,
+            bitwise_row_cost: DEFAULT_CHIPLET_ROW_COST,
+            memory_row_cost: DEFAULT_CHIPLET_ROW_COST,
+        }
+    }
+
+    /// Charges the specified cost for operations for which no cost was specified explicitly.
+    pub fn with_default_op_cost(mut self, cost: u64) -> Self {
+        self.default_op_cost = cost;
+        self
+    }
+
+    /// Charges the specified cost for executing the specified operation.
+    ///
+    /// Costs are keyed by opcode, and thus, the cost applies to the operation regardless of its
+    /// immediate value (e.g., the cost of `Push(1)` applies to all `PUSH` operations).
+    pub fn with_op_cost(mut self, op: Operation, cost: u64) -> Self {
+        self.op_costs.insert(op.op_code(), cost);
+        self
+    }
+
+    /// Charges the specified cost for every row added to the execution trace of the Hash chiplet.
+    pub fn with_hasher_row_cost(mut self, cost: u64) -> Self {
+        self.hasher_row_cost = cost;
+        self
+    }
+
+    /// Charges the specified cost for every row added to the execution trace of the Bitwise
+    /// chiplet.
+    pub fn with_bitwise_row_cost(mut self, cost: u64) -> Self {
+        self.bitwise_row_cost = cost;
+        self
+    }
+
+    /// Charges the specified cost for every row added to the execution trace of the Memory
+    /// chiplet.
+    pub fn with_memory_row_cost(mut self, cost: u64) -> Self {
+        self.memory_row_cost = cost;
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the amount of gas charged for executing the specified operation, excluding the
+    /// cost of the chiplet rows the operation adds.
+    pub fn op_cost(&self, op: Operation) -> u64 {
+        self.op_costs
+            .get(&op.op_code())
+            .copied()
+            .unwrap_or(self.default_op_cost)
+    }
+
+    /// Returns the amount of gas charged for adding the specified numbers of rows to the
+    /// execution traces of the Hash, Bitwise, and Memory chiplets.
+    pub fn chiplet_rows_cost(
+        &self,
+        hasher_rows: usize,
+        bitwise_rows: usize,
+        memory_rows: usize,
+    ) -> u64 {
+        (hasher_rows as u64)
+            .saturating_mul(self.hasher_row_cost)
+            .saturating_add((bitwise_rows as u64).saturating_mul(self.bitwise_row_cost))
+            .saturating_add((memory_rows as u64).saturating_mul(self.memory_row_cost))
+    }
+}
+
+impl Default for GasCosts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// GAS METER
+// ================================================================================================
+
+/// Charges the costs of executed operations against a gas limit.
+///
+/// The meter keeps track of the lengths of the chiplet traces at the last charge, so that every
+/// operation is charged only for the chiplet rows it added.
+#[derive(Clone, Debug)]
+pub(super) struct GasMeter {
+    costs: GasCosts,
+    limit: u64,
+    consumed: u64,
+    hasher_len: usize,
+    bitwise_len: usize,
+    memory_len: usize,
+}
+
+impl GasMeter {
+    /// Returns a new meter which charges the specified costs against the specified limit.
+    pub fn new(costs: GasCosts, limit: u64) -> Self {
+        Self {
+            costs,
+            limit,
+            consumed: 0,
+            hasher_len: 0,
+            bitwise_len: 0,
+            memory_len: 0,
+        }
+    }
+
+    /// Replaces the cost table of this meter with the specified one.
+    pub fn set_costs(&mut self, costs: GasCosts) {
+        self.costs = costs;
+    }
+
+    /// Returns the amount of gas consumed so far.
+    pub fn consumed(&self) -> u64 {
+        self.consumed
+    }
+
+    /// Charges the cost of the specified operation, given the lengths of the chiplet traces
+    /// after the operation was executed.
+    ///
+    /// # Errors
+    /// Returns [ExecutionError::OutOfGas] if the consumed gas exceeds the limit of this meter.
+    pub fn charge(
+        &mut self,
+        op: Operation,
+        hasher_len: usize,
+        bitwise_len: usize,
+        memory_len: usize,
+    ) -> Result<(), ExecutionError> {
+        let cost = self
+            .costs
+            .op_cost(op)
+            .saturating_add(self.costs.chiplet_rows_cost(
+                hasher_len - self.hasher_len,
+                bitwise_len - self.bitwise_len,
+                memory_len - self.memory_len,
+            ));
+        self.hasher_len = hasher_len;
+        self.bitwise_len = bitwise_len;
+        self.memory_len = memory_len;
+
+        self.consumed = self.consumed.saturating_add(cost);
+        if self.consumed > self.limit {
+            return Err(ExecutionError::OutOfGas(self.limit, self.consumed));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use super::{
+    super::{
+        dry_run, execute, execute_with_options, ExecutionError, ExecutionOptions, Program,
+        ProgramInputs,
+    },
+    GasCosts,
+};
+use vm_core::{chiplets::hasher::HASH_CYCLE_LEN, code_blocks::CodeBlock, Operation, ONE};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn gas_used_default_costs() {
+    let program = build_program();
+    let inputs = build_inputs().with_gas_limit(1000);
+    let trace = execute(&program, &inputs).unwrap();
+
+    // SPAN, PAD, INCR, and END cost one unit each, and hashing the single batch of the SPAN
+    // block adds one hash cycle to the Hash chiplet
+    assert_eq!(Some(4 + HASH_CYCLE_LEN as u64), trace.gas_used());
+    assert_eq!(ONE, trace.last_stack_state()[0]);
+
+    // the dry run reports the same amount of gas
+    let report = dry_run(&program, &inputs).unwrap();
+    assert_eq!(trace.gas_used(), report.gas_used);
+}
+
+#[test]
+fn gas_used_custom_costs() {
+    let costs = GasCosts::new()
+        .with_op_cost(Operation::Incr, 10)
+        .with_hasher_row_cost(0);
+    let inputs = build_inputs().with_gas_limit(1000);
+    let options = ExecutionOptions::new().with_gas_costs(costs);
+    let trace = execute_with_options(&build_program(), &inputs, options).unwrap();
+    assert_eq!(Some(13), trace.gas_used());
+}
+
+#[test]
+fn gas_not_metered_without_limit() {
+    let trace = execute(&build_program(), &build_inputs()).unwrap();
+    assert_eq!(None, trace.gas_used());
+}
+
+#[test]
+fn out_of_gas() {
+    let body = CodeBlock::new_span(vec![Operation::Pad, Operation::Incr]);
+    let program = Program::new(CodeBlock::new_loop(body));
+    let inputs = ProgramInputs::new(&[1], &[], vec![])
+        .unwrap()
+        .with_gas_limit(1000);
+
+    // the execution is aborted by the first operation which exceeds the limit, and thus, the
+    // consumed gas exceeds the limit by less than the cost of a single operation
+    match execute(&program, &inputs) {
+        Err(ExecutionError::OutOfGas(limit, consumed)) => {
+            assert_eq!(1000, limit);
+            assert!(consumed > limit && consumed <= limit + 1 + HASH_CYCLE_LEN as u64);
+        }
+        _ => panic!("execution was expected to run out of gas"),
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_program() -> Program {
+    Program::new(CodeBlock::new_span(vec![Operation::Pad, Operation::Incr]))
+}
+
+fn build_inputs() -> ProgramInputs {
+    ProgramInputs::none()
+}
@@ -0,0 +1,126 @@
+use super::{Box, Chiplets, CodeBlock, Operation, Process};
+
+#[cfg(test)]
+mod tests;
+
+// CHIPLET
+// ================================================================================================
+
+/// A chiplet which can be called by the operations of a program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chiplet {
+    Hasher,
+    Bitwise,
+    Memory,
+}
+
+// EXECUTION HOOKS
+// ================================================================================================
+
+/// Observer of the events of an execution, notified while the program is being executed.
+///
+/// Hooks are meant for tools which follow an execution as it happens, such as tracing UIs,
+/// coverage tools, and fuzzers. All methods do nothing by default, so that a tool implements only
+/// the events it is interested in. The hooks installed in a process are owned by it; a tool which
+/// collects data from the events should share it with the host, e.g., via `Rc<RefCell<_>>`.
+///
+/// Events are reported at the following points:
+/// - [ExecutionHooks::on_block_start] when the execution of a code block starts, before any of
+///   its operations are executed.
+/// - [ExecutionHooks::on_op] before an operation is executed. Control flow operations are
+///   reported as the operations they are executed as (i.e., `NOOP` or `DROP`), and so are the
+///   `NOOP`s padding operation groups and batches.
+/// - [ExecutionHooks::on_chiplet_call] after an operation is executed, once for every chiplet
+///   which added rows to its execution trace since the previous operation. The hashing of a code
+///   block is thus attributed to the operation which starts the block.
+///
+/// Processes without hooks only check that no hooks are installed at these points.
+pub trait ExecutionHooks {
+    /// Called when the execution of the specified code block starts at the specified cycle.
+    fn on_block_start(&mut self, _block: &CodeBlock, _clk: usize) {}
+
+    /// Called before the specified operation is executed at the specified cycle.
+    fn on_op(&mut self, _op: Operation, _clk: usize) {}
+
+    /// Called when the operation executed at the specified cycle added the specified number of
+    /// rows to the execution trace of the specified chiplet.
+    fn on_chiplet_call(&mut self, _chiplet: Chiplet, _num_rows: usize, _clk: usize) {}
+}
+
+// HOOK RUNNER
+// ================================================================================================
+
+/// Execution hooks installed in a process, together with the lengths of the execution traces of
+/// the chiplets at the previous operation.
+pub(super) struct HookRunner {
+    hooks: Box<dyn ExecutionHooks>,
+    chiplet_lens: [usize; 3],
+}
+
+impl HookRunner {
+    /// Returns a runner of the provided hooks for a process with the provided chiplets.
+    pub fn new(hooks: Box<dyn ExecutionHooks>, chiplets: &Chiplets) -> Self {
+        Self {
+            hooks,
+            chiplet_lens: chiplet_lens(chiplets),
+        }
+    }
+
+    /// Forgets the rows added to the execution traces of the chiplets since the previous
+    /// operation; this is needed when the chiplets are replaced by ones restored from a snapshot.
+    pub fn sync(&mut self, chiplets: &Chiplets) {
+        self.chiplet_lens = chiplet_lens(chiplets);
+    }
+}
+
+// HOOKS PROCESS EXTENSION
+// ================================================================================================
+
+impl Process {
+    /// Notifies the hooks of this process (if any) that the execution of the specified block
+    /// starts.
+    pub(super) fn notify_block_start(&mut self, block: &CodeBlock) {
+        if let Some(runner) = self.hooks.as_mut() {
+            runner.hooks.on_block_start(block, self.system.clk());
+        }
+    }
+
+    /// Notifies the hooks of this process (if any) that the specified operation is about to be
+    /// executed.
+    pub(super) fn notify_op(&mut self, op: Operation) {
+        if let Some(runner) = self.hooks.as_mut() {
+            runner.hooks.on_op(op, self.system.clk());
+        }
+    }
+
+    /// Notifies the hooks of this process (if any) of the chiplets which added rows to their
+    /// execution traces since the previous operation, which was executed at the specified cycle.
+    pub(super) fn notify_chiplet_calls(&mut self, clk: usize) {
+        if let Some(runner) = self.hooks.as_mut() {
+            let lens = chiplet_lens(&self.chiplets);
+            let chiplets = [Chiplet::Hasher, Chiplet::Bitwise, Chiplet::Memory];
+            for ((chiplet, &len), prev_len) in chiplets
+                .into_iter()
+                .zip(lens.iter())
+                .zip(runner.chiplet_lens)
+            {
+                if len > prev_len {
+                    runner.hooks.on_chiplet_call(chiplet, len - prev_len, clk);
+                }
+            }
+            runner.chiplet_lens = lens;
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the lengths of the execution traces of the hasher, bitwise, and memory chiplets.
+fn chiplet_lens(chiplets: &Chiplets) -> [usize; 3] {
+    [
+        chiplets.hasher_trace_len(),
+        chiplets.bitwise_trace_len(),
+        chiplets.memory_trace_len(),
+    ]
+}
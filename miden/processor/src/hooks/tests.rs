@@ -0,0 +1,145 @@
+use super::{
+    super::{
+        dry_run, execute_with_options, Digest, ExecutionOptions, Process, Program,
+        ProgramExecution, ProgramInputs,
+    },
+    Chiplet, CodeBlock, ExecutionHooks, Operation,
+};
+use std::{cell::RefCell, rc::Rc};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn hooks_observe_execution() {
+    let first = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let second = CodeBlock::new_span(vec![Operation::Pad, Operation::U32and, Operation::Drop]);
+    let program = Program::new(CodeBlock::new_join([first.clone(), second.clone()]));
+    let inputs = ProgramInputs::new(&[3, 5], &[], vec![]).unwrap();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let hooks = Box::new(Recorder(events.clone()));
+    execute_with_options(&program, &inputs, ExecutionOptions::new().with_hooks(hooks)).unwrap();
+    let events = events.borrow();
+
+    // blocks are reported in the order in which they are started
+    let blocks: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Block(hash, clk) => Some((*hash, *clk)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![(program.hash(), 0), (first.hash(), 1), (second.hash(), 5)],
+        blocks
+    );
+
+    // every executed operation is reported once, starting with the NOOP executed for JOIN
+    let ops: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Op(op, clk) => Some((*op, *clk)),
+            _ => None,
+        })
+        .collect();
+    let report = dry_run(&program, &inputs).unwrap();
+    assert_eq!(report.num_cycles, ops.len());
+    assert_eq!((Operation::Noop, 0), ops[0]);
+    assert!(ops.iter().enumerate().all(|(i, &(_, clk))| i == clk));
+
+    // the hashing of the JOIN block is attributed to the operation which starts it, and the rows
+    // reported for every chiplet add up to the lengths of their traces
+    let calls: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Chiplet(chiplet, num_rows, clk) => Some((*chiplet, *num_rows, *clk)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!((Chiplet::Hasher, 8, 0), calls[0]);
+    let num_rows = |chiplet| -> usize {
+        calls
+            .iter()
+            .filter(|call| call.0 == chiplet)
+            .map(|call| call.1)
+            .sum()
+    };
+    assert_eq!(report.hasher_len, num_rows(Chiplet::Hasher));
+    assert_eq!(report.bitwise_len, num_rows(Chiplet::Bitwise));
+    assert_eq!(0, num_rows(Chiplet::Memory));
+
+    // the bitwise chiplet is called by U32AND only
+    let u32and_clk = ops
+        .iter()
+        .find(|(op, _)| *op == Operation::U32and)
+        .map(|(_, clk)| *clk)
+        .unwrap();
+    assert_eq!(
+        vec![(Chiplet::Bitwise, report.bitwise_len, u32and_clk)],
+        calls
+            .iter()
+            .filter(|call| call.0 == Chiplet::Bitwise)
+            .copied()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn hooks_after_restore() {
+    let first = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let second = CodeBlock::new_span(vec![Operation::Pad, Operation::U32and, Operation::Drop]);
+    let program = Program::new(CodeBlock::new_join([first, second]));
+    let inputs = ProgramInputs::new(&[3, 5], &[], vec![]).unwrap();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let process = Process::new(inputs).with_hooks(Box::new(Recorder(events.clone())));
+    let mut execution = ProgramExecution::with_process(&program, process);
+
+    // execute the JOIN block and the first SPAN block, and run the rest of the program twice
+    execution.step().unwrap();
+    execution.step().unwrap();
+    let snapshot = execution.snapshot();
+    let num_events = events.borrow().len();
+    while execution.step().unwrap() {}
+    let first_run = events.borrow()[num_events..].to_vec();
+
+    execution.restore(&snapshot);
+    let num_events = events.borrow().len();
+    while execution.step().unwrap() {}
+    let second_run = events.borrow()[num_events..].to_vec();
+
+    // rows added to the chiplets before the restore are not reported again
+    assert!(first_run
+        .iter()
+        .any(|event| matches!(event, Event::Chiplet(..))));
+    assert_eq!(first_run, second_run);
+}
+
+// HELPER TYPES
+// ================================================================================================
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Event {
+    Block(Digest, usize),
+    Op(Operation, usize),
+    Chiplet(Chiplet, usize, usize),
+}
+
+struct Recorder(Rc<RefCell<Vec<Event>>>);
+
+impl ExecutionHooks for Recorder {
+    fn on_block_start(&mut self, block: &CodeBlock, clk: usize) {
+        self.0.borrow_mut().push(Event::Block(block.hash(), clk));
+    }
+
+    fn on_op(&mut self, op: Operation, clk: usize) {
+        self.0.borrow_mut().push(Event::Op(op, clk));
+    }
+
+    fn on_chiplet_call(&mut self, chiplet: Chiplet, num_rows: usize, clk: usize) {
+        self.0
+            .borrow_mut()
+            .push(Event::Chiplet(chiplet, num_rows, clk));
+    }
+}
@@ -6,14 +6,18 @@ extern crate alloc;
 
 pub use vm_core::{
     chiplets::hasher::Digest,
-    errors::{AdviceSetError, InputError},
-    AdviceSet, Program, ProgramInputs,
+    code_blocks::CodeBlock,
+    errors::{AdviceSetError, HasherAddressError, InputError},
+    AdviceSet, Operation, Program, ProgramInputs,
 };
 use vm_core::{
-    code_blocks::{CodeBlock, Join, Loop, OpBatch, Span, Split, OP_BATCH_SIZE, OP_GROUP_SIZE},
-    utils::collections::{BTreeMap, Vec},
-    AdviceInjector, Decorator, DecoratorIterator, Felt, FieldElement, Operation, StackTopState,
-    StarkField, Word, CHIPLETS_WIDTH, DECODER_TRACE_WIDTH, MIN_STACK_DEPTH, MIN_TRACE_LEN,
+    code_blocks::{Join, Loop, OpBatch, Span, Split, OP_BATCH_SIZE, OP_GROUP_SIZE},
+    utils::{
+        collections::{BTreeMap, Vec},
+        Box,
+    },
+    AdviceInjector, Decorator, DecoratorIterator, Felt, FieldElement, StackTopState, StarkField,
+    Word, CHIPLETS_WIDTH, DECODER_TRACE_WIDTH, MIN_STACK_DEPTH, MIN_TRACE_LEN,
     NUM_STACK_HELPER_COLS, ONE, RANGE_CHECK_TRACE_WIDTH, STACK_TRACE_WIDTH, SYS_TRACE_WIDTH, ZERO,
 };
 
@@ -38,19 +42,59 @@ use advice::AdviceProvider;
 
 mod chiplets;
 use chiplets::Chiplets;
+pub use chiplets::{AuxTraceBuilder as ChipletsAuxTraceBuilder, ChipletsBus};
 
 mod trace;
-pub use trace::ExecutionTrace;
 use trace::TraceFragment;
+pub use trace::{
+    reduce_bitwise_row, reduce_elements, reduce_hasher_row, reduce_memory_row, AssertionStep,
+    BoundaryAssertion, ColumnInfo, ExecutionTrace, PaddingPolicy, TraceMetadata,
+};
 
 mod errors;
-pub use errors::ExecutionError;
+pub use errors::{ExecutionError, ValidationError};
+
+mod validation;
+pub use validation::{validate_program, ProgramLimits};
+
+mod estimate;
+pub use estimate::{estimate_program, ExecutionEstimate};
+
+mod batching;
+pub use batching::{analyze_batching, BatchingReport, BatchingSuggestion, SpanBatching};
 
 mod utils;
 
 mod debug;
 pub use debug::{AsmOpInfo, VmState, VmStateIterator};
 
+mod watchdog;
+pub use watchdog::{AbortReason, CancellationToken, ExecutionReport, Watchdog};
+
+mod gas;
+pub use gas::GasCosts;
+
+mod snapshot;
+pub use snapshot::{ExecutionSnapshot, ProcessSnapshot, ProgramExecution};
+
+mod dry_run;
+pub use dry_run::{dry_run, DryRunReport};
+
+mod loops;
+pub use loops::{analyze_loops, LoopReport, LoopStats};
+
+mod call_stack;
+pub use call_stack::{execute_with_call_stack, BlockKind, CallFrame, CallStack, ExecutionFailure};
+
+mod hooks;
+pub use hooks::{Chiplet, ExecutionHooks};
+
+mod coverage;
+pub use coverage::{collect_coverage, BlockCoverage, CoverageReport};
+
+mod options;
+pub use options::ExecutionOptions;
+
 // TYPE ALIASES
 // ================================================================================================
 
@@ -86,9 +130,37 @@ pub fn execute(
     program: &Program,
     inputs: &ProgramInputs,
 ) -> Result<ExecutionTrace, ExecutionError> {
-    let mut process = Process::new(inputs.clone());
+    execute_with_options(program, inputs, ExecutionOptions::default())
+}
+
+/// Returns an execution trace resulting from executing the provided program against the provided
+/// inputs according to the specified options.
+///
+/// Depending on the options, the program is checked against limits before it is executed, the
+/// execution is watched by a watchdog and followed by hooks, gas is charged according to a custom
+/// cost table, and the trace is padded according to a custom policy; see [ExecutionOptions] for
+/// details.
+///
+/// # Errors
+/// Returns an [ExecutionError] if the execution fails for the same reasons as [execute], and in
+/// addition:
+/// - [ExecutionError::InvalidProgram] if the program does not satisfy the limits of the options.
+/// - [ExecutionError::ExecutionAborted] with a report describing the state of the execution at
+///   the moment it was aborted if the execution was aborted by the watchdog of the options.
+pub fn execute_with_options(
+    program: &Program,
+    inputs: &ProgramInputs,
+    options: ExecutionOptions,
+) -> Result<ExecutionTrace, ExecutionError> {
+    if let Some(limits) = options.limits() {
+        validate_program(program, limits).map_err(ExecutionError::InvalidProgram)?;
+    }
+    let padding = options.padding();
+
+    let mut process = options.into_process(inputs.clone());
+    process.reserve(&estimate_program(program, inputs));
     process.execute(program)?;
-    let trace = ExecutionTrace::new(process);
+    let trace = ExecutionTrace::with_padding(process, padding);
     assert_eq!(
         program.hash(),
         trace.program_hash(),
@@ -122,6 +194,12 @@ pub struct Process {
     range: RangeChecker,
     chiplets: Chiplets,
     advice: AdviceProvider,
+    watchdog: Option<Watchdog>,
+    gas: Option<gas::GasMeter>,
+    loops: Option<loops::LoopTracker>,
+    call_stack: Option<CallStack>,
+    hooks: Option<hooks::HookRunner>,
+    coverage: Option<coverage::CoverageTracker>,
 }
 
 impl Process {
@@ -138,6 +216,9 @@ impl Process {
     }
 
     fn initialize(inputs: ProgramInputs, in_debug_mode: bool) -> Self {
+        let gas = inputs
+            .gas_limit()
+            .map(|limit| gas::GasMeter::new(GasCosts::default(), limit));
         Self {
             system: System::new(MIN_TRACE_LEN),
             decoder: Decoder::new(in_debug_mode),
@@ -145,9 +226,55 @@ impl Process {
             range: RangeChecker::new(),
             chiplets: Chiplets::default(),
             advice: AdviceProvider::new(inputs),
+            watchdog: None,
+            gas,
+            loops: None,
+            call_stack: None,
+            hooks: None,
+            coverage: None,
         }
     }
 
+    /// Sets the watchdog which periodically checks whether executions in this process should be
+    /// aborted.
+    pub fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Sets the hooks which are notified of the events of executions in this process; see
+    /// [ExecutionHooks] for details.
+    pub fn with_hooks(mut self, hooks: Box<dyn ExecutionHooks>) -> Self {
+        self.hooks = Some(hooks::HookRunner::new(hooks, &self.chiplets));
+        self
+    }
+
+    /// Sets the cost table according to which gas consumed by executions in this process is
+    /// charged. Gas is charged only if a gas limit was provided with the inputs of this process.
+    pub fn with_gas_costs(mut self, costs: GasCosts) -> Self {
+        if let Some(gas) = self.gas.as_mut() {
+            gas.set_costs(costs);
+        }
+        self
+    }
+
+    /// Returns the amount of gas consumed by executions in this process, if a gas limit was
+    /// provided with the inputs of this process.
+    pub fn gas_used(&self) -> Option<u64> {
+        self.gas.as_ref().map(gas::GasMeter::consumed)
+    }
+
+    /// Returns the call stack at which the last execution in this process failed, if it failed.
+    pub fn call_stack(&self) -> Option<&CallStack> {
+        self.call_stack.as_ref()
+    }
+
+    /// Reserves capacity in the execution traces of this process for executing a program with
+    /// the provided resource estimate.
+    fn reserve(&mut self, estimate: &ExecutionEstimate) {
+        self.chiplets.reserve(estimate);
+    }
+
     // PROGRAM EXECUTOR
     // --------------------------------------------------------------------------------------------
 
@@ -166,77 +293,147 @@ impl Process {
 
     /// Executes the specified [CodeBlock].
     ///
+    /// Blocks are executed iteratively: the work which remains to be done is kept on an explicit
+    /// stack of [Continuation]s, which are executed one by one by [Process::execute_continuation].
+    ///
     /// # Errors
     /// Returns an [ExecutionError] if executing the specified block fails for any reason.
     fn execute_code_block(&mut self, block: &CodeBlock) -> Result<(), ExecutionError> {
-        match block {
-            CodeBlock::Join(block) => self.execute_join_block(block),
-            CodeBlock::Split(block) => self.execute_split_block(block),
-            CodeBlock::Loop(block) => self.execute_loop_block(block),
-            CodeBlock::Span(block) => self.execute_span_block(block),
-            CodeBlock::Proxy(_) => Err(ExecutionError::UnexecutableCodeBlock(block.clone())),
-            _ => Err(ExecutionError::UnsupportedCodeBlock(block.clone())),
+        let mut continuations = vec![Continuation::Start(block)];
+        while let Some(continuation) = continuations.pop() {
+            self.execute_step(continuation, &mut continuations)?;
         }
+        Ok(())
     }
 
-    /// Executes the specified [Join] block.
-    #[inline(always)]
-    fn execute_join_block(&mut self, block: &Join) -> Result<(), ExecutionError> {
-        self.start_join_block(block)?;
-
-        // execute first and then second child of the join block
-        self.execute_code_block(block.first())?;
-        self.execute_code_block(block.second())?;
-
-        self.end_join_block(block)
+    /// Executes the specified [Continuation] in the same way as [Process::execute_continuation],
+    /// and records the call stack of this process if the continuation fails.
+    ///
+    /// # Errors
+    /// Returns an [ExecutionError] if executing the specified continuation fails for any reason.
+    fn execute_step<'a>(
+        &mut self,
+        continuation: Continuation<'a>,
+        continuations: &mut Vec<Continuation<'a>>,
+    ) -> Result<(), ExecutionError> {
+        let result = self.execute_continuation(continuation, continuations);
+        if result.is_err() {
+            self.call_stack = Some(CallStack::new(self, &continuation, continuations));
+        }
+        result
     }
 
-    /// Executes the specified [Split] block.
-    #[inline(always)]
-    fn execute_split_block(&mut self, block: &Split) -> Result<(), ExecutionError> {
-        // start the SPLIT block; this also pops the stack and returns the popped element
-        let condition = self.start_split_block(block)?;
-
-        // execute either the true or the false branch of the split block based on the condition
-        if condition == ONE {
-            self.execute_code_block(block.on_true())?;
-        } else if condition == ZERO {
-            self.execute_code_block(block.on_false())?;
-        } else {
-            return Err(ExecutionError::NotBinaryValue(condition));
+    /// Executes the specified [Continuation], and pushes the continuations which follow from it
+    /// onto the provided stack. The continuation to be executed next is pushed last.
+    ///
+    /// SPAN blocks are executed in a single step; for control blocks, starting and ending the
+    /// block are separate steps, and the children of the block are executed in between.
+    ///
+    /// # Errors
+    /// Returns an [ExecutionError] if executing the specified continuation fails for any reason.
+    fn execute_continuation<'a>(
+        &mut self,
+        continuation: Continuation<'a>,
+        continuations: &mut Vec<Continuation<'a>>,
+    ) -> Result<(), ExecutionError> {
+        if let Continuation::Start(block) = continuation {
+            self.notify_block_start(block);
+            if let Some(coverage) = self.coverage.as_mut() {
+                coverage.start(block);
+            }
         }
 
-        self.end_split_block(block)
-    }
+        match continuation {
+            Continuation::Start(CodeBlock::Join(block)) => {
+                self.start_join_block(block)?;
 
-    /// Executes the specified [Loop] block.
-    #[inline(always)]
-    fn execute_loop_block(&mut self, block: &Loop) -> Result<(), ExecutionError> {
-        // start the LOOP block; this also pops the stack and returns the popped element
-        let condition = self.start_loop_block(block)?;
-
-        // if the top of the stack is ONE, execute the loop body; otherwise skip the loop body
-        if condition == ONE {
-            // execute the loop body at least once
-            self.execute_code_block(block.body())?;
-
-            // keep executing the loop body until the condition on the top of the stack is no
-            // longer ONE; each iteration of the loop is preceded by executing REPEAT operation
-            // which drops the condition from the stack
-            while self.stack.peek() == ONE {
-                self.decoder.repeat();
-                self.execute_op(Operation::Drop)?;
-                self.execute_code_block(block.body())?;
+                // execute first and then second child of the join block
+                continuations.push(Continuation::EndJoin(block));
+                continuations.push(Continuation::Start(block.second()));
+                continuations.push(Continuation::Start(block.first()));
+                Ok(())
             }
+            Continuation::Start(CodeBlock::Split(block)) => {
+                // start the SPLIT block; this also pops the stack and returns the popped element
+                let condition = self.start_split_block(block)?;
+
+                // execute either the true or the false branch of the split block based on the
+                // condition
+                let branch = if condition == ONE {
+                    block.on_true()
+                } else if condition == ZERO {
+                    block.on_false()
+                } else {
+                    return Err(ExecutionError::NotBinaryValue(condition));
+                };
+                if let Some(coverage) = self.coverage.as_mut() {
+                    coverage.branch(block.hash(), condition == ONE);
+                }
+                continuations.push(Continuation::EndSplit(block));
+                continuations.push(Continuation::Start(branch));
+                Ok(())
+            }
+            Continuation::Start(CodeBlock::Loop(block)) => {
+                if let Some(loops) = self.loops.as_mut() {
+                    loops.enter(block.hash(), self.system.clk(), self.chiplets.trace_len());
+                }
 
-            // end the LOOP block and drop the condition from the stack
-            self.end_loop_block(block, true)
-        } else if condition == ZERO {
-            // end the LOOP block, but don't drop the condition from the stack because it was
-            // already dropped when we started the LOOP block
-            self.end_loop_block(block, false)
-        } else {
-            Err(ExecutionError::NotBinaryValue(condition))
+                // start the LOOP block; this also pops the stack and returns the popped element
+                let condition = self.start_loop_block(block)?;
+
+                // if the top of the stack is ONE, execute the loop body at least once; otherwise
+                // end the LOOP block, but don't drop the condition from the stack because it was
+                // already dropped when we started the LOOP block
+                if condition == ONE {
+                    if let Some(loops) = self.loops.as_mut() {
+                        loops.iterate();
+                    }
+                    continuations.push(Continuation::RepeatLoop(block));
+                    continuations.push(Continuation::Start(block.body()));
+                    Ok(())
+                } else if condition == ZERO {
+                    self.end_loop_block(block, false)?;
+                    self.exit_loop();
+                    Ok(())
+                } else {
+                    Err(ExecutionError::NotBinaryValue(condition))
+                }
+            }
+            Continuation::RepeatLoop(block) => {
+                // keep executing the loop body until the condition on the top of the stack is no
+                // longer ONE; each iteration of the loop is preceded by executing REPEAT operation
+                // which drops the condition from the stack
+                if self.stack.peek() == ONE {
+                    self.decoder.repeat();
+                    self.execute_op(Operation::Drop)?;
+                    if let Some(loops) = self.loops.as_mut() {
+                        loops.iterate();
+                    }
+                    continuations.push(Continuation::RepeatLoop(block));
+                    continuations.push(Continuation::Start(block.body()));
+                    Ok(())
+                } else {
+                    // end the LOOP block and drop the condition from the stack
+                    self.end_loop_block(block, true)?;
+                    self.exit_loop();
+                    Ok(())
+                }
+            }
+            Continuation::Start(CodeBlock::Span(block)) => self.execute_span_block(block),
+            Continuation::Start(block @ CodeBlock::Proxy(_)) => {
+                Err(ExecutionError::UnexecutableCodeBlock(block.clone()))
+            }
+            Continuation::Start(block) => Err(ExecutionError::UnsupportedCodeBlock(block.clone())),
+            Continuation::EndJoin(block) => self.end_join_block(block),
+            Continuation::EndSplit(block) => self.end_split_block(block),
+        }
+    }
+
+    /// Records the end of the innermost active LOOP block with the loop tracker of this process,
+    /// if any.
+    fn exit_loop(&mut self) {
+        if let Some(loops) = self.loops.as_mut() {
+            loops.exit(self.system.clk(), self.chiplets.trace_len());
         }
     }
 
@@ -368,3 +565,20 @@ impl Process {
         )
     }
 }
+
+// CONTINUATION
+// ================================================================================================
+
+/// A step of the execution of a program which remains to be executed.
+#[derive(Clone, Copy, Debug)]
+enum Continuation<'a> {
+    /// Start executing the block; SPAN blocks are executed in full.
+    Start(&'a CodeBlock),
+    /// End the JOIN block after both of its children were executed.
+    EndJoin(&'a Join),
+    /// End the SPLIT block after the selected branch was executed.
+    EndSplit(&'a Split),
+    /// Execute the body of the LOOP block again if the top of the stack is ONE, or end the block
+    /// otherwise.
+    RepeatLoop(&'a Loop),
+}
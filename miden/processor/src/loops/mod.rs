@@ -0,0 +1,241 @@
+use super::{
+    BTreeMap, Digest, ExecutionError, PaddingPolicy, Process, Program, ProgramInputs, StarkField,
+    Vec, Word,
+};
+
+#[cfg(test)]
+mod tests;
+
+// LOOP REPORT
+// ================================================================================================
+
+/// Report on the iterations executed by the LOOP blocks of a program, and on the contribution of
+/// every LOOP block to the execution trace.
+///
+/// Since the number of iterations of a loop depends on the inputs of the program, the report is
+/// built by executing the program (without building its execution trace). Loops are reported
+/// individually up to the nesting depth the report was built with; the iterations of loops which
+/// are nested deeper are not counted, but their cycles and chiplet rows are counted towards the
+/// deepest enclosing loop which is reported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoopReport {
+    /// Reported LOOP blocks, in the order in which they were first entered.
+    pub loops: Vec<LoopStats>,
+    /// Number of VM cycles executed by the program.
+    pub num_cycles: usize,
+    /// Number of rows in the execution traces of all chiplets, including the mandatory padding
+    /// row which follows the Memory chiplet.
+    pub chiplets_len: usize,
+    /// Length of the execution trace, padded according to the default [PaddingPolicy], which a
+    /// full execution of the program would generate.
+    pub trace_len: usize,
+}
+
+impl LoopReport {
+    /// Returns the loop which executed the largest number of cycles, if the program executed any
+    /// reported loops.
+    pub fn hottest(&self) -> Option<&LoopStats> {
+        self.loops.iter().max_by_key(|stats| stats.num_cycles)
+    }
+
+    /// Returns the reported loops ordered by the number of rows they contribute to the execution
+    /// trace, starting with the most expensive one.
+    pub fn by_trace_cost(&self) -> Vec<&LoopStats> {
+        let mut loops: Vec<&LoopStats> = self.loops.iter().collect();
+        loops.sort_by_key(|stats| core::cmp::Reverse(stats.trace_rows()));
+        loops
+    }
+
+    /// Returns the number of rows the execution trace would need if the specified loop were
+    /// removed from the program, i.e., the rows not contributed by the loop.
+    pub fn trace_rows_without(&self, stats: &LoopStats) -> usize {
+        let num_cycles = self.num_cycles - stats.num_cycles;
+        let chiplets_len = self.chiplets_len - stats.num_chiplet_rows;
+        num_cycles.max(chiplets_len)
+    }
+}
+
+/// Iterations executed by a single LOOP block, and its contribution to the execution trace.
+///
+/// A LOOP block which is executed from several places of a program (e.g., a procedure containing
+/// a loop invoked more than once) is reported once, with the statistics of all its executions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoopStats {
+    /// Hash of the LOOP block.
+    pub hash: Digest,
+    /// Number of LOOP blocks enclosing this block when it was first entered.
+    pub depth: usize,
+    /// Number of times the LOOP block was entered.
+    pub num_entries: usize,
+    /// Total number of times the body of the loop was executed.
+    pub num_iterations: usize,
+    /// Largest number of times the body of the loop was executed after entering the block once.
+    pub max_iterations: usize,
+    /// Number of VM cycles executed between entering and ending the block, including the cycles
+    /// executed by the LOOP, REPEAT, and END operations and by nested blocks.
+    pub num_cycles: usize,
+    /// Number of rows added to the execution traces of the chiplets between entering and ending
+    /// the block.
+    pub num_chiplet_rows: usize,
+}
+
+impl LoopStats {
+    /// Returns the number of rows this loop contributes to the main execution trace, which is the
+    /// larger of the number of cycles and the number of chiplet rows it executed.
+    pub fn trace_rows(&self) -> usize {
+        self.num_cycles.max(self.num_chiplet_rows)
+    }
+
+    /// Returns the average number of cycles executed by an iteration of the loop, or None if the
+    /// body of the loop was never executed.
+    pub fn cycles_per_iteration(&self) -> Option<usize> {
+        // every entry executes one LOOP and one END operation besides the iterations
+        match self.num_iterations {
+            0 => None,
+            n => Some((self.num_cycles - 2 * self.num_entries) / n),
+        }
+    }
+}
+
+// LOOP ANALYSIS
+// ================================================================================================
+
+/// Executes the provided program against the provided inputs, and returns a report on the
+/// iterations executed by its LOOP blocks and their contribution to the execution trace.
+///
+/// Loops nested in more than `max_depth` other loops are not reported individually; thus, a depth
+/// of zero reports the outermost loops only. The execution trace of the program is not built,
+/// so this can be used to find the loops responsible for oversized traces before proving.
+///
+/// # Errors
+/// Returns an error if the execution fails for the same reasons as [super::execute].
+pub fn analyze_loops(
+    program: &Program,
+    inputs: &ProgramInputs,
+    max_depth: usize,
+) -> Result<LoopReport, ExecutionError> {
+    let mut process = Process::new(inputs.clone());
+    process.loops = Some(LoopTracker::new(max_depth));
+    process.execute(program)?;
+    assert_eq!(
+        program.hash(),
+        process.decoder.program_hash().into(),
+        "inconsistent program hash"
+    );
+
+    let loops = process
+        .loops
+        .take()
+        .expect("loop tracker was removed")
+        .into_stats();
+    let num_cycles = process.system.clk();
+    let chiplets_len = process.chiplets.trace_len();
+    Ok(LoopReport {
+        loops,
+        num_cycles,
+        chiplets_len,
+        trace_len: PaddingPolicy::default().trace_len(num_cycles.max(chiplets_len)),
+    })
+}
+
+// LOOP TRACKER
+// ================================================================================================
+
+/// Records the LOOP blocks entered and ended by a process.
+#[derive(Debug)]
+pub(super) struct LoopTracker {
+    max_depth: usize,
+    active: Vec<ActiveLoop>,
+    loops: Vec<LoopStats>,
+    indexes: BTreeMap<[u64; 4], usize>,
+}
+
+/// A LOOP block which was entered but not ended yet.
+#[derive(Debug)]
+struct ActiveLoop {
+    /// Index of the statistics of the block, or None if the block is nested too deep to be
+    /// reported.
+    index: Option<usize>,
+    start_cycle: usize,
+    start_chiplets_len: usize,
+    num_iterations: usize,
+}
+
+impl LoopTracker {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            active: Vec::new(),
+            loops: Vec::new(),
+            indexes: BTreeMap::new(),
+        }
+    }
+
+    /// Records that the LOOP block with the specified hash is entered at the specified cycle,
+    /// when the chiplets hold the specified number of rows.
+    pub fn enter(&mut self, hash: Digest, cycle: usize, chiplets_len: usize) {
+        let depth = self.active.len();
+        let index = if depth > self.max_depth {
+            None
+        } else {
+            let word: Word = hash.into();
+            let key = word.map(|element| element.as_int());
+            let loops = &mut self.loops;
+            let index = *self.indexes.entry(key).or_insert_with(|| {
+                loops.push(LoopStats {
+                    hash,
+                    depth,
+                    num_entries: 0,
+                    num_iterations: 0,
+                    max_iterations: 0,
+                    num_cycles: 0,
+                    num_chiplet_rows: 0,
+                });
+                loops.len() - 1
+            });
+            self.loops[index].num_entries += 1;
+            Some(index)
+        };
+        self.active.push(ActiveLoop {
+            index,
+            start_cycle: cycle,
+            start_chiplets_len: chiplets_len,
+            num_iterations: 0,
+        });
+    }
+
+    /// Records that the body of the innermost active loop is executed once more.
+    ///
+    /// # Panics
+    /// Panics if no loop is active.
+    pub fn iterate(&mut self) {
+        self.active
+            .last_mut()
+            .expect("no active loop")
+            .num_iterations += 1;
+    }
+
+    /// Records that the innermost active loop is ended at the specified cycle, when the chiplets
+    /// hold the specified number of rows.
+    ///
+    /// # Panics
+    /// Panics if no loop is active.
+    pub fn exit(&mut self, cycle: usize, chiplets_len: usize) {
+        let active = self.active.pop().expect("no active loop");
+        if let Some(index) = active.index {
+            let stats = &mut self.loops[index];
+            stats.num_iterations += active.num_iterations;
+            stats.max_iterations = stats.max_iterations.max(active.num_iterations);
+            // a loop executed within itself is counted once by its outermost execution
+            if !self.active.iter().any(|outer| outer.index == Some(index)) {
+                stats.num_cycles += cycle - active.start_cycle;
+                stats.num_chiplet_rows += chiplets_len - active.start_chiplets_len;
+            }
+        }
+    }
+
+    fn into_stats(self) -> Vec<LoopStats> {
+        debug_assert!(self.active.is_empty(), "loops are still active");
+        self.loops
+    }
+}
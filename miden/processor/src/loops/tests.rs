@@ -0,0 +1,85 @@
+use super::{
+    super::{dry_run, Program, ProgramInputs},
+    analyze_loops,
+};
+use vm_core::{code_blocks::CodeBlock, Operation};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn analyze_single_loop() {
+    let body = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let program = Program::new(CodeBlock::new_loop(body.clone()));
+
+    // the loop is entered with ONE on top of the stack and repeated once
+    let inputs = ProgramInputs::new(&[0, 1, 1], &[], vec![]).unwrap();
+    let report = analyze_loops(&program, &inputs, 0).unwrap();
+    assert_eq!(1, report.loops.len());
+
+    let stats = &report.loops[0];
+    assert_eq!(program.root().hash(), stats.hash);
+    assert_eq!(0, stats.depth);
+    assert_eq!(1, stats.num_entries);
+    assert_eq!(2, stats.num_iterations);
+    assert_eq!(2, stats.max_iterations);
+
+    // LOOP, 2 iterations of SPAN, PAD, DROP, END, one REPEAT, and END
+    assert_eq!(11, stats.num_cycles);
+    assert_eq!(Some(4), stats.cycles_per_iteration());
+    assert_eq!(Some(stats), report.hottest());
+
+    // the totals are the same as those of a dry run
+    let dry_run = dry_run(&program, &inputs).unwrap();
+    assert_eq!(dry_run.num_cycles, report.num_cycles);
+    assert_eq!(dry_run.chiplets_len(), report.chiplets_len);
+    assert_eq!(dry_run.trace_len, report.trace_len);
+}
+
+#[test]
+fn analyze_skipped_loop() {
+    let body = CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]);
+    let program = Program::new(CodeBlock::new_loop(body));
+
+    let inputs = ProgramInputs::new(&[0], &[], vec![]).unwrap();
+    let report = analyze_loops(&program, &inputs, 0).unwrap();
+
+    // the LOOP block is entered, but its body is never executed
+    let stats = &report.loops[0];
+    assert_eq!(1, stats.num_entries);
+    assert_eq!(0, stats.num_iterations);
+    assert_eq!(2, stats.num_cycles);
+    assert_eq!(None, stats.cycles_per_iteration());
+}
+
+#[test]
+fn analyze_nested_loops() {
+    let inner = CodeBlock::new_loop(CodeBlock::new_span(vec![Operation::Pad, Operation::Drop]));
+    let outer = CodeBlock::new_loop(inner.clone());
+    let program = Program::new(outer);
+
+    // conditions in the order in which they are consumed: the outer loop is entered, the inner
+    // loop is entered and executed once, the outer loop is repeated, the inner loop is skipped,
+    // and the outer loop is ended
+    let inputs = ProgramInputs::new(&[0, 0, 1, 0, 1, 1], &[], vec![]).unwrap();
+    let report = analyze_loops(&program, &inputs, 1).unwrap();
+    assert_eq!(2, report.loops.len());
+
+    let (outer, inner_stats) = (&report.loops[0], &report.loops[1]);
+    assert_eq!(program.root().hash(), outer.hash);
+    assert_eq!(2, outer.num_iterations);
+    assert_eq!(inner.hash(), inner_stats.hash);
+    assert_eq!(1, inner_stats.depth);
+    assert_eq!(2, inner_stats.num_entries);
+    assert_eq!(1, inner_stats.num_iterations);
+    assert_eq!(1, inner_stats.max_iterations);
+
+    // the cycles of the inner loop are included in the cycles of the outer loop
+    assert!(outer.num_cycles > inner_stats.num_cycles);
+    assert_eq!(report.num_cycles, outer.num_cycles);
+    assert_eq!(vec![outer, inner_stats], report.by_trace_cost());
+
+    // with a depth of zero, only the outer loop is reported, with the same totals
+    let shallow = analyze_loops(&program, &inputs, 0).unwrap();
+    assert_eq!(vec![outer.clone()], shallow.loops);
+}
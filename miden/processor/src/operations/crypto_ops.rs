@@ -1,4 +1,4 @@
-use vm_core::{Felt, StarkField};
+use vm_core::{Felt, StarkField, Word};
 
 use super::{ExecutionError, Operation, Process};
 
@@ -205,6 +205,35 @@ impl Process {
 
         Ok(())
     }
+
+    // MEMORY HASHING
+    // --------------------------------------------------------------------------------------------
+    /// Computes the hash of `len` consecutive words of memory starting at the specified address
+    /// in the specified context, and returns the result.
+    ///
+    /// The words are streamed from the memory chiplet into the hasher chiplet at the current
+    /// cycle, and the accesses of both chiplets are requested from the chiplets bus with a single
+    /// combined lookup. Thus, a large in-memory buffer can be hashed with one operation, instead
+    /// of loading every word onto the stack and absorbing it with `RPPERM`. The result is the
+    /// same as the hash of all elements of the words computed by
+    /// `hasher::hash_elements_in_domain()` in the domain of user hashes, which differs from the
+    /// hash of the same elements computed for code blocks.
+    ///
+    /// This must not be called in a cycle in which another operation requests a chiplet lookup.
+    ///
+    /// # Errors
+    /// Returns an error if the context is not the root context, if the region is empty or
+    /// extends beyond the largest valid address, or if the first address of the region is too
+    /// far away from all previously accessed addresses.
+    pub fn hash_memory(
+        &mut self,
+        ctx: u32,
+        addr: Felt,
+        len: usize,
+    ) -> Result<Word, ExecutionError> {
+        let (_addr, digest) = self.chiplets.hash_memory(ctx, addr, len)?;
+        Ok(digest)
+    }
 }
 
 // TESTS
@@ -214,12 +243,12 @@ impl Process {
 mod tests {
     use super::{
         super::{init_stack_with, Felt, FieldElement, Operation, StarkField},
-        Process,
+        ExecutionError, Process,
     };
     use crate::Word;
     use rand_utils::rand_vector;
     use vm_core::{
-        chiplets::hasher::{apply_permutation, STATE_WIDTH},
+        chiplets::hasher::{self, apply_permutation, STATE_WIDTH, USER_HASH_DOMAIN},
         AdviceSet, ProgramInputs,
     };
 
@@ -415,6 +444,49 @@ mod tests {
         assert!(process.advice.has_advice_set(new_tree.root()));
     }
 
+    #[test]
+    fn hash_memory() {
+        let mut process = Process::new_dummy();
+        let words = [init_leaf(1), init_leaf(2), init_leaf(3)];
+        for (i, &word) in words.iter().enumerate() {
+            process
+                .chiplets
+                .write_mem(Felt::new(10 + i as u64), word)
+                .unwrap();
+        }
+        let memory_len = process.chiplets.get_mem_size();
+
+        // --- an odd number of words is padded with ZEROs ----------------------------------------
+        let elements = words.iter().flatten().copied().collect::<Vec<_>>();
+        let expected: Word = hasher::hash_elements_in_domain(&elements, USER_HASH_DOMAIN).into();
+        assert_eq!(expected, process.hash_memory(0, Felt::new(10), 3).unwrap());
+
+        // --- an even number of words fills the rate of every permutation ------------------------
+        let expected: Word =
+            hasher::hash_elements_in_domain(&elements[..8], USER_HASH_DOMAIN).into();
+        assert_eq!(expected, process.hash_memory(0, Felt::new(10), 2).unwrap());
+
+        // --- words which were never written are read as ZEROs -----------------------------------
+        let expected: Word =
+            hasher::hash_elements_in_domain(&[Felt::ZERO; 4], USER_HASH_DOMAIN).into();
+        assert_eq!(expected, process.hash_memory(0, Felt::new(13), 1).unwrap());
+        assert_eq!(memory_len + 1, process.chiplets.get_mem_size());
+
+        // --- invalid regions are rejected -------------------------------------------------------
+        assert!(matches!(
+            process.hash_memory(1, Felt::new(10), 1),
+            Err(ExecutionError::InvalidMemoryContext(1))
+        ));
+        assert!(matches!(
+            process.hash_memory(0, Felt::new(10), 0),
+            Err(ExecutionError::InvalidMemoryRange(10, 0))
+        ));
+        assert!(matches!(
+            process.hash_memory(0, Felt::new(Felt::MODULUS - 1), 2),
+            Err(ExecutionError::InvalidMemoryRange(_, 2))
+        ));
+    }
+
     // HELPER FUNCTIONS
     // --------------------------------------------------------------------------------------------
     fn init_leaves(values: &[u64]) -> Vec<Word> {
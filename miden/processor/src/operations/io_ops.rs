@@ -32,7 +32,7 @@ impl Process {
     pub(super) fn op_mloadw(&mut self) -> Result<(), ExecutionError> {
         // get the address from the stack and read the word from memory
         let addr = self.stack.get(0);
-        let word = self.chiplets.read_mem(addr);
+        let word = self.chiplets.read_mem(addr)?;
 
         // update the stack state
         for (i, &value) in word.iter().rev().enumerate() {
@@ -57,7 +57,7 @@ impl Process {
     pub(super) fn op_mload(&mut self) -> Result<(), ExecutionError> {
         // get the address from the stack and read the word from memory
         let addr = self.stack.get(0);
-        let word = self.chiplets.read_mem(addr);
+        let word = self.chiplets.read_mem(addr)?;
 
         // update the stack state
         self.stack.set(0, word[0]);
@@ -92,7 +92,7 @@ impl Process {
         ];
 
         // write the word to memory and get the previous word
-        let old_word = self.chiplets.write_mem(addr, word);
+        let old_word = self.chiplets.write_mem(addr, word)?;
 
         // update the stack state
         for (i, &value) in word.iter().rev().enumerate() {
@@ -124,7 +124,7 @@ impl Process {
         let value = self.stack.get(1);
 
         // write the value to the memory and get the previous word
-        let old_word = self.chiplets.write_mem_single(addr, value);
+        let old_word = self.chiplets.write_mem_single(addr, value)?;
 
         self.decoder
             .set_user_op_helpers(Operation::MStore, &old_word);
@@ -1,4 +1,4 @@
-use super::{ExecutionError, Felt, FieldElement, Operation, Process, StarkField};
+use super::{ExecutionError, ExecutionReport, Felt, FieldElement, Operation, Process, StarkField};
 
 mod crypto_ops;
 mod field_ops;
@@ -16,12 +16,13 @@ impl Process {
     pub(super) fn execute_op(&mut self, op: Operation) -> Result<(), ExecutionError> {
         // make sure there is enough memory allocated to hold the execution trace
         self.ensure_trace_capacity();
+        self.notify_op(op);
 
         // execute the operation
         match op {
             // ----- system operations ------------------------------------------------------------
             Operation::Noop => self.stack.copy_state(0),
-            Operation::Assert => self.op_assert()?,
+            Operation::Assert(err_code) => self.op_assert(err_code)?,
 
             // ----- flow control operations ------------------------------------------------------
             // control flow operations are never executed directly
@@ -60,6 +61,11 @@ impl Process {
             Operation::U32and => self.op_u32and()?,
             Operation::U32or => self.op_u32or()?,
             Operation::U32xor => self.op_u32xor()?,
+
+            Operation::U64and => self.op_u64and()?,
+            Operation::U64or => self.op_u64or()?,
+            Operation::U64xor => self.op_u64xor()?,
+
             Operation::U32assert2 => self.op_u32assert2()?,
 
             // ----- stack manipulation -----------------------------------------------------------
@@ -127,9 +133,10 @@ impl Process {
             Operation::MrUpdate(copy) => self.op_mrupdate(copy)?,
         }
 
+        self.notify_chiplet_calls(self.system.clk());
         self.advance_clock();
-
-        Ok(())
+        self.charge_gas(op)?;
+        self.check_watchdog()
     }
 
     /// Increments the clock cycle for all components of the process.
@@ -140,6 +147,46 @@ impl Process {
         self.advice.advance_clock();
     }
 
+    /// Checks the watchdog of the process (if any) once every `check_interval` clock cycles.
+    ///
+    /// # Errors
+    /// Returns [ExecutionError::ExecutionAborted] if the watchdog requests the execution to be
+    /// aborted.
+    fn check_watchdog(&self) -> Result<(), ExecutionError> {
+        let clk = self.system.clk();
+        let reason = match &self.watchdog {
+            Some(watchdog) if clk % watchdog.check_interval() == 0 => watchdog.check(clk),
+            _ => None,
+        };
+
+        match reason {
+            Some(reason) => Err(ExecutionError::ExecutionAborted(ExecutionReport {
+                reason,
+                clk,
+                fmp: self.system.get_fmp_at(clk),
+                stack: self.stack.get_state_at(clk),
+            })),
+            None => Ok(()),
+        }
+    }
+
+    /// Charges the cost of the specified operation, including the cost of the chiplet rows added
+    /// since the previous operation, against the gas limit of the process (if any).
+    ///
+    /// # Errors
+    /// Returns [ExecutionError::OutOfGas] if the process consumed more gas than its limit.
+    fn charge_gas(&mut self, op: Operation) -> Result<(), ExecutionError> {
+        match self.gas.as_mut() {
+            Some(gas) => gas.charge(
+                op,
+                self.chiplets.hasher_trace_len(),
+                self.chiplets.bitwise_trace_len(),
+                self.chiplets.memory_trace_len(),
+            ),
+            None => Ok(()),
+        }
+    }
+
     /// Makes sure there is enough memory allocated for the trace to accommodate a new clock cycle.
     fn ensure_trace_capacity(&mut self) {
         self.system.ensure_trace_capacity();
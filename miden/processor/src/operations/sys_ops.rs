@@ -10,10 +10,12 @@ impl Process {
     /// Pops a value off the stack and asserts that it is equal to ONE.
     ///
     /// # Errors
-    /// Returns an error if the popped value is not ONE.
-    pub(super) fn op_assert(&mut self) -> Result<(), ExecutionError> {
+    /// Returns [ExecutionError::FailedAssertion] with the current clock cycle and the specified
+    /// error code if the popped value is not ONE. The error code is not committed in the outputs
+    /// of the program, since a failed execution cannot be proven.
+    pub(super) fn op_assert(&mut self, err_code: u32) -> Result<(), ExecutionError> {
         if self.stack.get(0) != Felt::ONE {
-            return Err(ExecutionError::FailedAssertion(self.system.clk()));
+            return Err(ExecutionError::FailedAssertion(self.system.clk(), err_code));
         }
         self.stack.shift_left(1);
         Ok(())
@@ -61,7 +63,7 @@ impl Process {
 mod tests {
     use super::{
         super::{init_stack_with, Operation},
-        Felt, FieldElement, Process, FMP_MAX, FMP_MIN,
+        ExecutionError, Felt, FieldElement, Process, FMP_MAX, FMP_MIN,
     };
 
     #[test]
@@ -72,7 +74,21 @@ mod tests {
         process.execute_op(Operation::Swap).unwrap();
         process.execute_op(Operation::Drop).unwrap();
 
-        assert!(process.execute_op(Operation::Assert).is_ok());
+        assert!(process.execute_op(Operation::Assert(0)).is_ok());
+    }
+
+    #[test]
+    fn op_assert_fails_with_err_code() {
+        // the error code of the failed assertion is reported together with the clock cycle
+        let mut process = Process::new_dummy();
+        process.execute_op(Operation::Pad).unwrap();
+        match process.execute_op(Operation::Assert(42)) {
+            Err(ExecutionError::FailedAssertion(clk, err_code)) => {
+                assert_eq!(1, clk);
+                assert_eq!(42, err_code);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
     }
 
     #[test]
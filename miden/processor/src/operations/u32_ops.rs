@@ -202,6 +202,57 @@ impl Process {
         Ok(())
     }
 
+    /// Computes a bitwise AND of two 64-bit values specified by their 32-bit limbs at the top of
+    /// the stack, and overwrites the limbs of the second value with the limbs of the result:
+    /// [b_hi, b_lo, a_hi, a_lo, ...] -> [b_hi, b_lo, c_hi, c_lo, ...], where c = a AND b.
+    pub(super) fn op_u64and(&mut self) -> Result<(), ExecutionError> {
+        let (a, b) = self.get_u64_operands();
+        let result = self.chiplets.u64and(a, b)?;
+        self.set_u64_result(b, result);
+
+        Ok(())
+    }
+
+    /// Computes a bitwise OR of two 64-bit values specified by their 32-bit limbs at the top of
+    /// the stack, and overwrites the limbs of the second value with the limbs of the result:
+    /// [b_hi, b_lo, a_hi, a_lo, ...] -> [b_hi, b_lo, c_hi, c_lo, ...], where c = a OR b.
+    pub(super) fn op_u64or(&mut self) -> Result<(), ExecutionError> {
+        let (a, b) = self.get_u64_operands();
+        let result = self.chiplets.u64or(a, b)?;
+        self.set_u64_result(b, result);
+
+        Ok(())
+    }
+
+    /// Computes a bitwise XOR of two 64-bit values specified by their 32-bit limbs at the top of
+    /// the stack, and overwrites the limbs of the second value with the limbs of the result:
+    /// [b_hi, b_lo, a_hi, a_lo, ...] -> [b_hi, b_lo, c_hi, c_lo, ...], where c = a XOR b.
+    pub(super) fn op_u64xor(&mut self) -> Result<(), ExecutionError> {
+        let (a, b) = self.get_u64_operands();
+        let result = self.chiplets.u64xor(a, b)?;
+        self.set_u64_result(b, result);
+
+        Ok(())
+    }
+
+    /// Returns the limbs (hi, lo) of the 64-bit operands `a` and `b` of a bitwise operation, which
+    /// are expected to be at the top of the stack as [b_hi, b_lo, a_hi, a_lo, ...].
+    fn get_u64_operands(&self) -> ([Felt; 2], [Felt; 2]) {
+        let b = [self.stack.get(0), self.stack.get(1)];
+        let a = [self.stack.get(2), self.stack.get(3)];
+        (a, b)
+    }
+
+    /// Keeps the limbs of operand `b` at the top of the stack, overwrites the limbs of operand `a`
+    /// with the limbs of the `result`, and copies the rest of the stack.
+    fn set_u64_result(&mut self, b: [Felt; 2], result: [Felt; 2]) {
+        self.stack.set(0, b[0]);
+        self.stack.set(1, b[1]);
+        self.stack.set(2, result[0]);
+        self.stack.set(3, result[1]);
+        self.stack.copy_state(4);
+    }
+
     /// Adds 16-bit range checks to the RangeChecker for the high and low 16-bit limbs of two field
     /// elements which are assumed to have 32-bit integer values. This results in 4 range checks.
     ///
@@ -459,6 +510,49 @@ mod tests {
         assert!(process.execute_op(Operation::U32xor).is_ok());
     }
 
+    #[test]
+    fn op_u64and() {
+        let mut process = Process::new_dummy_with_decoder_helpers();
+        let (b_hi, b_lo, a_hi, a_lo) = init_stack_rand(&mut process);
+
+        process.execute_op(Operation::U64and).unwrap();
+        let expected = build_expected(&[b_hi, b_lo, a_hi & b_hi, a_lo & b_lo]);
+        assert_eq!(expected, process.stack.trace_state());
+
+        // --- test with minimum stack depth ----------------------------------
+        let mut process = Process::new_dummy_with_decoder_helpers();
+        assert!(process.execute_op(Operation::U64and).is_ok());
+    }
+
+    #[test]
+    fn op_u64or() {
+        let mut process = Process::new_dummy_with_decoder_helpers();
+        let (b_hi, b_lo, a_hi, a_lo) = init_stack_rand(&mut process);
+
+        process.execute_op(Operation::U64or).unwrap();
+        let expected = build_expected(&[b_hi, b_lo, a_hi | b_hi, a_lo | b_lo]);
+        assert_eq!(expected, process.stack.trace_state());
+
+        // --- test with minimum stack depth ----------------------------------
+        let mut process = Process::new_dummy_with_decoder_helpers();
+        assert!(process.execute_op(Operation::U64or).is_ok());
+    }
+
+    #[test]
+    fn op_u64xor() {
+        let mut process = Process::new_dummy_with_decoder_helpers();
+        let (b_hi, b_lo, a_hi, a_lo) = init_stack_rand(&mut process);
+
+        process.execute_op(Operation::U64xor).unwrap();
+        let expected = build_expected(&[b_hi, b_lo, a_hi ^ b_hi, a_lo ^ b_lo]);
+        assert_eq!(expected, process.stack.trace_state());
+
+        // --- test with a limb which is not a 32-bit value -------------------
+        let mut process = Process::new_dummy_with_decoder_helpers();
+        init_stack_with(&mut process, &[1, 2, u32::MAX as u64 + 1, 4]);
+        assert!(process.execute_op(Operation::U64xor).is_err());
+    }
+
     // HELPER FUNCTIONS
     // --------------------------------------------------------------------------------------------
 
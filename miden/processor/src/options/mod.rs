@@ -0,0 +1,102 @@
+use super::{
+    Box, ExecutionHooks, GasCosts, PaddingPolicy, Process, ProgramInputs, ProgramLimits, Watchdog,
+};
+
+#[cfg(test)]
+mod tests;
+
+// EXECUTION OPTIONS
+// ================================================================================================
+
+/// Options controlling how a program is executed by [super::execute_with_options].
+///
+/// By default, the program is executed in the same way as by [super::execute]: it is
+/// not checked against any limits, it is executed without a watchdog or hooks, gas is charged
+/// according to the default cost table (if a gas limit was provided with the inputs), and the
+/// trace is padded according to the default [PaddingPolicy]. The options are set using the
+/// `with_*` methods, and can be combined freely.
+#[derive(Default)]
+pub struct ExecutionOptions {
+    limits: Option<ProgramLimits>,
+    watchdog: Option<Watchdog>,
+    hooks: Option<Box<dyn ExecutionHooks>>,
+    gas_costs: Option<GasCosts>,
+    padding: PaddingPolicy,
+}
+
+impl ExecutionOptions {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns options which execute a program in the same way as [super::execute].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns these options with the program checked against the specified limits before it is
+    /// executed.
+    pub fn with_limits(mut self, limits: ProgramLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Returns these options with the execution periodically checked by the specified watchdog,
+    /// and aborted if the watchdog's cancellation token was cancelled or if the execution runs
+    /// for too many cycles.
+    pub fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Returns these options with the specified hooks notified of the events of the execution as
+    /// it happens; see [ExecutionHooks] for details.
+    pub fn with_hooks(mut self, hooks: Box<dyn ExecutionHooks>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Returns these options with the gas consumed by the execution charged according to the
+    /// specified cost table. Gas is charged only if a gas limit was provided with the inputs.
+    pub fn with_gas_costs(mut self, costs: GasCosts) -> Self {
+        self.gas_costs = Some(costs);
+        self
+    }
+
+    /// Returns these options with the execution trace padded according to the specified policy.
+    pub fn with_padding(mut self, padding: PaddingPolicy) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the limits the program is checked against before it is executed, if any.
+    pub fn limits(&self) -> Option<&ProgramLimits> {
+        self.limits.as_ref()
+    }
+
+    /// Returns the policy according to which the execution trace is padded.
+    pub fn padding(&self) -> PaddingPolicy {
+        self.padding
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new process with the provided inputs, into which the watchdog, the hooks, and
+    /// the gas cost table of these options are moved.
+    pub(super) fn into_process(self, inputs: ProgramInputs) -> Process {
+        let mut process = Process::new(inputs);
+        if let Some(watchdog) = self.watchdog {
+            process = process.with_watchdog(watchdog);
+        }
+        if let Some(hooks) = self.hooks {
+            process = process.with_hooks(hooks);
+        }
+        if let Some(costs) = self.gas_costs {
+            process = process.with_gas_costs(costs);
+        }
+        process
+    }
+}
@@ -0,0 +1,58 @@
+use super::{
+    super::{execute, execute_with_options, ExecutionError, Program, ValidationError},
+    ExecutionOptions, GasCosts, PaddingPolicy, ProgramInputs, ProgramLimits, Watchdog,
+};
+use vm_core::{code_blocks::CodeBlock, Operation, ONE};
+use winterfell::Trace;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn default_options_match_execute() {
+    let program = build_program();
+    let inputs = ProgramInputs::none();
+    let trace = execute_with_options(&program, &inputs, ExecutionOptions::new()).unwrap();
+    let expected = execute(&program, &inputs).unwrap();
+    assert_eq!(expected.length(), trace.length());
+    assert_eq!(expected.last_stack_state(), trace.last_stack_state());
+}
+
+#[test]
+fn limits_are_checked_before_execution() {
+    let limits = ProgramLimits::default().with_disallowed_op(Operation::Incr);
+    let options = ExecutionOptions::new().with_limits(limits);
+    let result = execute_with_options(&build_program(), &ProgramInputs::none(), options);
+    assert!(matches!(
+        result,
+        Err(ExecutionError::InvalidProgram(
+            ValidationError::DisallowedOperation(Operation::Incr)
+        ))
+    ));
+}
+
+#[test]
+fn options_are_combined() {
+    let costs = GasCosts::new()
+        .with_op_cost(Operation::Incr, 10)
+        .with_hasher_row_cost(0);
+    let watchdog = Watchdog::new().with_max_cycles(1000);
+    let options = ExecutionOptions::new()
+        .with_limits(ProgramLimits::default())
+        .with_watchdog(watchdog)
+        .with_gas_costs(costs)
+        .with_padding(PaddingPolicy::new().with_min_trace_len(1024));
+
+    let inputs = ProgramInputs::none().with_gas_limit(1000);
+    let trace = execute_with_options(&build_program(), &inputs, options).unwrap();
+    assert_eq!(Some(13), trace.gas_used());
+    assert_eq!(1024, trace.length());
+    assert_eq!(ONE, trace.last_stack_state()[0]);
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_program() -> Program {
+    Program::new(CodeBlock::new_span(vec![Operation::Pad, Operation::Incr]))
+}
@@ -1,5 +1,4 @@
-use super::{Felt, FieldElement};
-use crate::trace::LookupTableRow;
+use super::Felt;
 
 // PROCESSOR RANGE CHECKS
 // ================================================================================================
@@ -17,12 +16,13 @@ impl CycleRangeChecks {
     // --------------------------------------------------------------------------------------------
 
     /// Creates a new set of cycle range checks from range checks requested by the Memory processor.
-    pub fn new_from_memory(values: &[u16; 2]) -> Self {
+    pub fn new_from_memory(values: &[u16; 3]) -> Self {
         Self {
             stack: None,
             memory: Some(RangeCheckRequest::Memory([
                 Felt::from(values[0]),
                 Felt::from(values[1]),
+                Felt::from(values[2]),
             ])),
         }
     }
@@ -50,48 +50,26 @@ impl CycleRangeChecks {
         self.memory = Some(RangeCheckRequest::Memory([
             Felt::from(values[0]),
             Felt::from(values[1]),
+            Felt::from(values[2]),
         ]));
     }
 
     // ACCESSORS
     // --------------------------------------------------------------------------------------------
 
-    /// Reduces all range checks requested at this cycle by the Stack processor to a single field
-    /// element in the field specified by E.
-    pub fn to_stack_value<E: FieldElement<BaseField = Felt>>(&self, alphas: &[E]) -> E {
-        let mut value = E::ONE;
-
-        if let Some(stack_checks) = &self.stack {
-            value *= stack_checks.to_value(alphas);
+    /// Returns the values range-checked at this cycle by the Stack processor.
+    pub fn stack_values(&self) -> &[Felt] {
+        match &self.stack {
+            Some(stack_checks) => stack_checks.values(),
+            None => &[],
         }
-
-        value
     }
 
-    /// Reduces all range checks requested at this cycle by the Memory processor to a single field
-    /// element in the field specified by E.
-    fn to_mem_value<E: FieldElement<BaseField = Felt>>(&self, alphas: &[E]) -> E {
-        let mut value = E::ONE;
-
-        if let Some(mem_checks) = &self.memory {
-            value = mem_checks.to_value(alphas);
-        }
-
-        value
-    }
-}
-
-impl LookupTableRow for CycleRangeChecks {
-    /// Reduces this row to a single field element in the field specified by E. This requires
-    /// at least 1 alpha value. Includes all values included at this cycle from all processors.
-    fn to_value<E: FieldElement<BaseField = Felt>>(&self, alphas: &[E]) -> E {
-        let stack_value = self.to_stack_value(alphas);
-        let mem_value = self.to_mem_value(alphas);
-
-        if stack_value != E::ONE {
-            stack_value * mem_value
-        } else {
-            mem_value
+    /// Returns the values range-checked at this cycle by the Memory processor.
+    pub fn memory_values(&self) -> &[Felt] {
+        match &self.memory {
+            Some(mem_checks) => mem_checks.values(),
+            None => &[],
         }
     }
 }
@@ -100,26 +78,16 @@ impl LookupTableRow for CycleRangeChecks {
 /// range-checked.
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum RangeCheckRequest {
-    Memory([Felt; 2]),
+    Memory([Felt; 3]),
     Stack([Felt; 4]),
 }
 
-impl LookupTableRow for RangeCheckRequest {
-    /// Reduces this row to a single field element in the field specified by E. This requires
-    /// at least 1 alpha value.
-    fn to_value<E: FieldElement<BaseField = Felt>>(&self, alphas: &[E]) -> E {
-        let alpha: E = alphas[0];
-        let mut value = E::ONE;
-
-        let values_iter = match self {
-            Self::Memory(values) => values.iter(),
-            Self::Stack(values) => values.iter(),
-        };
-
-        for &rc_value in values_iter {
-            value *= alpha + rc_value.into();
+impl RangeCheckRequest {
+    /// Returns the values to be range-checked by this request.
+    fn values(&self) -> &[Felt] {
+        match self {
+            Self::Memory(values) => values,
+            Self::Stack(values) => values,
         }
-
-        value
     }
 }
@@ -30,13 +30,37 @@ fn range_checks() {
     validate_row(&trace, i, 0, 1);
     validate_row(&trace, i + 1, 1, 1);
     validate_row(&trace, i + 2, 2, 4);
-    validate_row(&trace, i + 3, 3, 2);
-    validate_row(&trace, i + 4, 3, 1);
-    validate_row(&trace, i + 5, 4, 2);
-    validate_row(&trace, i + 6, 100, 1);
-    validate_row(&trace, i + 7, 355, 1);
-    validate_row(&trace, i + 8, 610, 0);
-    validate_row(&trace, i + 9, 620, 1);
+    validate_row(&trace, i + 3, 3, 3);
+    validate_row(&trace, i + 4, 4, 2);
+    validate_row(&trace, i + 5, 100, 1);
+    validate_row(&trace, i + 6, 355, 1);
+    validate_row(&trace, i + 7, 610, 0);
+    validate_row(&trace, i + 8, 620, 1);
+}
+
+#[test]
+fn range_checks_many_lookups() {
+    let mut checker = RangeChecker::new();
+    let values = [7u16, 300, 65000];
+
+    // every value takes a single row in the 16-bit segment regardless of the number of lookups
+    let trace_len_before = checker.trace_len();
+    for _ in 0..100 {
+        for &value in values.iter() {
+            checker.add_value(value);
+        }
+    }
+    assert_eq!(trace_len_before + values.len(), checker.trace_len());
+
+    let lookups = values
+        .iter()
+        .flat_map(|&value| [Felt::from(value); 100])
+        .collect::<Vec<_>>();
+    let RangeCheckTrace {
+        trace,
+        aux_builder: _,
+    } = checker.into_trace(1024, 0);
+    validate_trace(&trace, &lookups);
 }
 
 #[test]
@@ -64,21 +88,12 @@ fn range_checks_rand() {
 // ================================================================================================
 
 fn validate_row(trace: &[Vec<Felt>], row_idx: usize, value: u64, num_lookups: u64) {
-    let (s0, s1) = match num_lookups {
-        0 => (Felt::ZERO, Felt::ZERO),
-        1 => (Felt::ONE, Felt::ZERO),
-        2 => (Felt::ZERO, Felt::ONE),
-        4 => (Felt::ONE, Felt::ONE),
-        _ => panic!("invalid lookup value"),
-    };
-
-    assert_eq!(s0, trace[1][row_idx]);
-    assert_eq!(s1, trace[2][row_idx]);
-    assert_eq!(Felt::new(value), trace[3][row_idx]);
+    assert_eq!(Felt::new(num_lookups), trace[1][row_idx]);
+    assert_eq!(Felt::new(value), trace[2][row_idx]);
 }
 
 fn validate_trace(trace: &[Vec<Felt>], lookups: &[Felt]) {
-    assert_eq!(4, trace.len());
+    assert_eq!(3, trace.len());
 
     // trace length must be a power of two
     let trace_len = get_trace_len(trace);
@@ -88,7 +103,7 @@ fn validate_trace(trace: &[Vec<Felt>], lookups: &[Felt]) {
 
     // should start with a ZERO
     assert_eq!(Felt::ZERO, trace[0][0]);
-    assert_eq!(Felt::ZERO, trace[3][0]);
+    assert_eq!(Felt::ZERO, trace[2][0]);
 
     let mut lookups_8bit = BTreeMap::new();
 
@@ -98,7 +113,7 @@ fn validate_trace(trace: &[Vec<Felt>], lookups: &[Felt]) {
     let mut prev_value = 0u8;
     while trace[0][i] == Felt::ZERO {
         // make sure the value is an 8-bit value
-        let value = trace[3][i].as_int();
+        let value = trace[2][i].as_int();
         assert!(value <= 255, "not an 8-bit value");
         let value = value as u8;
 
@@ -118,7 +133,7 @@ fn validate_trace(trace: &[Vec<Felt>], lookups: &[Felt]) {
     }
 
     // validate the last row (must be 255)
-    let last_value = trace[3][i - 1].as_int();
+    let last_value = trace[2][i - 1].as_int();
     assert_eq!(255, last_value);
 
     // all possible 8-bit values must be in the map
@@ -129,7 +144,7 @@ fn validate_trace(trace: &[Vec<Felt>], lookups: &[Felt]) {
     let mut lookups_16bit = BTreeMap::new();
 
     // process the first row
-    assert_eq!(Felt::ZERO, trace[3][i]);
+    assert_eq!(Felt::ZERO, trace[2][i]);
     let count = get_lookup_count(trace, i);
     lookups_16bit.insert(0u16, count);
     i += 1;
@@ -141,8 +156,8 @@ fn validate_trace(trace: &[Vec<Felt>], lookups: &[Felt]) {
         assert_eq!(Felt::ONE, trace[0][i]);
 
         // make sure the value is a 16-bit value
-        let value = trace[3][i].as_int();
-        assert!(value <= 65535, "not an 8-bit value");
+        let value = trace[2][i].as_int();
+        assert!(value <= 65535, "not a 16-bit value");
         let value = value as u16;
 
         // make sure the delta between this and the previous value is less than 255,
@@ -167,7 +182,7 @@ fn validate_trace(trace: &[Vec<Felt>], lookups: &[Felt]) {
     }
 
     // validate the last row (must be 65535)
-    let last_value = trace[3][i - 1].as_int();
+    let last_value = trace[2][i - 1].as_int();
     assert_eq!(65535, last_value);
 
     // at the end, 8-bit table should be empty
@@ -195,15 +210,5 @@ fn validate_trace(trace: &[Vec<Felt>], lookups: &[Felt]) {
 }
 
 fn get_lookup_count(trace: &[Vec<Felt>], step: usize) -> usize {
-    if trace[1][step] == Felt::ZERO && trace[2][step] == Felt::ZERO {
-        0
-    } else if trace[1][step] == Felt::ONE && trace[2][step] == Felt::ZERO {
-        1
-    } else if trace[1][step] == Felt::ZERO && trace[2][step] == Felt::ONE {
-        2
-    } else if trace[1][step] == Felt::ONE && trace[2][step] == Felt::ONE {
-        4
-    } else {
-        panic!("not a valid count");
-    }
+    trace[1][step].as_int() as usize
 }
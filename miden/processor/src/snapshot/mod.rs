@@ -0,0 +1,228 @@
+use super::{
+    gas::GasMeter, AdviceProvider, Chiplets, Continuation, Decoder, ExecutionError, ExecutionTrace,
+    Process, Program, ProgramInputs, RangeChecker, Stack, System, Vec,
+};
+
+#[cfg(test)]
+mod tests;
+
+// PROCESS SNAPSHOT
+// ================================================================================================
+
+/// State of a [Process] captured at a specific clock cycle.
+///
+/// A snapshot holds the state of the stack, memory, and advice provider, as well as the partial
+/// execution traces of the system, the decoder, the range checker, and the chiplets accumulated
+/// up to the cycle at which it was taken. Restoring a snapshot brings the process back to this
+/// cycle, so that tools can execute speculatively and backtrack without re-running the program
+/// from the start. The gas consumed up to this cycle is captured as well, so that gas consumed
+/// by a backtracked execution is refunded on restore.
+///
+/// The pages of the memory are shared between a snapshot and the process it was taken from, and
+/// copied only when the process accesses them, so taking and restoring snapshots does not copy
+/// the entire memory.
+///
+/// The watchdog of the process, if any, is not a part of its state, and thus, it is neither
+/// captured nor restored.
+#[derive(Clone)]
+pub struct ProcessSnapshot {
+    system: System,
+    decoder: Decoder,
+    stack: Stack,
+    range: RangeChecker,
+    chiplets: Chiplets,
+    advice: AdviceProvider,
+    gas: Option<GasMeter>,
+}
+
+impl ProcessSnapshot {
+    /// Returns the clock cycle at which this snapshot was taken.
+    pub fn clk(&self) -> usize {
+        self.system.clk()
+    }
+}
+
+impl Process {
+    // SNAPSHOTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a snapshot of the current state of this process.
+    pub fn snapshot(&self) -> ProcessSnapshot {
+        ProcessSnapshot {
+            system: self.system.clone(),
+            decoder: self.decoder.clone(),
+            stack: self.stack.clone(),
+            range: self.range.clone(),
+            chiplets: self.chiplets.clone(),
+            advice: self.advice.clone(),
+            gas: self.gas.clone(),
+        }
+    }
+
+    /// Brings this process back to the state captured by the provided snapshot.
+    ///
+    /// The snapshot is expected to be taken from this process, or from a process created with
+    /// the same inputs; the watchdog and the hooks of this process are kept, and the call stack of
+    /// a failed execution is cleared.
+    pub fn restore(&mut self, snapshot: &ProcessSnapshot) {
+        self.system = snapshot.system.clone();
+        self.decoder = snapshot.decoder.clone();
+        self.stack = snapshot.stack.clone();
+        self.range = snapshot.range.clone();
+        self.chiplets = snapshot.chiplets.clone();
+        self.advice = snapshot.advice.clone();
+        self.gas = snapshot.gas.clone();
+        self.call_stack = None;
+        if let Some(hooks) = self.hooks.as_mut() {
+            hooks.sync(&self.chiplets);
+        }
+    }
+}
+
+// PROGRAM EXECUTION
+// ================================================================================================
+
+/// An execution of a program which is advanced one step at a time.
+///
+/// Every step either executes a SPAN block in full, or starts or ends a control block (JOIN,
+/// SPLIT, or LOOP). Between steps, the state of the process can be inspected, and the execution
+/// can be captured in an [ExecutionSnapshot] and later restored to resume the execution from
+/// that point.
+pub struct ProgramExecution<'a> {
+    program: &'a Program,
+    process: Process,
+    continuations: Vec<Continuation<'a>>,
+}
+
+impl<'a> ProgramExecution<'a> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an execution of the provided program against the provided inputs, which has not
+    /// executed any steps yet.
+    pub fn new(program: &'a Program, inputs: ProgramInputs) -> Self {
+        Self::with_process(program, Process::new(inputs))
+    }
+
+    /// Returns an execution of the provided program in the provided process.
+    ///
+    /// # Panics
+    /// Panics if a program has already been executed in the provided process.
+    pub fn with_process(program: &'a Program, process: Process) -> Self {
+        assert_eq!(
+            process.system.clk(),
+            0,
+            "a program has already been executed in this process"
+        );
+        Self {
+            program,
+            process,
+            continuations: vec![Continuation::Start(program.root())],
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the process in which the program is executed.
+    pub fn process(&self) -> &Process {
+        &self.process
+    }
+
+    /// Returns the current clock cycle of the execution.
+    pub fn clk(&self) -> usize {
+        self.process.system.clk()
+    }
+
+    /// Returns true if all steps of the execution have been executed.
+    pub fn is_finished(&self) -> bool {
+        self.continuations.is_empty()
+    }
+
+    // EXECUTION
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes the next step of the execution, and returns true if there are steps left to
+    /// execute.
+    ///
+    /// If the step fails, the execution is left in an unspecified state, but it can be brought
+    /// back to a snapshot taken before the failure.
+    ///
+    /// # Errors
+    /// Returns an [ExecutionError] if executing the step fails for any reason.
+    ///
+    /// # Panics
+    /// Panics if the execution is already finished.
+    pub fn step(&mut self) -> Result<bool, ExecutionError> {
+        let continuation = self
+            .continuations
+            .pop()
+            .expect("the execution is already finished");
+        self.process
+            .execute_step(continuation, &mut self.continuations)?;
+        Ok(!self.is_finished())
+    }
+
+    /// Executes all remaining steps of the execution, and returns the execution trace of the
+    /// program.
+    ///
+    /// # Errors
+    /// Returns an [ExecutionError] if executing any of the remaining steps fails.
+    pub fn finish(mut self) -> Result<ExecutionTrace, ExecutionError> {
+        while !self.is_finished() {
+            self.step()?;
+        }
+
+        let trace = ExecutionTrace::new(self.process);
+        assert_eq!(
+            self.program.hash(),
+            trace.program_hash(),
+            "inconsistent program hash"
+        );
+        Ok(trace)
+    }
+
+    // SNAPSHOTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a snapshot of the current state of this execution.
+    pub fn snapshot(&self) -> ExecutionSnapshot<'a> {
+        ExecutionSnapshot {
+            process: self.process.snapshot(),
+            continuations: self.continuations.clone(),
+        }
+    }
+
+    /// Brings this execution back to the state captured by the provided snapshot, so that the
+    /// execution resumes from the step at which the snapshot was taken.
+    ///
+    /// The snapshot is expected to be taken from an execution of the same program against the
+    /// same inputs.
+    pub fn restore(&mut self, snapshot: &ExecutionSnapshot<'a>) {
+        self.process.restore(&snapshot.process);
+        self.continuations = snapshot.continuations.clone();
+    }
+}
+
+// EXECUTION SNAPSHOT
+// ================================================================================================
+
+/// State of a [ProgramExecution] captured between two of its steps: the state of the process,
+/// together with the steps which remain to be executed.
+#[derive(Clone)]
+pub struct ExecutionSnapshot<'a> {
+    process: ProcessSnapshot,
+    continuations: Vec<Continuation<'a>>,
+}
+
+impl<'a> ExecutionSnapshot<'a> {
+    /// Returns the clock cycle at which this snapshot was taken.
+    pub fn clk(&self) -> usize {
+        self.process.clk()
+    }
+
+    /// Returns the snapshot of the process captured by this snapshot.
+    pub fn process(&self) -> &ProcessSnapshot {
+        &self.process
+    }
+}
@@ -0,0 +1,118 @@
+use super::{
+    super::{execute, ExecutionError, Program, ProgramInputs},
+    ProgramExecution,
+};
+use vm_core::{code_blocks::CodeBlock, Felt, Operation, ONE};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn step_through_program() {
+    let program = build_program();
+    let expected = execute(&program, &build_inputs()).unwrap();
+
+    // stepping through the program results in the same trace as executing it directly
+    let mut execution = ProgramExecution::new(&program, build_inputs());
+    let mut num_steps = 0;
+    while execution.step().unwrap() {
+        num_steps += 1;
+    }
+    assert!(execution.is_finished());
+    assert!(num_steps > 0);
+
+    let trace = execution.finish().unwrap();
+    assert_eq!(expected.hash(), trace.hash());
+    assert_eq!(ONE, trace.last_stack_state()[0]);
+}
+
+#[test]
+fn restore_snapshot() {
+    let program = build_program();
+    let expected = execute(&program, &build_inputs()).unwrap();
+
+    // take a snapshot in the middle of the execution, and run the execution to the end
+    let mut execution = ProgramExecution::new(&program, build_inputs());
+    for _ in 0..4 {
+        execution.step().unwrap();
+    }
+    let snapshot = execution.snapshot();
+    let snapshot_clk = execution.clk();
+    assert_eq!(snapshot_clk, snapshot.clk());
+    assert_eq!(snapshot_clk, snapshot.process().clk());
+
+    while execution.step().unwrap() {}
+    assert!(execution.clk() > snapshot_clk);
+
+    // after restoring the snapshot, the execution resumes from the cycle of the snapshot and
+    // results in the same trace
+    execution.restore(&snapshot);
+    assert_eq!(snapshot_clk, execution.clk());
+    assert!(!execution.is_finished());
+
+    let trace = execution.finish().unwrap();
+    assert_eq!(expected.hash(), trace.hash());
+}
+
+#[test]
+fn restore_after_failure() {
+    // the SPLIT block fails because its condition is not a binary value
+    let program = Program::new(CodeBlock::new_join([
+        CodeBlock::new_span(vec![Operation::Push(Felt::new(2))]),
+        CodeBlock::new_split(
+            CodeBlock::new_span(vec![Operation::Pad]),
+            CodeBlock::new_span(vec![Operation::Noop]),
+        ),
+    ]));
+
+    let mut execution = ProgramExecution::new(&program, build_inputs());
+    execution.step().unwrap();
+    execution.step().unwrap();
+    let snapshot = execution.snapshot();
+
+    let result = execution.step();
+    assert!(matches!(result, Err(ExecutionError::NotBinaryValue(value)) if value == Felt::new(2)));
+
+    // the failed step can be retried from the snapshot
+    execution.restore(&snapshot);
+    assert_eq!(snapshot.clk(), execution.clk());
+    assert!(execution.step().is_err());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a program which executes a SPLIT block, and then a LOOP block which counts down from 3
+/// to 0.
+fn build_program() -> Program {
+    let init = CodeBlock::new_span(vec![
+        Operation::Push(Felt::new(3)),
+        Operation::Pad,
+        Operation::Incr,
+    ]);
+    let split = CodeBlock::new_split(
+        CodeBlock::new_span(vec![Operation::Pad, Operation::Incr]),
+        CodeBlock::new_span(vec![Operation::Pad]),
+    );
+    let countdown = CodeBlock::new_loop(CodeBlock::new_span(vec![
+        Operation::Pad,
+        Operation::Incr,
+        Operation::Neg,
+        Operation::Add,
+        Operation::Dup0,
+        Operation::Eqz,
+        Operation::Not,
+    ]));
+    // drop the counter, so that the stack input is on top of the stack and the stack overflow is
+    // empty on exit
+    let cleanup = CodeBlock::new_span(vec![Operation::Drop]);
+
+    Program::new(CodeBlock::new_join([
+        CodeBlock::new_join([init, split]),
+        CodeBlock::new_join([countdown, cleanup]),
+    ]))
+}
+
+fn build_inputs() -> ProgramInputs {
+    ProgramInputs::new(&[1], &[], vec![]).unwrap()
+}
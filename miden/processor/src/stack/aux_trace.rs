@@ -1,5 +1,6 @@
 use super::{
-    super::trace::AuxColumnBuilder, Felt, FieldElement, OverflowTableRow, OverflowTableUpdate, Vec,
+    super::trace::{build_lookup_table_row_values, AuxColumnBuilder},
+    Felt, FieldElement, OverflowTableRow, OverflowTableUpdate, Vec,
 };
 use winterfell::Matrix;
 
@@ -29,15 +30,7 @@ impl AuxTraceBuilder {
 // OVERFLOW TABLE
 // ================================================================================================
 
-impl AuxColumnBuilder<OverflowTableUpdate, OverflowTableRow> for AuxTraceBuilder {
-    /// Returns a list of rows which were added to and then removed from the stack overflow table.
-    ///
-    /// The order of the rows in the list is the same as the order in which the rows were added to
-    /// the table.
-    fn get_table_rows(&self) -> &[OverflowTableRow] {
-        &self.overflow_table_rows
-    }
-
+impl AuxColumnBuilder<OverflowTableUpdate> for AuxTraceBuilder {
     /// Returns hints which describe how the stack overflow table was updated during program
     /// execution. Each update hint is accompanied by a clock cycle at which the update happened.
     ///
@@ -64,4 +57,15 @@ impl AuxColumnBuilder<OverflowTableUpdate, OverflowTableRow> for AuxTraceBuilder
             }
         }
     }
+
+    /// Builds the row values and inverse values of the rows which were added to and then removed
+    /// from the stack overflow table.
+    ///
+    /// The order of the rows is the same as the order in which the rows were added to the table.
+    fn build_row_values<E>(&self, _main_trace: &Matrix<Felt>, alphas: &[E]) -> (Vec<E>, Vec<E>)
+    where
+        E: FieldElement<BaseField = Felt>,
+    {
+        build_lookup_table_row_values(&self.overflow_table_rows, alphas)
+    }
 }
@@ -52,6 +52,7 @@ const MAX_TOP_IDX: usize = MIN_STACK_DEPTH - 1;
 /// - Helper column h0 is used to ensure that stack depth does not drop below 16. Values in this
 ///   column are set by the prover non-deterministically to 1 / (b0−16) when b0 != 16, and to any
 ///   other value otherwise.
+#[derive(Clone)]
 pub struct Stack {
     clk: usize,
     trace: StackTrace,
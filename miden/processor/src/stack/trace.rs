@@ -13,6 +13,7 @@ use vm_core::StarkField;
 /// The trace consists of 19 columns grouped logically as follows:
 /// - 16 stack columns holding the top of the stack.
 /// - 3 columns for bookkeeping and helper values that manage left and right shifts.
+#[derive(Clone)]
 pub struct StackTrace {
     stack: [Vec<Felt>; MIN_STACK_DEPTH],
     helpers: [Vec<Felt>; NUM_STACK_HELPER_COLS],
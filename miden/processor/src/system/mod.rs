@@ -14,6 +14,7 @@ pub const FMP_MAX: u64 = FMP_MIN + u32::MAX as u64;
 /// System info container for the VM.
 ///
 /// Currently, this keeps track of the clock cycle and free memory pointer registers.
+#[derive(Clone)]
 pub struct System {
     clk: usize,
     clk_trace: Vec<Felt>,
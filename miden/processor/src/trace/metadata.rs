@@ -0,0 +1,260 @@
+use super::{ExecutionTrace, Felt, Vec};
+use vm_core::{
+    chiplets::{bitwise, hasher, memory, HASHER_ROW_COL_IDX},
+    decoder::{NUM_HASHER_COLUMNS, NUM_OP_BATCH_FLAGS, NUM_OP_BITS},
+    range,
+    utils::string::String,
+    StarkField, CHIPLETS_OFFSET, CHIPLETS_WIDTH, CLK_COL_IDX, FMP_COL_IDX, MIN_STACK_DEPTH,
+    STACK_TRACE_OFFSET, TRACE_WIDTH,
+};
+
+// TRACE METADATA
+// ================================================================================================
+
+/// Step of the execution trace at which a boundary assertion is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssertionStep {
+    /// The first row of the trace.
+    First,
+    /// The last row of the trace which is not injected with random values.
+    Last,
+}
+
+/// Boundary assertion which the AIR places on a column of the main trace, together with the value
+/// of the column at the asserted step of this trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoundaryAssertion {
+    pub step: AssertionStep,
+    pub value: Felt,
+}
+
+/// Metadata of a single column of the main trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnInfo {
+    /// Index of the column in the main trace.
+    pub index: usize,
+    /// Component of the VM which generates the column.
+    pub owner: &'static str,
+    /// Semantic name of the column. Chiplet columns are shared between the chiplets, and thus,
+    /// their names consist of the names the column has in every chiplet separated by `/`.
+    pub name: String,
+    /// Boundary assertions placed on the column.
+    pub assertions: Vec<BoundaryAssertion>,
+}
+
+/// Per-column metadata of the main trace of an execution, which enables external tooling to
+/// generate or validate constraint code against the actual trace layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceMetadata {
+    /// Number of rows in the trace.
+    pub trace_len: usize,
+    /// Index of the row at which the last-step boundary assertions are placed.
+    pub last_step: usize,
+    /// Metadata of every column of the main trace, ordered by column index.
+    pub columns: Vec<ColumnInfo>,
+}
+
+impl TraceMetadata {
+    /// Returns the metadata serialized as a JSON object. Column owners and names consist of
+    /// ASCII identifiers only, and thus, no escaping is needed.
+    pub fn to_json(&self) -> String {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| {
+                let assertions = column
+                    .assertions
+                    .iter()
+                    .map(|assertion| {
+                        let step = match assertion.step {
+                            AssertionStep::First => "first",
+                            AssertionStep::Last => "last",
+                        };
+                        format!(
+                            "{{\"step\":\"{}\",\"value\":{}}}",
+                            step,
+                            assertion.value.as_int()
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "{{\"index\":{},\"owner\":\"{}\",\"name\":\"{}\",\"assertions\":[{}]}}",
+                    column.index,
+                    column.owner,
+                    column.name,
+                    assertions.join(",")
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"trace_len\":{},\"last_step\":{},\"columns\":[{}]}}",
+            self.trace_len,
+            self.last_step,
+            columns.join(",")
+        )
+    }
+}
+
+// METADATA BUILDER
+// ================================================================================================
+
+/// Builds the metadata of the main trace of the provided execution trace; boundary assertions on
+/// the stack are placed on the specified number of stack inputs and outputs.
+pub(super) fn build_metadata(
+    trace: &ExecutionTrace,
+    num_stack_inputs: usize,
+    num_stack_outputs: usize,
+) -> TraceMetadata {
+    let last_step = trace.last_step();
+    let mut columns = build_column_layout();
+
+    // boundary assertions mirror the assertions of the processor AIR
+    let mut add_assertion = |col_idx: usize, step: AssertionStep| {
+        let row = match step {
+            AssertionStep::First => 0,
+            AssertionStep::Last => last_step,
+        };
+        let value = trace.main_trace.get(col_idx, row);
+        columns[col_idx]
+            .assertions
+            .push(BoundaryAssertion { step, value });
+    };
+    add_assertion(CLK_COL_IDX, AssertionStep::First);
+    add_assertion(FMP_COL_IDX, AssertionStep::First);
+    for i in 0..num_stack_inputs.min(MIN_STACK_DEPTH) {
+        add_assertion(STACK_TRACE_OFFSET + i, AssertionStep::First);
+    }
+    add_assertion(range::V_COL_IDX, AssertionStep::First);
+    add_assertion(HASHER_ROW_COL_IDX, AssertionStep::First);
+    for i in 0..num_stack_outputs.min(MIN_STACK_DEPTH) {
+        add_assertion(STACK_TRACE_OFFSET + i, AssertionStep::Last);
+    }
+    add_assertion(range::V_COL_IDX, AssertionStep::Last);
+
+    TraceMetadata {
+        trace_len: trace.main_trace.num_rows(),
+        last_step,
+        columns,
+    }
+}
+
+/// Returns the owners and names of all columns of the main trace, without boundary assertions.
+fn build_column_layout() -> Vec<ColumnInfo> {
+    let mut columns = Vec::with_capacity(TRACE_WIDTH);
+    let mut push = |owner: &'static str, name: String| {
+        let index = columns.len();
+        columns.push(ColumnInfo {
+            index,
+            owner,
+            name,
+            assertions: Vec::new(),
+        });
+    };
+
+    // --- system ---------------------------------------------------------------------------------
+    push("system", "clk".into());
+    push("system", "fmp".into());
+
+    // --- decoder --------------------------------------------------------------------------------
+    push("decoder", "addr".into());
+    for i in 0..NUM_OP_BITS {
+        push("decoder", format!("op_bit{}", i));
+    }
+    for i in 0..NUM_HASHER_COLUMNS {
+        push("decoder", format!("h{}", i));
+    }
+    push("decoder", "in_span".into());
+    push("decoder", "group_count".into());
+    push("decoder", "op_index".into());
+    for i in 0..NUM_OP_BATCH_FLAGS {
+        push("decoder", format!("op_batch_flag{}", i));
+    }
+    push("decoder", "op_bit_extra".into());
+
+    // --- stack ----------------------------------------------------------------------------------
+    for i in 0..MIN_STACK_DEPTH {
+        push("stack", format!("s{}", i));
+    }
+    push("stack", "b0".into());
+    push("stack", "b1".into());
+    push("stack", "h0".into());
+
+    // --- range checker --------------------------------------------------------------------------
+    push("range", "t".into());
+    push("range", "m".into());
+    push("range", "v".into());
+
+    // --- chiplets -------------------------------------------------------------------------------
+    let hasher_names = chiplet_column_names(1, &hasher_column_names());
+    let bitwise_names = chiplet_column_names(2, &bitwise_column_names());
+    let memory_names = chiplet_column_names(3, &memory_column_names());
+    for i in 0..CHIPLETS_WIDTH {
+        // chiplet selector columns have the same name in all chiplets which use them
+        let mut names: Vec<&str> = Vec::new();
+        for name in [&hasher_names[i], &bitwise_names[i], &memory_names[i]] {
+            if !name.is_empty() && !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        push("chiplets", names.join("/"));
+    }
+
+    debug_assert_eq!(TRACE_WIDTH, columns.len());
+    debug_assert_eq!(CHIPLETS_OFFSET + CHIPLETS_WIDTH, columns.len());
+    columns
+}
+
+/// Returns the names of all chiplet columns for a chiplet which is identified by the specified
+/// number of chiplet selector columns, followed by the specified columns of the chiplet itself.
+/// Columns not used by the chiplet have empty names.
+fn chiplet_column_names(num_selectors: usize, names: &[String]) -> Vec<String> {
+    let mut result = (0..num_selectors)
+        .map(|i| format!("s{}", i))
+        .collect::<Vec<_>>();
+    result.extend(names.iter().cloned());
+    result.resize(CHIPLETS_WIDTH, String::new());
+    result
+}
+
+/// Returns the names of the columns of the hasher chiplet, qualified with the chiplet name.
+fn hasher_column_names() -> Vec<String> {
+    let mut result = (0..hasher::NUM_SELECTORS)
+        .map(|i| format!("hasher.s{}", i))
+        .collect::<Vec<_>>();
+    result.push("hasher.row_addr".into());
+    result.extend((0..hasher::STATE_WIDTH).map(|i| format!("hasher.h{}", i)));
+    result.push("hasher.node_index".into());
+    result
+}
+
+/// Returns the names of the columns of the bitwise chiplet, qualified with the chiplet name.
+fn bitwise_column_names() -> Vec<String> {
+    let mut result = (0..bitwise::NUM_SELECTORS)
+        .map(|i| format!("bitwise.s{}", i))
+        .collect::<Vec<_>>();
+    result.push("bitwise.a".into());
+    result.push("bitwise.b".into());
+    result.extend((0..bitwise::A_COL_RANGE.len()).map(|i| format!("bitwise.a{}", i)));
+    result.extend((0..bitwise::B_COL_RANGE.len()).map(|i| format!("bitwise.b{}", i)));
+    result.push("bitwise.prev_output".into());
+    result.push("bitwise.output".into());
+    result.push("bitwise.high_limb".into());
+    result
+}
+
+/// Returns the names of the columns of the memory chiplet, qualified with the chiplet name.
+fn memory_column_names() -> Vec<String> {
+    let mut result = vec![
+        "memory.ctx".into(),
+        "memory.addr".into(),
+        "memory.clk".into(),
+    ];
+    result.extend((0..memory::NUM_ELEMENTS).map(|i| format!("memory.u{}", i)));
+    result.extend((0..memory::NUM_ELEMENTS).map(|i| format!("memory.v{}", i)));
+    result.push("memory.d0".into());
+    result.push("memory.d1".into());
+    result.push("memory.d2".into());
+    result.push("memory.d_inv".into());
+    result
+}
@@ -1,11 +1,12 @@
 use super::{
-    chiplets::{AuxTraceBuilder as ChipletsAuxTraceBuilder, HasherAuxTraceBuilder},
+    chiplets::{AuxTraceBuilder as ChipletsAuxTraceBuilder, ChipletsBus, HasherAuxTraceBuilder},
     decoder::AuxTraceHints as DecoderAuxTraceHints,
     range::AuxTraceBuilder as RangeCheckerAuxTraceBuilder,
     stack::AuxTraceBuilder as StackAuxTraceBuilder,
     Digest, Felt, FieldElement, Process, StackTopState, Vec,
 };
 use vm_core::{
+    chiplets::hasher,
     decoder::{NUM_USER_OP_HELPERS, USER_OP_HELPERS_OFFSET},
     AUX_TRACE_RAND_ELEMENTS, AUX_TRACE_WIDTH, DECODER_TRACE_OFFSET, MIN_STACK_DEPTH, MIN_TRACE_LEN,
     STACK_TRACE_OFFSET, TRACE_WIDTH, ZERO,
@@ -16,10 +17,19 @@ use winterfell::{EvaluationFrame, Matrix, Serializable, Trace, TraceLayout};
 use vm_core::StarkField;
 
 mod utils;
-pub use utils::{build_lookup_table_row_values, AuxColumnBuilder, LookupTableRow, TraceFragment};
+pub use utils::{
+    build_lookup_table_row_values, fragment_checksum, reduce_bitwise_row, reduce_elements,
+    reduce_hasher_row, reduce_memory_row, AuxColumnBuilder, LookupTableRow, TraceFragment,
+};
 
 mod decoder;
 
+mod padding;
+pub use padding::PaddingPolicy;
+
+mod metadata;
+pub use metadata::{AssertionStep, BoundaryAssertion, ColumnInfo, TraceMetadata};
+
 #[cfg(test)]
 mod tests;
 
@@ -58,6 +68,8 @@ pub struct ExecutionTrace {
     main_trace: Matrix<Felt>,
     aux_trace_hints: AuxTraceHints,
     program_hash: Digest,
+    padding: PaddingPolicy,
+    gas_used: Option<u64>,
 }
 
 impl ExecutionTrace {
@@ -71,13 +83,20 @@ impl ExecutionTrace {
     // --------------------------------------------------------------------------------------------
     /// Builds an execution trace for the provided process.
     pub(super) fn new(process: Process) -> Self {
-        // use program hash to initialize random element generator; this generator will be used
-        // to inject random values at the end of the trace; using program hash here is OK because
-        // we are using random values only to stabilize constraint degrees, and not to achieve
-        // perfect zero knowledge.
+        Self::with_padding(process, PaddingPolicy::default())
+    }
+
+    /// Builds an execution trace for the provided process, padded according to the specified
+    /// policy.
+    pub(super) fn with_padding(process: Process, padding: PaddingPolicy) -> Self {
+        // unless the policy specifies a seed, use program hash to initialize random element
+        // generator; this generator will be used to inject random values at the end of the trace;
+        // using program hash here is OK because we are using random values only to stabilize
+        // constraint degrees, and not to achieve perfect zero knowledge.
         let program_hash: Digest = process.decoder.program_hash().into();
-        let rng = RandomCoin::new(&program_hash.to_bytes());
-        let (main_trace, aux_trace_hints) = finalize_trace(process, rng);
+        let rng = padding.rand_coin(program_hash);
+        let gas_used = process.gas_used();
+        let (main_trace, aux_trace_hints) = finalize_trace(process, rng, &padding);
 
         Self {
             meta: Vec::new(),
@@ -85,6 +104,8 @@ impl ExecutionTrace {
             main_trace: Matrix::new(main_trace),
             aux_trace_hints,
             program_hash,
+            padding,
+            gas_used,
         }
     }
 
@@ -96,6 +117,26 @@ impl ExecutionTrace {
         self.program_hash
     }
 
+    /// Returns the amount of gas consumed by the execution, if a gas limit was provided with the
+    /// program inputs.
+    pub fn gas_used(&self) -> Option<u64> {
+        self.gas_used
+    }
+
+    /// Returns the lookups sent to and provided by the chiplets during the execution.
+    ///
+    /// The bus can be serialized and shipped together with the main trace, so that the `b_chip`
+    /// auxiliary column can be built in a separate process once the random elements for the
+    /// auxiliary trace are available.
+    pub fn chiplets_bus(&self) -> ChipletsBus {
+        ChipletsBus::from_aux_builder(&self.aux_trace_hints.chiplets)
+    }
+
+    /// Returns the policy this execution trace was padded with.
+    pub fn padding_policy(&self) -> &PaddingPolicy {
+        &self.padding
+    }
+
     /// Returns the initial state of the top 16 stack registers.
     pub fn init_stack_state(&self) -> StackTopState {
         let mut result = [ZERO; MIN_STACK_DEPTH];
@@ -115,6 +156,35 @@ impl ExecutionTrace {
         result
     }
 
+    /// Returns a digest of all columns of the main trace.
+    ///
+    /// Execution is deterministic, and the random values injected into the last rows of the trace
+    /// are derived from the program hash, so two machines executing the same program against the
+    /// same inputs get the same digest. Comparing the digests is a cheap way to confirm that the
+    /// machines generated identical traces before proving; the digest can also be computed for
+    /// individual columns via [ExecutionTrace::column_hash()] to localize a difference.
+    pub fn hash(&self) -> Digest {
+        let column_hashes = (0..self.main_trace.num_cols())
+            .flat_map(|i| self.column_hash(i).as_elements().to_vec())
+            .collect::<Vec<_>>();
+        hasher::hash_elements(&column_hashes)
+    }
+
+    /// Returns a digest of the specified column of the main trace.
+    ///
+    /// # Panics
+    /// Panics if `col_idx` is out of bounds of the main trace.
+    pub fn column_hash(&self, col_idx: usize) -> Digest {
+        hasher::hash_elements(self.main_trace.get_column(col_idx))
+    }
+
+    /// Returns the owners, semantic names, and boundary assertions of all columns of the main
+    /// trace, with the stack assertions placed on the specified number of stack inputs and
+    /// outputs (only the top 16 stack values are asserted against the trace).
+    pub fn metadata(&self, num_stack_inputs: usize, num_stack_outputs: usize) -> TraceMetadata {
+        metadata::build_metadata(self, num_stack_inputs, num_stack_outputs)
+    }
+
     /// Returns helper registers state at the specified `clk` of the VM
     pub fn get_user_op_helpers_at(&self, clk: usize) -> [Felt; NUM_USER_OP_HELPERS] {
         let mut result = [ZERO; NUM_USER_OP_HELPERS];
@@ -149,7 +219,7 @@ impl ExecutionTrace {
     #[cfg(test)]
     pub fn test_finalize_trace(process: Process) -> (Vec<Vec<Felt>>, AuxTraceHints) {
         let rng = RandomCoin::new(&[0; 32]);
-        finalize_trace(process, rng)
+        finalize_trace(process, rng, &PaddingPolicy::default())
     }
 }
 
@@ -228,7 +298,7 @@ impl Trace for ExecutionTrace {
             .collect::<Vec<_>>();
 
         // inject random values into the last rows of the trace
-        let mut rng = RandomCoin::new(&self.program_hash.to_bytes());
+        let mut rng = self.padding.rand_coin(self.program_hash);
         for i in self.length() - NUM_RAND_ROWS..self.length() {
             for column in aux_columns.iter_mut() {
                 column[i] = rng.draw().expect("failed to draw a random value");
@@ -252,12 +322,17 @@ impl Trace for ExecutionTrace {
 /// Converts a process into a set of execution trace columns for each component of the trace.
 ///
 /// The process includes:
-/// - Determining the length of the trace required to accommodate the longest trace column.
+/// - Determining the length of the trace required to accommodate the longest trace column, and
+///   the length this length is padded to according to the provided padding policy.
 /// - Padding the columns to make sure all columns are of the same length.
 /// - Inserting random values in the last row of all columns. This helps ensure that there
 ///   are no repeating patterns in each column and each column contains a least two distinct
 ///   values. This, in turn, ensures that polynomial degrees of all columns are stable.
-fn finalize_trace(process: Process, mut rng: RandomCoin) -> (Vec<Vec<Felt>>, AuxTraceHints) {
+fn finalize_trace(
+    process: Process,
+    mut rng: RandomCoin,
+    padding: &PaddingPolicy,
+) -> (Vec<Vec<Felt>>, AuxTraceHints) {
     let (system, decoder, stack, mut range, chiplets) = process.to_components();
 
     let clk = system.clk();
@@ -280,9 +355,9 @@ fn finalize_trace(process: Process, mut rng: RandomCoin) -> (Vec<Vec<Felt>>, Aux
         .max()
         .expect("failed to get max of component trace lengths");
 
-    // pad the trace length to the next power of two and ensure that there is space for the
-    // rows to hold random values
-    let trace_len = (max_len + NUM_RAND_ROWS).next_power_of_two();
+    // pad the trace length to the next power of two (or to the length required by the padding
+    // policy) and ensure that there is space for the rows to hold random values
+    let trace_len = padding.trace_len(max_len);
     assert!(
         trace_len >= MIN_TRACE_LEN,
         "trace length must be at least {}, but was {}",
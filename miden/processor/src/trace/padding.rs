@@ -0,0 +1,102 @@
+use super::{Digest, RandomCoin, Serializable, NUM_RAND_ROWS};
+use vm_core::MIN_TRACE_LEN;
+
+// PADDING POLICY
+// ================================================================================================
+
+/// Policy controlling how an execution trace is padded to a power-of-two length.
+///
+/// The rows after the last executed cycle are always filled in the same way, as required by the
+/// AIR: the decoder executes HALT operations, the stack and the system columns keep their last
+/// values, and the range checker and the chiplets pad their segments with their padding rows. The
+/// last [NUM_RAND_ROWS] rows of the trace are then filled with random values.
+///
+/// By default, the trace is padded to the smallest power of two which holds all of its rows, and
+/// the random values are drawn from a coin seeded with the program hash. The policy can fix the
+/// length of the trace, so that executions of different programs, or of the same program on
+/// different versions of the VM, are padded to the same length, and it can fix the seed of the
+/// random values, so that traces can be diffed and cached across runs regardless of the program
+/// hash.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PaddingPolicy {
+    min_trace_len: Option<usize>,
+    rand_seed: Option<[u8; 32]>,
+}
+
+impl PaddingPolicy {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a policy which pads the trace to the smallest power of two which holds all of its
+    /// rows, and seeds the random values with the program hash.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns this policy with the trace padded to at least the specified length.
+    ///
+    /// If the execution needs more rows than that, the trace is padded to the smallest power of
+    /// two which holds all of its rows instead.
+    ///
+    /// # Panics
+    /// Panics if `trace_len` is not a power of two or is smaller than the minimum trace length.
+    pub fn with_min_trace_len(mut self, trace_len: usize) -> Self {
+        assert!(
+            trace_len.is_power_of_two(),
+            "trace length must be a power of two, but was {}",
+            trace_len
+        );
+        assert!(
+            trace_len >= MIN_TRACE_LEN,
+            "trace length must be at least {}, but was {}",
+            MIN_TRACE_LEN,
+            trace_len
+        );
+        self.min_trace_len = Some(trace_len);
+        self
+    }
+
+    /// Returns this policy with the random values in the last rows of the trace drawn from a coin
+    /// seeded with the specified seed instead of the program hash.
+    ///
+    /// The values in these rows are not constrained by the AIR, and thus, proofs of traces padded
+    /// with any seed verify.
+    pub fn with_rand_seed(mut self, seed: [u8; 32]) -> Self {
+        self.rand_seed = Some(seed);
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the length the trace is padded to at least, or None if the trace is padded to the
+    /// smallest power of two which holds all of its rows.
+    pub fn min_trace_len(&self) -> Option<usize> {
+        self.min_trace_len
+    }
+
+    /// Returns the seed of the random values in the last rows of the trace, or None if they are
+    /// seeded with the program hash.
+    pub fn rand_seed(&self) -> Option<[u8; 32]> {
+        self.rand_seed
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the length of a trace which needs the specified number of rows before its random
+    /// rows.
+    pub(crate) fn trace_len(&self, num_rows: usize) -> usize {
+        (num_rows + NUM_RAND_ROWS)
+            .next_power_of_two()
+            .max(self.min_trace_len.unwrap_or(0))
+    }
+
+    /// Returns a coin which draws the random values of a trace of the program with the specified
+    /// hash.
+    pub(super) fn rand_coin(&self, program_hash: Digest) -> RandomCoin {
+        match self.rand_seed {
+            Some(seed) => RandomCoin::new(&seed),
+            None => RandomCoin::new(&program_hash.to_bytes()),
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use super::{
     build_trace_from_ops, rand_array, rand_value, ExecutionTrace, Felt, FieldElement, Operation,
     Trace, AUX_TRACE_RAND_ELEMENTS, CHIPLETS_AUX_TRACE_OFFSET, HASH_CYCLE_LEN, NUM_RAND_ROWS, ONE,
+    ZERO,
 };
+use crate::trace::reduce_bitwise_row;
 use vm_core::chiplets::{
     bitwise::{
         Selectors, BITWISE_AND, BITWISE_AND_LABEL, BITWISE_OR, BITWISE_OR_LABEL, BITWISE_XOR,
@@ -168,7 +170,8 @@ fn b_aux_trace_bitwise() {
 // ================================================================================================
 
 fn build_expected_bitwise(alphas: &[Felt], label: Felt, a: Felt, b: Felt, result: Felt) -> Felt {
-    alphas[0] + alphas[1] * label + alphas[2] * a + alphas[3] * b + alphas[4] * result
+    // the high limbs of the operands and of the result are ZERO for operations on 32-bit values
+    reduce_bitwise_row(alphas, label, [ZERO, a], [ZERO, b], [ZERO, result])
 }
 
 fn build_expected_bitwise_from_trace(trace: &ExecutionTrace, alphas: &[Felt], row: usize) -> Felt {
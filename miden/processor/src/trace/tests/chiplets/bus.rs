@@ -0,0 +1,144 @@
+use super::{
+    build_trace_from_ops, rand_array, Felt, FieldElement, Operation, Trace,
+    AUX_TRACE_RAND_ELEMENTS, CHIPLETS_AUX_TRACE_OFFSET, NUM_RAND_ROWS, ONE,
+};
+use proptest::prelude::*;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum number of chiplet operations in a randomly generated program.
+const MAX_CHIPLET_OPS: usize = 32;
+
+/// Number of memory addresses accessed by randomly generated programs. Addresses are kept close
+/// to each other so that all memory accesses are valid.
+const NUM_MEM_ADDRS: u64 = 64;
+
+// RANDOMIZED TESTS
+// ================================================================================================
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// Tests that the `b_chip` bus column is balanced for random mixes of hasher, bitwise, and
+    /// memory operations. The column must start and end at ONE, and it must never be ZERO, since
+    /// this would mean that a request or a response was reduced to ZERO and the bus no longer
+    /// tracks it.
+    #[test]
+    fn b_chip_random_ops(chiplet_ops in prop::collection::vec(chiplet_op(), 1..MAX_CHIPLET_OPS)) {
+        let operations = chiplet_ops.into_iter().flatten().collect::<Vec<_>>();
+        let mut trace = build_trace_from_ops(operations, &[]);
+
+        let rand_elements = rand_array::<Felt, AUX_TRACE_RAND_ELEMENTS>();
+        let aux_columns = trace.build_aux_segment(&[], &rand_elements).unwrap();
+        let b_chip = aux_columns.get_column(CHIPLETS_AUX_TRACE_OFFSET);
+
+        let last_row = trace.length() - NUM_RAND_ROWS - 1;
+        prop_assert_eq!(trace.length(), b_chip.len());
+        prop_assert_eq!(ONE, b_chip[0]);
+        prop_assert_eq!(ONE, b_chip[last_row]);
+
+        for (row, &value) in b_chip[..=last_row].iter().enumerate() {
+            prop_assert_ne!(Felt::ZERO, value, "b_chip is ZERO at row {}", row);
+        }
+    }
+}
+
+// STRATEGIES
+// ================================================================================================
+
+/// Returns a strategy which generates a sequence of operations making a single request to the
+/// chiplets. All sequences leave the depth of the stack unchanged, so that they can be composed
+/// in any order.
+fn chiplet_op() -> impl Strategy<Value = Vec<Operation>> {
+    prop_oneof![
+        hasher_op(),
+        u32_bitwise_op(),
+        u64_bitwise_op(),
+        mem_store_op(),
+        mem_load_op(),
+    ]
+}
+
+/// Applies a permutation to the top 12 elements of the stack.
+fn hasher_op() -> impl Strategy<Value = Vec<Operation>> {
+    Just(vec![Operation::RpPerm])
+}
+
+/// Computes a bitwise operation over two random u32 values and drops the result.
+fn u32_bitwise_op() -> impl Strategy<Value = Vec<Operation>> {
+    let op = prop_oneof![
+        Just(Operation::U32and),
+        Just(Operation::U32or),
+        Just(Operation::U32xor)
+    ];
+    (op, any::<u32>(), any::<u32>()).prop_map(|(op, a, b)| {
+        vec![
+            Operation::Push(Felt::from(a)),
+            Operation::Push(Felt::from(b)),
+            op,
+            Operation::Drop,
+        ]
+    })
+}
+
+/// Computes a bitwise operation over two random u64 values and drops the operands and the
+/// result.
+fn u64_bitwise_op() -> impl Strategy<Value = Vec<Operation>> {
+    let op = prop_oneof![
+        Just(Operation::U64and),
+        Just(Operation::U64or),
+        Just(Operation::U64xor)
+    ];
+    (op, any::<u64>(), any::<u64>()).prop_map(|(op, a, b)| {
+        let mut operations = vec![
+            Operation::Push(Felt::new(a as u32 as u64)),
+            Operation::Push(Felt::new(a >> 32)),
+            Operation::Push(Felt::new(b as u32 as u64)),
+            Operation::Push(Felt::new(b >> 32)),
+            op,
+        ];
+        operations.resize(operations.len() + 4, Operation::Drop);
+        operations
+    })
+}
+
+/// Stores either a full word or a single element at a random address and drops the stored values.
+fn mem_store_op() -> impl Strategy<Value = Vec<Operation>> {
+    (0..NUM_MEM_ADDRS, any::<[u32; 4]>(), any::<bool>()).prop_map(|(addr, word, single)| {
+        if single {
+            vec![
+                Operation::Push(Felt::from(word[0])),
+                Operation::Push(Felt::new(addr)),
+                Operation::MStore,
+                Operation::Drop,
+            ]
+        } else {
+            let mut operations = word
+                .iter()
+                .rev()
+                .map(|&value| Operation::Push(Felt::from(value)))
+                .collect::<Vec<_>>();
+            operations.push(Operation::Push(Felt::new(addr)));
+            operations.push(Operation::MStoreW);
+            operations.resize(operations.len() + 4, Operation::Drop);
+            operations
+        }
+    })
+}
+
+/// Loads either a full word or a single element from a random address. A loaded word overwrites
+/// the top four stack elements, while a loaded element is dropped.
+fn mem_load_op() -> impl Strategy<Value = Vec<Operation>> {
+    (0..NUM_MEM_ADDRS, any::<bool>()).prop_map(|(addr, single)| {
+        if single {
+            vec![
+                Operation::Push(Felt::new(addr)),
+                Operation::MLoad,
+                Operation::Drop,
+            ]
+        } else {
+            vec![Operation::Push(Felt::new(addr)), Operation::MLoadW]
+        }
+    })
+}
@@ -5,13 +5,14 @@ use super::{
     rand_array, ExecutionTrace, Felt, FieldElement, Operation, Trace, AUX_TRACE_RAND_ELEMENTS,
     CHIPLETS_AUX_TRACE_OFFSET, NUM_RAND_ROWS, ONE, ZERO,
 };
+use crate::trace::{reduce_elements, reduce_hasher_row};
 
 use vm_core::{
     chiplets::{
         hasher::{
-            apply_permutation, init_state_from_words, HasherState, Selectors, CAPACITY_LEN,
-            DIGEST_RANGE, HASH_CYCLE_LEN, LINEAR_HASH, LINEAR_HASH_LABEL, MP_VERIFY,
-            MP_VERIFY_LABEL, MR_UPDATE_NEW, MR_UPDATE_NEW_LABEL, MR_UPDATE_OLD,
+            addr_to_lookup_cycle_row, apply_permutation, init_state_from_words, HasherState,
+            Selectors, CAPACITY_LEN, DIGEST_RANGE, HASH_CYCLE_LEN, LINEAR_HASH, LINEAR_HASH_LABEL,
+            MP_VERIFY, MP_VERIFY_LABEL, MR_UPDATE_NEW, MR_UPDATE_NEW_LABEL, MR_UPDATE_OLD,
             MR_UPDATE_OLD_LABEL, RETURN_HASH, RETURN_HASH_LABEL, RETURN_STATE, RETURN_STATE_LABEL,
             STATE_WIDTH,
         },
@@ -591,40 +592,45 @@ fn build_expected(
     addr: Felt,
     index: Felt,
 ) -> Felt {
-    let first_cycle_row = addr_to_cycle_row(addr) == 0;
+    let first_cycle_row =
+        addr_to_lookup_cycle_row(addr).expect("invalid address for hasher lookup") == 0;
     let transition_label = if first_cycle_row {
         label + 16_u8
     } else {
         label + 32_u8
     };
-    let header =
-        alphas[0] + alphas[1] * Felt::from(transition_label) + alphas[2] * addr + alphas[3] * index;
-    let mut value = header;
-
-    if (first_cycle_row && label == LINEAR_HASH_LABEL) || label == RETURN_STATE_LABEL {
-        // include the entire state (words a, b, c)
-        value += build_value(&alphas[4..16], &state);
-    } else if label == LINEAR_HASH_LABEL {
-        // include the delta between the next and current rate elements (words b and c)
-        value += build_value(&alphas[8..16], &next_state[CAPACITY_LEN..]);
-        value -= build_value(&alphas[8..16], &state[CAPACITY_LEN..]);
-    } else if label == RETURN_HASH_LABEL {
-        // include the digest (word b)
-        value += build_value(&alphas[8..12], &state[DIGEST_RANGE]);
-    } else {
-        assert!(
-            label == MP_VERIFY_LABEL
-                || label == MR_UPDATE_NEW_LABEL
-                || label == MR_UPDATE_OLD_LABEL
-        );
-        let bit = (index.as_int() >> 1) & 1;
-        let left_word = build_value(&alphas[8..12], &state[DIGEST_RANGE]);
-        let right_word = build_value(&alphas[8..12], &state[DIGEST_RANGE.end..]);
-
-        value += Felt::new(1 - bit) * left_word + Felt::new(bit) * right_word;
-    }
 
-    value
+    let state_value =
+        if (first_cycle_row && label == LINEAR_HASH_LABEL) || label == RETURN_STATE_LABEL {
+            // include the entire state (words a, b, c)
+            reduce_elements(&alphas[4..16], &state)
+        } else if label == LINEAR_HASH_LABEL {
+            // include the delta between the next and current rate elements (words b and c)
+            reduce_elements(&alphas[8..16], &next_state[CAPACITY_LEN..])
+                - reduce_elements(&alphas[8..16], &state[CAPACITY_LEN..])
+        } else if label == RETURN_HASH_LABEL {
+            // include the digest (word b)
+            reduce_elements(&alphas[8..12], &state[DIGEST_RANGE])
+        } else {
+            assert!(
+                label == MP_VERIFY_LABEL
+                    || label == MR_UPDATE_NEW_LABEL
+                    || label == MR_UPDATE_OLD_LABEL
+            );
+            let bit = (index.as_int() >> 1) & 1;
+            let left_word = reduce_elements(&alphas[8..12], &state[DIGEST_RANGE]);
+            let right_word = reduce_elements(&alphas[8..12], &state[DIGEST_RANGE.end..]);
+
+            Felt::new(1 - bit) * left_word + Felt::new(bit) * right_word
+        };
+
+    reduce_hasher_row(
+        alphas,
+        Felt::from(transition_label),
+        addr,
+        index,
+        state_value,
+    )
 }
 
 /// Reduces the specified row in the execution trace to an expected value representing a hash
@@ -641,7 +647,7 @@ fn build_expected_from_trace(trace: &ExecutionTrace, alphas: &[Felt], row: usize
     let addr = trace.main_trace.get_column(HASHER_ROW_COL_IDX)[row];
     let index = trace.main_trace.get_column(HASHER_NODE_INDEX_COL_IDX)[row];
 
-    let cycle_row = addr_to_cycle_row(addr);
+    let cycle_row = addr_to_lookup_cycle_row(addr).expect("invalid address for hasher lookup");
 
     let mut state = [ZERO; STATE_WIDTH];
     let mut next_state = [ZERO; STATE_WIDTH];
@@ -656,16 +662,6 @@ fn build_expected_from_trace(trace: &ExecutionTrace, alphas: &[Felt], row: usize
     build_expected(alphas, label, state, next_state, addr, index)
 }
 
-/// Builds a value from alphas and elements of matching lengths. This can be used to build the
-/// value for a single word or for the entire state.
-fn build_value(alphas: &[Felt], elements: &[Felt]) -> Felt {
-    let mut value = ZERO;
-    for (&alpha, &element) in alphas.iter().zip(elements.iter()) {
-        value += alpha * element;
-    }
-    value
-}
-
 /// Returns the hash operation label for the specified selectors.
 fn get_label_from_selectors(selectors: Selectors) -> Option<u8> {
     if selectors == LINEAR_HASH {
@@ -701,18 +697,6 @@ fn absorb_state_from_decoder(trace: &ExecutionTrace, state: &mut HasherState, ro
     }
 }
 
-/// Returns the row of the hash cycle which corresponds to the provided Hasher address.
-fn addr_to_cycle_row(addr: Felt) -> usize {
-    let cycle = (addr.as_int() - 1) as usize;
-    let cycle_row = cycle % HASH_CYCLE_LEN;
-    debug_assert!(
-        cycle_row == 0 || cycle_row == HASH_CYCLE_LEN - 1,
-        "invalid address for hasher lookup"
-    );
-
-    cycle_row
-}
-
 /// Initializes Merkle tree leaves with the specified values.
 fn init_leaves(values: &[u64]) -> Vec<Word> {
     values.iter().map(|&v| init_leaf(v)).collect()
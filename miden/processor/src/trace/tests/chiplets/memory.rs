@@ -2,8 +2,9 @@ use super::{
     build_trace_from_ops, rand_array, ExecutionTrace, Felt, FieldElement, Operation, Trace, Word,
     AUX_TRACE_RAND_ELEMENTS, CHIPLETS_AUX_TRACE_OFFSET, NUM_RAND_ROWS, ONE, ZERO,
 };
+use crate::trace::reduce_memory_row;
 use vm_core::chiplets::memory::{
-    ADDR_COL_IDX, CLK_COL_IDX, CTX_COL_IDX, MEMORY_LABEL, NUM_ELEMENTS, U_COL_RANGE, V_COL_RANGE,
+    ADDR_COL_IDX, CLK_COL_IDX, CTX_COL_IDX, NUM_ELEMENTS, U_COL_RANGE, V_COL_RANGE,
 };
 
 /// Tests the generation of the `b_aux` bus column when only memory lookups are included. It ensures
@@ -128,21 +129,7 @@ fn build_expected_memory(
     old_word: Word,
     new_word: Word,
 ) -> Felt {
-    let mut old_word_value = ZERO;
-    let mut new_word_value = ZERO;
-
-    for i in 0..NUM_ELEMENTS {
-        old_word_value += alphas[i + 5] * old_word[i];
-        new_word_value += alphas[i + 9] * new_word[i];
-    }
-
-    alphas[0]
-        + alphas[1] * MEMORY_LABEL
-        + alphas[2] * ctx
-        + alphas[3] * addr
-        + alphas[4] * clk
-        + old_word_value
-        + new_word_value
+    reduce_memory_row(alphas, ctx, addr, clk, &old_word, &new_word)
 }
 
 fn build_expected_memory_from_trace(trace: &ExecutionTrace, alphas: &[Felt], row: usize) -> Felt {
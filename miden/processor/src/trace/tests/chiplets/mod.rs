@@ -9,5 +9,6 @@ use vm_core::{
 };
 
 mod bitwise;
+mod bus;
 mod hasher;
 mod memory;
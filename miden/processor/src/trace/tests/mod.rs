@@ -1,12 +1,125 @@
-use super::{ExecutionTrace, Felt, FieldElement, LookupTableRow, Process, Trace, NUM_RAND_ROWS};
+use super::{
+    fragment_checksum, AssertionStep, BoundaryAssertion, ExecutionTrace, Felt, FieldElement,
+    LookupTableRow, PaddingPolicy, Process, Trace, TraceFragment, NUM_RAND_ROWS,
+};
 use rand_utils::rand_array;
-use vm_core::{code_blocks::CodeBlock, Operation, ProgramInputs, Word, ONE, ZERO};
+use vm_core::{
+    code_blocks::CodeBlock, Operation, ProgramInputs, Word, CHIPLETS_OFFSET, CLK_COL_IDX, ONE,
+    STACK_TRACE_OFFSET, TRACE_WIDTH, ZERO,
+};
 
 mod chiplets;
 mod hasher;
 mod range;
 mod stack;
 
+// TESTS
+// ================================================================================================
+
+#[test]
+fn trace_hash_is_deterministic() {
+    let ops = vec![Operation::Add, Operation::U32and, Operation::RpPerm];
+    let trace = build_trace_from_ops(ops.clone(), &[1, 2, 3]);
+    let other = build_trace_from_ops(ops.clone(), &[1, 2, 3]);
+    assert_eq!(trace.hash(), other.hash());
+
+    // executions against different inputs differ in the stack columns, but not in the clock column
+    let other = build_trace_from_ops(ops, &[1, 2, 4]);
+    assert_ne!(trace.hash(), other.hash());
+    assert_eq!(trace.column_hash(0), other.column_hash(0));
+    assert_ne!(
+        trace.column_hash(STACK_TRACE_OFFSET),
+        other.column_hash(STACK_TRACE_OFFSET)
+    );
+}
+
+#[test]
+fn trace_metadata() {
+    let trace = build_trace_from_ops(vec![Operation::Add, Operation::U32and], &[1, 2, 3]);
+    let metadata = trace.metadata(3, 2);
+    assert_eq!(TRACE_WIDTH, metadata.columns.len());
+    assert_eq!(trace.length(), metadata.trace_len);
+
+    let clk = &metadata.columns[CLK_COL_IDX];
+    assert_eq!(("system", "clk"), (clk.owner, clk.name.as_str()));
+    assert_eq!(
+        vec![BoundaryAssertion {
+            step: AssertionStep::First,
+            value: ZERO
+        }],
+        clk.assertions
+    );
+
+    // the first stack input is at the top of the stack, and the top output is asserted too
+    let s0 = &metadata.columns[STACK_TRACE_OFFSET];
+    assert_eq!(("stack", "s0"), (s0.owner, s0.name.as_str()));
+    assert_eq!(Felt::new(3), s0.assertions[0].value);
+    assert_eq!(AssertionStep::Last, s0.assertions[1].step);
+    assert_eq!(2, metadata.columns[STACK_TRACE_OFFSET + 1].assertions.len());
+    assert_eq!(1, metadata.columns[STACK_TRACE_OFFSET + 2].assertions.len());
+
+    // chiplet columns are named after all chiplets which use them
+    let chiplet_col = &metadata.columns[CHIPLETS_OFFSET + 4];
+    assert_eq!("hasher.row_addr/bitwise.a/memory.addr", chiplet_col.name);
+
+    let json = metadata.to_json();
+    assert!(json.starts_with(&format!("{{\"trace_len\":{},", trace.length())));
+    assert!(json.contains(
+        "{\"index\":0,\"owner\":\"system\",\"name\":\"clk\",\"assertions\":[{\"step\":\"first\",\"value\":0}]}"
+    ));
+}
+
+#[test]
+fn trace_padding_policy() {
+    let ops = vec![Operation::Add, Operation::U32and];
+    let trace = build_trace_from_ops(ops.clone(), &[1, 2, 3]);
+
+    // the default policy matches the padding of traces built without a policy
+    let padded = build_trace_with_padding(ops.clone(), &[1, 2, 3], PaddingPolicy::default());
+    assert_eq!(trace.hash(), padded.hash());
+
+    // fixing the trace length pads the trace further, but keeps the last stack state
+    let trace_len = trace.length() * 4;
+    let policy = PaddingPolicy::new().with_min_trace_len(trace_len);
+    let padded = build_trace_with_padding(ops.clone(), &[1, 2, 3], policy);
+    assert_eq!(trace_len, padded.length());
+    assert_eq!(trace.last_stack_state(), padded.last_stack_state());
+    assert_eq!(&policy, padded.padding_policy());
+
+    // the seed of the random rows changes only the last rows of the trace
+    let policy = PaddingPolicy::new().with_rand_seed([7; 32]);
+    let seeded = build_trace_with_padding(ops.clone(), &[1, 2, 3], policy);
+    let other = build_trace_with_padding(ops, &[1, 2, 3], policy);
+    assert_eq!(seeded.hash(), other.hash());
+    assert_ne!(trace.hash(), seeded.hash());
+    let last_row = trace.length() - NUM_RAND_ROWS;
+    for i in 0..TRACE_WIDTH {
+        let column = trace.main_segment().get_column(i);
+        let seeded_column = seeded.main_segment().get_column(i);
+        assert_eq!(column[..last_row], seeded_column[..last_row]);
+    }
+}
+
+#[test]
+fn fragment_checksums() {
+    let mut columns = vec![vec![ONE, Felt::new(2)], vec![Felt::new(3), ZERO]];
+    let checksum = fragment_checksum(columns.iter().map(|column| column.as_slice()));
+    assert_eq!(
+        checksum,
+        TraceFragment::trace_to_fragment(&mut columns).checksum()
+    );
+
+    // moving a value to another cell changes the checksum, even within the same column
+    columns[0].swap(0, 1);
+    let swapped = fragment_checksum(columns.iter().map(|column| column.as_slice()));
+    assert_ne!(checksum, swapped);
+    columns[0].swap(0, 1);
+    columns[1][1] = Felt::new(2);
+    columns[0][1] = ZERO;
+    let moved = fragment_checksum(columns.iter().map(|column| column.as_slice()));
+    assert_ne!(checksum, moved);
+}
+
 // TEST HELPERS
 // ================================================================================================
 
@@ -25,6 +138,21 @@ pub fn build_trace_from_ops(operations: Vec<Operation>, stack: &[u64]) -> Execut
     build_trace_from_block(&program, stack)
 }
 
+/// Builds a sample trace by executing a span block containing the specified operations, and pads
+/// it according to the specified policy.
+pub fn build_trace_with_padding(
+    operations: Vec<Operation>,
+    stack: &[u64],
+    padding: PaddingPolicy,
+) -> ExecutionTrace {
+    let inputs = ProgramInputs::new(stack, &[], vec![]).unwrap();
+    let mut process = Process::new(inputs);
+    process
+        .execute_code_block(&CodeBlock::new_span(operations))
+        .unwrap();
+    ExecutionTrace::with_padding(process, padding)
+}
+
 /// Builds a sample trace by executing a span block containing the specified operations. Unlike the
 /// function above, this function accepts the full [ProgramInputs] object, which means it can run
 /// the programs with initialized advice provider.
@@ -1,6 +1,6 @@
 use super::{Felt, FieldElement, Matrix, Vec};
 use core::slice;
-use vm_core::utils::uninit_vector;
+use vm_core::{chiplets::memory::MEMORY_LABEL, utils::uninit_vector, Word};
 
 // TRACE FRAGMENT
 // ================================================================================================
@@ -31,6 +31,11 @@ impl<'a> TraceFragment<'a> {
         self.data[0].len()
     }
 
+    /// Returns the checksum of the values in this fragment (see [fragment_checksum]).
+    pub fn checksum(&self) -> Felt {
+        fragment_checksum(self.data.iter().map(|column| &**column))
+    }
+
     // DATA MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -67,6 +72,23 @@ impl<'a> TraceFragment<'a> {
     }
 }
 
+/// Returns the checksum of a trace fragment with the provided columns.
+///
+/// The checksum is the evaluation of the values of the fragment, column after column, at a fixed
+/// point; it thus changes when a value is overwritten or moved to another cell of the fragment.
+/// Fragments are checksummed after they were filled and again after the trace they were taken
+/// from is assembled, so that writes to the wrong cells surface before the trace is proven. The
+/// checksum guards against bugs only, and does not bind the values of the fragment.
+pub fn fragment_checksum<'c>(columns: impl IntoIterator<Item = &'c [Felt]>) -> Felt {
+    columns
+        .into_iter()
+        .flatten()
+        .fold(Felt::ZERO, |acc, &value| acc * CHECKSUM_POINT + value)
+}
+
+/// Point at which the values of a trace fragment are evaluated by [fragment_checksum].
+const CHECKSUM_POINT: Felt = Felt::new(0x9e37_79b9_7f4a_7c15);
+
 // LOOKUP TABLES
 // ================================================================================================
 
@@ -116,18 +138,106 @@ pub fn build_lookup_table_row_values<E: FieldElement<BaseField = Felt>, R: Looku
     (row_values, inv_row_values)
 }
 
+// BUS REDUCTIONS
+// ================================================================================================
+
+/// Reduces the provided elements to a single value in the field specified by E using the provided
+/// random values, i.e., computes sum(alphas[i] * elements[i]). The reduction stops at the end of
+/// the shorter of the two slices.
+pub fn reduce_elements<E: FieldElement<BaseField = Felt>>(alphas: &[E], elements: &[Felt]) -> E {
+    alphas
+        .iter()
+        .zip(elements.iter())
+        .fold(E::ZERO, |acc, (&alpha, &element)| {
+            acc + alpha.mul_base(element)
+        })
+}
+
+/// Reduces a row of the hasher chiplet sent over the chiplets bus to a single value in the field
+/// specified by E, i.e., computes:
+///
+/// alphas[0] + alphas[1] * transition_label + alphas[2] * addr + alphas[3] * index + state_value
+///
+/// The transition label is the label of the hash operation plus 16 on the first row of a hash
+/// cycle, and plus 32 on the last one. The reduction of the hasher state included in the row
+/// depends on the operation (e.g., the digest only is included when a hash is returned), and thus,
+/// is computed by the caller with [reduce_elements] and the random values following the first 4.
+pub fn reduce_hasher_row<E: FieldElement<BaseField = Felt>>(
+    alphas: &[E],
+    transition_label: Felt,
+    addr: Felt,
+    index: Felt,
+    state_value: E,
+) -> E {
+    alphas[0]
+        + alphas[1].mul_base(transition_label)
+        + alphas[2].mul_base(addr)
+        + alphas[3].mul_base(index)
+        + state_value
+}
+
+/// Reduces a row of the memory chiplet sent over the chiplets bus to a single value in the field
+/// specified by E, i.e., computes:
+///
+/// alphas[0] + alphas[1] * MEMORY_LABEL + alphas[2] * ctx + alphas[3] * addr + alphas[4] * clk
+///     + sum(alphas[i + 5] * old_word[i]) + sum(alphas[i + 9] * new_word[i])
+///
+/// This requires at least 13 random values.
+pub fn reduce_memory_row<E: FieldElement<BaseField = Felt>>(
+    alphas: &[E],
+    ctx: Felt,
+    addr: Felt,
+    clk: Felt,
+    old_word: &Word,
+    new_word: &Word,
+) -> E {
+    alphas[0]
+        + alphas[1].mul_base(MEMORY_LABEL)
+        + alphas[2].mul_base(ctx)
+        + alphas[3].mul_base(addr)
+        + alphas[4].mul_base(clk)
+        + reduce_elements(&alphas[5..9], old_word)
+        + reduce_elements(&alphas[9..13], new_word)
+}
+
+/// Reduces a row of the bitwise chiplet sent over the chiplets bus to a single value in the field
+/// specified by E, i.e., computes:
+///
+/// alphas[0] + alphas[1] * op_id + alphas[2] * a_lo + alphas[3] * b_lo + alphas[4] * z_lo
+///     + alphas[5] * a_hi + alphas[6] * b_hi + alphas[7] * z_hi
+///
+/// The operands `a` and `b`, and the result `z`, are specified by their 32-bit limbs (hi, lo); the
+/// high limbs are ZERO for operations on 32-bit values. This requires at least 8 random values.
+pub fn reduce_bitwise_row<E: FieldElement<BaseField = Felt>>(
+    alphas: &[E],
+    op_id: Felt,
+    a: [Felt; 2],
+    b: [Felt; 2],
+    z: [Felt; 2],
+) -> E {
+    alphas[0]
+        + alphas[1].mul_base(op_id)
+        + alphas[2].mul_base(a[1])
+        + alphas[3].mul_base(b[1])
+        + alphas[4].mul_base(z[1])
+        + alphas[5].mul_base(a[0])
+        + alphas[6].mul_base(b[0])
+        + alphas[7].mul_base(z[0])
+}
+
 // AUX COLUMN BUILDER
 // ================================================================================================
 
 /// Defines a builder responsible for building a single column in an auxiliary segment of the
 /// execution trace.
-pub trait AuxColumnBuilder<H: Copy, R: LookupTableRow> {
+///
+/// Builders which hold the rows of their lookup table can compute the row values via
+/// [build_lookup_table_row_values()]; builders which resolve their rows from the main trace
+/// compute them directly.
+pub trait AuxColumnBuilder<H: Copy> {
     // REQUIRED METHODS
     // --------------------------------------------------------------------------------------------
 
-    /// Returns an exhaustive list of rows which are present in the table.
-    fn get_table_rows(&self) -> &[R];
-
     /// Returns a sequence of hints which indicate how the table was updated. Each hint consists
     /// of a clock cycle at which the update happened as well as the hint describing the update.
     fn get_table_hints(&self) -> &[(usize, H)];
@@ -141,6 +251,12 @@ pub trait AuxColumnBuilder<H: Copy, R: LookupTableRow> {
         inv_row_values: &[E],
     ) -> E;
 
+    /// Builds and returns row values and their inverses for all rows which were added to the
+    /// lookup table managed by this column builder.
+    fn build_row_values<E>(&self, main_trace: &Matrix<Felt>, alphas: &[E]) -> (Vec<E>, Vec<E>)
+    where
+        E: FieldElement<BaseField = Felt>;
+
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -193,15 +309,6 @@ pub trait AuxColumnBuilder<H: Copy, R: LookupTableRow> {
         result
     }
 
-    /// Builds and returns row values and their inverses for all rows which were added to the
-    /// lookup table managed by this column builder.
-    fn build_row_values<E>(&self, _main_trace: &Matrix<Felt>, alphas: &[E]) -> (Vec<E>, Vec<E>)
-    where
-        E: FieldElement<BaseField = Felt>,
-    {
-        build_lookup_table_row_values(self.get_table_rows(), alphas)
-    }
-
     /// Returns the initial value in the auxiliary column. Default implementation of this method
     /// returns ONE.
     fn init_column_value<E: FieldElement<BaseField = Felt>>(&self, _alphas: &[E]) -> E {
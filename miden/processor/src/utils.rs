@@ -1,4 +1,5 @@
-use super::{Felt, StarkField, Vec};
+use super::{Felt, StarkField, Vec, ZERO};
+use winterfell::{ByteReader, Deserializable, DeserializationError};
 
 // HELPER FUNCTIONS
 // ================================================================================================
@@ -18,11 +19,15 @@ pub fn split_element(value: Felt) -> (Felt, Felt) {
     (Felt::new(hi), Felt::new(lo))
 }
 
-/// Splits an element into two 16 bit integer limbs. It assumes that the field element contains a
-/// valid 32-bit integer value.
-pub fn split_element_u32_into_u16(value: Felt) -> (Felt, Felt) {
-    let (hi, lo) = split_u32_into_u16(value.as_int());
-    (Felt::new(hi as u64), Felt::new(lo as u64))
+/// Splits an element into three 16 bit integer limbs. It assumes that the field element contains a
+/// valid 48-bit integer value.
+pub fn split_element_u48_into_u16(value: Felt) -> (Felt, Felt, Felt) {
+    let (hi, mid, lo) = split_u48_into_u16(value.as_int());
+    (
+        Felt::new(hi as u64),
+        Felt::new(mid as u64),
+        Felt::new(lo as u64),
+    )
 }
 
 /// Splits a u64 integer assumed to contain a 32-bit value into two u16 integers.
@@ -38,3 +43,34 @@ pub fn split_u32_into_u16(value: u64) -> (u16, u16) {
 
     (hi, lo)
 }
+
+/// Splits a u64 integer assumed to contain a 48-bit value into three u16 integers.
+///
+/// The value is first split into the upper and the lower 32-bit limbs, and the lower limb is then
+/// split into two u16 integers. The values are returned as (hi, mid, lo).
+///
+/// # Errors
+/// Fails in debug mode if the provided value is not a 48-bit value.
+pub fn split_u48_into_u16(value: u64) -> (u16, u16, u16) {
+    const U48MAX: u64 = (1 << 48) - 1;
+    debug_assert!(value <= U48MAX, "not a 48-bit value");
+
+    let (mid, lo) = split_u32_into_u16(value as u32 as u64);
+    let hi = (value >> 32) as u16;
+
+    (hi, mid, lo)
+}
+
+/// Reads `N` field elements from the specified source.
+///
+/// # Errors
+/// Returns an error if the source does not contain `N` valid field elements.
+pub fn read_elements<R: ByteReader, const N: usize>(
+    source: &mut R,
+) -> Result<[Felt; N], DeserializationError> {
+    let mut result = [ZERO; N];
+    for element in result.iter_mut() {
+        *element = Felt::read_from(source)?;
+    }
+    Ok(result)
+}
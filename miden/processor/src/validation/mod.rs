@@ -0,0 +1,164 @@
+use super::{CodeBlock, Operation, Program, Span, ValidationError, Vec};
+
+#[cfg(test)]
+mod tests;
+
+// PROGRAM LIMITS
+// ================================================================================================
+
+/// Limits enforced on the structure of a program before it is executed.
+///
+/// By default, no limits are imposed, and only the structure of the program is validated (i.e.,
+/// the program must not contain blocks which the processor cannot execute). Limits can be
+/// tightened using the `with_*` methods. This allows callers executing untrusted programs to
+/// reject pathological programs before spending any time executing them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramLimits {
+    max_span_batches: usize,
+    max_total_batches: usize,
+    max_nesting_depth: usize,
+    disallowed_ops: Vec<u8>,
+}
+
+impl ProgramLimits {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns program limits with the maximum number of operation batches in a single SPAN
+    /// block set to the specified value.
+    pub fn with_max_span_batches(mut self, max_span_batches: usize) -> Self {
+        self.max_span_batches = max_span_batches;
+        self
+    }
+
+    /// Returns program limits with the maximum number of operation batches across all SPAN
+    /// blocks of a program set to the specified value.
+    pub fn with_max_total_batches(mut self, max_total_batches: usize) -> Self {
+        self.max_total_batches = max_total_batches;
+        self
+    }
+
+    /// Returns program limits with the maximum nesting depth of code blocks set to the specified
+    /// value. The root block of a program is at depth 0, and children of JOIN, SPLIT, and LOOP
+    /// blocks are one level deeper than their parent.
+    pub fn with_max_nesting_depth(mut self, max_nesting_depth: usize) -> Self {
+        self.max_nesting_depth = max_nesting_depth;
+        self
+    }
+
+    /// Returns program limits with the specified operation added to the list of disallowed
+    /// operations.
+    ///
+    /// Operations are matched by their opcode only, and thus, disallowing `Push(x)` disallows
+    /// pushes of all values.
+    pub fn with_disallowed_op(mut self, op: Operation) -> Self {
+        let op_code = op.op_code();
+        if !self.disallowed_ops.contains(&op_code) {
+            self.disallowed_ops.push(op_code);
+        }
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the maximum number of operation batches allowed in a single SPAN block.
+    pub fn max_span_batches(&self) -> usize {
+        self.max_span_batches
+    }
+
+    /// Returns the maximum number of operation batches allowed across all SPAN blocks.
+    pub fn max_total_batches(&self) -> usize {
+        self.max_total_batches
+    }
+
+    /// Returns the maximum allowed nesting depth of code blocks.
+    pub fn max_nesting_depth(&self) -> usize {
+        self.max_nesting_depth
+    }
+
+    /// Returns true if the specified operation is allowed by these limits.
+    pub fn is_op_allowed(&self, op: &Operation) -> bool {
+        !self.disallowed_ops.contains(&op.op_code())
+    }
+}
+
+impl Default for ProgramLimits {
+    fn default() -> Self {
+        Self {
+            max_span_batches: usize::MAX,
+            max_total_batches: usize::MAX,
+            max_nesting_depth: usize::MAX,
+            disallowed_ops: Vec::new(),
+        }
+    }
+}
+
+// PROGRAM VALIDATION
+// ================================================================================================
+
+/// Checks that the provided program can be executed by the processor and that it satisfies the
+/// specified limits.
+///
+/// The code block tree is traversed iteratively, and thus, validating deeply nested programs does
+/// not risk overflowing the call stack.
+///
+/// # Errors
+/// Returns an error if:
+/// - The program contains a CALL or a PROXY block.
+/// - A code block is nested deeper than the allowed nesting depth.
+/// - A SPAN block contains more operation batches than allowed.
+/// - The total number of operation batches in the program exceeds the allowed number.
+/// - A SPAN block contains a disallowed operation.
+pub fn validate_program(program: &Program, limits: &ProgramLimits) -> Result<(), ValidationError> {
+    let mut total_batches = 0_usize;
+    let mut blocks = vec![(program.root(), 0_usize)];
+
+    while let Some((block, depth)) = blocks.pop() {
+        if depth > limits.max_nesting_depth {
+            return Err(ValidationError::NestingTooDeep(depth));
+        }
+
+        match block {
+            CodeBlock::Join(block) => {
+                // push the second child first so that blocks are visited in execution order
+                blocks.push((block.second(), depth + 1));
+                blocks.push((block.first(), depth + 1));
+            }
+            CodeBlock::Split(block) => {
+                blocks.push((block.on_false(), depth + 1));
+                blocks.push((block.on_true(), depth + 1));
+            }
+            CodeBlock::Loop(block) => blocks.push((block.body(), depth + 1)),
+            CodeBlock::Span(block) => {
+                total_batches = total_batches.saturating_add(block.op_batches().len());
+                if total_batches > limits.max_total_batches {
+                    return Err(ValidationError::TooManyTotalBatches(total_batches));
+                }
+                validate_span_block(block, limits)?;
+            }
+            _ => return Err(ValidationError::UnsupportedCodeBlock(block.clone())),
+        }
+    }
+
+    Ok(())
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Checks that the provided SPAN block satisfies the specified limits.
+fn validate_span_block(block: &Span, limits: &ProgramLimits) -> Result<(), ValidationError> {
+    let num_batches = block.op_batches().len();
+    if num_batches > limits.max_span_batches {
+        return Err(ValidationError::TooManySpanBatches(num_batches));
+    }
+
+    for batch in block.op_batches() {
+        if let Some(&op) = batch.ops().iter().find(|op| !limits.is_op_allowed(op)) {
+            return Err(ValidationError::DisallowedOperation(op));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use super::{validate_program, CodeBlock, Operation, Program, ProgramLimits, ValidationError};
+use vm_core::{Felt, FieldElement};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn validate_default_limits() {
+    let program = Program::new(CodeBlock::new_join([
+        build_span(8),
+        CodeBlock::new_loop(build_span(4)),
+    ]));
+    assert!(validate_program(&program, &ProgramLimits::default()).is_ok());
+}
+
+#[test]
+fn validate_unsupported_blocks() {
+    let program = Program::new(CodeBlock::new_join([
+        build_span(1),
+        CodeBlock::new_proxy(build_span(1).hash()),
+    ]));
+    let result = validate_program(&program, &ProgramLimits::default());
+    assert!(matches!(
+        result,
+        Err(ValidationError::UnsupportedCodeBlock(CodeBlock::Proxy(_)))
+    ));
+
+    let program = Program::new(CodeBlock::new_call(build_span(1).hash()));
+    let result = validate_program(&program, &ProgramLimits::default());
+    assert!(matches!(
+        result,
+        Err(ValidationError::UnsupportedCodeBlock(CodeBlock::Call(_)))
+    ));
+}
+
+#[test]
+fn validate_nesting_depth() {
+    // root join at depth 0, split at depth 1, loop at depth 2, and span at depth 3
+    let program = Program::new(CodeBlock::new_join([
+        build_span(1),
+        CodeBlock::new_split(CodeBlock::new_loop(build_span(1)), build_span(1)),
+    ]));
+
+    let limits = ProgramLimits::default().with_max_nesting_depth(3);
+    assert!(validate_program(&program, &limits).is_ok());
+
+    let limits = ProgramLimits::default().with_max_nesting_depth(2);
+    let result = validate_program(&program, &limits);
+    assert!(matches!(result, Err(ValidationError::NestingTooDeep(3))));
+}
+
+#[test]
+fn validate_span_batches() {
+    // a single batch can hold at most 72 operations (8 groups of 9 operations each)
+    let program = Program::new(build_span(200));
+    let num_batches = get_num_batches(&program);
+    assert!(num_batches > 1);
+
+    let limits = ProgramLimits::default().with_max_span_batches(num_batches);
+    assert!(validate_program(&program, &limits).is_ok());
+
+    let limits = ProgramLimits::default().with_max_span_batches(num_batches - 1);
+    let result = validate_program(&program, &limits);
+    assert!(matches!(result, Err(ValidationError::TooManySpanBatches(n)) if n == num_batches));
+}
+
+#[test]
+fn validate_total_batches() {
+    let program = Program::new(CodeBlock::new_join([build_span(1), build_span(1)]));
+
+    let limits = ProgramLimits::default()
+        .with_max_span_batches(1)
+        .with_max_total_batches(2);
+    assert!(validate_program(&program, &limits).is_ok());
+
+    let limits = ProgramLimits::default()
+        .with_max_span_batches(1)
+        .with_max_total_batches(1);
+    let result = validate_program(&program, &limits);
+    assert!(matches!(
+        result,
+        Err(ValidationError::TooManyTotalBatches(2))
+    ));
+}
+
+#[test]
+fn validate_disallowed_ops() {
+    let span = CodeBlock::new_span(vec![Operation::Push(Felt::new(7)), Operation::Add]);
+    let program = Program::new(CodeBlock::new_loop(span));
+
+    let limits = ProgramLimits::default().with_disallowed_op(Operation::Mul);
+    assert!(validate_program(&program, &limits).is_ok());
+
+    // operations are matched by opcode, and thus, immediate values are ignored
+    let limits = ProgramLimits::default().with_disallowed_op(Operation::Push(Felt::ONE));
+    let result = validate_program(&program, &limits);
+    assert!(matches!(
+        result,
+        Err(ValidationError::DisallowedOperation(Operation::Push(_)))
+    ));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_span(num_ops: usize) -> CodeBlock {
+    CodeBlock::new_span(vec![Operation::Pad; num_ops])
+}
+
+fn get_num_batches(program: &Program) -> usize {
+    match program.root() {
+        CodeBlock::Span(span) => span.op_batches().len(),
+        _ => panic!("expected a span block"),
+    }
+}
@@ -0,0 +1,171 @@
+use super::{Felt, Vec};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Default number of clock cycles between two consecutive checks performed by a [Watchdog].
+pub const DEFAULT_CHECK_INTERVAL: usize = 1 << 12;
+
+// CANCELLATION TOKEN
+// ================================================================================================
+
+/// A handle through which a running execution can be cancelled.
+///
+/// The token can be cloned and shared with other threads; cancelling any of the clones cancels
+/// all executions watched by a [Watchdog] holding the token. Cancellation is cooperative: the
+/// processor checks the token periodically, and thus, the execution stops at the next check
+/// after the token has been cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Returns a new token which has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation of all executions watched through this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if cancellation has been requested through this token.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+// WATCHDOG
+// ================================================================================================
+
+/// Aborts executions which were cancelled or which run for too many cycles.
+///
+/// The watchdog is checked by the processor every `check_interval` clock cycles, and thus, an
+/// execution may run for up to `check_interval - 1` cycles past the cycle limit or past the
+/// moment it was cancelled. Smaller intervals make the watchdog more responsive at a slight
+/// cost to execution speed.
+#[derive(Clone, Debug)]
+pub struct Watchdog {
+    token: Option<CancellationToken>,
+    max_cycles: Option<usize>,
+    check_interval: usize,
+}
+
+impl Watchdog {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new watchdog which does not abort executions until a cancellation token or a
+    /// cycle limit is specified.
+    pub fn new() -> Self {
+        Self {
+            token: None,
+            max_cycles: None,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+
+    /// Aborts executions once the specified token has been cancelled.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Aborts executions once they run for more than the specified number of cycles.
+    pub fn with_max_cycles(mut self, max_cycles: usize) -> Self {
+        self.max_cycles = Some(max_cycles);
+        self
+    }
+
+    /// Sets the number of clock cycles between two consecutive checks.
+    ///
+    /// # Panics
+    /// Panics if `check_interval` is zero.
+    pub fn with_check_interval(mut self, check_interval: usize) -> Self {
+        assert!(
+            check_interval > 0,
+            "check interval must be greater than zero"
+        );
+        self.check_interval = check_interval;
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of clock cycles between two consecutive checks.
+    pub fn check_interval(&self) -> usize {
+        self.check_interval
+    }
+
+    /// Returns the maximum number of cycles executions may run for, if a limit was specified.
+    pub fn max_cycles(&self) -> Option<usize> {
+        self.max_cycles
+    }
+
+    // CHECKS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the reason for aborting the execution at the specified clock cycle, or None if
+    /// the execution may continue.
+    pub(super) fn check(&self, clk: usize) -> Option<AbortReason> {
+        if self
+            .token
+            .as_ref()
+            .map_or(false, CancellationToken::is_cancelled)
+        {
+            return Some(AbortReason::Cancelled);
+        }
+
+        match self.max_cycles {
+            Some(max_cycles) if clk > max_cycles => {
+                Some(AbortReason::CycleLimitExceeded(max_cycles))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// EXECUTION REPORT
+// ================================================================================================
+
+/// Reasons for which a [Watchdog] aborts an execution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AbortReason {
+    /// The cancellation token of the watchdog was cancelled.
+    Cancelled,
+    /// The execution ran for more than the specified number of cycles.
+    CycleLimitExceeded(usize),
+}
+
+/// State of an execution at the moment it was aborted by a [Watchdog].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExecutionReport {
+    /// The reason for aborting the execution.
+    pub reason: AbortReason,
+    /// The number of cycles executed before the execution was aborted.
+    pub clk: usize,
+    /// The value of the free memory pointer at the moment the execution was aborted.
+    pub fmp: Felt,
+    /// The state of the stack (including the overflow table) at the moment the execution was
+    /// aborted.
+    pub stack: Vec<Felt>,
+}
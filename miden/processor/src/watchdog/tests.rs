@@ -0,0 +1,87 @@
+use super::{
+    super::{execute_with_options, ExecutionError, ExecutionOptions, Program, ProgramInputs},
+    AbortReason, CancellationToken, Watchdog,
+};
+use vm_core::{code_blocks::CodeBlock, Operation, ONE};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn watchdog_cycle_limit() {
+    let watchdog = Watchdog::new()
+        .with_max_cycles(1000)
+        .with_check_interval(64);
+    let result = execute_with_options(
+        &build_infinite_loop(),
+        &build_inputs(),
+        ExecutionOptions::new().with_watchdog(watchdog),
+    );
+
+    // the limit is exceeded at cycle 1001, but the watchdog only notices at the next check
+    match result {
+        Err(ExecutionError::ExecutionAborted(report)) => {
+            assert_eq!(AbortReason::CycleLimitExceeded(1000), report.reason);
+            assert_eq!(1024, report.clk);
+        }
+        _ => panic!("execution was expected to be aborted"),
+    }
+}
+
+#[test]
+fn watchdog_cancellation() {
+    let token = CancellationToken::new();
+    let watchdog = Watchdog::new()
+        .with_cancellation_token(token.clone())
+        .with_check_interval(16);
+
+    // cancelling the token before the execution starts aborts it at the first check
+    token.cancel();
+    assert!(token.is_cancelled());
+
+    let result = execute_with_options(
+        &build_infinite_loop(),
+        &build_inputs(),
+        ExecutionOptions::new().with_watchdog(watchdog),
+    );
+    match result {
+        Err(ExecutionError::ExecutionAborted(report)) => {
+            assert_eq!(AbortReason::Cancelled, report.reason);
+            assert_eq!(16, report.clk);
+        }
+        _ => panic!("execution was expected to be aborted"),
+    }
+}
+
+#[test]
+fn watchdog_no_abort() {
+    let token = CancellationToken::new();
+    let watchdog = Watchdog::new()
+        .with_cancellation_token(token)
+        .with_max_cycles(1000)
+        .with_check_interval(1);
+
+    let program = Program::new(CodeBlock::new_span(vec![Operation::Pad, Operation::Incr]));
+    let trace = execute_with_options(
+        &program,
+        &build_inputs(),
+        ExecutionOptions::new().with_watchdog(watchdog),
+    )
+    .unwrap();
+    assert_eq!(ONE, trace.last_stack_state()[0]);
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a program which never terminates, as the body of its loop always leaves ONE at the
+/// top of the stack.
+fn build_infinite_loop() -> Program {
+    let body = CodeBlock::new_span(vec![Operation::Pad, Operation::Incr]);
+    Program::new(CodeBlock::new_loop(body))
+}
+
+/// Returns inputs with ONE at the top of the stack, so that the loop body is entered.
+fn build_inputs() -> ProgramInputs {
+    ProgramInputs::new(&[1], &[], vec![]).unwrap()
+}
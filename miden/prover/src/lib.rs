@@ -1,8 +1,10 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "pub-input-extension")]
+use air::PUB_INPUT_EXTENSION_SIZE;
 use air::{ProcessorAir, PublicInputs};
-use processor::ExecutionTrace;
-use prover::Prover;
+use processor::{ExecutionOptions, ExecutionTrace};
+use prover::{crypto::hashers::Rp64_256, Prover};
 use vm_core::{utils::collections::Vec, Felt, StarkField, MIN_STACK_DEPTH};
 
 #[cfg(feature = "std")]
@@ -16,7 +18,7 @@ use std::time::Instant;
 // ================================================================================================
 
 pub use air::{FieldExtension, HashFunction, ProofOptions};
-pub use processor::ExecutionError;
+pub use processor::{ExecutionError, PaddingPolicy};
 pub use prover::StarkProof;
 pub use vm_core::{
     chiplets::hasher::Digest,
@@ -33,7 +35,7 @@ pub use vm_core::{
 /// * `inputs` specifies the initial state of the stack as well as non-deterministic (secret)
 ///   inputs for the VM.
 /// * `num_stack_outputs` specifies the number of elements from the top of the stack to be
-///   returned.
+///   returned; it cannot be greater than 16.
 /// * `options` defines parameters for STARK proof generation.
 ///
 /// # Errors
@@ -44,6 +46,113 @@ pub fn prove(
     num_stack_outputs: usize,
     options: &ProofOptions,
 ) -> Result<(Vec<u64>, StarkProof), ExecutionError> {
+    prove_with_padding(
+        program,
+        inputs,
+        num_stack_outputs,
+        options,
+        PaddingPolicy::default(),
+    )
+}
+
+/// Executes and proves the specified `program` in the same way as [prove()], but pads the
+/// execution trace according to the specified `padding` policy.
+///
+/// Fixing the length of the trace and the seed of its random rows makes proofs of the same
+/// execution identical across runs and versions of the VM which pad traces differently by
+/// default, e.g., for proof caching.
+///
+/// # Errors
+/// Returns an error if program execution or STARK proof generation fails for any reason.
+pub fn prove_with_padding(
+    program: &Program,
+    inputs: &ProgramInputs,
+    num_stack_outputs: usize,
+    options: &ProofOptions,
+    padding: PaddingPolicy,
+) -> Result<(Vec<u64>, StarkProof), ExecutionError> {
+    let (trace, outputs) = execute_program(program, inputs, num_stack_outputs, padding)?;
+
+    // generate STARK proof
+    let num_stack_inputs = inputs.stack_init().len();
+    let prover = ExecutionProver::new(options.clone(), num_stack_inputs, num_stack_outputs);
+    let proof = generate_proof(&prover, trace)?;
+
+    Ok((outputs, proof))
+}
+
+/// Executes and proves the specified `program` in the same way as [prove()], but binds the proof
+/// to the specified public input `extension`.
+///
+/// The extension is mixed into the seed of the public coin, and thus, the resulting proof can be
+/// verified only against the same extension. This can be used, for example, to bind a proof to a
+/// commitment supplied by an L1 contract for replay protection.
+///
+/// # Errors
+/// Returns an error if program execution or STARK proof generation fails for any reason.
+#[cfg(feature = "pub-input-extension")]
+pub fn prove_with_extension(
+    program: &Program,
+    inputs: &ProgramInputs,
+    num_stack_outputs: usize,
+    options: &ProofOptions,
+    extension: [u8; PUB_INPUT_EXTENSION_SIZE],
+) -> Result<(Vec<u64>, StarkProof), ExecutionError> {
+    let (trace, outputs) =
+        execute_program(program, inputs, num_stack_outputs, PaddingPolicy::default())?;
+
+    // generate STARK proof
+    let num_stack_inputs = inputs.stack_init().len();
+    let prover = ExecutionProver::new(options.clone(), num_stack_inputs, num_stack_outputs)
+        .with_extension(extension);
+    let proof = generate_proof(&prover, trace)?;
+
+    Ok((outputs, proof))
+}
+
+/// Executes and proves the specified `program` in the same way as [prove()], but binds the proof
+/// to a commitment to the outputs of the program instead of to the outputs themselves.
+///
+/// The program must leave the commitment to its outputs on top of the stack, with the first
+/// element of the commitment at the top. The commitment is expected to be the sequential hash of
+/// the outputs (`hasher::hash_elements()`), which programs compute via the `hperm` operation,
+/// and which verifiers recompute from the outputs via `output_commitment()` of the verifier. The
+/// returned commitment is all a verifier needs to verify the proof.
+///
+/// # Errors
+/// Returns an error if program execution or STARK proof generation fails for any reason.
+pub fn prove_with_output_commitment(
+    program: &Program,
+    inputs: &ProgramInputs,
+    options: &ProofOptions,
+) -> Result<(Digest, StarkProof), ExecutionError> {
+    let (trace, _) = execute_program(program, inputs, 0, PaddingPolicy::default())?;
+    let stack_top = trace.last_stack_state();
+    let commitment = Digest::new([stack_top[0], stack_top[1], stack_top[2], stack_top[3]]);
+
+    // generate STARK proof
+    let num_stack_inputs = inputs.stack_init().len();
+    let prover = ExecutionProver::new(options.clone(), num_stack_inputs, 0)
+        .with_output_commitment(commitment);
+    let proof = generate_proof(&prover, trace)?;
+
+    Ok((commitment, proof))
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Executes the specified `program` and returns its execution trace, padded according to the
+/// specified policy, together with the specified number of elements from the top of the stack.
+///
+/// # Errors
+/// Returns an error if more than 16 stack outputs are requested, or if the execution fails.
+fn execute_program(
+    program: &Program,
+    inputs: &ProgramInputs,
+    num_stack_outputs: usize,
+    padding: PaddingPolicy,
+) -> Result<(ExecutionTrace, Vec<u64>), ExecutionError> {
     if num_stack_outputs > MIN_STACK_DEPTH {
         return Err(ExecutionError::TooManyStackOutputs(num_stack_outputs));
     }
@@ -51,7 +160,8 @@ pub fn prove(
     // execute the program to create an execution trace
     #[cfg(feature = "std")]
     let now = Instant::now();
-    let trace = processor::execute(program, inputs)?;
+    let options = ExecutionOptions::new().with_padding(padding);
+    let trace = processor::execute_with_options(program, inputs, options)?;
     #[cfg(feature = "std")]
     debug!(
         "Generated execution trace of {} columns and {} steps in {} ms",
@@ -66,12 +176,23 @@ pub fn prove(
         .map(|&v| v.as_int())
         .collect::<Vec<_>>();
 
-    // generate STARK proof
-    let num_stack_inputs = inputs.stack_init().len();
-    let prover = ExecutionProver::new(options.clone(), num_stack_inputs, num_stack_outputs);
-    let proof = prover.prove(trace).map_err(ExecutionError::ProverError)?;
+    Ok((trace, outputs))
+}
 
-    Ok((outputs, proof))
+/// Generates a STARK proof of the provided execution trace with the provided prover, using the
+/// hash function declared by the proof options of the prover.
+///
+/// RPO is defined over the field of the VM only, and thus, the generic STARK prover cannot
+/// instantiate it; proofs which declare it are generated with the hasher instantiated here.
+fn generate_proof(
+    prover: &ExecutionProver,
+    trace: ExecutionTrace,
+) -> Result<StarkProof, ExecutionError> {
+    let proof = match prover.options().hash_fn() {
+        HashFunction::Rp64_256 => prover.prove_with_hasher::<Rp64_256>(trace),
+        _ => prover.prove(trace),
+    };
+    proof.map_err(ExecutionError::ProverError)
 }
 
 // PROVER
@@ -81,6 +202,9 @@ struct ExecutionProver {
     options: ProofOptions,
     num_stack_inputs: usize,
     num_stack_outputs: usize,
+    output_commitment: Option<Digest>,
+    #[cfg(feature = "pub-input-extension")]
+    extension: Option<[u8; PUB_INPUT_EXTENSION_SIZE]>,
 }
 
 impl ExecutionProver {
@@ -89,8 +213,24 @@ impl ExecutionProver {
             options,
             num_stack_inputs,
             num_stack_outputs,
+            output_commitment: None,
+            #[cfg(feature = "pub-input-extension")]
+            extension: None,
         }
     }
+
+    /// Returns this prover with public inputs bound to the specified output commitment.
+    pub fn with_output_commitment(mut self, commitment: Digest) -> Self {
+        self.output_commitment = Some(commitment);
+        self
+    }
+
+    /// Returns this prover with public inputs extended with the specified bytes.
+    #[cfg(feature = "pub-input-extension")]
+    pub fn with_extension(mut self, extension: [u8; PUB_INPUT_EXTENSION_SIZE]) -> Self {
+        self.extension = Some(extension);
+        self
+    }
 }
 
 impl Prover for ExecutionProver {
@@ -103,10 +243,20 @@ impl Prover for ExecutionProver {
     }
 
     fn get_pub_inputs(&self, trace: &ExecutionTrace) -> PublicInputs {
-        PublicInputs::new(
-            trace.program_hash(),
-            trace.init_stack_state()[..self.num_stack_inputs].to_vec(),
-            trace.last_stack_state()[..self.num_stack_outputs].to_vec(),
-        )
+        let program_hash = trace.program_hash();
+        let stack_inputs = trace.init_stack_state()[..self.num_stack_inputs].to_vec();
+        let stack_outputs = trace.last_stack_state()[..self.num_stack_outputs].to_vec();
+
+        let mut pub_inputs = PublicInputs::new(program_hash, stack_inputs, stack_outputs);
+        if let Some(commitment) = self.output_commitment {
+            pub_inputs = pub_inputs.with_output_commitment(commitment);
+        }
+
+        #[cfg(feature = "pub-input-extension")]
+        if let Some(extension) = self.extension {
+            pub_inputs = pub_inputs.with_extension(extension);
+        }
+
+        pub_inputs
     }
 }
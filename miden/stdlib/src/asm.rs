@@ -12095,12 +12095,7 @@ end
 # Stack transition looks as follows:
 # [b_hi, b_lo, a_hi, a_lo, ...] -> [c_hi, c_lo, ...], where c = a AND b.
 export.checked_and
-    swap
-    movup.3
-    u32checked_and
-    swap
-    movup.2
-    u32checked_and
+    u64checked_and
 end
 
 # Performs bitwise OR of two unsigned 64 bit integers.
@@ -12108,12 +12103,7 @@ end
 # Stack transition looks as follows:
 # [b_hi, b_lo, a_hi, a_lo, ...] -> [c_hi, c_lo, ...], where c = a OR b.
 export.checked_or
-    swap
-    movup.3
-    u32checked_or
-    swap
-    movup.2
-    u32checked_or
+    u64checked_or
 end
 
 # Performs bitwise XOR of two unsigned 64 bit integers.
@@ -12121,12 +12111,7 @@ end
 # Stack transition looks as follows:
 # [b_hi, b_lo, a_hi, a_lo, ...] -> [c_hi, c_lo, ...], where c = a XOR b.
 export.checked_xor
-    swap
-    movup.3
-    u32checked_xor
-    swap
-    movup.2
-    u32checked_xor
+    u64checked_xor
 end
 
 # Performs left shift of one unsigned 64-bit integer using the pow2 operation.
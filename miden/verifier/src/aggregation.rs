@@ -0,0 +1,236 @@
+use super::{
+    convert_stack_values, Digest, Felt, PublicInputs, StarkProof, Vec, VerificationError,
+    MIN_STACK_DEPTH,
+};
+use vm_core::{chiplets::hasher::DIGEST_LEN, utils::string::ToString, FieldElement};
+use winterfell::{
+    crypto::{
+        hashers::{
+            Blake3_192, Blake3_256, DefaultSha2, DefaultSha384, DefaultSha512, Keccak256, Rp64_256,
+            Sha2_256, Sha2_384, Sha2_512, Sha3_256,
+        },
+        Hasher,
+    },
+    ByteReader, ByteWriter, Deserializable, DeserializationError, HashFunction, ProofOptions,
+    Serializable, VerifierError,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the serialized form of [AggregationInputs]; it is incremented whenever the layout of
+/// the serialized form changes.
+pub const AGGREGATION_INPUTS_VERSION: u8 = 3;
+
+/// Number of public input elements in [AggregationInputs]: the program hash, 16 stack inputs, and
+/// 16 stack outputs.
+pub const NUM_AGGREGATION_PUB_INPUTS: usize = DIGEST_LEN + MIN_STACK_DEPTH * 2;
+
+// AGGREGATION INPUTS
+// ================================================================================================
+
+/// Everything a recursive aggregator needs to verify a Miden VM proof, extracted from the proof
+/// and normalized so that the layout does not depend on the number of stack values or on the hash
+/// function of the proof.
+///
+/// Public inputs are laid out as a fixed number of field elements; the exact serialized public
+/// inputs and proof context, which seed the public coin of the verifier, are carried alongside
+/// them. Commitments are carried as serialized digests of the hash function specified by the
+/// proof options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AggregationInputs {
+    /// Options the proof was generated with.
+    pub options: ProofOptions,
+    /// Serialized context of the proof.
+    pub context: Vec<u8>,
+    /// Serialized public inputs of the proof.
+    pub pub_inputs: Vec<u8>,
+    /// Public inputs of the proof as [NUM_AGGREGATION_PUB_INPUTS] field elements: the program
+    /// hash, the stack inputs with the top of the stack first, and the stack outputs. Missing
+    /// stack values are set to ZERO.
+    pub pub_input_elements: Vec<Felt>,
+    /// Commitments to the segments of the execution trace.
+    pub trace_commitments: Vec<Vec<u8>>,
+    /// Commitment to the evaluations of the constraint composition polynomial.
+    pub constraint_commitment: Vec<u8>,
+    /// Commitments to the FRI layers, including the remainder.
+    pub fri_commitments: Vec<Vec<u8>>,
+}
+
+impl AggregationInputs {
+    /// Returns the seed of the public coin of the verifier, i.e., the serialized public inputs
+    /// followed by the serialized proof context.
+    pub fn public_coin_seed(&self) -> Vec<u8> {
+        let mut seed = self.pub_inputs.clone();
+        seed.extend_from_slice(&self.context);
+        seed
+    }
+}
+
+impl Serializable for AggregationInputs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(AGGREGATION_INPUTS_VERSION);
+        self.options.write_into(target);
+        write_bytes(target, &self.context);
+        write_bytes(target, &self.pub_inputs);
+        target.write(self.pub_input_elements.as_slice());
+
+        target.write_u8(self.trace_commitments.len() as u8);
+        for commitment in self.trace_commitments.iter() {
+            write_bytes(target, commitment);
+        }
+        write_bytes(target, &self.constraint_commitment);
+        target.write_u8(self.fri_commitments.len() as u8);
+        for commitment in self.fri_commitments.iter() {
+            write_bytes(target, commitment);
+        }
+    }
+}
+
+impl Deserializable for AggregationInputs {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let version = source.read_u8()?;
+        if version != AGGREGATION_INPUTS_VERSION {
+            return Err(DeserializationError::InvalidValue(
+                "unsupported aggregation inputs version".to_string(),
+            ));
+        }
+
+        let options = ProofOptions::read_from(source)?;
+        let context = read_bytes(source)?;
+        let pub_inputs = read_bytes(source)?;
+        let pub_input_elements = Felt::read_batch_from(source, NUM_AGGREGATION_PUB_INPUTS)?;
+
+        let num_trace_commitments = source.read_u8()? as usize;
+        let trace_commitments = (0..num_trace_commitments)
+            .map(|_| read_bytes(source))
+            .collect::<Result<Vec<_>, _>>()?;
+        let constraint_commitment = read_bytes(source)?;
+        let num_fri_commitments = source.read_u8()? as usize;
+        let fri_commitments = (0..num_fri_commitments)
+            .map(|_| read_bytes(source))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            options,
+            context,
+            pub_inputs,
+            pub_input_elements,
+            trace_commitments,
+            constraint_commitment,
+            fri_commitments,
+        })
+    }
+}
+
+// AGGREGATION PRE-CHECK
+// ================================================================================================
+
+/// Extracts the inputs a recursive aggregator needs to verify the specified proof of a correct
+/// execution of the program with the specified `program_hash` against the provided stack inputs
+/// and outputs.
+///
+/// The proof itself is not verified; instead, the stack values are checked to be valid, and the
+/// commitments of the proof are checked to be well-formed for the hash function specified by its
+/// options. Stack inputs and outputs are expected to be ordered in the same way as for
+/// [verify()](super::verify). Proofs bound to a public input extension are not supported.
+///
+/// # Errors
+/// Returns an error if the stack inputs or outputs are malformed, or if the commitments of the
+/// proof cannot be parsed.
+pub fn prepare_for_aggregation(
+    program_hash: Digest,
+    stack_inputs: &[u64],
+    stack_outputs: &[u64],
+    proof: &StarkProof,
+) -> Result<AggregationInputs, VerificationError> {
+    let (stack_input_felts, stack_output_felts) =
+        convert_stack_values(stack_inputs, stack_outputs)?;
+    let pub_inputs = PublicInputs::new(
+        program_hash,
+        stack_input_felts.clone(),
+        stack_output_felts.clone(),
+    );
+
+    // lay out the public inputs as a fixed number of elements
+    let mut pub_input_elements = Vec::with_capacity(NUM_AGGREGATION_PUB_INPUTS);
+    pub_input_elements.extend_from_slice(program_hash.as_elements());
+    append_padded(&mut pub_input_elements, &stack_input_felts);
+    append_padded(&mut pub_input_elements, &stack_output_felts);
+
+    let (trace_commitments, constraint_commitment, fri_commitments) =
+        match proof.options().hash_fn() {
+            HashFunction::Blake3_192 => parse_commitments::<Blake3_192<Felt>>(proof),
+            HashFunction::Blake3_256 => parse_commitments::<Blake3_256<Felt>>(proof),
+            HashFunction::Sha3_256 => parse_commitments::<Sha3_256<Felt>>(proof),
+            HashFunction::Sha2_256 => parse_commitments::<Sha2_256<Felt, DefaultSha2>>(proof),
+            HashFunction::Sha2_512 => parse_commitments::<Sha2_512<Felt, DefaultSha512>>(proof),
+            HashFunction::Sha2_384 => parse_commitments::<Sha2_384<Felt, DefaultSha384>>(proof),
+            HashFunction::Keccak256 => parse_commitments::<Keccak256<Felt>>(proof),
+            HashFunction::Rp64_256 => parse_commitments::<Rp64_256>(proof),
+            // Poseidon is implemented outside of the VM, which has no hasher to parse it with
+            hash_fn => Err(VerificationError::VerifierError(
+                VerifierError::UnsupportedHashFunction(hash_fn),
+            )),
+        }?;
+
+    Ok(AggregationInputs {
+        options: proof.options().clone(),
+        context: proof.context.to_bytes(),
+        pub_inputs: pub_inputs.to_bytes(),
+        pub_input_elements,
+        trace_commitments,
+        constraint_commitment,
+        fri_commitments,
+    })
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Parses the commitments of the provided proof as digests of the hash function `H`, and returns
+/// them serialized.
+#[allow(clippy::type_complexity)]
+fn parse_commitments<H: Hasher>(
+    proof: &StarkProof,
+) -> Result<(Vec<Vec<u8>>, Vec<u8>, Vec<Vec<u8>>), VerificationError> {
+    let num_trace_segments = proof.context.trace_layout().num_segments();
+    let num_fri_layers = proof
+        .options()
+        .to_fri_options()
+        .num_fri_layers(proof.lde_domain_size());
+
+    let (trace_commitments, constraint_commitment, fri_commitments) = proof
+        .commitments
+        .clone()
+        .parse::<H>(num_trace_segments, num_fri_layers)
+        .map_err(|err| {
+            VerificationError::VerifierError(VerifierError::ProofDeserializationError(
+                err.to_string(),
+            ))
+        })?;
+
+    Ok((
+        trace_commitments.iter().map(|c| c.to_bytes()).collect(),
+        constraint_commitment.to_bytes(),
+        fri_commitments.iter().map(|c| c.to_bytes()).collect(),
+    ))
+}
+
+/// Appends the provided stack values to `elements`, padded with ZERO to 16 values.
+fn append_padded(elements: &mut Vec<Felt>, values: &[Felt]) {
+    elements.extend_from_slice(values);
+    elements.resize(elements.len() + MIN_STACK_DEPTH - values.len(), Felt::ZERO);
+}
+
+/// Writes the provided bytes prefixed with their length.
+fn write_bytes<W: ByteWriter>(target: &mut W, bytes: &[u8]) {
+    target.write_u32(bytes.len() as u32);
+    target.write_u8_slice(bytes);
+}
+
+/// Reads bytes prefixed with their length.
+fn read_bytes<R: ByteReader>(source: &mut R) -> Result<Vec<u8>, DeserializationError> {
+    let len = source.read_u32()? as usize;
+    source.read_u8_vec(len)
+}
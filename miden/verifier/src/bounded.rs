@@ -0,0 +1,275 @@
+use super::{
+    verify_processor_proof, Digest, Felt, PublicInputs, VerificationError, MIN_STACK_DEPTH,
+};
+use vm_core::{utils::string::ToString, FieldElement, TRACE_WIDTH};
+use winterfell::{StarkProof, VerifierError};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bytes in a serialized trace layout: the width of the main segment, followed by the
+/// width and the number of random elements of the auxiliary segment.
+const TRACE_LAYOUT_SIZE: usize = 3;
+
+/// Number of bytes in serialized proof options.
+const PROOF_OPTIONS_SIZE: usize = 7;
+
+/// Number of bytes in the serialized proof-of-work nonce at the end of a proof.
+const POW_NONCE_SIZE: usize = 8;
+
+// BOUNDED STACK VALUES
+// ================================================================================================
+
+/// Up to 16 stack values held in a fixed-size array.
+///
+/// Unlike the stack values accepted by [verify()](super::verify), bounded stack values never
+/// allocate, and thus, stack outputs beyond the top 16 stack registers are not supported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoundedStackValues {
+    values: [Felt; MIN_STACK_DEPTH],
+    len: usize,
+}
+
+impl BoundedStackValues {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns stack inputs converted from the provided values.
+    ///
+    /// Stack inputs are expected to be ordered in the same way as for [verify()](super::verify),
+    /// and are reversed so that the last value ends up at the top of the stack.
+    ///
+    /// # Errors
+    /// Returns an error if more than 16 values are provided, or if any of the values is not a
+    /// valid field element.
+    pub fn from_inputs(stack_inputs: &[u64]) -> Result<Self, VerificationError> {
+        if stack_inputs.len() > MIN_STACK_DEPTH {
+            return Err(VerificationError::TooManyInputValues(
+                MIN_STACK_DEPTH,
+                stack_inputs.len(),
+            ));
+        }
+
+        let mut values = [Felt::ZERO; MIN_STACK_DEPTH];
+        for (value, &input) in values.iter_mut().zip(stack_inputs.iter().rev()) {
+            *value = input
+                .try_into()
+                .map_err(|_| VerificationError::InputNotFieldElement(input))?;
+        }
+
+        Ok(Self {
+            values,
+            len: stack_inputs.len(),
+        })
+    }
+
+    /// Returns stack outputs converted from the provided values.
+    ///
+    /// Stack outputs are expected to be ordered in the same way as for [verify()](super::verify),
+    /// with the value at the top of the stack first.
+    ///
+    /// # Errors
+    /// Returns an error if more than 16 values are provided, or if any of the values is not a
+    /// valid field element.
+    pub fn from_outputs(stack_outputs: &[u64]) -> Result<Self, VerificationError> {
+        if stack_outputs.len() > MIN_STACK_DEPTH {
+            return Err(VerificationError::TooManyOutputValues(
+                MIN_STACK_DEPTH,
+                stack_outputs.len(),
+            ));
+        }
+
+        let mut values = [Felt::ZERO; MIN_STACK_DEPTH];
+        for (value, &output) in values.iter_mut().zip(stack_outputs.iter()) {
+            *value = output
+                .try_into()
+                .map_err(|_| VerificationError::OutputNotFieldElement(output))?;
+        }
+
+        Ok(Self {
+            values,
+            len: stack_outputs.len(),
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the stack values, with the top of the stack first.
+    pub fn as_slice(&self) -> &[Felt] {
+        &self.values[..self.len]
+    }
+
+    /// Returns the number of stack values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no stack values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+// PROOF VIEW
+// ================================================================================================
+
+/// Sections of a serialized STARK proof, borrowed from the bytes of the proof.
+///
+/// The view is parsed without allocation, and thus, it can be used to reject malformed proofs,
+/// and to inspect their parameters, before any memory is committed to deserializing them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofView<'a> {
+    context: &'a [u8],
+    commitments: &'a [u8],
+    body: &'a [u8],
+}
+
+impl<'a> ProofView<'a> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Splits the provided serialized proof into its sections.
+    ///
+    /// # Errors
+    /// Returns an error if a section of the proof is truncated; the error carries the offset of
+    /// the section in the proof.
+    pub fn parse(proof: &'a [u8]) -> Result<Self, VerificationError> {
+        // the context consists of the trace layout, the trace length, the trace metadata, the
+        // field modulus, and the proof options
+        let mut pos = TRACE_LAYOUT_SIZE + 1;
+        let meta_len = read_u16(proof, pos)? as usize;
+        pos += 2 + meta_len;
+        let modulus_len = read_u8(proof, pos)? as usize;
+        pos += 1 + modulus_len + PROOF_OPTIONS_SIZE;
+        let context = take(proof, 0, pos)?;
+
+        // the commitments are prefixed with their length
+        let num_commitment_bytes = read_u16(proof, pos)? as usize;
+        let commitments = take(proof, pos + 2, num_commitment_bytes)?;
+        pos += 2 + num_commitment_bytes;
+
+        // the rest of the proof ends with the proof-of-work nonce
+        let body = &proof[pos..];
+        if body.len() < POW_NONCE_SIZE {
+            return Err(VerificationError::MalformedProof(pos));
+        }
+
+        Ok(Self {
+            context,
+            commitments,
+            body,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the serialized context of the proof.
+    pub fn context(&self) -> &'a [u8] {
+        self.context
+    }
+
+    /// Returns the width of the main segment of the execution trace.
+    pub fn main_trace_width(&self) -> usize {
+        self.context[0] as usize
+    }
+
+    /// Returns the length of the execution trace.
+    ///
+    /// The length is stored as its base 2 logarithm, and thus, a value which overflows `usize`
+    /// saturates to `usize::MAX`.
+    pub fn trace_length(&self) -> usize {
+        1usize
+            .checked_shl(self.context[TRACE_LAYOUT_SIZE] as u32)
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Returns the serialized proof options.
+    pub fn options(&self) -> &'a [u8] {
+        &self.context[self.context.len() - PROOF_OPTIONS_SIZE..]
+    }
+
+    /// Returns the serialized commitments to the trace segments, the constraint evaluations, and
+    /// the FRI layers.
+    pub fn commitments(&self) -> &'a [u8] {
+        self.commitments
+    }
+
+    /// Returns the serialized queries, out-of-domain frame, and FRI proof, followed by the
+    /// proof-of-work nonce.
+    pub fn body(&self) -> &'a [u8] {
+        self.body
+    }
+
+    /// Returns the proof-of-work nonce of the proof.
+    pub fn pow_nonce(&self) -> u64 {
+        let mut nonce = [0; POW_NONCE_SIZE];
+        nonce.copy_from_slice(&self.body[self.body.len() - POW_NONCE_SIZE..]);
+        u64::from_le_bytes(nonce)
+    }
+}
+
+// BOUNDED VERIFIER
+// ================================================================================================
+
+/// Returns Ok(()) if the serialized proof attests to a correct execution of the program with the
+/// specified `program_hash` against the specified inputs and outputs.
+///
+/// Stack values are converted into [BoundedStackValues], and the proof is parsed into a
+/// [ProofView] and checked to be a proof of a Miden VM execution, without allocation. Thus,
+/// malformed inputs and proofs are rejected before any memory is allocated. The STARK verifier
+/// does not support verification from borrowed proofs yet, and thus, the proof is deserialized
+/// only once these checks pass.
+///
+/// Stack inputs and outputs are expected to be ordered in the same way as for
+/// [verify()](super::verify), but at most 16 stack outputs may be provided.
+///
+/// # Errors
+/// Returns an error if the stack values or the proof are malformed, or if the proof does not
+/// prove a correct execution of the program.
+pub fn verify_bounded(
+    program_hash: Digest,
+    stack_inputs: &[u64],
+    stack_outputs: &[u64],
+    proof: &[u8],
+) -> Result<(), VerificationError> {
+    let stack_inputs = BoundedStackValues::from_inputs(stack_inputs)?;
+    let stack_outputs = BoundedStackValues::from_outputs(stack_outputs)?;
+
+    let view = ProofView::parse(proof)?;
+    if view.main_trace_width() != TRACE_WIDTH {
+        return Err(VerificationError::MalformedProof(0));
+    }
+
+    let proof = StarkProof::from_bytes(proof).map_err(|err| {
+        VerificationError::VerifierError(VerifierError::ProofDeserializationError(err.to_string()))
+    })?;
+    let pub_inputs = PublicInputs::new(
+        program_hash,
+        stack_inputs.as_slice().to_vec(),
+        stack_outputs.as_slice().to_vec(),
+    );
+    verify_processor_proof(proof, pub_inputs).map_err(VerificationError::VerifierError)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns `len` bytes of the proof starting at `pos`.
+fn take(proof: &[u8], pos: usize, len: usize) -> Result<&[u8], VerificationError> {
+    proof
+        .get(pos..pos + len)
+        .ok_or(VerificationError::MalformedProof(pos))
+}
+
+/// Reads a byte of the proof at `pos`.
+fn read_u8(proof: &[u8], pos: usize) -> Result<u8, VerificationError> {
+    Ok(take(proof, pos, 1)?[0])
+}
+
+/// Reads a little-endian u16 value of the proof at `pos`.
+fn read_u16(proof: &[u8], pos: usize) -> Result<u16, VerificationError> {
+    let bytes = take(proof, pos, 2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
@@ -2,15 +2,36 @@
 
 use air::{ProcessorAir, PublicInputs};
 use core::fmt;
-use vm_core::{utils::collections::Vec, MIN_STACK_DEPTH};
-use winterfell::VerifierError;
+use vm_core::{
+    chiplets::hasher,
+    utils::{
+        collections::Vec,
+        string::{String, ToString},
+    },
+    Felt, MIN_STACK_DEPTH,
+};
+use winterfell::{
+    crypto::{hashers::Rp64_256, ElementHasher},
+    HashFunction, VerifierError,
+};
+
+mod aggregation;
+mod bounded;
 
 // EXPORTS
 // ================================================================================================
 
+pub use aggregation::{
+    prepare_for_aggregation, AggregationInputs, AGGREGATION_INPUTS_VERSION,
+    NUM_AGGREGATION_PUB_INPUTS,
+};
+pub use air::NUM_PUB_INPUT_ELEMENTS;
+#[cfg(feature = "pub-input-extension")]
+pub use air::PUB_INPUT_EXTENSION_SIZE;
 pub use assembly;
+pub use bounded::{verify_bounded, BoundedStackValues, ProofView};
 pub use vm_core::chiplets::hasher::Digest;
-pub use winterfell::StarkProof;
+pub use winterfell::{crypto::hashers, StarkProof};
 
 // VERIFIER
 // ================================================================================================
@@ -38,6 +59,236 @@ pub fn verify(
     stack_outputs: &[u64],
     proof: StarkProof,
 ) -> Result<(), VerificationError> {
+    let (stack_input_felts, stack_output_felts) =
+        convert_stack_values(stack_inputs, stack_outputs)?;
+
+    // build public inputs and try to verify the proof
+    let pub_inputs = PublicInputs::new(program_hash, stack_input_felts, stack_output_felts);
+    verify_processor_proof(proof, pub_inputs).map_err(VerificationError::VerifierError)
+}
+
+/// Returns Ok(()) if the specified program was executed correctly against the specified inputs
+/// and outputs, and the proof was bound to the specified public input `extension`.
+///
+/// The extension is mixed into the seed of the public coin, and thus, a proof verifies only
+/// against the extension it was generated with (see `prove_with_extension()` in the prover).
+/// This enables replay protection for on-chain verification flows, where the extension is a
+/// context or fee commitment supplied by an L1 contract.
+///
+/// Stack inputs and outputs are expected to be ordered in the same way as for [verify()].
+///
+/// # Errors
+/// Returns an error if the provided proof does not prove a correct execution of the program, or
+/// if the proof was not bound to the specified extension.
+#[cfg(feature = "pub-input-extension")]
+pub fn verify_with_extension(
+    program_hash: Digest,
+    stack_inputs: &[u64],
+    stack_outputs: &[u64],
+    extension: [u8; PUB_INPUT_EXTENSION_SIZE],
+    proof: StarkProof,
+) -> Result<(), VerificationError> {
+    let (stack_input_felts, stack_output_felts) =
+        convert_stack_values(stack_inputs, stack_outputs)?;
+
+    // build public inputs and try to verify the proof
+    let pub_inputs = PublicInputs::new(program_hash, stack_input_felts, stack_output_felts)
+        .with_extension(extension);
+    verify_processor_proof(proof, pub_inputs).map_err(VerificationError::VerifierError)
+}
+
+/// Returns Ok(()) if the specified program was executed correctly against the specified inputs
+/// and outputs, for a proof whose execution trace was committed to with the hash function `HC`.
+///
+/// Proofs with hybrid commitments commit to the execution trace with a hash function which is
+/// cheap to verify in a recursive or algebraic setting (e.g., [hashers::Rp64_256]), while the
+/// constraint and FRI commitments and the Fiat-Shamir transcript use the hash function declared
+/// by the proof options (e.g., SHA-2). The digests of both hash functions must be of the same
+/// size. A proof without hybrid commitments verifies via this function only if `HC` is the hash
+/// function declared by its options.
+///
+/// Stack inputs and outputs are expected to be ordered in the same way as for [verify()].
+///
+/// # Errors
+/// Returns an error if the provided proof does not prove a correct execution of the program, if
+/// its trace commitments were not made with `HC`, or if the digests of `HC` and of the declared
+/// hash function are of different sizes.
+pub fn verify_hybrid<HC>(
+    program_hash: Digest,
+    stack_inputs: &[u64],
+    stack_outputs: &[u64],
+    proof: StarkProof,
+) -> Result<(), VerificationError>
+where
+    HC: ElementHasher<BaseField = Felt>,
+{
+    let (stack_input_felts, stack_output_felts) =
+        convert_stack_values(stack_inputs, stack_outputs)?;
+
+    // build public inputs and try to verify the proof
+    let pub_inputs = PublicInputs::new(program_hash, stack_input_felts, stack_output_felts);
+    winterfell::verify_hybrid::<ProcessorAir, HC>(proof, pub_inputs)
+        .map_err(VerificationError::VerifierError)
+}
+
+/// Returns Ok(()) if the specified program was executed correctly against the specified inputs,
+/// and left the specified commitment to its outputs on top of the stack.
+///
+/// Programs which produce large outputs may expose only a commitment to them, which is asserted
+/// against the top four stack registers at the last step (see `prove_with_output_commitment()` in
+/// the prover). The outputs themselves can be checked against the commitment via
+/// [output_commitment()], or verified together with the proof via [verify_outputs()].
+///
+/// Stack inputs are expected to be ordered in the same way as for [verify()].
+///
+/// # Errors
+/// Returns an error if the provided proof does not prove a correct execution of the program, or
+/// if the program left a different commitment on the stack.
+pub fn verify_with_output_commitment(
+    program_hash: Digest,
+    stack_inputs: &[u64],
+    output_commitment: Digest,
+    proof: StarkProof,
+) -> Result<(), VerificationError> {
+    let (stack_input_felts, _) = convert_stack_values(stack_inputs, &[])?;
+
+    // build public inputs and try to verify the proof
+    let pub_inputs = PublicInputs::new(program_hash, stack_input_felts, Vec::new())
+        .with_output_commitment(output_commitment);
+    verify_processor_proof(proof, pub_inputs).map_err(VerificationError::VerifierError)
+}
+
+/// Returns Ok(()) if the specified program was executed correctly against the specified inputs,
+/// and left the commitment to the specified outputs on top of the stack.
+///
+/// This is equivalent to [verify_with_output_commitment()] against the commitment computed from
+/// the outputs via [output_commitment()].
+///
+/// # Errors
+/// Returns an error if any of the outputs is not a valid field element, or if the proof does not
+/// verify against the commitment to the outputs.
+pub fn verify_outputs(
+    program_hash: Digest,
+    stack_inputs: &[u64],
+    outputs: &[u64],
+    proof: StarkProof,
+) -> Result<(), VerificationError> {
+    let commitment = output_commitment(outputs)?;
+    verify_with_output_commitment(program_hash, stack_inputs, commitment, proof)
+}
+
+/// Returns the commitment to the specified program outputs, i.e., the sequential hash of the
+/// outputs, which programs compute via the `hperm` operation.
+///
+/// # Errors
+/// Returns an error if any of the outputs is not a valid field element.
+pub fn output_commitment(outputs: &[u64]) -> Result<Digest, VerificationError> {
+    let mut output_felts = Vec::with_capacity(outputs.len());
+    for &output in outputs {
+        output_felts.push(
+            output
+                .try_into()
+                .map_err(|_| VerificationError::OutputNotFieldElement(output))?,
+        );
+    }
+    Ok(hasher::hash_elements(&output_felts))
+}
+
+/// Returns the canonical hash of the statement that the program with the specified hash was
+/// executed against the specified stack inputs, and produced the specified stack outputs.
+///
+/// The hash covers the public inputs exactly as [verify()] instantiates the AIR with them, i.e.,
+/// with the stack inputs reversed (see `PublicInputs::hash()` in the AIR). It is computed with the hash
+/// function of the VM, so that a recursive verifier or an external system can bind the same
+/// statement without reproducing this reduction.
+///
+/// Stack inputs and outputs are expected to be ordered in the same way as for [verify()].
+///
+/// # Errors
+/// Returns an error if the stack inputs or outputs are not valid field elements, or if there are
+/// too many stack inputs or outputs.
+pub fn public_inputs_hash(
+    program_hash: Digest,
+    stack_inputs: &[u64],
+    stack_outputs: &[u64],
+) -> Result<Digest, VerificationError> {
+    let (stack_input_felts, stack_output_felts) =
+        convert_stack_values(stack_inputs, stack_outputs)?;
+    Ok(PublicInputs::new(program_hash, stack_input_felts, stack_output_felts).hash())
+}
+
+/// Returns the hash of the program against which the proof was verified if the specified proof
+/// attests to a correct execution of any of the programs in `program_hashes` against the
+/// specified inputs and outputs.
+///
+/// This is useful for upgradable program policies, where several versions of a program (e.g.,
+/// multiple versions of a contract) are acceptable. Program hashes are tried in the order in which
+/// they are provided, and the first matching hash is returned.
+///
+/// Stack inputs and outputs are expected to be ordered in the same way as for [verify()].
+///
+/// # Errors
+/// Returns an error if:
+/// - `program_hashes` is empty.
+/// - The stack inputs or outputs are malformed.
+/// - The provided proof does not prove a correct execution of any of the specified programs.
+pub fn verify_any(
+    program_hashes: &[Digest],
+    stack_inputs: &[u64],
+    stack_outputs: &[u64],
+    proof: StarkProof,
+) -> Result<Digest, VerificationError> {
+    if program_hashes.is_empty() {
+        return Err(VerificationError::NoProgramHashes);
+    }
+
+    let (stack_input_felts, stack_output_felts) =
+        convert_stack_values(stack_inputs, stack_outputs)?;
+
+    for &program_hash in program_hashes {
+        let pub_inputs = PublicInputs::new(
+            program_hash,
+            stack_input_felts.clone(),
+            stack_output_felts.clone(),
+        );
+        if verify_processor_proof(proof.clone(), pub_inputs).is_ok() {
+            return Ok(program_hash);
+        }
+    }
+
+    Err(VerificationError::NoMatchingProgramHash(
+        program_hashes.len(),
+    ))
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Verifies the provided proof of a correct execution of [ProcessorAir] against the provided
+/// public inputs, with the hash function declared by the proof options.
+///
+/// RPO is defined over the field of the VM only, and thus, the generic STARK verifier cannot
+/// instantiate it; proofs which declare it are verified with the hasher instantiated here.
+fn verify_processor_proof(
+    proof: StarkProof,
+    pub_inputs: PublicInputs,
+) -> Result<(), VerifierError> {
+    match proof.options().hash_fn() {
+        HashFunction::Rp64_256 => {
+            winterfell::verify_with_hasher::<ProcessorAir, Rp64_256>(proof, pub_inputs)
+        }
+        _ => winterfell::verify::<ProcessorAir>(proof, pub_inputs),
+    }
+}
+
+/// Converts stack inputs and outputs into field elements.
+///
+/// Stack inputs are reversed so that the last value in `stack_inputs` ends up at the top of the
+/// stack; the order of stack outputs is preserved.
+fn convert_stack_values(
+    stack_inputs: &[u64],
+    stack_outputs: &[u64],
+) -> Result<(Vec<Felt>, Vec<Felt>), VerificationError> {
     if stack_inputs.len() > MIN_STACK_DEPTH {
         return Err(VerificationError::TooManyInputValues(
             MIN_STACK_DEPTH,
@@ -72,9 +323,7 @@ pub fn verify(
         );
     }
 
-    // build public inputs and try to verify the proof
-    let pub_inputs = PublicInputs::new(program_hash, stack_input_felts, stack_output_felts);
-    winterfell::verify::<ProcessorAir>(proof, pub_inputs).map_err(VerificationError::VerifierError)
+    Ok((stack_input_felts, stack_output_felts))
 }
 
 // ERRORS
@@ -88,6 +337,9 @@ pub enum VerificationError {
     TooManyInputValues(usize, usize),
     OutputNotFieldElement(u64),
     TooManyOutputValues(usize, usize),
+    MalformedProof(usize),
+    NoProgramHashes,
+    NoMatchingProgramHash(usize),
 }
 
 impl fmt::Display for VerificationError {
@@ -96,3 +348,74 @@ impl fmt::Display for VerificationError {
         write!(f, "{:?}", self)
     }
 }
+
+impl VerificationError {
+    /// Returns the stable category of the STARK verification failure, or None if the error was
+    /// not caused by the STARK verifier (e.g., the stack inputs were malformed).
+    pub fn failure(&self) -> Option<VerificationFailure> {
+        match self {
+            Self::VerifierError(err) => Some(err.into()),
+            _ => None,
+        }
+    }
+}
+
+// VERIFICATION FAILURE
+// ================================================================================================
+
+/// Stable categories of the reasons why the STARK verifier rejected a proof.
+///
+/// The error enum of the underlying STARK verifier changes shape between its releases. Downstream
+/// crates which need to react to specific verification failures should match on these categories
+/// instead, since they are not changed when the verifier's errors are. The verifier's errors are
+/// matched exhaustively, so a new verifier error does not compile until it is explicitly assigned
+/// to one of the categories. Every category carries the message of the original error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerificationFailure {
+    /// Queried trace or constraint evaluations did not match the commitments made by the prover.
+    CommitmentMismatch(String),
+    /// Constraint evaluations over the out-of-domain frame were inconsistent with the trace.
+    OodMismatch(String),
+    /// Verification of the low-degree (FRI) proof failed.
+    FriFailure(String),
+    /// Query positions or other random values could not be drawn, or the proof-of-work of the
+    /// query seed was invalid.
+    QueryFailure(String),
+    /// The proof could not be deserialized, or its parameters are not supported by the verifier.
+    Deserialization(String),
+}
+
+impl From<&VerifierError> for VerificationFailure {
+    fn from(err: &VerifierError) -> Self {
+        let message = err.to_string();
+        match err {
+            VerifierError::TraceQueryDoesNotMatchCommitment
+            | VerifierError::ConstraintQueryDoesNotMatchCommitment => {
+                Self::CommitmentMismatch(message)
+            }
+            VerifierError::InconsistentOodConstraintEvaluations => Self::OodMismatch(message),
+            VerifierError::FriVerificationFailed(_) => Self::FriFailure(message),
+            VerifierError::RandomCoinError
+            | VerifierError::QuerySeedProofOfWorkVerificationFailed => Self::QueryFailure(message),
+            VerifierError::ProofDeserializationError(_)
+            | VerifierError::InconsistentBaseField
+            | VerifierError::UnsupportedFieldExtension(_)
+            | VerifierError::UnsupportedHashFunction(_)
+            | VerifierError::InconsistentTraceCommitmentDigests(..) => {
+                Self::Deserialization(message)
+            }
+        }
+    }
+}
+
+impl fmt::Display for VerificationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CommitmentMismatch(msg) => write!(f, "commitment mismatch: {}", msg),
+            Self::OodMismatch(msg) => write!(f, "out-of-domain mismatch: {}", msg),
+            Self::FriFailure(msg) => write!(f, "FRI failure: {}", msg),
+            Self::QueryFailure(msg) => write!(f, "query failure: {}", msg),
+            Self::Deserialization(msg) => write!(f, "deserialization failure: {}", msg),
+        }
+    }
+}
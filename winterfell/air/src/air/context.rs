@@ -160,6 +160,66 @@ impl<B: StarkField> AirContext<B> {
         }
     }
 
+    /// Returns a new instance of [AirContext] instantiated for computations which require a single
+    /// execution trace segment, using a precomputed constraint evaluation blowup factor and
+    /// precomputed domain generators.
+    ///
+    /// Unlike [AirContext::new()], this does not derive the constraint evaluation blowup factor
+    /// from the transition constraint degrees, and does not compute the generators of the trace
+    /// and LDE domains. This is useful in constrained environments where the AIR is fixed, and
+    /// thus, these values can be computed ahead of time. The caller is responsible for making sure
+    /// that `ce_blowup_factor` is consistent with `transition_constraint_degrees`, and that the
+    /// generators are the roots of unity of orders `trace_info.length()` and
+    /// `trace_info.length() * options.blowup_factor()` respectively.
+    ///
+    /// # Panics
+    /// Panics if
+    /// * `transition_constraint_degrees` is an empty vector.
+    /// * `num_assertions` is zero.
+    /// * Blowup factor specified by the provided `options` is smaller than `ce_blowup_factor`.
+    /// * `trace_info` describes a multi-segment execution trace.
+    pub fn new_precomputed(
+        trace_info: TraceInfo,
+        transition_constraint_degrees: Vec<TransitionConstraintDegree>,
+        num_assertions: usize,
+        ce_blowup_factor: usize,
+        trace_domain_generator: B,
+        lde_domain_generator: B,
+        options: ProofOptions,
+    ) -> Self {
+        assert!(
+            !trace_info.is_multi_segment(),
+            "provided trace info describes a multi-segment execution trace"
+        );
+        assert!(
+            !transition_constraint_degrees.is_empty(),
+            "at least one transition constraint degree must be specified"
+        );
+        assert!(
+            num_assertions > 0,
+            "at least one assertion must be specified"
+        );
+        assert!(
+            options.blowup_factor() >= ce_blowup_factor,
+            "blowup factor too small; expected at least {}, but was {}",
+            ce_blowup_factor,
+            options.blowup_factor()
+        );
+
+        AirContext {
+            options,
+            trace_info,
+            main_transition_constraint_degrees: transition_constraint_degrees,
+            aux_transition_constraint_degrees: Vec::new(),
+            num_main_assertions: num_assertions,
+            num_aux_assertions: 0,
+            ce_blowup_factor,
+            trace_domain_generator,
+            lde_domain_generator,
+            num_transition_exemptions: 1,
+        }
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -35,6 +35,43 @@ pub enum HashFunction {
     Sha3_256 = 3,
 
     Sha2_256 = 4,
+
+    /// SHA2 hash function with 512 bit output.
+    ///
+    /// When this function is used in the STARK protocol, proof security cannot exceed 256 bits.
+    Sha2_512 = 5,
+
+    /// Rescue Prime hash function with 256 bit output over the 64-bit field with modulus
+    /// 2^64 - 2^32 + 1 (see [Rp64_256](crypto::hashers::Rp64_256)).
+    ///
+    /// The function is defined over a specific field, and thus, it can be used only by proofs
+    /// whose base field is that field. The generic prover and verifier cannot instantiate it for
+    /// an arbitrary base field; proofs which use it are generated and verified by code which is
+    /// specific to the 64-bit field.
+    ///
+    /// When this function is used in the STARK protocol, proof security cannot exceed 128 bits.
+    Rp64_256 = 6,
+
+    /// Poseidon hash function with 256 bit output over the 64-bit field with modulus
+    /// 2^64 - 2^32 + 1.
+    ///
+    /// The function is implemented outside of this library, and thus, the generic prover and
+    /// verifier cannot instantiate it; proofs which use it are generated and verified with the
+    /// hasher passed explicitly.
+    ///
+    /// When this function is used in the STARK protocol, proof security cannot exceed 128 bits.
+    Poseidon64_256 = 7,
+
+    /// SHA2 hash function with 384 bit output.
+    ///
+    /// When this function is used in the STARK protocol, proof security cannot exceed 192 bits.
+    Sha2_384 = 8,
+
+    /// Keccak hash function with 256 bit output, as used by Ethereum (see
+    /// [Keccak256](crypto::hashers::Keccak256)).
+    ///
+    /// When this function is used in the STARK protocol, proof security cannot exceed 128 bits.
+    Keccak256 = 9,
 }
 
 /// Defines an extension field for the composition polynomial.
@@ -307,6 +344,11 @@ impl HashFunction {
             Self::Blake3_256 => 128,
             Self::Sha3_256 => 128,
             Self::Sha2_256 => 128,
+            Self::Sha2_512 => 256,
+            Self::Rp64_256 => 128,
+            Self::Poseidon64_256 => 128,
+            Self::Sha2_384 => 192,
+            Self::Keccak256 => 128,
         }
     }
 }
@@ -326,6 +368,11 @@ impl Deserializable for HashFunction {
             2 => Ok(HashFunction::Blake3_256),
             3 => Ok(HashFunction::Sha3_256),
             4 => Ok(HashFunction::Sha2_256),
+            5 => Ok(HashFunction::Sha2_512),
+            6 => Ok(HashFunction::Rp64_256),
+            7 => Ok(HashFunction::Poseidon64_256),
+            8 => Ok(HashFunction::Sha2_384),
+            9 => Ok(HashFunction::Keccak256),
             value => Err(DeserializationError::InvalidValue(format!(
                 "value {} cannot be deserialized as HashFunction enum",
                 value
@@ -67,10 +67,28 @@ impl Commitments {
         num_trace_segments: usize,
         num_fri_layers: usize,
     ) -> Result<(Vec<H::Digest>, H::Digest, Vec<H::Digest>), DeserializationError> {
+        self.parse_hybrid::<H, H>(num_trace_segments, num_fri_layers)
+    }
+
+    /// Parses the serialized commitments of a proof whose execution trace commitments were made
+    /// with the hash function `HC`, while all other commitments were made with the hash function
+    /// `H`.
+    ///
+    /// The parts are returned in the same order as by [parse()](Commitments::parse).
+    ///
+    /// # Errors
+    /// Returns an error if the bytes stored in self could not be parsed into the requested number
+    /// of commitments, or if there are any unconsumed bytes remaining after the parsing completes.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_hybrid<HC: Hasher, H: Hasher>(
+        self,
+        num_trace_segments: usize,
+        num_fri_layers: usize,
+    ) -> Result<(Vec<HC::Digest>, H::Digest, Vec<H::Digest>), DeserializationError> {
         let mut reader = SliceReader::new(&self.0);
 
         // parse trace commitments
-        let trace_commitments = H::Digest::read_batch_from(&mut reader, num_trace_segments)?;
+        let trace_commitments = HC::Digest::read_batch_from(&mut reader, num_trace_segments)?;
 
         // parse constraint evaluation commitment:
         let constraint_commitment = H::Digest::read_from(&mut reader)?;
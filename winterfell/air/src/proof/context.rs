@@ -91,6 +91,21 @@ impl Context {
     pub fn options(&self) -> &ProofOptions {
         &self.options
     }
+
+    // PUBLIC COIN SEED
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the seed of the public coin of a proof generated in this context for the specified
+    /// serialized public inputs, i.e., the public inputs followed by this context.
+    ///
+    /// The prover and every verifier of the proof must seed their coins with the same bytes for
+    /// their transcripts to agree; this includes verifiers replaying the transcript outside of
+    /// this library, e.g., in a recursive verifier.
+    pub fn public_coin_seed(&self, pub_inputs: &[u8]) -> Vec<u8> {
+        let mut seed = pub_inputs.to_vec();
+        self.write_into(&mut seed);
+        seed
+    }
 }
 
 impl Serializable for Context {
@@ -80,11 +80,22 @@ impl<E: FieldElement> Table<E> {
         &self.data[row_offset..row_offset + self.row_width]
     }
 
+    /// Returns a mutable reference to a row at the specified index.
+    pub fn get_row_mut(&mut self, row_idx: usize) -> &mut [E] {
+        let row_offset = row_idx * self.row_width;
+        &mut self.data[row_offset..row_offset + self.row_width]
+    }
+
     /// Returns an iterator over rows of this table.
     pub fn rows(&self) -> RowIterator<E> {
         RowIterator::new(self)
     }
 
+    /// Returns all elements of this table, laid out row by row.
+    pub fn data(&self) -> &[E] {
+        &self.data
+    }
+
     // TABLE PROCESSING
     // --------------------------------------------------------------------------------------------
 
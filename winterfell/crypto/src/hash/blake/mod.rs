@@ -58,6 +58,29 @@ impl<B: StarkField> ElementHasher for Blake3_256<B> {
     }
 }
 
+impl<B: StarkField> Blake3_256<B> {
+    /// Returns a hash of the concatenation of `first` and `second` element sequences.
+    ///
+    /// The result is the same as calling `hash_elements()` on a concatenated vector of elements,
+    /// but both sequences are fed into the incremental hasher one after another. This is
+    /// intended for hashing evaluation frames with main and auxiliary segments without merging
+    /// the segments into a single vector first.
+    pub fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> ByteDigest<32> {
+        let mut hasher = BlakeHasher::new();
+        if B::IS_CANONICAL {
+            hasher.write_u8_slice(E::elements_as_bytes(first));
+            hasher.write_u8_slice(E::elements_as_bytes(second));
+        } else {
+            hasher.write(first);
+            hasher.write(second);
+        }
+        ByteDigest(hasher.finalize())
+    }
+}
+
 // BLAKE3 192-BIT OUTPUT
 // ================================================================================================
 
@@ -4,8 +4,11 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{Blake3_256, ElementHasher, Hasher};
-use math::{fields::f62::BaseElement, FieldElement};
-use rand_utils::rand_array;
+use math::{
+    fields::{f62::BaseElement, f64, QuadExtension},
+    FieldElement,
+};
+use rand_utils::{rand_array, rand_vector};
 
 #[test]
 fn hash_padding() {
@@ -28,3 +31,22 @@ fn hash_elements_padding() {
     let r2 = Blake3_256::hash_elements(&e2);
     assert_ne!(r1, r2);
 }
+
+#[test]
+fn hash_split_elements() {
+    // f62 elements are not in canonical form internally
+    let e1 = rand_vector::<BaseElement>(7);
+    let e2 = rand_vector::<BaseElement>(3);
+    let expected = Blake3_256::hash_elements(&[e1.clone(), e2.clone()].concat());
+    assert_eq!(expected, Blake3_256::hash_split_elements(&e1, &e2));
+
+    // extension field elements
+    let e1 = rand_vector::<QuadExtension<f64::BaseElement>>(5);
+    let e2 = rand_vector::<QuadExtension<f64::BaseElement>>(2);
+    let expected = Blake3_256::hash_elements(&[e1.clone(), e2.clone()].concat());
+    assert_eq!(expected, Blake3_256::hash_split_elements(&e1, &e2));
+
+    // an empty second sequence is the same as hashing the first sequence alone
+    let expected = Blake3_256::hash_elements(&e1);
+    assert_eq!(expected, Blake3_256::hash_split_elements(&e1, &[]));
+}
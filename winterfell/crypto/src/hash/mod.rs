@@ -6,16 +6,19 @@
 use core::{fmt::Debug, slice};
 use math::{FieldElement, StarkField};
 use rkyv::{Archive, Deserialize, Serialize};
-use utils::{ByteReader, Deserializable, DeserializationError, Serializable};
+use utils::{collections::Vec, ByteReader, Deserializable, DeserializationError, Serializable};
 
 mod blake;
 pub use blake::{Blake3_192, Blake3_256};
 
 mod sha;
-pub use sha::Sha3_256;
+pub use sha::{Keccak256, Sha3_256};
 
 mod sha2;
-pub use sha2::{DefaultSha2, Sha2_256, ShaHasherT};
+pub use sha2::{
+    DefaultSha2, DefaultSha384, DefaultSha512, Sha2_256, Sha2_384, Sha2_512, Sha384HasherT,
+    Sha512HasherT, ShaHasherT,
+};
 
 mod rescue;
 pub use rescue::{Rp62_248, Rp64_256};
@@ -56,6 +59,27 @@ pub trait ElementHasher: Hasher {
     fn hash_elements<E>(elements: &[E]) -> Self::Digest
     where
         E: FieldElement<BaseField = Self::BaseField>;
+
+    /// Returns hashes of the consecutive rows of `width` elements of the provided slice, i.e.,
+    /// the result of calling `hash_elements()` on every row.
+    ///
+    /// Hashers which serialize elements before hashing them can override this method to
+    /// serialize all rows into a single buffer, instead of allocating a buffer for every row.
+    ///
+    /// # Panics
+    /// Panics if `width` is zero, or if the number of elements is not a multiple of `width`.
+    fn hash_elements_batch<E>(elements: &[E], width: usize) -> Vec<Self::Digest>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        assert!(width > 0, "row width must be greater than zero");
+        assert_eq!(
+            elements.len() % width,
+            0,
+            "number of elements must be a multiple of the row width"
+        );
+        elements.chunks(width).map(Self::hash_elements).collect()
+    }
 }
 
 // DIGEST TRAIT
@@ -70,7 +94,8 @@ pub trait Digest:
     /// Ideally, the length of the returned array should be defined by an associated constant, but
     /// using associated constants in const generics is not supported by Rust yet. Thus, we put an
     /// upper limit on the possible digest size. For digests which are smaller than 32 bytes, the
-    /// unused bytes should be set to 0.
+    /// unused bytes should be set to 0; digests which are larger than 32 bytes are truncated to
+    /// their first 32 bytes.
     fn as_bytes(&self) -> [u8; 32];
 }
 
@@ -108,7 +133,8 @@ impl<const N: usize> ByteDigest<N> {
 impl<const N: usize> Digest for ByteDigest<N> {
     fn as_bytes(&self) -> [u8; 32] {
         let mut result = [0; 32];
-        result[..N].copy_from_slice(&self.0);
+        let len = N.min(32);
+        result[..len].copy_from_slice(&self.0[..len]);
         result
     }
 }
@@ -144,5 +170,10 @@ mod tests {
         let mut expected = [255_u8; 32];
         expected[31] = 0;
         assert_eq!(expected, d.as_bytes());
+
+        let mut bytes = [0_u8; 64];
+        bytes[..32].copy_from_slice(&[255_u8; 32]);
+        let d = ByteDigest::new(bytes);
+        assert_eq!([255_u8; 32], d.as_bytes());
     }
 }
@@ -6,13 +6,14 @@
 use super::{Digest, DIGEST_SIZE};
 use core::slice;
 use math::{fields::f64::BaseElement, StarkField};
+use rkyv::{Archive, Deserialize as RD, Serialize as RS};
 use serde::{Deserialize, Serialize};
 use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 // DIGEST TRAIT IMPLEMENTATIONS
 // ================================================================================================
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Archive, RS, RD)]
 pub struct ElementDigest([BaseElement; DIGEST_SIZE]);
 
 impl ElementDigest {
@@ -175,3 +175,14 @@ fn hash_elements_padding() {
     let r2 = Rp64_256::hash_elements(&e2);
     assert_ne!(r1, r2);
 }
+
+#[test]
+fn hash_split_elements() {
+    // the split spans a permutation boundary, so that both parts are absorbed across rounds
+    let elements: [BaseElement; 13] = rand_array();
+    let expected = Rp64_256::hash_elements(&elements);
+    for split in [0, 5, 8, 13] {
+        let (first, second) = elements.split_at(split);
+        assert_eq!(expected, Rp64_256::hash_split_elements(first, second));
+    }
+}
@@ -9,6 +9,9 @@ use math::{FieldElement, StarkField};
 use sha3::Digest;
 use utils::ByteWriter;
 
+#[cfg(test)]
+mod tests;
+
 // SHA3 WITH 256-BIT OUTPUT
 // ================================================================================================
 
@@ -54,6 +57,71 @@ impl<B: StarkField> ElementHasher for Sha3_256<B> {
     }
 }
 
+// KECCAK WITH 256-BIT OUTPUT
+// ================================================================================================
+
+/// Implementation of the [Hasher](super::Hasher) trait for Keccak hash function with 256-bit
+/// output.
+///
+/// This is the original Keccak submission used by Ethereum, which differs from SHA3 with 256-bit
+/// output in its padding only; commitments built with it can be checked by EVM contracts with the
+/// `keccak256` opcode.
+pub struct Keccak256<B: StarkField>(PhantomData<B>);
+
+impl<B: StarkField> Hasher for Keccak256<B> {
+    type Digest = ByteDigest<32>;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        ByteDigest(sha3::Keccak256::digest(bytes).into())
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        ByteDigest(sha3::Keccak256::digest(ByteDigest::digests_as_bytes(values)).into())
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        let mut data = [0; 40];
+        data[..32].copy_from_slice(&seed.0);
+        data[32..].copy_from_slice(&value.to_le_bytes());
+        ByteDigest(sha3::Keccak256::digest(&data).into())
+    }
+}
+
+impl<B: StarkField> ElementHasher for Keccak256<B> {
+    type BaseField = B;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        Self::hash_split_elements(elements, &[])
+    }
+}
+
+impl<B: StarkField> Keccak256<B> {
+    /// Returns a hash of the concatenation of `first` and `second` element sequences.
+    ///
+    /// The result is the same as calling `hash_elements()` on a concatenated vector of elements,
+    /// but both sequences are fed into the incremental hasher one after another. This is
+    /// intended for hashing evaluation frames with main and auxiliary segments without merging
+    /// the segments into a single vector first.
+    pub fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> ByteDigest<32> {
+        let mut hasher = KeccakHasher(sha3::Keccak256::new());
+        if B::IS_CANONICAL {
+            // when element's internal and canonical representations are the same, we can hash
+            // element bytes directly
+            hasher.write_u8_slice(E::elements_as_bytes(first));
+            hasher.write_u8_slice(E::elements_as_bytes(second));
+        } else {
+            // when elements' internal and canonical representations differ, we need to serialize
+            // them before hashing
+            hasher.write(first);
+            hasher.write(second);
+        }
+        ByteDigest(hasher.0.finalize().into())
+    }
+}
+
 // SHA HASHER
 // ================================================================================================
 
@@ -79,3 +147,16 @@ impl ByteWriter for ShaHasher {
         self.0.update(values);
     }
 }
+
+/// Wrapper around Keccak hasher to implement [ByteWriter] trait for it.
+struct KeccakHasher(sha3::Keccak256);
+
+impl ByteWriter for KeccakHasher {
+    fn write_u8(&mut self, value: u8) {
+        self.0.update(&[value]);
+    }
+
+    fn write_u8_slice(&mut self, values: &[u8]) {
+        self.0.update(values);
+    }
+}
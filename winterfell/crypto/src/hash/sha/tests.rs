@@ -0,0 +1,44 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{ElementHasher, Hasher, Keccak256, Sha3_256};
+use math::fields::{f62, f64, QuadExtension};
+use rand_utils::rand_vector;
+
+#[test]
+fn keccak256_known_answers() {
+    // digests of the original Keccak differ from the ones of SHA3 on the same input
+    let empty = Keccak256::<f64::BaseElement>::hash(&[]);
+    assert_eq!(
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+        hex(&empty.0)
+    );
+    assert_ne!(Sha3_256::<f64::BaseElement>::hash(&[]).0, empty.0);
+
+    let abc = Keccak256::<f64::BaseElement>::hash(b"abc");
+    assert_eq!(
+        "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+        hex(&abc.0)
+    );
+}
+
+#[test]
+fn keccak256_hash_split_elements() {
+    // f62 elements are not in canonical form internally
+    let e1 = rand_vector::<f62::BaseElement>(7);
+    let e2 = rand_vector::<f62::BaseElement>(3);
+    let expected = Keccak256::hash_elements(&[e1.clone(), e2.clone()].concat());
+    assert_eq!(expected, Keccak256::hash_split_elements(&e1, &e2));
+
+    // extension field elements
+    let e1 = rand_vector::<QuadExtension<f64::BaseElement>>(5);
+    let e2 = rand_vector::<QuadExtension<f64::BaseElement>>(2);
+    let expected = Keccak256::hash_elements(&[e1.clone(), e2.clone()].concat());
+    assert_eq!(expected, Keccak256::hash_split_elements(&e1, &e2));
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -10,6 +10,9 @@ use sha2_external::Digest;
 use utils::collections::Vec;
 use utils::ByteWriter;
 
+#[cfg(test)]
+mod tests;
+
 // SHA3 WITH 256-BIT OUTPUT
 // ================================================================================================
 
@@ -53,6 +56,173 @@ impl<B: StarkField, H: ShaHasherT> ElementHasher for Sha2_256<B, H> {
             ByteDigest(H::digest(&buf).into())
         }
     }
+
+    fn hash_elements_batch<E: FieldElement<BaseField = Self::BaseField>>(
+        elements: &[E],
+        width: usize,
+    ) -> Vec<Self::Digest> {
+        assert!(width > 0, "row width must be greater than zero");
+        assert_eq!(
+            elements.len() % width,
+            0,
+            "number of elements must be a multiple of the row width"
+        );
+        let row_bytes = width * E::ELEMENT_BYTES;
+        let digests = if B::IS_CANONICAL {
+            H::digest_batch(E::elements_as_bytes(elements), row_bytes)
+        } else {
+            // all rows are serialized into a single buffer, which is then hashed row by row
+            let mut buf = Vec::with_capacity(elements.len() * E::ELEMENT_BYTES);
+            buf.write(elements);
+            H::digest_batch(&buf, row_bytes)
+        };
+        digests.into_iter().map(ByteDigest).collect()
+    }
+}
+
+impl<B: StarkField, H: ShaHasherT> Sha2_256<B, H> {
+    /// Returns a hash of the concatenation of `first` and `second` element sequences.
+    ///
+    /// The result is the same as calling `hash_elements()` on a concatenated vector of elements,
+    /// but the sequences are passed to the hasher one after another (see
+    /// [ShaHasherT::digest_parts()] and [ShaHasherT::digest_elements()]). This is intended for
+    /// hashing evaluation frames with main and auxiliary segments; the segments are merged into
+    /// a single buffer only by hashers which digest a contiguous input.
+    pub fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> ByteDigest<32> {
+        if B::IS_CANONICAL {
+            let parts = [E::elements_as_bytes(first), E::elements_as_bytes(second)];
+            ByteDigest(H::digest_parts(&parts))
+        } else {
+            // elements whose internal and canonical representations differ are serialized as they
+            // are hashed
+            ByteDigest(H::digest_elements(&[first, second]))
+        }
+    }
+}
+
+// SHA2 WITH 512-BIT OUTPUT
+// ================================================================================================
+
+/// Implementation of the [Hasher](super::Hasher) trait for SHA2 hash function with 512-bit
+/// output.
+///
+/// Digests of this hasher are 64 bytes long, and thus, [Digest::as_bytes](super::Digest::as_bytes)
+/// returns only the first 32 bytes of a digest.
+pub struct Sha2_512<B: StarkField, H: Sha512HasherT>(PhantomData<B>, PhantomData<H>);
+
+impl<B: StarkField, H: Sha512HasherT> Hasher for Sha2_512<B, H> {
+    type Digest = ByteDigest<64>;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        ByteDigest(H::digest(bytes))
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        ByteDigest(H::digest(ByteDigest::digests_as_bytes(values)))
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        let mut data = [0; 72];
+        data[..64].copy_from_slice(&seed.0);
+        data[64..].copy_from_slice(&value.to_le_bytes());
+        ByteDigest(H::digest(&data))
+    }
+}
+
+impl<B: StarkField, H: Sha512HasherT> ElementHasher for Sha2_512<B, H> {
+    type BaseField = B;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        if B::IS_CANONICAL {
+            // when element's internal and canonical representations are the same, we can hash
+            // element bytes directly
+            let bytes = E::elements_as_bytes(elements);
+            ByteDigest(H::digest(bytes))
+        } else {
+            let mut buf = Vec::new();
+            // when elements' internal and canonical representations differ, we need to serialize
+            // them before hashing
+            buf.write(elements);
+            ByteDigest(H::digest(&buf))
+        }
+    }
+}
+
+impl<B: StarkField, H: Sha512HasherT> Sha2_512<B, H> {
+    /// Returns a hash of the concatenation of `first` and `second` element sequences.
+    ///
+    /// This is the 512-bit counterpart of [Sha2_256::hash_split_elements()]; the sequences are
+    /// passed to the hasher one after another (see [Sha512HasherT::digest_elements()]).
+    pub fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> ByteDigest<64> {
+        ByteDigest(H::digest_elements(&[first, second]))
+    }
+}
+
+// SHA2 WITH 384-BIT OUTPUT
+// ================================================================================================
+
+/// Implementation of the [Hasher](super::Hasher) trait for SHA2 hash function with 384-bit
+/// output.
+///
+/// Digests of this hasher are 48 bytes long, and thus, [Digest::as_bytes](super::Digest::as_bytes)
+/// returns only the first 32 bytes of a digest.
+pub struct Sha2_384<B: StarkField, H: Sha384HasherT>(PhantomData<B>, PhantomData<H>);
+
+impl<B: StarkField, H: Sha384HasherT> Hasher for Sha2_384<B, H> {
+    type Digest = ByteDigest<48>;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        ByteDigest(H::digest(bytes))
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        ByteDigest(H::digest(ByteDigest::digests_as_bytes(values)))
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        let mut data = [0; 56];
+        data[..48].copy_from_slice(&seed.0);
+        data[48..].copy_from_slice(&value.to_le_bytes());
+        ByteDigest(H::digest(&data))
+    }
+}
+
+impl<B: StarkField, H: Sha384HasherT> ElementHasher for Sha2_384<B, H> {
+    type BaseField = B;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        if B::IS_CANONICAL {
+            // when element's internal and canonical representations are the same, we can hash
+            // element bytes directly
+            let bytes = E::elements_as_bytes(elements);
+            ByteDigest(H::digest(bytes))
+        } else {
+            let mut buf = Vec::new();
+            // when elements' internal and canonical representations differ, we need to serialize
+            // them before hashing
+            buf.write(elements);
+            ByteDigest(H::digest(&buf))
+        }
+    }
+}
+
+impl<B: StarkField, H: Sha384HasherT> Sha2_384<B, H> {
+    /// Returns a hash of the concatenation of `first` and `second` element sequences.
+    ///
+    /// This is the 384-bit counterpart of [Sha2_256::hash_split_elements()]; the sequences are
+    /// passed to the hasher one after another (see [Sha384HasherT::digest_elements()]).
+    pub fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> ByteDigest<48> {
+        ByteDigest(H::digest_elements(&[first, second]))
+    }
 }
 
 // SHA HASHER
@@ -64,6 +234,37 @@ pub trait ShaHasherT {
     fn digest(data: &[u8]) -> [u8; 32];
     // fn update(&mut self, data: impl AsRef<[u8]>);
     // fn finalize(self) -> [u8; 32];
+
+    /// Returns the digest of the concatenation of the provided parts.
+    ///
+    /// Implementations with an incremental hasher state should override this method to feed the
+    /// parts into the state one after another; the default implementation concatenates the
+    /// parts, as accelerators which digest a contiguous input need to anyway.
+    fn digest_parts(parts: &[&[u8]]) -> [u8; 32] {
+        let mut data = Vec::with_capacity(parts.iter().map(|part| part.len()).sum());
+        parts.iter().for_each(|part| data.extend_from_slice(part));
+        Self::digest(&data)
+    }
+
+    /// Returns the digest of the concatenated canonical serializations of the provided element
+    /// sequences.
+    ///
+    /// Implementations with an incremental hasher state should override this method to serialize
+    /// the elements into the state as they are hashed; the default implementation serializes
+    /// them into a single buffer first, for the same reason as [ShaHasherT::digest_parts()].
+    fn digest_elements<E: FieldElement>(parts: &[&[E]]) -> [u8; 32] {
+        let mut data = Vec::with_capacity(elements_len(parts));
+        parts.iter().for_each(|part| data.write(*part));
+        Self::digest(&data)
+    }
+
+    /// Returns digests of the consecutive chunks of `chunk_len` bytes of the provided data.
+    ///
+    /// Implementations backed by an accelerator can override this method to prepare the
+    /// accelerator once for the whole batch, rather than once for every chunk.
+    fn digest_batch(data: &[u8], chunk_len: usize) -> Vec<[u8; 32]> {
+        data.chunks(chunk_len).map(Self::digest).collect()
+    }
 }
 
 pub struct DefaultSha2(sha2_external::Sha256);
@@ -84,26 +285,98 @@ impl ShaHasherT for DefaultSha2 {
     fn digest(data: &[u8]) -> [u8; 32] {
         sha2_external::Sha256::digest(data).into()
     }
+
+    fn digest_parts(parts: &[&[u8]]) -> [u8; 32] {
+        let mut hasher = sha2_external::Sha256::new();
+        parts.iter().for_each(|part| hasher.update(part));
+        hasher.finalize().into()
+    }
+
+    fn digest_elements<E: FieldElement>(parts: &[&[E]]) -> [u8; 32] {
+        digest_elements_with(sha2_external::Sha256::new(), parts).into()
+    }
 }
 
-// struct ShaHasher<H: ShaHasherT>(H);
+/// Wrapper around SHA2 hasher with 512-bit output, which allows guests to substitute an
+/// accelerated implementation.
+pub trait Sha512HasherT {
+    fn digest(data: &[u8]) -> [u8; 64];
+
+    /// Returns the digest of the concatenated canonical serializations of the provided element
+    /// sequences; this is the 512-bit counterpart of [ShaHasherT::digest_elements()].
+    fn digest_elements<E: FieldElement>(parts: &[&[E]]) -> [u8; 64] {
+        let mut data = Vec::with_capacity(elements_len(parts));
+        parts.iter().for_each(|part| data.write(*part));
+        Self::digest(&data)
+    }
+}
 
-// impl<H: ShaHasherT> ShaHasher<H> {
-//     pub fn new() -> Self {
-//         Self(H::new())
-//     }
+pub struct DefaultSha512(sha2_external::Sha512);
 
-//     pub fn finalize(self) -> [u8; 32] {
-//         self.0.finalize()
-//     }
-// }
+impl Sha512HasherT for DefaultSha512 {
+    fn digest(data: &[u8]) -> [u8; 64] {
+        sha2_external::Sha512::digest(data).into()
+    }
 
-// impl<H: ShaHasherT> ByteWriter for ShaHasher<H> {
-//     fn write_u8(&mut self, value: u8) {
-//         self.0.update(&[value]);
-//     }
+    fn digest_elements<E: FieldElement>(parts: &[&[E]]) -> [u8; 64] {
+        digest_elements_with(sha2_external::Sha512::new(), parts).into()
+    }
+}
+
+/// Wrapper around SHA2 hasher with 384-bit output, which allows guests to substitute an
+/// accelerated implementation.
+pub trait Sha384HasherT {
+    fn digest(data: &[u8]) -> [u8; 48];
+
+    /// Returns the digest of the concatenated canonical serializations of the provided element
+    /// sequences; this is the 384-bit counterpart of [ShaHasherT::digest_elements()].
+    fn digest_elements<E: FieldElement>(parts: &[&[E]]) -> [u8; 48] {
+        let mut data = Vec::with_capacity(elements_len(parts));
+        parts.iter().for_each(|part| data.write(*part));
+        Self::digest(&data)
+    }
+}
+
+pub struct DefaultSha384(sha2_external::Sha384);
+
+impl Sha384HasherT for DefaultSha384 {
+    fn digest(data: &[u8]) -> [u8; 48] {
+        sha2_external::Sha384::digest(data).into()
+    }
 
-//     fn write_u8_slice(&mut self, values: &[u8]) {
-//         self.0.update(values);
-//     }
-// }
+    fn digest_elements<E: FieldElement>(parts: &[&[E]]) -> [u8; 48] {
+        digest_elements_with(sha2_external::Sha384::new(), parts).into()
+    }
+}
+
+// INCREMENTAL HASHER
+// ================================================================================================
+
+/// Incremental SHA2 state which elements are serialized into as they are hashed.
+struct IncrementalSha2<D: Digest>(D);
+
+impl<D: Digest> ByteWriter for IncrementalSha2<D> {
+    fn write_u8(&mut self, value: u8) {
+        self.0.update([value]);
+    }
+
+    fn write_u8_slice(&mut self, values: &[u8]) {
+        self.0.update(values);
+    }
+}
+
+/// Serializes the provided element sequences into the provided SHA2 state one after another, and
+/// returns the resulting digest.
+fn digest_elements_with<D: Digest, E: FieldElement>(
+    hasher: D,
+    parts: &[&[E]],
+) -> sha2_external::digest::Output<D> {
+    let mut hasher = IncrementalSha2(hasher);
+    parts.iter().for_each(|part| hasher.write(*part));
+    hasher.0.finalize()
+}
+
+/// Returns the number of bytes in the serializations of the provided element sequences.
+fn elements_len<E: FieldElement>(parts: &[&[E]]) -> usize {
+    parts.iter().map(|part| part.len()).sum::<usize>() * E::ELEMENT_BYTES
+}
@@ -0,0 +1,174 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    DefaultSha2, DefaultSha384, DefaultSha512, ElementHasher, Hasher, Sha2_256, Sha2_384, Sha2_512,
+    ShaHasherT,
+};
+use crate::Digest;
+use math::fields::{f62, f64, QuadExtension};
+use rand_utils::rand_vector;
+use utils::ByteWriter;
+
+#[test]
+fn digest_parts() {
+    // a hasher which digests contiguous inputs only, and thus, relies on the default
+    // implementation
+    struct ContiguousSha2;
+    impl ShaHasherT for ContiguousSha2 {
+        fn digest(data: &[u8]) -> [u8; 32] {
+            DefaultSha2::digest(data)
+        }
+    }
+
+    let data: Vec<u8> = (0..200).map(|i| i as u8).collect();
+    let expected = DefaultSha2::digest(&data);
+    for split in [0, 1, 64, 150, 200] {
+        let parts = [&data[..split], &data[split..]];
+        assert_eq!(expected, DefaultSha2::digest_parts(&parts));
+        assert_eq!(expected, ContiguousSha2::digest_parts(&parts));
+    }
+
+    // elements are serialized into the hasher state, or into a single buffer without one
+    let e1 = rand_vector::<f62::BaseElement>(7);
+    let e2 = rand_vector::<f62::BaseElement>(3);
+    let mut data = Vec::new();
+    data.write(&e1[..]);
+    data.write(&e2[..]);
+    let expected = DefaultSha2::digest(&data);
+    assert_eq!(expected, DefaultSha2::digest_elements(&[&e1[..], &e2[..]]));
+    assert_eq!(
+        expected,
+        ContiguousSha2::digest_elements(&[&e1[..], &e2[..]])
+    );
+}
+
+#[test]
+fn hash_split_elements() {
+    // f62 elements are not in canonical form internally
+    let e1 = rand_vector::<f62::BaseElement>(7);
+    let e2 = rand_vector::<f62::BaseElement>(3);
+    let expected = Sha2_256::<_, DefaultSha2>::hash_elements(&[e1.clone(), e2.clone()].concat());
+    assert_eq!(
+        expected,
+        Sha2_256::<_, DefaultSha2>::hash_split_elements(&e1, &e2)
+    );
+
+    // extension field elements
+    let e1 = rand_vector::<QuadExtension<f64::BaseElement>>(5);
+    let e2 = rand_vector::<QuadExtension<f64::BaseElement>>(2);
+    let expected = Sha2_256::<_, DefaultSha2>::hash_elements(&[e1.clone(), e2.clone()].concat());
+    assert_eq!(
+        expected,
+        Sha2_256::<_, DefaultSha2>::hash_split_elements(&e1, &e2)
+    );
+
+    // an empty second sequence is the same as hashing the first sequence alone
+    let expected = Sha2_256::<_, DefaultSha2>::hash_elements(&e1);
+    assert_eq!(
+        expected,
+        Sha2_256::<_, DefaultSha2>::hash_split_elements(&e1, &[])
+    );
+}
+
+#[test]
+fn sha2_512_hash_split_elements() {
+    let e1 = rand_vector::<f62::BaseElement>(7);
+    let e2 = rand_vector::<f62::BaseElement>(3);
+    let expected = Sha2_512::<_, DefaultSha512>::hash_elements(&[e1.clone(), e2.clone()].concat());
+    assert_eq!(
+        expected,
+        Sha2_512::<_, DefaultSha512>::hash_split_elements(&e1, &e2)
+    );
+}
+
+#[test]
+fn sha2_512_hash() {
+    let digest = Sha2_512::<f64::BaseElement, DefaultSha512>::hash(b"abc");
+    assert_eq!(
+        [
+            0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, 0xcc, 0x41, 0x73, 0x49, 0xae, 0x20,
+            0x41, 0x31
+        ],
+        digest.as_bytes()[..16]
+    );
+}
+
+#[test]
+fn sha2_384_hash_split_elements() {
+    let e1 = rand_vector::<f62::BaseElement>(7);
+    let e2 = rand_vector::<f62::BaseElement>(3);
+    let expected = Sha2_384::<_, DefaultSha384>::hash_elements(&[e1.clone(), e2.clone()].concat());
+    assert_eq!(
+        expected,
+        Sha2_384::<_, DefaultSha384>::hash_split_elements(&e1, &e2)
+    );
+}
+
+#[test]
+fn sha2_384_hash() {
+    let digest = Sha2_384::<f64::BaseElement, DefaultSha384>::hash(b"abc");
+    assert_eq!(
+        [
+            0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b, 0xb5, 0xa0, 0x3d, 0x69, 0x9a, 0xc6,
+            0x50, 0x07
+        ],
+        digest.as_bytes()[..16]
+    );
+}
+
+#[test]
+fn sha2_384_merge_with_int() {
+    // the seed is hashed in full, and not only the bytes returned by as_bytes()
+    let seed = Sha2_384::<f64::BaseElement, DefaultSha384>::hash(b"abc");
+    let mut other = seed;
+    other.0[47] ^= 1;
+    assert_ne!(
+        Sha2_384::<f64::BaseElement, DefaultSha384>::merge_with_int(seed, 7),
+        Sha2_384::<f64::BaseElement, DefaultSha384>::merge_with_int(other, 7)
+    );
+}
+
+#[test]
+fn hash_elements_batch() {
+    // f62 elements are not in canonical form internally
+    let elements = rand_vector::<f62::BaseElement>(12);
+    let expected: Vec<_> = elements
+        .chunks(4)
+        .map(Sha2_256::<_, DefaultSha2>::hash_elements)
+        .collect();
+    assert_eq!(
+        expected,
+        Sha2_256::<_, DefaultSha2>::hash_elements_batch(&elements, 4)
+    );
+
+    // extension field elements in canonical form
+    let elements = rand_vector::<QuadExtension<f64::BaseElement>>(6);
+    let expected: Vec<_> = elements
+        .chunks(2)
+        .map(Sha2_256::<_, DefaultSha2>::hash_elements)
+        .collect();
+    assert_eq!(
+        expected,
+        Sha2_256::<_, DefaultSha2>::hash_elements_batch(&elements, 2)
+    );
+
+    // hashers without a batched implementation hash the rows one by one
+    let expected: Vec<_> = elements
+        .chunks(3)
+        .map(Sha2_512::<_, DefaultSha512>::hash_elements)
+        .collect();
+    assert_eq!(
+        expected,
+        Sha2_512::<_, DefaultSha512>::hash_elements_batch(&elements, 3)
+    );
+}
+
+#[test]
+#[should_panic]
+fn hash_elements_batch_partial_row() {
+    let elements = rand_vector::<f62::BaseElement>(5);
+    Sha2_256::<_, DefaultSha2>::hash_elements_batch(&elements, 2);
+}
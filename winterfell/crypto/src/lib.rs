@@ -29,10 +29,14 @@ pub mod hashers {
 
     pub use super::hash::Blake3_192;
     pub use super::hash::Blake3_256;
+    pub use super::hash::Keccak256;
     pub use super::hash::Rp62_248;
     pub use super::hash::Rp64_256;
     pub use super::hash::Sha3_256;
-    pub use super::hash::{DefaultSha2, Sha2_256, ShaHasherT};
+    pub use super::hash::{
+        DefaultSha2, DefaultSha384, DefaultSha512, Sha2_256, Sha2_384, Sha2_512, Sha384HasherT,
+        Sha512HasherT, ShaHasherT,
+    };
 }
 
 mod merkle;
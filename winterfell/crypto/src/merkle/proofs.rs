@@ -118,6 +118,12 @@ impl<H: Hasher> BatchMerkleProof<H> {
         }
     }
 
+    /// Returns the leaves authenticated by this proof, in the order of the indexes the proof was
+    /// generated for.
+    pub fn leaves(&self) -> &[H::Digest] {
+        &self.leaves
+    }
+
     /// Computes a node to which all Merkle paths aggregated in this proof resolve.
     ///
     /// # Errors
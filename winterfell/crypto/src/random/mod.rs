@@ -136,6 +136,14 @@ impl<B: StarkField, H: Hasher> RandomCoin<B, H> {
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the current seed of this coin.
+    ///
+    /// The seed is changed only by reseeding the coin, and thus, it is a commitment to all data
+    /// the coin was reseeded with; drawing values from the coin does not change the seed.
+    pub fn seed(&self) -> H::Digest {
+        self.seed
+    }
+
     /// Returns the number of leading zeros in the seed if it is interpreted as an integer in
     /// big-endian byte order.
     ///
@@ -316,17 +316,12 @@ impl FriProofLayer {
                 "a FRI layer must contain at least one query".to_string(),
             ));
         }
-        let mut hashed_queries = vec![H::Digest::default(); num_queries];
-        let mut query_values = Vec::with_capacity(num_queries * folding_factor);
 
-        // read bytes corresponding to each query, convert them into field elements,
-        // and also hash them to build leaf nodes of the batch Merkle proof
+        // read bytes corresponding to all queries, convert them into field elements, and hash
+        // the values of every query to build leaf nodes of the batch Merkle proof
         let mut reader = SliceReader::new(&self.values);
-        for query_hash in hashed_queries.iter_mut() {
-            let mut qe = E::read_batch_from(&mut reader, folding_factor)?;
-            *query_hash = H::hash_elements(&qe);
-            query_values.append(&mut qe);
-        }
+        let query_values = E::read_batch_from(&mut reader, num_queries * folding_factor)?;
+        let hashed_queries = H::hash_elements_batch(&query_values, folding_factor);
         if reader.has_more_bytes() {
             return Err(DeserializationError::UnconsumedBytes);
         }
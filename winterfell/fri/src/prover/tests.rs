@@ -5,10 +5,10 @@
 
 use super::{DefaultProverChannel, FriProver};
 use crate::{
-    verifier::{DefaultVerifierChannel, FriVerifier},
+    verifier::{DefaultVerifierChannel, FriVerifier, VerifierChannel},
     FriOptions, FriProof, VerifierError,
 };
-use crypto::{hashers::Blake3_256, Hasher, RandomCoin};
+use crypto::{hashers::Blake3_256, BatchMerkleProof, Hasher, RandomCoin};
 use math::{fft, fields::f128::BaseElement, FieldElement};
 use utils::{collections::Vec, Deserializable, Serializable, SliceReader};
 
@@ -59,6 +59,56 @@ fn fri_prove_verify() {
     assert!(result.is_err());
 }
 
+#[test]
+fn fri_reject_tampered_layer_queries() {
+    let trace_length = 1024;
+    let lde_blowup = 8;
+
+    let options = FriOptions::new(lde_blowup, 4, 256);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions();
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+
+    // change a queried value of the first layer after the proof was parsed; the evaluations at
+    // the queried positions are changed accordingly, so that the values are consistent with the
+    // evaluations, but no longer with the layer commitment
+    let mut queried_evaluations = positions
+        .iter()
+        .map(|&p| evaluations[p])
+        .collect::<Vec<_>>();
+    let tampered = queried_evaluations[0] + BaseElement::ONE;
+    let original = queried_evaluations[0];
+    for evaluation in queried_evaluations.iter_mut() {
+        if *evaluation == original {
+            *evaluation = tampered;
+        }
+    }
+
+    let mut channel = TamperedChannel {
+        inner: DefaultVerifierChannel::new(
+            proof,
+            commitments,
+            trace_length * lde_blowup,
+            options.folding_factor(),
+        )
+        .unwrap(),
+        original,
+        tampered,
+        layer_idx: 0,
+    };
+    let mut coin = RandomCoin::<BaseElement, Blake3>::new(&[]);
+    let verifier = FriVerifier::new(&mut channel, &mut coin, options, trace_length - 1).unwrap();
+    assert_eq!(
+        Err(VerifierError::LayerCommitmentMismatch),
+        verifier.verify(&mut channel, &queried_evaluations, &positions)
+    );
+}
+
 // TEST UTILS
 // ================================================================================================
 
@@ -82,6 +132,46 @@ pub fn build_evaluations(trace_length: usize, lde_blowup: usize) -> Vec<BaseElem
     p
 }
 
+/// Verifier channel which replaces a value in the queries of the first FRI layer after the layer
+/// proofs were built from the queries.
+struct TamperedChannel {
+    inner: DefaultVerifierChannel<BaseElement, Blake3>,
+    original: BaseElement,
+    tampered: BaseElement,
+    layer_idx: usize,
+}
+
+impl VerifierChannel<BaseElement> for TamperedChannel {
+    type Hasher = Blake3;
+
+    fn read_fri_num_partitions(&self) -> usize {
+        self.inner.read_fri_num_partitions()
+    }
+
+    fn read_fri_layer_commitments(&mut self) -> Vec<<Blake3 as Hasher>::Digest> {
+        self.inner.read_fri_layer_commitments()
+    }
+
+    fn take_next_fri_layer_queries(&mut self) -> Vec<BaseElement> {
+        let mut queries = self.inner.take_next_fri_layer_queries();
+        if self.layer_idx == 0 {
+            for value in queries.iter_mut().filter(|value| **value == self.original) {
+                *value = self.tampered;
+            }
+        }
+        self.layer_idx += 1;
+        queries
+    }
+
+    fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<Blake3> {
+        self.inner.take_next_fri_layer_proof()
+    }
+
+    fn take_fri_remainder(&mut self) -> Vec<BaseElement> {
+        self.inner.take_fri_remainder()
+    }
+}
+
 pub fn verify_proof(
     proof: FriProof,
     commitments: Vec<<Blake3 as Hasher>::Digest>,
@@ -71,7 +71,9 @@ pub trait VerifierChannel<E: FieldElement> {
     /// Returns FRI query values at the specified positions from the current FRI layer and advances
     /// layer pointer by one.
     ///
-    /// This also checks if the values are valid against the provided FRI layer commitment.
+    /// This also checks if the values are valid against the provided FRI layer commitment: the
+    /// values must hash into the leaves of the layer proof, and the layer proof must resolve to
+    /// the commitment.
     ///
     /// # Errors
     /// Returns an error if query values did not match layer commitment.
@@ -81,13 +83,22 @@ pub trait VerifierChannel<E: FieldElement> {
         commitment: &<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest,
     ) -> Result<Vec<[E; N]>, VerifierError> {
         let layer_proof = self.take_next_fri_layer_proof();
-        MerkleTree::<Self::Hasher>::verify_batch(commitment, positions, &layer_proof)
-            .map_err(|_| VerifierError::LayerCommitmentMismatch)?;
+        let layer_queries = self.take_next_fri_layer_queries();
 
-        // TODO: make sure layer queries hash into leaves of layer proof
+        // make sure layer queries hash into leaves of layer proof; otherwise, the values would
+        // not be bound to the commitment, and the evaluations checked against them (including
+        // the DEEP composition evaluations at the first layer) could be chosen freely
+        if layer_queries.len() != layer_proof.leaves().len() * N {
+            return Err(VerifierError::LayerCommitmentMismatch);
+        }
+        let layer_values = group_vector_elements(layer_queries);
+        if hash_values::<Self::Hasher, E, N>(&layer_values) != layer_proof.leaves() {
+            return Err(VerifierError::LayerCommitmentMismatch);
+        }
 
-        let layer_queries = self.take_next_fri_layer_queries();
-        Ok(group_vector_elements(layer_queries))
+        MerkleTree::<Self::Hasher>::verify_batch(commitment, positions, &layer_proof)
+            .map_err(|_| VerifierError::LayerCommitmentMismatch)?;
+        Ok(layer_values)
     }
 
     /// Returns FRI remainder values (last FRI layer) read from this channel.
@@ -172,6 +172,13 @@ impl StarkField for BaseElement {
     fn as_int(&self) -> Self::PositiveInteger {
         self.0
     }
+
+    /// Field arithmetic keeps values smaller than the modulus, but values in the range [M, 2^128)
+    /// can be read from memory directly, and these are not reduced.
+    #[inline]
+    fn is_reduced(&self) -> bool {
+        self.0 < M
+    }
 }
 
 impl Randomizable for BaseElement {
@@ -278,13 +285,12 @@ impl ExtensibleField<2> for BaseElement {
         [x[0] + x[1], Self::ZERO - x[1]]
     }
 
-    fn use_hint(a: [Self; 2]) -> Option<[Self; 2]> {
-        todo!()
+    /// Inverses in this field are computed directly, and thus, no hints are kept for them.
+    fn use_hint(_a: [Self; 2]) -> Option<[Self; 2]> {
+        None
     }
 
-    fn save_hint(a: [Self; 2], b: [Self; 2]) -> () {
-        todo!()
-    }
+    fn save_hint(_a: [Self; 2], _b: [Self; 2]) -> () {}
 }
 
 // CUBIC EXTENSION
@@ -122,6 +122,19 @@ fn conjugate() {
     assert_eq!(a, b);
 }
 
+#[test]
+fn is_reduced() {
+    let a = BaseElement::new(M - 1);
+    assert!(a.is_reduced());
+    assert!((a * a).is_reduced());
+
+    // values which are not smaller than the modulus are not reduced, and do not compare as equal
+    // to the elements they encode
+    let b = BaseElement(M + 1);
+    assert!(!b.is_reduced());
+    assert_ne!(BaseElement::ONE, b);
+}
+
 // ROOTS OF UNITY
 // ================================================================================================
 
@@ -159,6 +172,13 @@ fn quad_mul_base() {
     assert_eq!(expected, a.mul_base(b0));
 }
 
+#[test]
+fn quad_inv() {
+    // inverses in the extension are computed without hints
+    let a = <QuadExtension<BaseElement>>::new(rand_value(), rand_value());
+    assert_eq!(<QuadExtension<BaseElement>>::ONE, a * a.inv());
+}
+
 // SERIALIZATION / DESERIALIZATION
 // ================================================================================================
 
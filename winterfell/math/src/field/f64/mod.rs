@@ -14,6 +14,8 @@
 //!   and shifts.
 //! * $8$ is the 64th root of unity which opens up potential for optimized FFT implementations.
 
+#[cfg(feature = "schoolbook-mul")]
+use super::schoolbook::{mont_mul, mont_red};
 use super::{ExtensibleField, FieldElement, StarkField};
 use core::{
     convert::{TryFrom, TryInto},
@@ -78,7 +80,7 @@ impl BaseElement {
     /// Creates a new field element from the provided `value`; the value is converted into
     /// Montgomery representation.
     pub const fn new(value: u64) -> BaseElement {
-        Self(mont_red_cst((value as u128) * (R2 as u128)))
+        Self(mont_mul(value, R2))
     }
 
     /// Returns a new field element from the provided 'value'. Assumes that 'value' is already
@@ -283,7 +285,14 @@ impl StarkField for BaseElement {
 
     #[inline]
     fn as_int(&self) -> Self::PositiveInteger {
-        mont_red_cst(self.0 as u128)
+        mont_red(self.0 as u128)
+    }
+
+    /// Internal values in the range [M, 2^64) encode the same elements as values in the range
+    /// [0, 2^64 - M), and thus, only values smaller than the modulus are reduced.
+    #[inline]
+    fn is_reduced(&self) -> bool {
+        self.0 < M
     }
 }
 
@@ -360,7 +369,7 @@ impl Mul for BaseElement {
 
     #[inline]
     fn mul(self, rhs: Self) -> Self {
-        Self(mont_red_cst((self.0 as u128) * (rhs.0 as u128)))
+        Self(mont_mul(self.0, rhs.0))
     }
 }
 
@@ -520,7 +529,7 @@ impl From<u128> for BaseElement {
     fn from(x: u128) -> Self {
         //const R3: u128 = 1 (= 2^192 mod M );// thus we get that mont_red_var((mont_red_var(x) as u128) * R3) becomes
         //Self(mont_red_var(mont_red_var(x) as u128))  // Variable time implementation
-        Self(mont_red_cst(mont_red_cst(x) as u128)) // Constant time implementation
+        Self(mont_red(mont_red(x) as u128)) // Constant time implementation
     }
 }
 
@@ -640,6 +649,21 @@ fn exp_acc<const N: usize>(base: BaseElement, tail: BaseElement) -> BaseElement
     result * tail
 }
 
+/// Multiplies two elements in Montgomery representation with the default multiplication backend;
+/// see [super::schoolbook] for the backend selected by the `schoolbook-mul` feature.
+#[cfg(not(feature = "schoolbook-mul"))]
+#[inline(always)]
+const fn mont_mul(a: u64, b: u64) -> u64 {
+    mont_red_cst((a as u128) * (b as u128))
+}
+
+/// Montgomery reduction with the default multiplication backend.
+#[cfg(not(feature = "schoolbook-mul"))]
+#[inline(always)]
+const fn mont_red(x: u128) -> u64 {
+    mont_red_cst(x)
+}
+
 /// Montgomery reduction (variable time)
 #[allow(dead_code)]
 #[inline(always)]
@@ -656,6 +680,7 @@ const fn mont_red_var(x: u128) -> u64 {
 }
 
 /// Montgomery reduction (constant time)
+#[cfg_attr(feature = "schoolbook-mul", allow(dead_code))]
 #[inline(always)]
 const fn mont_red_cst(x: u128) -> u64 {
     // See reference above for a description of the following implementation.
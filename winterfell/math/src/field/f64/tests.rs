@@ -6,7 +6,7 @@
 use super::{
     mont_red_cst, BaseElement, DeserializationError, FieldElement, Serializable, StarkField, M,
 };
-use crate::field::{CubeExtension, ExtensionOf, QuadExtension};
+use crate::field::{schoolbook, CubeExtension, ExtensionOf, QuadExtension};
 use core::convert::TryFrom;
 use num_bigint::BigUint;
 use proptest::prelude::*;
@@ -129,6 +129,21 @@ fn equals() {
     assert_eq!(a.to_bytes(), b.to_bytes());
 }
 
+#[test]
+fn is_reduced() {
+    let a = BaseElement::new(u64::MAX);
+    let b = a * BaseElement::new(M - 1) + BaseElement::new(M - 1);
+    assert!(a.is_reduced());
+    assert!(b.is_reduced());
+
+    // the same element, represented by a value which is not reduced, does not compare as equal
+    let c = BaseElement::from_mont(1);
+    let d = BaseElement::from_mont(M + 1);
+    assert!(!d.is_reduced());
+    assert_eq!(c.as_int(), d.as_int());
+    assert_ne!(c, d);
+}
+
 // ROOTS OF UNITY
 // ------------------------------------------------------------------------------------------------
 
@@ -423,6 +438,13 @@ proptest! {
         prop_assert_eq!(expected, result.as_int());
     }
 
+    #[test]
+    fn mul_backends_proptest(a in any::<u64>(), b in any::<u64>()) {
+        // the default backend may return values in [M, 2^64), the schoolbook backend never does
+        let expected = mont_red_cst((a as u128) * (b as u128)) % M;
+        prop_assert_eq!(expected, schoolbook::mont_mul(a, b));
+    }
+
     #[test]
     fn double_proptest(x in any::<u64>()) {
         let v = BaseElement::from(x);
@@ -14,6 +14,8 @@
 //!   and shifts.
 //! * $8$ is the 64th root of unity which opens up potential for optimized FFT implementations.
 
+#[cfg(feature = "schoolbook-mul")]
+use super::schoolbook::{mont_mul, mont_red};
 use super::{ExtensibleField, FieldElement, StarkField};
 use core::marker::PhantomData;
 use core::{
@@ -105,7 +107,7 @@ impl<A: NativeMontMul> AccelBaseElementRisc0<A> {
     /// Montgomery representation.
     pub const fn convert_into(value: u64) -> AccelBaseElementRisc0<A> {
         Self {
-            val: mont_red_cst((value as u128) * (R2 as u128)),
+            val: mont_mul(value, R2),
             t: PhantomData,
         }
     }
@@ -310,7 +312,14 @@ impl<A: NativeMontMul> StarkField for AccelBaseElementRisc0<A> {
 
     #[inline]
     fn as_int(&self) -> Self::PositiveInteger {
-        mont_red_cst(self.val as u128)
+        mont_red(self.val as u128)
+    }
+
+    /// Internal values in the range [M, 2^64) encode the same elements as values in the range
+    /// [0, 2^64 - M), and thus, only values smaller than the modulus are reduced.
+    #[inline]
+    fn is_reduced(&self) -> bool {
+        self.val < M
     }
 }
 
@@ -387,7 +396,7 @@ impl<A: NativeMontMul> Mul for AccelBaseElementRisc0<A> {
 
     #[inline]
     fn mul(self, rhs: Self) -> Self {
-        Self::from_mont(mont_red_cst((self.val as u128) * (rhs.val as u128)))
+        Self::from_mont(mont_mul(self.val, rhs.val))
     }
 }
 
@@ -548,7 +557,7 @@ impl<A: NativeMontMul> From<u128> for AccelBaseElementRisc0<A> {
     fn from(x: u128) -> Self {
         //const R3: u128 = 1 (= 2^192 mod M );// thus we get that mont_red_var((mont_red_var(x) as u128) * R3) becomes
         //Self(mont_red_var(mont_red_var(x) as u128))  // Variable time implementation
-        Self::from_mont(mont_red_cst(mont_red_cst(x) as u128)) // Constant time implementation
+        Self::from_mont(mont_red(mont_red(x) as u128)) // Constant time implementation
     }
 }
 
@@ -671,6 +680,21 @@ fn exp_acc<const N: usize, A: NativeMontMul>(
     result * tail
 }
 
+/// Multiplies two elements in Montgomery representation with the default multiplication backend;
+/// see [super::schoolbook] for the backend selected by the `schoolbook-mul` feature.
+#[cfg(not(feature = "schoolbook-mul"))]
+#[inline(always)]
+const fn mont_mul(a: u64, b: u64) -> u64 {
+    mont_red_cst((a as u128) * (b as u128))
+}
+
+/// Montgomery reduction with the default multiplication backend.
+#[cfg(not(feature = "schoolbook-mul"))]
+#[inline(always)]
+const fn mont_red(x: u128) -> u64 {
+    mont_red_cst(x)
+}
+
 /// Montgomery reduction (variable time)
 #[allow(dead_code)]
 #[inline(always)]
@@ -687,6 +711,7 @@ const fn mont_red_var(x: u128) -> u64 {
 }
 
 /// Montgomery reduction (constant time)
+#[cfg_attr(feature = "schoolbook-mul", allow(dead_code))]
 #[inline(always)]
 const fn mont_red_cst(x: u128) -> u64 {
     // See reference above for a description of the following implementation.
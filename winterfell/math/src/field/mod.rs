@@ -11,5 +11,8 @@ pub mod f62;
 pub mod f64;
 pub mod f64_risc0;
 
+#[cfg_attr(not(feature = "schoolbook-mul"), allow(dead_code))]
+mod schoolbook;
+
 mod extensions;
 pub use extensions::{CubeExtension, QuadExtension};
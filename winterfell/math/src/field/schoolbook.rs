@@ -0,0 +1,110 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Reference implementation of the Montgomery multiplication in the 64-bit prime field with
+//! modulus $2^{64} - 2^{32} + 1$, used by the `f64` and `f64_risc0` fields when the crate is
+//! compiled with the `schoolbook-mul` feature.
+//!
+//! The product of two elements is computed by schoolbook multiplication of their 32-bit limbs,
+//! and reduced with plain 128-bit remainders instead of the constant-time reduction of the
+//! fields. This is much slower than the default backend, but relies on no bit tricks, and is kept
+//! out of line, so that its results do not depend on how the compiler inlines and schedules the
+//! field arithmetic of its callers; results which differ between the two backends point at a
+//! miscompilation of the default one.
+
+// CONSTANTS
+// ================================================================================================
+
+/// Field modulus = 2^64 - 2^32 + 1
+const M: u64 = 0xFFFFFFFF00000001;
+
+/// 2^-64 mod M, i.e., the inverse of the Montgomery radix; since 2^192 = 1 mod M, this is equal
+/// to 2^128 mod M.
+const R_INV: u64 = 0xFFFFFFFE00000001;
+
+/// Mask of the low 32-bit limb of a 64-bit value.
+const LIMB_MASK: u64 = 0xFFFFFFFF;
+
+// MULTIPLICATION
+// ================================================================================================
+
+/// Multiplies two field elements in Montgomery representation, and returns their product in
+/// Montgomery representation, in the range [0, M).
+#[inline(never)]
+pub const fn mont_mul(a: u64, b: u64) -> u64 {
+    mont_red(mul_wide(a, b))
+}
+
+/// Returns x * 2^-64 mod M, in the range [0, M); for x = a * b, with a and b in Montgomery
+/// representation, this is the product of a and b in Montgomery representation.
+#[inline(never)]
+pub const fn mont_red(x: u128) -> u64 {
+    let x = (x % (M as u128)) as u64;
+    (mul_wide(x, R_INV) % (M as u128)) as u64
+}
+
+/// Returns the 128-bit product of a and b, computed from their 32-bit limbs.
+const fn mul_wide(a: u64, b: u64) -> u128 {
+    let (a0, a1) = (a & LIMB_MASK, a >> 32);
+    let (b0, b1) = (b & LIMB_MASK, b >> 32);
+
+    // none of the partial products of two 32-bit limbs overflows 64 bits, and their sum is the
+    // product of a and b, which does not overflow 128 bits
+    let low = (a0 * b0) as u128;
+    let mid = (a0 * b1) as u128 + (a1 * b0) as u128;
+    let high = (a1 * b1) as u128;
+    low + (mid << 32) + (high << 64)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{mont_mul, mont_red, mul_wide, M, R_INV};
+    use proptest::prelude::*;
+
+    /// 2^64 mod M, i.e., the Montgomery representation of one.
+    const R: u64 = 0xFFFFFFFF;
+
+    #[test]
+    fn mont_mul_identity() {
+        assert_eq!(R, mont_mul(R, R));
+        assert_eq!(0, mont_mul(0, R));
+        assert_eq!(1, mont_red(R as u128));
+        assert_eq!(R_INV, mont_red(1));
+    }
+
+    #[test]
+    fn mont_mul_of_large_values() {
+        // M - 1 = -1, in Montgomery representation M - R
+        let minus_one = M - R;
+        assert_eq!(R, mont_mul(minus_one, minus_one));
+        assert_eq!(minus_one, mont_mul(minus_one, R));
+
+        // values in [M, 2^64) encode the same elements as values in [0, 2^64 - M)
+        assert_eq!(5, mont_mul(M + 5, R));
+        assert_eq!(
+            u128::MAX - 2 * u64::MAX as u128,
+            mul_wide(u64::MAX, u64::MAX)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn mul_wide_proptest(a in any::<u64>(), b in any::<u64>()) {
+            prop_assert_eq!(a as u128 * b as u128, mul_wide(a, b));
+        }
+
+        #[test]
+        fn mont_red_proptest(x in any::<u128>()) {
+            let result = mont_red(x);
+            prop_assert!(result < M);
+            // 2^64 * result = x mod M
+            let radix = (1u128 << 64) % M as u128;
+            prop_assert_eq!(x % M as u128, (result as u128 * radix) % M as u128);
+        }
+    }
+}
@@ -239,6 +239,18 @@ pub trait StarkField: FieldElement<BaseField = Self> {
 
     /// Returns a canonical integer representation of this field element.
     fn as_int(&self) -> Self::PositiveInteger;
+
+    /// Returns true if the internal representation of this field element is reduced, i.e., if it
+    /// is the unique internal representation of the element.
+    ///
+    /// Field arithmetic always produces reduced elements, but elements whose internal
+    /// representation is read from memory directly (e.g., from a zero-copy archive) may not be
+    /// reduced. Equality of elements is defined over the internal representation, and thus, an
+    /// element which is not reduced does not compare as equal to the same element in reduced
+    /// form. Fields whose internal representation is always unique return true.
+    fn is_reduced(&self) -> bool {
+        true
+    }
 }
 
 // EXTENSIBLE FIELD
@@ -11,7 +11,7 @@ use core::marker::PhantomData;
 use crypto::{ElementHasher, RandomCoin};
 use fri::{self, FriProof};
 use math::FieldElement;
-use utils::{collections::Vec, Serializable};
+use utils::collections::Vec;
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
@@ -52,8 +52,7 @@ where
         // build a seed for the public coin; the initial seed is the hash of public inputs and proof
         // context, but as the protocol progresses, the coin will be reseeded with the info sent to
         // the verifier
-        let mut coin_seed = pub_inputs_bytes;
-        context.write_into(&mut coin_seed);
+        let coin_seed = context.public_coin_seed(&pub_inputs_bytes);
 
         ProverChannel {
             air,
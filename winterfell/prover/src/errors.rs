@@ -5,6 +5,7 @@
 
 //! Contains common error types for prover and verifier.
 
+use air::HashFunction;
 use core::fmt;
 
 // PROVER ERROR
@@ -21,6 +22,10 @@ pub enum ProverError {
     /// This error occurs when the base field specified by the AIR does not support field extension
     /// of degree specified by proof options.
     UnsupportedFieldExtension(usize),
+    /// This error occurs when the hash function specified by proof options is defined over a
+    /// specific base field, or is not implemented by this library, and thus, it cannot be
+    /// instantiated by the generic prover.
+    UnsupportedHashFunction(HashFunction),
 }
 
 impl fmt::Display for ProverError {
@@ -36,6 +41,9 @@ impl fmt::Display for ProverError {
             Self::UnsupportedFieldExtension(degree) => {
                 write!(f, "field extension of degree {} is not supported for the specified base field", degree)
             }
+            Self::UnsupportedHashFunction(hash_fn) => {
+                write!(f, "hash function {:?} cannot be used by the generic prover; use prove_with_hasher() instead", hash_fn)
+            }
         }
     }
 }
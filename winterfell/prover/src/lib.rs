@@ -66,7 +66,10 @@ use math::{
 
 pub use crypto;
 use crypto::{
-    hashers::{Blake3_192, Blake3_256, DefaultSha2, Sha2_256, Sha3_256},
+    hashers::{
+        Blake3_192, Blake3_256, DefaultSha2, DefaultSha384, DefaultSha512, Keccak256, Sha2_256,
+        Sha2_384, Sha2_512, Sha3_256,
+    },
     ElementHasher, MerkleTree,
 };
 
@@ -149,6 +152,16 @@ pub trait Prover {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the bytes appended to the serialized public inputs in the seed of the public coin
+    /// of a proof for the provided trace; this is empty by default.
+    ///
+    /// Provers may bind data which is not part of the public inputs of the AIR to the proof in
+    /// this way, e.g., a commitment to auxiliary data of an application; the verifier must seed
+    /// its coin with the same extension for the proof to verify.
+    fn get_seed_extension(&self, _trace: &Self::Trace) -> Vec<u8> {
+        Vec::new()
+    }
+
     /// Returns a STARK proof attesting to a correct execution of a computation defined by the
     /// provided trace.
     ///
@@ -166,6 +179,11 @@ pub trait Prover {
                 HashFunction::Blake3_192 => self.generate_proof::<Self::BaseField, Blake3_192<Self::BaseField>>(trace),
                 HashFunction::Sha3_256 => self.generate_proof::<Self::BaseField, Sha3_256<Self::BaseField>>(trace),
                 HashFunction::Sha2_256 => self.generate_proof::<Self::BaseField, Sha2_256<Self::BaseField, DefaultSha2>>(trace),
+                HashFunction::Sha2_512 => self.generate_proof::<Self::BaseField, Sha2_512<Self::BaseField, DefaultSha512>>(trace),
+                HashFunction::Sha2_384 => self.generate_proof::<Self::BaseField, Sha2_384<Self::BaseField, DefaultSha384>>(trace),
+                HashFunction::Keccak256 => self.generate_proof::<Self::BaseField, Keccak256<Self::BaseField>>(trace),
+                HashFunction::Rp64_256 => Err(ProverError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+                HashFunction::Poseidon64_256 => Err(ProverError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
             },
             FieldExtension::Quadratic => {
                 if !<QuadExtension<Self::BaseField>>::is_supported() {
@@ -176,6 +194,11 @@ pub trait Prover {
                     HashFunction::Blake3_192 => self.generate_proof::<QuadExtension<Self::BaseField>, Blake3_192<Self::BaseField>>(trace),
                     HashFunction::Sha3_256 => self.generate_proof::<QuadExtension<Self::BaseField>, Sha3_256<Self::BaseField>>(trace),
                     HashFunction::Sha2_256 => self.generate_proof::<QuadExtension<Self::BaseField>, Sha2_256<Self::BaseField, DefaultSha2>>(trace),
+                    HashFunction::Sha2_512 => self.generate_proof::<QuadExtension<Self::BaseField>, Sha2_512<Self::BaseField, DefaultSha512>>(trace),
+                    HashFunction::Sha2_384 => self.generate_proof::<QuadExtension<Self::BaseField>, Sha2_384<Self::BaseField, DefaultSha384>>(trace),
+                    HashFunction::Keccak256 => self.generate_proof::<QuadExtension<Self::BaseField>, Keccak256<Self::BaseField>>(trace),
+                    HashFunction::Rp64_256 => Err(ProverError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+                    HashFunction::Poseidon64_256 => Err(ProverError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
                 }
             }
             FieldExtension::Cubic => {
@@ -187,7 +210,42 @@ pub trait Prover {
                     HashFunction::Blake3_192 => self.generate_proof::<CubeExtension<Self::BaseField>, Blake3_192<Self::BaseField>>(trace),
                     HashFunction::Sha3_256 => self.generate_proof::<CubeExtension<Self::BaseField>, Sha3_256<Self::BaseField>>(trace),
                     HashFunction::Sha2_256 => self.generate_proof::<CubeExtension<Self::BaseField>, Sha2_256<Self::BaseField, DefaultSha2>>(trace),
+                    HashFunction::Sha2_512 => self.generate_proof::<CubeExtension<Self::BaseField>, Sha2_512<Self::BaseField, DefaultSha512>>(trace),
+                    HashFunction::Sha2_384 => self.generate_proof::<CubeExtension<Self::BaseField>, Sha2_384<Self::BaseField, DefaultSha384>>(trace),
+                    HashFunction::Keccak256 => self.generate_proof::<CubeExtension<Self::BaseField>, Keccak256<Self::BaseField>>(trace),
+                    HashFunction::Rp64_256 => Err(ProverError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+                    HashFunction::Poseidon64_256 => Err(ProverError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
+                }
+            }
+        }
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, with all commitments made with the hash function `H`.
+    ///
+    /// This is the counterpart of [Self::prove()](Prover::prove) for hash functions which the
+    /// generic dispatch of that method cannot instantiate for an arbitrary base field, i.e.,
+    /// [HashFunction::Rp64_256], or which it does not implement, i.e.,
+    /// [HashFunction::Poseidon64_256]. The hash function specified by the proof options is
+    /// written into the proof as is; it is up to the caller to make sure that it names `H`.
+    #[rustfmt::skip]
+    fn prove_with_hasher<H>(&self, trace: Self::Trace) -> Result<StarkProof, ProverError>
+    where
+        H: ElementHasher<BaseField = Self::BaseField>,
+    {
+        match self.options().field_extension() {
+            FieldExtension::None => self.generate_proof::<Self::BaseField, H>(trace),
+            FieldExtension::Quadratic => {
+                if !<QuadExtension<Self::BaseField>>::is_supported() {
+                    return Err(ProverError::UnsupportedFieldExtension(2));
+                }
+                self.generate_proof::<QuadExtension<Self::BaseField>, H>(trace)
+            }
+            FieldExtension::Cubic => {
+                if !<CubeExtension<Self::BaseField>>::is_supported() {
+                    return Err(ProverError::UnsupportedFieldExtension(3));
                 }
+                self.generate_proof::<CubeExtension<Self::BaseField>, H>(trace)
             }
         }
     }
@@ -210,6 +268,7 @@ pub trait Prover {
         let pub_inputs = self.get_pub_inputs(&trace);
         let mut pub_inputs_bytes = Vec::new();
         pub_inputs.write_into(&mut pub_inputs_bytes);
+        pub_inputs_bytes.extend_from_slice(&self.get_seed_extension(&trace));
 
         // create an instance of AIR for the provided parameters. this takes a generic description
         // of the computation (provided via AIR type), and creates a description of a specific
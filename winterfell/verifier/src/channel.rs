@@ -22,11 +22,20 @@ use utils::{collections::Vec, string::ToString};
 /// A channel is instantiated for a specific proof, which is parsed into structs over the
 /// appropriate field (specified by type parameter `E`). This also validates that the proof is
 /// well-formed in the context of the computation for the specified [Air].
+///
+/// Commitments to the execution trace are made with the hash function specified by type
+/// parameter `HC`, and all other commitments with the hash function specified by `H`. Unless the
+/// proof uses hybrid commitments (see [verify_hybrid()](crate::verify_hybrid)), both are the
+/// same hash function.
 #[derive(Archive, Deserialize, Serialize)]
-pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+pub struct VerifierChannel<
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    HC: ElementHasher<BaseField = E::BaseField> = H,
+> {
     // trace queries
-    trace_roots: Vec<H::Digest>,
-    trace_queries: Option<TraceQueries<E, H>>,
+    trace_roots: Vec<HC::Digest>,
+    trace_queries: Option<TraceQueries<E, HC>>,
     // constraint queries
     constraint_root: H::Digest,
     constraint_queries: Option<ConstraintQueries<E, H>>,
@@ -43,7 +52,12 @@ pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::Base
     pow_nonce: u64,
 }
 
-impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChannel<E, H> {
+impl<E, H, HC> VerifierChannel<E, H, HC>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    HC: ElementHasher<BaseField = E::BaseField>,
+{
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Creates and returns a new [VerifierChannel] initialized from the specified `proof`.
@@ -74,7 +88,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
 
         // --- parse commitments ------------------------------------------------------------------
         let (trace_roots, constraint_root, fri_roots) = commitments
-            .parse::<H>(
+            .parse_hybrid::<HC, H>(
                 num_trace_segments,
                 fri_options.num_fri_layers(lde_domain_size),
             )
@@ -120,6 +134,53 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         })
     }
 
+    // VALIDATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that all field elements carried by this channel are in canonical form.
+    ///
+    /// Elements parsed from a proof are always canonical, but a channel which is accessed in
+    /// place from a zero-copy archive, or deserialized from one, carries the internal
+    /// representation of its elements exactly as it was archived. An element which is not in
+    /// canonical form does not compare as equal to the same element computed by the verifier,
+    /// which would make the verification of an honest proof fail in unexpected places (e.g., as
+    /// an out-of-domain consistency mismatch). This check should thus be performed before a
+    /// channel obtained from an archive is read.
+    ///
+    /// # Errors
+    /// Returns an error if any of the unread elements of this channel is not in canonical form.
+    pub fn check_canonical_elements(&self) -> Result<(), VerifierError> {
+        if let Some(frame) = &self.ood_trace_frame {
+            check_canonical(frame.main_rows(), "out-of-domain main trace frame")?;
+            check_canonical(frame.aux_rows(), "out-of-domain auxiliary trace frame")?;
+        }
+        if let Some(evaluations) = &self.ood_constraint_evaluations {
+            check_canonical(evaluations, "out-of-domain constraint evaluations")?;
+        }
+        if let Some(queries) = &self.trace_queries {
+            for row in queries.main_states.rows() {
+                check_canonical(row, "main trace queries")?;
+            }
+            for aux_states in queries.aux_states.iter() {
+                for row in aux_states.rows() {
+                    check_canonical(row, "auxiliary trace queries")?;
+                }
+            }
+        }
+        if let Some(queries) = &self.constraint_queries {
+            for row in queries.evaluations.rows() {
+                check_canonical(row, "constraint evaluation queries")?;
+            }
+        }
+        for layer in self.fri_layer_queries.iter() {
+            check_canonical(layer, "FRI layer queries")?;
+        }
+        if let Some(remainder) = &self.fri_remainder {
+            check_canonical(remainder, "FRI remainder")?;
+        }
+        Ok(())
+    }
+
     // DATA READERS
     // --------------------------------------------------------------------------------------------
 
@@ -127,7 +188,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
     ///
     /// For computations requiring multiple trace segment, the returned slice will contain a
     /// commitment for each trace segment.
-    pub fn read_trace_commitments(&self) -> &[H::Digest] {
+    pub fn read_trace_commitments(&self) -> &[HC::Digest] {
         &self.trace_roots
     }
 
@@ -143,8 +204,10 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
     /// polynomials are also included as the second value of the returned tuple. Otherwise, the
     /// second value is None.
     pub fn read_ood_trace_frame(&mut self) -> (EvaluationFrame<E>, Option<EvaluationFrame<E>>) {
-        let frame = self.ood_trace_frame.take().expect("already read");
-        (frame.main_frame, frame.aux_frame)
+        self.ood_trace_frame
+            .take()
+            .expect("already read")
+            .into_frames()
     }
 
     /// Returns evaluations of composition polynomial columns at z^m, where z is the out-of-domain
@@ -166,12 +229,39 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
     /// For computations requiring multiple trace segments, trace states for auxiliary segments
     /// are also included as the second value of the returned tuple (trace states for all auxiliary
     /// segments are merged into a single table). Otherwise, the second value is None.
+    ///
+    /// # Errors
+    /// Returns an error if the number of trace segments, or the width of the states of any
+    /// segment, differs from the trace layout of the specified `air`; a channel deserialized from
+    /// an untrusted source may omit segments or columns, which would otherwise never reach the
+    /// DEEP composition. Returns an error if the states do not match the trace commitment.
     #[allow(clippy::type_complexity)]
-    pub fn read_queried_trace_states(
+    pub fn read_queried_trace_states<A: Air<BaseField = E::BaseField>>(
         &mut self,
+        air: &A,
         positions: &[usize],
     ) -> Result<(Table<E::BaseField>, Option<Table<E>>), VerifierError> {
         let queries = self.trace_queries.take().expect("already read");
+        check_trace_queries_shape(air, &self.trace_roots, &queries)?;
+
+        // make sure the states included in the proof hash into the leaves of the authentication
+        // paths; the leaves are hashed from the states when the channel is built, but a channel
+        // deserialized from an untrusted source (e.g., a proof packaged for a guest) carries the
+        // states and the paths separately
+        let (main_proof, aux_proofs) = queries
+            .query_proofs
+            .split_first()
+            .ok_or(VerifierError::TraceQueryDoesNotMatchCommitment)?;
+        if aux_proofs.len() != queries.aux_states.len()
+            || !hashes_into_leaves(&queries.main_states, main_proof)
+            || !queries
+                .aux_states
+                .iter()
+                .zip(aux_proofs)
+                .all(|(states, proof)| hashes_into_leaves(states, proof))
+        {
+            return Err(VerifierError::TraceQueryDoesNotMatchCommitment);
+        }
 
         // make sure the states included in the proof correspond to the trace commitment
         for (root, proof) in self.trace_roots.iter().zip(queries.query_proofs.iter()) {
@@ -179,32 +269,127 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
                 .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)?;
         }
 
-        Ok((queries.main_states, queries.aux_states))
+        // merge tables for each auxiliary segment into a single table
+        let aux_states = if queries.aux_states.is_empty() {
+            None
+        } else {
+            Some(Table::merge(queries.aux_states))
+        };
+        Ok((queries.main_states, aux_states))
     }
 
     /// Returns constraint evaluations at the specified positions of the LDE domain. This also
     /// checks if the constraint evaluations are valid against the constraint commitment sent by
     /// the prover.
-    pub fn read_constraint_evaluations(
+    ///
+    /// # Errors
+    /// Returns an error if the number of evaluation columns differs from the number of
+    /// composition columns of the specified `air`, or if the evaluations do not match the
+    /// constraint commitment.
+    pub fn read_constraint_evaluations<A: Air<BaseField = E::BaseField>>(
         &mut self,
+        air: &A,
         positions: &[usize],
     ) -> Result<Table<E>, VerifierError> {
         let queries = self.constraint_queries.take().expect("already read");
+        if queries.evaluations.num_columns() != air.ce_blowup_factor() {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "expected {} constraint evaluation columns, but the queries open {}",
+                air.ce_blowup_factor(),
+                queries.evaluations.num_columns()
+            )));
+        }
 
+        // make sure the evaluations included in the proof hash into the leaves of the
+        // authentication paths, for the same reason as the trace states
+        if !hashes_into_leaves(&queries.evaluations, &queries.query_proofs) {
+            return Err(VerifierError::ConstraintQueryDoesNotMatchCommitment);
+        }
         MerkleTree::verify_batch(&self.constraint_root, positions, &queries.query_proofs)
             .map_err(|_| VerifierError::ConstraintQueryDoesNotMatchCommitment)?;
 
         Ok(queries.evaluations)
     }
+
+    // DATA MUTATORS
+    // --------------------------------------------------------------------------------------------
+    // These methods give mutable access to the data parsed from the proof, so that tests can
+    // check that a verifier rejects proofs which were tampered with after they were packaged.
+
+    /// Returns mutable trace commitments sent by the prover.
+    pub fn trace_commitments_mut(&mut self) -> &mut [HC::Digest] {
+        &mut self.trace_roots
+    }
+
+    /// Returns mutable constraint commitment sent by the prover.
+    pub fn constraint_commitment_mut(&mut self) -> &mut H::Digest {
+        &mut self.constraint_root
+    }
+
+    /// Returns mutable current and next rows of the main trace frame of the out-of-domain
+    /// evaluations, or None if the frame has already been read.
+    pub fn ood_main_trace_frame_mut(&mut self) -> Option<(&mut [E], &mut [E])> {
+        self.ood_trace_frame
+            .as_mut()
+            .map(|frame| frame.main_rows_mut())
+    }
+
+    /// Returns mutable out-of-domain constraint evaluations, or None if they have already been
+    /// read.
+    pub fn ood_constraint_evaluations_mut(&mut self) -> Option<&mut Vec<E>> {
+        self.ood_constraint_evaluations.as_mut()
+    }
+
+    /// Returns mutable queried states of the main trace segment, or None if they have already been
+    /// read.
+    pub fn queried_main_trace_states_mut(&mut self) -> Option<&mut Table<E::BaseField>> {
+        self.trace_queries
+            .as_mut()
+            .map(|queries| &mut queries.main_states)
+    }
+
+    /// Returns mutable queried states of the auxiliary trace segments, one table per segment, or
+    /// None if they have already been read.
+    pub fn queried_aux_trace_states_mut(&mut self) -> Option<&mut Vec<Table<E>>> {
+        self.trace_queries
+            .as_mut()
+            .map(|queries| &mut queries.aux_states)
+    }
+
+    /// Returns mutable Merkle authentication paths of the trace queries, one per trace segment,
+    /// or None if they have already been read.
+    pub fn trace_query_proofs_mut(&mut self) -> Option<&mut Vec<BatchMerkleProof<HC>>> {
+        self.trace_queries
+            .as_mut()
+            .map(|queries| &mut queries.query_proofs)
+    }
+
+    /// Returns mutable queried constraint evaluations, or None if they have already been read.
+    pub fn queried_constraint_evaluations_mut(&mut self) -> Option<&mut Table<E>> {
+        self.constraint_queries
+            .as_mut()
+            .map(|queries| &mut queries.evaluations)
+    }
+
+    /// Returns mutable queried evaluations of all FRI layers which have not been read yet.
+    pub fn fri_layer_queries_mut(&mut self) -> &mut [Vec<E>] {
+        &mut self.fri_layer_queries
+    }
+
+    /// Returns mutable query proof-of-work nonce sent by the prover.
+    pub fn pow_nonce_mut(&mut self) -> &mut u64 {
+        &mut self.pow_nonce
+    }
 }
 
 // FRI VERIFIER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<E, H> FriVerifierChannel<E> for VerifierChannel<E, H>
+impl<E, H, HC> FriVerifierChannel<E> for VerifierChannel<E, H, HC>
 where
     E: FieldElement,
     H: ElementHasher<BaseField = E::BaseField>,
+    HC: ElementHasher<BaseField = E::BaseField>,
 {
     type Hasher = H;
 
@@ -229,6 +414,85 @@ where
     }
 }
 
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns an error naming the specified part of a proof if any of the provided elements is not
+/// in canonical form.
+fn check_canonical<E: FieldElement>(elements: &[E], source: &str) -> Result<(), VerifierError> {
+    if E::as_base_elements(elements)
+        .iter()
+        .all(|element| element.is_reduced())
+    {
+        Ok(())
+    } else {
+        Err(VerifierError::ProofDeserializationError(format!(
+            "field elements of the {} are not in canonical form",
+            source
+        )))
+    }
+}
+
+/// Checks that the provided trace queries open every segment of the trace layout of the specified
+/// AIR, with states as wide as the segment, and that there is a trace commitment per segment.
+fn check_trace_queries_shape<A, E, H>(
+    air: &A,
+    trace_roots: &[H::Digest],
+    queries: &TraceQueries<E, H>,
+) -> Result<(), VerifierError>
+where
+    A: Air<BaseField = E::BaseField>,
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    let layout = air.trace_layout();
+    let num_segments = layout.num_segments();
+    if trace_roots.len() != num_segments
+        || queries.query_proofs.len() != num_segments
+        || queries.aux_states.len() != layout.num_aux_segments()
+    {
+        return Err(VerifierError::ProofDeserializationError(format!(
+            "expected {} trace segments, but the proof commits to {} and the queries open {}",
+            num_segments,
+            trace_roots.len(),
+            queries.aux_states.len() + 1
+        )));
+    }
+
+    if queries.main_states.num_columns() != layout.main_trace_width() {
+        return Err(VerifierError::ProofDeserializationError(format!(
+            "expected {} main trace columns, but the queries open {}",
+            layout.main_trace_width(),
+            queries.main_states.num_columns()
+        )));
+    }
+    for (i, states) in queries.aux_states.iter().enumerate() {
+        if states.num_columns() != layout.get_aux_segment_width(i) {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "expected {} columns in auxiliary trace segment {}, but the queries open {}",
+                layout.get_aux_segment_width(i),
+                i,
+                states.num_columns()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if the rows of the provided table hash into the leaves of the provided proof, one
+/// row per leaf.
+///
+/// The rows are hashed as a single batch, which lets hashers amortize the serialization of the
+/// rows across the whole table.
+fn hashes_into_leaves<E, H>(table: &Table<E>, proof: &BatchMerkleProof<H>) -> bool
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    table.num_rows() == proof.leaves().len()
+        && H::hash_elements_batch(table.data(), table.num_columns()) == proof.leaves()
+}
+
 // TRACE QUERIES
 // ================================================================================================
 
@@ -236,13 +500,15 @@ where
 /// * Queried states for all trace segments.
 /// * Merkle authentication paths for all queries.
 ///
-/// Trace states for all auxiliary segments are stored in a single table.
+/// Trace states of every auxiliary segment are stored in a separate table, so that they can be
+/// checked against the authentication paths of the segment; the tables are merged into a single
+/// table when the states are read.
 #[derive(Archive, Deserialize, Serialize, Debug)]
 #[archive(compare(PartialEq))]
 struct TraceQueries<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
     query_proofs: Vec<BatchMerkleProof<H>>,
     main_states: Table<E::BaseField>,
-    aux_states: Option<Table<E>>,
+    aux_states: Vec<Table<E>>,
 }
 
 impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E, H> {
@@ -278,11 +544,10 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
         // all query proofs will be aggregated into a single vector
         let mut query_proofs = vec![main_segment_query_proofs];
 
-        // parse auxiliary trace segment queries (if any), and merge resulting tables into a
-        // single table; parsing also validates that hashes of each table row form the leaves
-        // of Merkle authentication paths in the proofs
-        let aux_trace_states = if air.trace_info().is_multi_segment() {
-            let mut aux_trace_states = Vec::new();
+        // parse auxiliary trace segment queries (if any); parsing also validates that hashes of
+        // each table row form the leaves of Merkle authentication paths in the proofs
+        let mut aux_trace_states = Vec::new();
+        if air.trace_info().is_multi_segment() {
             for (i, segment_queries) in queries.into_iter().enumerate() {
                 let segment_width = air.trace_layout().get_aux_segment_width(i);
                 let (segment_query_proof, segment_trace_states) = segment_queries
@@ -297,12 +562,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
                 query_proofs.push(segment_query_proof);
                 aux_trace_states.push(segment_trace_states);
             }
-
-            // merge tables for each auxiliary segment into a single table
-            Some(Table::merge(aux_trace_states))
-        } else {
-            None
-        };
+        }
 
         Ok(Self {
             query_proofs,
@@ -352,18 +612,76 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintQuer
 // TRACE OUT-OF-DOMAIN FRAME
 // ================================================================================================
 
+/// Out-of-domain evaluations of the main and auxiliary trace segments.
+///
+/// The evaluations are kept in a single vector prefixed by the widths of the segments, rather
+/// than in an [EvaluationFrame] per segment, so that a channel accessed in place from an archive
+/// holds one contiguous run of elements instead of four separately allocated rows. The vector
+/// consists of the current and the next rows of the main segment, followed by the current and
+/// the next rows of the auxiliary segments; the auxiliary rows are empty if the trace has a single
+/// segment.
 #[derive(Archive, Deserialize, Serialize, Debug)]
 #[archive(compare(PartialEq))]
 struct TraceOodFrame<E: FieldElement> {
-    main_frame: EvaluationFrame<E>,
-    aux_frame: Option<EvaluationFrame<E>>,
+    main_width: u32,
+    aux_width: u32,
+    values: Vec<E>,
 }
 
 impl<E: FieldElement> TraceOodFrame<E> {
     pub fn new(main_frame: EvaluationFrame<E>, aux_frame: Option<EvaluationFrame<E>>) -> Self {
+        let main_width = main_frame.current().len();
+        let aux_width = aux_frame.as_ref().map_or(0, |frame| frame.current().len());
+
+        let mut values = Vec::with_capacity(2 * (main_width + aux_width));
+        values.extend_from_slice(main_frame.current());
+        values.extend_from_slice(main_frame.next());
+        if let Some(aux_frame) = aux_frame {
+            values.extend_from_slice(aux_frame.current());
+            values.extend_from_slice(aux_frame.next());
+        }
+
         Self {
-            main_frame,
-            aux_frame,
+            main_width: main_width as u32,
+            aux_width: aux_width as u32,
+            values,
         }
     }
+
+    /// Returns the current row of the main segment followed by its next row.
+    pub fn main_rows(&self) -> &[E] {
+        &self.values[..2 * self.main_width as usize]
+    }
+
+    /// Returns the current row of the auxiliary segments followed by their next row; this is
+    /// empty if the trace has a single segment.
+    pub fn aux_rows(&self) -> &[E] {
+        &self.values[2 * self.main_width as usize..]
+    }
+
+    /// Returns mutable current and next rows of the main segment.
+    pub fn main_rows_mut(&mut self) -> (&mut [E], &mut [E]) {
+        let main_width = self.main_width as usize;
+        self.values[..2 * main_width].split_at_mut(main_width)
+    }
+
+    /// Splits the evaluations into the frames of the main and auxiliary segments; the frame of
+    /// the auxiliary segments is None if the trace has a single segment.
+    ///
+    /// The current row of the main segment reuses the allocation of the evaluations.
+    pub fn into_frames(self) -> (EvaluationFrame<E>, Option<EvaluationFrame<E>>) {
+        let main_width = self.main_width as usize;
+        let aux_width = self.aux_width as usize;
+
+        let mut current = self.values;
+        let mut next = current.split_off(main_width);
+        let aux_frame = if aux_width > 0 {
+            let mut aux_current = next.split_off(main_width);
+            let aux_next = aux_current.split_off(aux_width);
+            Some(EvaluationFrame::from_rows(aux_current, aux_next))
+        } else {
+            None
+        };
+        (EvaluationFrame::from_rows(current, next), aux_frame)
+    }
 }
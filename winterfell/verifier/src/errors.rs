@@ -5,6 +5,7 @@
 
 //! Contains common error types for prover and verifier.
 
+use air::HashFunction;
 use core::fmt;
 use utils::string::String;
 
@@ -41,6 +42,14 @@ pub enum VerifierError {
     /// constraint evaluation queries do not represent a polynomial of the degree expected by the
     /// verifier.
     FriVerificationFailed(fri::VerifierError),
+    /// This error occurs when the trace commitments of a proof with hybrid commitments cannot be
+    /// absorbed into the public coin, because the size of their digests differs from the size of
+    /// the digests of the hash function specified by the proof options.
+    InconsistentTraceCommitmentDigests(usize, usize),
+    /// This error occurs when the hash function specified by the proof options is defined over a
+    /// specific base field, or is not implemented by this library, and thus, it cannot be
+    /// instantiated by the generic verifier.
+    UnsupportedHashFunction(HashFunction),
 }
 
 impl fmt::Display for VerifierError {
@@ -74,6 +83,12 @@ impl fmt::Display for VerifierError {
             Self::FriVerificationFailed(err) => {
                 write!(f, "verification of low-degree proof failed: {}", err)
             }
+            Self::InconsistentTraceCommitmentDigests(trace_size, size) => {
+                write!(f, "trace commitments with {}-byte digests cannot be absorbed into a public coin over {}-byte digests", trace_size, size)
+            }
+            Self::UnsupportedHashFunction(hash_fn) => {
+                write!(f, "hash function {:?} cannot be used by the generic verifier; use verify_with_hasher() instead", hash_fn)
+            }
         }
     }
 }
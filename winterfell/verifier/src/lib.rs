@@ -48,14 +48,17 @@ use math::{
     FieldElement,
 };
 
-use utils::collections::Vec;
+use utils::{collections::Vec, string::ToString};
 pub use utils::{
     ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
 };
 
 pub use crypto;
 use crypto::{
-    hashers::{Blake3_192, Blake3_256, DefaultSha2, Sha2_256, Sha3_256},
+    hashers::{
+        Blake3_192, Blake3_256, DefaultSha2, DefaultSha384, DefaultSha512, Keccak256, Sha2_256,
+        Sha2_384, Sha2_512, Sha3_256,
+    },
     ElementHasher, RandomCoin,
 };
 
@@ -94,9 +97,7 @@ pub fn verify<AIR: Air>(
     // build a seed for the public coin; the initial seed is the hash of public inputs and proof
     // context, but as the protocol progresses, the coin will be reseeded with the info received
     // from the prover
-    let mut public_coin_seed = Vec::new();
-    pub_inputs.write_into(&mut public_coin_seed);
-    proof.context.write_into(&mut public_coin_seed);
+    let public_coin_seed = proof.context.public_coin_seed(&pub_inputs.to_bytes());
 
     // create AIR instance for the computation specified in the proof
     let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
@@ -125,6 +126,23 @@ pub fn verify<AIR: Air>(
                 let channel = VerifierChannel::new(&air, proof)?;
                 perform_verification::<AIR, AIR::BaseField, Sha2_256<AIR::BaseField, DefaultSha2>>(air, channel, public_coin)
             }
+            HashFunction::Sha2_512 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_verification::<AIR, AIR::BaseField, Sha2_512<AIR::BaseField, DefaultSha512>>(air, channel, public_coin)
+            }
+            HashFunction::Sha2_384 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_verification::<AIR, AIR::BaseField, Sha2_384<AIR::BaseField, DefaultSha384>>(air, channel, public_coin)
+            }
+            HashFunction::Keccak256 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_verification::<AIR, AIR::BaseField, Keccak256<AIR::BaseField>>(air, channel, public_coin)
+            }
+            HashFunction::Rp64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+            HashFunction::Poseidon64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
         },
         FieldExtension::Quadratic => {
             if !<QuadExtension<AIR::BaseField>>::is_supported() {
@@ -151,6 +169,23 @@ pub fn verify<AIR: Air>(
                     let channel = VerifierChannel::new(&air, proof)?;
                     perform_verification::<AIR, QuadExtension<AIR::BaseField>, Sha2_256<AIR::BaseField, DefaultSha2>>(air, channel, public_coin)
                 }
+                HashFunction::Sha2_512 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_verification::<AIR, QuadExtension<AIR::BaseField>, Sha2_512<AIR::BaseField, DefaultSha512>>(air, channel, public_coin)
+                }
+                HashFunction::Sha2_384 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_verification::<AIR, QuadExtension<AIR::BaseField>, Sha2_384<AIR::BaseField, DefaultSha384>>(air, channel, public_coin)
+                }
+                HashFunction::Keccak256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_verification::<AIR, QuadExtension<AIR::BaseField>, Keccak256<AIR::BaseField>>(air, channel, public_coin)
+                }
+                HashFunction::Rp64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+                HashFunction::Poseidon64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
             }
         },
         FieldExtension::Cubic => {
@@ -178,24 +213,298 @@ pub fn verify<AIR: Air>(
                     let channel = VerifierChannel::new(&air, proof)?;
                     perform_verification::<AIR, CubeExtension<AIR::BaseField>, Sha2_256<AIR::BaseField, DefaultSha2>>(air, channel, public_coin)
                 }
+                HashFunction::Sha2_512 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_verification::<AIR, CubeExtension<AIR::BaseField>, Sha2_512<AIR::BaseField, DefaultSha512>>(air, channel, public_coin)
+                }
+                HashFunction::Sha2_384 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_verification::<AIR, CubeExtension<AIR::BaseField>, Sha2_384<AIR::BaseField, DefaultSha384>>(air, channel, public_coin)
+                }
+                HashFunction::Keccak256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_verification::<AIR, CubeExtension<AIR::BaseField>, Keccak256<AIR::BaseField>>(air, channel, public_coin)
+                }
+                HashFunction::Rp64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+                HashFunction::Poseidon64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
+            }
+        },
+    }
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// for a proof with hybrid commitments.
+///
+/// In a proof with hybrid commitments, the execution trace is committed to with the hash function
+/// specified by `HC` (e.g., an algebraic hash function, which is cheap to verify recursively),
+/// while the constraint and FRI commitments, as well as the public coin, use the hash function
+/// declared by the proof options (e.g., SHA-2, which is cheap to verify on-chain). The trace
+/// commitments are absorbed into the public coin as the digests of the declared hash function
+/// with the same serialization; thus, the digests of both hash functions must be of the same
+/// size. Verifying a proof without hybrid commitments via this function with `HC` set to the
+/// declared hash function is the same as verifying it via [verify()].
+///
+/// # Errors
+/// Returns an error if combination of the provided proof and public inputs does not attest to
+/// a correct execution of the computation, or if the proof does not declare a configuration for
+/// which trace commitments made with `HC` can be verified. This could happen, in addition to the
+/// reasons listed for [verify()], if:
+/// - The digests of `HC` and of the hash function declared by the proof are of different sizes.
+/// - The trace commitments of the proof were made with a hash function other than `HC`.
+#[rustfmt::skip]
+pub fn verify_hybrid<AIR, HC>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HC: ElementHasher<BaseField = AIR::BaseField>,
+{
+    // build a seed for the public coin; the initial seed is the hash of public inputs and proof
+    // context, but as the protocol progresses, the coin will be reseeded with the info received
+    // from the prover
+    let public_coin_seed = proof.context.public_coin_seed(&pub_inputs.to_bytes());
+
+    // create AIR instance for the computation specified in the proof
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+
+    // figure out which version of the generic proof verification procedure to run; the hash
+    // function declared by the proof options is used for all commitments except the trace
+    // commitments, which are made with HC.
+    match air.options().field_extension() {
+        FieldExtension::None => match air.options().hash_fn() {
+            HashFunction::Blake3_256 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_hybrid_verification::<AIR, AIR::BaseField, Blake3_256<AIR::BaseField>, HC>(air, channel, public_coin)
+            }
+            HashFunction::Blake3_192 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_hybrid_verification::<AIR, AIR::BaseField, Blake3_192<AIR::BaseField>, HC>(air, channel, public_coin)
+            }
+            HashFunction::Sha3_256 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_hybrid_verification::<AIR, AIR::BaseField, Sha3_256<AIR::BaseField>, HC>(air, channel, public_coin)
+            },
+            HashFunction::Sha2_256 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_hybrid_verification::<AIR, AIR::BaseField, Sha2_256<AIR::BaseField, DefaultSha2>, HC>(air, channel, public_coin)
+            }
+            HashFunction::Sha2_512 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_hybrid_verification::<AIR, AIR::BaseField, Sha2_512<AIR::BaseField, DefaultSha512>, HC>(air, channel, public_coin)
+            }
+            HashFunction::Sha2_384 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_hybrid_verification::<AIR, AIR::BaseField, Sha2_384<AIR::BaseField, DefaultSha384>, HC>(air, channel, public_coin)
+            }
+            HashFunction::Keccak256 => {
+                let public_coin = RandomCoin::new(&public_coin_seed);
+                let channel = VerifierChannel::new(&air, proof)?;
+                perform_hybrid_verification::<AIR, AIR::BaseField, Keccak256<AIR::BaseField>, HC>(air, channel, public_coin)
+            }
+            HashFunction::Rp64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+            HashFunction::Poseidon64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
+        },
+        FieldExtension::Quadratic => {
+            if !<QuadExtension<AIR::BaseField>>::is_supported() {
+                return Err(VerifierError::UnsupportedFieldExtension(2));
+            }
+            match air.options().hash_fn() {
+                HashFunction::Blake3_256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, QuadExtension<AIR::BaseField>, Blake3_256<AIR::BaseField>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Blake3_192 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, QuadExtension<AIR::BaseField>, Blake3_192<AIR::BaseField>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Sha3_256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, QuadExtension<AIR::BaseField>, Sha3_256<AIR::BaseField>, HC>(air, channel, public_coin)
+                },
+                HashFunction::Sha2_256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, QuadExtension<AIR::BaseField>, Sha2_256<AIR::BaseField, DefaultSha2>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Sha2_512 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, QuadExtension<AIR::BaseField>, Sha2_512<AIR::BaseField, DefaultSha512>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Sha2_384 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, QuadExtension<AIR::BaseField>, Sha2_384<AIR::BaseField, DefaultSha384>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Keccak256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, QuadExtension<AIR::BaseField>, Keccak256<AIR::BaseField>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Rp64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+                HashFunction::Poseidon64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
+            }
+        },
+        FieldExtension::Cubic => {
+            if !<CubeExtension<AIR::BaseField>>::is_supported() {
+                return Err(VerifierError::UnsupportedFieldExtension(3));
+            }
+            match air.options().hash_fn() {
+                HashFunction::Blake3_256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, CubeExtension<AIR::BaseField>, Blake3_256<AIR::BaseField>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Blake3_192 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, CubeExtension<AIR::BaseField>, Blake3_192<AIR::BaseField>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Sha3_256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, CubeExtension<AIR::BaseField>, Sha3_256<AIR::BaseField>, HC>(air, channel, public_coin)
+                },
+                HashFunction::Sha2_256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, CubeExtension<AIR::BaseField>, Sha2_256<AIR::BaseField, DefaultSha2>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Sha2_512 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, CubeExtension<AIR::BaseField>, Sha2_512<AIR::BaseField, DefaultSha512>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Sha2_384 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, CubeExtension<AIR::BaseField>, Sha2_384<AIR::BaseField, DefaultSha384>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Keccak256 => {
+                    let public_coin = RandomCoin::new(&public_coin_seed);
+                    let channel = VerifierChannel::new(&air, proof)?;
+                    perform_hybrid_verification::<AIR, CubeExtension<AIR::BaseField>, Keccak256<AIR::BaseField>, HC>(air, channel, public_coin)
+                }
+                HashFunction::Rp64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Rp64_256)),
+                HashFunction::Poseidon64_256 => Err(VerifierError::UnsupportedHashFunction(HashFunction::Poseidon64_256)),
             }
         },
     }
 }
 
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// for a proof with all commitments made with the hash function `H`.
+///
+/// This is the counterpart of [verify()] for hash functions which the generic dispatch of that
+/// function cannot instantiate for an arbitrary base field, i.e., [HashFunction::Rp64_256], or
+/// which it does not implement, i.e., [HashFunction::Poseidon64_256]. The hash function declared
+/// by the proof options is not checked against `H`; it is up to the caller to select `H` from the
+/// declared hash function.
+///
+/// # Errors
+/// Returns an error for the same reasons as [verify()], which also include commitments which
+/// were not made with `H`.
+pub fn verify_with_hasher<AIR, H>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    H: ElementHasher<BaseField = AIR::BaseField>,
+{
+    verify_with_seed_extension::<AIR, H>(proof, pub_inputs, &[])
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// for a proof with all commitments made with the hash function `H`, whose public coin was seeded
+/// with the serialized public inputs followed by the specified `seed_extension`.
+///
+/// This is the counterpart of [verify_with_hasher()] for proofs generated by provers which bind
+/// data outside of the public inputs of the AIR to the proof (see
+/// `Prover::get_seed_extension()`); with an empty extension, the two functions are the same.
+///
+/// # Errors
+/// Returns an error for the same reasons as [verify_with_hasher()], which also include a seed
+/// extension different from the one the proof was generated with.
+pub fn verify_with_seed_extension<AIR, H>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    seed_extension: &[u8],
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    H: ElementHasher<BaseField = AIR::BaseField>,
+{
+    let mut pub_inputs_bytes = pub_inputs.to_bytes();
+    pub_inputs_bytes.extend_from_slice(seed_extension);
+    let public_coin_seed = proof.context.public_coin_seed(&pub_inputs_bytes);
+    let public_coin = RandomCoin::new(&public_coin_seed);
+
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+    match air.options().field_extension() {
+        FieldExtension::None => {
+            let channel = VerifierChannel::new(&air, proof)?;
+            perform_verification::<AIR, AIR::BaseField, H>(air, channel, public_coin)
+        }
+        FieldExtension::Quadratic => {
+            if !<QuadExtension<AIR::BaseField>>::is_supported() {
+                return Err(VerifierError::UnsupportedFieldExtension(2));
+            }
+            let channel = VerifierChannel::new(&air, proof)?;
+            perform_verification::<AIR, QuadExtension<AIR::BaseField>, H>(air, channel, public_coin)
+        }
+        FieldExtension::Cubic => {
+            if !<CubeExtension<AIR::BaseField>>::is_supported() {
+                return Err(VerifierError::UnsupportedFieldExtension(3));
+            }
+            let channel = VerifierChannel::new(&air, proof)?;
+            perform_verification::<AIR, CubeExtension<AIR::BaseField>, H>(air, channel, public_coin)
+        }
+    }
+}
+
 // VERIFICATION PROCEDURE
 // ================================================================================================
 /// Performs the actual verification by reading the data from the `channel` and making sure it
 /// attests to a correct execution of the computation specified by the provided `air`.
 fn perform_verification<A, E, H>(
     air: A,
-    mut channel: VerifierChannel<E, H>,
+    channel: VerifierChannel<E, H>,
+    public_coin: RandomCoin<A::BaseField, H>,
+) -> Result<(), VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+{
+    perform_hybrid_verification::<A, E, H, H>(air, channel, public_coin)
+}
+
+/// Performs the actual verification of a proof whose trace commitments were made with the hash
+/// function `HC`, and all other commitments with the hash function `H`.
+fn perform_hybrid_verification<A, E, H, HC>(
+    air: A,
+    mut channel: VerifierChannel<E, H, HC>,
     mut public_coin: RandomCoin<A::BaseField, H>,
 ) -> Result<(), VerifierError>
 where
     A: Air,
     E: FieldElement<BaseField = A::BaseField>,
     H: ElementHasher<BaseField = A::BaseField>,
+    HC: ElementHasher<BaseField = A::BaseField>,
 {
     // 1 ----- trace commitment -------------------------------------------------------------------
     // Read the commitments to evaluations of the trace polynomials over the LDE domain sent by the
@@ -206,7 +515,25 @@ where
     // used to draw random elements needed to construct the next trace segment. The last trace
     // commitment is used to draw a set of random coefficients which the prover uses to compute
     // constraint composition polynomial.
-    let trace_commitments = channel.read_trace_commitments();
+    //
+    // trace commitments are absorbed into the public coin as digests of H with the same
+    // serialization; for proofs without hybrid commitments, these are the commitments themselves
+    let trace_digest_size = HC::Digest::default().to_bytes().len();
+    let digest_size = H::Digest::default().to_bytes().len();
+    if trace_digest_size != digest_size {
+        return Err(VerifierError::InconsistentTraceCommitmentDigests(
+            trace_digest_size,
+            digest_size,
+        ));
+    }
+    let trace_commitments = channel
+        .read_trace_commitments()
+        .iter()
+        .map(|commitment| {
+            H::Digest::read_from(&mut SliceReader::new(&commitment.to_bytes()))
+                .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     // reseed the coin with the commitment to the main trace segment
     public_coin.reseed(trace_commitments[0]);
@@ -339,8 +666,9 @@ where
     // read evaluations of trace and constraint composition polynomials at the queried positions;
     // this also checks that the read values are valid against trace and constraint commitments
     let (queried_main_trace_states, queried_aux_trace_states) =
-        channel.read_queried_trace_states(&query_positions)?;
-    let queried_constraint_evaluations = channel.read_constraint_evaluations(&query_positions)?;
+        channel.read_queried_trace_states(&air, &query_positions)?;
+    let queried_constraint_evaluations =
+        channel.read_constraint_evaluations(&air, &query_positions)?;
 
     // 6 ----- DEEP composition -------------------------------------------------------------------
     // compute evaluations of the DEEP composition polynomial at the queried positions
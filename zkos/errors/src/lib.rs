@@ -0,0 +1,255 @@
+//! Typed error chains shared by the hosts, the guests, and the utilities of the workspace.
+//!
+//! Failures are raised in several places which do not share an error type: the guests report
+//! `anyhow` errors, the risc0 host library reports exceptions thrown by its C++ prover as plain
+//! strings, and the miden processor and the winterfell verifier have their own error enums. An
+//! [ErrorChain] carries a failure across these boundaries as a list of [ErrorFrame]s, each of
+//! which is tagged with an [ErrorKind], so that a host can tell what failed from the journal or
+//! the error it receives without parsing error messages.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use rkyv::{Archive, Deserialize, Serialize};
+use serde::{Deserialize as sDeserialize, Serialize as sSerialize};
+
+// ERROR KIND
+// ================================================================================================
+
+/// Component of the workspace which raised a failure.
+#[derive(
+    Archive, Deserialize, Serialize, sSerialize, sDeserialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+pub enum ErrorKind {
+    /// Encoding or decoding of the aux input.
+    AuxInput,
+    /// Deserialization of a proof or of the inputs of a guest.
+    Deserialization,
+    /// Verification of a STARK proof.
+    Verification,
+    /// Execution of a program by the miden processor.
+    Execution,
+    /// Check of the heap a guest estimates for its inputs against its cap.
+    HeapEstimate,
+    /// Mutation of a packaged proof.
+    Mutation,
+    /// Proving by the risc0 prover, including exceptions thrown by its C++ implementation.
+    Prover,
+    /// Verification of a risc0 receipt.
+    Receipt,
+    /// Decoding or checking of a journal.
+    Journal,
+    /// Enforcement of a proof options policy.
+    Policy,
+    /// Any other failure.
+    Other,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AuxInput => write!(f, "aux input"),
+            Self::Deserialization => write!(f, "deserialization"),
+            Self::Verification => write!(f, "verification"),
+            Self::Execution => write!(f, "execution"),
+            Self::HeapEstimate => write!(f, "heap estimate"),
+            Self::Mutation => write!(f, "mutation"),
+            Self::Prover => write!(f, "prover"),
+            Self::Receipt => write!(f, "receipt"),
+            Self::Journal => write!(f, "journal"),
+            Self::Policy => write!(f, "policy"),
+            Self::Other => write!(f, "other"),
+        }
+    }
+}
+
+// ERROR CHAIN
+// ================================================================================================
+
+/// A single level of an [ErrorChain].
+#[derive(
+    Archive, Deserialize, Serialize, sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq,
+)]
+pub struct ErrorFrame {
+    /// Component which raised or propagated the failure at this level.
+    pub kind: ErrorKind,
+    /// Human-readable description of the failure at this level.
+    pub message: String,
+}
+
+/// A failure together with the context it was propagated through, ordered from the outermost
+/// context to the root cause.
+#[derive(
+    Archive, Deserialize, Serialize, sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq,
+)]
+pub struct ErrorChain {
+    frames: Vec<ErrorFrame>,
+}
+
+impl ErrorChain {
+    /// Returns a new chain consisting of the specified root cause only.
+    pub fn new(kind: ErrorKind, message: impl ToString) -> Self {
+        Self {
+            frames: vec![ErrorFrame {
+                kind,
+                message: message.to_string(),
+            }],
+        }
+    }
+
+    /// Returns a chain built from the provided messages, ordered from the outermost context to
+    /// the root cause, all of which are tagged with the specified kind.
+    ///
+    /// This is the conversion shim for `anyhow` errors: a guest passes
+    /// `error.chain().map(|e| e.to_string())` to preserve every level of context of the error.
+    /// If no messages are provided, the chain consists of a single frame without a message.
+    pub fn from_messages<I, S>(kind: ErrorKind, messages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        let frames = messages
+            .into_iter()
+            .map(|message| ErrorFrame {
+                kind,
+                message: message.to_string(),
+            })
+            .collect::<Vec<_>>();
+        match frames.is_empty() {
+            true => Self::new(kind, ""),
+            false => Self { frames },
+        }
+    }
+
+    /// Returns a chain for an exception which crossed the FFI boundary of the risc0 host library.
+    ///
+    /// Exceptions thrown by the C++ prover reach Rust as the message returned by their `what()`,
+    /// which may be padded with NUL characters or surrounded by whitespace; both are stripped.
+    pub fn from_ffi(kind: ErrorKind, what: &str) -> Self {
+        let message = what.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+        match message.is_empty() {
+            true => Self::new(kind, "unknown exception"),
+            false => Self::new(kind, message),
+        }
+    }
+
+    /// Wraps this chain into the specified context.
+    pub fn context(mut self, kind: ErrorKind, message: impl ToString) -> Self {
+        self.frames.insert(
+            0,
+            ErrorFrame {
+                kind,
+                message: message.to_string(),
+            },
+        );
+        self
+    }
+
+    /// Returns the kind of the outermost frame of this chain.
+    pub fn kind(&self) -> ErrorKind {
+        self.frames[0].kind
+    }
+
+    /// Returns the innermost frame of this chain, i.e., the root cause of the failure.
+    pub fn root_cause(&self) -> &ErrorFrame {
+        self.frames.last().expect("error chain is empty")
+    }
+
+    /// Returns true if any frame of this chain has the specified kind.
+    pub fn contains(&self, kind: ErrorKind) -> bool {
+        self.frames.iter().any(|frame| frame.kind == kind)
+    }
+
+    /// Returns all frames of this chain, ordered from the outermost context to the root cause.
+    pub fn frames(&self) -> &[ErrorFrame] {
+        &self.frames
+    }
+}
+
+impl fmt::Display for ErrorChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, frame) in self.frames.iter().enumerate() {
+            if i > 0 {
+                write!(f, ": ")?;
+            }
+            write!(f, "{}", frame.message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErrorChain {}
+
+// RESULT EXTENSIONS
+// ================================================================================================
+
+/// Conversion of results with foreign error types, e.g., processor or verifier errors, into
+/// results with an [ErrorChain].
+pub trait ResultExt<T> {
+    /// Converts the error of this result into the root cause of a new chain with the specified
+    /// kind.
+    fn with_kind(self, kind: ErrorKind) -> Result<T, ErrorChain>;
+}
+
+impl<T, E: fmt::Display> ResultExt<T> for Result<T, E> {
+    fn with_kind(self, kind: ErrorKind) -> Result<T, ErrorChain> {
+        self.map_err(|e| ErrorChain::new(kind, e))
+    }
+}
+
+/// Addition of context to results with an [ErrorChain].
+pub trait ChainContext<T> {
+    /// Wraps the error of this result into the specified context.
+    fn chain_context(self, kind: ErrorKind, message: impl ToString) -> Result<T, ErrorChain>;
+}
+
+impl<T> ChainContext<T> for Result<T, ErrorChain> {
+    fn chain_context(self, kind: ErrorKind, message: impl ToString) -> Result<T, ErrorChain> {
+        self.map_err(|e| e.context(kind, message))
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{ChainContext, ErrorChain, ErrorKind, ResultExt};
+
+    #[test]
+    fn chain_context() {
+        let result: Result<(), &str> = Err("OOD constraint evaluations do not match");
+        let chain = result
+            .with_kind(ErrorKind::Verification)
+            .chain_context(ErrorKind::Journal, "failed to verify first fib proof")
+            .unwrap_err();
+
+        assert_eq!(ErrorKind::Journal, chain.kind());
+        assert_eq!(ErrorKind::Verification, chain.root_cause().kind);
+        assert!(chain.contains(ErrorKind::Verification));
+        assert!(!chain.contains(ErrorKind::Prover));
+        assert_eq!(
+            "failed to verify first fib proof: OOD constraint evaluations do not match",
+            chain.to_string()
+        );
+    }
+
+    #[test]
+    fn chain_conversions() {
+        let chain = ErrorChain::from_messages(ErrorKind::Verification, ["outer", "root"]);
+        assert_eq!(2, chain.frames().len());
+        assert_eq!("root", chain.root_cause().message);
+
+        let chain = ErrorChain::from_messages(ErrorKind::Verification, Vec::<String>::new());
+        assert_eq!(1, chain.frames().len());
+
+        let chain = ErrorChain::from_ffi(ErrorKind::Prover, "  segment too large\0\0");
+        assert_eq!("segment too large", chain.root_cause().message);
+        let chain = ErrorChain::from_ffi(ErrorKind::Prover, "\0");
+        assert_eq!("unknown exception", chain.root_cause().message);
+    }
+}
@@ -0,0 +1,147 @@
+//! Differential fuzzing of the verification flow of the guests against the native verifier.
+//!
+//! Every case proves a small Fibonacci computation with random proof options, optionally
+//! tampers with the serialized proof or with the claimed result, and verifies it twice: with
+//! `winter_verifier::verify`, and with [verify_in_guest] compiled for the host. The two must
+//! accept and reject the same proofs; a divergence is caught here, before it reaches the zkVM.
+//!
+//! Run with `cargo fuzz run differential_verify` from `zkos/fuzz`.
+#![no_main]
+extern crate alloc;
+
+#[path = "../../methods/guest/src/verify.rs"]
+mod verify;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use utils::fib::example::{Example, FibExample};
+use utils::fib::fib_air::FibAir;
+use utils::inputs::trace_segment_infos;
+use verify::verify_in_guest;
+use winter_air::proof::StarkProof;
+use winter_air::{Air, FieldExtension, HashFunction, ProofOptions};
+use winter_crypto::hashers::{DefaultSha2, Sha2_256};
+use winter_math::fields::f64_risc0::{BaseElement, DefaultNativeMul};
+use winter_math::fields::{CubeExtension, QuadExtension};
+use winter_math::FieldElement;
+use winter_utils::Serializable;
+use winter_verifier::VerifierChannel;
+
+type Fib = FibAir<DefaultNativeMul>;
+type B = BaseElement;
+type H = Sha2_256<B, DefaultSha2>;
+
+// FUZZ CASE
+// ================================================================================================
+
+/// Parameters of a proof, and the ways in which it is tampered with before it is verified.
+#[derive(Arbitrary, Debug)]
+struct FuzzCase {
+    /// Selects the length of the Fibonacci sequence, between 16 and 512.
+    log_sequence_length: u8,
+    /// Selects the number of queries, between 1 and 16.
+    num_queries: u8,
+    /// Selects the blowup factor, between 4 and 16.
+    log_blowup_factor: u8,
+    /// Selects the grinding factor, between 0 and 3.
+    grinding_factor: u8,
+    /// Selects the field of the out-of-domain evaluations.
+    field_extension: u8,
+    /// Selects the FRI folding factor, between 4 and 16.
+    log_folding_factor: u8,
+    /// Byte of the serialized proof to tamper with, and the mask it is XORed with.
+    mutation: Option<(u16, u8)>,
+    /// Indicates whether the proof is verified against the wrong result.
+    wrong_result: bool,
+}
+
+impl FuzzCase {
+    fn sequence_length(&self) -> usize {
+        1 << (4 + self.log_sequence_length % 6)
+    }
+
+    fn options(&self) -> ProofOptions {
+        let field_extension = match self.field_extension % 3 {
+            0 => FieldExtension::None,
+            1 => FieldExtension::Quadratic,
+            _ => FieldExtension::Cubic,
+        };
+        ProofOptions::new(
+            1 + (self.num_queries % 16) as usize,
+            1 << (2 + self.log_blowup_factor % 3),
+            (self.grinding_factor % 4) as u32,
+            HashFunction::Sha2_256,
+            field_extension,
+            1 << (2 + self.log_folding_factor % 3),
+            256,
+        )
+    }
+}
+
+// FUZZ TARGET
+// ================================================================================================
+
+fuzz_target!(|case: FuzzCase| {
+    let example = FibExample::new(case.sequence_length(), case.options());
+    let mut proof = example.prove();
+
+    if let Some((index, mask)) = case.mutation {
+        let mut bytes = proof.to_bytes();
+        let index = index as usize % bytes.len();
+        bytes[index] ^= mask.max(1);
+        // the AIR is instantiated from the context of the proof, and panics on a context it was
+        // not built for; the context is bound by the statement digest of the guests instead
+        proof = match StarkProof::from_bytes(&bytes) {
+            Ok(mutated) if mutated.context == proof.context => mutated,
+            _ => return,
+        };
+    }
+    let result = if case.wrong_result {
+        example.result + B::ONE
+    } else {
+        example.result
+    };
+
+    let native = winter_verifier::verify::<Fib>(proof.clone(), result).is_ok();
+    let guest = match proof.options().field_extension() {
+        FieldExtension::None => verify_on_host::<B>(proof, result),
+        FieldExtension::Quadratic => verify_on_host::<QuadExtension<B>>(proof, result),
+        FieldExtension::Cubic => verify_on_host::<CubeExtension<B>>(proof, result),
+    };
+    assert_eq!(
+        native, guest,
+        "native verifier and guest verification flow disagree on {:?}",
+        case
+    );
+
+    // untampered proofs of the true result must be accepted by both
+    if case.mutation.is_none() && !case.wrong_result {
+        assert!(native, "valid proof rejected for {:?}", case);
+    }
+});
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Verifies the provided proof against the provided result in the same way as the fib verifier
+/// guest, with out-of-domain evaluations in the field `E`, and returns true if it is accepted.
+fn verify_on_host<E: FieldElement<BaseField = B>>(proof: StarkProof, result: B) -> bool {
+    // the coin is seeded with the public inputs followed by the proof context, as by the prover
+    let coin_seed = proof.context.public_coin_seed(&result.to_bytes());
+
+    let air = Fib::new(proof.get_trace_info(), result, proof.options().clone());
+    let trace_segments = trace_segment_infos(proof.context.trace_layout());
+    let verifier_channel = match VerifierChannel::<E, H>::new(&air, proof) {
+        Ok(channel) => channel,
+        Err(_) => return false,
+    };
+    verify_in_guest(
+        &air,
+        verifier_channel,
+        &trace_segments,
+        None,
+        &coin_seed,
+        &(),
+    )
+    .is_ok()
+}
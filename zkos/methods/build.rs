@@ -1,12 +1,63 @@
 use std::collections::HashMap;
 
+/// Environment variables which override settings of the release profile of the guest build,
+/// mapped to the Cargo variables of the corresponding settings. The variables are set by the
+/// ci-matrix mode of zkprunner, which builds the guests with several codegen settings.
+const GUEST_PROFILE_VARS: [(&str, &str); 3] = [
+    ("ZKOS_GUEST_OPT_LEVEL", "CARGO_PROFILE_RELEASE_OPT_LEVEL"),
+    (
+        "ZKOS_GUEST_CODEGEN_UNITS",
+        "CARGO_PROFILE_RELEASE_CODEGEN_UNITS",
+    ),
+    ("ZKOS_GUEST_LTO", "CARGO_PROFILE_RELEASE_LTO"),
+];
+
 fn main() {
+    // set ZKOS_GUEST_PROFILE to build guests with cycle attribution enabled
+    println!("cargo:rerun-if-env-changed=ZKOS_GUEST_PROFILE");
+    // set ZKOS_GUEST_LOG to build guests which log their progress and errors
+    println!("cargo:rerun-if-env-changed=ZKOS_GUEST_LOG");
+    // set ZKOS_GUEST_HEAP_ESTIMATE_CAP to change the cap on the heap the verifier guests estimate
+    // for their inputs
+    println!("cargo:rerun-if-env-changed=ZKOS_GUEST_HEAP_ESTIMATE_CAP");
+    // set ZKOS_GUEST_MIN_TRACE_LENGTH and ZKOS_GUEST_MAX_TRACE_LENGTH to change the trace lengths
+    // of the proofs the verifier guests accept
+    println!("cargo:rerun-if-env-changed=ZKOS_GUEST_MIN_TRACE_LENGTH");
+    println!("cargo:rerun-if-env-changed=ZKOS_GUEST_MAX_TRACE_LENGTH");
+    // set ZKOS_GUEST_MUL_BACKEND to "schoolbook" to build guests which multiply field elements
+    // with the reference backend of winter-math instead of the optimized one
+    println!("cargo:rerun-if-env-changed=ZKOS_GUEST_MUL_BACKEND");
+    let mut features = vec![];
+    if std::env::var("ZKOS_GUEST_PROFILE").is_ok() {
+        features.push("profile".to_string());
+    }
+    if std::env::var("ZKOS_GUEST_LOG").is_ok() {
+        features.push("log".to_string());
+    }
+    match std::env::var("ZKOS_GUEST_MUL_BACKEND").as_deref() {
+        Ok("schoolbook") => features.push("schoolbook-mul".to_string()),
+        Ok("default") | Err(_) => (),
+        Ok(backend) => panic!(
+            "invalid ZKOS_GUEST_MUL_BACKEND '{}'; expected 'default' or 'schoolbook'",
+            backend
+        ),
+    }
+
+    // the guests are built by a nested Cargo invocation, which inherits the environment of this
+    // script; thus, the overrides apply to the guest build only
+    for (var, profile_var) in GUEST_PROFILE_VARS {
+        println!("cargo:rerun-if-env-changed={}", var);
+        if let Ok(value) = std::env::var(var) {
+            std::env::set_var(profile_var, value);
+        }
+    }
+
     let options_map = HashMap::from([(
         "methods-guest",
         risc0_build::GuestOptions {
             // change to 16 to run miden fully (will increase compile time)
             code_limit: 12,
-            features: vec![],
+            features,
         },
     )]);
     risc0_build::embed_methods_with_options(options_map);
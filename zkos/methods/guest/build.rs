@@ -1,3 +1,65 @@
+use std::{env, fs, path::Path};
+use utils::fib::vk::FibVerificationKey;
+use utils::heap::DEFAULT_HEAP_ESTIMATE_CAP;
+use utils::trace_bounds::{DEFAULT_MAX_TRACE_LENGTH, DEFAULT_MIN_TRACE_LENGTH};
+use winter_math::fields::f64_risc0::DefaultNativeMul;
+
 fn main() {
     risc0_build::link();
+
+    // precompute the verification key of the Fibonacci AIR on the host, so that the verifier
+    // guest does not need to recompute the AIR setup for every proof
+    println!("cargo:rerun-if-changed=../../utils/src/fib");
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    let vk = FibVerificationKey::build::<DefaultNativeMul>();
+    fs::write(
+        Path::new(&out_dir).join("fib_vk.rs"),
+        vk.to_const_declaration("FIB_VK"),
+    )
+    .expect("failed to write the Fibonacci verification key");
+
+    // the cap on heap estimates is fixed at build time, so that it is bound to the image ID of the
+    // guest
+    println!("cargo:rerun-if-env-changed=ZKOS_GUEST_HEAP_ESTIMATE_CAP");
+    let heap_estimate_cap = match env::var("ZKOS_GUEST_HEAP_ESTIMATE_CAP") {
+        Ok(cap) => cap
+            .parse::<usize>()
+            .expect("ZKOS_GUEST_HEAP_ESTIMATE_CAP must be a number of bytes"),
+        Err(_) => DEFAULT_HEAP_ESTIMATE_CAP,
+    };
+    fs::write(
+        Path::new(&out_dir).join("heap_estimate_cap.rs"),
+        format!("const HEAP_ESTIMATE_CAP: usize = {};\n", heap_estimate_cap),
+    )
+    .expect("failed to write the heap estimate cap");
+
+    // the trace length bounds are fixed at build time for the same reason
+    let min_trace_length =
+        trace_length_var("ZKOS_GUEST_MIN_TRACE_LENGTH", DEFAULT_MIN_TRACE_LENGTH);
+    let max_trace_length =
+        trace_length_var("ZKOS_GUEST_MAX_TRACE_LENGTH", DEFAULT_MAX_TRACE_LENGTH);
+    assert!(
+        min_trace_length <= max_trace_length,
+        "ZKOS_GUEST_MIN_TRACE_LENGTH must not exceed ZKOS_GUEST_MAX_TRACE_LENGTH"
+    );
+    fs::write(
+        Path::new(&out_dir).join("trace_bounds.rs"),
+        format!(
+            "const TRACE_LENGTH_BOUNDS: TraceLengthBounds = TraceLengthBounds::new({}, {});\n",
+            min_trace_length, max_trace_length
+        ),
+    )
+    .expect("failed to write the trace length bounds");
+}
+
+/// Returns the trace length set by the specified environment variable, or the provided default
+/// if the variable is not set.
+fn trace_length_var(var: &str, default: usize) -> usize {
+    println!("cargo:rerun-if-env-changed={}", var);
+    match env::var(var) {
+        Ok(length) => length
+            .parse::<usize>()
+            .unwrap_or_else(|_| panic!("{} must be a number of steps", var)),
+        Err(_) => default,
+    }
 }
@@ -0,0 +1,173 @@
+#![no_main]
+#![no_std]
+extern crate alloc;
+
+use alloc::format;
+use alloc::vec::Vec;
+use anyhow::{anyhow, Result};
+use errors::{ErrorChain, ErrorKind};
+use methods_guest::verify::verify_in_guest;
+use methods_guest::{
+    check_proof_bounds, decode_aux_input, failed_journal, guest_log, run_verifier, GuestSha2,
+};
+use risc0_zkvm_guest::env;
+use rkyv::{Archive, Archived, Deserialize, Infallible};
+use utils::context::VerifierContext;
+use utils::fib::fib128::Fib128Air;
+use utils::fib::fib_air::TRACE_WIDTH;
+use utils::inputs::{ArchivedFib128RiscInput, Fib128RiscInput, FibAirInput, TraceSegmentInfo};
+use utils::journal::{options_digest, statement_digest, trace_info_bytes, Journal};
+use utils::policy::ProofPolicy;
+use utils::trace_bounds::TraceLengthBounds;
+use winter_air::{Air, FieldExtension};
+use winter_crypto::hashers::Sha2_256;
+use winter_math::fields::f128::BaseElement;
+use winter_math::fields::QuadExtension;
+use winter_math::{FieldElement, StarkField};
+use winter_utils::Serializable;
+use winter_verifier::VerifierChannel;
+
+risc0_zkvm_guest::entry!(main);
+
+type B = BaseElement;
+type Quad = QuadExtension<B>;
+type H = Sha2_256<B, GuestSha2>;
+
+// defines TRACE_LENGTH_BOUNDS, the trace lengths of the accepted proofs, set by the build script
+include!(concat!(env!("OUT_DIR"), "/trace_bounds.rs"));
+
+/// Verifies the Fibonacci proof over the 128-bit field and returns the journal describing the
+/// outcome; verification failures are logged, and reported via the result and the error chain of
+/// the journal.
+///
+/// The inputs are laid out as the ones of the fib verifier guest, except that a single proof is
+/// packaged, and the journal is bound to the statement and the policy in the same way.
+pub fn run_main_logic() -> Journal {
+    run_verifier(verify_inputs)
+}
+
+/// Verifies the Fibonacci proof under the provided policy and returns the journal describing the
+/// outcome.
+fn verify_inputs(policy: &ProofPolicy) -> Journal {
+    let aux_input = match decode_aux_input(env::read_aux_input()) {
+        Ok(aux_input) => aux_input,
+        Err(error) => return failed_journal([0; 32], [0; 32], error),
+    };
+    let air_input: FibAirInput = env::read();
+
+    // the layout of the packaged proof depends on the field of its out-of-domain evaluations; the
+    // 128-bit field has no cubic extension
+    match air_input.proof_options.field_extension() {
+        FieldExtension::None => verify_single::<B>(aux_input.as_bytes(), air_input, policy),
+        FieldExtension::Quadratic => verify_single::<Quad>(aux_input.as_bytes(), air_input, policy),
+        extension => {
+            let error = ErrorChain::new(
+                ErrorKind::Verification,
+                format!(
+                    "fib proofs with field extension {:?} are not supported",
+                    extension
+                ),
+            );
+            failed_journal([0; 32], [0; 32], error)
+        }
+    }
+}
+
+/// Verifies the Fibonacci proof packaged in the provided aux input, with out-of-domain
+/// evaluations in the field `E`, under the provided policy, and returns the journal describing
+/// the outcome.
+fn verify_single<E: FieldElement<BaseField = B>>(
+    aux_input: &[u8],
+    air_input: FibAirInput,
+    policy: &ProofPolicy,
+) -> Journal
+where
+    VerifierChannel<E, H>: Archive,
+    Archived<VerifierChannel<E, H>>: Deserialize<VerifierChannel<E, H>, Infallible>,
+{
+    let pub_inputs = unsafe { rkyv::archived_root::<Fib128RiscInput<E, H>>(aux_input) };
+    let result: B = pub_inputs.result.deserialize(&mut Infallible).unwrap();
+
+    // bind the journal to the verified statement and the options it is verified under
+    let statement_digest = statement_digest::<GuestSha2>(&[
+        pub_inputs.context.as_slice(),
+        result.to_bytes().as_slice(),
+        trace_info_bytes(&air_input.trace_info).as_slice(),
+    ]);
+    let options_digest = options_digest::<GuestSha2>(&[air_input.proof_options.clone()]);
+
+    // proofs generated with options weaker than the policy allows, or of traces with lengths
+    // outside of the bounds of the guest, are rejected before they are verified
+    if let Err(error) = check_proof_bounds(
+        policy,
+        &TRACE_LENGTH_BOUNDS,
+        &air_input.proof_options,
+        air_input.trace_info.length(),
+        "fib proof",
+    ) {
+        return failed_journal(statement_digest, options_digest, error);
+    }
+
+    match verify_fib128_proof(pub_inputs, air_input, result) {
+        Ok(()) => Journal::new(statement_digest, options_digest, true),
+        Err(e) => {
+            guest_log!("error: {:?}", e);
+            let error = ErrorChain::from_messages(ErrorKind::Verification, e.chain());
+            Journal::with_error(statement_digest, options_digest, error)
+        }
+    }
+}
+
+/// Verifies the packaged Fibonacci proof with the provided result against the AIR instantiated
+/// from the provided AIR inputs; the protocol itself is run by [verify_in_guest].
+pub fn verify_fib128_proof<E: FieldElement<BaseField = B>>(
+    pub_inputs: &ArchivedFib128RiscInput<E, H>,
+    air_input: FibAirInput,
+    result: B,
+) -> Result<()>
+where
+    VerifierChannel<E, H>: Archive,
+    Archived<VerifierChannel<E, H>>: Deserialize<VerifierChannel<E, H>, Infallible>,
+{
+    let verifier_channel: VerifierChannel<E, H> = pub_inputs
+        .verifier_channel
+        .deserialize(&mut Infallible)
+        .unwrap();
+
+    // elements are archived in their internal representation, which is not checked by rkyv (the
+    // elements of the channel are checked by the verification flow)
+    if !result.is_reduced() {
+        return Err(anyhow!("fib result is not in canonical form"));
+    }
+
+    // the AIR is instantiated from scratch, since there is no verification key of the 128-bit
+    // field; the trace width is checked here, as the constructor of the AIR panics on a mismatch
+    let trace_width = air_input.trace_info.width();
+    if trace_width != TRACE_WIDTH || air_input.trace_info.is_multi_segment() {
+        return Err(anyhow!("fib proof has an unexpected trace layout"));
+    }
+    let air = Fib128Air::new(air_input.trace_info, result, air_input.proof_options);
+
+    // Extract the proof context; the domain tag of the verifier context is bound to the journal
+    // by the statement digest, but the prover seeded the coin with the proof context only
+    let context =
+        VerifierContext::from_bytes(pub_inputs.context.as_slice()).map_err(|e| anyhow!("{}", e))?;
+    let trace_segments: Vec<TraceSegmentInfo> = pub_inputs
+        .trace_segments
+        .deserialize(&mut Infallible)
+        .unwrap();
+    let coin_seed = context.coin_seed(&result.to_bytes());
+    verify_in_guest(
+        &air,
+        verifier_channel,
+        &trace_segments,
+        None,
+        &coin_seed,
+        &(),
+    )
+}
+
+pub fn main() {
+    let journal = run_main_logic();
+    env::commit(&journal.with_cycle_count(env::get_cycle_count() as u64));
+}
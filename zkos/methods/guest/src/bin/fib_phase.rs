@@ -0,0 +1,297 @@
+#![no_main]
+#![no_std]
+extern crate alloc;
+
+use alloc::format;
+use alloc::vec::Vec;
+use anyhow::{anyhow, Context, Result};
+use errors::{ErrorChain, ErrorKind};
+use methods_guest::{guest_log, GuestSha2};
+use risc0_zkvm_guest::{env, mul};
+use rkyv::{Deserialize, Infallible};
+use utils::aux_data::seed_pub_inputs;
+use utils::aux_input::decode_aux_input;
+use utils::context::VerifierContext;
+use utils::fib::fib_air::FibAir as FA;
+use utils::inputs::{
+    check_trace_segments, ArchivedFibRiscInput, FibAirInput, FibRiscInput, TraceSegmentInfo,
+};
+use utils::journal::{
+    options_digest, phase_states_digest, statement_digest, trace_info_bytes, PhaseJournal,
+};
+use utils::phase::{verify_phase, PhaseState, VerificationPhase};
+use utils::policy::{ProofPolicy, POLICY_LEN};
+use utils::trace_bounds::TraceLengthBounds;
+use winter_air::Air;
+use winter_crypto::hashers::Sha2_256;
+use winter_math::fields::f64_risc0::{
+    AccelBaseElementRisc0, NativeMontMul, INV_NONDET, INV_NONDET_QUAD,
+};
+use winter_math::fields::QuadExtension;
+use winter_math::StarkField;
+use winter_utils::Serializable;
+use winter_verifier::VerifierChannel;
+
+risc0_zkvm_guest::entry!(main);
+
+// Runs a single phase of the verification of the packaged Fibonacci proofs (see utils::phase).
+// The guest reads the same inputs as the fib_verify guest, i.e., the policy header followed by
+// the AIR inputs of the proofs, and then the phase to run and the states committed by the
+// previous phase, one per proof. Like fib_verify, it enforces the policy and the trace length
+// bounds on every proof in every phase. It commits the states reached by the phase together with
+// the digest of the states it was resumed from, and the digests of the options and the policy
+// the phase was run under, so that the host can stitch the receipts of all phases into a
+// complete verification.
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Risc0NativeMul {}
+impl NativeMontMul for Risc0NativeMul {
+    fn native_mul_ext(a: [u64; 2], b: [u64; 2]) -> [u64; 2] {
+        mul::mul_goldilocks(&a, &b).get_u64()
+    }
+}
+
+type B = AccelBaseElementRisc0<Risc0NativeMul>;
+type FibAir = FA<Risc0NativeMul>;
+type E = QuadExtension<B>;
+type H = Sha2_256<B, GuestSha2>;
+type C = VerifierChannel<E, H>;
+
+// VERIFICATION KEY
+// ================================================================================================
+
+// defines FIB_VK, the verification key of the Fibonacci AIR computed by the build script
+include!(concat!(env!("OUT_DIR"), "/fib_vk.rs"));
+
+// defines TRACE_LENGTH_BOUNDS, the trace lengths of the accepted proofs, set by the build script
+include!(concat!(env!("OUT_DIR"), "/trace_bounds.rs"));
+
+// PHASE VERIFICATION
+// ================================================================================================
+
+/// Runs the specified phase of the verification of the provided packaged proof, resumed from the
+/// provided state, and returns the state reached by the phase.
+pub fn verify_fib_phase(
+    pub_inputs: &ArchivedFibRiscInput<E, H>,
+    air_input: FibAirInput,
+    result: B,
+    phase: VerificationPhase,
+    prev_state: Option<&PhaseState>,
+) -> Result<PhaseState> {
+    // elements are archived in their internal representation, which is not checked by rkyv
+    if !result.is_reduced() {
+        return Err(anyhow!("fib result is not in canonical form"));
+    }
+    let air = FibAir::from_verification_key(
+        &FIB_VK,
+        air_input.trace_info,
+        result,
+        air_input.proof_options,
+    )
+    .map_err(|e| {
+        anyhow!(
+            "trace info is inconsistent with the verification key: {}",
+            e
+        )
+    })?;
+
+    let mut verifier_channel: C = pub_inputs
+        .verifier_channel
+        .deserialize(&mut Infallible)
+        .unwrap();
+    verifier_channel
+        .check_canonical_elements()
+        .map_err(|e| anyhow!("{}", e))?;
+
+    for (a, inv_a) in pub_inputs.inv_nondet.iter() {
+        let a_copy: u64 = a.deserialize(&mut Infallible).unwrap();
+        let inv_a_copy: u64 = inv_a.deserialize(&mut Infallible).unwrap();
+        // inverses are returned from the hints as they are, and thus, must be canonical
+        if !B::from_mont(inv_a_copy).is_reduced() {
+            return Err(anyhow!("inverse hint is not in canonical form"));
+        }
+        INV_NONDET.lock().insert(a_copy, inv_a_copy);
+    }
+    for (a, inv_a) in pub_inputs.inv_nondet_quad.iter() {
+        let a_copy: [u64; 2] = a.deserialize(&mut Infallible).unwrap();
+        let inv_a_copy: [u64; 2] = inv_a.deserialize(&mut Infallible).unwrap();
+        INV_NONDET_QUAD.lock().insert(a_copy, inv_a_copy);
+    }
+
+    // make sure the trace commitments were packaged for the trace layout of the AIR, so that the
+    // random elements drawn from the public coin are associated with the right segments
+    let trace_segments: Vec<TraceSegmentInfo> = pub_inputs
+        .trace_segments
+        .deserialize(&mut Infallible)
+        .unwrap();
+    check_trace_segments(
+        &trace_segments,
+        air.trace_layout(),
+        verifier_channel.read_trace_commitments().len(),
+    )
+    .map_err(|e| anyhow!("{}", e))?;
+
+    // the coin is seeded in the same way as by the fib_verify guest
+    let context =
+        VerifierContext::from_bytes(pub_inputs.context.as_slice()).map_err(|e| anyhow!("{}", e))?;
+    let aux_data_commitment = pub_inputs.aux_data_commitment.as_ref();
+    let public_coin_seed =
+        context.coin_seed(&seed_pub_inputs(&result.to_bytes(), aux_data_commitment));
+
+    verify_phase(
+        &air,
+        &mut verifier_channel,
+        &public_coin_seed,
+        phase,
+        prev_state,
+    )
+    .map_err(|e| anyhow!("{}", e))
+}
+
+/// Checks that every proof with the provided AIR input was generated with options which satisfy
+/// the provided policy, and of a trace with a length within the bounds of the guest.
+fn check_policy(policy: &ProofPolicy, air_inputs: &[FibAirInput]) -> Result<(), ErrorChain> {
+    for (i, air_input) in air_inputs.iter().enumerate() {
+        policy.check(&air_input.proof_options).map_err(|e| {
+            ErrorChain::from(e).context(
+                ErrorKind::Policy,
+                format!("fib proof #{} violates the proof policy", i + 1),
+            )
+        })?;
+        TRACE_LENGTH_BOUNDS
+            .check(air_input.trace_info.length())
+            .map_err(|e| {
+                ErrorChain::from(e).context(
+                    ErrorKind::Policy,
+                    format!("fib proof #{} has an unsupported trace length", i + 1),
+                )
+            })?;
+    }
+    Ok(())
+}
+
+/// Runs the specified phase for every packaged proof, each resumed from its own state.
+fn verify_fib_phases(
+    pub_inputs: &[ArchivedFibRiscInput<E, H>],
+    air_inputs: Vec<FibAirInput>,
+    results: &[B],
+    phase: VerificationPhase,
+    prev_states: &[PhaseState],
+) -> Result<Vec<PhaseState>> {
+    // the first phase is not resumed from any state
+    if phase.previous().is_some() && prev_states.len() != pub_inputs.len() {
+        return Err(anyhow!(
+            "expected {} states to resume the {} phase from, but received {}",
+            pub_inputs.len(),
+            phase,
+            prev_states.len()
+        ));
+    }
+    let mut states = Vec::with_capacity(pub_inputs.len());
+    for (i, ((pub_inputs, air_input), &result)) in
+        pub_inputs.iter().zip(air_inputs).zip(results).enumerate()
+    {
+        let state = verify_fib_phase(pub_inputs, air_input, result, phase, prev_states.get(i))
+            .with_context(|| format!("failed to run the {} phase of proof #{}", phase, i + 1))?;
+        states.push(state);
+    }
+    Ok(states)
+}
+
+pub fn main() {
+    // the policy header is read before any other input
+    let policy_header: [u8; POLICY_LEN] = env::read();
+    let policy = ProofPolicy::from_bytes(&policy_header).map_err(|e| {
+        ErrorChain::from(e).context(ErrorKind::Policy, "failed to decode proof policy")
+    });
+    let journal = match &policy {
+        Ok(policy) => run_phase(Ok(policy)).with_policy_digest(policy.digest::<GuestSha2>()),
+        Err(error) => run_phase(Err(error)),
+    };
+    guest_log!("ran the {} phase: {}", journal.phase, journal.result);
+    env::commit(&journal);
+}
+
+/// Runs the phase read from the inputs for both packaged proofs under the provided policy, and
+/// returns the phase journal describing the outcome; the phase fails without running if the
+/// policy or the aux input could not be decoded.
+fn run_phase(policy: Result<&ProofPolicy, &ErrorChain>) -> PhaseJournal {
+    let aux_input = decode_aux_input(env::read_aux_input());
+    // the phase and the states it is resumed from are read after the AIR inputs of all proofs
+    let air_inputs: Vec<FibAirInput> = (0..2).map(|_| env::read()).collect();
+    let phase: VerificationPhase = env::read();
+    let prev_states: Vec<PhaseState> = env::read();
+    let prev_states_digest = phase_states_digest::<GuestSha2>(&prev_states);
+
+    // the statements are unknown if the aux input cannot be decoded, and thus, the digests of the
+    // journal are zero
+    let aux_input = match aux_input {
+        Ok(aux_input) => aux_input,
+        Err(e) => {
+            let error =
+                ErrorChain::from(e).context(ErrorKind::AuxInput, "failed to decode aux input");
+            guest_log!("error: {}", error);
+            return PhaseJournal::with_error([0; 32], [0; 32], phase, prev_states_digest, error);
+        }
+    };
+    let pub_inputs_arr =
+        unsafe { rkyv::archived_root::<[FibRiscInput<E, H>; 2]>(aux_input.as_bytes()) };
+
+    let mut parts = Vec::new();
+    let mut results = Vec::new();
+    for (pub_inputs, air_input) in pub_inputs_arr.iter().zip(&air_inputs) {
+        let result: B = pub_inputs.result.deserialize(&mut Infallible).unwrap();
+
+        parts.push(pub_inputs.context.as_slice().to_vec());
+        parts.push(seed_pub_inputs(
+            &result.to_bytes(),
+            pub_inputs.aux_data_commitment.as_ref(),
+        ));
+        parts.push(trace_info_bytes(&air_input.trace_info));
+        results.push(result);
+    }
+
+    let parts: Vec<&[u8]> = parts.iter().map(|part| part.as_slice()).collect();
+    let statement_digest = statement_digest::<GuestSha2>(&parts);
+    let options: Vec<_> = air_inputs
+        .iter()
+        .map(|air_input| air_input.proof_options.clone())
+        .collect();
+    let options_digest = options_digest::<GuestSha2>(&options);
+
+    // proofs which fib_verify would reject under the policy are rejected in every phase
+    let checked = policy
+        .map_err(ErrorChain::clone)
+        .and_then(|policy| check_policy(policy, &air_inputs));
+    if let Err(error) = checked {
+        guest_log!("error: {}", error);
+        return PhaseJournal::with_error(
+            statement_digest,
+            options_digest,
+            phase,
+            prev_states_digest,
+            error,
+        );
+    }
+
+    match verify_fib_phases(pub_inputs_arr, air_inputs, &results, phase, &prev_states) {
+        Ok(states) => PhaseJournal::new(
+            statement_digest,
+            options_digest,
+            phase,
+            prev_states_digest,
+            states,
+        ),
+        Err(e) => {
+            guest_log!("error: {:?}", e);
+            let error = ErrorChain::from_messages(ErrorKind::Verification, e.chain());
+            PhaseJournal::with_error(
+                statement_digest,
+                options_digest,
+                phase,
+                prev_states_digest,
+                error,
+            )
+        }
+    }
+}
@@ -0,0 +1,177 @@
+#![no_main]
+#![no_std]
+extern crate alloc;
+
+use alloc::format;
+use alloc::vec::Vec;
+use anyhow::{anyhow, Context, Result};
+use errors::{ErrorChain, ErrorKind};
+use methods_guest::{guest_log, GuestSha2};
+use risc0_zkvm_guest::{env, mul};
+use rkyv::Deserialize;
+use utils::aux_data::seed_pub_inputs;
+use utils::aux_input::decode_aux_input;
+use utils::context::VerifierContext;
+use utils::fib::fib_air::FibAir as FA;
+use utils::inputs::{
+    check_trace_segments, ArchivedFibRiscInput, FibAirInput, FibRiscInput, TraceSegmentInfo,
+};
+use utils::journal::{statement_digest, trace_info_bytes, TranscriptJournal};
+use utils::transcript::replay_transcript;
+use winter_air::Air;
+use winter_crypto::hashers::Sha2_256;
+use winter_math::fields::f64_risc0::{AccelBaseElementRisc0, NativeMontMul};
+use winter_math::fields::QuadExtension;
+use winter_math::StarkField;
+use winter_utils::Serializable;
+use winter_verifier::VerifierChannel;
+
+risc0_zkvm_guest::entry!(main);
+
+// Replays the Fiat–Shamir transcripts of the packaged Fibonacci proofs without verifying them.
+// The guest reads the same inputs as the fib_verify guest, and commits the final seed of the
+// public coin of every proof, which the host compares against the seeds it computes from the
+// original proofs. This is a cheap way to detect bugs in the packaging of the proofs before
+// running the full verification.
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Risc0NativeMul {}
+impl NativeMontMul for Risc0NativeMul {
+    fn native_mul_ext(a: [u64; 2], b: [u64; 2]) -> [u64; 2] {
+        mul::mul_goldilocks(&a, &b).get_u64()
+    }
+}
+
+type B = AccelBaseElementRisc0<Risc0NativeMul>;
+type FibAir = FA<Risc0NativeMul>;
+type E = QuadExtension<B>;
+type H = Sha2_256<B, GuestSha2>;
+type C = VerifierChannel<E, H>;
+
+// VERIFICATION KEY
+// ================================================================================================
+
+// defines FIB_VK, the verification key of the Fibonacci AIR computed by the build script
+include!(concat!(env!("OUT_DIR"), "/fib_vk.rs"));
+
+// TRANSCRIPT REPLAY
+// ================================================================================================
+
+/// Replays the transcript of the provided packaged proof and returns the final seed of the
+/// public coin.
+pub fn replay_fib_transcript(
+    pub_inputs: &ArchivedFibRiscInput<E, H>,
+    air_input: FibAirInput,
+    result: B,
+) -> Result<[u8; 32]> {
+    // elements are archived in their internal representation, which is not checked by rkyv
+    if !result.is_reduced() {
+        return Err(anyhow!("fib result is not in canonical form"));
+    }
+    let air = FibAir::from_verification_key(
+        &FIB_VK,
+        air_input.trace_info,
+        result,
+        air_input.proof_options,
+    )
+    .map_err(|e| {
+        anyhow!(
+            "trace info is inconsistent with the verification key: {}",
+            e
+        )
+    })?;
+
+    let mut verifier_channel: C = pub_inputs
+        .verifier_channel
+        .deserialize(&mut rkyv::Infallible)
+        .unwrap();
+    verifier_channel
+        .check_canonical_elements()
+        .map_err(|e| anyhow!("{}", e))?;
+
+    // make sure the trace commitments were packaged for the trace layout of the AIR, so that the
+    // random elements drawn from the public coin are associated with the right segments
+    let trace_segments: Vec<TraceSegmentInfo> = pub_inputs
+        .trace_segments
+        .deserialize(&mut rkyv::Infallible)
+        .unwrap();
+    check_trace_segments(
+        &trace_segments,
+        air.trace_layout(),
+        verifier_channel.read_trace_commitments().len(),
+    )
+    .map_err(|e| anyhow!("{}", e))?;
+
+    // the coin is seeded in the same way as by the fib_verify guest
+    let context =
+        VerifierContext::from_bytes(pub_inputs.context.as_slice()).map_err(|e| anyhow!("{}", e))?;
+    let aux_data_commitment = pub_inputs.aux_data_commitment.as_ref();
+    let public_coin_seed =
+        context.coin_seed(&seed_pub_inputs(&result.to_bytes(), aux_data_commitment));
+
+    let state = replay_transcript(&air, &mut verifier_channel, &public_coin_seed)
+        .map_err(|e| anyhow!("failed to replay transcript: {}", e))?;
+    Ok(state.seed)
+}
+
+pub fn main() {
+    let journal = replay_inputs();
+    guest_log!(
+        "replayed {} transcripts: {}",
+        journal.final_seeds.len(),
+        journal.result
+    );
+    env::commit(&journal);
+}
+
+/// Replays the transcripts of both packaged proofs and returns the transcript journal describing
+/// the outcome; failures are logged, and reported via the result and the error chain of the
+/// journal.
+fn replay_inputs() -> TranscriptJournal {
+    // the statements are unknown if the aux input cannot be decoded, and thus, the digest of the
+    // journal is zero
+    let aux_input = match decode_aux_input(env::read_aux_input()) {
+        Ok(aux_input) => aux_input,
+        Err(e) => {
+            let error =
+                ErrorChain::from(e).context(ErrorKind::AuxInput, "failed to decode aux input");
+            guest_log!("error: {}", error);
+            return TranscriptJournal::with_error([0; 32], error);
+        }
+    };
+    let pub_inputs_arr =
+        unsafe { rkyv::archived_root::<[FibRiscInput<E, H>; 2]>(aux_input.as_bytes()) };
+
+    let mut parts = Vec::new();
+    let mut seeds = Vec::new();
+    for (i, pub_inputs) in pub_inputs_arr.iter().enumerate() {
+        let result: B = pub_inputs
+            .result
+            .deserialize(&mut rkyv::Infallible)
+            .unwrap();
+        let air_input: FibAirInput = env::read();
+
+        parts.push(pub_inputs.context.as_slice().to_vec());
+        parts.push(seed_pub_inputs(
+            &result.to_bytes(),
+            pub_inputs.aux_data_commitment.as_ref(),
+        ));
+        parts.push(trace_info_bytes(&air_input.trace_info));
+
+        seeds.push(
+            replay_fib_transcript(pub_inputs, air_input, result)
+                .with_context(|| format!("failed to replay the transcript of proof #{}", i + 1)),
+        );
+    }
+
+    let parts: Vec<&[u8]> = parts.iter().map(|part| part.as_slice()).collect();
+    let statement_digest = statement_digest::<GuestSha2>(&parts);
+    match seeds.into_iter().collect::<Result<Vec<_>>>() {
+        Ok(final_seeds) => TranscriptJournal::new(statement_digest, final_seeds),
+        Err(e) => {
+            guest_log!("error: {:?}", e);
+            let error = ErrorChain::from_messages(ErrorKind::Verification, e.chain());
+            TranscriptJournal::with_error(statement_digest, error)
+        }
+    }
+}
@@ -5,32 +5,101 @@ extern crate alloc;
 use alloc::format;
 use alloc::vec::Vec;
 use anyhow::{anyhow, Context, Result};
-use risc0_zkvm_guest::{env, mul, sha};
-use rkyv::Deserialize;
+use errors::{ErrorChain, ErrorKind};
+use methods_guest::verify::{verify_in_guest, VerifyProfiler};
+use methods_guest::{
+    check_proof_bounds, decode_aux_input, failed_journal, guest_log, run_verifier, GuestSha2,
+};
+use risc0_zkvm_guest::{env, mul};
+use rkyv::{Archive, Archived, Deserialize, Infallible};
+use utils::aux_data::seed_pub_inputs;
+use utils::context::VerifierContext;
+use utils::estimate::{estimate, TraceShape};
 use utils::fib::fib_air::FibAir as FA;
-use utils::inputs::{ArchivedFibRiscInput, FibAirInput, FibRiscInput};
-use winter_air::{Air, AuxTraceRandElements, ConstraintCompositionCoefficients};
-use winter_crypto::ElementHasher;
-use winter_crypto::{
-    hashers::{Sha2_256, ShaHasherT},
-    RandomCoin,
+use utils::hasher::GuestHasher;
+use utils::heap::{aux_input_heap_estimate, EstimatedHeapBudget, HeapPhase};
+use utils::inputs::{ArchivedFibRiscInput, FibAirInput, FibRiscInput, TraceSegmentInfo};
+use utils::journal::{options_digest, statement_digest, trace_info_bytes, Journal};
+use utils::policy::ProofPolicy;
+use utils::poseidon::Poseidon64_256;
+#[cfg(feature = "profile")]
+use utils::profiler::CycleProfiler;
+use utils::trace_bounds::TraceLengthBounds;
+use winter_air::HashFunction;
+use winter_crypto::hashers::{
+    Blake3_256, DefaultSha384, DefaultSha512, Keccak256, Sha2_256, Sha2_384, Sha2_512,
+    Sha384HasherT, Sha512HasherT, ShaHasherT,
 };
 use winter_math::fields::f64_risc0::{
     AccelBaseElementRisc0, BaseElement, DefaultNativeMul, NativeMontMul, INV_NONDET,
     INV_NONDET_QUAD,
 };
 use winter_math::fields::QuadExtension;
-use winter_math::FieldElement;
+use winter_math::{FieldElement, StarkField};
 use winter_utils::Serializable;
-use winter_verifier::{evaluate_constraints, DeepComposer, FriVerifier, VerifierChannel};
+use winter_verifier::VerifierChannel;
 
 risc0_zkvm_guest::entry!(main);
 
-pub struct GuestSha2;
+/// The accelerated SHA-256 of [GuestSha2], with the cycles spent on digests attributed to a
+/// frame of the cycle profiler.
+pub struct ProfiledSha2;
 
-impl ShaHasherT for GuestSha2 {
+impl ShaHasherT for ProfiledSha2 {
     fn digest(data: &[u8]) -> [u8; 32] {
-        sha::digest_u8_slice(data).get_u8()
+        profile_enter("sha2");
+        let digest = GuestSha2::digest(data);
+        profile_exit();
+        digest
+    }
+
+    /// Digests the rows of queried trace states and FRI layers, and the out-of-domain rows, under
+    /// a single profiler scope.
+    fn digest_batch(data: &[u8], chunk_len: usize) -> Vec<[u8; 32]> {
+        profile_enter("sha2");
+        let digests = GuestSha2::digest_batch(data, chunk_len);
+        profile_exit();
+        digests
+    }
+}
+
+/// SHA-512 for proofs generated with 64-byte digests; the guest has no SHA-512 accelerator, and
+/// thus, digests are computed in software.
+pub struct GuestSha512;
+
+impl Sha512HasherT for GuestSha512 {
+    fn digest(data: &[u8]) -> [u8; 64] {
+        profile_enter("sha512");
+        let digest = DefaultSha512::digest(data);
+        profile_exit();
+        digest
+    }
+
+    fn digest_elements<E: FieldElement>(parts: &[&[E]]) -> [u8; 64] {
+        profile_enter("sha512");
+        let digest = DefaultSha512::digest_elements(parts);
+        profile_exit();
+        digest
+    }
+}
+
+/// SHA-384 for proofs generated with 48-byte digests, whose collision resistance allows proof
+/// security of up to 192 bits; like SHA-512, digests are computed in software.
+pub struct GuestSha384;
+
+impl Sha384HasherT for GuestSha384 {
+    fn digest(data: &[u8]) -> [u8; 48] {
+        profile_enter("sha384");
+        let digest = DefaultSha384::digest(data);
+        profile_exit();
+        digest
+    }
+
+    fn digest_elements<E: FieldElement>(parts: &[&[E]]) -> [u8; 48] {
+        profile_enter("sha384");
+        let digest = DefaultSha384::digest_elements(parts);
+        profile_exit();
+        digest
     }
 }
 
@@ -38,8 +107,10 @@ impl ShaHasherT for GuestSha2 {
 pub struct Risc0NativeMul {}
 impl NativeMontMul for Risc0NativeMul {
     fn native_mul_ext(a: [u64; 2], b: [u64; 2]) -> [u64; 2] {
+        profile_enter("field_mul");
         let res = mul::mul_goldilocks(&a, &b);
         let r64 = res.get_u64();
+        profile_exit();
 
         // let a_fp = [BaseElement::from_mont(a[0]), BaseElement::from_mont(a[1])];
         // let b_fp = [BaseElement::from_mont(b[0]), BaseElement::from_mont(b[1])];
@@ -71,267 +142,403 @@ impl NativeMontMul for Risc0NativeMul {
 type B = AccelBaseElementRisc0<Risc0NativeMul>;
 type FibAir = FA<Risc0NativeMul>;
 type E = QuadExtension<B>;
-type H = Sha2_256<B, GuestSha2>;
-type C = VerifierChannel<E, H>;
-
-pub fn aux_trace_segments(
-    verifier_channel: &C,
-    public_coin: &mut RandomCoin<B, H>,
-    air: &FibAir,
-) -> Result<AuxTraceRandElements<E>> {
-    let mut aux_trace_rand_elements = AuxTraceRandElements::<E>::new();
-    for (i, commitment) in verifier_channel
-        .read_trace_commitments()
-        .iter()
-        .skip(1)
-        .enumerate()
+type H256 = Sha2_256<B, ProfiledSha2>;
+type H384 = Sha2_384<B, GuestSha384>;
+type H512 = Sha2_512<B, GuestSha512>;
+type HBlake3 = Blake3_256<B>;
+type HKeccak = Keccak256<B>;
+type HPoseidon = Poseidon64_256<B>;
+
+// VERIFICATION KEY
+// ================================================================================================
+
+// defines FIB_VK, the verification key of the Fibonacci AIR computed by the build script
+include!(concat!(env!("OUT_DIR"), "/fib_vk.rs"));
+
+// defines HEAP_ESTIMATE_CAP, the number of estimated heap bytes the verification may reserve, set
+// by the build script
+include!(concat!(env!("OUT_DIR"), "/heap_estimate_cap.rs"));
+
+// defines TRACE_LENGTH_BOUNDS, the trace lengths of the accepted proofs, set by the build script
+include!(concat!(env!("OUT_DIR"), "/trace_bounds.rs"));
+
+// PROFILING
+// ================================================================================================
+
+#[cfg(feature = "profile")]
+static PROFILER: spin::Mutex<Option<CycleProfiler>> = spin::Mutex::new(None);
+
+/// Opens a profiler frame with the specified name; this is a no-op unless the guest is built
+/// with the `profile` feature.
+#[inline(always)]
+fn profile_enter(_name: &'static str) {
+    #[cfg(feature = "profile")]
     {
-        let rand_elements = air
-            .get_aux_trace_segment_random_elements(i, public_coin)
-            .map_err(|_| anyhow!("Random coin error"))?;
-        aux_trace_rand_elements.add_segment_elements(rand_elements);
-        public_coin.reseed(*commitment);
+        let cycle = env::get_cycle_count() as u64;
+        PROFILER
+            .lock()
+            .get_or_insert_with(CycleProfiler::new)
+            .enter(_name, cycle);
     }
-    Ok(aux_trace_rand_elements)
 }
 
-pub fn get_constraint_coffs(
-    public_coin: &mut RandomCoin<B, H>,
-    air: &FibAir,
-) -> Result<ConstraintCompositionCoefficients<E>> {
-    let constraint_coeffs = air
-        .get_constraint_composition_coefficients(public_coin)
-        .map_err(|_| anyhow!("Random coin error"))?;
-    Ok(constraint_coeffs)
+/// Closes the innermost profiler frame; this is a no-op unless the guest is built with the
+/// `profile` feature.
+#[inline(always)]
+fn profile_exit() {
+    #[cfg(feature = "profile")]
+    {
+        let cycle = env::get_cycle_count() as u64;
+        if let Some(profiler) = PROFILER.lock().as_mut() {
+            profiler.exit(cycle);
+        }
+    }
 }
 
-pub fn init_public_coin_seed<S: Serializable>(
-    public_coin_seed: &mut Vec<u8>,
-    result: S,
-    context: &[u8],
-) {
-    result.write_into(public_coin_seed);
-    public_coin_seed.extend(context);
+/// Commits the collected folded stacks to the journal after the [Journal]; this is a no-op unless
+/// the guest is built with the `profile` feature.
+fn profile_commit() {
+    #[cfg(feature = "profile")]
+    {
+        let folded = PROFILER
+            .lock()
+            .take()
+            .map(CycleProfiler::into_folded)
+            .unwrap_or_default();
+        env::commit(&folded);
+    }
 }
 
-pub fn run_main_logic() -> Result<()> {
-    // Deserialize public inputs
-    let aux_input: &[u8] = env::read_aux_input();
-    let pub_inputs_arr = unsafe { rkyv::archived_root::<[FibRiscInput<E, H>; 2]>(&aux_input[..]) };
+/// Profiler of the shared verification flow, which opens a frame of the cycle profiler for every
+/// phase of the verification.
+struct FibProfiler;
+
+impl VerifyProfiler for FibProfiler {
+    fn enter(&self, name: &'static str) {
+        profile_enter(name);
+    }
+
+    fn exit(&self) {
+        profile_exit();
+    }
+}
+
+/// Verifies both Fibonacci proofs and returns the journal describing the outcome; verification
+/// failures are logged, and reported via the result and the error chain of the journal.
+///
+/// The proofs are verified under the proof policy read from the header of the inputs, and the
+/// journal is bound to the digest of the policy.
+pub fn run_main_logic() -> Journal {
+    run_verifier(verify_inputs)
+}
+
+/// Verifies both Fibonacci proofs under the provided policy and returns the journal describing
+/// the outcome.
+fn verify_inputs(policy: &ProofPolicy) -> Journal {
+    // oversized inputs are rejected before they are decompressed
+    let mut heap_budget = EstimatedHeapBudget::new(HEAP_ESTIMATE_CAP);
+    let raw_aux_input = env::read_aux_input();
+    let aux_input_heap_size = match aux_input_heap_estimate(raw_aux_input) {
+        Ok(size) => size,
+        Err(error) => {
+            return failed_journal([0; 32], [0; 32], error);
+        }
+    };
+    if let Err(e) = heap_budget.reserve_estimate(HeapPhase::AuxInput, aux_input_heap_size) {
+        guest_log!("error: {}", e);
+        return Journal::with_heap_estimate_exceeded([0; 32], [0; 32], e);
+    }
+
+    // Deserialize public inputs; the host may have compressed them to reduce the input size
+    profile_enter("decode_aux_input");
+    let aux_input = match decode_aux_input(raw_aux_input) {
+        Ok(aux_input) => aux_input,
+        Err(error) => return failed_journal([0; 32], [0; 32], error),
+    };
+    profile_exit();
+    let air_input_1: FibAirInput = env::read();
+    let air_input_2: FibAirInput = env::read();
+
+    // the layout of the packaged proofs depends on the digest size of their hash function, and
+    // thus, the proofs are accessed only once the hash function is known
+    let hash_fn = air_input_1.proof_options.hash_fn();
+    if air_input_2.proof_options.hash_fn() != hash_fn {
+        let error = ErrorChain::new(
+            ErrorKind::Verification,
+            "fib proofs are generated with different hash functions",
+        );
+        return failed_journal([0; 32], [0; 32], error);
+    }
+    match hash_fn {
+        HashFunction::Sha2_256 => verify_fib_proofs::<H256>(
+            aux_input.as_bytes(),
+            air_input_1,
+            air_input_2,
+            policy,
+            heap_budget,
+        ),
+        HashFunction::Sha2_384 => verify_fib_proofs::<H384>(
+            aux_input.as_bytes(),
+            air_input_1,
+            air_input_2,
+            policy,
+            heap_budget,
+        ),
+        HashFunction::Sha2_512 => verify_fib_proofs::<H512>(
+            aux_input.as_bytes(),
+            air_input_1,
+            air_input_2,
+            policy,
+            heap_budget,
+        ),
+        HashFunction::Blake3_256 => verify_fib_proofs::<HBlake3>(
+            aux_input.as_bytes(),
+            air_input_1,
+            air_input_2,
+            policy,
+            heap_budget,
+        ),
+        HashFunction::Keccak256 => verify_fib_proofs::<HKeccak>(
+            aux_input.as_bytes(),
+            air_input_1,
+            air_input_2,
+            policy,
+            heap_budget,
+        ),
+        HashFunction::Poseidon64_256 => verify_fib_proofs::<HPoseidon>(
+            aux_input.as_bytes(),
+            air_input_1,
+            air_input_2,
+            policy,
+            heap_budget,
+        ),
+        hash_fn => {
+            let error = ErrorChain::new(
+                ErrorKind::Verification,
+                format!(
+                    "fib proofs with hash function {:?} are not supported",
+                    hash_fn
+                ),
+            );
+            failed_journal([0; 32], [0; 32], error)
+        }
+    }
+}
+
+/// Verifies both Fibonacci proofs packaged in the provided aux input with the specified hasher
+/// under the provided policy, and returns the journal describing the outcome.
+fn verify_fib_proofs<H: GuestHasher<BaseField = B>>(
+    aux_input: &[u8],
+    air_input_1: FibAirInput,
+    air_input_2: FibAirInput,
+    policy: &ProofPolicy,
+    mut heap_budget: EstimatedHeapBudget,
+) -> Journal
+where
+    VerifierChannel<E, H>: Archive,
+    Archived<VerifierChannel<E, H>>: Deserialize<VerifierChannel<E, H>, Infallible>,
+{
+    let pub_inputs_arr = unsafe { rkyv::archived_root::<[FibRiscInput<E, H>; 2]>(aux_input) };
     let pub_inputs_1 = &pub_inputs_arr[0];
     let pub_inputs_2 = &pub_inputs_arr[1];
 
-    // verify first proof
-    env::log("Running proof #1 execution trace simulation");
-    let result = pub_inputs_1
-        .result
-        .deserialize(&mut rkyv::Infallible)
-        .unwrap();
-    let air_input_1: FibAirInput = env::read();
-    let air_1 = FibAir::new(air_input_1.trace_info, result, air_input_1.proof_options);
+    let result_1: B = pub_inputs_1.result.deserialize(&mut Infallible).unwrap();
+    let result_2: B = pub_inputs_2.result.deserialize(&mut Infallible).unwrap();
+    let aux_data_commitments: [Option<[u8; 32]>; 2] = [
+        pub_inputs_1
+            .aux_data_commitment
+            .deserialize(&mut Infallible)
+            .unwrap(),
+        pub_inputs_2
+            .aux_data_commitment
+            .deserialize(&mut Infallible)
+            .unwrap(),
+    ];
+
+    // bind the journal to the verified statements, including the commitments to auxiliary data
+    // which follow the results in the seeds and the trace infos the AIRs are instantiated from,
+    // and to the options they are verified under
+    let statement_digest = statement_digest::<ProfiledSha2>(&[
+        pub_inputs_1.context.as_slice(),
+        seed_pub_inputs(&result_1.to_bytes(), aux_data_commitments[0].as_ref()).as_slice(),
+        trace_info_bytes(&air_input_1.trace_info).as_slice(),
+        pub_inputs_2.context.as_slice(),
+        seed_pub_inputs(&result_2.to_bytes(), aux_data_commitments[1].as_ref()).as_slice(),
+        trace_info_bytes(&air_input_2.trace_info).as_slice(),
+    ]);
+    let options_digest = options_digest::<ProfiledSha2>(&[
+        air_input_1.proof_options.clone(),
+        air_input_2.proof_options.clone(),
+    ]);
+    // every journal from here on surfaces the commitments of the statements
+    let journal_of =
+        |journal: Journal| journal.with_aux_data_commitments(aux_data_commitments.to_vec());
+
+    // proofs generated with options weaker than the policy allows, or of traces with lengths
+    // outside of the bounds of the guest, are rejected before they are verified
+    for (i, air_input) in [&air_input_1, &air_input_2].iter().enumerate() {
+        if let Err(error) = check_proof_bounds(
+            policy,
+            &TRACE_LENGTH_BOUNDS,
+            &air_input.proof_options,
+            air_input.trace_info.length(),
+            &format!("fib proof #{}", i + 1),
+        ) {
+            return journal_of(failed_journal(statement_digest, options_digest, error));
+        }
+    }
 
-    verify_winter_fib_proof(pub_inputs_1, air_1)
-        .with_context(|| "failed to verify first fib proof")?;
+    // reserve the heap needed to verify both proofs before verifying the first one
+    for air_input in [&air_input_1, &air_input_2] {
+        if let Err(e) =
+            heap_budget.reserve_estimate(HeapPhase::Verification, verification_heap_size(air_input))
+        {
+            guest_log!("error: {}", e);
+            return journal_of(Journal::with_heap_estimate_exceeded(
+                statement_digest,
+                options_digest,
+                e,
+            ));
+        }
+    }
+
+    // verify first proof
+    guest_log!("Running proof #1 execution trace simulation");
+    profile_enter("verify_proof_1");
+    let result = build_air(air_input_1, result_1)
+        .and_then(|air_1| verify_winter_fib_proof(pub_inputs_1, air_1))
+        .with_context(|| "failed to verify first fib proof");
+    profile_exit();
 
     // verify second proof
-    env::log("Running proof #2 execution trace simulation");
-    let result = pub_inputs_2
-        .result
-        .deserialize(&mut rkyv::Infallible)
-        .unwrap();
-    let air_input_2: FibAirInput = env::read();
-    let air_2 = FibAir::new(air_input_2.trace_info, result, air_input_2.proof_options);
+    let result = result.and_then(|_| {
+        guest_log!("Running proof #2 execution trace simulation");
+        profile_enter("verify_proof_2");
+        let result = build_air(air_input_2, result_2)
+            .and_then(|air_2| verify_winter_fib_proof(pub_inputs_2, air_2))
+            .with_context(|| "failed to verify second fib proof");
+        profile_exit();
+        result
+    });
+
+    match result {
+        Ok(()) => journal_of(Journal::new(statement_digest, options_digest, true)),
+        Err(e) => {
+            guest_log!("error: {:?}", e);
+            let error = ErrorChain::from_messages(ErrorKind::Verification, e.chain());
+            journal_of(Journal::with_error(statement_digest, options_digest, error))
+        }
+    }
+}
 
-    verify_winter_fib_proof(pub_inputs_2, air_2)
-        .with_context(|| "failed to verify second fib proof")
+/// Returns the estimated number of heap bytes needed to deserialize and verify a proof with the
+/// provided trace info and proof options.
+pub fn verification_heap_size(air_input: &FibAirInput) -> usize {
+    let shape = TraceShape::new(air_input.trace_info.length(), air_input.trace_info.width());
+    estimate(&shape, &air_input.proof_options).heap_size
 }
 
-pub fn verify_winter_fib_proof(pub_inputs: &ArchivedFibRiscInput<E, H>, air: FibAir) -> Result<()> {
-    let mut verifier_channel: C = pub_inputs
+/// Instantiates the Fibonacci AIR from the embedded verification key; this fails if the trace
+/// info or the proof options provided by the host are not consistent with the key.
+pub fn build_air(air_input: FibAirInput, result: B) -> Result<FibAir> {
+    profile_enter("build_air");
+    let air = FibAir::from_verification_key(
+        &FIB_VK,
+        air_input.trace_info,
+        result,
+        air_input.proof_options,
+    )
+    .map_err(|e| {
+        anyhow!(
+            "trace info is inconsistent with the verification key: {}",
+            e
+        )
+    });
+    profile_exit();
+    air
+}
+
+/// Verifies the packaged Fibonacci proof against the provided AIR; the public input, the inverse
+/// hints, the verifier context, and the positions of the openings are read from the packaged
+/// inputs, and the protocol itself is run by [verify_in_guest].
+pub fn verify_winter_fib_proof<H: GuestHasher<BaseField = B>>(
+    pub_inputs: &ArchivedFibRiscInput<E, H>,
+    air: FibAir,
+) -> Result<()>
+where
+    VerifierChannel<E, H>: Archive,
+    Archived<VerifierChannel<E, H>>: Deserialize<VerifierChannel<E, H>, Infallible>,
+{
+    profile_enter("deserialize");
+    let verifier_channel: VerifierChannel<E, H> = pub_inputs
         .verifier_channel
-        .deserialize(&mut rkyv::Infallible)
+        .deserialize(&mut Infallible)
         .unwrap();
     // Extract result (pub input to Fib proof)
-    let result: B = pub_inputs
-        .result
-        .deserialize(&mut rkyv::Infallible)
-        .unwrap();
+    let result: B = pub_inputs.result.deserialize(&mut Infallible).unwrap();
+
+    // elements are archived in their internal representation, which is not checked by rkyv; an
+    // element which is not in canonical form would not compare as equal to the same element
+    // computed by the guest (the elements of the channel are checked by the verification flow)
+    if !result.is_reduced() {
+        return Err(anyhow!("fib result is not in canonical form"));
+    }
 
     for (a, inv_a) in pub_inputs.inv_nondet.iter() {
-        let a_copy: u64 = a.deserialize(&mut rkyv::Infallible).unwrap();
-        let inv_a_copy: u64 = inv_a.deserialize(&mut rkyv::Infallible).unwrap();
+        let a_copy: u64 = a.deserialize(&mut Infallible).unwrap();
+        let inv_a_copy: u64 = inv_a.deserialize(&mut Infallible).unwrap();
+        // inverses are returned from the hints as they are, and thus, must be canonical
+        if !B::from_mont(inv_a_copy).is_reduced() {
+            return Err(anyhow!("inverse hint is not in canonical form"));
+        }
         INV_NONDET.lock().insert(a_copy, inv_a_copy);
     }
 
     for (a, inv_a) in pub_inputs.inv_nondet_quad.iter() {
-        let a_copy: [u64; 2] = a.deserialize(&mut rkyv::Infallible).unwrap();
-        let inv_a_copy: [u64; 2] = inv_a.deserialize(&mut rkyv::Infallible).unwrap();
+        let a_copy: [u64; 2] = a.deserialize(&mut Infallible).unwrap();
+        let inv_a_copy: [u64; 2] = inv_a.deserialize(&mut Infallible).unwrap();
         INV_NONDET_QUAD.lock().insert(a_copy, inv_a_copy);
     }
 
-    // Extract context
-    let context = pub_inputs.context.as_slice();
-
-    // build a seed for the public coin; the initial seed is the hash of public inputs and proof
-    // context, but as the protocol progresses, the coin will be reseeded with the info received
-    // from the prover
-    let mut public_coin_seed = Vec::new();
-    init_public_coin_seed(&mut public_coin_seed, result, context);
-
-    let mut public_coin: RandomCoin<B, H> = RandomCoin::new(&public_coin_seed);
-
-    // reseed the coin with the commitment to the main trace segment
-    public_coin.reseed(verifier_channel.read_trace_commitments()[0]);
-
-    // process auxiliary trace segments (if any), to build a set of random elements for each segment
-    let aux_trace_rand_elements = aux_trace_segments(&verifier_channel, &mut public_coin, &air)
-        .expect("aux trace segments failed");
-
-    // build random coefficients for the composition polynomial
-    let constraint_coeffs =
-        get_constraint_coffs(&mut public_coin, &air).expect("constraint_coeffs_error");
-    // env::log(&format!("constraint coeffs: {:?}", &constraint_coeffs));
-
-    // 2 ----- constraint commitment --------------------------------------------------------------
-    // let constraint_commitment = ByteDigest::new(pub_inputs.constraint_commitment);
-    // env::log(&format!("constraint commitment"));
-    public_coin.reseed(verifier_channel.read_constraint_commitment());
-    let z = public_coin
-        .draw::<E>()
-        .map_err(|_| anyhow!("Random coin error"))
-        .expect("constraint_commitment");
-
-    // 3 ----- OOD consistency check --------------------------------------------------------------
-    // make sure that evaluations obtained by evaluating constraints over the out-of-domain frame
-    // are consistent with the evaluations of composition polynomial columns sent by the prover
-
-    // read the out-of-domain trace frames (the main trace frame and auxiliary trace frame, if
-    // provided) sent by the prover and evaluate constraints over them; also, reseed the public
-    // coin with the OOD frames received from the prover.
-
-    // env::log(&format!("ood_frame"));
-    let (ood_main_trace_frame, ood_aux_trace_frame) = verifier_channel.read_ood_trace_frame();
-    let ood_constraint_evaluation_1 = evaluate_constraints(
-        &air,
-        constraint_coeffs,
-        &ood_main_trace_frame,
-        &ood_aux_trace_frame,
-        aux_trace_rand_elements,
-        z,
-    );
-
-    // env::log(&format!("reseed ood_frame"));
-    if let Some(ref aux_trace_frame) = ood_aux_trace_frame {
-        // when the trace contains auxiliary segments, append auxiliary trace elements at the
-        // end of main trace elements for both current and next rows in the frame. this is
-        // needed to be consistent with how the prover writes OOD frame into the channel.
-
-        let mut current = ood_main_trace_frame.current().to_vec();
-        current.extend_from_slice(aux_trace_frame.current());
-        public_coin.reseed(H::hash_elements(&current));
-
-        let mut next = ood_main_trace_frame.next().to_vec();
-        next.extend_from_slice(aux_trace_frame.next());
-        public_coin.reseed(H::hash_elements(&next));
-    } else {
-        public_coin.reseed(H::hash_elements(ood_main_trace_frame.current()));
-        public_coin.reseed(H::hash_elements(ood_main_trace_frame.next()));
-    }
-
-    // // read evaluations of composition polynomial columns sent by the prover, and reduce them into
-    // // a single value by computing sum(z^i * value_i), where value_i is the evaluation of the ith
-    // // column polynomial at z^m, where m is the total number of column polynomials; also, reseed
-    // // the public coin with the OOD constraint evaluations received from the prover.
-    // env::log(&format!("ood_constraint_evaluation_2"));
-    let ood_constraint_evaluations = verifier_channel.read_ood_constraint_evaluations();
-    let ood_constraint_evaluation_2 = ood_constraint_evaluations
-        .iter()
-        .enumerate()
-        .fold(E::ZERO, |result, (i, &value)| {
-            result + z.exp((i as u32).into()) * value
-        });
-    public_coin.reseed(H::hash_elements(&ood_constraint_evaluations));
-
-    // finally, make sure the values are the same
-    if ood_constraint_evaluation_1 != ood_constraint_evaluation_2 {
-        panic!("Inconsistent OOD constraint evaluations");
-    }
-
-    // // 4 ----- FRI commitments --------------------------------------------------------------------
-    // // draw coefficients for computing DEEP composition polynomial from the public coin; in the
-    // // interactive version of the protocol, the verifier sends these coefficients to the prover
-    // // and the prover uses them to compute the DEEP composition polynomial. the prover, then
-    // // applies FRI protocol to the evaluations of the DEEP composition polynomial.
-    let deep_coefficients = air
-        .get_deep_composition_coefficients::<E, H>(&mut public_coin)
-        .map_err(|msg| anyhow!(msg))
+    // Extract the proof context; the domain tag of the verifier context is bound to the journal
+    // by the statement digest, but the prover seeded the coin with the proof context only
+    let context =
+        VerifierContext::from_bytes(pub_inputs.context.as_slice()).map_err(|e| anyhow!("{}", e))?;
+    let trace_segments: Vec<TraceSegmentInfo> = pub_inputs
+        .trace_segments
+        .deserialize(&mut Infallible)
         .unwrap();
+    let query_positions: Vec<u64> = pub_inputs
+        .query_positions
+        .deserialize(&mut Infallible)
+        .unwrap();
+    let aux_data_commitment: Option<[u8; 32]> = pub_inputs
+        .aux_data_commitment
+        .deserialize(&mut Infallible)
+        .unwrap();
+    profile_exit();
 
-    // instantiates a FRI verifier with the FRI layer commitments read from the channel. From the
-    // verifier's perspective, this is equivalent to executing the commit phase of the FRI protocol.
-    // The verifier uses these commitments to update the public coin and draw random points alpha
-    // from them; in the interactive version of the protocol, the verifier sends these alphas to
-    // the prover, and the prover uses them to compute and commit to the subsequent FRI layers.
-    let fri_verifier: FriVerifier<B, E, C, H> = FriVerifier::new(
-        &mut verifier_channel,
-        &mut public_coin,
-        air.options().to_fri_options(),
-        air.trace_poly_degree(),
+    let coin_seed = context.coin_seed(&seed_pub_inputs(
+        &result.to_bytes(),
+        aux_data_commitment.as_ref(),
+    ));
+    verify_in_guest(
+        &air,
+        verifier_channel,
+        &trace_segments,
+        Some(&query_positions),
+        &coin_seed,
+        &FibProfiler,
     )
-    .expect("fri verifier init failed");
-
-    // 5 ----- trace and constraint queries -------------------------------------------------------
-    // read proof-of-work nonce sent by the prover and update the public coin with it
-    public_coin.reseed_with_int(verifier_channel.read_pow_nonce());
-
-    // make sure the proof-of-work specified by the grinding factor is satisfied
-    if public_coin.leading_zeros() < air.options().grinding_factor() {
-        panic!("QuerySeedProofOfWorkVerificationFailed");
-    }
-
-    // draw pseudo-random query positions for the LDE domain from the public coin; in the
-    // interactive version of the protocol, the verifier sends these query positions to the prover,
-    // and the prover responds with decommitments against these positions for trace and constraint
-    // composition polynomial evaluations.
-    let query_positions = public_coin
-        .draw_integers(air.options().num_queries(), air.lde_domain_size())
-        .map_err(|_| anyhow!("random coin error"))?;
-    // read evaluations of trace and constraint composition polynomials at the queried positions;
-    // this also checks that the read values are valid against trace and constraint commitments
-    let (queried_main_trace_states, queried_aux_trace_states) = verifier_channel
-        .read_queried_trace_states(&query_positions)
-        .map_err(|e| anyhow!("read_queried_trace_states, e = {}", e))?;
-    let queried_constraint_evaluations = verifier_channel
-        .read_constraint_evaluations(&query_positions)
-        .map_err(|e| anyhow!("read_constraint_evaluations, e = {}", e))?;
-
-    // 6 ----- DEEP composition -------------------------------------------------------------------
-    // compute evaluations of the DEEP composition polynomial at the queried positions
-    let composer = DeepComposer::new(&air, &query_positions, z, deep_coefficients);
-    let t_composition = composer.compose_trace_columns(
-        queried_main_trace_states,
-        queried_aux_trace_states,
-        ood_main_trace_frame,
-        ood_aux_trace_frame,
-    );
-    let c_composition = composer
-        .compose_constraint_evaluations(queried_constraint_evaluations, ood_constraint_evaluations);
-    let deep_evaluations = composer.combine_compositions(t_composition, c_composition);
-
-    // 7 ----- Verify low-degree proof -------------------------------------------------------------
-    // make sure that evaluations of the DEEP composition polynomial we computed in the previous
-    // step are in fact evaluations of a polynomial of degree equal to trace polynomial degree
-    fri_verifier
-        .verify(&mut verifier_channel, &deep_evaluations, &query_positions)
-        .map_err(|e| anyhow!("fri verifier failed, e = {}", e))?;
-    Ok(())
 }
 
 pub fn main() {
-    match run_main_logic() {
-        Ok(_) => {}
-        Err(e) => {
-            env::log(&format!("error: {:?}", e));
-        }
-    }
+    profile_enter("main");
+    let journal = run_main_logic();
+    profile_exit();
+
+    // the cycle count is read last, so that it covers all of the verification
+    let journal = journal.with_cycle_count(env::get_cycle_count() as u64);
+    env::commit(&journal);
+    profile_commit();
 }
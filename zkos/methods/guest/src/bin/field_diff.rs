@@ -0,0 +1,15 @@
+#![no_main]
+#![no_std]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use risc0_zkvm_guest::env;
+use utils::ref_field::check_f64_pairs;
+
+risc0_zkvm_guest::entry!(main);
+
+pub fn main() {
+    let pairs: Vec<(u64, u64)> = env::read();
+    let mismatches = check_f64_pairs(&pairs);
+    env::commit(&(mismatches.len() as u32));
+}
@@ -0,0 +1,52 @@
+#![no_main]
+#![no_std]
+extern crate alloc;
+
+use errors::{ErrorChain, ErrorKind};
+use methods_guest::{guest_log, GuestSha2};
+use risc0_zkvm_guest::env;
+use utils::aux_input::decode_aux_input;
+use utils::journal::MerkleBatchJournal;
+use utils::merkle_batch::{leaves_digest, verify_batch, MerkleBatch};
+
+risc0_zkvm_guest::entry!(main);
+
+// Verifies a batch of Merkle inclusion proofs against a single root, and commits the root and a
+// digest of the included leaves. Every node on every path is hashed with the accelerated SHA-256
+// of the zkVM, so the guest doubles as a benchmark of the guest hashing throughput.
+
+pub fn main() {
+    let aux_input = match decode_aux_input(env::read_aux_input()) {
+        Ok(aux_input) => aux_input,
+        Err(e) => {
+            let error = ErrorChain::new(ErrorKind::AuxInput, e);
+            env::commit(&MerkleBatchJournal::with_error([0; 32], [0; 32], 0, error));
+            return;
+        }
+    };
+    // the batch is verified in place, without deserializing the paths
+    let batch = unsafe { rkyv::archived_root::<MerkleBatch>(aux_input.as_bytes()) };
+    let indices = batch.indices.as_slice();
+    let leaves = batch.leaves.as_slice();
+    let num_leaves = leaves.len() as u64;
+
+    let journal = match verify_batch::<GuestSha2>(
+        &batch.root,
+        batch.depth,
+        indices,
+        leaves,
+        batch.siblings.as_slice(),
+    ) {
+        Ok(digest) => MerkleBatchJournal::new(batch.root, digest, num_leaves, true),
+        Err(e) => {
+            let digest = leaves_digest::<GuestSha2>(indices, leaves);
+            MerkleBatchJournal::with_error(batch.root, digest, num_leaves, e.into())
+        }
+    };
+    guest_log!(
+        "verified inclusion of {} leaves: {}",
+        journal.num_leaves,
+        journal.result
+    );
+    env::commit(&journal);
+}
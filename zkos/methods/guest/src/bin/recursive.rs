@@ -4,263 +4,392 @@ extern crate alloc;
 
 use alloc::format;
 use alloc::vec::Vec;
-use anyhow::{anyhow, Result};
-use miden_air::{FieldElement, ProcessorAir};
-use risc0_zkvm_guest::{env, sha};
-use rkyv::Deserialize;
-use utils::inputs::{MidenAirInput, MidenRiscInput};
-use winter_air::{Air, AuxTraceRandElements, ConstraintCompositionCoefficients};
-use winter_crypto::ElementHasher;
-use winter_crypto::{
-    hashers::{Sha2_256, ShaHasherT},
-    RandomCoin,
+use anyhow::{anyhow, Context, Result};
+use errors::{ErrorChain, ErrorKind};
+use methods_guest::verify::verify_in_guest;
+use methods_guest::{
+    check_proof_bounds, decode_aux_input, failed_journal, guest_log, run_verifier, GuestSha2,
 };
-use winter_math::fields::f64::{BaseElement, INV_NONDET};
+use miden_air::{FieldElement, ProcessorAir, StarkField};
+use risc0_zkvm_guest::env;
+use rkyv::{Archive, Archived, Deserialize, Infallible};
+use utils::context::VerifierContext;
+use utils::hasher::GuestHasher;
+use utils::inputs::{ArchivedMidenRiscInput, MidenAirInput, MidenRiscInput, TraceSegmentInfo};
+use utils::journal::{options_digest, statement_digest, trace_info_bytes, Journal};
+use utils::link::{LinkedStatement, ProofLink, RecursiveMode};
+use utils::policy::ProofPolicy;
+use utils::trace_bounds::TraceLengthBounds;
+use winter_air::{FieldExtension, HashFunction};
+use winter_crypto::hashers::{Blake3_256, Keccak256, Rp64_256, Sha2_256};
+use winter_math::fields::f64::{BaseElement, INV_NONDET, INV_NONDET_QUAD};
+use winter_math::fields::{CubeExtension, QuadExtension};
 use winter_utils::Serializable;
-use winter_verifier::{evaluate_constraints, DeepComposer, FriVerifier, VerifierChannel};
+use winter_verifier::VerifierChannel;
 
 risc0_zkvm_guest::entry!(main);
 
-pub struct GuestSha2;
+type B = BaseElement;
+type Quad = QuadExtension<B>;
+type Cube = CubeExtension<B>;
+type H256 = Sha2_256<B, GuestSha2>;
+type HBlake3 = Blake3_256<B>;
+type HKeccak = Keccak256<B>;
+type VerfierAIR = ProcessorAir;
 
-impl ShaHasherT for GuestSha2 {
-    fn digest(data: &[u8]) -> [u8; 32] {
-        sha::digest_u8_slice(data).get_u8()
+// defines TRACE_LENGTH_BOUNDS, the trace lengths of the accepted proofs, set by the build script
+include!(concat!(env!("OUT_DIR"), "/trace_bounds.rs"));
+
+/// Verifies the Miden proof (or the two linked Miden proofs) and returns the journal describing the
+/// outcome; verification failures are logged, and reported via the result and the error chain of
+/// the journal.
+///
+/// The proofs are verified under the proof policy read from the header of the inputs, and the
+/// journal is bound to the digest of the policy. The mode in which the inputs are verified is read
+/// right after the policy header.
+pub fn run_main_logic() -> Journal {
+    run_verifier(|policy| {
+        let mode: RecursiveMode = env::read();
+        match mode {
+            RecursiveMode::Single => verify_inputs(policy),
+            RecursiveMode::Linked(link) => verify_linked_inputs(policy, link),
+        }
+    })
+}
+
+/// Verifies the Miden proof under the provided policy and returns the journal describing the
+/// outcome.
+fn verify_inputs(policy: &ProofPolicy) -> Journal {
+    // Deserialize public inputs; the statement is unknown if they cannot be decoded, and thus,
+    // the digests of the journal are zero
+    let aux_input = match decode_aux_input(env::read_aux_input()) {
+        Ok(aux_input) => aux_input,
+        Err(error) => return failed_journal([0; 32], [0; 32], error),
+    };
+    let air_input: MidenAirInput = env::read();
+
+    // the layout of the packaged proof depends on the digests of its hash function, and on the
+    // field of its out-of-domain evaluations, and thus, the proof is accessed only once both are
+    // known; Miden-native proofs are hashed with RPO, and proofs checked on EVM chains with
+    // Keccak-256, both of which the guest computes in software
+    let aux_input = aux_input.as_bytes();
+    match air_input.proof_options.hash_fn() {
+        HashFunction::Sha2_256 => verify_single_with::<H256>(aux_input, air_input, policy),
+        HashFunction::Blake3_256 => verify_single_with::<HBlake3>(aux_input, air_input, policy),
+        HashFunction::Keccak256 => verify_single_with::<HKeccak>(aux_input, air_input, policy),
+        HashFunction::Rp64_256 => verify_single_with::<Rp64_256>(aux_input, air_input, policy),
+        hash_fn => unsupported_hash_fn(hash_fn),
     }
 }
 
-type E = BaseElement;
-type H = Sha2_256<E, GuestSha2>;
-type C = VerifierChannel<E, H>;
-type VerfierAIR = ProcessorAir;
+/// Verifies the Miden proof packaged in the provided aux input with the specified hasher, in the
+/// same way as [verify_single].
+fn verify_single_with<H: GuestHasher<BaseField = B>>(
+    aux_input: &[u8],
+    air_input: MidenAirInput,
+    policy: &ProofPolicy,
+) -> Journal
+where
+    VerifierChannel<B, H>: Archive,
+    Archived<VerifierChannel<B, H>>: Deserialize<VerifierChannel<B, H>, Infallible>,
+    VerifierChannel<Quad, H>: Archive,
+    Archived<VerifierChannel<Quad, H>>: Deserialize<VerifierChannel<Quad, H>, Infallible>,
+    VerifierChannel<Cube, H>: Archive,
+    Archived<VerifierChannel<Cube, H>>: Deserialize<VerifierChannel<Cube, H>, Infallible>,
+{
+    match air_input.proof_options.field_extension() {
+        FieldExtension::None => verify_single::<B, H>(aux_input, air_input, policy),
+        FieldExtension::Quadratic => verify_single::<Quad, H>(aux_input, air_input, policy),
+        FieldExtension::Cubic => verify_single::<Cube, H>(aux_input, air_input, policy),
+    }
+}
+
+/// Verifies the Miden proof packaged in the provided aux input, with out-of-domain evaluations
+/// in the field `E` and commitments made with the hasher `H`, under the provided policy, and
+/// returns the journal describing the outcome.
+fn verify_single<E: FieldElement<BaseField = B>, H: GuestHasher<BaseField = B>>(
+    aux_input: &[u8],
+    air_input: MidenAirInput,
+    policy: &ProofPolicy,
+) -> Journal
+where
+    VerifierChannel<E, H>: Archive,
+    Archived<VerifierChannel<E, H>>: Deserialize<VerifierChannel<E, H>, Infallible>,
+{
+    let pub_inputs = unsafe { rkyv::archived_root::<MidenRiscInput<E, H>>(aux_input) };
+
+    // bind the journal to the verified statement and the options it is verified under
+    let statement_digest = air_input.statement_digest::<GuestSha2>(pub_inputs.context.as_slice());
+    let options_digest = options_digest::<GuestSha2>(&[air_input.proof_options.clone()]);
+
+    // proofs generated with options weaker than the policy allows, or of traces with lengths
+    // outside of the bounds of the guest, are rejected before they are verified
+    if let Err(error) = check_proof_bounds(
+        policy,
+        &TRACE_LENGTH_BOUNDS,
+        &air_input.proof_options,
+        air_input.trace_info.length(),
+        "miden proof",
+    ) {
+        return failed_journal(statement_digest, options_digest, error);
+    }
+    // the processor AIR panics when it is instantiated from a trace info it was not built for
+    if let Err(e) = air_input.check_trace_info() {
+        let error = ErrorChain::from(e).context(
+            ErrorKind::Verification,
+            "miden proof does not match the processor AIR",
+        );
+        return failed_journal(statement_digest, options_digest, error);
+    }
+
+    match verify_miden_proof(pub_inputs, air_input) {
+        Ok(()) => Journal::new(statement_digest, options_digest, true),
+        Err(e) => {
+            guest_log!("error: {}", e);
+            let error = ErrorChain::from_messages(ErrorKind::Verification, e.chain());
+            Journal::with_error(statement_digest, options_digest, error)
+        }
+    }
+}
 
-pub fn aux_trace_segments(
-    verifier_channel: &C,
-    public_coin: &mut RandomCoin<E, Sha2_256<E, GuestSha2>>,
-    air: &VerfierAIR,
-) -> Result<AuxTraceRandElements<E>> {
-    let mut aux_trace_rand_elements = AuxTraceRandElements::<E>::new();
-    for (i, commitment) in verifier_channel
-        .read_trace_commitments()
-        .iter()
-        .skip(1)
-        .enumerate()
-    {
-        let rand_elements = air
-            .get_aux_trace_segment_random_elements(i, public_coin)
-            .map_err(|_| anyhow!("Random coin error"))?;
-        aux_trace_rand_elements.add_segment_elements(rand_elements);
-        public_coin.reseed(*commitment);
+/// Verifies the two linked Miden proofs under the provided policy, checks that their statements are
+/// related by the provided link, and returns the journal describing the outcome.
+///
+/// The statement digest of the journal covers the contexts, public inputs, and trace infos of both
+/// proofs, followed by the encoding of the link.
+fn verify_linked_inputs(policy: &ProofPolicy, link: ProofLink) -> Journal {
+    let aux_input = match decode_aux_input(env::read_aux_input()) {
+        Ok(aux_input) => aux_input,
+        Err(error) => return failed_journal([0; 32], [0; 32], error),
+    };
+    let air_inputs = [env::read::<MidenAirInput>(), env::read::<MidenAirInput>()];
+
+    // both proofs are packaged in one archive, which is laid out for a single hash function and
+    // field extension
+    let hash_fn = air_inputs[0].proof_options.hash_fn();
+    if air_inputs[1].proof_options.hash_fn() != hash_fn {
+        let error = ErrorChain::new(
+            ErrorKind::Verification,
+            "linked miden proofs are generated with different hash functions",
+        );
+        return failed_journal([0; 32], [0; 32], error);
+    }
+    let extension = air_inputs[0].proof_options.field_extension();
+    if air_inputs[1].proof_options.field_extension() != extension {
+        let error = ErrorChain::new(
+            ErrorKind::Verification,
+            "linked miden proofs are generated with different field extensions",
+        );
+        return failed_journal([0; 32], [0; 32], error);
+    }
+    let aux_input = aux_input.as_bytes();
+    match hash_fn {
+        HashFunction::Sha2_256 => verify_linked_with::<H256>(aux_input, air_inputs, policy, link),
+        HashFunction::Blake3_256 => {
+            verify_linked_with::<HBlake3>(aux_input, air_inputs, policy, link)
+        }
+        HashFunction::Keccak256 => {
+            verify_linked_with::<HKeccak>(aux_input, air_inputs, policy, link)
+        }
+        HashFunction::Rp64_256 => {
+            verify_linked_with::<Rp64_256>(aux_input, air_inputs, policy, link)
+        }
+        hash_fn => unsupported_hash_fn(hash_fn),
     }
-    Ok(aux_trace_rand_elements)
 }
 
-pub fn get_constraint_coffs(
-    public_coin: &mut RandomCoin<E, Sha2_256<E, GuestSha2>>,
-    air: &VerfierAIR,
-) -> Result<ConstraintCompositionCoefficients<E>> {
-    let constraint_coeffs = air
-        .get_constraint_composition_coefficients(public_coin)
-        .map_err(|_| anyhow!("Random coin error"))?;
-    Ok(constraint_coeffs)
+/// Verifies the two linked Miden proofs packaged in the provided aux input with the specified
+/// hasher, in the same way as [verify_linked_inputs].
+fn verify_linked_with<H: GuestHasher<BaseField = B>>(
+    aux_input: &[u8],
+    air_inputs: [MidenAirInput; 2],
+    policy: &ProofPolicy,
+    link: ProofLink,
+) -> Journal
+where
+    VerifierChannel<B, H>: Archive,
+    Archived<VerifierChannel<B, H>>: Deserialize<VerifierChannel<B, H>, Infallible>,
+    VerifierChannel<Quad, H>: Archive,
+    Archived<VerifierChannel<Quad, H>>: Deserialize<VerifierChannel<Quad, H>, Infallible>,
+    VerifierChannel<Cube, H>: Archive,
+    Archived<VerifierChannel<Cube, H>>: Deserialize<VerifierChannel<Cube, H>, Infallible>,
+{
+    match air_inputs[0].proof_options.field_extension() {
+        FieldExtension::None => verify_linked::<B, H>(aux_input, air_inputs, policy, link),
+        FieldExtension::Quadratic => verify_linked::<Quad, H>(aux_input, air_inputs, policy, link),
+        FieldExtension::Cubic => verify_linked::<Cube, H>(aux_input, air_inputs, policy, link),
+    }
 }
 
-pub fn init_public_coin_seed<S: Serializable>(
-    public_coin_seed: &mut Vec<u8>,
-    result: S,
-    context: &[u8],
-) {
-    result.write_into(public_coin_seed);
-    public_coin_seed.extend(context);
+/// Returns the journal of a verification which failed because the packaged proofs are hashed
+/// with a hash function the guest does not verify proofs with; the statements are unknown, as
+/// the layout of the packaged proofs depends on the hash function, and thus, the digests are
+/// zero.
+fn unsupported_hash_fn(hash_fn: HashFunction) -> Journal {
+    let error = ErrorChain::new(
+        ErrorKind::Verification,
+        format!(
+            "miden proofs with hash function {:?} are not supported",
+            hash_fn
+        ),
+    );
+    failed_journal([0; 32], [0; 32], error)
 }
 
-pub fn run_main_logic() -> Result<()> {
-    // Deserialize public inputs
-    let aux_input: &[u8] = env::read_aux_input();
-    let pub_inputs = unsafe { rkyv::archived_root::<MidenRiscInput<E, H>>(&aux_input[..]) };
+/// Verifies the two linked Miden proofs packaged in the provided aux input, with out-of-domain
+/// evaluations in the field `E` and commitments made with the hasher `H`, in the same way as
+/// [verify_linked_inputs].
+fn verify_linked<E: FieldElement<BaseField = B>, H: GuestHasher<BaseField = B>>(
+    aux_input: &[u8],
+    air_inputs: [MidenAirInput; 2],
+    policy: &ProofPolicy,
+    link: ProofLink,
+) -> Journal
+where
+    VerifierChannel<E, H>: Archive,
+    Archived<VerifierChannel<E, H>>: Deserialize<VerifierChannel<E, H>, Infallible>,
+{
+    let pub_inputs_arr = unsafe { rkyv::archived_root::<[MidenRiscInput<E, H>; 2]>(aux_input) };
+
+    let public_inputs = [
+        air_inputs[0].public_inputs.to_bytes(),
+        air_inputs[1].public_inputs.to_bytes(),
+    ];
+    let trace_infos = [
+        trace_info_bytes(&air_inputs[0].trace_info),
+        trace_info_bytes(&air_inputs[1].trace_info),
+    ];
+    let statement_digest = statement_digest::<GuestSha2>(&[
+        pub_inputs_arr[0].context.as_slice(),
+        public_inputs[0].as_slice(),
+        trace_infos[0].as_slice(),
+        pub_inputs_arr[1].context.as_slice(),
+        public_inputs[1].as_slice(),
+        trace_infos[1].as_slice(),
+        &link.to_bytes(),
+    ]);
+    let options_digest = options_digest::<GuestSha2>(&[
+        air_inputs[0].proof_options.clone(),
+        air_inputs[1].proof_options.clone(),
+    ]);
+
+    for (i, air_input) in air_inputs.iter().enumerate() {
+        if let Err(error) = check_proof_bounds(
+            policy,
+            &TRACE_LENGTH_BOUNDS,
+            &air_input.proof_options,
+            air_input.trace_info.length(),
+            &format!("miden proof #{}", i + 1),
+        ) {
+            return failed_journal(statement_digest, options_digest, error);
+        }
+        if let Err(e) = air_input.check_trace_info() {
+            let error = ErrorChain::from(e).context(
+                ErrorKind::Verification,
+                format!("miden proof #{} does not match the processor AIR", i + 1),
+            );
+            return failed_journal(statement_digest, options_digest, error);
+        }
+    }
+
+    // the link is much cheaper to check than the proofs, and thus, it is checked first
+    let first = LinkedStatement {
+        context: pub_inputs_arr[0].context.as_slice(),
+        public_inputs: &air_inputs[0].public_inputs,
+    };
+    let second = LinkedStatement {
+        context: pub_inputs_arr[1].context.as_slice(),
+        public_inputs: &air_inputs[1].public_inputs,
+    };
+    if let Err(e) = link.check(&first, &second) {
+        let error = ErrorChain::from(e).context(
+            ErrorKind::Verification,
+            format!("miden proofs are not linked by the {} link", link),
+        );
+        return failed_journal(statement_digest, options_digest, error);
+    }
+
+    for (i, (pub_inputs, air_input)) in pub_inputs_arr.iter().zip(air_inputs).enumerate() {
+        if let Err(e) = verify_miden_proof(pub_inputs, air_input)
+            .with_context(|| format!("failed to verify miden proof #{}", i + 1))
+        {
+            guest_log!("error: {:?}", e);
+            let error = ErrorChain::from_messages(ErrorKind::Verification, e.chain());
+            return Journal::with_error(statement_digest, options_digest, error);
+        }
+    }
+    Journal::new(statement_digest, options_digest, true)
+}
 
-    let mut verifier_channel: C = pub_inputs
+/// Verifies the packaged Miden proof against the processor AIR instantiated from the provided
+/// AIR inputs; the protocol itself is run by [verify_in_guest].
+///
+/// The out-of-domain evaluations of the proof are in the field `E`, which is the base field or its
+/// quadratic or cubic extension, as selected by the field extension of the proof options; the
+/// commitments of the proof are made with the hasher `H`, as selected by its hash function.
+pub fn verify_miden_proof<E: FieldElement<BaseField = B>, H: GuestHasher<BaseField = B>>(
+    pub_inputs: &ArchivedMidenRiscInput<E, H>,
+    air_input: MidenAirInput,
+) -> Result<()>
+where
+    VerifierChannel<E, H>: Archive,
+    Archived<VerifierChannel<E, H>>: Deserialize<VerifierChannel<E, H>, Infallible>,
+{
+    let verifier_channel: VerifierChannel<E, H> = pub_inputs
         .verifier_channel
-        .deserialize(&mut rkyv::Infallible)
+        .deserialize(&mut Infallible)
         .unwrap();
 
+    // hints are archived in their internal representation, which is not checked by rkyv; an
+    // element which is not in canonical form would not compare as equal to the same element
+    // computed by the guest
     for (a, inv_a) in pub_inputs.inv_nondet.iter() {
-        let a_copy: BaseElement = a.deserialize(&mut rkyv::Infallible).unwrap();
-        let inv_a_copy: BaseElement = inv_a.deserialize(&mut rkyv::Infallible).unwrap();
+        let a_copy: B = a.deserialize(&mut Infallible).unwrap();
+        let inv_a_copy: B = inv_a.deserialize(&mut Infallible).unwrap();
+        if !a_copy.is_reduced() || !inv_a_copy.is_reduced() {
+            return Err(anyhow!("inverse hint is not in canonical form"));
+        }
         INV_NONDET.lock().insert(a_copy, inv_a_copy);
     }
+    // inverses of extension elements are hinted only for proofs over the quadratic extension; the
+    // cubic extension inverts its elements through the inverses of their norms in the base field
+    for (a, inv_a) in pub_inputs.inv_nondet_quad.iter() {
+        let a_copy: [B; 2] = a.deserialize(&mut Infallible).unwrap();
+        let inv_a_copy: [B; 2] = inv_a.deserialize(&mut Infallible).unwrap();
+        if !a_copy.iter().chain(inv_a_copy.iter()).all(B::is_reduced) {
+            return Err(anyhow!("inverse hint is not in canonical form"));
+        }
+        INV_NONDET_QUAD.lock().insert(a_copy, inv_a_copy);
+    }
 
-    // Extract context
-    let context = pub_inputs.context.as_slice();
+    // Extract the proof context; the domain tag of the verifier context is bound to the journal
+    // by the statement digest, but the prover seeded the coin with the proof context only
+    let context =
+        VerifierContext::from_bytes(pub_inputs.context.as_slice()).map_err(|e| anyhow!("{}", e))?;
 
-    // Extract Fibonacci AIR
-    let air_input: MidenAirInput = env::read();
+    // Extract Miden AIR
     let air = VerfierAIR::new(
         air_input.trace_info,
         air_input.public_inputs.clone(),
         air_input.proof_options,
     );
 
-    // build a seed for the public coin; the initial seed is the hash of public inputs and proof
-    // context, but as the protocol progresses, the coin will be reseeded with the info received
-    // from the prover
-    let mut public_coin_seed = Vec::new();
-    init_public_coin_seed(&mut public_coin_seed, air_input.public_inputs, context);
-
-    let mut public_coin: RandomCoin<E, Sha2_256<E, GuestSha2>> = RandomCoin::new(&public_coin_seed);
-
-    // reseed the coin with the commitment to the main trace segment
-    public_coin.reseed(verifier_channel.read_trace_commitments()[0]);
-
-    // process auxiliary trace segments (if any), to build a set of random elements for each segment
-    let aux_trace_rand_elements = aux_trace_segments(&verifier_channel, &mut public_coin, &air)
-        .expect("aux trace segments failed");
-
-    // build random coefficients for the composition polynomial
-    let constraint_coeffs =
-        get_constraint_coffs(&mut public_coin, &air).expect("constraint_coeffs_error");
-    // env::log(&format!("constraint coeffs: {:?}", &constraint_coeffs));
-
-    // 2 ----- constraint commitment --------------------------------------------------------------
-    // let constraint_commitment = ByteDigest::new(pub_inputs.constraint_commitment);
-    // env::log(&format!("constraint commitment"));
-    public_coin.reseed(verifier_channel.read_constraint_commitment());
-    let z = public_coin
-        .draw::<E>()
-        .map_err(|_| anyhow!("Random coin error"))
-        .expect("constraint_commitment");
-
-    // 3 ----- OOD consistency check --------------------------------------------------------------
-    // make sure that evaluations obtained by evaluating constraints over the out-of-domain frame
-    // are consistent with the evaluations of composition polynomial columns sent by the prover
-
-    // read the out-of-domain trace frames (the main trace frame and auxiliary trace frame, if
-    // provided) sent by the prover and evaluate constraints over them; also, reseed the public
-    // coin with the OOD frames received from the prover.
-
-    // env::log(&format!("ood_frame"));
-    let (ood_main_trace_frame, ood_aux_trace_frame) = verifier_channel.read_ood_trace_frame();
-    let ood_constraint_evaluation_1 = evaluate_constraints(
-        &air,
-        constraint_coeffs,
-        &ood_main_trace_frame,
-        &ood_aux_trace_frame,
-        aux_trace_rand_elements,
-        z,
-    );
-
-    // env::log(&format!("reseed ood_frame"));
-    if let Some(ref aux_trace_frame) = ood_aux_trace_frame {
-        // when the trace contains auxiliary segments, append auxiliary trace elements at the
-        // end of main trace elements for both current and next rows in the frame. this is
-        // needed to be consistent with how the prover writes OOD frame into the channel.
-
-        let mut current = ood_main_trace_frame.current().to_vec();
-        current.extend_from_slice(aux_trace_frame.current());
-        public_coin.reseed(H::hash_elements(&current));
-
-        let mut next = ood_main_trace_frame.next().to_vec();
-        next.extend_from_slice(aux_trace_frame.next());
-        public_coin.reseed(H::hash_elements(&next));
-    } else {
-        public_coin.reseed(H::hash_elements(ood_main_trace_frame.current()));
-        public_coin.reseed(H::hash_elements(ood_main_trace_frame.next()));
-    }
-
-    // // read evaluations of composition polynomial columns sent by the prover, and reduce them into
-    // // a single value by computing sum(z^i * value_i), where value_i is the evaluation of the ith
-    // // column polynomial at z^m, where m is the total number of column polynomials; also, reseed
-    // // the public coin with the OOD constraint evaluations received from the prover.
-    // env::log(&format!("ood_constraint_evaluation_2"));
-    let ood_constraint_evaluations = verifier_channel.read_ood_constraint_evaluations();
-    let ood_constraint_evaluation_2 = ood_constraint_evaluations
-        .iter()
-        .enumerate()
-        .fold(E::ZERO, |result, (i, &value)| {
-            result + z.exp((i as u32).into()) * value
-        });
-    public_coin.reseed(H::hash_elements(&ood_constraint_evaluations));
-
-    // finally, make sure the values are the same
-    if ood_constraint_evaluation_1 != ood_constraint_evaluation_2 {
-        panic!("Inconsistent OOD constraint evaluations");
-    }
-
-    // // 4 ----- FRI commitments --------------------------------------------------------------------
-    // // draw coefficients for computing DEEP composition polynomial from the public coin; in the
-    // // interactive version of the protocol, the verifier sends these coefficients to the prover
-    // // and the prover uses them to compute the DEEP composition polynomial. the prover, then
-    // // applies FRI protocol to the evaluations of the DEEP composition polynomial.
-    let deep_coefficients = air
-        .get_deep_composition_coefficients::<E, H>(&mut public_coin)
-        .map_err(|msg| anyhow!(msg))
+    let trace_segments: Vec<TraceSegmentInfo> = pub_inputs
+        .trace_segments
+        .deserialize(&mut Infallible)
         .unwrap();
-
-    // instantiates a FRI verifier with the FRI layer commitments read from the channel. From the
-    // verifier's perspective, this is equivalent to executing the commit phase of the FRI protocol.
-    // The verifier uses these commitments to update the public coin and draw random points alpha
-    // from them; in the interactive version of the protocol, the verifier sends these alphas to
-    // the prover, and the prover uses them to compute and commit to the subsequent FRI layers.
-    let fri_verifier: FriVerifier<E, E, C, H> = FriVerifier::new(
-        &mut verifier_channel,
-        &mut public_coin,
-        air.options().to_fri_options(),
-        air.trace_poly_degree(),
+    let coin_seed = context.coin_seed(&air_input.public_inputs.to_bytes());
+    verify_in_guest(
+        &air,
+        verifier_channel,
+        &trace_segments,
+        None,
+        &coin_seed,
+        &(),
     )
-    .expect("fri verifier init failed");
-
-    // 5 ----- trace and constraint queries -------------------------------------------------------
-    // read proof-of-work nonce sent by the prover and update the public coin with it
-    public_coin.reseed_with_int(verifier_channel.read_pow_nonce());
-
-    // make sure the proof-of-work specified by the grinding factor is satisfied
-    if public_coin.leading_zeros() < air.options().grinding_factor() {
-        panic!("QuerySeedProofOfWorkVerificationFailed");
-    }
-
-    // draw pseudo-random query positions for the LDE domain from the public coin; in the
-    // interactive version of the protocol, the verifier sends these query positions to the prover,
-    // and the prover responds with decommitments against these positions for trace and constraint
-    // composition polynomial evaluations.
-    let query_positions = public_coin
-        .draw_integers(air.options().num_queries(), air.lde_domain_size())
-        .map_err(|_| anyhow!("random coin error"))?;
-    // read evaluations of trace and constraint composition polynomials at the queried positions;
-    // this also checks that the read values are valid against trace and constraint commitments
-    let (queried_main_trace_states, queried_aux_trace_states) = verifier_channel
-        .read_queried_trace_states(&query_positions)
-        .map_err(|e| anyhow!("read_queried_trace_states, e = {}", e))?;
-    let queried_constraint_evaluations = verifier_channel
-        .read_constraint_evaluations(&query_positions)
-        .map_err(|e| anyhow!("read_constraint_evaluations, e = {}", e))?;
-
-    // 6 ----- DEEP composition -------------------------------------------------------------------
-    // compute evaluations of the DEEP composition polynomial at the queried positions
-    let composer = DeepComposer::new(&air, &query_positions, z, deep_coefficients);
-    let t_composition = composer.compose_trace_columns(
-        queried_main_trace_states,
-        queried_aux_trace_states,
-        ood_main_trace_frame,
-        ood_aux_trace_frame,
-    );
-    let c_composition = composer
-        .compose_constraint_evaluations(queried_constraint_evaluations, ood_constraint_evaluations);
-    let deep_evaluations = composer.combine_compositions(t_composition, c_composition);
-
-    // 7 ----- Verify low-degree proof -------------------------------------------------------------
-    // make sure that evaluations of the DEEP composition polynomial we computed in the previous
-    // step are in fact evaluations of a polynomial of degree equal to trace polynomial degree
-    fri_verifier
-        .verify(&mut verifier_channel, &deep_evaluations, &query_positions)
-        .map_err(|e| anyhow!("fri verifier failed, e = {}", e))?;
-    Ok(())
 }
 
 pub fn main() {
-    match run_main_logic() {
-        Ok(_) => {}
-        Err(e) => {
-            env::log(&format!("error: {}", e));
-        }
-    }
+    let journal = run_main_logic();
+    env::commit(&journal.with_cycle_count(env::get_cycle_count() as u64));
 }
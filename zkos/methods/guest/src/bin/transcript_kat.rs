@@ -0,0 +1,204 @@
+#![no_main]
+#![no_std]
+extern crate alloc;
+
+use alloc::format;
+use alloc::vec::Vec;
+use anyhow::{anyhow, Context, Result};
+use errors::{ErrorChain, ErrorKind};
+use methods_guest::{guest_log, GuestSha2};
+use risc0_zkvm_guest::{env, mul};
+use rkyv::Deserialize;
+use utils::aux_data::seed_pub_inputs;
+use utils::aux_input::decode_aux_input;
+use utils::context::VerifierContext;
+use utils::fib::fib_air::FibAir as FA;
+use utils::inputs::{
+    check_trace_segments, ArchivedFibRiscInput, FibAirInput, FibRiscInput, TraceSegmentInfo,
+};
+use utils::journal::{kats_digest, statement_digest, trace_info_bytes, TranscriptKatJournal};
+use utils::kat::TranscriptKat;
+use utils::transcript::{replay_transcript, TranscriptState};
+use winter_air::Air;
+use winter_crypto::hashers::Sha2_256;
+use winter_math::fields::f64_risc0::{AccelBaseElementRisc0, NativeMontMul};
+use winter_math::fields::QuadExtension;
+use winter_math::StarkField;
+use winter_utils::Serializable;
+use winter_verifier::VerifierChannel;
+
+risc0_zkvm_guest::entry!(main);
+
+// Replays the Fiat–Shamir transcripts of the packaged Fibonacci proofs, and checks the values
+// drawn from the public coin against the known answers passed by the host after the AIR inputs.
+// The guest commits the digest of the known answers it checked, so that the host can tell that
+// the transcripts computed in the guest are pinned to the same answers as the transcripts it
+// computes itself.
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Risc0NativeMul {}
+impl NativeMontMul for Risc0NativeMul {
+    fn native_mul_ext(a: [u64; 2], b: [u64; 2]) -> [u64; 2] {
+        mul::mul_goldilocks(&a, &b).get_u64()
+    }
+}
+
+type B = AccelBaseElementRisc0<Risc0NativeMul>;
+type FibAir = FA<Risc0NativeMul>;
+type E = QuadExtension<B>;
+type H = Sha2_256<B, GuestSha2>;
+type C = VerifierChannel<E, H>;
+
+// VERIFICATION KEY
+// ================================================================================================
+
+// defines FIB_VK, the verification key of the Fibonacci AIR computed by the build script
+include!(concat!(env!("OUT_DIR"), "/fib_vk.rs"));
+
+// TRANSCRIPT REPLAY
+// ================================================================================================
+
+/// Replays the transcript of the provided packaged proof and returns the values drawn from the
+/// public coin.
+pub fn replay_fib_transcript(
+    pub_inputs: &ArchivedFibRiscInput<E, H>,
+    air_input: FibAirInput,
+    result: B,
+) -> Result<TranscriptState> {
+    // elements are archived in their internal representation, which is not checked by rkyv
+    if !result.is_reduced() {
+        return Err(anyhow!("fib result is not in canonical form"));
+    }
+    let air = FibAir::from_verification_key(
+        &FIB_VK,
+        air_input.trace_info,
+        result,
+        air_input.proof_options,
+    )
+    .map_err(|e| {
+        anyhow!(
+            "trace info is inconsistent with the verification key: {}",
+            e
+        )
+    })?;
+
+    let mut verifier_channel: C = pub_inputs
+        .verifier_channel
+        .deserialize(&mut rkyv::Infallible)
+        .unwrap();
+    verifier_channel
+        .check_canonical_elements()
+        .map_err(|e| anyhow!("{}", e))?;
+
+    let trace_segments: Vec<TraceSegmentInfo> = pub_inputs
+        .trace_segments
+        .deserialize(&mut rkyv::Infallible)
+        .unwrap();
+    check_trace_segments(
+        &trace_segments,
+        air.trace_layout(),
+        verifier_channel.read_trace_commitments().len(),
+    )
+    .map_err(|e| anyhow!("{}", e))?;
+
+    // the coin is seeded in the same way as by the fib_verify guest
+    let context =
+        VerifierContext::from_bytes(pub_inputs.context.as_slice()).map_err(|e| anyhow!("{}", e))?;
+    let aux_data_commitment = pub_inputs.aux_data_commitment.as_ref();
+    let public_coin_seed =
+        context.coin_seed(&seed_pub_inputs(&result.to_bytes(), aux_data_commitment));
+
+    replay_transcript(&air, &mut verifier_channel, &public_coin_seed)
+        .map_err(|e| anyhow!("failed to replay transcript: {}", e))
+}
+
+/// Checks the replayed transcripts against the provided known answers, in the order of the
+/// proofs.
+fn check_kats(
+    pub_inputs: &[ArchivedFibRiscInput<E, H>],
+    air_inputs: Vec<FibAirInput>,
+    results: &[B],
+    kats: &[TranscriptKat],
+) -> Result<()> {
+    if kats.len() != pub_inputs.len() {
+        return Err(anyhow!(
+            "expected known answers for {} transcripts, but received {}",
+            pub_inputs.len(),
+            kats.len()
+        ));
+    }
+    for (i, (((pub_inputs, air_input), &result), kat)) in pub_inputs
+        .iter()
+        .zip(air_inputs)
+        .zip(results)
+        .zip(kats)
+        .enumerate()
+    {
+        let state = replay_fib_transcript(pub_inputs, air_input, result)
+            .with_context(|| format!("failed to replay transcript of proof #{}", i + 1))?;
+        kat.check(&state)
+            .map_err(|e| anyhow!("proof #{}: {}", i + 1, e))?;
+    }
+    Ok(())
+}
+
+pub fn main() {
+    let aux_input = decode_aux_input(env::read_aux_input());
+    // the known answers are read after the AIR inputs of all proofs
+    let air_inputs: Vec<FibAirInput> = (0..2).map(|_| env::read()).collect();
+    let kats: Vec<TranscriptKat> = env::read();
+    let kats_digest = kats_digest::<GuestSha2>(&kats);
+
+    // the statements are unknown if the aux input cannot be decoded, and thus, the statement
+    // digest of the journal is zero
+    let aux_input = match aux_input {
+        Ok(aux_input) => aux_input,
+        Err(e) => {
+            let error =
+                ErrorChain::from(e).context(ErrorKind::AuxInput, "failed to decode aux input");
+            guest_log!("error: {}", error);
+            env::commit(&TranscriptKatJournal::with_error(
+                [0; 32],
+                kats_digest,
+                error,
+            ));
+            return;
+        }
+    };
+    let pub_inputs_arr =
+        unsafe { rkyv::archived_root::<[FibRiscInput<E, H>; 2]>(aux_input.as_bytes()) };
+
+    let mut parts = Vec::new();
+    let mut results = Vec::new();
+    for (pub_inputs, air_input) in pub_inputs_arr.iter().zip(&air_inputs) {
+        let result: B = pub_inputs
+            .result
+            .deserialize(&mut rkyv::Infallible)
+            .unwrap();
+
+        parts.push(pub_inputs.context.as_slice().to_vec());
+        parts.push(seed_pub_inputs(
+            &result.to_bytes(),
+            pub_inputs.aux_data_commitment.as_ref(),
+        ));
+        parts.push(trace_info_bytes(&air_input.trace_info));
+        results.push(result);
+    }
+
+    let parts: Vec<&[u8]> = parts.iter().map(|part| part.as_slice()).collect();
+    let statement_digest = statement_digest::<GuestSha2>(&parts);
+    let journal = match check_kats(pub_inputs_arr, air_inputs, &results, &kats) {
+        Ok(()) => TranscriptKatJournal::new(statement_digest, kats_digest, true),
+        Err(e) => {
+            guest_log!("error: {:?}", e);
+            let error = ErrorChain::from_messages(ErrorKind::Verification, e.chain());
+            TranscriptKatJournal::with_error(statement_digest, kats_digest, error)
+        }
+    };
+    guest_log!(
+        "checked {} transcripts against known answers: {}",
+        kats.len(),
+        journal.result
+    );
+    env::commit(&journal);
+}
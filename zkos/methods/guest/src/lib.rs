@@ -0,0 +1,137 @@
+//! Helpers shared by the guest binaries.
+#![no_std]
+extern crate alloc;
+
+use alloc::format;
+use alloc::vec::Vec;
+use errors::{ErrorChain, ErrorKind};
+use risc0_zkvm_guest::{env, sha};
+use utils::aux_input::AuxInput;
+use utils::journal::Journal;
+use utils::policy::{ProofPolicy, POLICY_LEN};
+use utils::trace_bounds::TraceLengthBounds;
+use winter_air::ProofOptions;
+use winter_crypto::hashers::ShaHasherT;
+
+pub mod verify;
+
+// LOGGING
+// ================================================================================================
+
+/// Indicates whether the guests are built with logging enabled; this is the case only when the
+/// guests are built with the `log` feature, which is enabled by setting ZKOS_GUEST_LOG.
+pub const LOG_ENABLED: bool = cfg!(feature = "log");
+
+/// Logs the message formatted from the provided arguments, in the same way as `format!`.
+///
+/// Unless the guests are built with the `log` feature, the message is neither formatted nor
+/// logged: the arguments are still type-checked, but the logging code is guarded by a constant
+/// condition and thus removed from the guest, together with the formatting machinery it pulls in.
+#[macro_export]
+macro_rules! guest_log {
+    ($($arg:tt)*) => {
+        if $crate::LOG_ENABLED {
+            $crate::__private::env::log(&$crate::__private::format!($($arg)*));
+        }
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::format;
+    pub use risc0_zkvm_guest::env;
+}
+
+// GUEST HASHER
+// ================================================================================================
+
+/// SHA-256 computed with the accelerator of the zkVM.
+pub struct GuestSha2;
+
+/// The accelerator digests a contiguous input, and thus, the default `digest_parts()` and
+/// `digest_elements()`, which merge their inputs into a single buffer first, are kept.
+impl ShaHasherT for GuestSha2 {
+    fn digest(data: &[u8]) -> [u8; 32] {
+        sha::digest_u8_slice(data).get_u8()
+    }
+
+    /// Digests the consecutive chunks of the provided data into a buffer allocated up front.
+    fn digest_batch(data: &[u8], chunk_len: usize) -> Vec<[u8; 32]> {
+        let mut digests = Vec::with_capacity(data.len() / chunk_len);
+        for chunk in data.chunks(chunk_len) {
+            digests.push(sha::digest_u8_slice(chunk).get_u8());
+        }
+        digests
+    }
+}
+
+// VERIFIER GUESTS
+// ================================================================================================
+
+/// Runs the verifier guest whose verification is performed by `verify`, and returns the journal
+/// describing the outcome.
+///
+/// The proof policy is read from the header of the inputs, before any other input, and passed to
+/// `verify`; the journal returned by `verify` is bound to the digest of the policy. A policy
+/// which cannot be decoded is reported in a journal bound to no statement.
+pub fn run_verifier(verify: impl FnOnce(&ProofPolicy) -> Journal) -> Journal {
+    let policy_header: [u8; POLICY_LEN] = env::read();
+    let policy = match ProofPolicy::from_bytes(&policy_header) {
+        Ok(policy) => policy,
+        Err(e) => {
+            let error =
+                ErrorChain::from(e).context(ErrorKind::Policy, "failed to decode proof policy");
+            return failed_journal([0; 32], [0; 32], error);
+        }
+    };
+    verify(&policy).with_policy_digest(policy.digest::<GuestSha2>())
+}
+
+/// Decodes the provided aux input of a verifier guest; the host may have compressed it to reduce
+/// the input size.
+///
+/// # Errors
+/// Returns an error if the aux input cannot be decoded (see
+/// [decode_aux_input()](utils::aux_input::decode_aux_input)).
+pub fn decode_aux_input(input: &[u8]) -> Result<AuxInput<'_>, ErrorChain> {
+    utils::aux_input::decode_aux_input(input)
+        .map_err(|e| ErrorChain::from(e).context(ErrorKind::AuxInput, "failed to decode aux input"))
+}
+
+/// Checks the options and the trace length of the proof with the specified name against the
+/// provided policy and trace length bounds.
+///
+/// # Errors
+/// Returns an error if the proof options violate the policy, or if the trace length is out of
+/// the bounds.
+pub fn check_proof_bounds(
+    policy: &ProofPolicy,
+    trace_bounds: &TraceLengthBounds,
+    proof_options: &ProofOptions,
+    trace_length: usize,
+    proof_name: &str,
+) -> Result<(), ErrorChain> {
+    policy.check(proof_options).map_err(|e| {
+        ErrorChain::from(e).context(
+            ErrorKind::Policy,
+            format!("{} violates the proof policy", proof_name),
+        )
+    })?;
+    trace_bounds.check(trace_length).map_err(|e| {
+        ErrorChain::from(e).context(
+            ErrorKind::Policy,
+            format!("{} has an unsupported trace length", proof_name),
+        )
+    })
+}
+
+/// Logs the provided error, and returns the journal of a verification which failed with it; the
+/// digests are zero if the failure occurred before the statement was known.
+pub fn failed_journal(
+    statement_digest: [u8; 32],
+    options_digest: [u8; 32],
+    error: ErrorChain,
+) -> Journal {
+    guest_log!("error: {}", error);
+    Journal::with_error(statement_digest, options_digest, error)
+}
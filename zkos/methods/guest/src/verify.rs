@@ -0,0 +1,219 @@
+//! STARK verification flow shared by the verifier guests.
+//!
+//! The guests differ only in the AIR of the proofs they verify and in how the public inputs of
+//! the AIR are read from the packaged inputs. [verify_in_guest] runs the rest of the protocol for
+//! any AIR: it checks the packaged channel, replays the Fiat–Shamir transcript from the seed of
+//! the public coin (see [replay]), and performs the OOD consistency, proof-of-work, and
+//! low-degree checks. The outcomes of the OOD consistency and proof-of-work checks are recorded
+//! without branching on them (see [VerificationChecks]), so that the time it takes to reject a
+//! proof does not tell which of these two checks failed.
+//!
+//! The remaining checks are not hidden: an opening which does not authenticate against its
+//! commitment is rejected as soon as it is read, and the FRI verifier returns at the first layer
+//! which fails. The outcome of the FRI verification is recorded only so that a proof failing it
+//! is rejected at the same decision point as the others.
+
+use alloc::vec::Vec;
+use anyhow::{anyhow, Result};
+use utils::ct::{ct_ge_u32, VerificationChecks};
+use utils::hasher::GuestHasher;
+use utils::inputs::{check_trace_segments, opening_indexes, TraceSegmentInfo};
+use utils::transcript::replay;
+use winter_air::Air;
+use winter_math::FieldElement;
+use winter_verifier::{evaluate_constraints, DeepComposer, VerifierChannel};
+
+// PROFILER
+// ================================================================================================
+
+/// Receives the phases of [verify_in_guest], so that a guest can attribute the cycles spent on
+/// every phase; the phases are named as the frames of the cycle profiler.
+pub trait VerifyProfiler {
+    /// Called when the phase with the specified name starts.
+    fn enter(&self, name: &'static str);
+
+    /// Called when the most recently started phase ends.
+    fn exit(&self);
+}
+
+/// Profiler of guests which do not profile the verification.
+impl VerifyProfiler for () {
+    fn enter(&self, _name: &'static str) {}
+
+    fn exit(&self) {}
+}
+
+// VERIFICATION
+// ================================================================================================
+
+/// Verifies the proof packaged in the provided channel against the provided AIR.
+///
+/// The `coin_seed` is the seed the prover initialized its public coin with, i.e., the serialized
+/// public inputs of the AIR followed by the proof context (see
+/// [VerifierContext::coin_seed()](utils::context::VerifierContext::coin_seed)); the
+/// `trace_segments` are the segments the trace commitments were packaged for. Deserializing the
+/// public inputs, building the AIR, and installing the inverse hints of the field are left to
+/// the guest, since they depend on the AIR.
+///
+/// If the packaged inputs carry the positions of the trace and constraint openings, these are
+/// passed as `opened_positions`, and every drawn query position is looked up among them (see
+/// [opening_indexes]); otherwise, the openings are taken to be in the order of the drawn
+/// positions. Either way, the positions are drawn from the public coin, and never taken from the
+/// packaged inputs.
+///
+/// # Errors
+/// Returns an error if:
+/// * The channel holds elements which are not in canonical form.
+/// * The trace commitments were not packaged for the trace layout of the AIR.
+/// * Random values cannot be drawn from the public coin, or random elements of an auxiliary
+///   segment repeat earlier ones.
+/// * The opened positions are not the drawn query positions.
+/// * Queried values do not match the commitments of the proof.
+/// * The proof fails the OOD consistency, proof-of-work, or low-degree checks.
+pub fn verify_in_guest<A, E, H>(
+    air: &A,
+    mut verifier_channel: VerifierChannel<E, H>,
+    trace_segments: &[TraceSegmentInfo],
+    opened_positions: Option<&[u64]>,
+    coin_seed: &[u8],
+    profiler: &impl VerifyProfiler,
+) -> Result<()>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: GuestHasher<BaseField = A::BaseField>,
+{
+    // elements are archived in their internal representation, which is not checked by rkyv; an
+    // element which is not in canonical form would not compare as equal to the same element
+    // computed by the guest
+    verifier_channel
+        .check_canonical_elements()
+        .map_err(|e| anyhow!("{}", e))?;
+
+    // make sure the trace commitments were packaged for the trace layout of the AIR, so that the
+    // random elements drawn from the public coin are associated with the right segments
+    check_trace_segments(
+        trace_segments,
+        air.trace_layout(),
+        verifier_channel.read_trace_commitments().len(),
+    )
+    .map_err(|e| anyhow!("{}", e))?;
+
+    // 1 ----- transcript replay ------------------------------------------------------------------
+    // the initial seed is the hash of public inputs and proof context; the coin is then reseeded
+    // with the commitments, the OOD frame and evaluations, the FRI layer commitments, and the
+    // proof-of-work nonce read from the channel, in the same way as by the phase verifier and the
+    // host, and the query positions are drawn from the final seed
+    profiler.enter("transcript");
+    let transcript = replay(air, &mut verifier_channel, coin_seed).map_err(|e| anyhow!("{}", e))?;
+    let z = transcript.z;
+    profiler.exit();
+
+    // 2 ----- OOD consistency check --------------------------------------------------------------
+    // make sure that evaluations obtained by evaluating constraints over the out-of-domain frame
+    // are consistent with the evaluations of composition polynomial columns sent by the prover
+    profiler.enter("ood_check");
+    let ood_constraint_evaluation_1 = evaluate_constraints(
+        air,
+        transcript.constraint_coeffs,
+        &transcript.ood_main_trace_frame,
+        &transcript.ood_aux_trace_frame,
+        transcript.aux_trace_rand_elements,
+        z,
+    );
+
+    // reduce the evaluations of composition polynomial columns sent by the prover into a single
+    // value by computing sum(z^i * value_i), where value_i is the evaluation of the ith column
+    // polynomial at z^m, where m is the total number of column polynomials
+    let ood_constraint_evaluation_2 = transcript
+        .ood_constraint_evaluations
+        .iter()
+        .enumerate()
+        .fold(E::ZERO, |result, (i, &value)| {
+            result + z.exp((i as u32).into()) * value
+        });
+
+    // finally, make sure the values are the same; the outcome is recorded without branching on
+    // it, and verification carries on regardless
+    let mut checks = VerificationChecks::new();
+    checks.record_eq_elements(ood_constraint_evaluation_1, ood_constraint_evaluation_2);
+    profiler.exit();
+
+    // 3 ----- trace and constraint queries -------------------------------------------------------
+    profiler.enter("queries");
+    // make sure the proof-of-work specified by the grinding factor is satisfied
+    checks.record(ct_ge_u32(
+        transcript.pow_leading_zeros,
+        air.options().grinding_factor(),
+    ));
+
+    // the query positions were drawn from the public coin by the replay; in the interactive
+    // version of the protocol, the verifier sends these query positions to the prover, and the
+    // prover responds with decommitments against these positions for trace and constraint
+    // composition polynomial evaluations.
+    let query_positions = transcript.query_positions;
+    // index into the openings by position; the openings are read at the positions they were
+    // packaged at, which are exactly the drawn positions, possibly in a different order
+    let (opened_positions, opening_order) = match opened_positions {
+        Some(opened) => {
+            let indexes =
+                opening_indexes(&query_positions, opened).map_err(|e| anyhow!("{}", e))?;
+            let positions: Vec<usize> = opened.iter().map(|&position| position as usize).collect();
+            (positions, Some(indexes))
+        }
+        None => (query_positions.clone(), None),
+    };
+    // read evaluations of trace and constraint composition polynomials at the queried positions;
+    // this also checks that the read values are valid against trace and constraint commitments
+    let (queried_main_trace_states, queried_aux_trace_states) = verifier_channel
+        .read_queried_trace_states(air, &opened_positions)
+        .map_err(|e| anyhow!("read_queried_trace_states, e = {}", e))?;
+    let queried_constraint_evaluations = verifier_channel
+        .read_constraint_evaluations(air, &opened_positions)
+        .map_err(|e| anyhow!("read_constraint_evaluations, e = {}", e))?;
+    profiler.exit();
+
+    // 4 ----- DEEP composition -------------------------------------------------------------------
+    // compute evaluations of the DEEP composition polynomial at the queried positions
+    profiler.enter("deep_composition");
+    let composer = DeepComposer::new(air, &opened_positions, z, transcript.deep_coefficients);
+    let t_composition = composer.compose_trace_columns(
+        queried_main_trace_states,
+        queried_aux_trace_states,
+        transcript.ood_main_trace_frame,
+        transcript.ood_aux_trace_frame,
+    );
+    let c_composition = composer.compose_constraint_evaluations(
+        queried_constraint_evaluations,
+        transcript.ood_constraint_evaluations,
+    );
+    let deep_evaluations = composer.combine_compositions(t_composition, c_composition);
+    // the FRI proof is checked at the drawn positions, in the order they were drawn in
+    let deep_evaluations = match opening_order {
+        Some(indexes) => indexes.iter().map(|&i| deep_evaluations[i]).collect(),
+        None => deep_evaluations,
+    };
+    profiler.exit();
+
+    // 5 ----- Verify low-degree proof -------------------------------------------------------------
+    // make sure that evaluations of the DEEP composition polynomial we computed in the previous
+    // step are in fact evaluations of a polynomial of degree equal to trace polynomial degree;
+    // the evaluations are first compared against the queried values of the first FRI layer,
+    // which are checked to hash into the leaves of the layer commitment
+    profiler.enter("fri_verify");
+    let fri_result =
+        transcript
+            .fri_verifier
+            .verify(&mut verifier_channel, &deep_evaluations, &query_positions);
+    checks.record(fri_result.is_ok() as u8);
+    profiler.exit();
+
+    // 6 ----- Final decision ---------------------------------------------------------------------
+    // the OOD consistency, proof-of-work, and low-degree checks are decided on together; errors
+    // returned before this point are caused by proofs which are malformed, or whose openings do
+    // not authenticate against their commitments
+    if !checks.passed() {
+        return Err(anyhow!("proof verification failed"));
+    }
+    Ok(())
+}
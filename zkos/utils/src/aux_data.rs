@@ -0,0 +1,135 @@
+//! Commitments to auxiliary data of an application, bound to packaged Fibonacci proofs.
+//!
+//! An application may attach data to a verified statement, e.g., the metadata of a batch the
+//! proofs belong to, without changing the AIR: a packaged proof carries a commitment to the data
+//! (see [FibRiscInput](crate::inputs::FibRiscInput)), which is appended to the public inputs in
+//! the seed of the public coin (see [seed_pub_inputs]), and thus, bound to the proof by the
+//! prover. The verifier guests reproduce the seed with the packaged commitment, bind it to the
+//! statement digest, and surface it in the journal.
+//!
+//! The commitment is a vector commitment: the root of a binary SHA-256 Merkle tree (see
+//! [MerkleTree]) over the digests of the data items, so that a relying party can be convinced of
+//! single items with the inclusion proofs of a [MerkleBatch](crate::merkle_batch::MerkleBatch)
+//! against the committed root, without being given the whole data.
+
+use crate::merkle_batch::MerkleTree;
+use winter_prover::crypto::hashers::ShaHasherT;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Domain separator of the leaves of an aux data tree, so that a leaf is never the digest of an
+/// inner node.
+const AUX_DATA_LEAF_DOMAIN: &[u8] = b"zkos-aux-data-leaf";
+
+/// Leaf which pads an aux data tree to a power-of-two number of leaves.
+const PADDING_LEAF: [u8; 32] = [0; 32];
+
+// AUX DATA COMMITMENT
+// ================================================================================================
+
+/// Returns the commitment to the provided data items, i.e., the root of their aux data tree.
+pub fn commit_aux_data<S: ShaHasherT>(items: &[&[u8]]) -> [u8; 32] {
+    aux_data_tree::<S>(items).root()
+}
+
+/// Returns the Merkle tree whose root commits to the provided data items; the leaf at index `i`
+/// is the [aux_data_leaf] of the item at index `i`, and the leaves are padded with zeros to the
+/// next power of two.
+///
+/// # Panics
+/// Panics if the tree would be deeper than [MAX_DEPTH](crate::merkle_batch::MAX_DEPTH).
+pub fn aux_data_tree<S: ShaHasherT>(items: &[&[u8]]) -> MerkleTree {
+    let mut leaves: Vec<[u8; 32]> = items.iter().map(|item| aux_data_leaf::<S>(item)).collect();
+    leaves.resize(items.len().max(1).next_power_of_two(), PADDING_LEAF);
+    MerkleTree::new::<S>(leaves)
+}
+
+/// Returns the leaf of the provided data item in an aux data tree.
+pub fn aux_data_leaf<S: ShaHasherT>(item: &[u8]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(AUX_DATA_LEAF_DOMAIN.len() + 8 + item.len());
+    data.extend_from_slice(AUX_DATA_LEAF_DOMAIN);
+    data.extend_from_slice(&(item.len() as u64).to_le_bytes());
+    data.extend_from_slice(item);
+    S::digest(&data)
+}
+
+// PUBLIC COIN SEED
+// ================================================================================================
+
+/// Returns the provided serialized public inputs of a proof followed by the provided aux data
+/// commitment, if any.
+///
+/// These are the public inputs the public coin of the proof is seeded with (see
+/// [VerifierContext::coin_seed()](crate::context::VerifierContext::coin_seed)), and the ones the
+/// statement digest is computed over; without a commitment, these are the public inputs alone.
+pub fn seed_pub_inputs(pub_inputs: &[u8], aux_data_commitment: Option<&[u8; 32]>) -> Vec<u8> {
+    let mut result = Vec::with_capacity(pub_inputs.len() + 32);
+    result.extend_from_slice(pub_inputs);
+    if let Some(commitment) = aux_data_commitment {
+        result.extend_from_slice(commitment);
+    }
+    result
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{aux_data_leaf, aux_data_tree, commit_aux_data, seed_pub_inputs, PADDING_LEAF};
+    use crate::merkle_batch::{verify_batch, MerkleBatch};
+    use winter_prover::crypto::hashers::DefaultSha2;
+
+    #[test]
+    fn aux_data_tree_is_padded() {
+        let items: [&[u8]; 3] = [b"batch", b"of", b"metadata"];
+        let tree = aux_data_tree::<DefaultSha2>(&items);
+        assert_eq!(2, tree.depth());
+        assert_eq!(aux_data_leaf::<DefaultSha2>(b"of"), tree.leaf(1));
+        assert_eq!(PADDING_LEAF, tree.leaf(3));
+        assert_eq!(tree.root(), commit_aux_data::<DefaultSha2>(&items));
+
+        // a single item, and no items at all, are committed to by a tree of depth zero
+        assert_eq!(
+            aux_data_leaf::<DefaultSha2>(b"batch"),
+            commit_aux_data::<DefaultSha2>(&items[..1])
+        );
+        assert_eq!(PADDING_LEAF, commit_aux_data::<DefaultSha2>(&[]));
+    }
+
+    #[test]
+    fn aux_data_commitment_binds_items() {
+        let items: [&[u8]; 2] = [b"ab", b"c"];
+        let commitment = commit_aux_data::<DefaultSha2>(&items);
+        let resplit: [&[u8]; 2] = [b"a", b"bc"];
+        let reordered: [&[u8]; 2] = [b"c", b"ab"];
+        let extended: [&[u8]; 3] = [b"ab", b"c", b""];
+        for other in [&resplit[..], &reordered, &extended] {
+            assert_ne!(commitment, commit_aux_data::<DefaultSha2>(other));
+        }
+
+        // single items are opened against the commitment with inclusion proofs
+        let batch = MerkleBatch::from_tree(&aux_data_tree::<DefaultSha2>(&items), &[1]);
+        assert_eq!(aux_data_leaf::<DefaultSha2>(b"c"), batch.leaves[0]);
+        assert!(verify_batch::<DefaultSha2>(
+            &commitment,
+            batch.depth,
+            &batch.indices,
+            &batch.leaves,
+            &batch.siblings
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn seed_pub_inputs_with_commitment() {
+        assert_eq!(vec![1, 2], seed_pub_inputs(&[1, 2], None));
+
+        let commitment = [7; 32];
+        let seed = seed_pub_inputs(&[1, 2], Some(&commitment));
+        assert_eq!(34, seed.len());
+        assert_eq!([1, 2], seed[..2]);
+        assert_eq!(commitment, seed[2..]);
+    }
+}
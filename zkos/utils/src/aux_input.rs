@@ -0,0 +1,363 @@
+//! Framing and optional compression of the aux input passed from the host to the guests.
+//!
+//! The packaged proofs passed to the verifier guests can be large, and host→guest I/O makes up a
+//! significant part of the guest cost. The host can therefore compress the rkyv blob with LZ4,
+//! trading guest cycles spent on decompression for fewer input bytes. Every aux input starts
+//! with an [AUX_INPUT_HEADER_LEN]-byte header which identifies the compression used by the
+//! payload and the length of the decompressed payload:
+//!
+//! ```text
+//! | magic (4 bytes) | compression (1 byte) | reserved (3 bytes) | payload length (8 bytes, LE) |
+//! ```
+//!
+//! The header is 16 bytes long, so that an uncompressed payload keeps the alignment of the input
+//! buffer and can be accessed in place. The LZ4 decompressor is implemented here without any
+//! dependencies, so that it can be used by `no_std` guests.
+
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+use rkyv::AlignedVec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bytes in the header of an aux input.
+pub const AUX_INPUT_HEADER_LEN: usize = 16;
+
+/// Magic bytes at the start of every aux input.
+const MAGIC: [u8; 4] = *b"ZKAI";
+
+/// Minimum length of an LZ4 match.
+const MIN_MATCH: usize = 4;
+
+/// Number of bytes at the end of the input which must be encoded as literals.
+const LAST_LITERALS: usize = 5;
+
+/// Minimum distance from the end of the input at which a match may start.
+const MF_LIMIT: usize = 12;
+
+/// Maximum offset of an LZ4 match.
+const MAX_OFFSET: usize = u16::MAX as usize;
+
+/// Number of bits in the positions of the compressor hash table.
+const HASH_TABLE_BITS: u32 = 12;
+
+// AUX INPUT COMPRESSION
+// ================================================================================================
+
+/// Compression of the payload of an aux input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuxInputCompression {
+    /// The payload is stored as is.
+    None,
+    /// The payload is compressed with the LZ4 block format.
+    Lz4,
+}
+
+impl AuxInputCompression {
+    /// Returns the header flag identifying this compression.
+    fn flag(&self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Lz4 => 1,
+        }
+    }
+
+    /// Returns the compression identified by the provided header flag.
+    fn from_flag(flag: u8) -> Result<Self, AuxInputError> {
+        match flag {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Lz4),
+            _ => Err(AuxInputError::UnknownCompression(flag)),
+        }
+    }
+}
+
+// AUX INPUT
+// ================================================================================================
+
+/// Payload of a decoded aux input.
+pub enum AuxInput<'a> {
+    /// The payload was not compressed and is borrowed from the input buffer.
+    Raw(&'a [u8]),
+    /// The payload was decompressed into an aligned buffer.
+    Decompressed(AlignedVec),
+}
+
+impl<'a> AuxInput<'a> {
+    /// Returns the bytes of the payload.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Raw(bytes) => bytes,
+            Self::Decompressed(bytes) => bytes.as_slice(),
+        }
+    }
+}
+
+/// Returns an aux input consisting of a header followed by the provided payload compressed as
+/// specified.
+pub fn encode_aux_input(payload: &[u8], compression: AuxInputCompression) -> AlignedVec {
+    let mut result = AlignedVec::with_capacity(AUX_INPUT_HEADER_LEN + payload.len());
+    result.extend_from_slice(&MAGIC);
+    result.extend_from_slice(&[compression.flag(), 0, 0, 0]);
+    result.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+
+    match compression {
+        AuxInputCompression::None => result.extend_from_slice(payload),
+        AuxInputCompression::Lz4 => result.extend_from_slice(&lz4_compress(payload)),
+    }
+    result
+}
+
+/// Returns the number of heap bytes needed to decode the provided aux input: the length of the
+/// payload if it is compressed, and zero otherwise, since uncompressed payloads are accessed in
+/// place.
+///
+/// # Errors
+/// Returns an error if the input does not start with a valid header.
+pub fn aux_input_heap_size(input: &[u8]) -> Result<usize, AuxInputError> {
+    let (compression, payload_len) = read_header(input)?;
+    match compression {
+        AuxInputCompression::None => Ok(0),
+        AuxInputCompression::Lz4 => Ok(payload_len),
+    }
+}
+
+/// Decodes the provided aux input, decompressing its payload if needed.
+///
+/// # Errors
+/// Returns an error if:
+/// - The input does not start with a valid header.
+/// - The payload is not a valid LZ4 block, or its length differs from the length in the header.
+pub fn decode_aux_input(input: &[u8]) -> Result<AuxInput<'_>, AuxInputError> {
+    let (compression, payload_len) = read_header(input)?;
+    let payload = &input[AUX_INPUT_HEADER_LEN..];
+
+    match compression {
+        AuxInputCompression::None if payload.len() == payload_len => Ok(AuxInput::Raw(payload)),
+        AuxInputCompression::None => Err(AuxInputError::LengthMismatch(payload_len, payload.len())),
+        AuxInputCompression::Lz4 => {
+            lz4_decompress(payload, payload_len).map(AuxInput::Decompressed)
+        }
+    }
+}
+
+// AUX INPUT ERROR
+// ================================================================================================
+
+/// Reasons why an aux input cannot be decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuxInputError {
+    /// The input is too short or does not start with the magic bytes.
+    InvalidHeader,
+    /// The header specifies an unknown compression flag.
+    UnknownCompression(u8),
+    /// The compressed payload ends in the middle of a sequence.
+    TruncatedPayload,
+    /// A match refers to bytes before the start of the decompressed payload.
+    InvalidOffset(usize),
+    /// The decompressed payload length (second value) differs from the length in the header
+    /// (first value).
+    LengthMismatch(usize, usize),
+}
+
+impl fmt::Display for AuxInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHeader => write!(f, "aux input does not start with a valid header"),
+            Self::UnknownCompression(flag) => {
+                write!(f, "unknown aux input compression flag {}", flag)
+            }
+            Self::TruncatedPayload => write!(f, "compressed aux input payload is truncated"),
+            Self::InvalidOffset(offset) => {
+                write!(f, "invalid match offset {} in compressed aux input", offset)
+            }
+            Self::LengthMismatch(expected, actual) => {
+                write!(
+                    f,
+                    "expected aux input payload of {} bytes, but was {} bytes",
+                    expected, actual
+                )
+            }
+        }
+    }
+}
+
+impl From<AuxInputError> for ErrorChain {
+    fn from(error: AuxInputError) -> Self {
+        ErrorChain::new(ErrorKind::AuxInput, error)
+    }
+}
+
+// HEADER
+// ================================================================================================
+
+/// Reads the compression and the payload length from the header of the provided aux input.
+fn read_header(input: &[u8]) -> Result<(AuxInputCompression, usize), AuxInputError> {
+    if input.len() < AUX_INPUT_HEADER_LEN || input[..4] != MAGIC {
+        return Err(AuxInputError::InvalidHeader);
+    }
+    let compression = AuxInputCompression::from_flag(input[4])?;
+    let payload_len = u64::from_le_bytes(input[8..16].try_into().unwrap()) as usize;
+    Ok((compression, payload_len))
+}
+
+// LZ4 DECOMPRESSION
+// ================================================================================================
+
+/// Decompresses the provided LZ4 block into an aligned buffer of the expected length.
+///
+/// Matches may overlap the bytes they produce, and thus, they are copied one byte at a time.
+fn lz4_decompress(input: &[u8], expected_len: usize) -> Result<AlignedVec, AuxInputError> {
+    let mut output = AlignedVec::with_capacity(expected_len);
+    let mut pos = 0;
+
+    loop {
+        let token = *input.get(pos).ok_or(AuxInputError::TruncatedPayload)?;
+        pos += 1;
+
+        // copy the literals of the sequence
+        let literals_len = read_length(input, &mut pos, (token >> 4) as usize)?;
+        let literals = input
+            .get(pos..pos + literals_len)
+            .ok_or(AuxInputError::TruncatedPayload)?;
+        output.extend_from_slice(literals);
+        pos += literals_len;
+
+        // the last sequence consists of literals only
+        if pos == input.len() {
+            break;
+        }
+
+        let offset = input
+            .get(pos..pos + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+            .ok_or(AuxInputError::TruncatedPayload)?;
+        pos += 2;
+        if offset == 0 || offset > output.len() {
+            return Err(AuxInputError::InvalidOffset(offset));
+        }
+
+        let match_len = read_length(input, &mut pos, (token & 0xf) as usize)? + MIN_MATCH;
+        if output.len() + match_len > expected_len {
+            return Err(AuxInputError::LengthMismatch(
+                expected_len,
+                output.len() + match_len,
+            ));
+        }
+        let start = output.len() - offset;
+        for i in 0..match_len {
+            let byte = output[start + i];
+            output.push(byte);
+        }
+    }
+
+    if output.len() != expected_len {
+        return Err(AuxInputError::LengthMismatch(expected_len, output.len()));
+    }
+    Ok(output)
+}
+
+/// Reads the length of literals or of a match; if the 4-bit length in the token is 15, it is
+/// followed by bytes which are added to it until a byte smaller than 255 is read.
+fn read_length(input: &[u8], pos: &mut usize, token_len: usize) -> Result<usize, AuxInputError> {
+    let mut len = token_len;
+    if token_len == 0xf {
+        loop {
+            let byte = *input.get(*pos).ok_or(AuxInputError::TruncatedPayload)?;
+            *pos += 1;
+            len += byte as usize;
+            if byte != 0xff {
+                break;
+            }
+        }
+    }
+    Ok(len)
+}
+
+// LZ4 COMPRESSION
+// ================================================================================================
+
+/// Compresses the provided bytes into a single LZ4 block.
+///
+/// The compressor greedily takes the match found through a hash table of 4-byte sequences; this
+/// does not compress as well as the reference implementation, but it is simple and fast enough
+/// for packaging proofs on the host.
+fn lz4_compress(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len() / 2);
+    let mut table = vec![0usize; 1 << HASH_TABLE_BITS];
+    let mut literals_start = 0;
+    let mut pos = 0;
+
+    while input.len() >= MF_LIMIT && pos + MF_LIMIT <= input.len() {
+        let sequence = read_u32(input, pos);
+        let hash = hash_sequence(sequence);
+        // positions are stored shifted by one, so that zero marks an empty slot
+        let candidate = table[hash];
+        table[hash] = pos + 1;
+
+        let is_match = candidate != 0
+            && pos - (candidate - 1) <= MAX_OFFSET
+            && read_u32(input, candidate - 1) == sequence;
+        if !is_match {
+            pos += 1;
+            continue;
+        }
+
+        let match_start = candidate - 1;
+        let max_len = input.len() - LAST_LITERALS - pos;
+        let mut match_len = MIN_MATCH;
+        while match_len < max_len && input[match_start + match_len] == input[pos + match_len] {
+            match_len += 1;
+        }
+
+        write_sequence(
+            &mut output,
+            &input[literals_start..pos],
+            Some((pos - match_start, match_len)),
+        );
+        pos += match_len;
+        literals_start = pos;
+    }
+
+    write_sequence(&mut output, &input[literals_start..], None);
+    output
+}
+
+/// Writes a sequence consisting of the provided literals followed by an optional match, given by
+/// its offset and length.
+fn write_sequence(output: &mut Vec<u8>, literals: &[u8], match_: Option<(usize, usize)>) {
+    let match_len = match_.map(|(_, len)| len - MIN_MATCH).unwrap_or(0);
+    let token = ((literals.len().min(0xf) as u8) << 4) | match_len.min(0xf) as u8;
+    output.push(token);
+    write_length(output, literals.len());
+    output.extend_from_slice(literals);
+
+    if let Some((offset, _)) = match_ {
+        output.extend_from_slice(&(offset as u16).to_le_bytes());
+        write_length(output, match_len);
+    }
+}
+
+/// Writes the bytes extending a length which does not fit into the 4 bits of the token.
+fn write_length(output: &mut Vec<u8>, len: usize) {
+    if len < 0xf {
+        return;
+    }
+    let mut remaining = len - 0xf;
+    while remaining >= 0xff {
+        output.push(0xff);
+        remaining -= 0xff;
+    }
+    output.push(remaining as u8);
+}
+
+/// Reads 4 bytes at the specified position as a little-endian integer.
+fn read_u32(input: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(input[pos..pos + 4].try_into().unwrap())
+}
+
+/// Returns the position in the compressor hash table of a 4-byte sequence.
+fn hash_sequence(sequence: u32) -> usize {
+    (sequence.wrapping_mul(2654435761) >> (32 - HASH_TABLE_BITS)) as usize
+}
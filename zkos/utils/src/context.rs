@@ -0,0 +1,311 @@
+//! Domain-separated verifier contexts of the packaged proofs.
+//!
+//! The `context` of a packaged proof used to be the bare serialized proof context, so nothing in
+//! a journal told which protocol, or which deployment of it, the verified statement was meant
+//! for; two protocols verifying the same proof would commit identical statement digests. The
+//! host packer therefore wraps the serialized proof context in a [VerifierContext], which prefixes
+//! it with the canonical encoding of a [DomainTag]:
+//!
+//! ```text
+//! [protocol length: u8] [protocol: ASCII] [version: u32 LE] [chain ID: u64 LE] [proof context]
+//! ```
+//!
+//! The guests parse the context, seed the public coin with the public inputs and the proof
+//! context only (this is the seed the prover used, so the transcript is unchanged), and use the
+//! full encoding, domain tag included, as the context part of the statement digest. A relying
+//! party recomputing the statement digest for its own domain thus rejects journals committed for
+//! any other protocol, version, or chain.
+
+use crate::journal::VERIFIER_VERSION;
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Name of the protocol of the domain tags, in which the runner packages proofs.
+pub const ZKOS_PROTOCOL: &str = "zkos";
+
+/// Maximum length of the name of a protocol, in bytes.
+pub const MAX_PROTOCOL_LEN: usize = 64;
+
+// DOMAIN TAG
+// ================================================================================================
+
+/// Protocol, version, and chain a verified statement is meant for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainTag {
+    protocol: String,
+    version: u32,
+    chain_id: u64,
+}
+
+impl DomainTag {
+    /// Returns a new domain tag for the specified protocol, version, and chain.
+    ///
+    /// # Errors
+    /// Returns an error if the name of the protocol is empty, longer than [MAX_PROTOCOL_LEN]
+    /// bytes, or contains characters other than printable, non-space ASCII characters.
+    pub fn new(protocol: &str, version: u32, chain_id: u64) -> Result<Self, ContextError> {
+        check_protocol(protocol.as_bytes())?;
+        Ok(Self {
+            protocol: protocol.to_string(),
+            version,
+            chain_id,
+        })
+    }
+
+    /// Returns the domain tag of the current version of the verification logic on the specified
+    /// chain.
+    pub fn zkos(chain_id: u64) -> Self {
+        Self {
+            protocol: ZKOS_PROTOCOL.to_string(),
+            version: VERIFIER_VERSION,
+            chain_id,
+        }
+    }
+
+    /// Returns the name of the protocol.
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// Returns the version of the protocol.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the ID of the chain.
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Writes the canonical encoding of this tag into the target.
+    fn write_into(&self, target: &mut Vec<u8>) {
+        target.push(self.protocol.len() as u8);
+        target.extend_from_slice(self.protocol.as_bytes());
+        target.extend_from_slice(&self.version.to_le_bytes());
+        target.extend_from_slice(&self.chain_id.to_le_bytes());
+    }
+}
+
+impl Default for DomainTag {
+    /// Returns the domain tag of the current version of the verification logic on chain 0, which
+    /// the runner packages proofs for.
+    fn default() -> Self {
+        Self::zkos(0)
+    }
+}
+
+impl fmt::Display for DomainTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/v{}/{}", self.protocol, self.version, self.chain_id)
+    }
+}
+
+// VERIFIER CONTEXT
+// ================================================================================================
+
+/// Serialized proof context of a packaged proof, bound to the domain it is verified for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierContext {
+    domain: DomainTag,
+    proof_context: Vec<u8>,
+}
+
+impl VerifierContext {
+    /// Returns a new context which binds the serialized proof context to the specified domain.
+    pub fn new(domain: DomainTag, proof_context: Vec<u8>) -> Self {
+        Self {
+            domain,
+            proof_context,
+        }
+    }
+
+    /// Parses a context from its canonical encoding.
+    ///
+    /// # Errors
+    /// Returns an error if the encoding is truncated, if the domain tag is not canonical, or if
+    /// the proof context is empty.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ContextError> {
+        let (&protocol_len, rest) = bytes.split_first().ok_or(ContextError::Truncated)?;
+        let protocol_len = protocol_len as usize;
+        if rest.len() < protocol_len + 12 {
+            return Err(ContextError::Truncated);
+        }
+        let (protocol, rest) = rest.split_at(protocol_len);
+        check_protocol(protocol)?;
+        let (version, rest) = rest.split_at(4);
+        let (chain_id, proof_context) = rest.split_at(8);
+        if proof_context.is_empty() {
+            return Err(ContextError::EmptyProofContext);
+        }
+
+        let domain = DomainTag {
+            // the protocol was checked to consist of ASCII characters only
+            protocol: String::from_utf8(protocol.to_vec()).unwrap(),
+            version: u32::from_le_bytes(version.try_into().unwrap()),
+            chain_id: u64::from_le_bytes(chain_id.try_into().unwrap()),
+        };
+        Ok(Self::new(domain, proof_context.to_vec()))
+    }
+
+    /// Returns the canonical encoding of this context; this is the context part of the statement
+    /// digest.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes =
+            Vec::with_capacity(1 + self.domain.protocol.len() + 12 + self.proof_context.len());
+        self.domain.write_into(&mut bytes);
+        bytes.extend_from_slice(&self.proof_context);
+        bytes
+    }
+
+    /// Returns the domain this context is bound to.
+    pub fn domain(&self) -> &DomainTag {
+        &self.domain
+    }
+
+    /// Returns the serialized proof context.
+    pub fn proof_context(&self) -> &[u8] {
+        &self.proof_context
+    }
+
+    /// Returns the seed of the public coin of the proof with the specified serialized public
+    /// inputs, i.e., the public inputs followed by the proof context.
+    ///
+    /// The domain tag is not part of the seed: the seed must be the one the prover used, and
+    /// the prover knows nothing about the domain the proof is verified for. For a well-formed
+    /// proof context, this is the seed returned by `Context::public_coin_seed()`.
+    pub fn coin_seed(&self, pub_inputs: &[u8]) -> Vec<u8> {
+        let mut seed = Vec::with_capacity(pub_inputs.len() + self.proof_context.len());
+        seed.extend_from_slice(pub_inputs);
+        seed.extend_from_slice(&self.proof_context);
+        seed
+    }
+}
+
+// CONTEXT ERROR
+// ================================================================================================
+
+/// Errors which can occur while building or parsing a verifier context.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContextError {
+    /// The encoding ends before the end of the domain tag.
+    Truncated,
+    /// The name of the protocol is empty, too long, or not printable ASCII.
+    InvalidProtocol,
+    /// The encoding holds no proof context after the domain tag.
+    EmptyProofContext,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "verifier context ends within the domain tag"),
+            Self::InvalidProtocol => write!(
+                f,
+                "protocol name must be 1 to {} printable ASCII characters",
+                MAX_PROTOCOL_LEN
+            ),
+            Self::EmptyProofContext => write!(f, "verifier context holds no proof context"),
+        }
+    }
+}
+
+impl From<ContextError> for ErrorChain {
+    fn from(error: ContextError) -> Self {
+        ErrorChain::new(ErrorKind::Deserialization, error)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn check_protocol(protocol: &[u8]) -> Result<(), ContextError> {
+    if protocol.is_empty()
+        || protocol.len() > MAX_PROTOCOL_LEN
+        || !protocol.iter().all(|c| c.is_ascii_graphic())
+    {
+        return Err(ContextError::InvalidProtocol);
+    }
+    Ok(())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{ContextError, DomainTag, VerifierContext, MAX_PROTOCOL_LEN};
+
+    #[test]
+    fn encode_context() {
+        let domain = DomainTag::new("ab", 3, 0x0102).unwrap();
+        let context = VerifierContext::new(domain, vec![9, 9]);
+        assert_eq!(
+            vec![2, b'a', b'b', 3, 0, 0, 0, 2, 1, 0, 0, 0, 0, 0, 0, 9, 9],
+            context.to_bytes()
+        );
+        assert_eq!(
+            Ok(context.clone()),
+            VerifierContext::from_bytes(&context.to_bytes())
+        );
+
+        // the domain tag is not absorbed into the coin seed
+        assert_eq!(vec![7, 9, 9], context.coin_seed(&[7]));
+    }
+
+    #[test]
+    fn separate_domains() {
+        let proof_context = vec![1, 2, 3];
+        let contexts = [
+            VerifierContext::new(DomainTag::zkos(0), proof_context.clone()),
+            VerifierContext::new(DomainTag::zkos(1), proof_context.clone()),
+            VerifierContext::new(DomainTag::new("zkos", 0, 0).unwrap(), proof_context.clone()),
+            VerifierContext::new(
+                DomainTag::new("zkos2", 0, 0).unwrap(),
+                proof_context.clone(),
+            ),
+        ];
+        for (i, a) in contexts.iter().enumerate() {
+            for b in contexts.iter().skip(i + 1) {
+                assert_ne!(a.to_bytes(), b.to_bytes());
+                assert_eq!(a.coin_seed(&[]), b.coin_seed(&[]));
+            }
+        }
+    }
+
+    #[test]
+    fn reject_malformed_contexts() {
+        assert_eq!(Err(ContextError::InvalidProtocol), DomainTag::new("", 0, 0));
+        assert_eq!(
+            Err(ContextError::InvalidProtocol),
+            DomainTag::new("zk os", 0, 0)
+        );
+        let long = "a".repeat(MAX_PROTOCOL_LEN + 1);
+        assert_eq!(
+            Err(ContextError::InvalidProtocol),
+            DomainTag::new(&long, 0, 0)
+        );
+
+        let bytes = VerifierContext::new(DomainTag::zkos(5), vec![1]).to_bytes();
+        assert_eq!(
+            Err(ContextError::Truncated),
+            VerifierContext::from_bytes(&[])
+        );
+        assert_eq!(
+            Err(ContextError::Truncated),
+            VerifierContext::from_bytes(&bytes[..bytes.len() - 2])
+        );
+        assert_eq!(
+            Err(ContextError::EmptyProofContext),
+            VerifierContext::from_bytes(&bytes[..bytes.len() - 1])
+        );
+        let mut invalid = bytes.clone();
+        invalid[1] = b' ';
+        assert_eq!(
+            Err(ContextError::InvalidProtocol),
+            VerifierContext::from_bytes(&invalid)
+        );
+    }
+}
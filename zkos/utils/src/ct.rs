@@ -0,0 +1,156 @@
+//! Constant-time comparisons for verifier checks.
+//!
+//! Verifiers running on remote prover services may be observed through the time they take to
+//! reject a proof. The comparisons defined here do not branch on the compared values, and the
+//! outcomes of checks are accumulated into [VerificationChecks], so that a verifier makes a single
+//! failure decision once all of them have run, rather than exiting at the first mismatch.
+//!
+//! This hides only which of the recorded checks failed. Checks which are not recorded here, or
+//! which run code that itself exits early (e.g., Merkle path authentication and FRI), still take
+//! time that depends on where they fail.
+
+use winter_math::FieldElement;
+use winter_verifier::Serializable;
+
+// CONSTANT-TIME COMPARISONS
+// ================================================================================================
+
+/// Returns 1 if the provided byte slices are equal, and 0 otherwise.
+///
+/// The running time depends only on the lengths of the slices, which are assumed to be public.
+pub fn ct_eq_bytes(a: &[u8], b: &[u8]) -> u8 {
+    if a.len() != b.len() {
+        return 0;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |diff, (&a, &b)| diff | (a ^ b));
+    is_zero(barrier(diff))
+}
+
+/// Returns 1 if the provided field elements are equal, and 0 otherwise.
+///
+/// Elements are compared through their serialized form, which is canonical; the in-memory form
+/// of an element is not, and thus, it cannot be compared byte by byte.
+pub fn ct_eq_elements<E: FieldElement>(a: E, b: E) -> u8 {
+    ct_eq_bytes(&a.to_bytes(), &b.to_bytes())
+}
+
+/// Returns 1 if `a` is greater than or equal to `b`, and 0 otherwise.
+pub fn ct_ge_u32(a: u32, b: u32) -> u8 {
+    // the subtraction borrows into the top bit if and only if a < b
+    let borrow = ((a as u64).wrapping_sub(b as u64) >> 63) as u8;
+    barrier(borrow) ^ 1
+}
+
+// VERIFICATION CHECKS
+// ================================================================================================
+
+/// Outcomes of a set of verifier checks, accumulated without branching on them.
+///
+/// Checks are recorded as they run, and the verifier decides whether to accept a proof only once
+/// after all of them have been recorded. Thus, the time it takes to reject a proof does not tell
+/// which of the recorded checks failed, as long as computing the outcome of each check does not
+/// itself depend on the outcome.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerificationChecks {
+    ok: u8,
+}
+
+impl VerificationChecks {
+    /// Returns an empty set of checks.
+    pub fn new() -> Self {
+        Self { ok: 1 }
+    }
+
+    /// Records a check which passed if `ok` is 1, and failed if it is 0.
+    pub fn record(&mut self, ok: u8) {
+        self.ok &= ok;
+    }
+
+    /// Records a check which passes if the provided byte slices are equal.
+    pub fn record_eq_bytes(&mut self, a: &[u8], b: &[u8]) {
+        self.record(ct_eq_bytes(a, b));
+    }
+
+    /// Records a check which passes if the provided field elements are equal.
+    pub fn record_eq_elements<E: FieldElement>(&mut self, a: E, b: E) {
+        self.record(ct_eq_elements(a, b));
+    }
+
+    /// Returns true if all recorded checks passed; this is the only point at which the outcome of
+    /// the checks is branched on.
+    pub fn passed(&self) -> bool {
+        barrier(self.ok) == 1
+    }
+}
+
+impl Default for VerificationChecks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns 1 if `value` is zero, and 0 otherwise.
+fn is_zero(value: u8) -> u8 {
+    // subtracting one from a byte widened to 16 bits wraps around into the top bit only if the
+    // byte was zero
+    ((value as u16).wrapping_sub(1) >> 15) as u8
+}
+
+/// Returns the provided value through a volatile read, so that the compiler cannot reason about
+/// it and turn the comparisons built on it back into branches.
+fn barrier(value: u8) -> u8 {
+    // SAFETY: the pointer is derived from a reference to a local value, and thus, it is valid and
+    // aligned
+    unsafe { core::ptr::read_volatile(&value) }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{ct_eq_bytes, ct_eq_elements, ct_ge_u32, VerificationChecks};
+    use winter_math::fields::f64::BaseElement;
+
+    #[test]
+    fn eq_bytes() {
+        assert_eq!(1, ct_eq_bytes(&[], &[]));
+        assert_eq!(1, ct_eq_bytes(&[1, 2, 3], &[1, 2, 3]));
+        assert_eq!(0, ct_eq_bytes(&[1, 2, 3], &[1, 2, 4]));
+        assert_eq!(0, ct_eq_bytes(&[0x80, 2, 3], &[0, 2, 3]));
+        assert_eq!(0, ct_eq_bytes(&[1, 2, 3], &[1, 2]));
+    }
+
+    #[test]
+    fn eq_elements() {
+        let a = BaseElement::new(12345);
+        assert_eq!(1, ct_eq_elements(a, BaseElement::new(12345)));
+        assert_eq!(0, ct_eq_elements(a, BaseElement::new(12346)));
+    }
+
+    #[test]
+    fn ge_u32() {
+        assert_eq!(1, ct_ge_u32(0, 0));
+        assert_eq!(1, ct_ge_u32(20, 16));
+        assert_eq!(0, ct_ge_u32(15, 16));
+        assert_eq!(1, ct_ge_u32(u32::MAX, 0));
+        assert_eq!(0, ct_ge_u32(0, u32::MAX));
+    }
+
+    #[test]
+    fn checks() {
+        let mut checks = VerificationChecks::new();
+        assert!(checks.passed());
+        checks.record_eq_bytes(&[1, 2], &[1, 2]);
+        checks.record(ct_ge_u32(8, 4));
+        assert!(checks.passed());
+
+        // a single failed check fails the set, regardless of the checks recorded after it
+        checks.record_eq_elements(BaseElement::new(1), BaseElement::new(2));
+        checks.record(1);
+        assert!(!checks.passed());
+    }
+}
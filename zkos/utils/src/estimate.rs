@@ -0,0 +1,235 @@
+//! Estimates of proof size and guest verification cost.
+//!
+//! Generating a STARK proof and verifying it in a guest are both expensive, and the cost of the
+//! latter depends heavily on the chosen [ProofOptions]. The estimator in this module predicts,
+//! for a given trace shape and proof options, the size of the proof, the size of the input
+//! packaged for the verifier guest, and the number of cycles the guest spends verifying the
+//! proof. Sizes are derived from the structure of the proof; cycle counts are based on
+//! calibration constants which are only approximate, and should be re-calibrated with the cycle
+//! profiler (see [crate::profiler]) whenever the verifier guest changes significantly.
+
+use winter_air::{HashFunction, ProofOptions};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bytes in a serialized base field element.
+const ELEMENT_BYTES: usize = 8;
+
+/// Number of bytes in a SHA-256 block.
+const SHA_BLOCK_BYTES: usize = 64;
+
+/// Approximate number of bytes in a serialized proof context and the proof-of-work nonce.
+const PROOF_OVERHEAD_BYTES: usize = 128;
+
+/// Approximate number of bytes by which the packaged inputs exceed the proof, due to the
+/// alignment and relative pointers of the archived verifier channel.
+const INPUT_OVERHEAD_BYTES: usize = 1024;
+
+/// Approximate number of field inversion hints needed per query and per FRI layer.
+const INV_HINTS_PER_QUERY_LAYER: usize = 2;
+
+/// Approximate number of guest cycles spent on a single SHA-256 block.
+const CYCLES_PER_SHA_BLOCK: u64 = 80;
+
+/// Approximate number of guest cycles spent on a single multiplication in the extension field.
+const CYCLES_PER_EXT_MUL: u64 = 12;
+
+/// Approximate number of guest cycles spent reading a single byte of the packaged inputs.
+const CYCLES_PER_INPUT_BYTE: u64 = 2;
+
+/// Approximate number of guest cycles spent on a proof independently of the proof options, e.g.,
+/// on the guest setup and on the out-of-domain consistency check.
+const BASE_CYCLES: u64 = 250_000;
+
+// TRACE SHAPE
+// ================================================================================================
+
+/// Shape of the execution trace of the proof to be estimated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceShape {
+    /// Number of rows in the execution trace; must be a power of two.
+    pub length: usize,
+    /// Number of columns in the main segment of the execution trace.
+    pub main_width: usize,
+    /// Number of columns in the auxiliary segment of the execution trace, or zero if the trace
+    /// has a single segment.
+    pub aux_width: usize,
+    /// Number of columns of the constraint composition polynomial, which is equal to the
+    /// constraint evaluation blowup factor of the AIR.
+    pub num_composition_columns: usize,
+}
+
+impl TraceShape {
+    /// Returns the shape of a single-segment trace with the specified length and width, and
+    /// constraints of degree at most 2.
+    ///
+    /// # Panics
+    /// Panics if `length` is not a power of two.
+    pub fn new(length: usize, main_width: usize) -> Self {
+        assert!(
+            length.is_power_of_two(),
+            "trace length must be a power of two, but was {}",
+            length
+        );
+        Self {
+            length,
+            main_width,
+            aux_width: 0,
+            num_composition_columns: ProofOptions::MIN_BLOWUP_FACTOR,
+        }
+    }
+
+    /// Sets the number of columns in the auxiliary segment of the trace.
+    pub fn with_aux_width(mut self, aux_width: usize) -> Self {
+        self.aux_width = aux_width;
+        self
+    }
+
+    /// Sets the number of columns of the constraint composition polynomial.
+    pub fn with_composition_columns(mut self, num_composition_columns: usize) -> Self {
+        self.num_composition_columns = num_composition_columns;
+        self
+    }
+
+    /// Returns the number of trace segments, each of which is committed to separately.
+    fn num_segments(&self) -> usize {
+        if self.aux_width > 0 {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+// ESTIMATE
+// ================================================================================================
+
+/// Predicted costs of proving a computation and verifying the proof in a guest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Estimate {
+    /// Size of the serialized proof in bytes.
+    pub proof_size: usize,
+    /// Size of the inputs packaged for the verifier guest in bytes.
+    pub input_size: usize,
+    /// Approximate number of cycles the guest spends verifying the proof.
+    pub guest_cycles: u64,
+    /// Number of heap bytes the guest needs to deserialize and verify the proof.
+    pub heap_size: usize,
+}
+
+/// Estimates the costs of a proof for a trace of the specified shape generated with the
+/// specified proof options.
+///
+/// The estimate assumes that authentication paths for different queries are not shared, and
+/// thus, the actual proof size is usually somewhat smaller, especially for large numbers of
+/// queries over small domains.
+pub fn estimate(shape: &TraceShape, options: &ProofOptions) -> Estimate {
+    let ext_degree = options.field_extension().degree() as usize;
+    let ext_bytes = ext_degree * ELEMENT_BYTES;
+    let num_queries = options.num_queries();
+    let num_segments = shape.num_segments();
+    let digest_bytes = digest_bytes(options.hash_fn());
+
+    let lde_domain_size = shape.length * options.blowup_factor();
+    let lde_depth = lde_domain_size.trailing_zeros() as usize;
+
+    let fri_options = options.to_fri_options();
+    let folding_factor = fri_options.folding_factor();
+    let num_fri_layers = fri_options.num_fri_layers(lde_domain_size);
+    let remainder_size = fri_options.fri_remainder_size(lde_domain_size);
+
+    // --- proof size -----------------------------------------------------------------------------
+    let main_row_bytes = shape.main_width * ELEMENT_BYTES;
+    let aux_row_bytes = shape.aux_width * ext_bytes;
+    let composition_row_bytes = shape.num_composition_columns * ext_bytes;
+
+    let commitments = (num_segments + 1 + num_fri_layers) * digest_bytes;
+    let ood_frame = 2 * (shape.main_width + shape.aux_width) * ext_bytes + composition_row_bytes;
+    let trace_queries =
+        num_queries * (main_row_bytes + aux_row_bytes + num_segments * lde_depth * digest_bytes);
+    let constraint_queries = num_queries * (composition_row_bytes + lde_depth * digest_bytes);
+
+    let mut fri_queries = 0;
+    let mut fri_path_hashes = 0;
+    let mut layer_depth = lde_depth;
+    for _ in 0..num_fri_layers {
+        // the values of a layer are committed to in groups of `folding_factor` elements
+        layer_depth -= folding_factor.trailing_zeros() as usize;
+        fri_queries += num_queries * (folding_factor * ext_bytes + layer_depth * digest_bytes);
+        fri_path_hashes += num_queries * layer_depth;
+    }
+    let fri_remainder = remainder_size * ext_bytes;
+
+    let proof_size = PROOF_OVERHEAD_BYTES
+        + commitments
+        + ood_frame
+        + trace_queries
+        + constraint_queries
+        + fri_queries
+        + fri_remainder;
+
+    // --- packaged input size --------------------------------------------------------------------
+    let num_inv_hints = num_queries * (num_fri_layers + 1) * INV_HINTS_PER_QUERY_LAYER;
+    let input_size = proof_size + num_inv_hints * 2 * ext_bytes + INPUT_OVERHEAD_BYTES;
+
+    // --- guest cycles ---------------------------------------------------------------------------
+    // hashing: Merkle paths are verified one 64-byte node at a time, and every queried row is
+    // hashed into a leaf
+    let leaf_blocks = |row_bytes: usize| match row_bytes {
+        0 => 0,
+        _ => row_bytes / SHA_BLOCK_BYTES + 1,
+    };
+    let path_hashes = num_queries * (num_segments + 1) * lde_depth + fri_path_hashes;
+    let leaf_hashes = num_queries
+        * (leaf_blocks(main_row_bytes)
+            + leaf_blocks(aux_row_bytes)
+            + leaf_blocks(composition_row_bytes)
+            + num_fri_layers * leaf_blocks(folding_factor * ext_bytes));
+    let sha_blocks = (path_hashes * 2 + leaf_hashes) as u64;
+
+    // field arithmetic: DEEP composition touches every queried trace and composition value
+    // twice, and FRI folding interpolates `folding_factor` values per query and layer
+    let deep_muls =
+        num_queries * 2 * (shape.main_width + shape.aux_width + shape.num_composition_columns);
+    let fri_muls = num_queries * num_fri_layers * folding_factor * folding_factor;
+    let ext_muls = (deep_muls + fri_muls) as u64;
+
+    let guest_cycles = BASE_CYCLES
+        + sha_blocks * CYCLES_PER_SHA_BLOCK
+        + ext_muls * CYCLES_PER_EXT_MUL
+        + input_size as u64 * CYCLES_PER_INPUT_BYTE;
+
+    // --- guest heap ------------------------------------------------------------------------------
+    // the packaged inputs are deserialized into owned structures of about the same size, and the
+    // DEEP composition keeps the queried trace and composition values, and their compositions
+    let deep_working_set = num_queries
+        * (2 * (shape.main_width + shape.aux_width + shape.num_composition_columns) + 1)
+        * ext_bytes;
+    let heap_size = input_size + deep_working_set;
+
+    Estimate {
+        proof_size,
+        input_size,
+        guest_cycles,
+        heap_size,
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of bytes in a digest of the specified hash function.
+fn digest_bytes(hash_fn: HashFunction) -> usize {
+    match hash_fn {
+        HashFunction::Blake3_192 => 24,
+        HashFunction::Blake3_256
+        | HashFunction::Sha3_256
+        | HashFunction::Sha2_256
+        | HashFunction::Rp64_256
+        | HashFunction::Poseidon64_256
+        | HashFunction::Keccak256 => 32,
+        HashFunction::Sha2_384 => 48,
+        HashFunction::Sha2_512 => 64,
+    }
+}
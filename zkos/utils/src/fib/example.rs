@@ -2,6 +2,7 @@ use winter_air::proof::StarkProof;
 use winter_air::ProofOptions;
 use winter_math::fields::f64_risc0::DefaultNativeMul;
 use winter_math::{fields::f64_risc0::BaseElement, FieldElement};
+use winter_prover::crypto::ElementHasher;
 use winter_prover::Prover;
 use winter_verifier::VerifierError;
 
@@ -15,7 +16,7 @@ pub trait Example {
 
 impl Example for FibExample {
     fn prove(&self) -> StarkProof {
-        let prover: FibProver<DefaultNativeMul> = FibProver::new(self.options.clone());
+        let prover = self.prover();
         let trace = prover.build_trace(self.sequence_length);
         prover.prove(trace).unwrap()
     }
@@ -41,6 +42,8 @@ pub struct FibExample {
     options: ProofOptions,
     sequence_length: usize,
     pub result: BaseElement,
+    /// Commitment to auxiliary data the proofs of this computation are bound to, if any.
+    pub aux_data_commitment: Option<[u8; 32]>,
 }
 
 impl FibExample {
@@ -56,6 +59,33 @@ impl FibExample {
             options,
             sequence_length,
             result,
+            aux_data_commitment: None,
+        }
+    }
+
+    /// Returns this computation with its proofs bound to the specified commitment to auxiliary
+    /// data (see [crate::aux_data]). Such proofs are rejected by [Example::verify]; they verify
+    /// with `winter_verifier::verify_with_seed_extension()` and the commitment as the extension.
+    pub fn with_aux_data_commitment(mut self, commitment: [u8; 32]) -> Self {
+        self.aux_data_commitment = Some(commitment);
+        self
+    }
+
+    /// Returns a proof of this computation with all commitments made with the hash function `H`,
+    /// which, unlike [Example::prove], may be a hash function the generic prover cannot
+    /// instantiate; `H` must be the hash function named by the proof options.
+    pub fn prove_with_hasher<H: ElementHasher<BaseField = BaseElement>>(&self) -> StarkProof {
+        let prover = self.prover();
+        let trace = prover.build_trace(self.sequence_length);
+        prover.prove_with_hasher::<H>(trace).unwrap()
+    }
+
+    /// Returns the prover of this computation.
+    fn prover(&self) -> FibProver<DefaultNativeMul> {
+        let prover = FibProver::new(self.options.clone());
+        match self.aux_data_commitment {
+            Some(commitment) => prover.with_aux_data_commitment(commitment),
+            None => prover,
         }
     }
 }
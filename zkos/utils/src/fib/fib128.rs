@@ -0,0 +1,195 @@
+//! Fibonacci computation over the 128-bit field.
+//!
+//! Proofs of the Fibonacci computation generated before the verifier guests were moved to the
+//! 64-bit field are over [f128](winter_math::fields::f128). The computation is the same as the one
+//! of [FibAir](super::fib_air::FibAir), but the AIR over the 128-bit field cannot use the
+//! accelerated field arithmetic or the verification key of the 64-bit field, and thus, it is
+//! instantiated from scratch.
+
+use super::fib_air::{are_equal, NUM_ASSERTIONS, TRACE_WIDTH, TRANSITION_CONSTRAINT_DEGREES};
+use winter_air::proof::StarkProof;
+use winter_air::{
+    Air, AirContext, Assertion, EvaluationFrame, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+use winter_math::{fields::f128::BaseElement, FieldElement};
+use winter_prover::{Prover, Trace, TraceTable};
+use winter_verifier::VerifierError;
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// AIR of the Fibonacci computation over the 128-bit field.
+pub struct Fib128Air {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for Fib128Air {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
+        let degrees = TRANSITION_CONSTRAINT_DEGREES
+            .iter()
+            .map(|&degree| TransitionConstraintDegree::new(degree))
+            .collect();
+        assert_eq!(TRACE_WIDTH, trace_info.width());
+        Fib128Air {
+            context: AirContext::new(trace_info, degrees, NUM_ASSERTIONS, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // same constraints as the ones of FibAir
+        result[0] = are_equal(next[0], current[0] + current[1]);
+        result[1] = are_equal(next[1], current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, Self::BaseField::ONE),
+            Assertion::single(1, 0, Self::BaseField::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+// FIBONACCI PROVER
+// ================================================================================================
+
+/// Prover of the Fibonacci computation over the 128-bit field.
+pub struct Fib128Prover {
+    options: ProofOptions,
+}
+
+impl Fib128Prover {
+    pub fn new(options: ProofOptions) -> Self {
+        Self { options }
+    }
+
+    /// Builds an execution trace for computing a Fibonacci sequence of the specified length such
+    /// that each row advances the sequence by 2 terms.
+    pub fn build_trace(&self, sequence_length: usize) -> TraceTable<BaseElement> {
+        assert!(
+            sequence_length.is_power_of_two(),
+            "sequence length must be a power of 2"
+        );
+
+        let mut trace = TraceTable::new(TRACE_WIDTH, sequence_length / 2);
+        trace.fill(
+            |state| {
+                state[0] = BaseElement::ONE;
+                state[1] = BaseElement::ONE;
+            },
+            |_, state| {
+                state[0] += state[1];
+                state[1] += state[0];
+            },
+        );
+
+        trace
+    }
+}
+
+impl Prover for Fib128Prover {
+    type BaseField = BaseElement;
+    type Air = Fib128Air;
+    type Trace = TraceTable<BaseElement>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        let last_step = trace.length() - 1;
+        trace.get(1, last_step)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+}
+
+// FIBONACCI EXAMPLE
+// ================================================================================================
+
+/// Fibonacci computation over the 128-bit field, in the same way as
+/// [FibExample](super::example::FibExample).
+pub struct Fib128Example {
+    options: ProofOptions,
+    sequence_length: usize,
+    pub result: BaseElement,
+}
+
+impl Fib128Example {
+    pub fn new(sequence_length: usize, options: ProofOptions) -> Self {
+        assert!(
+            sequence_length.is_power_of_two(),
+            "sequence length must be a power of 2"
+        );
+
+        Self {
+            options,
+            sequence_length,
+            result: compute_fib128_term(sequence_length),
+        }
+    }
+
+    pub fn prove(&self) -> StarkProof {
+        let prover = Fib128Prover::new(self.options.clone());
+        let trace = prover.build_trace(self.sequence_length);
+        prover.prove(trace).unwrap()
+    }
+
+    pub fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        winter_verifier::verify::<Fib128Air>(proof, self.result)
+    }
+}
+
+/// Returns the n-th term of the Fibonacci sequence over the 128-bit field.
+pub fn compute_fib128_term(n: usize) -> BaseElement {
+    let mut t0 = BaseElement::ONE;
+    let mut t1 = BaseElement::ONE;
+
+    for _ in 0..(n - 1) {
+        t1 = t0 + t1;
+        core::mem::swap(&mut t0, &mut t1);
+    }
+
+    t1
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::Fib128Example;
+    use winter_air::{FieldExtension, HashFunction, ProofOptions};
+
+    #[test]
+    fn fib128_proofs_verify() {
+        for extension in [FieldExtension::None, FieldExtension::Quadratic] {
+            let options = ProofOptions::new(9, 8, 0, HashFunction::Sha2_256, extension, 4, 256);
+            let example = Fib128Example::new(128, options);
+            let proof = example.prove();
+            assert_eq!(extension, proof.options().field_extension());
+            example.verify(proof).unwrap();
+        }
+    }
+}
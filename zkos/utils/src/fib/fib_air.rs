@@ -11,7 +11,16 @@ use winter_math::{
     FieldElement,
 };
 
-const TRACE_WIDTH: usize = 2;
+use super::vk::{FibVerificationKey, VerificationKeyError};
+
+/// Width of the execution trace of the Fibonacci computation.
+pub const TRACE_WIDTH: usize = 2;
+
+/// Degrees of the transition constraints of the Fibonacci computation.
+pub const TRANSITION_CONSTRAINT_DEGREES: [usize; 2] = [1, 1];
+
+/// Number of assertions against the execution trace of the Fibonacci computation.
+pub const NUM_ASSERTIONS: usize = 3;
 
 // FIBONACCI AIR
 // ================================================================================================
@@ -21,6 +30,42 @@ pub struct FibAir<A: NativeMontMul> {
     result: AccelBaseElementRisc0<A>,
 }
 
+impl<A: NativeMontMul> FibAir<A> {
+    /// Returns a new [FibAir] instantiated from the provided verification key.
+    ///
+    /// Unlike [FibAir::new()], this does not recompute the constraint composition structure of
+    /// the AIR, but takes it from the verification key instead.
+    ///
+    /// # Errors
+    /// Returns an error if the provided trace info or proof options are not consistent with the
+    /// verification key.
+    pub fn from_verification_key(
+        vk: &FibVerificationKey,
+        trace_info: TraceInfo,
+        pub_inputs: AccelBaseElementRisc0<A>,
+        options: ProofOptions,
+    ) -> Result<Self, VerificationKeyError> {
+        vk.check_trace_info(&trace_info, &options)?;
+
+        let trace_length = trace_info.length();
+        let lde_domain_size = trace_length * options.blowup_factor();
+        let context = AirContext::new_precomputed(
+            trace_info,
+            vk.transition_constraint_degrees(),
+            vk.num_assertions,
+            vk.ce_blowup_factor,
+            vk.domain_generator(trace_length),
+            vk.domain_generator(lde_domain_size),
+            options,
+        );
+
+        Ok(FibAir {
+            context,
+            result: pub_inputs,
+        })
+    }
+}
+
 impl<A: NativeMontMul> Air for FibAir<A> {
     type BaseField = AccelBaseElementRisc0<A>;
     type PublicInputs = AccelBaseElementRisc0<A>;
@@ -28,13 +73,13 @@ impl<A: NativeMontMul> Air for FibAir<A> {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     fn new(trace_info: TraceInfo, pub_inputs: Self::BaseField, options: ProofOptions) -> Self {
-        let degrees = vec![
-            TransitionConstraintDegree::new(1),
-            TransitionConstraintDegree::new(1),
-        ];
+        let degrees = TRANSITION_CONSTRAINT_DEGREES
+            .iter()
+            .map(|&degree| TransitionConstraintDegree::new(degree))
+            .collect();
         assert_eq!(TRACE_WIDTH, trace_info.width());
         FibAir {
-            context: AirContext::new(trace_info, degrees, 3, options),
+            context: AirContext::new(trace_info, degrees, NUM_ASSERTIONS, options),
             result: pub_inputs,
         }
     }
@@ -19,6 +19,7 @@ use super::fib_air::FibAir;
 
 pub struct FibProver<A> {
     options: ProofOptions,
+    aux_data_commitment: Option<[u8; 32]>,
     _marker: std::marker::PhantomData<A>,
 }
 
@@ -28,10 +29,18 @@ impl<A: NativeMontMul> FibProver<A> {
     pub fn new(options: ProofOptions) -> Self {
         Self {
             options,
+            aux_data_commitment: None,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// Returns this prover binding its proofs to the specified commitment to auxiliary data (see
+    /// [crate::aux_data]); the commitment follows the result in the seed of the public coin.
+    pub fn with_aux_data_commitment(mut self, commitment: [u8; 32]) -> Self {
+        self.aux_data_commitment = Some(commitment);
+        self
+    }
+
     /// Builds an execution trace for computing a Fibonacci sequence of the specified length such
     /// that each row advances the sequence by 2 terms.
     pub fn build_trace(&self, sequence_length: usize) -> TraceTable<AccelBaseElementRisc0<A>> {
@@ -69,4 +78,9 @@ impl<A: NativeMontMul> Prover for FibProver<A> {
     fn options(&self) -> &ProofOptions {
         &self.options
     }
+
+    fn get_seed_extension(&self, _trace: &Self::Trace) -> Vec<u8> {
+        self.aux_data_commitment
+            .map_or_else(Vec::new, |c| c.to_vec())
+    }
 }
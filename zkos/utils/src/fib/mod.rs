@@ -1,3 +1,5 @@
 pub mod example;
+pub mod fib128;
 pub mod fib_air;
 pub mod fib_prover;
+pub mod vk;
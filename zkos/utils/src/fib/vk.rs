@@ -0,0 +1,301 @@
+//! Constant verification key of the Fibonacci AIR.
+//!
+//! The verification key describes the constraint composition structure of
+//! [FibAir](super::fib_air::FibAir): the shape of its execution trace, the degrees of its
+//! transition constraints, the blowup factor needed to evaluate them, and the generators of all
+//! domains the AIR may be instantiated over. The key is computed ahead of time (the verifier guest
+//! computes it in its build script) and is embedded into the guest as a constant, so that the AIR
+//! setup does not need to be recomputed for every verified proof.
+
+use super::fib_air::{NUM_ASSERTIONS, TRACE_WIDTH, TRANSITION_CONSTRAINT_DEGREES};
+use core::fmt;
+use winter_air::{ProofOptions, TraceInfo, TransitionConstraintDegree};
+use winter_math::{
+    fields::f64_risc0::{AccelBaseElementRisc0, NativeMontMul},
+    StarkField,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of domain generators in a verification key; the generator at index `i` generates the
+/// domain of size 2^(i + 1).
+pub const NUM_DOMAIN_GENERATORS: usize = 32;
+
+// VERIFICATION KEY
+// ================================================================================================
+
+/// Precomputed constraint composition structure of the Fibonacci AIR.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FibVerificationKey {
+    /// Width of the execution trace.
+    pub trace_width: usize,
+    /// Degrees of the transition constraints.
+    pub constraint_degrees: [usize; TRANSITION_CONSTRAINT_DEGREES.len()],
+    /// Number of assertions against the execution trace.
+    pub num_assertions: usize,
+    /// Blowup factor needed to evaluate the transition constraints.
+    pub ce_blowup_factor: usize,
+    /// Generators of the domains of size 2^1 through 2^32 in Montgomery form.
+    pub domain_generators: [u64; NUM_DOMAIN_GENERATORS],
+}
+
+impl FibVerificationKey {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes the verification key of the Fibonacci AIR.
+    ///
+    /// This is expected to be done ahead of time, since computing the domain generators requires
+    /// a number of field exponentiations.
+    pub fn build<A: NativeMontMul>() -> Self {
+        let ce_blowup_factor = TRANSITION_CONSTRAINT_DEGREES
+            .iter()
+            .map(|&degree| TransitionConstraintDegree::new(degree).min_blowup_factor())
+            .fold(0, usize::max);
+
+        let mut domain_generators = [0; NUM_DOMAIN_GENERATORS];
+        for (i, generator) in domain_generators.iter_mut().enumerate() {
+            *generator = AccelBaseElementRisc0::<A>::get_root_of_unity(i as u32 + 1).inner();
+        }
+
+        Self {
+            trace_width: TRACE_WIDTH,
+            constraint_degrees: TRANSITION_CONSTRAINT_DEGREES,
+            num_assertions: NUM_ASSERTIONS,
+            ce_blowup_factor,
+            domain_generators,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the degrees of the transition constraints.
+    pub fn transition_constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        self.constraint_degrees
+            .iter()
+            .map(|&degree| TransitionConstraintDegree::new(degree))
+            .collect()
+    }
+
+    /// Returns the generator of the domain of the specified size.
+    ///
+    /// # Panics
+    /// Panics if `domain_size` is not a power of two, is smaller than 2, or is greater than 2^32.
+    pub fn domain_generator<A: NativeMontMul>(
+        &self,
+        domain_size: usize,
+    ) -> AccelBaseElementRisc0<A> {
+        assert!(
+            domain_size.is_power_of_two() && domain_size > 1,
+            "domain size must be a power of two greater than one, but was {}",
+            domain_size
+        );
+        let generator = self.domain_generators[domain_size.trailing_zeros() as usize - 1];
+        AccelBaseElementRisc0::from_mont(generator)
+    }
+
+    // TRACE INFO VALIDATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that an AIR described by this verification key can be instantiated for the
+    /// provided trace info and proof options.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The trace info describes a multi-segment trace, or a trace of a different width.
+    /// - The trace length is not a power of two, or is smaller than the minimum trace length.
+    /// - The blowup factor is too small to evaluate the transition constraints.
+    /// - The LDE domain is larger than the largest domain covered by the verification key.
+    pub fn check_trace_info(
+        &self,
+        trace_info: &TraceInfo,
+        options: &ProofOptions,
+    ) -> Result<(), VerificationKeyError> {
+        if trace_info.is_multi_segment() {
+            return Err(VerificationKeyError::MultiSegmentTrace);
+        }
+        if trace_info.width() != self.trace_width {
+            return Err(VerificationKeyError::TraceWidthMismatch(
+                self.trace_width,
+                trace_info.width(),
+            ));
+        }
+
+        let trace_length = trace_info.length();
+        if !trace_length.is_power_of_two() || trace_length < TraceInfo::MIN_TRACE_LENGTH {
+            return Err(VerificationKeyError::InvalidTraceLength(trace_length));
+        }
+        if options.blowup_factor() < self.ce_blowup_factor {
+            return Err(VerificationKeyError::BlowupFactorTooSmall(
+                self.ce_blowup_factor,
+                options.blowup_factor(),
+            ));
+        }
+
+        let max_domain_size = 1u64 << NUM_DOMAIN_GENERATORS;
+        let lde_domain_size = trace_length as u64 * options.blowup_factor() as u64;
+        if lde_domain_size > max_domain_size {
+            return Err(VerificationKeyError::DomainTooLarge(lde_domain_size));
+        }
+
+        Ok(())
+    }
+
+    // CODE GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a Rust declaration of a constant with the specified name holding this
+    /// verification key; this is used to embed the key into the verifier guest at build time.
+    pub fn to_const_declaration(&self, name: &str) -> String {
+        let domain_generators = self
+            .domain_generators
+            .iter()
+            .map(|generator| format!("{:#018x}", generator))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "pub const {}: utils::fib::vk::FibVerificationKey = \
+            utils::fib::vk::FibVerificationKey {{ \
+            trace_width: {}, \
+            constraint_degrees: {:?}, \
+            num_assertions: {}, \
+            ce_blowup_factor: {}, \
+            domain_generators: [{}] }};\n",
+            name,
+            self.trace_width,
+            self.constraint_degrees,
+            self.num_assertions,
+            self.ce_blowup_factor,
+            domain_generators
+        )
+    }
+}
+
+// VERIFICATION KEY ERROR
+// ================================================================================================
+
+/// Reasons why an AIR cannot be instantiated from a verification key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerificationKeyError {
+    /// The trace info describes a trace with auxiliary segments.
+    MultiSegmentTrace,
+    /// The trace width (second value) is different from the width in the key (first value).
+    TraceWidthMismatch(usize, usize),
+    /// The trace length is not a power of two, or is smaller than the minimum trace length.
+    InvalidTraceLength(usize),
+    /// The blowup factor (second value) is smaller than the minimum blowup factor (first value).
+    BlowupFactorTooSmall(usize, usize),
+    /// The LDE domain size exceeds the largest domain covered by the key.
+    DomainTooLarge(u64),
+}
+
+impl fmt::Display for VerificationKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MultiSegmentTrace => {
+                write!(
+                    f,
+                    "multi-segment traces are not supported by the verification key"
+                )
+            }
+            Self::TraceWidthMismatch(expected, actual) => {
+                write!(f, "expected trace width {}, but was {}", expected, actual)
+            }
+            Self::InvalidTraceLength(length) => {
+                write!(f, "trace length {} is not a valid trace length", length)
+            }
+            Self::BlowupFactorTooSmall(expected, actual) => {
+                write!(
+                    f,
+                    "blowup factor too small; expected at least {}, but was {}",
+                    expected, actual
+                )
+            }
+            Self::DomainTooLarge(size) => {
+                write!(
+                    f,
+                    "LDE domain of size {} is not covered by the verification key",
+                    size
+                )
+            }
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{FibVerificationKey, VerificationKeyError};
+    use crate::fib::fib_air::{FibAir, TRACE_WIDTH};
+    use winter_air::{Air, FieldExtension, HashFunction, ProofOptions, TraceInfo, TraceLayout};
+    use winter_math::fields::f64_risc0::{AccelBaseElementRisc0, DefaultNativeMul};
+    use winter_math::FieldElement;
+
+    type B = AccelBaseElementRisc0<DefaultNativeMul>;
+
+    fn options(blowup_factor: usize) -> ProofOptions {
+        ProofOptions::new(
+            27,
+            blowup_factor,
+            0,
+            HashFunction::Sha2_256,
+            FieldExtension::Quadratic,
+            8,
+            256,
+        )
+    }
+
+    #[test]
+    fn verification_key_matches_air_setup() {
+        let vk = FibVerificationKey::build::<DefaultNativeMul>();
+        for (trace_length, blowup_factor) in [(8, 2), (1024, 8), (1 << 20, 16)] {
+            let trace_info = TraceInfo::new(TRACE_WIDTH, trace_length);
+            let expected =
+                FibAir::<DefaultNativeMul>::new(trace_info.clone(), B::ONE, options(blowup_factor));
+            let air =
+                FibAir::from_verification_key(&vk, trace_info, B::ONE, options(blowup_factor))
+                    .unwrap();
+            assert!(expected.context() == air.context());
+            assert_eq!(expected.get_assertions(), air.get_assertions());
+        }
+    }
+
+    #[test]
+    fn check_trace_info_rejects_mismatched_traces() {
+        let vk = FibVerificationKey::build::<DefaultNativeMul>();
+        assert_eq!(
+            Ok(()),
+            vk.check_trace_info(&TraceInfo::new(TRACE_WIDTH, 1024), &options(8))
+        );
+
+        // traces of a different width are rejected, and so are traces with auxiliary segments
+        assert_eq!(
+            Err(VerificationKeyError::TraceWidthMismatch(
+                TRACE_WIDTH,
+                TRACE_WIDTH + 1
+            )),
+            vk.check_trace_info(&TraceInfo::new(TRACE_WIDTH + 1, 1024), &options(8))
+        );
+        let multi_segment =
+            TraceInfo::new_multi_segment(TraceLayout::new(TRACE_WIDTH, [1], [1]), 1024, vec![]);
+        assert_eq!(
+            Err(VerificationKeyError::MultiSegmentTrace),
+            vk.check_trace_info(&multi_segment, &options(8))
+        );
+
+        // traces too long for their LDE domain to be covered by the key are rejected
+        assert_eq!(
+            Ok(()),
+            vk.check_trace_info(&TraceInfo::new(TRACE_WIDTH, 1 << 29), &options(8))
+        );
+        assert_eq!(
+            Err(VerificationKeyError::DomainTooLarge(1 << 33)),
+            vk.check_trace_info(&TraceInfo::new(TRACE_WIDTH, 1 << 30), &options(8))
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Hashers the verifier guests can verify proofs with.
+//!
+//! The transcript replay shared by the guests and the host (see [crate::transcript]) is generic
+//! over these hashers, so that the host reproduces the transcript exactly as a guest hashes it.
+
+use crate::poseidon::Poseidon64_256;
+use winter_math::fields::f64::BaseElement;
+use winter_math::{FieldElement, StarkField};
+use winter_verifier::crypto::hashers::{
+    Blake3_256, Keccak256, Rp64_256, Sha2_256, Sha2_384, Sha2_512, Sha384HasherT, Sha512HasherT,
+    ShaHasherT,
+};
+use winter_verifier::crypto::ElementHasher;
+
+// GUEST HASHER
+// ================================================================================================
+
+/// Hashers the verifier guests can verify proofs with.
+///
+/// Out-of-domain frames are hashed with the main and auxiliary segments absorbed one after
+/// another; the SHA2, BLAKE3, Keccak, RPO, and Poseidon hashers provide this as an inherent method,
+/// which generic verification code cannot call directly.
+pub trait GuestHasher: ElementHasher {
+    /// Returns a hash of the concatenation of `first` and `second` element sequences.
+    fn hash_split_elements<E: FieldElement<BaseField = Self::BaseField>>(
+        first: &[E],
+        second: &[E],
+    ) -> Self::Digest;
+}
+
+impl<B: StarkField, S: ShaHasherT> GuestHasher for Sha2_256<B, S> {
+    fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> Self::Digest {
+        Sha2_256::<B, S>::hash_split_elements(first, second)
+    }
+}
+
+impl<B: StarkField, S: Sha512HasherT> GuestHasher for Sha2_512<B, S> {
+    fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> Self::Digest {
+        Sha2_512::<B, S>::hash_split_elements(first, second)
+    }
+}
+
+impl<B: StarkField, S: Sha384HasherT> GuestHasher for Sha2_384<B, S> {
+    fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> Self::Digest {
+        Sha2_384::<B, S>::hash_split_elements(first, second)
+    }
+}
+
+/// The guests have no BLAKE3 accelerator, and thus, BLAKE3 digests are computed in software; this
+/// is still cheaper than regenerating proofs of Winterfell's default hasher with SHA2.
+impl<B: StarkField> GuestHasher for Blake3_256<B> {
+    fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> Self::Digest {
+        Blake3_256::<B>::hash_split_elements(first, second)
+    }
+}
+
+/// The guests have no Keccak accelerator either; Keccak-256 is used for proofs whose commitments
+/// are eventually checked on EVM chains, where it is the native hash function.
+impl<B: StarkField> GuestHasher for Keccak256<B> {
+    fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> Self::Digest {
+        Keccak256::<B>::hash_split_elements(first, second)
+    }
+}
+
+/// RPO is defined over the 64-bit field only, and is computed in software by the guests; it is
+/// used to verify Miden-native proofs without re-proving them with a SHA2 hasher.
+impl GuestHasher for Rp64_256 {
+    fn hash_split_elements<E: FieldElement<BaseField = BaseElement>>(
+        first: &[E],
+        second: &[E],
+    ) -> Self::Digest {
+        Rp64_256::hash_split_elements(first, second)
+    }
+}
+
+/// Poseidon is computed with the field arithmetic of the guest, and thus, with the accelerated
+/// multiplication of the guests which have one; it is used to experiment with SNARK-friendly
+/// transcripts.
+impl<B: StarkField<PositiveInteger = u64>> GuestHasher for Poseidon64_256<B> {
+    fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> Self::Digest {
+        Poseidon64_256::<B>::hash_split_elements(first, second)
+    }
+}
@@ -0,0 +1,217 @@
+//! Deterministic checks of the heap the verifier guests estimate for their inputs.
+//!
+//! A guest accounts for the heap estimated for every phase of the verification in an
+//! [EstimatedHeapBudget] before the phase starts. The estimates are derived from the sizes of the
+//! inputs and the proof options only (see [crate::estimate]), and thus, whether a proof exceeds
+//! the cap does not depend on the allocator. When the cap would be exceeded, the guest commits a
+//! [HeapEstimateExceeded] failure to its journal and skips the rest of the verification.
+//!
+//! This is a check of the estimates only. The allocator is not instrumented, and a guest which
+//! allocates more than estimated still aborts with the error of the allocator once its heap is
+//! exhausted.
+
+use crate::aux_input::aux_input_heap_size;
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+use rkyv::{Archive, Deserialize, Serialize};
+use serde::{Deserialize as sDeserialize, Serialize as sSerialize};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Cap on the estimated heap of guests built without the `ZKOS_GUEST_HEAP_ESTIMATE_CAP` environment
+/// variable, in bytes.
+pub const DEFAULT_HEAP_ESTIMATE_CAP: usize = 64 * 1024 * 1024;
+
+// HEAP PHASE
+// ================================================================================================
+
+/// Phase of the verification in a guest for which estimated heap is reserved.
+#[derive(
+    Archive, Deserialize, Serialize, sSerialize, sDeserialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+pub enum HeapPhase {
+    /// Decompression of the aux input.
+    AuxInput,
+    /// Deserialization and verification of a proof.
+    Verification,
+}
+
+impl fmt::Display for HeapPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AuxInput => write!(f, "aux input decompression"),
+            Self::Verification => write!(f, "proof verification"),
+        }
+    }
+}
+
+// ESTIMATED HEAP BUDGET
+// ================================================================================================
+
+/// Heap estimated for the phases of the verification a guest has reserved so far, and the cap the
+/// estimates must not exceed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EstimatedHeapBudget {
+    cap: usize,
+    reserved: usize,
+}
+
+impl EstimatedHeapBudget {
+    /// Returns an empty budget with the specified cap in bytes.
+    pub fn new(cap: usize) -> Self {
+        Self { cap, reserved: 0 }
+    }
+
+    /// Returns the cap of this budget in bytes.
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the number of estimated bytes reserved so far.
+    pub fn reserved(&self) -> usize {
+        self.reserved
+    }
+
+    /// Reserves the specified estimate of the heap needed by the specified phase, in bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the reservation would exceed the cap; in this case, nothing is
+    /// reserved.
+    pub fn reserve_estimate(
+        &mut self,
+        phase: HeapPhase,
+        bytes: usize,
+    ) -> Result<(), HeapEstimateExceeded> {
+        match self.reserved.checked_add(bytes) {
+            Some(reserved) if reserved <= self.cap => {
+                self.reserved = reserved;
+                Ok(())
+            }
+            _ => Err(HeapEstimateExceeded {
+                phase,
+                requested: bytes as u64,
+                reserved: self.reserved as u64,
+                cap: self.cap as u64,
+            }),
+        }
+    }
+}
+
+/// Returns the estimated number of heap bytes needed to decode the provided aux input (see
+/// [aux_input_heap_size]).
+///
+/// # Errors
+/// Returns an error if the header of the aux input cannot be decoded; a guest commits this error
+/// to its journal, as it cannot tell how much heap the aux input needs.
+pub fn aux_input_heap_estimate(input: &[u8]) -> Result<usize, ErrorChain> {
+    aux_input_heap_size(input).map_err(|e| {
+        ErrorChain::from(e).context(
+            ErrorKind::HeapEstimate,
+            "failed to estimate the heap needed to decode the aux input",
+        )
+    })
+}
+
+// HEAP ESTIMATE EXCEEDED
+// ================================================================================================
+
+/// Failure committed by a guest when the heap estimated for a phase of the verification exceeds
+/// the heap left under the cap.
+#[derive(
+    Archive, Deserialize, Serialize, sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq,
+)]
+pub struct HeapEstimateExceeded {
+    /// Phase whose estimate exceeds the heap which is left.
+    pub phase: HeapPhase,
+    /// Number of bytes estimated for the phase.
+    pub requested: u64,
+    /// Number of bytes estimated for the previous phases.
+    pub reserved: u64,
+    /// Heap cap of the guest.
+    pub cap: u64,
+}
+
+impl fmt::Display for HeapEstimateExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is estimated to need {} bytes of heap, but only {} of {} bytes are left",
+            self.phase,
+            self.requested,
+            self.cap.saturating_sub(self.reserved),
+            self.cap
+        )
+    }
+}
+
+impl From<HeapEstimateExceeded> for ErrorChain {
+    fn from(error: HeapEstimateExceeded) -> Self {
+        ErrorChain::new(ErrorKind::HeapEstimate, error)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{aux_input_heap_estimate, EstimatedHeapBudget, HeapEstimateExceeded, HeapPhase};
+    use crate::aux_input::{encode_aux_input, AuxInputCompression};
+    use errors::ErrorKind;
+
+    #[test]
+    fn estimates_are_reserved_up_to_the_cap() {
+        let mut budget = EstimatedHeapBudget::new(100);
+        assert_eq!(Ok(()), budget.reserve_estimate(HeapPhase::AuxInput, 60));
+        assert_eq!(Ok(()), budget.reserve_estimate(HeapPhase::Verification, 40));
+        assert_eq!(100, budget.reserved());
+
+        // an estimate which exceeds the heap left is not reserved
+        assert_eq!(
+            Err(HeapEstimateExceeded {
+                phase: HeapPhase::Verification,
+                requested: 1,
+                reserved: 100,
+                cap: 100,
+            }),
+            budget.reserve_estimate(HeapPhase::Verification, 1)
+        );
+        assert_eq!(100, budget.reserved());
+
+        // so is an estimate which overflows the reservations
+        let mut budget = EstimatedHeapBudget::new(usize::MAX);
+        budget.reserve_estimate(HeapPhase::AuxInput, 1).unwrap();
+        assert!(budget
+            .reserve_estimate(HeapPhase::Verification, usize::MAX)
+            .is_err());
+        assert_eq!(1, budget.reserved());
+    }
+
+    #[test]
+    fn aux_input_estimates() {
+        // uncompressed payloads are accessed in place, and compressed ones are decompressed into
+        // a buffer of the length of the payload
+        let payload = [7u8; 1000];
+        let raw = encode_aux_input(&payload, AuxInputCompression::None);
+        assert_eq!(Ok(0), aux_input_heap_estimate(&raw));
+        let compressed = encode_aux_input(&payload, AuxInputCompression::Lz4);
+        assert_eq!(Ok(payload.len()), aux_input_heap_estimate(&compressed));
+    }
+
+    #[test]
+    fn aux_input_estimate_of_invalid_header() {
+        // the heap of an aux input whose header is corrupted cannot be estimated, which is
+        // reported as a typed failure instead of aborting the guest
+        let mut input = encode_aux_input(&[7u8; 100], AuxInputCompression::Lz4)
+            .as_slice()
+            .to_vec();
+        input[0] ^= 1;
+        let error = aux_input_heap_estimate(&input).unwrap_err();
+        assert_eq!(ErrorKind::HeapEstimate, error.kind());
+        assert_eq!(ErrorKind::AuxInput, error.root_cause().kind);
+
+        // so is an aux input which is too short to hold a header
+        assert!(aux_input_heap_estimate(&input[..8]).is_err());
+    }
+}
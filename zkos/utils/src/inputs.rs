@@ -1,16 +1,31 @@
-use miden_air::PublicInputs;
+use crate::journal::{statement_digest, trace_info_bytes};
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+use miden_air::{ProcessorAir, PublicInputs};
+use miden_core::{
+    Felt, StarkField, AUX_TRACE_RAND_ELEMENTS, AUX_TRACE_WIDTH, MIN_TRACE_LEN, TRACE_WIDTH,
+};
 use rkyv::{Archive, Deserialize, Serialize};
 use serde::{Deserialize as sDeserialize, Serialize as sSerialize};
-use winter_air::{ProofOptions, TraceInfo};
+use winter_air::{AuxTraceRandElements, ProofOptions, TraceInfo, TraceLayout};
 use winter_math::FieldElement;
+use winter_prover::crypto::hashers::ShaHasherT;
 use winter_prover::crypto::ElementHasher;
-use winter_verifier::VerifierChannel;
+use winter_verifier::{Serializable, VerifierChannel};
+
+// Archived inputs are accessed in place by the guests, and rkyv stores integers and the internal
+// representation of field elements in native byte order; inputs archived by a big-endian host
+// would thus be misread by the little-endian guest.
+#[cfg(not(target_endian = "little"))]
+compile_error!("guest inputs can be archived only on little-endian targets");
 
 #[derive(Archive, Deserialize, Serialize)]
 pub struct MidenRiscInput<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
     pub context: Vec<u8>,
+    pub trace_segments: Vec<TraceSegmentInfo>,
     pub verifier_channel: VerifierChannel<E, H>,
-    pub inv_nondet: Vec<(E, E)>,
+    pub inv_nondet: Vec<(E::BaseField, E::BaseField)>,
+    pub inv_nondet_quad: Vec<([E::BaseField; 2], [E::BaseField; 2])>,
 }
 
 #[derive(sSerialize, sDeserialize, Debug)]
@@ -20,12 +35,153 @@ pub struct MidenAirInput {
     pub proof_options: ProofOptions,
 }
 
+impl MidenAirInput {
+    /// Returns the statement digest which the recursive guest commits when it verifies a single
+    /// proof with these AIR inputs and the provided verifier context.
+    ///
+    /// The digest covers the program hash and the stack inputs and outputs of the public inputs,
+    /// followed by the trace info the AIR was instantiated from; thus, a relying party which
+    /// recomputes it from the execution it expects learns from the journal that this execution
+    /// was proven.
+    pub fn statement_digest<S: ShaHasherT>(&self, context: &[u8]) -> [u8; 32] {
+        statement_digest::<S>(&[
+            context,
+            self.public_inputs.to_bytes().as_slice(),
+            trace_info_bytes(&self.trace_info).as_slice(),
+        ])
+    }
+
+    /// Checks that the trace info describes an execution trace of the Miden processor which the
+    /// processor AIR can be instantiated from under the proof options; the constructor of the AIR
+    /// panics on most of these mismatches, and the constraints index into the trace by the column
+    /// layout of the processor.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The main or the auxiliary segment of the trace differs from the trace layout of the
+    ///   processor.
+    /// - The trace length is not a power of two, or is smaller than the minimum trace length.
+    /// - The blowup factor is too small to evaluate the transition constraints.
+    /// - The LDE domain is larger than the largest domain of the field.
+    pub fn check_trace_info(&self) -> Result<(), MidenTraceInfoError> {
+        let layout = self.trace_info.layout();
+        if layout.main_trace_width() != TRACE_WIDTH {
+            return Err(MidenTraceInfoError::MainTraceWidth(
+                TRACE_WIDTH,
+                layout.main_trace_width(),
+            ));
+        }
+        if layout.num_aux_segments() != 1 {
+            return Err(MidenTraceInfoError::NumAuxSegments(
+                layout.num_aux_segments(),
+            ));
+        }
+        if layout.get_aux_segment_width(0) != AUX_TRACE_WIDTH {
+            return Err(MidenTraceInfoError::AuxTraceWidth(
+                AUX_TRACE_WIDTH,
+                layout.get_aux_segment_width(0),
+            ));
+        }
+        if layout.get_aux_segment_rand_elements(0) != AUX_TRACE_RAND_ELEMENTS {
+            return Err(MidenTraceInfoError::AuxRandElements(
+                AUX_TRACE_RAND_ELEMENTS,
+                layout.get_aux_segment_rand_elements(0),
+            ));
+        }
+
+        let trace_length = self.trace_info.length();
+        if !trace_length.is_power_of_two() || trace_length < MIN_TRACE_LEN {
+            return Err(MidenTraceInfoError::InvalidTraceLength(trace_length));
+        }
+        let blowup_factor = self.proof_options.blowup_factor();
+        let min_blowup_factor = ProcessorAir::min_blowup_factor();
+        if blowup_factor < min_blowup_factor {
+            return Err(MidenTraceInfoError::BlowupFactorTooSmall(
+                min_blowup_factor,
+                blowup_factor,
+            ));
+        }
+
+        let max_domain_size = 1u64 << Felt::TWO_ADICITY;
+        let lde_domain_size = trace_length as u64 * blowup_factor as u64;
+        if lde_domain_size > max_domain_size {
+            return Err(MidenTraceInfoError::DomainTooLarge(lde_domain_size));
+        }
+        Ok(())
+    }
+}
+
+/// Mismatch between the trace info of a packaged Miden proof and the processor AIR.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MidenTraceInfoError {
+    /// The width of the main trace segment (second value) differs from the width of the main
+    /// trace of the processor (first value).
+    MainTraceWidth(usize, usize),
+    /// The trace has the specified number of auxiliary segments instead of one.
+    NumAuxSegments(usize),
+    /// The width of the auxiliary trace segment (second value) differs from the width of the
+    /// auxiliary trace of the processor (first value).
+    AuxTraceWidth(usize, usize),
+    /// The number of random elements of the auxiliary trace segment (second value) differs from
+    /// the number of random elements of the processor (first value).
+    AuxRandElements(usize, usize),
+    /// The trace length is not a power of two, or is smaller than the minimum trace length.
+    InvalidTraceLength(usize),
+    /// The blowup factor (second value) is smaller than the blowup factor required by the
+    /// transition constraints (first value).
+    BlowupFactorTooSmall(usize, usize),
+    /// The LDE domain of the specified size is larger than the largest domain of the field.
+    DomainTooLarge(u64),
+}
+
+impl fmt::Display for MidenTraceInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MainTraceWidth(expected, actual) => write!(
+                f,
+                "main trace must be {} columns wide, but was {}",
+                expected, actual
+            ),
+            Self::NumAuxSegments(actual) => write!(
+                f,
+                "trace must have one auxiliary segment, but had {}",
+                actual
+            ),
+            Self::AuxTraceWidth(expected, actual) => write!(
+                f,
+                "auxiliary trace must be {} columns wide, but was {}",
+                expected, actual
+            ),
+            Self::AuxRandElements(expected, actual) => write!(
+                f,
+                "auxiliary trace must be built from {} random elements, but was built from {}",
+                expected, actual
+            ),
+            Self::InvalidTraceLength(length) => write!(f, "trace length {} is invalid", length),
+            Self::BlowupFactorTooSmall(expected, actual) => write!(
+                f,
+                "blowup factor must be at least {}, but was {}",
+                expected, actual
+            ),
+            Self::DomainTooLarge(size) => write!(f, "LDE domain of size {} is too large", size),
+        }
+    }
+}
+
+impl From<MidenTraceInfoError> for ErrorChain {
+    fn from(error: MidenTraceInfoError) -> Self {
+        ErrorChain::new(ErrorKind::Verification, error)
+    }
+}
+
 #[derive(sSerialize, sDeserialize, Debug)]
 pub struct Output<E: FieldElement> {
     pub ood_constraint_evaluation_1: E,
     pub ood_constraint_evaluation_2: E,
 }
 
+/// Shape and options of a packaged Fibonacci proof; these do not depend on the field of the proof,
+/// and thus, they describe proofs over the 64-bit field as well as ones over the 128-bit field.
 #[derive(sSerialize, sDeserialize, Debug)]
 pub struct FibAirInput {
     pub trace_info: TraceInfo,
@@ -36,7 +192,455 @@ pub struct FibAirInput {
 pub struct FibRiscInput<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
     pub result: E::BaseField,
     pub context: Vec<u8>,
+    pub trace_segments: Vec<TraceSegmentInfo>,
+    /// Positions of the trace and constraint openings of the channel, in the order of the
+    /// openings; the guest looks up the positions it draws among these (see [opening_indexes]).
+    pub query_positions: Vec<u64>,
     pub verifier_channel: VerifierChannel<E, H>,
     pub inv_nondet: Vec<(u64, u64)>,
     pub inv_nondet_quad: Vec<([u64; 2], [u64; 2])>,
+    /// Commitment to auxiliary data of an application which the proof was generated for, if any;
+    /// it follows the result in the seed of the public coin (see [crate::aux_data]).
+    pub aux_data_commitment: Option<[u8; 32]>,
+}
+
+/// Packaged Fibonacci proof over the 128-bit field; unlike [FibRiscInput], it carries no inverse
+/// hints, since the guests compute inverses in the 128-bit field directly.
+#[derive(Archive, Deserialize, Serialize)]
+pub struct Fib128RiscInput<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+    pub result: E::BaseField,
+    pub context: Vec<u8>,
+    pub trace_segments: Vec<TraceSegmentInfo>,
+    pub verifier_channel: VerifierChannel<E, H>,
+}
+
+// TRACE SEGMENTS
+// ================================================================================================
+
+/// Shape of a segment of the execution trace of a packaged proof.
+///
+/// The verifier channel carries the commitments to the trace segments as a bare list, and a guest
+/// would otherwise associate the random elements it draws with the segments in the order of the
+/// commitments. Packaging the shape of every segment next to the commitments lets a guest check
+/// that the commitments were packaged for the trace layout of the AIR it verifies against before
+/// it draws any random elements.
+#[derive(
+    Archive, Deserialize, Serialize, sSerialize, sDeserialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+pub struct TraceSegmentInfo {
+    /// Number of columns in the segment.
+    pub width: u32,
+    /// Number of random elements drawn before the segment is committed to; this is zero for the
+    /// main segment.
+    pub num_rand_elements: u32,
+}
+
+/// Returns the shapes of all segments of the provided trace layout, starting with the main
+/// segment.
+pub fn trace_segment_infos(layout: &TraceLayout) -> Vec<TraceSegmentInfo> {
+    let main_segment = TraceSegmentInfo {
+        width: layout.main_trace_width() as u32,
+        num_rand_elements: 0,
+    };
+    let aux_segments = (0..layout.num_aux_segments()).map(|i| TraceSegmentInfo {
+        width: layout.get_aux_segment_width(i) as u32,
+        num_rand_elements: layout.get_aux_segment_rand_elements(i) as u32,
+    });
+    core::iter::once(main_segment).chain(aux_segments).collect()
+}
+
+/// Checks that the provided segment shapes match the provided trace layout, and that there is a
+/// commitment for every segment.
+///
+/// # Errors
+/// Returns an error if the number of segments or commitments differs from the number of segments
+/// of the layout, or if the shape of any segment differs from the shape of the same segment of
+/// the layout.
+pub fn check_trace_segments(
+    segments: &[TraceSegmentInfo],
+    layout: &TraceLayout,
+    num_commitments: usize,
+) -> Result<(), TraceSegmentMismatch> {
+    let expected = trace_segment_infos(layout);
+    if segments.len() != expected.len() {
+        return Err(TraceSegmentMismatch::NumSegments(
+            expected.len(),
+            segments.len(),
+        ));
+    }
+    if num_commitments != expected.len() {
+        return Err(TraceSegmentMismatch::NumCommitments(
+            expected.len(),
+            num_commitments,
+        ));
+    }
+
+    for (i, (segment, expected)) in segments.iter().zip(expected.iter()).enumerate() {
+        if segment.width != expected.width {
+            return Err(TraceSegmentMismatch::Width(
+                i,
+                expected.width,
+                segment.width,
+            ));
+        }
+        if segment.num_rand_elements != expected.num_rand_elements {
+            return Err(TraceSegmentMismatch::NumRandElements(
+                i,
+                expected.num_rand_elements,
+                segment.num_rand_elements,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the random elements drawn for the auxiliary trace segment with the specified index
+/// are fresh draws from the public coin, i.e., that none of them repeats an element drawn for the
+/// same segment or for an earlier auxiliary segment, whose elements are held by `drawn`.
+///
+/// The coin is reseeded with the commitment to every segment before the elements of the next
+/// segment are drawn, so a repeated element means that the randomness was reused rather than
+/// drawn in the right order, e.g., by a packer which skipped a reseed; the auxiliary columns
+/// would then be built from correlated randomness, which silently weakens soundness.
+///
+/// # Errors
+/// Returns an error if any of the elements was already drawn.
+pub fn check_aux_rand_elements<E: FieldElement>(
+    drawn: &AuxTraceRandElements<E>,
+    segment: usize,
+    rand_elements: &[E],
+) -> Result<(), TraceSegmentMismatch> {
+    for (i, element) in rand_elements.iter().enumerate() {
+        let reused = (0..segment)
+            .flat_map(|earlier| drawn.get_segment_elements(earlier))
+            .chain(&rand_elements[..i])
+            .any(|drawn| drawn == element);
+        if reused {
+            return Err(TraceSegmentMismatch::ReusedRandElements(segment));
+        }
+    }
+    Ok(())
+}
+
+/// Mismatch between the trace segments of a packaged proof and the trace layout of the AIR.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceSegmentMismatch {
+    /// The number of packaged segments (second value) differs from the number of segments of the
+    /// layout (first value).
+    NumSegments(usize, usize),
+    /// The number of trace commitments (second value) differs from the number of segments of the
+    /// layout (first value).
+    NumCommitments(usize, usize),
+    /// The width of the specified segment (third value) differs from the width of the segment in
+    /// the layout (second value).
+    Width(usize, u32, u32),
+    /// The number of random elements of the specified segment (third value) differs from the
+    /// number of random elements of the segment in the layout (second value).
+    NumRandElements(usize, u32, u32),
+    /// The random elements drawn for the specified auxiliary segment repeat elements which were
+    /// already drawn.
+    ReusedRandElements(usize),
+}
+
+impl fmt::Display for TraceSegmentMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NumSegments(expected, actual) => write!(
+                f,
+                "expected {} trace segments, but {} were packaged",
+                expected, actual
+            ),
+            Self::NumCommitments(expected, actual) => write!(
+                f,
+                "expected {} trace commitments, but {} were packaged",
+                expected, actual
+            ),
+            Self::Width(segment, expected, actual) => write!(
+                f,
+                "trace segment {} must be {} columns wide, but was {}",
+                segment, expected, actual
+            ),
+            Self::NumRandElements(segment, expected, actual) => write!(
+                f,
+                "trace segment {} must be built from {} random elements, but was built from {}",
+                segment, expected, actual
+            ),
+            Self::ReusedRandElements(segment) => write!(
+                f,
+                "random elements of auxiliary trace segment {} were already drawn",
+                segment
+            ),
+        }
+    }
+}
+
+impl From<TraceSegmentMismatch> for ErrorChain {
+    fn from(error: TraceSegmentMismatch) -> Self {
+        ErrorChain::new(ErrorKind::Deserialization, error)
+    }
+}
+
+// QUERY OPENINGS
+// ================================================================================================
+
+/// Returns, for every query position drawn by the verifier, the index of its opening among the
+/// openings packaged at the provided positions.
+///
+/// The query positions are derived by the verifier from its public coin, and are never read from
+/// the packaged proof. The packaged positions only tell which opening belongs to which position,
+/// so that the verifier can index into the openings by position: every drawn position must have
+/// exactly one opening, and there must be no openings at positions which were not drawn.
+///
+/// # Errors
+/// Returns an error if an opening is packaged twice for the same position, if a drawn position
+/// has no opening, or if an opening is packaged for a position which was not drawn.
+pub fn opening_indexes(
+    drawn: &[usize],
+    opened: &[u64],
+) -> Result<Vec<usize>, QueryOpeningMismatch> {
+    let mut sorted: Vec<(u64, usize)> = opened.iter().copied().zip(0..).collect();
+    sorted.sort_unstable();
+    if let Some(pair) = sorted.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        return Err(QueryOpeningMismatch::DuplicateOpening(pair[0].0));
+    }
+
+    let indexes = drawn
+        .iter()
+        .map(|&position| {
+            sorted
+                .binary_search_by_key(&(position as u64), |&(opened, _)| opened)
+                .map(|i| sorted[i].1)
+                .map_err(|_| QueryOpeningMismatch::MissingOpening(position as u64))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // every drawn position has a distinct opening, and thus, any further opening is at a position
+    // which was not drawn
+    if opened.len() > drawn.len() {
+        let mut used = vec![false; opened.len()];
+        indexes.iter().for_each(|&i| used[i] = true);
+        let unused = used
+            .iter()
+            .position(|&used| !used)
+            .expect("an opening is unused");
+        return Err(QueryOpeningMismatch::UnexpectedOpening(opened[unused]));
+    }
+    Ok(indexes)
+}
+
+/// Mismatch between the query positions drawn by a verifier and the positions of the openings of
+/// a packaged proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueryOpeningMismatch {
+    /// More than one opening is packaged for the specified position.
+    DuplicateOpening(u64),
+    /// No opening is packaged for the specified drawn position.
+    MissingOpening(u64),
+    /// An opening is packaged for the specified position, which was not drawn.
+    UnexpectedOpening(u64),
+}
+
+impl fmt::Display for QueryOpeningMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateOpening(position) => write!(
+                f,
+                "more than one opening is packaged for query position {}",
+                position
+            ),
+            Self::MissingOpening(position) => {
+                write!(f, "no opening is packaged for query position {}", position)
+            }
+            Self::UnexpectedOpening(position) => write!(
+                f,
+                "an opening is packaged for position {}, which was not queried",
+                position
+            ),
+        }
+    }
+}
+
+impl From<QueryOpeningMismatch> for ErrorChain {
+    fn from(error: QueryOpeningMismatch) -> Self {
+        ErrorChain::new(ErrorKind::Verification, error)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_aux_rand_elements, check_trace_segments, opening_indexes, trace_segment_infos,
+        MidenAirInput, MidenTraceInfoError, QueryOpeningMismatch, TraceSegmentMismatch,
+    };
+    use miden_air::{ProcessorAir, PublicInputs};
+    use miden_core::chiplets::hasher;
+    use miden_core::{AUX_TRACE_RAND_ELEMENTS, AUX_TRACE_WIDTH, TRACE_WIDTH};
+    use winter_air::{
+        AuxTraceRandElements, FieldExtension, HashFunction, ProofOptions, TraceInfo, TraceLayout,
+    };
+    use winter_math::fields::f64::BaseElement;
+
+    #[test]
+    fn miden_trace_info() {
+        let air_input = |layout: TraceLayout, length: usize, blowup_factor: usize| MidenAirInput {
+            trace_info: TraceInfo::new_multi_segment(layout, length, vec![]),
+            public_inputs: PublicInputs::new(hasher::hash_elements(&[]), vec![], vec![]),
+            proof_options: ProofOptions::new(
+                27,
+                blowup_factor,
+                16,
+                HashFunction::Sha2_256,
+                FieldExtension::Quadratic,
+                8,
+                256,
+            ),
+        };
+        let layout = || TraceLayout::new(TRACE_WIDTH, [AUX_TRACE_WIDTH], [AUX_TRACE_RAND_ELEMENTS]);
+        let blowup_factor = ProcessorAir::min_blowup_factor();
+        assert_eq!(
+            Ok(()),
+            air_input(layout(), 1024, blowup_factor).check_trace_info()
+        );
+
+        // traces of a different layout are rejected before the AIR is instantiated from them
+        let other = TraceLayout::new(
+            TRACE_WIDTH - 1,
+            [AUX_TRACE_WIDTH],
+            [AUX_TRACE_RAND_ELEMENTS],
+        );
+        assert_eq!(
+            Err(MidenTraceInfoError::MainTraceWidth(
+                TRACE_WIDTH,
+                TRACE_WIDTH - 1
+            )),
+            air_input(other, 1024, blowup_factor).check_trace_info()
+        );
+        let other = TraceLayout::new(TRACE_WIDTH, [0], [0]);
+        assert_eq!(
+            Err(MidenTraceInfoError::NumAuxSegments(0)),
+            air_input(other, 1024, blowup_factor).check_trace_info()
+        );
+        let other = TraceLayout::new(
+            TRACE_WIDTH,
+            [AUX_TRACE_WIDTH + 1],
+            [AUX_TRACE_RAND_ELEMENTS],
+        );
+        assert_eq!(
+            Err(MidenTraceInfoError::AuxTraceWidth(
+                AUX_TRACE_WIDTH,
+                AUX_TRACE_WIDTH + 1
+            )),
+            air_input(other, 1024, blowup_factor).check_trace_info()
+        );
+        let other = TraceLayout::new(TRACE_WIDTH, [AUX_TRACE_WIDTH], [1]);
+        assert_eq!(
+            Err(MidenTraceInfoError::AuxRandElements(
+                AUX_TRACE_RAND_ELEMENTS,
+                1
+            )),
+            air_input(other, 1024, blowup_factor).check_trace_info()
+        );
+
+        // so are traces of an invalid length, and options which the AIR cannot be built for
+        assert_eq!(
+            Err(MidenTraceInfoError::InvalidTraceLength(512)),
+            air_input(layout(), 512, blowup_factor).check_trace_info()
+        );
+        assert_eq!(
+            Err(MidenTraceInfoError::BlowupFactorTooSmall(
+                blowup_factor,
+                blowup_factor / 2
+            )),
+            air_input(layout(), 1024, blowup_factor / 2).check_trace_info()
+        );
+        assert_eq!(
+            Err(MidenTraceInfoError::DomainTooLarge(1 << 33)),
+            air_input(layout(), (1 << 33) / blowup_factor, blowup_factor).check_trace_info()
+        );
+    }
+
+    #[test]
+    fn trace_segments() {
+        let layout = TraceLayout::new(4, [3], [5]);
+        let segments = trace_segment_infos(&layout);
+        assert_eq!(2, segments.len());
+        assert_eq!((4, 0), (segments[0].width, segments[0].num_rand_elements));
+        assert_eq!((3, 5), (segments[1].width, segments[1].num_rand_elements));
+        assert_eq!(Ok(()), check_trace_segments(&segments, &layout, 2));
+
+        assert_eq!(
+            Err(TraceSegmentMismatch::NumCommitments(2, 1)),
+            check_trace_segments(&segments, &layout, 1)
+        );
+        assert_eq!(
+            Err(TraceSegmentMismatch::NumSegments(2, 1)),
+            check_trace_segments(&segments[..1], &layout, 2)
+        );
+
+        // segments packaged for a different layout are rejected
+        let other = trace_segment_infos(&TraceLayout::new(4, [2], [5]));
+        assert_eq!(
+            Err(TraceSegmentMismatch::Width(1, 3, 2)),
+            check_trace_segments(&other, &layout, 2)
+        );
+        let other = trace_segment_infos(&TraceLayout::new(4, [3], [1]));
+        assert_eq!(
+            Err(TraceSegmentMismatch::NumRandElements(1, 5, 1)),
+            check_trace_segments(&other, &layout, 2)
+        );
+    }
+
+    #[test]
+    fn aux_rand_elements() {
+        let elements = |values: &[u64]| -> Vec<BaseElement> {
+            values.iter().map(|&v| BaseElement::new(v)).collect()
+        };
+        let mut drawn = AuxTraceRandElements::new();
+        assert_eq!(
+            Ok(()),
+            check_aux_rand_elements(&drawn, 0, &elements(&[1, 2]))
+        );
+        assert_eq!(
+            Err(TraceSegmentMismatch::ReusedRandElements(0)),
+            check_aux_rand_elements(&drawn, 0, &elements(&[1, 1]))
+        );
+
+        // elements of a later segment must differ from the elements of the earlier ones
+        drawn.add_segment_elements(elements(&[1, 2]));
+        assert_eq!(
+            Ok(()),
+            check_aux_rand_elements(&drawn, 1, &elements(&[3, 4]))
+        );
+        assert_eq!(
+            Err(TraceSegmentMismatch::ReusedRandElements(1)),
+            check_aux_rand_elements(&drawn, 1, &elements(&[3, 2]))
+        );
+    }
+
+    #[test]
+    fn query_openings() {
+        // openings are indexed by position, regardless of the order they were packaged in
+        assert_eq!(Ok(vec![0, 1, 2]), opening_indexes(&[7, 3, 9], &[7, 3, 9]));
+        assert_eq!(Ok(vec![2, 0, 1]), opening_indexes(&[7, 3, 9], &[3, 9, 7]));
+
+        assert_eq!(
+            Err(QueryOpeningMismatch::MissingOpening(9)),
+            opening_indexes(&[7, 3, 9], &[7, 3])
+        );
+        assert_eq!(
+            Err(QueryOpeningMismatch::MissingOpening(9)),
+            opening_indexes(&[7, 3, 9], &[7, 3, 8])
+        );
+        assert_eq!(
+            Err(QueryOpeningMismatch::UnexpectedOpening(8)),
+            opening_indexes(&[7, 3], &[8, 7, 3])
+        );
+        assert_eq!(
+            Err(QueryOpeningMismatch::DuplicateOpening(3)),
+            opening_indexes(&[7, 3], &[3, 7, 3])
+        );
+    }
 }
@@ -0,0 +1,444 @@
+//! Schema of the journal committed by the verifier guests.
+//!
+//! Every verifier guest commits a single [Journal] which binds the receipt to the statement that
+//! was verified and to the proof options it was verified under. Hosts should decode journals
+//! through this type instead of parsing the journal bytes by hand.
+
+use crate::heap::HeapEstimateExceeded;
+use crate::kat::TranscriptKat;
+use crate::phase::{PhaseState, VerificationPhase};
+use errors::ErrorChain;
+use rkyv::{Archive, Deserialize, Serialize};
+use serde::{Deserialize as sDeserialize, Serialize as sSerialize};
+use winter_air::{ProofOptions, TraceInfo};
+use winter_prover::crypto::hashers::ShaHasherT;
+use winter_verifier::Serializable;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the verification logic run by the guests; this must be bumped whenever the checks
+/// performed by a verifier guest, the way the digests are computed, or the layout of the journal
+/// change.
+pub const VERIFIER_VERSION: u32 = 24;
+
+// GUEST JOURNAL
+// ================================================================================================
+
+/// Outcome shared by the journals committed by all guests: the version of the verification logic
+/// which produced the journal, whether the guest succeeded, and the failure it reported.
+pub trait GuestJournal: Sized {
+    /// Returns the version of the verification logic which produced this journal.
+    fn verifier_version(&self) -> u32;
+
+    /// Returns the result and the error of this journal, to be updated together.
+    fn outcome_mut(&mut self) -> (&mut bool, &mut Option<ErrorChain>);
+
+    /// Returns true if this journal was produced by the current version of the verification
+    /// logic.
+    fn is_current_version(&self) -> bool {
+        self.verifier_version() == VERIFIER_VERSION
+    }
+
+    /// Returns this journal marked as failed with the provided error.
+    fn failed_with(mut self, error: ErrorChain) -> Self {
+        let (result, slot) = self.outcome_mut();
+        *result = false;
+        *slot = Some(error);
+        self
+    }
+}
+
+/// Implements [GuestJournal] for journals with the `verifier_version`, `result`, and `error`
+/// fields.
+macro_rules! impl_guest_journal {
+    ($($journal:ty),*) => {
+        $(
+            impl GuestJournal for $journal {
+                fn verifier_version(&self) -> u32 {
+                    self.verifier_version
+                }
+
+                fn outcome_mut(&mut self) -> (&mut bool, &mut Option<ErrorChain>) {
+                    (&mut self.result, &mut self.error)
+                }
+            }
+        )*
+    };
+}
+
+impl_guest_journal!(
+    Journal,
+    TranscriptJournal,
+    TranscriptKatJournal,
+    PhaseJournal,
+    MerkleBatchJournal
+);
+
+// JOURNAL
+// ================================================================================================
+
+/// Public output of a verifier guest.
+#[derive(
+    Archive, Deserialize, Serialize, sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq,
+)]
+pub struct Journal {
+    /// Digest of the statements (proof contexts, public inputs, and trace infos) which were
+    /// verified.
+    pub statement_digest: [u8; 32],
+    /// Digest of the proof options the statements were verified under.
+    pub options_digest: [u8; 32],
+    /// Version of the verification logic which produced this journal.
+    pub verifier_version: u32,
+    /// True if all statements were verified successfully.
+    pub result: bool,
+    /// Set if the verification was skipped because the heap it is estimated to need exceeds the
+    /// heap estimate cap of the guest.
+    pub heap_estimate_exceeded: Option<HeapEstimateExceeded>,
+    /// Chain of the failure which caused the verification to fail, if the guest reported one.
+    pub error: Option<ErrorChain>,
+    /// Number of cycles the guest executed before committing this journal, if the guest
+    /// committed it. The count depends only on the guest image and its inputs, and thus, it can
+    /// be used to price the verification and to detect inputs which are abnormally expensive to
+    /// verify.
+    pub cycle_count: Option<u64>,
+    /// Digest of the proof policy (see [crate::policy]) the statements were verified under, if
+    /// the guest read the policy before it failed.
+    pub policy_digest: Option<[u8; 32]>,
+    /// Commitments to auxiliary data of an application (see [crate::aux_data]) the statements
+    /// were bound to, one entry per verified statement in the order of the statements, if the
+    /// guest read the statements before it failed; the entry of a statement without a commitment
+    /// is None.
+    pub aux_data_commitments: Vec<Option<[u8; 32]>>,
+}
+
+impl Journal {
+    /// Returns a new journal produced by the current version of the verification logic.
+    pub fn new(statement_digest: [u8; 32], options_digest: [u8; 32], result: bool) -> Self {
+        Self {
+            statement_digest,
+            options_digest,
+            verifier_version: VERIFIER_VERSION,
+            result,
+            heap_estimate_exceeded: None,
+            error: None,
+            cycle_count: None,
+            policy_digest: None,
+            aux_data_commitments: Vec::new(),
+        }
+    }
+
+    /// Returns a new journal of a verification which failed with the provided error.
+    pub fn with_error(
+        statement_digest: [u8; 32],
+        options_digest: [u8; 32],
+        error: ErrorChain,
+    ) -> Self {
+        Self::new(statement_digest, options_digest, false).failed_with(error)
+    }
+
+    /// Returns a new journal of a verification which was skipped because the heap it is estimated
+    /// to need exceeds the heap estimate cap of the guest.
+    pub fn with_heap_estimate_exceeded(
+        statement_digest: [u8; 32],
+        options_digest: [u8; 32],
+        failure: HeapEstimateExceeded,
+    ) -> Self {
+        let error: ErrorChain = failure.clone().into();
+        Self {
+            heap_estimate_exceeded: Some(failure),
+            ..Self::new(statement_digest, options_digest, false)
+        }
+        .failed_with(error)
+    }
+
+    /// Returns this journal with the specified number of cycles executed by the guest.
+    pub fn with_cycle_count(mut self, cycle_count: u64) -> Self {
+        self.cycle_count = Some(cycle_count);
+        self
+    }
+
+    /// Returns this journal bound to the proof policy with the specified digest.
+    pub fn with_policy_digest(mut self, policy_digest: [u8; 32]) -> Self {
+        self.policy_digest = Some(policy_digest);
+        self
+    }
+
+    /// Returns this journal with the specified commitments to auxiliary data, one per verified
+    /// statement.
+    pub fn with_aux_data_commitments(mut self, commitments: Vec<Option<[u8; 32]>>) -> Self {
+        self.aux_data_commitments = commitments;
+        self
+    }
+}
+
+// TRANSCRIPT JOURNAL
+// ================================================================================================
+
+/// Public output of a transcript-only guest, which replays the Fiat–Shamir transcripts of the
+/// packaged proofs without verifying them (see [crate::transcript]).
+#[derive(sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptJournal {
+    /// Digest of the statements (proof contexts, public inputs, and trace infos) whose
+    /// transcripts were replayed.
+    pub statement_digest: [u8; 32],
+    /// Final seed of the public coin of every replayed transcript, in the order of the proofs;
+    /// empty if the replay failed.
+    pub final_seeds: Vec<[u8; 32]>,
+    /// Version of the verification logic which produced this journal.
+    pub verifier_version: u32,
+    /// True if the transcripts of all proofs were replayed.
+    pub result: bool,
+    /// Chain of the failure which caused the replay to fail, if any.
+    pub error: Option<ErrorChain>,
+}
+
+impl TranscriptJournal {
+    /// Returns a new transcript journal produced by the current version of the verification
+    /// logic.
+    pub fn new(statement_digest: [u8; 32], final_seeds: Vec<[u8; 32]>) -> Self {
+        Self {
+            statement_digest,
+            final_seeds,
+            verifier_version: VERIFIER_VERSION,
+            result: true,
+            error: None,
+        }
+    }
+
+    /// Returns a new transcript journal of a replay which failed with the provided error.
+    pub fn with_error(statement_digest: [u8; 32], error: ErrorChain) -> Self {
+        Self::new(statement_digest, Vec::new()).failed_with(error)
+    }
+}
+
+// TRANSCRIPT KAT JOURNAL
+// ================================================================================================
+
+/// Public output of the transcript KAT guest, which checks the Fiat–Shamir transcripts of the
+/// packaged proofs against known answers (see [crate::kat]).
+#[derive(sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptKatJournal {
+    /// Digest of the statements (proof contexts, public inputs, and trace infos) whose
+    /// transcripts were checked.
+    pub statement_digest: [u8; 32],
+    /// Digest of the known answers the transcripts were checked against (see [kats_digest]).
+    pub kats_digest: [u8; 32],
+    /// Version of the verification logic which produced this journal.
+    pub verifier_version: u32,
+    /// True if all transcripts matched their known answers.
+    pub result: bool,
+    /// Chain of the failure which caused the check to fail, if any.
+    pub error: Option<ErrorChain>,
+}
+
+impl TranscriptKatJournal {
+    /// Returns a new transcript KAT journal produced by the current version of the verification
+    /// logic.
+    pub fn new(statement_digest: [u8; 32], kats_digest: [u8; 32], result: bool) -> Self {
+        Self {
+            statement_digest,
+            kats_digest,
+            verifier_version: VERIFIER_VERSION,
+            result,
+            error: None,
+        }
+    }
+
+    /// Returns a new transcript KAT journal of a check which failed with the provided error.
+    pub fn with_error(
+        statement_digest: [u8; 32],
+        kats_digest: [u8; 32],
+        error: ErrorChain,
+    ) -> Self {
+        Self::new(statement_digest, kats_digest, false).failed_with(error)
+    }
+}
+
+// PHASE JOURNAL
+// ================================================================================================
+
+/// Public output of the phase verifier guest, which runs a single phase of the verification of
+/// the packaged proofs (see [crate::phase]).
+#[derive(sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PhaseJournal {
+    /// Digest of the statements (proof contexts, public inputs, and trace infos) which are being
+    /// verified.
+    pub statement_digest: [u8; 32],
+    /// Digest of the proof options the statements are being verified under.
+    pub options_digest: [u8; 32],
+    /// The phase which was run.
+    pub phase: VerificationPhase,
+    /// Digest of the states the phase was resumed from (see [phase_states_digest]); for the first
+    /// phase, this is the digest of an empty list of states.
+    pub prev_states_digest: [u8; 32],
+    /// States reached by the phase, in the order of the proofs; empty if the phase failed.
+    pub states: Vec<PhaseState>,
+    /// Version of the verification logic which produced this journal.
+    pub verifier_version: u32,
+    /// True if the phase completed successfully for all proofs.
+    pub result: bool,
+    /// Chain of the failure which caused the phase to fail, if any.
+    pub error: Option<ErrorChain>,
+    /// Digest of the proof policy (see [crate::policy]) the statements are being verified under,
+    /// if the guest read the policy before it failed.
+    pub policy_digest: Option<[u8; 32]>,
+}
+
+impl PhaseJournal {
+    /// Returns a new phase journal produced by the current version of the verification logic.
+    pub fn new(
+        statement_digest: [u8; 32],
+        options_digest: [u8; 32],
+        phase: VerificationPhase,
+        prev_states_digest: [u8; 32],
+        states: Vec<PhaseState>,
+    ) -> Self {
+        Self {
+            statement_digest,
+            options_digest,
+            phase,
+            prev_states_digest,
+            states,
+            verifier_version: VERIFIER_VERSION,
+            result: true,
+            error: None,
+            policy_digest: None,
+        }
+    }
+
+    /// Returns a new phase journal of a phase which failed with the provided error.
+    pub fn with_error(
+        statement_digest: [u8; 32],
+        options_digest: [u8; 32],
+        phase: VerificationPhase,
+        prev_states_digest: [u8; 32],
+        error: ErrorChain,
+    ) -> Self {
+        Self::new(
+            statement_digest,
+            options_digest,
+            phase,
+            prev_states_digest,
+            Vec::new(),
+        )
+        .failed_with(error)
+    }
+
+    /// Returns this phase journal bound to the proof policy with the specified digest.
+    pub fn with_policy_digest(mut self, policy_digest: [u8; 32]) -> Self {
+        self.policy_digest = Some(policy_digest);
+        self
+    }
+}
+
+// MERKLE BATCH JOURNAL
+// ================================================================================================
+
+/// Public output of the Merkle batch guest, which verifies a batch of Merkle inclusion proofs
+/// against a committed root (see [crate::merkle_batch]).
+#[derive(sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MerkleBatchJournal {
+    /// Root the inclusion proofs were verified against.
+    pub root: [u8; 32],
+    /// Digest of the indices and the leaves of the batch (see
+    /// [crate::merkle_batch::leaves_digest]).
+    pub leaves_digest: [u8; 32],
+    /// Number of leaves in the batch.
+    pub num_leaves: u64,
+    /// Version of the verification logic which produced this journal.
+    pub verifier_version: u32,
+    /// True if the inclusion of all leaves was verified successfully.
+    pub result: bool,
+    /// Chain of the failure which caused the verification to fail, if any.
+    pub error: Option<ErrorChain>,
+}
+
+impl MerkleBatchJournal {
+    /// Returns a new Merkle batch journal produced by the current version of the verification
+    /// logic.
+    pub fn new(root: [u8; 32], leaves_digest: [u8; 32], num_leaves: u64, result: bool) -> Self {
+        Self {
+            root,
+            leaves_digest,
+            num_leaves,
+            verifier_version: VERIFIER_VERSION,
+            result,
+            error: None,
+        }
+    }
+
+    /// Returns a new Merkle batch journal of a verification which failed with the provided
+    /// error.
+    pub fn with_error(
+        root: [u8; 32],
+        leaves_digest: [u8; 32],
+        num_leaves: u64,
+        error: ErrorChain,
+    ) -> Self {
+        Self::new(root, leaves_digest, num_leaves, false).failed_with(error)
+    }
+}
+
+// DIGESTS
+// ================================================================================================
+
+/// Computes the statement digest over the provided parts of the statements, e.g., the serialized
+/// proof context and public inputs of every verified proof.
+pub fn statement_digest<S: ShaHasherT>(parts: &[&[u8]]) -> [u8; 32] {
+    digest_parts::<S>(parts)
+}
+
+/// Returns the encoding of the provided trace info as a part of the statement digest: the
+/// serialized trace layout, followed by the trace length and the length-prefixed trace metadata.
+///
+/// The AIR of a statement is instantiated from its trace info, and thus, the trace info is a part
+/// of the statement which was verified.
+pub fn trace_info_bytes(trace_info: &TraceInfo) -> Vec<u8> {
+    let mut bytes = trace_info.layout().to_bytes();
+    bytes.extend_from_slice(&(trace_info.length() as u64).to_le_bytes());
+    bytes.extend_from_slice(&(trace_info.meta().len() as u64).to_le_bytes());
+    bytes.extend_from_slice(trace_info.meta());
+    bytes
+}
+
+/// Computes the options digest over the serialized proof options of every verified proof.
+pub fn options_digest<S: ShaHasherT>(options: &[ProofOptions]) -> [u8; 32] {
+    let parts: Vec<Vec<u8>> = options.iter().map(|options| options.to_bytes()).collect();
+    let parts: Vec<&[u8]> = parts.iter().map(|part| part.as_slice()).collect();
+    digest_parts::<S>(&parts)
+}
+
+/// Computes the digest of the provided known answers over their text format, in the order of
+/// the proofs.
+pub fn kats_digest<S: ShaHasherT>(kats: &[TranscriptKat]) -> [u8; 32] {
+    let parts: Vec<String> = kats.iter().map(TranscriptKat::to_text).collect();
+    let parts: Vec<&[u8]> = parts.iter().map(|part| part.as_bytes()).collect();
+    digest_parts::<S>(&parts)
+}
+
+/// Computes the digest of the provided phase states, in the order of the proofs.
+pub fn phase_states_digest<S: ShaHasherT>(states: &[PhaseState]) -> [u8; 32] {
+    let phases: Vec<[u8; 1]> = states.iter().map(|state| state.phase.to_bytes()).collect();
+    let mut parts: Vec<&[u8]> = Vec::with_capacity(states.len() * 3);
+    for (state, phase) in states.iter().zip(&phases) {
+        parts.push(phase);
+        parts.push(&state.seed);
+        parts.push(&state.deep_evaluations);
+    }
+    digest_parts::<S>(&parts)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Hashes the provided parts prefixed with their lengths, so that different splits of the same
+/// bytes result in different digests.
+fn digest_parts<S: ShaHasherT>(parts: &[&[u8]]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(parts.iter().map(|part| part.len() + 8).sum());
+    for part in parts {
+        data.extend_from_slice(&(part.len() as u64).to_le_bytes());
+        data.extend_from_slice(part);
+    }
+    S::digest(&data)
+}
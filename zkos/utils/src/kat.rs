@@ -0,0 +1,421 @@
+//! Known-answer transcripts of a fixed suite of fib proofs.
+//!
+//! The Fiat–Shamir transcript of a proof depends on the exact behavior of the hash functions, of
+//! the public coin, and of the way the verifier absorbs the proof into the coin; a dependency
+//! upgrade which changes any of these silently changes which proofs are accepted. A
+//! [TranscriptKat] pins the values drawn from the coin while replaying the transcript of a fixed
+//! proof (see [crate::transcript]): the out-of-domain point, the composition coefficients, and
+//! the final state of the coin. The known answers of the [KAT_SUITE] are kept in text files of
+//! `name = value` lines:
+//!
+//! ```text
+//! # known-answer transcript of a fixed fib proof
+//! name = fib_1024_q28_b8
+//! ood_point = <hex>
+//! constraint_coefficients = <hex>
+//! deep_coefficients = <hex>
+//! final_seed = <hex>
+//! query_positions = 12,345,6789
+//! ```
+//!
+//! Lines starting with `#` are comments. Field elements are written in their canonical
+//! little-endian serialization, so that the files do not depend on the internal representation
+//! of the field. The host checks the known answers against the transcripts it replays from the
+//! proofs it generates, and the transcript KAT guest checks them against the transcripts it
+//! replays from the packaged proofs.
+
+use crate::transcript::TranscriptState;
+use core::fmt;
+use core::str::FromStr;
+use errors::{ErrorChain, ErrorKind};
+use serde::{Deserialize as sDeserialize, Serialize as sSerialize};
+use winter_air::{FieldExtension, HashFunction, ProofOptions};
+
+// KAT SUITE
+// ================================================================================================
+
+/// Lengths of the Fibonacci sequences of the fixed proofs every case of the suite is generated
+/// for; these are the lengths of the fib proofs packaged by the runner.
+pub const KAT_SEQUENCE_LENGTHS: [usize; 2] = [1024, 2048];
+
+/// Proof options of the fixed proofs whose transcripts are pinned.
+pub const KAT_SUITE: [KatCase; 2] = [
+    KatCase {
+        name: "q28_b8",
+        num_queries: 28,
+        blowup_factor: 8,
+        grinding_factor: 0,
+        fri_folding_factor: 8,
+    },
+    KatCase {
+        name: "q9_b128_g20",
+        num_queries: 9,
+        blowup_factor: 128,
+        grinding_factor: 20,
+        fri_folding_factor: 8,
+    },
+];
+
+/// Proof options of a case of the [KAT_SUITE]; all cases use SHA2-256 and the quadratic field
+/// extension, which are the only options the transcript guests support.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KatCase {
+    /// Name of the case.
+    pub name: &'static str,
+    /// Number of queries of the proofs.
+    pub num_queries: usize,
+    /// Blowup factor of the proofs.
+    pub blowup_factor: usize,
+    /// Grinding factor of the proofs.
+    pub grinding_factor: u32,
+    /// FRI folding factor of the proofs.
+    pub fri_folding_factor: usize,
+}
+
+impl KatCase {
+    /// Returns the proof options of the proofs of this case.
+    pub fn proof_options(&self) -> ProofOptions {
+        ProofOptions::new(
+            self.num_queries,
+            self.blowup_factor,
+            self.grinding_factor,
+            HashFunction::Sha2_256,
+            FieldExtension::Quadratic,
+            self.fri_folding_factor,
+            256,
+        )
+    }
+
+    /// Returns the name of the known-answer transcript of the proof of the specified sequence
+    /// length, which is also the stem of the file it is kept in.
+    pub fn kat_name(&self, sequence_length: usize) -> String {
+        format!("fib_{}_{}", sequence_length, self.name)
+    }
+}
+
+// TRANSCRIPT KAT
+// ================================================================================================
+
+/// Known answers of the transcript of a fixed proof.
+#[derive(sSerialize, sDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptKat {
+    /// Name of the fixed proof.
+    pub name: String,
+    /// Serialized out-of-domain point z.
+    pub ood_point: Vec<u8>,
+    /// Serialized coefficients of the constraint composition polynomial.
+    pub constraint_coefficients: Vec<u8>,
+    /// Serialized coefficients of the DEEP composition polynomial.
+    pub deep_coefficients: Vec<u8>,
+    /// Seed of the public coin after it was reseeded with the proof-of-work nonce.
+    pub final_seed: [u8; 32],
+    /// Query positions drawn from the final seed.
+    pub query_positions: Vec<u64>,
+}
+
+impl TranscriptKat {
+    /// Returns the known answers recorded from the provided state of a replayed transcript.
+    pub fn new(name: &str, state: &TranscriptState) -> Self {
+        Self {
+            name: name.to_string(),
+            ood_point: state.ood_point.clone(),
+            constraint_coefficients: state.constraint_coefficients.clone(),
+            deep_coefficients: state.deep_coefficients.clone(),
+            final_seed: state.seed,
+            query_positions: state.query_positions.iter().map(|&p| p as u64).collect(),
+        }
+    }
+
+    /// Checks that the provided state of a replayed transcript matches these known answers.
+    ///
+    /// # Errors
+    /// Returns an error naming the first value, in the order in which they are drawn from the
+    /// coin, which does not match.
+    pub fn check(&self, state: &TranscriptState) -> Result<(), KatError> {
+        let actual = Self::new(&self.name, state);
+        let mismatch = if actual.constraint_coefficients != self.constraint_coefficients {
+            "constraint_coefficients"
+        } else if actual.ood_point != self.ood_point {
+            "ood_point"
+        } else if actual.deep_coefficients != self.deep_coefficients {
+            "deep_coefficients"
+        } else if actual.final_seed != self.final_seed {
+            "final_seed"
+        } else if actual.query_positions != self.query_positions {
+            "query_positions"
+        } else {
+            return Ok(());
+        };
+        Err(KatError::Mismatch(self.name.clone(), mismatch))
+    }
+
+    /// Returns these known answers in the text format of the KAT files.
+    pub fn to_text(&self) -> String {
+        let query_positions: Vec<String> =
+            self.query_positions.iter().map(u64::to_string).collect();
+        format!(
+            "# known-answer transcript of a fixed fib proof\n\
+             name = {}\n\
+             ood_point = {}\n\
+             constraint_coefficients = {}\n\
+             deep_coefficients = {}\n\
+             final_seed = {}\n\
+             query_positions = {}\n",
+            self.name,
+            hex::encode(&self.ood_point),
+            hex::encode(&self.constraint_coefficients),
+            hex::encode(&self.deep_coefficients),
+            hex::encode(self.final_seed),
+            query_positions.join(",")
+        )
+    }
+}
+
+impl FromStr for TranscriptKat {
+    type Err = KatError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut name = None;
+        let mut ood_point = None;
+        let mut constraint_coefficients = None;
+        let mut deep_coefficients = None;
+        let mut final_seed = None;
+        let mut query_positions = None;
+
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or(KatError::MalformedLine(i + 1))?;
+            let value = value.trim();
+            match key.trim() {
+                "name" => set_field(&mut name, "name", value.to_string())?,
+                "ood_point" => {
+                    set_field(&mut ood_point, "ood_point", parse_hex(value, "ood_point")?)?
+                }
+                "constraint_coefficients" => set_field(
+                    &mut constraint_coefficients,
+                    "constraint_coefficients",
+                    parse_hex(value, "constraint_coefficients")?,
+                )?,
+                "deep_coefficients" => set_field(
+                    &mut deep_coefficients,
+                    "deep_coefficients",
+                    parse_hex(value, "deep_coefficients")?,
+                )?,
+                "final_seed" => {
+                    let seed = parse_hex(value, "final_seed")?
+                        .try_into()
+                        .map_err(|_| KatError::InvalidValue("final_seed"))?;
+                    set_field(&mut final_seed, "final_seed", seed)?
+                }
+                "query_positions" => {
+                    let positions = value
+                        .split(',')
+                        .map(|position| position.trim().parse::<u64>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| KatError::InvalidValue("query_positions"))?;
+                    set_field(&mut query_positions, "query_positions", positions)?
+                }
+                key => return Err(KatError::UnknownField(key.to_string())),
+            }
+        }
+
+        Ok(Self {
+            name: name.ok_or(KatError::MissingField("name"))?,
+            ood_point: ood_point.ok_or(KatError::MissingField("ood_point"))?,
+            constraint_coefficients: constraint_coefficients
+                .ok_or(KatError::MissingField("constraint_coefficients"))?,
+            deep_coefficients: deep_coefficients
+                .ok_or(KatError::MissingField("deep_coefficients"))?,
+            final_seed: final_seed.ok_or(KatError::MissingField("final_seed"))?,
+            query_positions: query_positions.ok_or(KatError::MissingField("query_positions"))?,
+        })
+    }
+}
+
+// KAT ERROR
+// ================================================================================================
+
+/// Errors which can occur while reading or checking a known-answer transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KatError {
+    /// The line with the specified number is not a `name = value` line.
+    MalformedLine(usize),
+    /// The field is not a field of a known-answer transcript.
+    UnknownField(String),
+    /// The field is set more than once.
+    DuplicateField(&'static str),
+    /// The field is not set.
+    MissingField(&'static str),
+    /// The value of the field cannot be parsed.
+    InvalidValue(&'static str),
+    /// The value of the field of the named transcript does not match the replayed transcript.
+    Mismatch(String, &'static str),
+}
+
+impl fmt::Display for KatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedLine(line) => write!(f, "line {} is not a 'name = value' line", line),
+            Self::UnknownField(name) => write!(f, "'{}' is not a known-answer field", name),
+            Self::DuplicateField(name) => write!(f, "field '{}' is set more than once", name),
+            Self::MissingField(name) => write!(f, "field '{}' is not set", name),
+            Self::InvalidValue(name) => write!(f, "value of field '{}' is not valid", name),
+            Self::Mismatch(kat, name) => {
+                write!(
+                    f,
+                    "transcript diverged from known answers '{}' at '{}'",
+                    kat, name
+                )
+            }
+        }
+    }
+}
+
+impl From<KatError> for ErrorChain {
+    fn from(error: KatError) -> Self {
+        let kind = match error {
+            KatError::Mismatch(..) => ErrorKind::Verification,
+            _ => ErrorKind::Deserialization,
+        };
+        ErrorChain::new(kind, error)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn set_field<T>(field: &mut Option<T>, name: &'static str, value: T) -> Result<(), KatError> {
+    if field.replace(value).is_some() {
+        return Err(KatError::DuplicateField(name));
+    }
+    Ok(())
+}
+
+fn parse_hex(value: &str, name: &'static str) -> Result<Vec<u8>, KatError> {
+    hex::decode(value).map_err(|_| KatError::InvalidValue(name))
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{KatError, TranscriptKat, KAT_SEQUENCE_LENGTHS, KAT_SUITE};
+    use crate::fib::example::{Example, FibExample};
+    use crate::fib::fib_air::FibAir;
+    use crate::transcript::{replay_transcript, TranscriptState};
+    use std::path::PathBuf;
+    use winter_air::Air;
+    use winter_math::fields::f64_risc0::{BaseElement, DefaultNativeMul};
+    use winter_math::fields::QuadExtension;
+    use winter_prover::crypto::hashers::{DefaultSha2, Sha2_256};
+    use winter_verifier::{Serializable, VerifierChannel};
+
+    type E = QuadExtension<BaseElement>;
+    type H = Sha2_256<BaseElement, DefaultSha2>;
+
+    fn state() -> TranscriptState {
+        TranscriptState {
+            ood_point: vec![1, 2, 3],
+            constraint_coefficients: vec![4, 5],
+            deep_coefficients: vec![6],
+            seed: [7; 32],
+            query_positions: vec![8, 9, 10],
+        }
+    }
+
+    #[test]
+    fn parse_kat() {
+        let kat = TranscriptKat::new("test", &state());
+        assert_eq!(Ok(kat.clone()), kat.to_text().parse::<TranscriptKat>());
+
+        let text = kat.to_text().replace("query_positions", "queries");
+        assert_eq!(
+            Err(KatError::UnknownField("queries".to_string())),
+            text.parse::<TranscriptKat>()
+        );
+        let text = kat.to_text().replace("final_seed = 07", "final_seed = ");
+        assert_eq!(
+            Err(KatError::InvalidValue("final_seed")),
+            text.parse::<TranscriptKat>()
+        );
+        let text = format!("{}name = test\n", kat.to_text());
+        assert_eq!(
+            Err(KatError::DuplicateField("name")),
+            text.parse::<TranscriptKat>()
+        );
+        assert_eq!(
+            Err(KatError::MissingField("name")),
+            "".parse::<TranscriptKat>()
+        );
+    }
+
+    #[test]
+    fn check_kat() {
+        let kat = TranscriptKat::new("test", &state());
+        assert_eq!(Ok(()), kat.check(&state()));
+
+        let mut diverged = state();
+        diverged.ood_point[0] ^= 1;
+        diverged.seed[0] ^= 1;
+        assert_eq!(
+            Err(KatError::Mismatch("test".to_string(), "ood_point")),
+            kat.check(&diverged)
+        );
+
+        let mut diverged = state();
+        diverged.query_positions.pop();
+        assert_eq!(
+            Err(KatError::Mismatch("test".to_string(), "query_positions")),
+            kat.check(&diverged)
+        );
+    }
+
+    /// Checks the transcripts of the fixed proofs against the known answers in the KAT files;
+    /// when ZKOS_WRITE_KATS is set, the files are rewritten from the replayed transcripts
+    /// instead. This should only be done when the transcripts are expected to change, e.g., when
+    /// the way the verifier absorbs the proof into the coin is changed on purpose.
+    #[test]
+    fn fib_transcript_kats() {
+        let kat_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("kat");
+        let write_kats = std::env::var_os("ZKOS_WRITE_KATS").is_some();
+        for case in KAT_SUITE.iter() {
+            for sequence_length in KAT_SEQUENCE_LENGTHS {
+                let name = case.kat_name(sequence_length);
+                let example = FibExample::new(sequence_length, case.proof_options());
+                let proof = example.prove();
+                let air: FibAir<DefaultNativeMul> = FibAir::new(
+                    proof.get_trace_info(),
+                    example.result,
+                    proof.options().clone(),
+                );
+
+                let coin_seed = proof.context.public_coin_seed(&example.result.to_bytes());
+                let mut channel: VerifierChannel<E, H> = VerifierChannel::new(&air, proof).unwrap();
+                let state = replay_transcript(&air, &mut channel, &coin_seed).unwrap();
+
+                let path = kat_dir.join(format!("{}.kat", name));
+                if write_kats {
+                    std::fs::create_dir_all(&kat_dir).unwrap();
+                    std::fs::write(&path, TranscriptKat::new(&name, &state).to_text()).unwrap();
+                    continue;
+                }
+                let kat: TranscriptKat = std::fs::read_to_string(&path)
+                    .unwrap_or_else(|e| {
+                        panic!(
+                            "failed to read {}: {}; run with ZKOS_WRITE_KATS set to record it",
+                            path.display(),
+                            e
+                        )
+                    })
+                    .parse()
+                    .unwrap();
+                assert_eq!(name, kat.name);
+                kat.check(&state).unwrap();
+            }
+        }
+    }
+}
@@ -1,3 +1,24 @@
+pub mod aux_data;
+pub mod aux_input;
+pub mod context;
+pub mod ct;
+pub mod estimate;
 pub mod fib;
 pub mod fibonacci_miden;
+pub mod hasher;
+pub mod heap;
 pub mod inputs;
+pub mod journal;
+pub mod kat;
+pub mod link;
+pub mod merkle_batch;
+pub mod mutation;
+pub mod phase;
+pub mod policy;
+pub mod poseidon;
+pub mod profile;
+pub mod profiler;
+pub mod ref_field;
+pub mod release;
+pub mod trace_bounds;
+pub mod transcript;
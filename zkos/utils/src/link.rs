@@ -0,0 +1,248 @@
+//! Links between the two Miden proofs verified by the recursive guest in linked mode.
+//!
+//! In linked mode, the host packages two proofs together with a [ProofLink] which declares how
+//! the statements of the proofs are related. The guest checks the link in addition to verifying
+//! both proofs, so that a single receipt attests to a simple cross-proof consistency statement,
+//! e.g., that the second execution continued from the outputs of the first one, without an
+//! aggregation AIR.
+//!
+//! The mode is passed to the guest as a [RecursiveMode] read right after the policy header. The
+//! statement digest of a linked journal covers the contexts and public inputs of both proofs,
+//! followed by the encoding of the link (see [ProofLink::to_bytes]), so that the same pair of
+//! proofs verified under different links results in different journals.
+
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+use miden_air::PublicInputs;
+use serde::{Deserialize, Serialize};
+
+// RECURSIVE MODE
+// ================================================================================================
+
+/// Mode in which the recursive guest verifies its inputs.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecursiveMode {
+    /// A single proof is verified.
+    Single,
+    /// Two proofs are verified, and their statements are checked to be related by the link.
+    Linked(ProofLink),
+}
+
+// PROOF LINK
+// ================================================================================================
+
+/// Relation between the statements of two linked proofs.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofLink {
+    /// Both proofs were generated for the same proof context and packaged for the same domain,
+    /// i.e., their verifier contexts (domain tag, trace info, field modulus, and proof options)
+    /// are identical.
+    SameContext,
+    /// Both proofs attest to executions of the same program.
+    SameProgram,
+    /// The second proof attests to an execution which starts from the stack outputs of the
+    /// execution attested by the first one.
+    Chained,
+}
+
+impl ProofLink {
+    /// Returns the encoding of this link which is appended to the parts of the statement digest.
+    pub fn to_bytes(&self) -> [u8; 1] {
+        match self {
+            Self::SameContext => [1],
+            Self::SameProgram => [2],
+            Self::Chained => [3],
+        }
+    }
+
+    /// Checks that the statements of the provided proofs are related by this link.
+    ///
+    /// # Errors
+    /// Returns an error if the statements are not related by this link, or if the link cannot be
+    /// checked because the first proof is bound to a commitment to its outputs instead of the
+    /// outputs themselves.
+    pub fn check(
+        &self,
+        first: &LinkedStatement,
+        second: &LinkedStatement,
+    ) -> Result<(), LinkError> {
+        match self {
+            Self::SameContext if first.context != second.context => Err(LinkError::ContextMismatch),
+            Self::SameProgram
+                if first.public_inputs.program_hash() != second.public_inputs.program_hash() =>
+            {
+                Err(LinkError::ProgramMismatch)
+            }
+            Self::Chained if first.public_inputs.output_commitment().is_some() => {
+                Err(LinkError::OutputsNotRevealed)
+            }
+            Self::Chained
+                if first.public_inputs.stack_outputs() != second.public_inputs.stack_inputs() =>
+            {
+                Err(LinkError::StackMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ProofLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SameContext => write!(f, "same context"),
+            Self::SameProgram => write!(f, "same program"),
+            Self::Chained => write!(f, "chained"),
+        }
+    }
+}
+
+/// Parts of the statement of a proof which links are checked against.
+pub struct LinkedStatement<'a> {
+    /// Verifier context of the proof (see [crate::context::VerifierContext]).
+    pub context: &'a [u8],
+    /// Public inputs the proof is verified against.
+    pub public_inputs: &'a PublicInputs,
+}
+
+// LINK ERROR
+// ================================================================================================
+
+/// Failure of the statements of two proofs to be related by a [ProofLink].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkError {
+    /// The proofs were generated for different proof contexts.
+    ContextMismatch,
+    /// The proofs attest to executions of different programs.
+    ProgramMismatch,
+    /// The first proof is bound to a commitment to its outputs, and thus, its outputs cannot be
+    /// compared to the inputs of the second proof.
+    OutputsNotRevealed,
+    /// The stack outputs of the first proof are not the stack inputs of the second one.
+    StackMismatch,
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContextMismatch => write!(f, "the proofs were generated for different contexts"),
+            Self::ProgramMismatch => write!(f, "the proofs attest to different programs"),
+            Self::OutputsNotRevealed => write!(
+                f,
+                "the first proof is bound to a commitment to its outputs instead of the outputs"
+            ),
+            Self::StackMismatch => write!(
+                f,
+                "the stack outputs of the first proof are not the stack inputs of the second one"
+            ),
+        }
+    }
+}
+
+impl From<LinkError> for ErrorChain {
+    fn from(error: LinkError) -> Self {
+        ErrorChain::new(ErrorKind::Verification, error)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{LinkError, LinkedStatement, ProofLink};
+    use miden_air::PublicInputs;
+    use miden_core::{
+        chiplets::hasher::{self, Digest},
+        Felt,
+    };
+
+    fn digest(value: u64) -> Digest {
+        hasher::hash_elements(&[Felt::new(value)])
+    }
+
+    fn public_inputs(program: u64, inputs: &[u64], outputs: &[u64]) -> PublicInputs {
+        let to_felts = |values: &[u64]| values.iter().map(|&v| Felt::new(v)).collect();
+        PublicInputs::new(digest(program), to_felts(inputs), to_felts(outputs))
+    }
+
+    fn statement(public_inputs: &PublicInputs) -> LinkedStatement {
+        LinkedStatement {
+            context: &[],
+            public_inputs,
+        }
+    }
+
+    #[test]
+    fn same_context() {
+        let inputs = public_inputs(1, &[0, 1], &[5]);
+        let first = LinkedStatement {
+            context: &[1, 2, 3],
+            public_inputs: &inputs,
+        };
+        let second = LinkedStatement {
+            context: &[1, 2, 4],
+            public_inputs: &inputs,
+        };
+        assert_eq!(Ok(()), ProofLink::SameContext.check(&first, &first));
+        assert_eq!(
+            Err(LinkError::ContextMismatch),
+            ProofLink::SameContext.check(&first, &second)
+        );
+    }
+
+    #[test]
+    fn same_program() {
+        let (a, b, c) = (
+            public_inputs(1, &[0, 1], &[5]),
+            public_inputs(1, &[2, 3], &[8]),
+            public_inputs(2, &[0, 1], &[5]),
+        );
+        assert_eq!(
+            Ok(()),
+            ProofLink::SameProgram.check(&statement(&a), &statement(&b))
+        );
+        assert_eq!(
+            Err(LinkError::ProgramMismatch),
+            ProofLink::SameProgram.check(&statement(&a), &statement(&c))
+        );
+    }
+
+    #[test]
+    fn chained() {
+        let (a, b, c) = (
+            public_inputs(1, &[0, 1], &[5, 8]),
+            public_inputs(1, &[5, 8], &[13]),
+            public_inputs(1, &[8, 5], &[13]),
+        );
+        assert_eq!(
+            Ok(()),
+            ProofLink::Chained.check(&statement(&a), &statement(&b))
+        );
+        assert_eq!(
+            Err(LinkError::StackMismatch),
+            ProofLink::Chained.check(&statement(&a), &statement(&c))
+        );
+
+        // outputs hidden behind a commitment cannot be chained
+        let committed =
+            PublicInputs::new(digest(1), vec![], vec![]).with_output_commitment(digest(7));
+        assert_eq!(
+            Err(LinkError::OutputsNotRevealed),
+            ProofLink::Chained.check(&statement(&committed), &statement(&b))
+        );
+    }
+
+    #[test]
+    fn link_encodings_are_distinct() {
+        let links = [
+            ProofLink::SameContext,
+            ProofLink::SameProgram,
+            ProofLink::Chained,
+        ];
+        for (i, a) in links.iter().enumerate() {
+            for b in links[i + 1..].iter() {
+                assert_ne!(a.to_bytes(), b.to_bytes());
+            }
+        }
+    }
+}
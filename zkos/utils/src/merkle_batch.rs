@@ -0,0 +1,357 @@
+//! Batches of Merkle inclusion proofs verified by the Merkle batch guest.
+//!
+//! A [MerkleBatch] holds any number of (leaf, index, path) tuples against a single root of a
+//! binary SHA-256 Merkle tree, where every parent node is the digest of the concatenation of its
+//! left and its right child. The tuples are laid out as flat vectors, so that the archived batch
+//! can be read by the guest in place, and thousands of them can be verified without allocation.
+//! The guest commits the root and a digest of the included leaves (see [leaves_digest]), which
+//! makes the batch a standalone primitive and a stress test for the guest hashing throughput.
+
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+use rkyv::{Archive, Deserialize, Serialize};
+use winter_prover::crypto::hashers::ShaHasherT;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum depth of the tree a batch may be verified against, so that every leaf index fits
+/// into 64 bits.
+pub const MAX_DEPTH: u32 = 63;
+
+/// Domain separator of the digest of the included leaves.
+const LEAVES_DIGEST_DOMAIN: &[u8] = b"zkos-merkle-batch-leaves";
+
+// MERKLE BATCH
+// ================================================================================================
+
+/// Inclusion proofs of several leaves against the same root.
+///
+/// The path of the leaf at position `i` of the batch consists of the `depth` siblings at
+/// positions `i * depth..(i + 1) * depth` of `siblings`, ordered from the leaf level upwards.
+#[derive(Archive, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct MerkleBatch {
+    /// Root of the tree the leaves are included in.
+    pub root: [u8; 32],
+    /// Depth of the tree, i.e., the number of siblings in every path.
+    pub depth: u32,
+    /// Indices of the included leaves.
+    pub indices: Vec<u64>,
+    /// Included leaves, in the same order as their indices.
+    pub leaves: Vec<[u8; 32]>,
+    /// Siblings of the paths of all leaves, concatenated in the order of the leaves.
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerkleBatch {
+    /// Returns a batch of inclusion proofs of the leaves at the specified indices of the provided
+    /// tree.
+    ///
+    /// # Panics
+    /// Panics if any of the indices is not an index of a leaf of the tree.
+    pub fn from_tree(tree: &MerkleTree, indices: &[u64]) -> Self {
+        let mut leaves = Vec::with_capacity(indices.len());
+        let mut siblings = Vec::with_capacity(indices.len() * tree.depth() as usize);
+        for &index in indices {
+            leaves.push(tree.leaf(index));
+            siblings.extend(tree.path(index));
+        }
+        Self {
+            root: tree.root(),
+            depth: tree.depth(),
+            indices: indices.to_vec(),
+            leaves,
+            siblings,
+        }
+    }
+}
+
+// MERKLE TREE
+// ================================================================================================
+
+/// A binary SHA-256 Merkle tree built on the host to produce [MerkleBatch]es.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// Nodes of the tree level by level, starting with the leaves and ending with the root.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Returns a tree with the provided leaves, hashed with the specified hasher.
+    ///
+    /// # Panics
+    /// Panics if the number of leaves is not a power of two, or if the tree would be deeper than
+    /// [MAX_DEPTH].
+    pub fn new<S: ShaHasherT>(leaves: Vec<[u8; 32]>) -> Self {
+        assert!(
+            leaves.len().is_power_of_two(),
+            "number of leaves must be a power of two, but was {}",
+            leaves.len()
+        );
+        assert!(
+            leaves.len().trailing_zeros() <= MAX_DEPTH,
+            "tree depth must be at most {}",
+            MAX_DEPTH
+        );
+
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let level = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| hash_nodes::<S>(&pair[0], &pair[1]))
+                .collect();
+            levels.push(level);
+        }
+        Self { levels }
+    }
+
+    /// Returns the root of this tree.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Returns the depth of this tree.
+    pub fn depth(&self) -> u32 {
+        (self.levels.len() - 1) as u32
+    }
+
+    /// Returns the leaf at the specified index.
+    pub fn leaf(&self, index: u64) -> [u8; 32] {
+        self.levels[0][index as usize]
+    }
+
+    /// Returns the siblings of the path from the leaf at the specified index to the root.
+    pub fn path(&self, index: u64) -> Vec<[u8; 32]> {
+        let mut index = index as usize;
+        let mut path = Vec::with_capacity(self.levels.len() - 1);
+        for level in &self.levels[..self.levels.len() - 1] {
+            path.push(level[index ^ 1]);
+            index >>= 1;
+        }
+        path
+    }
+}
+
+// BATCH VERIFICATION
+// ================================================================================================
+
+/// Verifies the inclusion of every leaf of a batch against the specified root, and returns the
+/// digest of the included leaves.
+///
+/// The batch is passed as the flat vectors of a [MerkleBatch], so that it can be verified from
+/// the archived batch in place.
+///
+/// # Errors
+/// Returns an error if the vectors of the batch are inconsistent with each other or with the
+/// depth, if a leaf index is out of range, or if a path does not lead to the root.
+pub fn verify_batch<S: ShaHasherT>(
+    root: &[u8; 32],
+    depth: u32,
+    indices: &[u64],
+    leaves: &[[u8; 32]],
+    siblings: &[[u8; 32]],
+) -> Result<[u8; 32], MerkleBatchError> {
+    if depth > MAX_DEPTH {
+        return Err(MerkleBatchError::DepthTooLarge(depth));
+    }
+    if leaves.len() != indices.len() {
+        return Err(MerkleBatchError::NumLeaves(indices.len(), leaves.len()));
+    }
+    let depth = depth as usize;
+    if siblings.len() != indices.len() * depth {
+        return Err(MerkleBatchError::NumSiblings(
+            indices.len() * depth,
+            siblings.len(),
+        ));
+    }
+
+    for (i, (&index, leaf)) in indices.iter().zip(leaves).enumerate() {
+        if index >> depth != 0 {
+            return Err(MerkleBatchError::IndexOutOfRange(i, index));
+        }
+        let path = &siblings[i * depth..(i + 1) * depth];
+        if compute_root::<S>(leaf, index, path) != *root {
+            return Err(MerkleBatchError::RootMismatch(i, index));
+        }
+    }
+
+    Ok(leaves_digest::<S>(indices, leaves))
+}
+
+/// Returns the digest of the provided leaves and their indices, committed by the guest to bind
+/// the receipt to the included leaves.
+pub fn leaves_digest<S: ShaHasherT>(indices: &[u64], leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(LEAVES_DIGEST_DOMAIN.len() + 8 + leaves.len() * 40);
+    data.extend_from_slice(LEAVES_DIGEST_DOMAIN);
+    data.extend_from_slice(&(leaves.len() as u64).to_le_bytes());
+    for (index, leaf) in indices.iter().zip(leaves) {
+        data.extend_from_slice(&index.to_le_bytes());
+        data.extend_from_slice(leaf);
+    }
+    S::digest(&data)
+}
+
+// MERKLE BATCH ERROR
+// ================================================================================================
+
+/// Failure to verify a [MerkleBatch].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MerkleBatchError {
+    /// The depth of the tree exceeds [MAX_DEPTH].
+    DepthTooLarge(u32),
+    /// The number of leaves (second value) differs from the number of indices (first value).
+    NumLeaves(usize, usize),
+    /// The number of siblings (second value) differs from the number of indices times the depth
+    /// (first value).
+    NumSiblings(usize, usize),
+    /// The index (second value) of the leaf at the specified position of the batch (first value)
+    /// is not an index of a leaf of the tree.
+    IndexOutOfRange(usize, u64),
+    /// The path of the leaf at the specified position of the batch (first value) and index
+    /// (second value) does not lead to the root.
+    RootMismatch(usize, u64),
+}
+
+impl fmt::Display for MerkleBatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DepthTooLarge(depth) => {
+                write!(
+                    f,
+                    "tree depth {} exceeds the maximum of {}",
+                    depth, MAX_DEPTH
+                )
+            }
+            Self::NumLeaves(expected, actual) => {
+                write!(f, "expected {} leaves, but found {}", expected, actual)
+            }
+            Self::NumSiblings(expected, actual) => {
+                write!(
+                    f,
+                    "expected {} path siblings, but found {}",
+                    expected, actual
+                )
+            }
+            Self::IndexOutOfRange(i, index) => {
+                write!(f, "index {} of leaf #{} is out of range", index, i)
+            }
+            Self::RootMismatch(i, index) => write!(
+                f,
+                "path of leaf #{} at index {} does not lead to the root",
+                i, index
+            ),
+        }
+    }
+}
+
+impl From<MerkleBatchError> for ErrorChain {
+    fn from(error: MerkleBatchError) -> Self {
+        ErrorChain::new(ErrorKind::Verification, error)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the root computed from the leaf at the specified index and the siblings of its path.
+fn compute_root<S: ShaHasherT>(leaf: &[u8; 32], index: u64, path: &[[u8; 32]]) -> [u8; 32] {
+    let mut node = *leaf;
+    let mut index = index;
+    for sibling in path {
+        node = match index & 1 {
+            0 => hash_nodes::<S>(&node, sibling),
+            _ => hash_nodes::<S>(sibling, &node),
+        };
+        index >>= 1;
+    }
+    node
+}
+
+/// Returns the parent of the provided left and right nodes.
+fn hash_nodes<S: ShaHasherT>(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut data = [0; 64];
+    data[..32].copy_from_slice(left);
+    data[32..].copy_from_slice(right);
+    S::digest(&data)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{leaves_digest, verify_batch, MerkleBatch, MerkleBatchError, MerkleTree};
+    use winter_prover::crypto::hashers::{DefaultSha2, ShaHasherT};
+
+    fn build_tree(num_leaves: u64) -> MerkleTree {
+        let leaves = (0..num_leaves)
+            .map(|i| DefaultSha2::digest(&i.to_le_bytes()))
+            .collect();
+        MerkleTree::new::<DefaultSha2>(leaves)
+    }
+
+    fn verify(batch: &MerkleBatch) -> Result<[u8; 32], MerkleBatchError> {
+        verify_batch::<DefaultSha2>(
+            &batch.root,
+            batch.depth,
+            &batch.indices,
+            &batch.leaves,
+            &batch.siblings,
+        )
+    }
+
+    #[test]
+    fn verify_inclusions() {
+        let tree = build_tree(16);
+        assert_eq!(4, tree.depth());
+
+        let indices = [0, 5, 6, 15, 5];
+        let batch = MerkleBatch::from_tree(&tree, &indices);
+        assert_eq!(
+            Ok(leaves_digest::<DefaultSha2>(&batch.indices, &batch.leaves)),
+            verify(&batch)
+        );
+
+        // a tree with a single leaf has the leaf as its root
+        let tree = build_tree(1);
+        let batch = MerkleBatch::from_tree(&tree, &[0]);
+        assert_eq!(tree.leaf(0), tree.root());
+        assert!(verify(&batch).is_ok());
+    }
+
+    #[test]
+    fn reject_invalid_inclusions() {
+        let tree = build_tree(16);
+        let batch = MerkleBatch::from_tree(&tree, &[1, 2, 3]);
+
+        let mut leaf = batch.clone();
+        leaf.leaves[1][0] ^= 1;
+        assert_eq!(Err(MerkleBatchError::RootMismatch(1, 2)), verify(&leaf));
+
+        let mut index = batch.clone();
+        index.indices[2] = 4;
+        assert_eq!(Err(MerkleBatchError::RootMismatch(2, 4)), verify(&index));
+
+        let mut index = batch.clone();
+        index.indices[0] = 16;
+        assert_eq!(
+            Err(MerkleBatchError::IndexOutOfRange(0, 16)),
+            verify(&index)
+        );
+
+        let mut sibling = batch.clone();
+        sibling.siblings[7][31] ^= 1;
+        assert_eq!(Err(MerkleBatchError::RootMismatch(1, 2)), verify(&sibling));
+
+        let mut siblings = batch.clone();
+        siblings.siblings.pop();
+        assert_eq!(
+            Err(MerkleBatchError::NumSiblings(12, 11)),
+            verify(&siblings)
+        );
+
+        let mut leaves = batch;
+        leaves.leaves.pop();
+        assert_eq!(Err(MerkleBatchError::NumLeaves(3, 2)), verify(&leaves));
+    }
+}
@@ -0,0 +1,315 @@
+//! Typed mutations of packaged proofs.
+//!
+//! A verifier must reject every proof which was tampered with after it was generated. Rather than
+//! tampering with proofs ad-hoc in every test, host tests and guest rejection tests apply the
+//! mutations defined here to a packaged [FibRiscInput], so that every verifier is checked against
+//! the same set of attacks.
+
+use crate::inputs::FibRiscInput;
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+use winter_math::FieldElement;
+use winter_verifier::crypto::ElementHasher;
+use winter_verifier::VerifierChannel;
+
+// PROOF MUTATION
+// ================================================================================================
+
+/// A mutation of the data read by a verifier from a packaged proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofMutation {
+    /// Adds one to the specified element of the current row of the out-of-domain main trace
+    /// frame.
+    FlipOodTraceElement(usize),
+    /// Adds one to the specified out-of-domain constraint evaluation.
+    FlipOodConstraintEvaluation(usize),
+    /// Removes the last queried evaluation of the specified FRI layer.
+    TruncateFriLayer(usize),
+    /// Adds one to the specified element of the first queried state of the main trace.
+    FlipTraceQueryElement(usize),
+    /// Adds one to the specified element of the first queried constraint evaluation row.
+    FlipConstraintQueryElement(usize),
+    /// Swaps the main trace commitment with the constraint commitment.
+    SwapCommitments,
+    /// Adds one to the query proof-of-work nonce.
+    ChangeNonce,
+}
+
+impl ProofMutation {
+    /// Returns a set of mutations covering every part of a proof, each of which must cause a
+    /// verifier to reject a proof.
+    pub fn standard_set() -> Vec<Self> {
+        vec![
+            Self::FlipOodTraceElement(0),
+            Self::FlipOodConstraintEvaluation(0),
+            Self::TruncateFriLayer(0),
+            Self::FlipTraceQueryElement(0),
+            Self::FlipConstraintQueryElement(0),
+            Self::SwapCommitments,
+            Self::ChangeNonce,
+        ]
+    }
+
+    /// Returns true if this mutation changes the Fiat–Shamir transcript of the proof, i.e., the
+    /// values the public coin is reseeded with.
+    pub fn affects_transcript(&self) -> bool {
+        !matches!(
+            self,
+            Self::TruncateFriLayer(_)
+                | Self::FlipTraceQueryElement(_)
+                | Self::FlipConstraintQueryElement(_)
+        )
+    }
+
+    /// Applies this mutation to the verifier channel of the provided packaged proof.
+    ///
+    /// # Errors
+    /// Returns an error if the part of the proof targeted by this mutation does not exist.
+    pub fn apply<E, H>(&self, input: &mut FibRiscInput<E, H>) -> Result<(), MutationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        self.apply_to_channel(&mut input.verifier_channel)
+    }
+
+    /// Applies this mutation to the provided verifier channel.
+    ///
+    /// # Errors
+    /// Returns an error if the part of the proof targeted by this mutation does not exist, or if
+    /// it has already been read from the channel.
+    pub fn apply_to_channel<E, H>(
+        &self,
+        channel: &mut VerifierChannel<E, H>,
+    ) -> Result<(), MutationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        match self {
+            Self::FlipOodTraceElement(index) => {
+                let (current, _) = channel
+                    .ood_main_trace_frame_mut()
+                    .ok_or(MutationError::AlreadyRead)?;
+                flip_element(current, *index)
+            }
+            Self::FlipOodConstraintEvaluation(index) => {
+                let evaluations = channel
+                    .ood_constraint_evaluations_mut()
+                    .ok_or(MutationError::AlreadyRead)?;
+                flip_element(evaluations, *index)
+            }
+            Self::TruncateFriLayer(index) => {
+                let layers = channel.fri_layer_queries_mut();
+                let num_layers = layers.len();
+                let layer = layers
+                    .get_mut(*index)
+                    .ok_or(MutationError::IndexOutOfBounds(*index, num_layers))?;
+                layer.pop().ok_or(MutationError::EmptyFriLayer(*index))?;
+                Ok(())
+            }
+            Self::FlipTraceQueryElement(index) => {
+                let states = channel
+                    .queried_main_trace_states_mut()
+                    .ok_or(MutationError::AlreadyRead)?;
+                flip_element(states.get_row_mut(0), *index)
+            }
+            Self::FlipConstraintQueryElement(index) => {
+                let evaluations = channel
+                    .queried_constraint_evaluations_mut()
+                    .ok_or(MutationError::AlreadyRead)?;
+                flip_element(evaluations.get_row_mut(0), *index)
+            }
+            Self::SwapCommitments => {
+                let trace_commitment = *channel
+                    .trace_commitments_mut()
+                    .first()
+                    .ok_or(MutationError::IndexOutOfBounds(0, 0))?;
+                let constraint_commitment =
+                    core::mem::replace(channel.constraint_commitment_mut(), trace_commitment);
+                channel.trace_commitments_mut()[0] = constraint_commitment;
+                Ok(())
+            }
+            Self::ChangeNonce => {
+                let nonce = channel.pow_nonce_mut();
+                *nonce = nonce.wrapping_add(1);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for ProofMutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FlipOodTraceElement(index) => write!(f, "flip OOD trace element {}", index),
+            Self::FlipOodConstraintEvaluation(index) => {
+                write!(f, "flip OOD constraint evaluation {}", index)
+            }
+            Self::TruncateFriLayer(index) => write!(f, "truncate FRI layer {}", index),
+            Self::FlipTraceQueryElement(index) => write!(f, "flip trace query element {}", index),
+            Self::FlipConstraintQueryElement(index) => {
+                write!(f, "flip constraint query element {}", index)
+            }
+            Self::SwapCommitments => write!(f, "swap trace and constraint commitments"),
+            Self::ChangeNonce => write!(f, "change proof-of-work nonce"),
+        }
+    }
+}
+
+// MUTATION ERROR
+// ================================================================================================
+
+/// Reasons why a mutation cannot be applied to a proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MutationError {
+    /// The index targeted by the mutation (first value) is not smaller than the number of
+    /// elements (second value).
+    IndexOutOfBounds(usize, usize),
+    /// The targeted part of the proof has already been read from the verifier channel.
+    AlreadyRead,
+    /// The specified FRI layer contains no queried evaluations.
+    EmptyFriLayer(usize),
+}
+
+impl fmt::Display for MutationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IndexOutOfBounds(index, len) => {
+                write!(
+                    f,
+                    "mutation targets element {}, but the proof contains {} elements",
+                    index, len
+                )
+            }
+            Self::AlreadyRead => write!(f, "mutated data has already been read from the channel"),
+            Self::EmptyFriLayer(index) => write!(f, "FRI layer {} contains no evaluations", index),
+        }
+    }
+}
+
+impl From<MutationError> for ErrorChain {
+    fn from(error: MutationError) -> Self {
+        ErrorChain::new(ErrorKind::Mutation, error)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Adds one to the specified element of the provided slice.
+fn flip_element<E: FieldElement>(elements: &mut [E], index: usize) -> Result<(), MutationError> {
+    let len = elements.len();
+    let element = elements
+        .get_mut(index)
+        .ok_or(MutationError::IndexOutOfBounds(index, len))?;
+    *element += E::ONE;
+    Ok(())
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{MutationError, ProofMutation};
+    use crate::fib::example::{Example, FibExample};
+    use crate::fib::fib_air::FibAir;
+    use crate::transcript::replay_transcript;
+    use winter_air::{Air, FieldExtension, HashFunction, ProofOptions};
+    use winter_math::fields::f64_risc0::{BaseElement, DefaultNativeMul};
+    use winter_math::fields::QuadExtension;
+    use winter_verifier::crypto::hashers::{DefaultSha2, Sha2_256};
+    use winter_verifier::{Serializable, VerifierChannel, VerifierError};
+
+    type C = VerifierChannel<QuadExtension<BaseElement>, Sha2_256<BaseElement, DefaultSha2>>;
+
+    /// Returns the AIR of a sample Fibonacci proof, a function building verifier channels of the
+    /// proof, and the seed of its public coin.
+    fn build_proof() -> (FibAir<DefaultNativeMul>, impl Fn() -> C, Vec<u8>) {
+        let options = ProofOptions::new(
+            4,
+            8,
+            0,
+            HashFunction::Sha2_256,
+            FieldExtension::Quadratic,
+            8,
+            256,
+        );
+        let example = FibExample::new(1024, options);
+        let proof = example.prove();
+        let air = FibAir::new(
+            proof.get_trace_info(),
+            example.result,
+            proof.options().clone(),
+        );
+
+        let coin_seed = proof.context.public_coin_seed(&example.result.to_bytes());
+
+        let channel_air = FibAir::new(
+            proof.get_trace_info(),
+            example.result,
+            proof.options().clone(),
+        );
+        let build_channel = move || C::new(&channel_air, proof.clone()).unwrap();
+        (air, build_channel, coin_seed)
+    }
+
+    #[test]
+    fn mutations_change_transcript() {
+        let (air, build_channel, coin_seed) = build_proof();
+        let expected = replay_transcript(&air, &mut build_channel(), &coin_seed).unwrap();
+
+        for mutation in ProofMutation::standard_set() {
+            let mut channel = build_channel();
+            let num_fri_queries = channel.fri_layer_queries_mut()[0].len();
+            mutation.apply_to_channel(&mut channel).unwrap();
+
+            let state = replay_transcript(&air, &mut channel, &coin_seed).unwrap();
+            if mutation.affects_transcript() {
+                assert_ne!(expected.seed, state.seed, "{}", mutation);
+                continue;
+            }
+
+            // mutations of the queries do not change the transcript, but the mutated values no
+            // longer match the commitments
+            assert_eq!(expected.seed, state.seed, "{}", mutation);
+            let positions = &state.query_positions;
+            match mutation {
+                ProofMutation::TruncateFriLayer(_) => assert_eq!(
+                    num_fri_queries - 1,
+                    channel.fri_layer_queries_mut()[0].len()
+                ),
+                ProofMutation::FlipTraceQueryElement(_) => assert_eq!(
+                    Err(VerifierError::TraceQueryDoesNotMatchCommitment),
+                    channel
+                        .read_queried_trace_states(&air, positions)
+                        .map(|_| ())
+                ),
+                _ => assert_eq!(
+                    Err(VerifierError::ConstraintQueryDoesNotMatchCommitment),
+                    channel
+                        .read_constraint_evaluations(&air, positions)
+                        .map(|_| ())
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn mutation_errors() {
+        let (_, build_channel, _) = build_proof();
+        let mut channel = build_channel();
+        let num_evaluations = channel.ood_constraint_evaluations_mut().unwrap().len();
+        assert_eq!(
+            Err(MutationError::IndexOutOfBounds(1000, num_evaluations)),
+            ProofMutation::FlipOodConstraintEvaluation(1000).apply_to_channel(&mut channel)
+        );
+
+        channel.read_ood_trace_frame();
+        assert_eq!(
+            Err(MutationError::AlreadyRead),
+            ProofMutation::FlipOodTraceElement(0).apply_to_channel(&mut channel)
+        );
+    }
+}
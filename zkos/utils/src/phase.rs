@@ -0,0 +1,665 @@
+//! Verification of a packaged proof split into resumable phases.
+//!
+//! Verifying a large proof in a single guest execution may exceed the cycle or memory limits of
+//! the zkVM. Instead, the verification can be split into the [VerificationPhase]s below, each of
+//! which is run by a separate guest execution. Every phase is deterministically re-entered from
+//! the packaged proof: the Fiat–Shamir transcript is replayed from the start, which is cheap, and
+//! only the checks of the current phase are performed. The [PhaseState] reached by a phase is
+//! committed to the journal of its receipt, and the host passes it to the execution of the next
+//! phase, which commits to the digest of the state it was resumed from.
+//!
+//! A host stitches the receipts of all phases with [check_phase_journals]: the journals must
+//! cover the phases in order, be bound to the same statement, proof options, and proof policy,
+//! and every phase must have been resumed from the states committed by the phase before it.
+
+use crate::ct::{ct_eq_elements, ct_ge_u32};
+use crate::hasher::GuestHasher;
+use crate::inputs::TraceSegmentMismatch;
+use crate::journal::{phase_states_digest, GuestJournal, PhaseJournal};
+use crate::transcript::{replay, serialize_elements, ReplayError};
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+use serde::{Deserialize, Serialize};
+use winter_air::Air;
+use winter_math::FieldElement;
+use winter_prover::crypto::hashers::ShaHasherT;
+use winter_verifier::crypto::Digest;
+use winter_verifier::{
+    evaluate_constraints, DeepComposer, Deserializable, DeserializationError, SliceReader,
+    VerifierChannel, VerifierError,
+};
+
+// VERIFICATION PHASE
+// ================================================================================================
+
+/// A resumable part of the verification of a proof.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationPhase {
+    /// Replay of the Fiat–Shamir transcript, including the proof-of-work check.
+    Transcript,
+    /// Consistency check of the constraints evaluated over the out-of-domain frame.
+    Ood,
+    /// Checks of the trace and constraint queries against their commitments, and computation of
+    /// the DEEP composition polynomial at the query positions.
+    Queries,
+    /// Verification of the FRI proof against the evaluations of the DEEP composition polynomial.
+    Fri,
+}
+
+impl VerificationPhase {
+    /// All phases, in the order in which they are run.
+    pub const ALL: [Self; 4] = [Self::Transcript, Self::Ood, Self::Queries, Self::Fri];
+
+    /// Returns the phase which is run right before this one, if any.
+    pub fn previous(&self) -> Option<Self> {
+        match self {
+            Self::Transcript => None,
+            Self::Ood => Some(Self::Transcript),
+            Self::Queries => Some(Self::Ood),
+            Self::Fri => Some(Self::Queries),
+        }
+    }
+
+    /// Returns the encoding of this phase which is hashed into the digest of a phase state.
+    pub fn to_bytes(&self) -> [u8; 1] {
+        match self {
+            Self::Transcript => [1],
+            Self::Ood => [2],
+            Self::Queries => [3],
+            Self::Fri => [4],
+        }
+    }
+}
+
+impl fmt::Display for VerificationPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transcript => write!(f, "transcript"),
+            Self::Ood => write!(f, "OOD"),
+            Self::Queries => write!(f, "queries"),
+            Self::Fri => write!(f, "FRI"),
+        }
+    }
+}
+
+// PHASE STATE
+// ================================================================================================
+
+/// State of the verification of a proof after a phase completed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PhaseState {
+    /// The phase which completed.
+    pub phase: VerificationPhase,
+    /// Seed of the public coin after it was reseeded with the proof-of-work nonce; this commits
+    /// to everything the verifier reads from the proof before the queries.
+    pub seed: [u8; 32],
+    /// Serialized evaluations of the DEEP composition polynomial at the query positions; this is
+    /// empty until the queries phase completed.
+    pub deep_evaluations: Vec<u8>,
+}
+
+// PHASE VERIFICATION
+// ================================================================================================
+
+/// Runs the specified phase of the verification of the proof read from the provided channel, and
+/// returns the state reached by the phase.
+///
+/// The transcript is replayed by [replay()], with the coin seeded with `coin_seed` (the
+/// serialized public inputs and proof context), but only the checks of the specified phase are
+/// performed; the OOD consistency and proof-of-work checks compare their values with the
+/// constant-time helpers of [crate::ct]. Every phase but the first one is resumed from the state
+/// of the phase before it, whose seed must match the seed derived from the channel.
+///
+/// # Errors
+/// Returns an error if:
+/// * `prev_state` is not the state of the phase before the specified one.
+/// * The seed derived from the channel differs from the seed of `prev_state`.
+/// * The DEEP evaluations of `prev_state` cannot be decoded.
+/// * The channel holds no trace commitments.
+/// * The random elements drawn for an auxiliary trace segment are not fresh draws from the coin.
+/// * A check of the specified phase fails.
+pub fn verify_phase<A, E, H>(
+    air: &A,
+    channel: &mut VerifierChannel<E, H>,
+    coin_seed: &[u8],
+    phase: VerificationPhase,
+    prev_state: Option<&PhaseState>,
+) -> Result<PhaseState, PhaseError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: GuestHasher<BaseField = A::BaseField>,
+{
+    if phase.previous() != prev_state.map(|state| state.phase) {
+        return Err(PhaseError::UnexpectedState(phase));
+    }
+    let transcript = replay(air, channel, coin_seed)?;
+
+    // --- out-of-domain evaluations --------------------------------------------------------------
+    if phase == VerificationPhase::Ood {
+        let ood_constraint_evaluation_1 = evaluate_constraints(
+            air,
+            transcript.constraint_coeffs,
+            &transcript.ood_main_trace_frame,
+            &transcript.ood_aux_trace_frame,
+            transcript.aux_trace_rand_elements,
+            transcript.z,
+        );
+        let ood_constraint_evaluation_2 = transcript
+            .ood_constraint_evaluations
+            .iter()
+            .enumerate()
+            .fold(E::ZERO, |result, (i, &value)| {
+                result + transcript.z.exp((i as u32).into()) * value
+            });
+        if ct_eq_elements(ood_constraint_evaluation_1, ood_constraint_evaluation_2) == 0 {
+            return Err(VerifierError::InconsistentOodConstraintEvaluations.into());
+        }
+    }
+
+    // --- proof-of-work --------------------------------------------------------------------------
+    if phase == VerificationPhase::Transcript
+        && ct_ge_u32(
+            transcript.pow_leading_zeros,
+            air.options().grinding_factor(),
+        ) == 0
+    {
+        return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed.into());
+    }
+    let seed = transcript.seed.as_bytes();
+    if let Some(prev_state) = prev_state {
+        if prev_state.seed != seed {
+            return Err(PhaseError::SeedMismatch(phase));
+        }
+    }
+    let query_positions = &transcript.query_positions;
+
+    // --- queries and FRI ------------------------------------------------------------------------
+    let deep_evaluations = match (phase, prev_state) {
+        (VerificationPhase::Queries, _) => {
+            let (queried_main_trace_states, queried_aux_trace_states) =
+                channel.read_queried_trace_states(air, query_positions)?;
+            let queried_constraint_evaluations =
+                channel.read_constraint_evaluations(air, query_positions)?;
+
+            let composer = DeepComposer::new(
+                air,
+                query_positions,
+                transcript.z,
+                transcript.deep_coefficients,
+            );
+            let t_composition = composer.compose_trace_columns(
+                queried_main_trace_states,
+                queried_aux_trace_states,
+                transcript.ood_main_trace_frame,
+                transcript.ood_aux_trace_frame,
+            );
+            let c_composition = composer.compose_constraint_evaluations(
+                queried_constraint_evaluations,
+                transcript.ood_constraint_evaluations,
+            );
+            let deep_evaluations = composer.combine_compositions(t_composition, c_composition);
+            serialize_elements(deep_evaluations.into_iter())
+        }
+        (VerificationPhase::Fri, Some(prev_state)) => {
+            let deep_evaluations =
+                read_deep_evaluations::<E>(&prev_state.deep_evaluations, query_positions.len())?;
+            transcript
+                .fri_verifier
+                .verify(channel, &deep_evaluations, query_positions)
+                .map_err(VerifierError::FriVerificationFailed)?;
+            prev_state.deep_evaluations.clone()
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(PhaseState {
+        phase,
+        seed,
+        deep_evaluations,
+    })
+}
+
+// PHASE STITCHING
+// ================================================================================================
+
+/// Checks that the provided journals of the phase verifier guest, ordered by phase, attest to the
+/// complete verification of the statement with the specified digest, under the proof options and
+/// the proof policy with the specified digests.
+///
+/// # Errors
+/// Returns an error if the journals do not cover every phase exactly once and in order, if a
+/// journal was produced by a different version of the verification logic or is bound to a
+/// different statement, different proof options, or a different policy, if a phase failed, or if
+/// a phase was not resumed from the states committed by the phase before it.
+pub fn check_phase_journals<S: ShaHasherT>(
+    journals: &[PhaseJournal],
+    statement_digest: &[u8; 32],
+    options_digest: &[u8; 32],
+    policy_digest: &[u8; 32],
+) -> Result<(), PhaseError> {
+    if journals.len() != VerificationPhase::ALL.len() {
+        return Err(PhaseError::PhaseCount(journals.len()));
+    }
+
+    let mut prev_states: &[PhaseState] = &[];
+    for (journal, &phase) in journals.iter().zip(VerificationPhase::ALL.iter()) {
+        if journal.phase != phase {
+            return Err(PhaseError::OutOfOrder(phase, journal.phase));
+        }
+        if !journal.is_current_version() {
+            return Err(PhaseError::StaleVersion(phase));
+        }
+        if journal.statement_digest != *statement_digest {
+            return Err(PhaseError::StatementMismatch(phase));
+        }
+        if journal.options_digest != *options_digest {
+            return Err(PhaseError::OptionsMismatch(phase));
+        }
+        if journal.policy_digest.as_ref() != Some(policy_digest) {
+            return Err(PhaseError::PolicyMismatch(phase));
+        }
+        if !journal.result {
+            return Err(PhaseError::PhaseFailed(phase));
+        }
+        if journal.prev_states_digest != phase_states_digest::<S>(prev_states)
+            || journal.states.iter().any(|state| state.phase != phase)
+            || (phase.previous().is_some() && journal.states.len() != prev_states.len())
+        {
+            return Err(PhaseError::BrokenChain(phase));
+        }
+        prev_states = &journal.states;
+    }
+    Ok(())
+}
+
+// PHASE ERROR
+// ================================================================================================
+
+/// Failure to run a phase of the verification, or to stitch the journals of all phases.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PhaseError {
+    /// The state the specified phase was resumed from is not the state of the phase before it.
+    UnexpectedState(VerificationPhase),
+    /// The seed derived from the proof when resuming the specified phase differs from the seed of
+    /// the state it was resumed from.
+    SeedMismatch(VerificationPhase),
+    /// The DEEP evaluations of the state the FRI phase was resumed from cannot be decoded.
+    InvalidDeepEvaluations(DeserializationError),
+    /// The proof holds no trace commitments.
+    NoTraceCommitments,
+    /// The random elements drawn for an auxiliary trace segment are not fresh draws from the
+    /// coin.
+    TraceSegments(TraceSegmentMismatch),
+    /// A check of the verifier failed.
+    Verifier(VerifierError),
+    /// The number of journals differs from the number of phases.
+    PhaseCount(usize),
+    /// The journal at the position of the specified phase (first value) is the journal of a
+    /// different phase (second value).
+    OutOfOrder(VerificationPhase, VerificationPhase),
+    /// The journal of the specified phase was produced by a different version of the
+    /// verification logic.
+    StaleVersion(VerificationPhase),
+    /// The journal of the specified phase is bound to a different statement.
+    StatementMismatch(VerificationPhase),
+    /// The journal of the specified phase is bound to different proof options.
+    OptionsMismatch(VerificationPhase),
+    /// The journal of the specified phase is bound to a different proof policy, or to none.
+    PolicyMismatch(VerificationPhase),
+    /// The specified phase failed.
+    PhaseFailed(VerificationPhase),
+    /// The specified phase was not resumed from the states committed by the phase before it.
+    BrokenChain(VerificationPhase),
+}
+
+impl fmt::Display for PhaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedState(phase) => write!(
+                f,
+                "the {} phase was not resumed from the state of the phase before it",
+                phase
+            ),
+            Self::SeedMismatch(phase) => write!(
+                f,
+                "the seed derived when resuming the {} phase does not match the resumed state",
+                phase
+            ),
+            Self::InvalidDeepEvaluations(err) => {
+                write!(f, "failed to decode the DEEP evaluations: {}", err)
+            }
+            Self::NoTraceCommitments => write!(f, "the proof holds no trace commitments"),
+            Self::TraceSegments(err) => write!(f, "{}", err),
+            Self::Verifier(err) => write!(f, "{}", err),
+            Self::PhaseCount(count) => write!(
+                f,
+                "expected {} phase journals, but received {}",
+                VerificationPhase::ALL.len(),
+                count
+            ),
+            Self::OutOfOrder(expected, actual) => write!(
+                f,
+                "expected the journal of the {} phase, but received the journal of the {} phase",
+                expected, actual
+            ),
+            Self::StaleVersion(phase) => write!(
+                f,
+                "the journal of the {} phase was produced by a different verifier version",
+                phase
+            ),
+            Self::StatementMismatch(phase) => write!(
+                f,
+                "the journal of the {} phase is bound to a different statement",
+                phase
+            ),
+            Self::OptionsMismatch(phase) => write!(
+                f,
+                "the journal of the {} phase is bound to different proof options",
+                phase
+            ),
+            Self::PolicyMismatch(phase) => write!(
+                f,
+                "the journal of the {} phase is bound to a different proof policy",
+                phase
+            ),
+            Self::PhaseFailed(phase) => write!(f, "the {} phase failed", phase),
+            Self::BrokenChain(phase) => write!(
+                f,
+                "the {} phase was not resumed from the states committed by the phase before it",
+                phase
+            ),
+        }
+    }
+}
+
+impl From<VerifierError> for PhaseError {
+    fn from(error: VerifierError) -> Self {
+        Self::Verifier(error)
+    }
+}
+
+impl From<ReplayError> for PhaseError {
+    fn from(error: ReplayError) -> Self {
+        match error {
+            ReplayError::NoTraceCommitments => Self::NoTraceCommitments,
+            ReplayError::TraceSegments(err) => Self::TraceSegments(err),
+            ReplayError::Verifier(err) => Self::Verifier(err),
+        }
+    }
+}
+
+impl From<PhaseError> for ErrorChain {
+    fn from(error: PhaseError) -> Self {
+        ErrorChain::new(ErrorKind::Verification, error)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Decodes the specified number of DEEP evaluations from the provided bytes, which must contain
+/// nothing else.
+fn read_deep_evaluations<E: FieldElement>(
+    bytes: &[u8],
+    num_evaluations: usize,
+) -> Result<Vec<E>, PhaseError> {
+    let mut reader = SliceReader::new(bytes);
+    let evaluations = E::read_batch_from(&mut reader, num_evaluations)
+        .map_err(PhaseError::InvalidDeepEvaluations)?;
+    if reader.has_more_bytes() {
+        return Err(PhaseError::InvalidDeepEvaluations(
+            DeserializationError::UnconsumedBytes,
+        ));
+    }
+    Ok(evaluations)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{check_phase_journals, verify_phase, PhaseError, PhaseState, VerificationPhase};
+    use crate::fib::example::{Example, FibExample};
+    use crate::fib::fib_air::FibAir;
+    use crate::journal::{phase_states_digest, PhaseJournal};
+    use crate::mutation::ProofMutation;
+    use winter_air::{Air, FieldExtension, HashFunction, ProofOptions};
+    use winter_math::fields::f64_risc0::{BaseElement, DefaultNativeMul};
+    use winter_math::fields::QuadExtension;
+    use winter_verifier::crypto::hashers::{DefaultSha2, Sha2_256};
+    use winter_verifier::{Serializable, VerifierChannel, VerifierError};
+
+    type C = VerifierChannel<QuadExtension<BaseElement>, Sha2_256<BaseElement, DefaultSha2>>;
+
+    const STATEMENT_DIGEST: [u8; 32] = [7; 32];
+    const OPTIONS_DIGEST: [u8; 32] = [5; 32];
+    const POLICY_DIGEST: [u8; 32] = [3; 32];
+
+    /// Returns the AIR of a sample Fibonacci proof, a function building verifier channels of the
+    /// proof, and the seed of its public coin.
+    fn build_proof() -> (FibAir<DefaultNativeMul>, impl Fn() -> C, Vec<u8>) {
+        let options = ProofOptions::new(
+            4,
+            8,
+            0,
+            HashFunction::Sha2_256,
+            FieldExtension::Quadratic,
+            8,
+            256,
+        );
+        let example = FibExample::new(1024, options);
+        let proof = example.prove();
+        let air = FibAir::new(
+            proof.get_trace_info(),
+            example.result,
+            proof.options().clone(),
+        );
+
+        let mut coin_seed = Vec::new();
+        example.result.write_into(&mut coin_seed);
+        proof.context.write_into(&mut coin_seed);
+
+        let channel_air = FibAir::new(
+            proof.get_trace_info(),
+            example.result,
+            proof.options().clone(),
+        );
+        let build_channel = move || C::new(&channel_air, proof.clone()).unwrap();
+        (air, build_channel, coin_seed)
+    }
+
+    /// Runs all phases of the verification, each on a fresh channel, and returns the journals
+    /// a guest would commit for them.
+    fn run_phases(
+        air: &FibAir<DefaultNativeMul>,
+        build_channel: &impl Fn() -> C,
+        coin_seed: &[u8],
+    ) -> Vec<PhaseJournal> {
+        let mut journals = Vec::new();
+        let mut prev_states: Vec<PhaseState> = Vec::new();
+        for phase in VerificationPhase::ALL {
+            let state = verify_phase(
+                air,
+                &mut build_channel(),
+                coin_seed,
+                phase,
+                prev_states.first(),
+            )
+            .unwrap();
+            let prev_states_digest = phase_states_digest::<DefaultSha2>(&prev_states);
+            prev_states = vec![state];
+            journals.push(
+                PhaseJournal::new(
+                    STATEMENT_DIGEST,
+                    OPTIONS_DIGEST,
+                    phase,
+                    prev_states_digest,
+                    prev_states.clone(),
+                )
+                .with_policy_digest(POLICY_DIGEST),
+            );
+        }
+        journals
+    }
+
+    /// Checks the provided journals against the digests the journals of [run_phases] are bound
+    /// to.
+    fn check_journals(journals: &[PhaseJournal]) -> Result<(), PhaseError> {
+        check_phase_journals::<DefaultSha2>(
+            journals,
+            &STATEMENT_DIGEST,
+            &OPTIONS_DIGEST,
+            &POLICY_DIGEST,
+        )
+    }
+
+    #[test]
+    fn phases_verify_valid_proof() {
+        let (air, build_channel, coin_seed) = build_proof();
+        let journals = run_phases(&air, &build_channel, &coin_seed);
+
+        // the DEEP evaluations are carried over from the queries phase to the FRI phase
+        assert!(journals[1].states[0].deep_evaluations.is_empty());
+        assert!(!journals[2].states[0].deep_evaluations.is_empty());
+        assert_eq!(
+            journals[2].states[0].deep_evaluations,
+            journals[3].states[0].deep_evaluations
+        );
+
+        assert_eq!(Ok(()), check_journals(&journals));
+    }
+
+    #[test]
+    fn phases_detect_invalid_proofs() {
+        let (air, build_channel, coin_seed) = build_proof();
+        let transcript = verify_phase(
+            &air,
+            &mut build_channel(),
+            &coin_seed,
+            VerificationPhase::Transcript,
+            None,
+        )
+        .unwrap();
+
+        let mutated_channel = |mutation: &ProofMutation| {
+            let mut channel = build_channel();
+            mutation.apply_to_channel(&mut channel).unwrap();
+            channel
+        };
+
+        // the OOD check is performed in the OOD phase only
+        let mutation = ProofMutation::FlipOodConstraintEvaluation(0);
+        let mutated_transcript = verify_phase(
+            &air,
+            &mut mutated_channel(&mutation),
+            &coin_seed,
+            VerificationPhase::Transcript,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(PhaseError::Verifier(
+                VerifierError::InconsistentOodConstraintEvaluations
+            )),
+            verify_phase(
+                &air,
+                &mut mutated_channel(&mutation),
+                &coin_seed,
+                VerificationPhase::Ood,
+                Some(&mutated_transcript)
+            )
+        );
+
+        // a phase cannot be resumed from the state of a different proof
+        assert_eq!(
+            Err(PhaseError::SeedMismatch(VerificationPhase::Ood)),
+            verify_phase(
+                &air,
+                &mut mutated_channel(&ProofMutation::ChangeNonce),
+                &coin_seed,
+                VerificationPhase::Ood,
+                Some(&transcript)
+            )
+        );
+
+        // nor out of order
+        assert_eq!(
+            Err(PhaseError::UnexpectedState(VerificationPhase::Fri)),
+            verify_phase(
+                &air,
+                &mut build_channel(),
+                &coin_seed,
+                VerificationPhase::Fri,
+                Some(&transcript)
+            )
+        );
+    }
+
+    #[test]
+    fn phase_journals_must_form_a_chain() {
+        let (air, build_channel, coin_seed) = build_proof();
+        let journals = run_phases(&air, &build_channel, &coin_seed);
+
+        assert_eq!(
+            Err(PhaseError::PhaseCount(3)),
+            check_journals(&journals[..3])
+        );
+
+        let mut swapped = journals.clone();
+        swapped.swap(1, 2);
+        assert_eq!(
+            Err(PhaseError::OutOfOrder(
+                VerificationPhase::Ood,
+                VerificationPhase::Queries
+            )),
+            check_journals(&swapped)
+        );
+
+        assert_eq!(
+            Err(PhaseError::StatementMismatch(VerificationPhase::Transcript)),
+            check_phase_journals::<DefaultSha2>(
+                &journals,
+                &[8; 32],
+                &OPTIONS_DIGEST,
+                &POLICY_DIGEST
+            )
+        );
+
+        // every phase must be run under the same proof options and policy
+        let mut other_options = journals.clone();
+        other_options[1].options_digest = [8; 32];
+        assert_eq!(
+            Err(PhaseError::OptionsMismatch(VerificationPhase::Ood)),
+            check_journals(&other_options)
+        );
+        let mut other_policy = journals.clone();
+        other_policy[2].policy_digest = Some([8; 32]);
+        assert_eq!(
+            Err(PhaseError::PolicyMismatch(VerificationPhase::Queries)),
+            check_journals(&other_policy)
+        );
+        let mut no_policy = journals.clone();
+        no_policy[3].policy_digest = None;
+        assert_eq!(
+            Err(PhaseError::PolicyMismatch(VerificationPhase::Fri)),
+            check_journals(&no_policy)
+        );
+
+        // the FRI phase must be resumed from the DEEP evaluations committed by the queries phase
+        let mut tampered = journals.clone();
+        tampered[2].states[0].deep_evaluations[0] ^= 1;
+        assert_eq!(
+            Err(PhaseError::BrokenChain(VerificationPhase::Fri)),
+            check_journals(&tampered)
+        );
+
+        let mut failed = journals;
+        failed[3].result = false;
+        assert_eq!(
+            Err(PhaseError::PhaseFailed(VerificationPhase::Fri)),
+            check_journals(&failed)
+        );
+    }
+}
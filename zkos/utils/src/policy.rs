@@ -0,0 +1,626 @@
+//! Policies on the proof options of the proofs accepted by the verifier guests.
+//!
+//! A [ProofPolicy] bounds the security parameters of the proofs a guest verifies: the minimum
+//! number of queries, blowup factor, and grinding factor, and the allowed hash functions and
+//! field extensions. Policies are written in a small language of comma-separated clauses:
+//!
+//! ```text
+//! queries>=27,blowup>=8,grinding>=16,hash=sha2_256|sha2_512,field=quadratic
+//! ```
+//!
+//! Clauses which are omitted do not restrict the options, and the empty policy (written as `any`)
+//! accepts all options. The host refuses to package proofs which violate the policy, and passes
+//! the policy to the guest as a [POLICY_LEN]-byte header read before any other input:
+//!
+//! ```text
+//! | version | min queries | min blowup | min grinding | hash fns (2) | field extensions | reserved |
+//! ```
+//!
+//! The allowed hash functions are encoded as a little-endian bitmask over two bytes, and the
+//! allowed field extensions as a bitmask over one byte.
+//!
+//! The guest enforces the policy on every proof it verifies, and commits the digest of the policy
+//! to its journal, so that a receipt tells under which policy the proofs were accepted.
+
+use core::fmt;
+use core::str::FromStr;
+use errors::{ErrorChain, ErrorKind};
+use winter_air::{FieldExtension, HashFunction, ProofOptions};
+use winter_prover::crypto::hashers::ShaHasherT;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bytes in an encoded policy.
+pub const POLICY_LEN: usize = 8;
+
+/// Version of the encoding of policies.
+const POLICY_VERSION: u8 = 2;
+
+/// Domain separator of policy digests.
+const POLICY_DIGEST_DOMAIN: &[u8] = b"zkos-proof-policy";
+
+/// All hash functions, in the order of their bits in an encoded policy.
+const HASH_FNS: [HashFunction; 9] = [
+    HashFunction::Blake3_192,
+    HashFunction::Blake3_256,
+    HashFunction::Sha3_256,
+    HashFunction::Sha2_256,
+    HashFunction::Sha2_512,
+    HashFunction::Rp64_256,
+    HashFunction::Poseidon64_256,
+    HashFunction::Sha2_384,
+    HashFunction::Keccak256,
+];
+
+/// All field extensions, in the order of their bits in an encoded policy.
+const FIELD_EXTENSIONS: [FieldExtension; 3] = [
+    FieldExtension::None,
+    FieldExtension::Quadratic,
+    FieldExtension::Cubic,
+];
+
+// PROOF POLICY
+// ================================================================================================
+
+/// Bounds on the proof options of the proofs accepted by a verifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofPolicy {
+    min_num_queries: u8,
+    min_blowup_factor: u8,
+    min_grinding_factor: u8,
+    hash_fns: u16,
+    field_extensions: u8,
+}
+
+impl ProofPolicy {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a policy which accepts all proof options.
+    pub fn new() -> Self {
+        Self {
+            min_num_queries: 0,
+            min_blowup_factor: 0,
+            min_grinding_factor: 0,
+            hash_fns: mask(HASH_FNS.len()),
+            field_extensions: mask(FIELD_EXTENSIONS.len()) as u8,
+        }
+    }
+
+    /// Returns this policy with proofs required to run at least the specified number of queries.
+    pub fn with_min_num_queries(mut self, num_queries: u8) -> Self {
+        self.min_num_queries = num_queries;
+        self
+    }
+
+    /// Returns this policy with proofs required to use at least the specified blowup factor.
+    pub fn with_min_blowup_factor(mut self, blowup_factor: u8) -> Self {
+        self.min_blowup_factor = blowup_factor;
+        self
+    }
+
+    /// Returns this policy with proofs required to grind for at least the specified number of
+    /// bits.
+    pub fn with_min_grinding_factor(mut self, grinding_factor: u8) -> Self {
+        self.min_grinding_factor = grinding_factor;
+        self
+    }
+
+    /// Returns this policy with proofs required to use one of the specified hash functions.
+    pub fn with_hash_fns(mut self, hash_fns: &[HashFunction]) -> Self {
+        self.hash_fns = hash_fns
+            .iter()
+            .fold(0, |bits, &hash_fn| bits | hash_fn_bit(hash_fn));
+        self
+    }
+
+    /// Returns this policy with proofs required to use one of the specified field extensions.
+    pub fn with_field_extensions(mut self, field_extensions: &[FieldExtension]) -> Self {
+        self.field_extensions = field_extensions
+            .iter()
+            .fold(0, |bits, &extension| bits | field_extension_bit(extension));
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the minimum number of queries of accepted proofs.
+    pub fn min_num_queries(&self) -> u8 {
+        self.min_num_queries
+    }
+
+    /// Returns the minimum blowup factor of accepted proofs.
+    pub fn min_blowup_factor(&self) -> u8 {
+        self.min_blowup_factor
+    }
+
+    /// Returns the minimum grinding factor of accepted proofs.
+    pub fn min_grinding_factor(&self) -> u8 {
+        self.min_grinding_factor
+    }
+
+    /// Returns true if proofs using the specified hash function are accepted.
+    pub fn allows_hash_fn(&self, hash_fn: HashFunction) -> bool {
+        self.hash_fns & hash_fn_bit(hash_fn) != 0
+    }
+
+    /// Returns true if proofs using the specified field extension are accepted.
+    pub fn allows_field_extension(&self, field_extension: FieldExtension) -> bool {
+        self.field_extensions & field_extension_bit(field_extension) != 0
+    }
+
+    // ENFORCEMENT
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that the provided proof options satisfy this policy.
+    ///
+    /// # Errors
+    /// Returns an error describing the first bound the options violate.
+    pub fn check(&self, options: &ProofOptions) -> Result<(), PolicyViolation> {
+        if options.num_queries() < self.min_num_queries as usize {
+            return Err(PolicyViolation::NumQueries(
+                self.min_num_queries,
+                options.num_queries(),
+            ));
+        }
+        if options.blowup_factor() < self.min_blowup_factor as usize {
+            return Err(PolicyViolation::BlowupFactor(
+                self.min_blowup_factor,
+                options.blowup_factor(),
+            ));
+        }
+        if options.grinding_factor() < self.min_grinding_factor as u32 {
+            return Err(PolicyViolation::GrindingFactor(
+                self.min_grinding_factor,
+                options.grinding_factor(),
+            ));
+        }
+        if !self.allows_hash_fn(options.hash_fn()) {
+            return Err(PolicyViolation::HashFn(options.hash_fn()));
+        }
+        if !self.allows_field_extension(options.field_extension()) {
+            return Err(PolicyViolation::FieldExtension(options.field_extension()));
+        }
+        Ok(())
+    }
+
+    // ENCODING
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns this policy encoded as a guest input header.
+    pub fn to_bytes(&self) -> [u8; POLICY_LEN] {
+        let [hash_fns_lo, hash_fns_hi] = self.hash_fns.to_le_bytes();
+        [
+            POLICY_VERSION,
+            self.min_num_queries,
+            self.min_blowup_factor,
+            self.min_grinding_factor,
+            hash_fns_lo,
+            hash_fns_hi,
+            self.field_extensions,
+            0,
+        ]
+    }
+
+    /// Returns the policy encoded in the provided guest input header.
+    ///
+    /// # Errors
+    /// Returns an error if the header was encoded by an unsupported version, if it allows
+    /// unknown hash functions or field extensions, or if its reserved bytes are not zero.
+    pub fn from_bytes(bytes: &[u8; POLICY_LEN]) -> Result<Self, PolicyError> {
+        if bytes[0] != POLICY_VERSION {
+            return Err(PolicyError::UnsupportedVersion(bytes[0]));
+        }
+        let hash_fns = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if hash_fns & !mask(HASH_FNS.len()) != 0
+            || u16::from(bytes[6]) & !mask(FIELD_EXTENSIONS.len()) != 0
+            || bytes[7] != 0
+        {
+            return Err(PolicyError::MalformedHeader);
+        }
+        Ok(Self {
+            min_num_queries: bytes[1],
+            min_blowup_factor: bytes[2],
+            min_grinding_factor: bytes[3],
+            hash_fns,
+            field_extensions: bytes[6],
+        })
+    }
+
+    /// Returns the digest of this policy committed by the guests.
+    pub fn digest<S: ShaHasherT>(&self) -> [u8; 32] {
+        let mut data = Vec::with_capacity(POLICY_DIGEST_DOMAIN.len() + POLICY_LEN);
+        data.extend_from_slice(POLICY_DIGEST_DOMAIN);
+        data.extend_from_slice(&self.to_bytes());
+        S::digest(&data)
+    }
+}
+
+impl Default for ProofPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromStr for ProofPolicy {
+    type Err = PolicyError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut policy = Self::new();
+        let source = source.trim();
+        if source.is_empty() || source == "any" {
+            return Ok(policy);
+        }
+
+        for clause in source.split(',').map(str::trim) {
+            if let Some((name, value)) = clause.split_once(">=") {
+                let value = value
+                    .trim()
+                    .parse::<u8>()
+                    .map_err(|_| PolicyError::InvalidValue(clause.to_string()))?;
+                policy = match name.trim() {
+                    "queries" => policy.with_min_num_queries(value),
+                    "blowup" => policy.with_min_blowup_factor(value),
+                    "grinding" => policy.with_min_grinding_factor(value),
+                    _ => return Err(PolicyError::InvalidClause(clause.to_string())),
+                };
+            } else if let Some((name, values)) = clause.split_once('=') {
+                let values = values.split('|').map(str::trim);
+                policy = match name.trim() {
+                    "hash" => policy
+                        .with_hash_fns(&values.map(parse_hash_fn).collect::<Result<Vec<_>, _>>()?),
+                    "field" => policy.with_field_extensions(
+                        &values
+                            .map(parse_field_extension)
+                            .collect::<Result<Vec<_>, _>>()?,
+                    ),
+                    _ => return Err(PolicyError::InvalidClause(clause.to_string())),
+                };
+            } else {
+                return Err(PolicyError::InvalidClause(clause.to_string()));
+            }
+        }
+        Ok(policy)
+    }
+}
+
+impl fmt::Display for ProofPolicy {
+    /// Writes this policy in the policy language; only the clauses which restrict the options are
+    /// written.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut clauses = Vec::new();
+        for (name, value) in [
+            ("queries", self.min_num_queries),
+            ("blowup", self.min_blowup_factor),
+            ("grinding", self.min_grinding_factor),
+        ] {
+            if value > 0 {
+                clauses.push(format!("{}>={}", name, value));
+            }
+        }
+        if self.hash_fns != mask(HASH_FNS.len()) {
+            let names = HASH_FNS
+                .iter()
+                .filter(|&&hash_fn| self.allows_hash_fn(hash_fn))
+                .map(|&hash_fn| hash_fn_name(hash_fn))
+                .collect::<Vec<_>>();
+            clauses.push(format!("hash={}", names.join("|")));
+        }
+        if u16::from(self.field_extensions) != mask(FIELD_EXTENSIONS.len()) {
+            let names = FIELD_EXTENSIONS
+                .iter()
+                .filter(|&&extension| self.allows_field_extension(extension))
+                .map(|&extension| field_extension_name(extension))
+                .collect::<Vec<_>>();
+            clauses.push(format!("field={}", names.join("|")));
+        }
+
+        match clauses.is_empty() {
+            true => write!(f, "any"),
+            false => write!(f, "{}", clauses.join(",")),
+        }
+    }
+}
+
+// POLICY VIOLATION
+// ================================================================================================
+
+/// A bound of a [ProofPolicy] violated by proof options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// The number of queries (second value) is smaller than the minimum (first value).
+    NumQueries(u8, usize),
+    /// The blowup factor (second value) is smaller than the minimum (first value).
+    BlowupFactor(u8, usize),
+    /// The grinding factor (second value) is smaller than the minimum (first value).
+    GrindingFactor(u8, u32),
+    /// The hash function is not allowed.
+    HashFn(HashFunction),
+    /// The field extension is not allowed.
+    FieldExtension(FieldExtension),
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NumQueries(min, actual) => {
+                write!(
+                    f,
+                    "proof runs {} queries, but at least {} are required",
+                    actual, min
+                )
+            }
+            Self::BlowupFactor(min, actual) => write!(
+                f,
+                "proof uses blowup factor {}, but at least {} is required",
+                actual, min
+            ),
+            Self::GrindingFactor(min, actual) => write!(
+                f,
+                "proof grinds for {} bits, but at least {} are required",
+                actual, min
+            ),
+            Self::HashFn(hash_fn) => {
+                write!(f, "hash function {} is not allowed", hash_fn_name(*hash_fn))
+            }
+            Self::FieldExtension(extension) => write!(
+                f,
+                "field extension {} is not allowed",
+                field_extension_name(*extension)
+            ),
+        }
+    }
+}
+
+impl From<PolicyViolation> for ErrorChain {
+    fn from(violation: PolicyViolation) -> Self {
+        ErrorChain::new(ErrorKind::Policy, violation)
+    }
+}
+
+// POLICY ERROR
+// ================================================================================================
+
+/// Failure to parse or to decode a [ProofPolicy].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyError {
+    /// The clause is not a clause of the policy language.
+    InvalidClause(String),
+    /// The bound of the clause is not a number between 0 and 255.
+    InvalidValue(String),
+    /// The name is not the name of a hash function.
+    UnknownHashFn(String),
+    /// The name is not the name of a field extension.
+    UnknownFieldExtension(String),
+    /// The header was encoded by an unsupported version of the encoding.
+    UnsupportedVersion(u8),
+    /// The header allows unknown hash functions or field extensions, or has non-zero reserved
+    /// bytes.
+    MalformedHeader,
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidClause(clause) => write!(f, "'{}' is not a valid policy clause", clause),
+            Self::InvalidValue(clause) => {
+                write!(
+                    f,
+                    "bound of policy clause '{}' is not a valid value",
+                    clause
+                )
+            }
+            Self::UnknownHashFn(name) => write!(f, "'{}' is not a known hash function", name),
+            Self::UnknownFieldExtension(name) => {
+                write!(f, "'{}' is not a known field extension", name)
+            }
+            Self::UnsupportedVersion(version) => {
+                write!(f, "policy header version {} is not supported", version)
+            }
+            Self::MalformedHeader => write!(f, "policy header is malformed"),
+        }
+    }
+}
+
+impl From<PolicyError> for ErrorChain {
+    fn from(error: PolicyError) -> Self {
+        ErrorChain::new(ErrorKind::Policy, error)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a bit mask with the lowest `len` bits set.
+fn mask(len: usize) -> u16 {
+    ((1u32 << len) - 1) as u16
+}
+
+/// Returns the bit of the specified hash function in an encoded policy.
+fn hash_fn_bit(hash_fn: HashFunction) -> u16 {
+    let index = HASH_FNS.iter().position(|&h| h == hash_fn).unwrap();
+    1 << index
+}
+
+/// Returns the bit of the specified field extension in an encoded policy.
+fn field_extension_bit(extension: FieldExtension) -> u8 {
+    let index = FIELD_EXTENSIONS
+        .iter()
+        .position(|&e| e == extension)
+        .unwrap();
+    1 << index
+}
+
+/// Returns the name of the specified hash function in the policy language.
+fn hash_fn_name(hash_fn: HashFunction) -> &'static str {
+    match hash_fn {
+        HashFunction::Blake3_192 => "blake3_192",
+        HashFunction::Blake3_256 => "blake3_256",
+        HashFunction::Sha3_256 => "sha3_256",
+        HashFunction::Sha2_256 => "sha2_256",
+        HashFunction::Sha2_512 => "sha2_512",
+        HashFunction::Rp64_256 => "rp64_256",
+        HashFunction::Poseidon64_256 => "poseidon64_256",
+        HashFunction::Sha2_384 => "sha2_384",
+        HashFunction::Keccak256 => "keccak256",
+    }
+}
+
+/// Parses the name of a hash function in the policy language.
+fn parse_hash_fn(name: &str) -> Result<HashFunction, PolicyError> {
+    HASH_FNS
+        .iter()
+        .copied()
+        .find(|&hash_fn| hash_fn_name(hash_fn) == name)
+        .ok_or_else(|| PolicyError::UnknownHashFn(name.to_string()))
+}
+
+/// Returns the name of the specified field extension in the policy language.
+fn field_extension_name(extension: FieldExtension) -> &'static str {
+    match extension {
+        FieldExtension::None => "none",
+        FieldExtension::Quadratic => "quadratic",
+        FieldExtension::Cubic => "cubic",
+    }
+}
+
+/// Parses the name of a field extension in the policy language.
+fn parse_field_extension(name: &str) -> Result<FieldExtension, PolicyError> {
+    FIELD_EXTENSIONS
+        .iter()
+        .copied()
+        .find(|&extension| field_extension_name(extension) == name)
+        .ok_or_else(|| PolicyError::UnknownFieldExtension(name.to_string()))
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{PolicyError, PolicyViolation, ProofPolicy, POLICY_LEN};
+    use winter_air::{FieldExtension, HashFunction, ProofOptions};
+
+    fn options(num_queries: usize, blowup_factor: usize, grinding_factor: u32) -> ProofOptions {
+        ProofOptions::new(
+            num_queries,
+            blowup_factor,
+            grinding_factor,
+            HashFunction::Sha2_256,
+            FieldExtension::Quadratic,
+            8,
+            256,
+        )
+    }
+
+    #[test]
+    fn parse_policy() {
+        let policy: ProofPolicy = "queries>=27, blowup>=8,grinding>=16,hash=sha2_256|sha2_512"
+            .parse()
+            .unwrap();
+        let expected = ProofPolicy::new()
+            .with_min_num_queries(27)
+            .with_min_blowup_factor(8)
+            .with_min_grinding_factor(16)
+            .with_hash_fns(&[HashFunction::Sha2_256, HashFunction::Sha2_512]);
+        assert_eq!(expected, policy);
+        assert_eq!(
+            "queries>=27,blowup>=8,grinding>=16,hash=sha2_256|sha2_512",
+            policy.to_string()
+        );
+        assert_eq!(policy, policy.to_string().parse().unwrap());
+
+        assert_eq!(ProofPolicy::new(), "".parse().unwrap());
+        assert_eq!(ProofPolicy::new(), "any".parse().unwrap());
+        assert_eq!("any", ProofPolicy::new().to_string());
+    }
+
+    #[test]
+    fn parse_invalid_policy() {
+        assert_eq!(
+            Err(PolicyError::InvalidClause("queries<=27".to_string())),
+            "queries<=27".parse::<ProofPolicy>()
+        );
+        assert_eq!(
+            Err(PolicyError::InvalidValue("blowup>=256".to_string())),
+            "blowup>=256".parse::<ProofPolicy>()
+        );
+        assert_eq!(
+            Err(PolicyError::UnknownHashFn("md5".to_string())),
+            "hash=sha2_256|md5".parse::<ProofPolicy>()
+        );
+        assert_eq!(
+            Err(PolicyError::UnknownFieldExtension("quartic".to_string())),
+            "field=quartic".parse::<ProofPolicy>()
+        );
+    }
+
+    #[test]
+    fn check_options() {
+        let policy: ProofPolicy = "queries>=27,blowup>=8,grinding>=16,field=quadratic|cubic"
+            .parse()
+            .unwrap();
+        assert_eq!(Ok(()), policy.check(&options(27, 8, 16)));
+        assert_eq!(Ok(()), ProofPolicy::new().check(&options(1, 2, 0)));
+
+        assert_eq!(
+            Err(PolicyViolation::NumQueries(27, 26)),
+            policy.check(&options(26, 8, 16))
+        );
+        assert_eq!(
+            Err(PolicyViolation::BlowupFactor(8, 4)),
+            policy.check(&options(27, 4, 16))
+        );
+        assert_eq!(
+            Err(PolicyViolation::GrindingFactor(16, 15)),
+            policy.check(&options(27, 8, 15))
+        );
+
+        let policy = policy.with_hash_fns(&[HashFunction::Sha2_512]);
+        assert_eq!(
+            Err(PolicyViolation::HashFn(HashFunction::Sha2_256)),
+            policy.check(&options(27, 8, 16))
+        );
+        let policy = ProofPolicy::new().with_field_extensions(&[FieldExtension::Cubic]);
+        assert_eq!(
+            Err(PolicyViolation::FieldExtension(FieldExtension::Quadratic)),
+            policy.check(&options(27, 8, 16))
+        );
+    }
+
+    #[test]
+    fn encode_policy() {
+        let policy: ProofPolicy = "queries>=27,grinding>=16,hash=sha2_256,field=quadratic"
+            .parse()
+            .unwrap();
+        let bytes = policy.to_bytes();
+        assert_eq!(Ok(policy), ProofPolicy::from_bytes(&bytes));
+
+        // hash functions after the first eight are encoded in the second byte of the mask
+        let keccak: ProofPolicy = "hash=keccak256".parse().unwrap();
+        assert_eq!([0, 1], keccak.to_bytes()[4..6]);
+        assert_eq!(Ok(keccak), ProofPolicy::from_bytes(&keccak.to_bytes()));
+
+        // headers of the first version have a single byte of hash functions
+        let mut version = bytes;
+        version[0] = 1;
+        assert_eq!(
+            Err(PolicyError::UnsupportedVersion(1)),
+            ProofPolicy::from_bytes(&version)
+        );
+        let mut unknown_hash_fn = bytes;
+        unknown_hash_fn[5] = 0x02;
+        assert_eq!(
+            Err(PolicyError::MalformedHeader),
+            ProofPolicy::from_bytes(&unknown_hash_fn)
+        );
+        let mut reserved = bytes;
+        reserved[POLICY_LEN - 1] = 1;
+        assert_eq!(
+            Err(PolicyError::MalformedHeader),
+            ProofPolicy::from_bytes(&reserved)
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Poseidon hash function over the 64-bit field, for experimenting with SNARK-friendly
+//! transcripts.
+//!
+//! The hasher is shared by the host, which generates the proofs, and the guests, which verify
+//! them; it is generic over the implementation of the field, so that the guests hash with their
+//! accelerated field arithmetic. Its sponge has the same shape as the one of
+//! [Rp64_256](winter_prover::crypto::hashers::Rp64_256): a state of 12 elements, of which 8 are
+//! the rate and 4 the capacity, and digests of 4 elements.
+//!
+//! The permutation follows the Poseidon paper with an S-box of x^7, 8 full rounds and 22 partial
+//! rounds, and the MDS matrix of Plonky2 for a state of 12 elements. The round constants are NOT
+//! the reference constants of Poseidon: they are generated from a fixed seed by SplitMix64, which
+//! is enough to experiment with the cost of the transcripts, but the hasher must not be relied on
+//! for the security of proofs until the reference constants are plugged in.
+
+use core::marker::PhantomData;
+use core::ops::Range;
+use winter_math::{FieldElement, StarkField};
+use winter_prover::crypto::{ByteDigest, ElementHasher, Hasher};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Modulus of the field the hasher is defined over, 2^64 - 2^32 + 1.
+const MODULUS: u64 = 0xffff_ffff_0000_0001;
+
+/// Number of elements of the state of the sponge.
+const STATE_WIDTH: usize = 12;
+
+/// The capacity portion of the state is located in elements 0, 1, 2, and 3.
+const CAPACITY_RANGE: Range<usize> = 0..4;
+
+/// The rate portion of the state is located in elements 4 through 11 (inclusive).
+const RATE_RANGE: Range<usize> = 4..12;
+const RATE_WIDTH: usize = RATE_RANGE.end - RATE_RANGE.start;
+
+/// The output of the hash function can be read from state elements 4, 5, 6, and 7.
+const DIGEST_RANGE: Range<usize> = 4..8;
+const DIGEST_SIZE: usize = DIGEST_RANGE.end - DIGEST_RANGE.start;
+
+/// Number of full rounds; half of them are applied before the partial rounds, and half after.
+const NUM_FULL_ROUNDS: usize = 8;
+
+/// Number of partial rounds, in which the S-box is applied to the first element of the state
+/// only.
+const NUM_PARTIAL_ROUNDS: usize = 22;
+
+const NUM_ROUNDS: usize = NUM_FULL_ROUNDS + NUM_PARTIAL_ROUNDS;
+
+/// First row of the circulant part of the MDS matrix.
+const MDS_CIRCULANT: [u64; STATE_WIDTH] = [17, 15, 41, 16, 2, 28, 13, 13, 39, 18, 34, 20];
+
+/// Diagonal part of the MDS matrix.
+const MDS_DIAGONAL: [u64; STATE_WIDTH] = [8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+/// Seed of the generator of the round constants.
+const ROUND_CONSTANTS_SEED: u64 = u64::from_le_bytes(*b"poseidon");
+
+/// Constants added to the state in every round, in canonical form.
+const ROUND_CONSTANTS: [[u64; STATE_WIDTH]; NUM_ROUNDS] = round_constants();
+
+// POSEIDON WITH 256-BIT OUTPUT
+// ================================================================================================
+
+/// Implementation of the [Hasher] trait for the Poseidon hash function with 256-bit output over
+/// the 64-bit field with modulus 2^64 - 2^32 + 1.
+///
+/// The digests are the 4 elements of the output of the sponge, serialized as canonical integers
+/// in little-endian byte order.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Poseidon64_256<B: StarkField<PositiveInteger = u64>>(PhantomData<B>);
+
+impl<B: StarkField<PositiveInteger = u64>> Hasher for Poseidon64_256<B> {
+    type Digest = ByteDigest<32>;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        // the bytes are absorbed in 7-byte chunks, since every 7-byte chunk maps to an element;
+        // the last chunk is padded with a byte with value 1, so that trailing zeros change the
+        // hash
+        let num_elements = (bytes.len() + 6) / 7;
+        let mut state = initial_state::<B>(num_elements);
+        let mut i = 0;
+        for (index, chunk) in bytes.chunks(7).enumerate() {
+            let mut buf = [0_u8; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            if index == num_elements - 1 {
+                buf[chunk.len()] = 1;
+            }
+            state[RATE_RANGE.start + i] += B::from(u64::from_le_bytes(buf));
+            i += 1;
+            if i % RATE_WIDTH == 0 {
+                Self::apply_permutation(&mut state);
+                i = 0;
+            }
+        }
+        if i > 0 {
+            Self::apply_permutation(&mut state);
+        }
+        digest_from_state(&state)
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        // the digests fill the rate portion of the state exactly
+        let mut state = initial_state::<B>(RATE_WIDTH);
+        state[RATE_RANGE.start..RATE_RANGE.start + DIGEST_SIZE]
+            .copy_from_slice(&digest_elements(&values[0]));
+        state[RATE_RANGE.start + DIGEST_SIZE..RATE_RANGE.end]
+            .copy_from_slice(&digest_elements(&values[1]));
+        Self::apply_permutation(&mut state);
+        digest_from_state(&state)
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        // the seed is followed by the value, which is split into two elements if it does not fit
+        // into a single one
+        let input = RATE_RANGE.start + DIGEST_SIZE;
+        let mut state = if value < MODULUS {
+            let mut state = initial_state::<B>(DIGEST_SIZE + 1);
+            state[input] = B::from(value);
+            state
+        } else {
+            let mut state = initial_state::<B>(DIGEST_SIZE + 2);
+            state[input] = B::from(value % MODULUS);
+            state[input + 1] = B::from(value / MODULUS);
+            state
+        };
+        state[RATE_RANGE.start..input].copy_from_slice(&digest_elements(&seed));
+        Self::apply_permutation(&mut state);
+        digest_from_state(&state)
+    }
+}
+
+impl<B: StarkField<PositiveInteger = u64>> ElementHasher for Poseidon64_256<B> {
+    type BaseField = B;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        Self::hash_base_elements(E::as_base_elements(elements), &[])
+    }
+}
+
+impl<B: StarkField<PositiveInteger = u64>> Poseidon64_256<B> {
+    /// Returns a hash of the concatenation of `first` and `second` element sequences.
+    ///
+    /// The result is the same as calling `hash_elements()` on a concatenated vector of elements,
+    /// but the sequences are absorbed one after another without being merged first.
+    pub fn hash_split_elements<E: FieldElement<BaseField = B>>(
+        first: &[E],
+        second: &[E],
+    ) -> ByteDigest<32> {
+        Self::hash_base_elements(E::as_base_elements(first), E::as_base_elements(second))
+    }
+
+    /// Returns a hash of the concatenation of `first` and `second` base element sequences.
+    fn hash_base_elements(first: &[B], second: &[B]) -> ByteDigest<32> {
+        // the number of elements is injected into the capacity, and thus, no padding is needed
+        let mut state = initial_state::<B>(first.len() + second.len());
+        let mut i = 0;
+        for &element in first.iter().chain(second) {
+            state[RATE_RANGE.start + i] += element;
+            i += 1;
+            if i % RATE_WIDTH == 0 {
+                Self::apply_permutation(&mut state);
+                i = 0;
+            }
+        }
+        if i > 0 {
+            Self::apply_permutation(&mut state);
+        }
+        digest_from_state(&state)
+    }
+
+    // POSEIDON PERMUTATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Applies the Poseidon permutation to the provided state.
+    ///
+    /// # Panics
+    /// Panics if the modulus of `B` is not 2^64 - 2^32 + 1.
+    pub fn apply_permutation(state: &mut [B; STATE_WIDTH]) {
+        assert_eq!(
+            MODULUS,
+            B::MODULUS,
+            "Poseidon64_256 requires the 64-bit field"
+        );
+        let circulant = MDS_CIRCULANT.map(B::from);
+        let diagonal = MDS_DIAGONAL.map(B::from);
+
+        let half_full_rounds = NUM_FULL_ROUNDS / 2;
+        for (round, constants) in ROUND_CONSTANTS.iter().enumerate() {
+            for (element, &constant) in state.iter_mut().zip(constants) {
+                *element += B::from(constant);
+            }
+            let is_full_round =
+                round < half_full_rounds || round >= half_full_rounds + NUM_PARTIAL_ROUNDS;
+            if is_full_round {
+                state
+                    .iter_mut()
+                    .for_each(|element| *element = sbox(*element));
+            } else {
+                state[0] = sbox(state[0]);
+            }
+            apply_mds(state, &circulant, &diagonal);
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a state of zeros, except for the first element of the capacity, which is set to the
+/// number of elements to be hashed.
+fn initial_state<B: StarkField>(num_elements: usize) -> [B; STATE_WIDTH] {
+    let mut state = [B::ZERO; STATE_WIDTH];
+    state[CAPACITY_RANGE.start] = B::from(num_elements as u64);
+    state
+}
+
+/// Returns the digest read from the provided state.
+fn digest_from_state<B: StarkField<PositiveInteger = u64>>(
+    state: &[B; STATE_WIDTH],
+) -> ByteDigest<32> {
+    let mut bytes = [0; 32];
+    for (chunk, element) in bytes.chunks_mut(8).zip(&state[DIGEST_RANGE]) {
+        chunk.copy_from_slice(&element.as_int().to_le_bytes());
+    }
+    ByteDigest::new(bytes)
+}
+
+/// Returns the elements of the provided digest; integers which are not in canonical form are
+/// reduced.
+fn digest_elements<B: StarkField>(digest: &ByteDigest<32>) -> [B; DIGEST_SIZE] {
+    let bytes = digest.get_raw();
+    let mut elements = [B::ZERO; DIGEST_SIZE];
+    for (element, chunk) in elements.iter_mut().zip(bytes.chunks(8)) {
+        *element = B::from(u64::from_le_bytes(chunk.try_into().unwrap()));
+    }
+    elements
+}
+
+/// Returns x^7.
+#[inline(always)]
+fn sbox<B: StarkField>(x: B) -> B {
+    let x2 = x.square();
+    let x4 = x2.square();
+    x4 * x2 * x
+}
+
+/// Multiplies the state by the MDS matrix, the sum of a circulant matrix and a diagonal one.
+fn apply_mds<B: StarkField>(
+    state: &mut [B; STATE_WIDTH],
+    circulant: &[B; STATE_WIDTH],
+    diagonal: &[B; STATE_WIDTH],
+) {
+    let mut result = [B::ZERO; STATE_WIDTH];
+    for (row, output) in result.iter_mut().enumerate() {
+        for (i, &coefficient) in circulant.iter().enumerate() {
+            *output += coefficient * state[(i + row) % STATE_WIDTH];
+        }
+        *output += diagonal[row] * state[row];
+    }
+    *state = result;
+}
+
+/// Returns the round constants generated by SplitMix64 from [ROUND_CONSTANTS_SEED]; outputs which
+/// are not in canonical form are skipped.
+const fn round_constants() -> [[u64; STATE_WIDTH]; NUM_ROUNDS] {
+    let mut constants = [[0; STATE_WIDTH]; NUM_ROUNDS];
+    let mut seed = ROUND_CONSTANTS_SEED;
+    let mut i = 0;
+    while i < NUM_ROUNDS * STATE_WIDTH {
+        seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        if z < MODULUS {
+            constants[i / STATE_WIDTH][i % STATE_WIDTH] = z;
+            i += 1;
+        }
+    }
+    constants
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{digest_elements, Poseidon64_256, MODULUS, ROUND_CONSTANTS, STATE_WIDTH};
+    use winter_math::fields::f64_risc0::BaseElement;
+    use winter_math::fields::QuadExtension;
+    use winter_math::{FieldElement, StarkField};
+    use winter_prover::crypto::{ElementHasher, Hasher};
+
+    type Poseidon = Poseidon64_256<BaseElement>;
+
+    #[test]
+    fn round_constants_are_canonical() {
+        assert_eq!(0xb30d_750a_0b06_82b4, ROUND_CONSTANTS[0][0]);
+        for constant in ROUND_CONSTANTS.iter().flatten() {
+            assert!(*constant < MODULUS);
+        }
+    }
+
+    #[test]
+    fn permutation_known_answer() {
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        for (i, element) in state.iter_mut().enumerate() {
+            *element = BaseElement::from(i as u64);
+        }
+        Poseidon::apply_permutation(&mut state);
+
+        let expected: [u64; STATE_WIDTH] = [
+            0xc061_f1b0_ba04_f929,
+            0x14d3_f701_46bc_27b8,
+            0x35dc_d17f_2a9d_1488,
+            0xa15d_4680_52a5_addf,
+            0x8e97_4fd5_13b0_b7a7,
+            0xdb1f_f526_2d6e_836c,
+            0x03e4_a4d5_5488_3625,
+            0x3688_c072_527e_0528,
+            0xa4b9_013d_e41b_1702,
+            0x6deb_12dd_2f4f_5e78,
+            0xf016_bdd6_f9a6_15df,
+            0xe795_cba1_4983_bf4e,
+        ];
+        assert_eq!(expected, state.map(|element| element.as_int()));
+    }
+
+    #[test]
+    fn hash_elements_known_answer() {
+        let elements: Vec<BaseElement> = (1..11u64).map(BaseElement::from).collect();
+        let digest = Poseidon::hash_elements(&elements);
+        let expected = [
+            0x9ab5_a20d_0ba6_7e14,
+            0xa36b_4de5_1308_ebb0,
+            0x496d_2c05_9a89_5718,
+            0x29a2_cb5d_31ce_122e,
+        ];
+        assert_eq!(
+            expected,
+            digest_elements::<BaseElement>(&digest).map(|element| element.as_int())
+        );
+    }
+
+    #[test]
+    fn hash_split_elements_matches_hash_elements() {
+        let elements: Vec<QuadExtension<BaseElement>> = (0..13)
+            .map(|i| QuadExtension::new(BaseElement::from(i as u64), BaseElement::from(7u64)))
+            .collect();
+        for split in [0, 4, 8, 13] {
+            let (first, second) = elements.split_at(split);
+            assert_eq!(
+                Poseidon::hash_elements(&elements),
+                Poseidon::hash_split_elements(first, second)
+            );
+        }
+    }
+
+    #[test]
+    fn hashes_are_domain_separated() {
+        // trailing zeros change the hash of both bytes and elements
+        assert_ne!(Poseidon::hash(&[1, 2, 3]), Poseidon::hash(&[1, 2, 3, 0]));
+        let elements = [BaseElement::ONE; 3];
+        assert_ne!(
+            Poseidon::hash_elements(&elements),
+            Poseidon::hash_elements(&[elements[0], elements[1], elements[2], BaseElement::ZERO])
+        );
+
+        // the order of merged digests matters, and values beyond the modulus are not reduced
+        let a = Poseidon::hash(b"a");
+        let b = Poseidon::hash(b"b");
+        assert_ne!(Poseidon::merge(&[a, b]), Poseidon::merge(&[b, a]));
+        assert_ne!(
+            Poseidon::merge_with_int(a, 5),
+            Poseidon::merge_with_int(a, MODULUS + 5)
+        );
+    }
+}
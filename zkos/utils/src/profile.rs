@@ -0,0 +1,240 @@
+//! Named parameter profiles of the proofs verified by the guests.
+//!
+//! The security of a proof is set by several options which must be chosen together: the number
+//! of queries, the blowup factor, the grinding factor, and the field extension. A [ParamProfile]
+//! fixes all of them under a single name, so that the prover, the policy the host packages proofs
+//! under, and the policy the guest enforces are always consistent:
+//!
+//! | profile             | queries | blowup | grinding | field     | security |
+//! | ------------------- | ------- | ------ | -------- | --------- | -------- |
+//! | `dev`               | 4       | 8      | 0        | quadratic | 11 bits  |
+//! | `recursion-fast`    | 9       | 128    | 20       | quadratic | 82 bits  |
+//! | `production-96bit`  | 27      | 8      | 16       | quadratic | 96 bits  |
+//! | `production-128bit` | 27      | 16     | 21       | cubic     | 128 bits |
+//!
+//! The security is the conjectured security of the ethSTARK paper,
+//! λ ≥ min{G + R · s, log2|K|} − 1, with G the grinding factor, R the log2 of the blowup factor, s
+//! the number of queries, and K the field of the out-of-domain evaluations; 128-bit security thus
+//! requires the cubic extension of the 64-bit field.
+
+use crate::policy::ProofPolicy;
+use core::fmt;
+use core::str::FromStr;
+use errors::{ErrorChain, ErrorKind};
+use winter_air::{FieldExtension, HashFunction, ProofOptions};
+
+// CONSTANTS
+// ================================================================================================
+
+/// FRI folding factor of the proofs of all profiles.
+const FRI_FOLDING_FACTOR: usize = 8;
+
+/// Maximum size of the FRI remainder of the proofs of all profiles.
+const FRI_MAX_REMAINDER_SIZE: usize = 256;
+
+/// Number of bits in an element of the base field.
+const BASE_FIELD_BITS: u32 = 64;
+
+// PARAMETER PROFILE
+// ================================================================================================
+
+/// Named set of the proof options which determine the security of a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamProfile {
+    /// Minimal security for fast local iteration; proofs of this profile must not be relied on.
+    Dev,
+    /// Security of the default options of the runner, with a large blowup factor which keeps the
+    /// number of queries, and thus, the cost of the verification in the guest, low.
+    RecursionFast,
+    /// 96-bit security, with the options of Miden's 96-bit proofs.
+    Production96,
+    /// 128-bit security, with the options of Miden's 128-bit proofs.
+    Production128,
+}
+
+impl ParamProfile {
+    /// All profiles, from the least to the most secure.
+    pub const ALL: [ParamProfile; 4] = [
+        Self::Dev,
+        Self::RecursionFast,
+        Self::Production96,
+        Self::Production128,
+    ];
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the name of this profile, as accepted by [ParamProfile::from_str].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Dev => "dev",
+            Self::RecursionFast => "recursion-fast",
+            Self::Production96 => "production-96bit",
+            Self::Production128 => "production-128bit",
+        }
+    }
+
+    /// Returns the number of queries of the proofs of this profile.
+    pub fn num_queries(&self) -> usize {
+        match self {
+            Self::Dev => 4,
+            Self::RecursionFast => 9,
+            Self::Production96 | Self::Production128 => 27,
+        }
+    }
+
+    /// Returns the blowup factor of the proofs of this profile.
+    pub fn blowup_factor(&self) -> usize {
+        match self {
+            Self::Dev | Self::Production96 => 8,
+            Self::RecursionFast => 128,
+            Self::Production128 => 16,
+        }
+    }
+
+    /// Returns the grinding factor of the proofs of this profile.
+    pub fn grinding_factor(&self) -> u32 {
+        match self {
+            Self::Dev => 0,
+            Self::RecursionFast => 20,
+            Self::Production96 => 16,
+            Self::Production128 => 21,
+        }
+    }
+
+    /// Returns the field extension of the out-of-domain evaluations of the proofs of this profile.
+    pub fn field_extension(&self) -> FieldExtension {
+        match self {
+            Self::Production128 => FieldExtension::Cubic,
+            _ => FieldExtension::Quadratic,
+        }
+    }
+
+    /// Returns the conjectured security of the proofs of this profile, in bits.
+    pub fn security_bits(&self) -> u32 {
+        let log_blowup = self.blowup_factor().trailing_zeros();
+        let query_bits = self.grinding_factor() + log_blowup * self.num_queries() as u32;
+        let field_bits = BASE_FIELD_BITS * self.field_extension().degree();
+        query_bits.min(field_bits) - 1
+    }
+
+    // PROOF OPTIONS AND POLICY
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the options of the proofs of this profile, with commitments made with the
+    /// specified hash function.
+    pub fn proof_options(&self, hash_fn: HashFunction) -> ProofOptions {
+        ProofOptions::new(
+            self.num_queries(),
+            self.blowup_factor(),
+            self.grinding_factor(),
+            hash_fn,
+            self.field_extension(),
+            FRI_FOLDING_FACTOR,
+            FRI_MAX_REMAINDER_SIZE,
+        )
+    }
+
+    /// Returns the policy which accepts proofs with a number of queries, a blowup factor, and a
+    /// grinding factor at least as large as the ones of this profile, and with the same field
+    /// extension; the policy does not restrict the hash function.
+    pub fn policy(&self) -> ProofPolicy {
+        ProofPolicy::new()
+            .with_min_num_queries(self.num_queries() as u8)
+            .with_min_blowup_factor(self.blowup_factor() as u8)
+            .with_min_grinding_factor(self.grinding_factor() as u8)
+            .with_field_extensions(&[self.field_extension()])
+    }
+}
+
+impl FromStr for ParamProfile {
+    type Err = ProfileError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.name() == name.trim())
+            .ok_or_else(|| ProfileError::UnknownProfile(name.to_string()))
+    }
+}
+
+impl fmt::Display for ParamProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// PROFILE ERROR
+// ================================================================================================
+
+/// A name which is not the name of a [ParamProfile].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProfileError {
+    /// No profile has the specified name.
+    UnknownProfile(String),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownProfile(name) => {
+                let names = ParamProfile::ALL.map(|profile| profile.name());
+                write!(
+                    f,
+                    "'{}' is not a parameter profile; expected one of {}",
+                    name,
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl From<ProfileError> for ErrorChain {
+    fn from(error: ProfileError) -> Self {
+        ErrorChain::new(ErrorKind::Policy, error)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{ParamProfile, ProfileError};
+    use winter_air::HashFunction;
+
+    #[test]
+    fn profile_names_round_trip() {
+        for profile in ParamProfile::ALL {
+            assert_eq!(Ok(profile), profile.name().parse());
+            assert_eq!(profile.name(), profile.to_string());
+        }
+        assert_eq!(
+            Err(ProfileError::UnknownProfile("production".to_string())),
+            "production".parse::<ParamProfile>()
+        );
+    }
+
+    #[test]
+    fn profile_security() {
+        let bits = ParamProfile::ALL.map(|profile| profile.security_bits());
+        assert_eq!([11, 82, 96, 128], bits);
+    }
+
+    #[test]
+    fn profiles_satisfy_their_policies() {
+        for profile in ParamProfile::ALL {
+            let options = profile.proof_options(HashFunction::Sha2_256);
+            assert_eq!(Ok(()), profile.policy().check(&options));
+
+            // proofs of less secure profiles violate the policy of a more secure one
+            for weaker in ParamProfile::ALL {
+                if weaker.security_bits() < profile.security_bits() {
+                    let options = weaker.proof_options(HashFunction::Sha2_256);
+                    assert!(profile.policy().check(&options).is_err());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Cycle attribution for guest execution.
+//!
+//! The guest marks entry and exit of the functions it wants to profile together with the current
+//! cycle count, and the [CycleProfiler] attributes the cycles spent between the two marks to the
+//! current call stack (excluding cycles spent in nested frames). The result is a list of folded
+//! stacks which can be written by the host in the format expected by `inferno` and
+//! `flamegraph.pl`.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+// CYCLE PROFILER
+// ================================================================================================
+
+/// Collects self-cycles for every distinct call stack observed during execution.
+#[derive(Debug, Default)]
+pub struct CycleProfiler {
+    stack: Vec<Frame>,
+    folded: BTreeMap<String, u64>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    name: &'static str,
+    start: u64,
+    child_cycles: u64,
+}
+
+impl CycleProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a new frame with the specified name at the specified cycle.
+    pub fn enter(&mut self, name: &'static str, cycle: u64) {
+        self.stack.push(Frame {
+            name,
+            start: cycle,
+            child_cycles: 0,
+        });
+    }
+
+    /// Closes the innermost frame at the specified cycle, and attributes the cycles spent in it
+    /// (excluding the cycles spent in nested frames) to the current call stack.
+    ///
+    /// # Panics
+    /// Panics if there are no open frames.
+    pub fn exit(&mut self, cycle: u64) {
+        let stack = self.folded_stack();
+        let frame = self.stack.pop().expect("no open profiler frames");
+        let total_cycles = cycle.saturating_sub(frame.start);
+        let self_cycles = total_cycles.saturating_sub(frame.child_cycles);
+        *self.folded.entry(stack).or_insert(0) += self_cycles;
+
+        if let Some(parent) = self.stack.last_mut() {
+            parent.child_cycles += total_cycles;
+        }
+    }
+
+    /// Returns the collected (folded stack, self cycles) pairs. Frames which are still open are
+    /// ignored.
+    pub fn into_folded(self) -> Vec<(String, u64)> {
+        self.folded.into_iter().collect()
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    fn folded_stack(&self) -> String {
+        self.stack
+            .iter()
+            .map(|frame| frame.name)
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+// OUTPUT
+// ================================================================================================
+
+/// Writes the provided folded stacks, one `stack cycles` pair per line.
+pub fn write_folded<W: Write>(target: &mut W, folded: &[(String, u64)]) -> io::Result<()> {
+    for (stack, cycles) in folded {
+        writeln!(target, "{} {}", stack, cycles)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+//! Reference (non-optimized) implementation of the 64-bit prime field with modulus
+//! 2^64 - 2^32 + 1.
+//!
+//! All arithmetic goes through `u128` followed by a single `%` reduction, so the implementation
+//! is slow but obviously correct. It is intended only for differential testing of the optimized
+//! field implementations in `winter_math` (on the host and inside the guest).
+
+use core::ops::{Add, Mul, Neg, Sub};
+use winter_math::fields::f64::BaseElement;
+use winter_math::{FieldElement, StarkField};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Field modulus = 2^64 - 2^32 + 1
+pub const MODULUS: u64 = 0xFFFFFFFF00000001;
+
+// REFERENCE FIELD ELEMENT
+// ================================================================================================
+
+/// Field element stored in canonical form, i.e. in the range [0, MODULUS).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RefElement(u64);
+
+impl RefElement {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1);
+
+    /// Creates a new field element from any u64 value by reducing it modulo the field modulus.
+    pub const fn new(value: u64) -> Self {
+        Self(value % MODULUS)
+    }
+
+    /// Returns the canonical integer representation of this element.
+    pub const fn as_int(&self) -> u64 {
+        self.0
+    }
+
+    /// Computes self^power using square-and-multiply.
+    pub fn exp(self, power: u64) -> Self {
+        let mut result = Self::ONE;
+        let mut base = self;
+        let mut power = power;
+        while power > 0 {
+            if power & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            power >>= 1;
+        }
+        result
+    }
+
+    /// Computes the multiplicative inverse via Fermat's little theorem; the inverse of ZERO is
+    /// defined to be ZERO to match `winter_math`.
+    pub fn inv(self) -> Self {
+        self.exp(MODULUS - 2)
+    }
+}
+
+impl Add for RefElement {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 + rhs.0 as u128) % MODULUS as u128) as u64)
+    }
+}
+
+impl Sub for RefElement {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 + MODULUS as u128 - rhs.0 as u128) % MODULUS as u128) as u64)
+    }
+}
+
+impl Mul for RefElement {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 * rhs.0 as u128) % MODULUS as u128) as u64)
+    }
+}
+
+impl Neg for RefElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+// DIFFERENTIAL CHECKS
+// ================================================================================================
+
+/// Arithmetic operation at which the optimized field diverged from the reference field.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FieldOp {
+    New,
+    Add,
+    Sub,
+    Mul,
+    Neg,
+    Inv,
+    Exp,
+}
+
+/// Describes a single disagreement between the reference and the optimized field.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FieldMismatch {
+    pub op: FieldOp,
+    pub a: u64,
+    pub b: u64,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+/// Returns a list of adversarial inputs: values around zero, around the modulus, around 2^32
+/// (which is where Montgomery reduction splits the product) and around 2^64.
+pub fn adversarial_values() -> Vec<u64> {
+    vec![
+        0,
+        1,
+        2,
+        (1 << 32) - 1,
+        1 << 32,
+        (1 << 32) + 1,
+        1 << 63,
+        MODULUS - 2,
+        MODULUS - 1,
+        MODULUS,
+        MODULUS + 1,
+        u64::MAX - 1,
+        u64::MAX,
+    ]
+}
+
+/// Compares all field operations on the provided pair of inputs between [RefElement] and
+/// [BaseElement] from `winter_math::fields::f64`.
+///
+/// # Errors
+/// Returns the first [FieldMismatch] encountered.
+pub fn check_f64_pair(a: u64, b: u64) -> Result<(), FieldMismatch> {
+    let (ra, rb) = (RefElement::new(a), RefElement::new(b));
+    let (fa, fb) = (BaseElement::new(a), BaseElement::new(b));
+
+    let checks = [
+        (FieldOp::New, ra, fa),
+        (FieldOp::Add, ra + rb, fa + fb),
+        (FieldOp::Sub, ra - rb, fa - fb),
+        (FieldOp::Mul, ra * rb, fa * fb),
+        (FieldOp::Neg, -ra, -fa),
+        (FieldOp::Inv, ra.inv(), fa.inv()),
+        (FieldOp::Exp, ra.exp(b), fa.exp(b)),
+    ];
+
+    for (op, expected, actual) in checks {
+        if expected.as_int() != actual.as_int() {
+            return Err(FieldMismatch {
+                op,
+                a,
+                b,
+                expected: expected.as_int(),
+                actual: actual.as_int(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs [check_f64_pair] over all provided pairs and returns all mismatches found.
+pub fn check_f64_pairs(pairs: &[(u64, u64)]) -> Vec<FieldMismatch> {
+    pairs
+        .iter()
+        .filter_map(|&(a, b)| check_f64_pair(a, b).err())
+        .collect()
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{adversarial_values, check_f64_pair, RefElement, MODULUS};
+    use proptest::prelude::*;
+
+    #[test]
+    fn ref_element_basics() {
+        assert_eq!(RefElement::ZERO, RefElement::new(MODULUS));
+        assert_eq!(
+            RefElement::ONE,
+            RefElement::new(MODULUS - 1) + RefElement::new(2)
+        );
+        assert_eq!(RefElement::new(MODULUS - 1), -RefElement::ONE);
+        assert_eq!(
+            RefElement::ONE,
+            RefElement::new(7) * RefElement::new(7).inv()
+        );
+        assert_eq!(RefElement::ZERO, RefElement::ZERO.inv());
+    }
+
+    #[test]
+    fn f64_adversarial_inputs() {
+        let values = adversarial_values();
+        for &a in values.iter() {
+            for &b in values.iter() {
+                assert_eq!(Ok(()), check_f64_pair(a, b));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn f64_random_inputs(a in any::<u64>(), b in any::<u64>()) {
+            prop_assert_eq!(Ok(()), check_f64_pair(a, b));
+        }
+
+        #[test]
+        fn f64_near_modulus(a in (MODULUS - 1024)..=u64::MAX, b in 0..1024u64) {
+            prop_assert_eq!(Ok(()), check_f64_pair(a, b));
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Golden end-to-end fixture of the fib verifier guest, checked before every release.
+//!
+//! The fixture pins the complete input and output of one run of the fib verifier guest: the
+//! fib proofs of the [RELEASE_CASE] as packaged by the runner, the image ID of the guest, and the
+//! bytes of the journal it commits. Re-proving the guest on freshly packaged proofs and
+//! comparing the results against the fixture detects any change of the guest, the packaging
+//! logic, or the dependencies which affects what the guest is given or what it attests to. The
+//! fixture is kept in a text file of `name = value` lines, in the same format as the known-answer
+//! transcripts (see [crate::kat]):
+//!
+//! ```text
+//! # golden release fixture of the fib verifier guest
+//! verifier_version = 22
+//! image_id = <hex>
+//! aux_input = <hex>
+//! inputs = <hex>,<hex>,<hex>
+//! journal = <hex>
+//! ```
+//!
+//! The `inputs` are the regular inputs of the guest in the order in which they are added to the
+//! prover, and together with the `journal`, they are written as the little-endian bytes of their
+//! words.
+
+use crate::kat::KatCase;
+use core::fmt;
+use core::str::FromStr;
+use errors::{ErrorChain, ErrorKind};
+
+// RELEASE CASE
+// ================================================================================================
+
+/// Proof options of the fib proofs of the release fixture; these are the default options of the
+/// runner.
+pub const RELEASE_CASE: KatCase = KatCase {
+    name: "release",
+    num_queries: 9,
+    blowup_factor: 128,
+    grinding_factor: 20,
+    fri_folding_factor: 8,
+};
+
+// RELEASE FIXTURE
+// ================================================================================================
+
+/// Pinned input and output of a run of the fib verifier guest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReleaseFixture {
+    /// Version of the verification logic of the guest which committed the journal.
+    pub verifier_version: u32,
+    /// Image ID of the guest.
+    pub image_id: Vec<u8>,
+    /// Packaged proofs passed to the guest as its aux input.
+    pub aux_input: Vec<u8>,
+    /// Regular inputs of the guest, in the order in which they are added to the prover.
+    pub inputs: Vec<Vec<u32>>,
+    /// Words of the journal committed by the guest.
+    pub journal: Vec<u32>,
+}
+
+impl ReleaseFixture {
+    /// Checks that the provided run of the guest matches this fixture.
+    ///
+    /// # Errors
+    /// Returns an error naming the first field, in the order in which they are listed in the
+    /// fixture, which does not match; a mismatch of an earlier field usually explains the
+    /// mismatches of the later ones.
+    pub fn check(&self, actual: &ReleaseFixture) -> Result<(), ReleaseError> {
+        let mismatch = if actual.verifier_version != self.verifier_version {
+            "verifier_version"
+        } else if actual.image_id != self.image_id {
+            "image_id"
+        } else if actual.aux_input != self.aux_input {
+            "aux_input"
+        } else if actual.inputs != self.inputs {
+            "inputs"
+        } else if actual.journal != self.journal {
+            "journal"
+        } else {
+            return Ok(());
+        };
+        Err(ReleaseError::Mismatch(mismatch))
+    }
+
+    /// Returns this fixture in the text format of the fixture file.
+    pub fn to_text(&self) -> String {
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|input| hex::encode(words_to_bytes(input)))
+            .collect();
+        format!(
+            "# golden release fixture of the fib verifier guest\n\
+             verifier_version = {}\n\
+             image_id = {}\n\
+             aux_input = {}\n\
+             inputs = {}\n\
+             journal = {}\n",
+            self.verifier_version,
+            hex::encode(&self.image_id),
+            hex::encode(&self.aux_input),
+            inputs.join(","),
+            hex::encode(words_to_bytes(&self.journal))
+        )
+    }
+}
+
+impl FromStr for ReleaseFixture {
+    type Err = ReleaseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut verifier_version = None;
+        let mut image_id = None;
+        let mut aux_input = None;
+        let mut inputs = None;
+        let mut journal = None;
+
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ReleaseError::MalformedLine(i + 1))?;
+            let value = value.trim();
+            match key.trim() {
+                "verifier_version" => {
+                    let version = value
+                        .parse::<u32>()
+                        .map_err(|_| ReleaseError::InvalidValue("verifier_version"))?;
+                    set_field(&mut verifier_version, "verifier_version", version)?
+                }
+                "image_id" => set_field(&mut image_id, "image_id", parse_hex(value, "image_id")?)?,
+                "aux_input" => {
+                    set_field(&mut aux_input, "aux_input", parse_hex(value, "aux_input")?)?
+                }
+                "inputs" => {
+                    let words = value
+                        .split(',')
+                        .map(|input| parse_words(input.trim(), "inputs"))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    set_field(&mut inputs, "inputs", words)?
+                }
+                "journal" => set_field(&mut journal, "journal", parse_words(value, "journal")?)?,
+                key => return Err(ReleaseError::UnknownField(key.to_string())),
+            }
+        }
+
+        Ok(Self {
+            verifier_version: verifier_version
+                .ok_or(ReleaseError::MissingField("verifier_version"))?,
+            image_id: image_id.ok_or(ReleaseError::MissingField("image_id"))?,
+            aux_input: aux_input.ok_or(ReleaseError::MissingField("aux_input"))?,
+            inputs: inputs.ok_or(ReleaseError::MissingField("inputs"))?,
+            journal: journal.ok_or(ReleaseError::MissingField("journal"))?,
+        })
+    }
+}
+
+// RELEASE ERROR
+// ================================================================================================
+
+/// Errors which can occur while reading or checking a release fixture.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReleaseError {
+    /// The line with the specified number is not a `name = value` line.
+    MalformedLine(usize),
+    /// The field is not a field of a release fixture.
+    UnknownField(String),
+    /// The field is set more than once.
+    DuplicateField(&'static str),
+    /// The field is not set.
+    MissingField(&'static str),
+    /// The value of the field cannot be parsed.
+    InvalidValue(&'static str),
+    /// The value of the field does not match the run of the guest.
+    Mismatch(&'static str),
+}
+
+impl fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedLine(line) => write!(f, "line {} is not a 'name = value' line", line),
+            Self::UnknownField(name) => write!(f, "'{}' is not a release fixture field", name),
+            Self::DuplicateField(name) => write!(f, "field '{}' is set more than once", name),
+            Self::MissingField(name) => write!(f, "field '{}' is not set", name),
+            Self::InvalidValue(name) => write!(f, "value of field '{}' is not valid", name),
+            Self::Mismatch(name) => {
+                write!(
+                    f,
+                    "run of the guest diverged from the fixture at '{}'",
+                    name
+                )
+            }
+        }
+    }
+}
+
+impl From<ReleaseError> for ErrorChain {
+    fn from(error: ReleaseError) -> Self {
+        let kind = match error {
+            ReleaseError::Mismatch(..) => ErrorKind::Verification,
+            _ => ErrorKind::Deserialization,
+        };
+        ErrorChain::new(kind, error)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn set_field<T>(field: &mut Option<T>, name: &'static str, value: T) -> Result<(), ReleaseError> {
+    if field.replace(value).is_some() {
+        return Err(ReleaseError::DuplicateField(name));
+    }
+    Ok(())
+}
+
+fn parse_hex(value: &str, name: &'static str) -> Result<Vec<u8>, ReleaseError> {
+    hex::decode(value).map_err(|_| ReleaseError::InvalidValue(name))
+}
+
+fn parse_words(value: &str, name: &'static str) -> Result<Vec<u32>, ReleaseError> {
+    let bytes = parse_hex(value, name)?;
+    if bytes.len() % 4 != 0 {
+        return Err(ReleaseError::InvalidValue(name));
+    }
+    Ok(bytes
+        .chunks(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect())
+}
+
+fn words_to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{ReleaseError, ReleaseFixture};
+
+    fn fixture() -> ReleaseFixture {
+        ReleaseFixture {
+            verifier_version: 7,
+            image_id: vec![1, 2, 3, 4],
+            aux_input: vec![5, 6, 7],
+            inputs: vec![vec![], vec![8, 0x0102_0304], vec![9]],
+            journal: vec![10, 11],
+        }
+    }
+
+    #[test]
+    fn parse_fixture() {
+        let fixture = fixture();
+        assert_eq!(
+            Ok(fixture.clone()),
+            fixture.to_text().parse::<ReleaseFixture>()
+        );
+
+        let text = fixture.to_text().replace("journal = 0a", "journal = 0a00");
+        assert_eq!(
+            Err(ReleaseError::InvalidValue("journal")),
+            text.parse::<ReleaseFixture>()
+        );
+        let text = fixture.to_text().replace("aux_input", "aux");
+        assert_eq!(
+            Err(ReleaseError::UnknownField("aux".to_string())),
+            text.parse::<ReleaseFixture>()
+        );
+        let text = format!("{}verifier_version = 8\n", fixture.to_text());
+        assert_eq!(
+            Err(ReleaseError::DuplicateField("verifier_version")),
+            text.parse::<ReleaseFixture>()
+        );
+        assert_eq!(
+            Err(ReleaseError::MissingField("verifier_version")),
+            "".parse::<ReleaseFixture>()
+        );
+    }
+
+    #[test]
+    fn check_fixture() {
+        let fixture = fixture();
+        assert_eq!(Ok(()), fixture.check(&fixture));
+
+        // the first diverging field is reported
+        let mut actual = fixture.clone();
+        actual.aux_input[0] ^= 1;
+        actual.journal[0] ^= 1;
+        assert_eq!(
+            Err(ReleaseError::Mismatch("aux_input")),
+            fixture.check(&actual)
+        );
+
+        let mut actual = fixture.clone();
+        actual.inputs[0].push(0);
+        assert_eq!(
+            Err(ReleaseError::Mismatch("inputs")),
+            fixture.check(&actual)
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Bounds on the trace lengths of the proofs accepted by the verifier guests.
+//!
+//! The proof options bound the soundness of a proof, but not the size of the statement it is
+//! about. A proof of a very short trace has a tiny evaluation domain, in which a forged proof is
+//! much cheaper to find than the options suggest; a proof of a very long trace makes the guest
+//! spend an unbounded number of cycles on inverting the domain and evaluating the constraints.
+//! Thus, the guests reject proofs whose trace length is outside of the [TraceLengthBounds] they
+//! are built with, before verifying them. The bounds are fixed at build time by the
+//! `ZKOS_GUEST_MIN_TRACE_LENGTH` and `ZKOS_GUEST_MAX_TRACE_LENGTH` environment variables, so that
+//! they are bound to the image IDs of the guests.
+
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Minimum trace length of guests built without the `ZKOS_GUEST_MIN_TRACE_LENGTH` environment
+/// variable.
+pub const DEFAULT_MIN_TRACE_LENGTH: usize = 64;
+
+/// Maximum trace length of guests built without the `ZKOS_GUEST_MAX_TRACE_LENGTH` environment
+/// variable.
+pub const DEFAULT_MAX_TRACE_LENGTH: usize = 1 << 20;
+
+// TRACE LENGTH BOUNDS
+// ================================================================================================
+
+/// Inclusive range of the trace lengths of the proofs accepted by a guest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceLengthBounds {
+    min: usize,
+    max: usize,
+}
+
+impl TraceLengthBounds {
+    /// Returns bounds which accept the trace lengths between `min` and `max`, inclusive.
+    ///
+    /// # Panics
+    /// Panics if `min` is greater than `max`.
+    pub const fn new(min: usize, max: usize) -> Self {
+        assert!(min <= max, "minimum trace length exceeds the maximum");
+        Self { min, max }
+    }
+
+    /// Returns the minimum trace length of accepted proofs.
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    /// Returns the maximum trace length of accepted proofs.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Checks that the specified trace length is within these bounds.
+    ///
+    /// # Errors
+    /// Returns an error if the trace length is smaller than the minimum or greater than the
+    /// maximum.
+    pub fn check(&self, trace_length: usize) -> Result<(), TraceLengthError> {
+        if trace_length < self.min {
+            return Err(TraceLengthError::TooShort {
+                length: trace_length,
+                min: self.min,
+            });
+        }
+        if trace_length > self.max {
+            return Err(TraceLengthError::TooLong {
+                length: trace_length,
+                max: self.max,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for TraceLengthBounds {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_TRACE_LENGTH, DEFAULT_MAX_TRACE_LENGTH)
+    }
+}
+
+// TRACE LENGTH ERROR
+// ================================================================================================
+
+/// A trace length outside of [TraceLengthBounds].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceLengthError {
+    /// The trace is shorter than the minimum.
+    TooShort { length: usize, min: usize },
+    /// The trace is longer than the maximum.
+    TooLong { length: usize, max: usize },
+}
+
+impl fmt::Display for TraceLengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { length, min } => write!(
+                f,
+                "proof has trace length {}, but at least {} is required",
+                length, min
+            ),
+            Self::TooLong { length, max } => write!(
+                f,
+                "proof has trace length {}, but at most {} is allowed",
+                length, max
+            ),
+        }
+    }
+}
+
+impl From<TraceLengthError> for ErrorChain {
+    fn from(error: TraceLengthError) -> Self {
+        ErrorChain::new(ErrorKind::Policy, error)
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{TraceLengthBounds, TraceLengthError};
+
+    #[test]
+    fn trace_length_bounds_are_inclusive() {
+        let bounds = TraceLengthBounds::new(64, 1024);
+        assert_eq!(Ok(()), bounds.check(64));
+        assert_eq!(Ok(()), bounds.check(1024));
+        assert_eq!(
+            Err(TraceLengthError::TooShort {
+                length: 32,
+                min: 64
+            }),
+            bounds.check(32)
+        );
+        assert_eq!(
+            Err(TraceLengthError::TooLong {
+                length: 2048,
+                max: 1024
+            }),
+            bounds.check(2048)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn trace_length_bounds_must_be_ordered() {
+        TraceLengthBounds::new(1024, 64);
+    }
+}
@@ -0,0 +1,508 @@
+//! Replay of the Fiat–Shamir transcript of a packaged proof.
+//!
+//! Full verification of a proof in a guest is expensive, and when it fails it is not obvious
+//! whether the proof is invalid or the host packaged it incorrectly (e.g., a commitment or an
+//! out-of-domain frame was serialized in the wrong order). Replaying only the transcript, i.e.,
+//! all reseeds and draws of the public coin, is much cheaper. The final seed of the coin commits
+//! to everything the verifier reads from the packaged proof before the queries, and thus, the
+//! host can compare the seed computed by a guest from the packaged proof against the seed it
+//! computes itself from the original proof, and detect packing bugs before running the full
+//! verification.
+//!
+//! The same replay, [replay()], is run by every verifier of packaged proofs: the guest verifier,
+//! the phase verifier (see [crate::phase]), and [replay_transcript()]; each of them performs its
+//! checks on the values returned by the replay.
+
+use crate::hasher::GuestHasher;
+use crate::inputs::{check_aux_rand_elements, TraceSegmentMismatch};
+use core::fmt;
+use errors::{ErrorChain, ErrorKind};
+use miden_air::{Felt, PublicInputs};
+use winter_air::proof::Context;
+use winter_air::{
+    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, DeepCompositionCoefficients,
+    EvaluationFrame,
+};
+use winter_math::{FieldElement, StarkField};
+use winter_verifier::crypto::hashers::Rp64_256;
+use winter_verifier::crypto::{Digest, ElementHasher, RandomCoin};
+use winter_verifier::{FriVerifier, Serializable, VerifierChannel, VerifierError};
+
+// REPLAYED TRANSCRIPT
+// ================================================================================================
+
+/// Values read from a verifier channel and drawn from the public coin while replaying the
+/// Fiat–Shamir transcript of a proof, up to and including the query positions.
+pub struct ReplayedTranscript<B, E, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    H: ElementHasher<BaseField = B>,
+{
+    /// Random elements drawn for every auxiliary trace segment.
+    pub aux_trace_rand_elements: AuxTraceRandElements<E>,
+    /// Coefficients of the constraint composition polynomial.
+    pub constraint_coeffs: ConstraintCompositionCoefficients<E>,
+    /// Out-of-domain point z.
+    pub z: E,
+    /// Out-of-domain frame of the main trace segment.
+    pub ood_main_trace_frame: EvaluationFrame<E>,
+    /// Out-of-domain frame of the auxiliary trace segments, if any.
+    pub ood_aux_trace_frame: Option<EvaluationFrame<E>>,
+    /// Out-of-domain evaluations of the constraint composition columns.
+    pub ood_constraint_evaluations: Vec<E>,
+    /// Coefficients of the DEEP composition polynomial.
+    pub deep_coefficients: DeepCompositionCoefficients<E>,
+    /// FRI verifier instantiated with the FRI layer commitments.
+    pub fri_verifier: FriVerifier<B, E, VerifierChannel<E, H>, H>,
+    /// Leading zeros of the seed of the public coin after it was reseeded with the
+    /// proof-of-work nonce; the proof-of-work check is left to the caller.
+    pub pow_leading_zeros: u32,
+    /// Seed of the public coin after it was reseeded with the proof-of-work nonce.
+    pub seed: H::Digest,
+    /// Query positions drawn from the final seed.
+    pub query_positions: Vec<usize>,
+}
+
+/// Replays the Fiat–Shamir transcript of the proof read from the provided channel with the coin
+/// seeded with `coin_seed` (the serialized public inputs and proof context).
+///
+/// The transcript is replayed in the same order as the verifier processes the proof: the coin is
+/// reseeded with the trace commitments, the constraint commitment, the out-of-domain frame and
+/// constraint evaluations, the FRI layer commitments, and the proof-of-work nonce, drawing all
+/// random values the verifier draws in between. The out-of-domain rows are hashed with
+/// [GuestHasher::hash_split_elements()]; whether the main and auxiliary segments are merged into
+/// a single buffer first depends on the hasher. None of the queried values are read from the
+/// channel, and none of the checks of the verifier but the freshness of the auxiliary random
+/// elements are performed.
+///
+/// # Errors
+/// Returns an error if:
+/// * The channel holds no trace commitments.
+/// * The random elements drawn for an auxiliary trace segment are not fresh draws from the coin.
+/// * A random value cannot be drawn from the coin.
+/// * The FRI layer commitments cannot be read from the channel.
+pub fn replay<A, E, H>(
+    air: &A,
+    channel: &mut VerifierChannel<E, H>,
+    coin_seed: &[u8],
+) -> Result<ReplayedTranscript<A::BaseField, E, H>, ReplayError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: GuestHasher<BaseField = A::BaseField>,
+{
+    let mut public_coin: RandomCoin<A::BaseField, H> = RandomCoin::new(coin_seed);
+
+    // --- trace commitments ----------------------------------------------------------------------
+    let trace_commitments = channel.read_trace_commitments().to_vec();
+    let (main_commitment, aux_commitments) = trace_commitments
+        .split_first()
+        .ok_or(ReplayError::NoTraceCommitments)?;
+    public_coin.reseed(*main_commitment);
+    let mut aux_trace_rand_elements = AuxTraceRandElements::<E>::new();
+    for (i, commitment) in aux_commitments.iter().enumerate() {
+        let rand_elements = air
+            .get_aux_trace_segment_random_elements(i, &mut public_coin)
+            .map_err(|_| VerifierError::RandomCoinError)?;
+        // the elements of every segment must be fresh draws from the coin reseeded with the
+        // commitments to the previous segments
+        check_aux_rand_elements(&aux_trace_rand_elements, i, &rand_elements)
+            .map_err(ReplayError::TraceSegments)?;
+        aux_trace_rand_elements.add_segment_elements(rand_elements);
+        public_coin.reseed(*commitment);
+    }
+
+    // --- constraint commitment ------------------------------------------------------------------
+    let constraint_coeffs = air
+        .get_constraint_composition_coefficients::<E, H>(&mut public_coin)
+        .map_err(|_| VerifierError::RandomCoinError)?;
+    public_coin.reseed(channel.read_constraint_commitment());
+    let z = public_coin
+        .draw::<E>()
+        .map_err(|_| VerifierError::RandomCoinError)?;
+
+    // --- out-of-domain evaluations --------------------------------------------------------------
+    // auxiliary trace elements are hashed right after main trace elements of the same row
+    let (ood_main_trace_frame, ood_aux_trace_frame) = channel.read_ood_trace_frame();
+    match &ood_aux_trace_frame {
+        Some(aux_trace_frame) => {
+            public_coin.reseed(H::hash_split_elements(
+                ood_main_trace_frame.current(),
+                aux_trace_frame.current(),
+            ));
+            public_coin.reseed(H::hash_split_elements(
+                ood_main_trace_frame.next(),
+                aux_trace_frame.next(),
+            ));
+        }
+        None => {
+            public_coin.reseed(H::hash_elements(ood_main_trace_frame.current()));
+            public_coin.reseed(H::hash_elements(ood_main_trace_frame.next()));
+        }
+    }
+    let ood_constraint_evaluations = channel.read_ood_constraint_evaluations();
+    public_coin.reseed(H::hash_elements(&ood_constraint_evaluations));
+
+    // --- FRI commitments ------------------------------------------------------------------------
+    let deep_coefficients = air
+        .get_deep_composition_coefficients::<E, H>(&mut public_coin)
+        .map_err(|_| VerifierError::RandomCoinError)?;
+    // instantiating the FRI verifier reseeds the coin with the FRI layer commitments and draws
+    // the folding challenges from it; the commitments cannot be absorbed with a single wide hash,
+    // since every layer is computed by the prover from the challenge drawn after the commitment
+    // to the previous layer, and thus, the seed after each commitment is part of the transcript
+    let fri_verifier = FriVerifier::<A::BaseField, E, VerifierChannel<E, H>, H>::new(
+        channel,
+        &mut public_coin,
+        air.options().to_fri_options(),
+        air.trace_poly_degree(),
+    )
+    .map_err(VerifierError::FriVerificationFailed)?;
+
+    // --- queries --------------------------------------------------------------------------------
+    public_coin.reseed_with_int(channel.read_pow_nonce());
+    let pow_leading_zeros = public_coin.leading_zeros();
+    let seed = public_coin.seed();
+    let query_positions = public_coin
+        .draw_integers(air.options().num_queries(), air.lde_domain_size())
+        .map_err(|_| VerifierError::RandomCoinError)?;
+
+    Ok(ReplayedTranscript {
+        aux_trace_rand_elements,
+        constraint_coeffs,
+        z,
+        ood_main_trace_frame,
+        ood_aux_trace_frame,
+        ood_constraint_evaluations,
+        deep_coefficients,
+        fri_verifier,
+        pow_leading_zeros,
+        seed,
+        query_positions,
+    })
+}
+
+// TRANSCRIPT STATE
+// ================================================================================================
+
+/// Values drawn from the public coin during a replayed transcript, and the state of the coin at
+/// the end of the transcript.
+///
+/// Drawn field elements are kept in their canonical serialization, so that states of transcripts
+/// over different fields can be compared and recorded (see [crate::kat]).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptState {
+    /// Serialized out-of-domain point z.
+    pub ood_point: Vec<u8>,
+    /// Serialized coefficients of the constraint composition polynomial: the transition
+    /// coefficients followed by the boundary coefficients.
+    pub constraint_coefficients: Vec<u8>,
+    /// Serialized coefficients of the DEEP composition polynomial: the trace coefficients
+    /// followed by the constraint and degree adjustment coefficients.
+    pub deep_coefficients: Vec<u8>,
+    /// Seed of the public coin after it was reseeded with the proof-of-work nonce.
+    pub seed: [u8; 32],
+    /// Query positions drawn from the final seed.
+    pub query_positions: Vec<usize>,
+}
+
+// TRANSCRIPT REPLAY
+// ================================================================================================
+
+/// Replays the Fiat–Shamir transcript of the proof read from the provided channel, and returns
+/// the final state of the public coin.
+///
+/// The transcript is replayed by [replay()], with the coin seeded with `coin_seed`; no
+/// constraints are evaluated, and the proof-of-work is not checked.
+///
+/// # Errors
+/// Returns an error if the transcript cannot be replayed (see [replay()]).
+pub fn replay_transcript<A, E, H>(
+    air: &A,
+    channel: &mut VerifierChannel<E, H>,
+    coin_seed: &[u8],
+) -> Result<TranscriptState, ReplayError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: GuestHasher<BaseField = A::BaseField>,
+{
+    let transcript = replay(air, channel, coin_seed)?;
+
+    let constraint_coefficients = transcript
+        .constraint_coeffs
+        .transition
+        .iter()
+        .chain(transcript.constraint_coeffs.boundary.iter())
+        .flat_map(|(a, b)| [*a, *b]);
+    let deep_coefficients = transcript
+        .deep_coefficients
+        .trace
+        .iter()
+        .flat_map(|(a, b, c)| [*a, *b, *c])
+        .chain(transcript.deep_coefficients.constraints.iter().copied())
+        .chain([
+            transcript.deep_coefficients.degree.0,
+            transcript.deep_coefficients.degree.1,
+        ]);
+
+    Ok(TranscriptState {
+        ood_point: transcript.z.to_bytes(),
+        constraint_coefficients: serialize_elements(constraint_coefficients),
+        deep_coefficients: serialize_elements(deep_coefficients),
+        seed: transcript.seed.as_bytes(),
+        query_positions: transcript.query_positions,
+    })
+}
+
+// REPLAY ERROR
+// ================================================================================================
+
+/// Failure to replay the Fiat–Shamir transcript of a proof.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The channel holds no trace commitments, and thus, not even the commitment to the main
+    /// trace segment.
+    NoTraceCommitments,
+    /// The random elements drawn for an auxiliary trace segment are not fresh draws from the
+    /// coin.
+    TraceSegments(TraceSegmentMismatch),
+    /// A random value cannot be drawn from the coin, or the FRI layer commitments cannot be read.
+    Verifier(VerifierError),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoTraceCommitments => write!(f, "the proof holds no trace commitments"),
+            Self::TraceSegments(err) => write!(f, "{}", err),
+            Self::Verifier(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<VerifierError> for ReplayError {
+    fn from(error: VerifierError) -> Self {
+        Self::Verifier(error)
+    }
+}
+
+impl From<ReplayError> for ErrorChain {
+    fn from(error: ReplayError) -> Self {
+        ErrorChain::new(ErrorKind::Verification, error)
+    }
+}
+
+// MIDEN TRANSCRIPT
+// ================================================================================================
+
+/// Public coin of Miden proofs committed to with RPO, i.e., proofs generated with
+/// [HashFunction::Rp64_256](winter_air::HashFunction::Rp64_256).
+///
+/// This is the coin the Miden prover derives its challenges from; a guest verifying such a proof
+/// recursively reproduces the challenges with this coin, seeded with [miden_coin_seed()].
+pub type RpoRandomCoin = RandomCoin<Felt, Rp64_256>;
+
+/// Returns the seed of the public coin of a Miden proof generated in the provided context for the
+/// provided public inputs.
+///
+/// The public inputs must be the ones the Miden prover built from the execution trace, including
+/// the commitment to the outputs, if any; public inputs built
+/// from the program hash and the stack values alone seed a different coin for such proofs. The
+/// seed is the same as the one returned by
+/// [VerifierContext::coin_seed()](crate::context::VerifierContext::coin_seed) for the serialized
+/// context, which is the seed the guests use.
+pub fn miden_coin_seed(pub_inputs: &PublicInputs, context: &Context) -> Vec<u8> {
+    context.public_coin_seed(&pub_inputs.to_bytes())
+}
+
+/// Returns the public coin of a Miden proof committed to with RPO, generated in the provided
+/// context for the provided public inputs, before the coin is reseeded with any commitment.
+pub fn miden_rpo_coin(pub_inputs: &PublicInputs, context: &Context) -> RpoRandomCoin {
+    RandomCoin::new(&miden_coin_seed(pub_inputs, context))
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the concatenated canonical serializations of the provided elements.
+pub(crate) fn serialize_elements<E: FieldElement>(elements: impl Iterator<Item = E>) -> Vec<u8> {
+    let mut result = Vec::new();
+    for element in elements {
+        element.write_into(&mut result);
+    }
+    result
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{miden_coin_seed, miden_rpo_coin, replay_transcript};
+    use crate::context::{DomainTag, VerifierContext};
+    use crate::fibonacci_miden::get_example;
+    use miden::{FieldExtension, HashFunction, ProofOptions};
+    use miden_air::{Felt, ProcessorAir, PublicInputs};
+    use winter_air::proof::Table;
+    use winter_air::Air;
+    use winter_math::FieldElement;
+    use winter_verifier::crypto::hashers::Rp64_256;
+    use winter_verifier::crypto::RandomCoin;
+    use winter_verifier::{Serializable, VerifierChannel, VerifierError};
+
+    /// Checks that the transcript replayed with the RPO coin reproduces the challenges of the
+    /// Miden prover: the query positions drawn from the coin open the trace commitments of the
+    /// proof only if every challenge before them was derived in the same way as by the prover.
+    #[test]
+    fn miden_rpo_transcript() {
+        let example = get_example(16);
+        let options = ProofOptions::new(
+            8,
+            8,
+            0,
+            HashFunction::Rp64_256,
+            FieldExtension::None,
+            8,
+            256,
+        );
+        let (outputs, proof) = miden::prove(
+            &example.program,
+            &example.inputs,
+            example.num_outputs,
+            &options,
+        )
+        .unwrap();
+
+        let stack_inputs = example.pub_inputs.iter().rev().map(|&v| Felt::new(v));
+        let stack_outputs = outputs.iter().map(|&v| Felt::new(v));
+        let pub_inputs = PublicInputs::new(
+            example.program.hash(),
+            stack_inputs.collect(),
+            stack_outputs.collect(),
+        );
+
+        // the seed of the RPO coin is the seed the guests build from the packaged context
+        let seed = miden_coin_seed(&pub_inputs, &proof.context);
+        let context = VerifierContext::new(DomainTag::default(), proof.context.to_bytes());
+        assert_eq!(seed, context.coin_seed(&pub_inputs.to_bytes()));
+        assert_eq!(
+            RandomCoin::<Felt, Rp64_256>::new(&seed).seed(),
+            miden_rpo_coin(&pub_inputs, &proof.context).seed()
+        );
+
+        let air = ProcessorAir::new(
+            proof.get_trace_info(),
+            pub_inputs.clone(),
+            proof.options().clone(),
+        );
+        let mut channel: VerifierChannel<Felt, Rp64_256> =
+            VerifierChannel::new(&air, proof.clone()).unwrap();
+        let state = replay_transcript(&air, &mut channel, &seed).unwrap();
+        assert!(channel
+            .read_queried_trace_states(&air, &state.query_positions)
+            .is_ok());
+
+        // a coin seeded for other public inputs draws positions which do not open the proof
+        let wrong_inputs = PublicInputs::new(example.program.hash(), vec![], vec![Felt::new(1)]);
+        let wrong_seed = miden_coin_seed(&wrong_inputs, &proof.context);
+        let mut channel: VerifierChannel<Felt, Rp64_256> =
+            VerifierChannel::new(&air, proof).unwrap();
+        let state = replay_transcript(&air, &mut channel, &wrong_seed).unwrap();
+        assert!(channel
+            .read_queried_trace_states(&air, &state.query_positions)
+            .is_err());
+    }
+
+    /// Checks that queries which do not open every segment of the trace layout of the AIR with
+    /// states as wide as the segment, or constraint evaluations in fewer columns than the
+    /// composition columns, are rejected before they are checked against the commitments.
+    #[test]
+    fn misshapen_queries_rejected() {
+        let example = get_example(16);
+        let options = ProofOptions::new(
+            8,
+            8,
+            0,
+            HashFunction::Rp64_256,
+            FieldExtension::None,
+            8,
+            256,
+        );
+        let (outputs, proof) = miden::prove(
+            &example.program,
+            &example.inputs,
+            example.num_outputs,
+            &options,
+        )
+        .unwrap();
+        let stack_inputs = example.pub_inputs.iter().rev().map(|&v| Felt::new(v));
+        let stack_outputs = outputs.iter().map(|&v| Felt::new(v));
+        let pub_inputs = PublicInputs::new(
+            example.program.hash(),
+            stack_inputs.collect(),
+            stack_outputs.collect(),
+        );
+        let seed = miden_coin_seed(&pub_inputs, &proof.context);
+        let air = ProcessorAir::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+        assert!(air.trace_info().is_multi_segment());
+
+        let build_channel = || VerifierChannel::<Felt, Rp64_256>::new(&air, proof.clone()).unwrap();
+        let positions = replay_transcript(&air, &mut build_channel(), &seed)
+            .unwrap()
+            .query_positions;
+        let mut channel = build_channel();
+        assert!(channel.read_queried_trace_states(&air, &positions).is_ok());
+        assert!(channel
+            .read_constraint_evaluations(&air, &positions)
+            .is_ok());
+
+        // the openings of the auxiliary segment are left out
+        let mut channel = build_channel();
+        channel.queried_aux_trace_states_mut().unwrap().clear();
+        channel.trace_query_proofs_mut().unwrap().truncate(1);
+        assert_shape_error(channel.read_queried_trace_states(&air, &positions));
+
+        // the queries open a segment which is not in the trace layout
+        let mut channel = build_channel();
+        let proofs = channel.trace_query_proofs_mut().unwrap();
+        proofs.push(proofs[0].clone());
+        assert_shape_error(channel.read_queried_trace_states(&air, &positions));
+
+        // the opened rows of the main and the auxiliary segment are narrowed
+        let mut channel = build_channel();
+        let states = channel.queried_main_trace_states_mut().unwrap();
+        *states = drop_last_column(states);
+        assert_shape_error(channel.read_queried_trace_states(&air, &positions));
+
+        let mut channel = build_channel();
+        let states = &mut channel.queried_aux_trace_states_mut().unwrap()[0];
+        *states = drop_last_column(states);
+        assert_shape_error(channel.read_queried_trace_states(&air, &positions));
+
+        // the opened rows of the constraint evaluations are narrowed
+        let mut channel = build_channel();
+        let evaluations = channel.queried_constraint_evaluations_mut().unwrap();
+        *evaluations = drop_last_column(evaluations);
+        assert_shape_error(channel.read_constraint_evaluations(&air, &positions));
+    }
+
+    /// Asserts that the provided result is the error reporting queries of an unexpected shape.
+    fn assert_shape_error<T>(result: Result<T, VerifierError>) {
+        assert!(matches!(
+            result,
+            Err(VerifierError::ProofDeserializationError(_))
+        ));
+    }
+
+    /// Returns the provided table without its last column.
+    fn drop_last_column<E: FieldElement>(table: &Table<E>) -> Table<E> {
+        let num_columns = table.num_columns() - 1;
+        let data: Vec<E> = table
+            .rows()
+            .flat_map(|row| row[..num_columns].to_vec())
+            .collect();
+        Table::from_bytes(E::elements_as_bytes(&data), table.num_rows(), num_columns).unwrap()
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::fmt::Write;
+use utils::aux_input::AuxInputCompression;
+use utils::heap::DEFAULT_HEAP_ESTIMATE_CAP;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Default number of input bytes which may be passed to a guest.
+pub const DEFAULT_MAX_INPUT_BYTES: usize = 32 * 1024 * 1024;
+
+// INPUT USAGE
+// ================================================================================================
+
+/// Resources a guest run needs for the inputs packaged by the host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputUsage {
+    /// Total size of the inputs passed to the guest, in bytes.
+    pub input_bytes: usize,
+    /// Compression of the aux input.
+    pub compression: AuxInputCompression,
+    /// Number of heap bytes the guest reserves to decode the aux input.
+    pub aux_input_heap_bytes: usize,
+    /// Number of heap bytes the guest reserves to verify the packaged proofs.
+    pub verification_heap_bytes: usize,
+    /// Number of proofs packaged for the guest.
+    pub num_proofs: usize,
+}
+
+impl InputUsage {
+    /// Returns the total number of heap bytes the guest reserves.
+    pub fn heap_bytes(&self) -> usize {
+        self.aux_input_heap_bytes + self.verification_heap_bytes
+    }
+}
+
+// INPUT BUDGET
+// ================================================================================================
+
+/// Limits on the inputs of a guest run, checked on the host before the guest is launched.
+///
+/// A guest which runs out of input space or heap fails deep in the zkVM, or commits a heap estimate
+/// failure only after the prover has already run; checking the packaged inputs against the same
+/// limits up front fails fast, and suggests options which make the inputs fit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputBudget {
+    max_input_bytes: usize,
+    heap_estimate_cap: usize,
+}
+
+impl InputBudget {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a budget with the specified limits on the input size and on the heap of the guest,
+    /// both in bytes.
+    pub fn new(max_input_bytes: usize, heap_estimate_cap: usize) -> Self {
+        Self {
+            max_input_bytes,
+            heap_estimate_cap,
+        }
+    }
+
+    /// Returns a budget with the specified limit on the input size, and the heap estimate cap the
+    /// guests are built with: the value of `ZKOS_GUEST_HEAP_ESTIMATE_CAP` if it is set, and the
+    /// default cap otherwise.
+    ///
+    /// # Errors
+    /// Returns an error if `ZKOS_GUEST_HEAP_ESTIMATE_CAP` is set, but is not a number of bytes.
+    pub fn from_env(max_input_bytes: usize) -> Result<Self> {
+        let heap_estimate_cap = match std::env::var("ZKOS_GUEST_HEAP_ESTIMATE_CAP") {
+            Ok(value) => value
+                .parse()
+                .map_err(|_| anyhow!("ZKOS_GUEST_HEAP_ESTIMATE_CAP must be a number of bytes"))?,
+            Err(_) => DEFAULT_HEAP_ESTIMATE_CAP,
+        };
+        Ok(Self::new(max_input_bytes, heap_estimate_cap))
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the maximum number of input bytes which may be passed to a guest.
+    pub fn max_input_bytes(&self) -> usize {
+        self.max_input_bytes
+    }
+
+    /// Returns the number of heap bytes a guest may reserve.
+    pub fn heap_estimate_cap(&self) -> usize {
+        self.heap_estimate_cap
+    }
+
+    // BUDGET CHECK
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that the provided usage fits within this budget.
+    ///
+    /// # Errors
+    /// Returns an error if the inputs are larger than the input limit, or if the guest would
+    /// reserve more heap than its cap; the error lists options which reduce the usage.
+    pub fn check(&self, usage: &InputUsage) -> Result<()> {
+        debug!(
+            "Guest inputs: {} of {} bytes, guest heap: {} of {} bytes",
+            usage.input_bytes,
+            self.max_input_bytes,
+            usage.heap_bytes(),
+            self.heap_estimate_cap
+        );
+        let input_exceeded = usage.input_bytes > self.max_input_bytes;
+        let heap_exceeded = usage.heap_bytes() > self.heap_estimate_cap;
+        if !input_exceeded && !heap_exceeded {
+            return Ok(());
+        }
+
+        let mut message = String::new();
+        if input_exceeded {
+            write!(
+                message,
+                "packaged inputs of {} bytes exceed the guest input budget of {} bytes",
+                usage.input_bytes, self.max_input_bytes
+            )?;
+        }
+        if heap_exceeded {
+            if input_exceeded {
+                message.push_str("; ");
+            }
+            write!(
+                message,
+                "the guest is estimated to need {} bytes of heap ({} to decode the aux input, {} \
+                 to verify the proofs), but its heap estimate cap is {} bytes",
+                usage.heap_bytes(),
+                usage.aux_input_heap_bytes,
+                usage.verification_heap_bytes,
+                self.heap_estimate_cap
+            )?;
+        }
+
+        message.push_str("; try:");
+        for suggestion in self.suggestions(usage, input_exceeded, heap_exceeded) {
+            write!(message, "\n  - {}", suggestion)?;
+        }
+        Err(anyhow!(message))
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns options which reduce the exceeded parts of the provided usage.
+    fn suggestions(
+        &self,
+        usage: &InputUsage,
+        input_exceeded: bool,
+        heap_exceeded: bool,
+    ) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        if input_exceeded && usage.compression == AuxInputCompression::None {
+            suggestions.push(
+                "pass --compress-aux to compress the packaged proofs with LZ4, at the cost of \
+                 guest cycles and heap spent on decompression"
+                    .to_string(),
+            );
+        }
+        if heap_exceeded && usage.compression == AuxInputCompression::Lz4 {
+            suggestions.push(format!(
+                "drop --compress-aux, so that the aux input is accessed in place instead of \
+                 being decompressed into {} bytes of heap",
+                usage.aux_input_heap_bytes
+            ));
+        }
+        if usage.num_proofs > 1 {
+            suggestions.push(format!(
+                "split the batch of {} proofs across several guest runs",
+                usage.num_proofs
+            ));
+        }
+        suggestions.push(
+            "lower --fri-queries or --blowup-factor to generate smaller proofs, if the security \
+             level allows it"
+                .to_string(),
+        );
+        if input_exceeded {
+            suggestions
+                .push("raise --max-input-bytes if the guest has more input space".to_string());
+        }
+        if heap_exceeded {
+            suggestions.push(
+                "rebuild the guests with a larger ZKOS_GUEST_HEAP_ESTIMATE_CAP, and run with the \
+                 same value set"
+                    .to_string(),
+            );
+        }
+        suggestions
+    }
+}
@@ -0,0 +1,240 @@
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use risc0_zkvm::host::Receipt;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Domain separator of cache keys; it is changed whenever the layout of cache entries changes, so
+/// that entries written by older versions of the runner are never read.
+const CACHE_KEY_DOMAIN: &[u8] = b"zkprunner-proof-cache-v1";
+
+/// Extension of the files holding cache entries.
+const ENTRY_EXTENSION: &str = "receipt";
+
+// CACHE KEY
+// ================================================================================================
+
+/// Content address of a cached receipt.
+///
+/// The key binds the image ID of the guest and the digest of all inputs passed to it. The inputs
+/// include the packaged proofs together with their AIR parameters and proof options, and thus,
+/// any change to the statement, to the proof options, or to the guest yields a different key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CacheKey([u8; 32]);
+
+impl CacheKey {
+    /// Returns the key of the receipt of a run of the guest with the specified image ID on inputs
+    /// with the specified digest.
+    pub fn new(image_id: &[u8], input_digest: &[u8; 32]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(CACHE_KEY_DOMAIN);
+        hasher.update((image_id.len() as u32).to_le_bytes());
+        hasher.update(image_id);
+        hasher.update(input_digest);
+        Self(hasher.finalize().into())
+    }
+
+    /// Returns this key encoded as a hex string.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+}
+
+// PROOF CACHE
+// ================================================================================================
+
+/// A content-addressed cache of receipts stored in a directory.
+///
+/// Every entry holds the journal and the seal of a receipt in a file named after its
+/// [CacheKey]. Entries are written only for receipts which were verified, but they are not
+/// trusted when read back: a receipt read from the cache is expected to be verified against the
+/// image IDs of the guest like a freshly proven one.
+#[derive(Clone, Debug)]
+pub struct ProofCache {
+    dir: PathBuf,
+}
+
+impl ProofCache {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a cache stored in the specified directory; the directory is created when the first
+    /// entry is written.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the directory this cache is stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // ENTRIES
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the receipt cached under the specified key, or None if there is no such entry.
+    ///
+    /// Entries which cannot be decoded are removed from the cache and reported as missing.
+    ///
+    /// # Errors
+    /// Returns an error if the cache entry exists but cannot be read.
+    pub fn get(&self, key: &CacheKey) -> Result<Option<Receipt>> {
+        let path = self.entry_path(key);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("failed to read cache entry {}", path.display()))
+            }
+        };
+
+        match decode_entry(&bytes) {
+            Ok(receipt) => {
+                debug!("Read cached receipt from {}", path.display());
+                Ok(Some(receipt))
+            }
+            Err(e) => {
+                warn!("removing malformed cache entry {}: {}", path.display(), e);
+                fs::remove_file(&path)
+                    .with_context(|| format!("failed to remove cache entry {}", path.display()))?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Caches the provided receipt under the specified key, replacing any existing entry.
+    ///
+    /// The entry is written to a temporary file first, and then moved into place, so that an
+    /// interrupted run never leaves a partially written entry behind.
+    ///
+    /// # Errors
+    /// Returns an error if the journal of the receipt cannot be read, or if the entry cannot be
+    /// written.
+    pub fn put(&self, key: &CacheKey, receipt: &Receipt) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create cache directory {}", self.dir.display()))?;
+
+        let journal = receipt
+            .get_journal_vec()
+            .context("failed to read journal")?;
+        let bytes = encode_entry(&journal, receipt.get_seal());
+
+        let path = self.entry_path(key);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)
+            .with_context(|| format!("failed to write cache entry {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("failed to write cache entry {}", path.display()))?;
+        debug!("Cached receipt in {}", path.display());
+
+        Ok(())
+    }
+
+    // GARBAGE COLLECTION
+    // --------------------------------------------------------------------------------------------
+
+    /// Removes entries which were written more than `max_age` ago, as well as leftovers of
+    /// interrupted writes, and returns the number of removed entries.
+    ///
+    /// # Errors
+    /// Returns an error if the cache directory cannot be listed, or if an expired entry cannot be
+    /// removed.
+    pub fn gc(&self, max_age: Duration) -> Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("failed to list cache directory {}", self.dir.display())
+                })
+            }
+        };
+
+        let now = SystemTime::now();
+        let mut num_removed = 0;
+        for entry in entries {
+            let path = entry
+                .with_context(|| format!("failed to list cache directory {}", self.dir.display()))?
+                .path();
+            let is_expired = match path.extension().and_then(|ext| ext.to_str()) {
+                Some(ENTRY_EXTENSION) => {
+                    let modified = fs::metadata(&path)
+                        .and_then(|metadata| metadata.modified())
+                        .with_context(|| {
+                            format!("failed to stat cache entry {}", path.display())
+                        })?;
+                    // entries with a modification time in the future are kept
+                    now.duration_since(modified).unwrap_or_default() > max_age
+                }
+                Some("tmp") => true,
+                _ => false,
+            };
+            if is_expired {
+                fs::remove_file(&path)
+                    .with_context(|| format!("failed to remove cache entry {}", path.display()))?;
+                num_removed += 1;
+            }
+        }
+
+        Ok(num_removed)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the path of the file holding the entry with the specified key.
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(key.to_hex()).with_extension(ENTRY_EXTENSION)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Encodes a cache entry as little-endian words: the number of journal words, followed by the
+/// journal and the seal.
+fn encode_entry(journal: &[u32], seal: &[u32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity((1 + journal.len() + seal.len()) * 4);
+    bytes.extend_from_slice(&(journal.len() as u32).to_le_bytes());
+    for word in journal.iter().chain(seal) {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+/// Decodes a cache entry encoded by [encode_entry] into a receipt.
+fn decode_entry(bytes: &[u8]) -> Result<Receipt> {
+    if bytes.len() % 4 != 0 {
+        return Err(anyhow!(
+            "entry of {} bytes is not word-aligned",
+            bytes.len()
+        ));
+    }
+    let words = bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect::<Vec<_>>();
+
+    let (&journal_len, words) = words
+        .split_first()
+        .ok_or_else(|| anyhow!("entry is empty"))?;
+    if journal_len as usize > words.len() {
+        return Err(anyhow!(
+            "entry holds {} words, but its journal is {} words long",
+            words.len(),
+            journal_len
+        ));
+    }
+    let (journal, seal) = words.split_at(journal_len as usize);
+    Receipt::new(journal, seal).context("failed to rebuild receipt")
+}
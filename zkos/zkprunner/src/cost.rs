@@ -0,0 +1,209 @@
+use anyhow::{Context, Result};
+use log::info;
+use risc0_zkvm::host::Receipt;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// COST REPORT
+// ================================================================================================
+
+/// Wall-clock time spent by the host in a phase of a prove run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PhaseCost {
+    /// Name of the phase, e.g., "prove".
+    pub name: String,
+    /// Wall-clock time spent in the phase, in milliseconds.
+    pub wall_ms: u64,
+}
+
+/// Cycles spent by the guest in a top-level frame of its cycle attribution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GuestPhaseCost {
+    /// Name of the frame, e.g., "verify_proof_1".
+    pub name: String,
+    /// Cycles spent in the frame, including its children.
+    pub cycles: u64,
+}
+
+/// Cost of a single prove run, which is appended to the cost history so that the cost of the
+/// recursion can be tracked across code changes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CostReport {
+    /// Time at which the run finished, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Git revision of the workspace the run was built from, if it can be determined.
+    pub revision: Option<String>,
+    /// Name of the guest which was proven.
+    pub guest: String,
+    /// Cycles executed by the guest; these are known only if the guest committed its cycle count
+    /// or was built with cycle attribution enabled.
+    pub cycles: Option<u64>,
+    /// Number of segments the execution was proven in; the prover proves every execution as a
+    /// single segment.
+    pub segments: u32,
+    /// Wall-clock time of the whole run, in milliseconds.
+    pub wall_ms: u64,
+    /// Size of the seal of the receipt, in bytes.
+    pub proof_bytes: usize,
+    /// Size of the journal of the receipt, in bytes.
+    pub journal_bytes: usize,
+    /// Size of the inputs passed to the guest, in bytes.
+    pub input_bytes: usize,
+    /// Wall-clock time of every phase of the run, in the order in which the phases were run.
+    pub phases: Vec<PhaseCost>,
+    /// Cycles of every top-level frame of the guest; empty unless the guest was built with cycle
+    /// attribution enabled.
+    pub guest_phases: Vec<GuestPhaseCost>,
+}
+
+impl CostReport {
+    /// Returns this report serialized as a single-line JSON object.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).context("failed to serialize cost report")
+    }
+
+    /// Appends this report as a single line to the cost history at the specified path; the file
+    /// is created if it does not exist.
+    ///
+    /// # Errors
+    /// Returns an error if the history file cannot be opened or written to.
+    pub fn append_to_history(&self, path: &Path) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open cost history {}", path.display()))?;
+        writeln!(file, "{}", self.to_json()?)
+            .with_context(|| format!("failed to write cost history {}", path.display()))
+    }
+}
+
+// COST RECORDER
+// ================================================================================================
+
+/// Records the wall-clock time of the phases of a prove run, and builds the [CostReport] of the
+/// run once its receipt is available.
+pub struct CostRecorder {
+    guest: String,
+    start: Instant,
+    phases: Vec<PhaseCost>,
+}
+
+impl CostRecorder {
+    /// Returns a new recorder for a prove run of the specified guest which starts now.
+    pub fn new(guest: &str) -> Self {
+        Self {
+            guest: guest.to_string(),
+            start: Instant::now(),
+            phases: Vec::new(),
+        }
+    }
+
+    /// Runs the provided function as a phase with the specified name, and records its wall-clock
+    /// time.
+    pub fn phase<T>(&mut self, name: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.phases.push(PhaseCost {
+            name: name.to_string(),
+            wall_ms: start.elapsed().as_millis() as u64,
+        });
+        result
+    }
+
+    /// Builds the report of the run from its receipt, the size of the guest inputs, the cycle
+    /// count committed by the guest, if any, and the cycle attribution committed by the guest, if
+    /// it was built with cycle attribution enabled.
+    ///
+    /// If the guest did not commit its cycle count, the cycles are summed over its cycle
+    /// attribution instead.
+    ///
+    /// # Errors
+    /// Returns an error if the seal or the journal cannot be read from the receipt.
+    pub fn finish(
+        self,
+        receipt: &Receipt,
+        input_bytes: usize,
+        cycle_count: Option<u64>,
+        folded: Option<&[(String, u64)]>,
+    ) -> Result<CostReport> {
+        let proof_bytes = receipt.get_seal().context("failed to read seal")?.len() * 4;
+        let journal_bytes = receipt
+            .get_journal_vec()
+            .context("failed to read journal")?
+            .len()
+            * 4;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        Ok(CostReport {
+            timestamp,
+            revision: git_revision(),
+            guest: self.guest,
+            cycles: cycle_count
+                .or_else(|| folded.map(|folded| folded.iter().map(|(_, cycles)| cycles).sum())),
+            segments: 1,
+            wall_ms: self.start.elapsed().as_millis() as u64,
+            proof_bytes,
+            journal_bytes,
+            input_bytes,
+            phases: self.phases,
+            guest_phases: folded.map(guest_phases).unwrap_or_default(),
+        })
+    }
+}
+
+/// Logs the provided report and appends it to the cost history at the specified path.
+///
+/// # Errors
+/// Returns an error if the report cannot be appended to the history.
+pub fn record_cost(report: &CostReport, history: &Path) -> Result<()> {
+    info!("Cost report: {}", report.to_json()?);
+    report.append_to_history(history)?;
+    info!("Appended cost report to {}", history.display());
+    Ok(())
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Sums the self cycles of the provided folded stacks by the top-level frame below the root
+/// frame of the guest; cycles of the root frame itself are reported under its own name.
+fn guest_phases(folded: &[(String, u64)]) -> Vec<GuestPhaseCost> {
+    let mut phases: BTreeMap<&str, u64> = BTreeMap::new();
+    for (stack, cycles) in folded {
+        let mut frames = stack.split(';');
+        let root = frames.next().unwrap_or_default();
+        let name = frames.next().unwrap_or(root);
+        *phases.entry(name).or_insert(0) += cycles;
+    }
+    phases
+        .into_iter()
+        .map(|(name, cycles)| GuestPhaseCost {
+            name: name.to_string(),
+            cycles,
+        })
+        .collect()
+}
+
+/// Returns the abbreviated git revision of the workspace zkprunner was built from, or None if it
+/// cannot be determined.
+fn git_revision() -> Option<String> {
+    let output = Command::new("git")
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let revision = String::from_utf8(output.stdout).ok()?;
+    Some(revision.trim().to_string())
+}
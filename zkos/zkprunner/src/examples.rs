@@ -1,31 +1,236 @@
 use anyhow::{anyhow, Result};
 use env_logger::Env;
+use errors::{ErrorChain, ErrorKind, ResultExt};
 use log::info;
-use methods::{EXP_ID, EXP_PATH, RECURSIVE_ID, RECURSIVE_PATH, SHA3_ID, SHA3_PATH};
-use miden::{Program, ProofOptions};
+use methods::{
+    EXP_ID, EXP_PATH, FIB128_VERIFY_ID, FIB128_VERIFY_PATH, FIELD_DIFF_ID, FIELD_DIFF_PATH,
+    RECURSIVE_ID, RECURSIVE_PATH, SHA3_ID, SHA3_PATH,
+};
+use miden::{FieldExtension, HashFunction, PaddingPolicy, Program, ProofOptions};
 use miden_air::{Felt, FieldElement, ProcessorAir, PublicInputs};
 use miden_core::utils::Serializable;
 use risc0_zkvm::host::Prover;
 use risc0_zkvm::serde::{from_slice, to_vec};
 use sha3::{Digest, Sha3_256};
-use utils::inputs::{MidenAirInput, MidenRiscInput};
+use std::io::Read;
+use utils::aux_input::{encode_aux_input, AuxInputCompression};
+use utils::context::{DomainTag, VerifierContext};
+use utils::fib::fib128::{Fib128Air, Fib128Example};
+use utils::inputs::{
+    trace_segment_infos, Fib128RiscInput, FibAirInput, MidenAirInput, MidenRiscInput,
+};
+use utils::journal::{statement_digest, trace_info_bytes};
+use utils::link::{ProofLink, RecursiveMode};
+use utils::policy::ProofPolicy;
 use winter_air::proof::{Commitments, Context, OodFrame, Queries, StarkProof};
 use winter_air::Air;
 use winter_crypto::hashers::DefaultSha2;
-use winter_crypto::hashers::Sha2_256;
-use winter_math::fields::f64::{BaseElement, INV_NONDET};
+use winter_crypto::hashers::{Keccak256, Rp64_256, Sha2_256};
+use winter_crypto::ElementHasher;
+use winter_math::fields::f128;
+use winter_math::fields::f64::{BaseElement, INV_NONDET, INV_NONDET_QUAD};
+use winter_math::fields::{CubeExtension, QuadExtension};
 use winter_verifier::VerifierChannel;
 
+use crate::journal::decode_journal;
+use crate::receipt::run_prover;
+use rkyv::ser::serializers::AllocSerializer;
 use utils::fibonacci_miden;
+use utils::ref_field;
+
+type H256 = Sha2_256<BaseElement, DefaultSha2>;
 
 #[allow(dead_code)]
 fn recursive_miden() -> Result<()> {
+    verify_miden_recursively::<BaseElement, H256>(get_proof_options_miden())
+}
+
+/// Verifies a proof with out-of-domain evaluations in the quadratic extension field in the
+/// recursive guest.
+#[allow(dead_code)]
+fn recursive_miden_quad() -> Result<()> {
+    verify_miden_recursively::<QuadExtension<BaseElement>, H256>(get_proof_options_miden_quad())
+}
+
+/// Verifies a proof with out-of-domain evaluations in the cubic extension field in the recursive
+/// guest.
+#[allow(dead_code)]
+fn recursive_miden_cube() -> Result<()> {
+    verify_miden_recursively::<CubeExtension<BaseElement>, H256>(get_proof_options_miden_cube())
+}
+
+/// Verifies a Miden-native proof, with commitments made with RPO, in the recursive guest.
+#[allow(dead_code)]
+fn recursive_miden_rpo() -> Result<()> {
+    verify_miden_recursively::<BaseElement, Rp64_256>(ProofOptions::with_rpo())
+}
+
+/// Verifies a proof with commitments made with Keccak-256, as checked on EVM chains, in the
+/// recursive guest.
+#[allow(dead_code)]
+fn recursive_miden_keccak() -> Result<()> {
+    verify_miden_recursively::<BaseElement, Keccak256<BaseElement>>(ProofOptions::with_keccak())
+}
+
+/// Proves a Miden execution with the provided options, verifies the proof in the recursive guest,
+/// and checks that the journal is bound to the execution; the out-of-domain evaluations of the
+/// proof are packaged in the field `E`, and its commitments are made with the hasher `H`, which
+/// must match the hash function of the options.
+fn verify_miden_recursively<E, H>(proof_options: ProofOptions) -> Result<()>
+where
+    E: FieldElement<BaseField = BaseElement>,
+    H: ElementHasher<BaseField = BaseElement>,
+    MidenRiscInput<E, H>: rkyv::Serialize<AllocSerializer<256>>,
+{
     println!("============================================================");
+    let (risc_inputs, air_input) = package_miden_proof::<E, H>(1024, None, proof_options)?;
+    let expected_statement_digest = air_input.statement_digest::<DefaultSha2>(&risc_inputs.context);
+
+    let mut prover = Prover::new(&std::fs::read(RECURSIVE_PATH).unwrap(), RECURSIVE_ID).unwrap();
+    let miden_risc_inputs = rkyv::to_bytes::<_, 256>(&risc_inputs).unwrap();
+    prover.add_input_u8_slice_aux(&encode_aux_input(
+        &miden_risc_inputs,
+        AuxInputCompression::None,
+    ));
+    // the guest reads the policy header before any other input, followed by the mode
+    let policy = ProofPolicy::new();
+    prover.add_input(to_vec(&policy.to_bytes())?.as_slice())?;
+    prover.add_input(to_vec(&RecursiveMode::Single)?.as_slice())?;
+    prover.add_input(to_vec(&air_input)?.as_slice())?;
+    let receipt = run_prover(&mut prover)?;
+    receipt.verify(RECURSIVE_ID).unwrap();
 
-    let proof_options = get_proof_options_miden();
+    let journal = decode_journal(&receipt)?;
+    if let Some(error) = journal.error {
+        return Err(anyhow::Error::new(error).context("guest failed to verify the miden proof"));
+    }
+    // the journal must be bound to the program hash and the stack inputs and outputs of the
+    // proven execution
+    if journal.statement_digest != expected_statement_digest {
+        return Err(anyhow!("journal is bound to a different execution"));
+    }
+    if !journal.result {
+        return Err(anyhow!("guest failed to verify the miden proof"));
+    }
+    Ok(())
+}
+
+/// Verifies two proofs of executions of the same program in the recursive guest in linked mode,
+/// and checks that the journal is bound to both statements and to the link.
+#[allow(dead_code)]
+fn recursive_miden_linked() -> Result<()> {
+    println!("============================================================");
+    let link = ProofLink::SameProgram;
+    let (first_inputs, first_air_input) =
+        package_miden_proof::<BaseElement, H256>(1024, None, get_proof_options_miden())?;
+    let (second_inputs, second_air_input) =
+        package_miden_proof::<BaseElement, H256>(1024, None, get_proof_options_miden())?;
+
+    let expected_statement_digest = statement_digest::<DefaultSha2>(&[
+        first_inputs.context.as_slice(),
+        first_air_input.public_inputs.to_bytes().as_slice(),
+        trace_info_bytes(&first_air_input.trace_info).as_slice(),
+        second_inputs.context.as_slice(),
+        second_air_input.public_inputs.to_bytes().as_slice(),
+        trace_info_bytes(&second_air_input.trace_info).as_slice(),
+        &link.to_bytes(),
+    ]);
+
+    let mut prover = Prover::new(&std::fs::read(RECURSIVE_PATH).unwrap(), RECURSIVE_ID).unwrap();
+    let miden_risc_inputs = rkyv::to_bytes::<_, 256>(&[first_inputs, second_inputs]).unwrap();
+    prover.add_input_u8_slice_aux(&encode_aux_input(
+        &miden_risc_inputs,
+        AuxInputCompression::None,
+    ));
+    let policy = ProofPolicy::new();
+    prover.add_input(to_vec(&policy.to_bytes())?.as_slice())?;
+    prover.add_input(to_vec(&RecursiveMode::Linked(link))?.as_slice())?;
+    prover.add_input(to_vec(&first_air_input)?.as_slice())?;
+    prover.add_input(to_vec(&second_air_input)?.as_slice())?;
+    let receipt = run_prover(&mut prover)?;
+    receipt.verify(RECURSIVE_ID).unwrap();
+
+    let journal = decode_journal(&receipt)?;
+    if let Some(error) = journal.error {
+        return Err(anyhow::Error::new(error).context("guest failed to verify the linked proofs"));
+    }
+    if journal.statement_digest != expected_statement_digest {
+        return Err(anyhow!(
+            "journal is bound to different statements or to a different link"
+        ));
+    }
+    if !journal.result {
+        return Err(anyhow!("guest failed to verify the linked proofs"));
+    }
+    Ok(())
+}
+
+/// Verifies a proof generated with blinding enabled in the recursive guest.
+///
+/// The proof is padded to twice the length of the trace of the execution, and the random rows at
+/// the end of the trace are drawn from a secret seed instead of the program hash. This is the
+/// only blinding supported by the prover: the commitments of winterfell proofs are not salted
+/// (the proof format carries no salts), so there are no salts for the packer to carry or for the
+/// guest to absorb into its transcript, and the guest verifies blinded proofs in the same way as
+/// any other proof. This checks that packing and verification depend neither on the values of
+/// the random rows nor on the trace being padded to its natural length.
+#[allow(dead_code)]
+fn recursive_miden_blinded() -> Result<()> {
+    println!("============================================================");
+    let mut rand_seed = [0u8; 32];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut source| source.read_exact(&mut rand_seed))
+        .map_err(|e| anyhow!("failed to draw the blinding seed: {}", e))?;
+    let (risc_inputs, air_input) =
+        package_miden_proof::<BaseElement, H256>(1024, Some(rand_seed), get_proof_options_miden())?;
+    let expected_statement_digest = air_input.statement_digest::<DefaultSha2>(&risc_inputs.context);
+
+    let mut prover = Prover::new(&std::fs::read(RECURSIVE_PATH).unwrap(), RECURSIVE_ID).unwrap();
+    let miden_risc_inputs = rkyv::to_bytes::<_, 256>(&risc_inputs).unwrap();
+    prover.add_input_u8_slice_aux(&encode_aux_input(
+        &miden_risc_inputs,
+        AuxInputCompression::None,
+    ));
+    let policy = ProofPolicy::new();
+    prover.add_input(to_vec(&policy.to_bytes())?.as_slice())?;
+    prover.add_input(to_vec(&RecursiveMode::Single)?.as_slice())?;
+    prover.add_input(to_vec(&air_input)?.as_slice())?;
+    let receipt = run_prover(&mut prover)?;
+    receipt.verify(RECURSIVE_ID).unwrap();
+
+    let journal = decode_journal(&receipt)?;
+    if let Some(error) = journal.error {
+        return Err(anyhow::Error::new(error).context("guest failed to verify the blinded proof"));
+    }
+    if journal.statement_digest != expected_statement_digest {
+        return Err(anyhow!("journal is bound to a different execution"));
+    }
+    if !journal.result {
+        return Err(anyhow!("guest failed to verify the blinded proof"));
+    }
+    Ok(())
+}
 
+/// Proves the execution of the program computing the `n`-th Fibonacci term with the provided
+/// options, and packages the proof for the recursive guest.
+///
+/// The out-of-domain evaluations of the proof are packaged in the field `E`, which must be the
+/// field selected by the field extension of the options, and the commitments of the proof are
+/// made with the hasher `H`, which must be the one selected by the hash function of the options.
+///
+/// If a blinding seed is provided, the trace is padded to twice its natural length, and the
+/// random rows at the end of the trace are drawn from the seed instead of the program hash.
+fn package_miden_proof<E, H>(
+    n: usize,
+    blinding_seed: Option<[u8; 32]>,
+    proof_options: ProofOptions,
+) -> Result<(MidenRiscInput<E, H>, MidenAirInput)>
+where
+    E: FieldElement<BaseField = BaseElement>,
+    H: ElementHasher<BaseField = BaseElement>,
+{
     // instantiate and prepare the example
-    let example = fibonacci_miden::get_example(1024);
+    let example = fibonacci_miden::get_example(n);
 
     let fibonacci_miden::Example {
         program,
@@ -36,8 +241,22 @@ fn recursive_miden() -> Result<()> {
     } = example;
     println!("--------------------------------");
 
+    // processor errors implement Debug only
+    let padding = match blinding_seed {
+        Some(seed) => {
+            let report = miden::dry_run(&program, &inputs)
+                .map_err(|e| ErrorChain::new(ErrorKind::Execution, format!("{:?}", e)))?;
+            PaddingPolicy::new()
+                .with_min_trace_len(2 * report.trace_len)
+                .with_rand_seed(seed)
+        }
+        None => PaddingPolicy::new(),
+    };
+
     // execute the program and generate the proof of execution
-    let (outputs, proof) = miden::prove(&program, &inputs, num_outputs, &proof_options).unwrap();
+    let (outputs, proof) =
+        miden::prove_with_padding(&program, &inputs, num_outputs, &proof_options, padding)
+            .map_err(|e| ErrorChain::new(ErrorKind::Execution, format!("{:?}", e)))?;
     println!("--------------------------------");
     println!("Trace length: {}", proof.context.trace_length());
     println!("Trace queries length: {}", proof.trace_queries.len());
@@ -49,37 +268,36 @@ fn recursive_miden() -> Result<()> {
 
     let mut proof_context = Vec::new();
     proof.context.write_into(&mut proof_context);
+    let trace_segments = trace_segment_infos(proof.context.trace_layout());
 
     let (verifier_channel, air_input) =
         get_verifier_channel(&proof, &outputs, &pub_inputs, program.clone())?;
 
     // run verify in order to generate nondet inv inputs
-    miden::verify(program.hash().clone(), &pub_inputs[..], &outputs[..], proof).unwrap();
+    miden::verify(program.hash().clone(), &pub_inputs[..], &outputs[..], proof)
+        .with_kind(ErrorKind::Verification)?;
 
+    let context = VerifierContext::new(DomainTag::default(), proof_context);
     let risc_inputs = MidenRiscInput {
-        context: proof_context,
+        context: context.to_bytes(),
+        trace_segments,
         verifier_channel,
         inv_nondet: INV_NONDET.lock().clone().into_iter().collect(),
+        inv_nondet_quad: INV_NONDET_QUAD.lock().clone().into_iter().collect(),
     };
-
-    let mut prover = Prover::new(&std::fs::read(RECURSIVE_PATH).unwrap(), RECURSIVE_ID).unwrap();
-    let miden_risc_inputs = rkyv::to_bytes::<_, 256>(&risc_inputs).unwrap();
-    prover.add_input_u8_slice_aux(&miden_risc_inputs);
-    prover.add_input(to_vec(&air_input)?.as_slice())?;
-    let receipt = prover.run().unwrap();
-    receipt.verify(RECURSIVE_ID).unwrap();
-    Ok(())
+    Ok((risc_inputs, air_input))
 }
 
-fn get_verifier_channel(
+fn get_verifier_channel<E, H>(
     proof: &StarkProof,
     outputs: &Vec<u64>,
     inputs: &Vec<u64>,
     program: Program,
-) -> Result<(
-    VerifierChannel<BaseElement, Sha2_256<BaseElement, DefaultSha2>>,
-    MidenAirInput,
-)> {
+) -> Result<(VerifierChannel<E, H>, MidenAirInput)>
+where
+    E: FieldElement<BaseField = BaseElement>,
+    H: ElementHasher<BaseField = BaseElement>,
+{
     let mut stack_input_felts: Vec<Felt> = Vec::with_capacity(inputs.len());
     for &input in inputs.iter().rev() {
         stack_input_felts.push(
@@ -111,6 +329,93 @@ fn get_verifier_channel(
     ))
 }
 
+/// Verifies a Fibonacci proof over the 128-bit field in the f128 fib verifier guest, with
+/// out-of-domain evaluations in the base field and in its quadratic extension.
+#[allow(dead_code)]
+fn fib128_verify() -> Result<()> {
+    for extension in [FieldExtension::None, FieldExtension::Quadratic] {
+        let proof_options =
+            winter_air::ProofOptions::new(27, 8, 16, HashFunction::Sha2_256, extension, 8, 256);
+        match extension {
+            FieldExtension::None => verify_fib128_recursively::<f128::BaseElement>(proof_options)?,
+            _ => verify_fib128_recursively::<QuadExtension<f128::BaseElement>>(proof_options)?,
+        }
+    }
+    Ok(())
+}
+
+/// Proves a Fibonacci computation over the 128-bit field with the provided options, verifies the
+/// proof in the f128 fib verifier guest, and checks that the journal is bound to the result; the
+/// out-of-domain evaluations of the proof are packaged in the field `E`.
+fn verify_fib128_recursively<E: FieldElement<BaseField = f128::BaseElement>>(
+    proof_options: winter_air::ProofOptions,
+) -> Result<()>
+where
+    Fib128RiscInput<E, Sha2_256<f128::BaseElement, DefaultSha2>>:
+        rkyv::Serialize<AllocSerializer<256>>,
+{
+    println!("============================================================");
+    let example = Fib128Example::new(1024, proof_options);
+    let proof = example.prove();
+    example
+        .verify(proof.clone())
+        .map_err(|e| anyhow!("failed to verify the fib proof: {}", e))?;
+
+    let air = Fib128Air::new(
+        proof.get_trace_info(),
+        example.result,
+        proof.options().clone(),
+    );
+    let verifier_channel: VerifierChannel<E, Sha2_256<f128::BaseElement, DefaultSha2>> =
+        VerifierChannel::new::<Fib128Air>(&air, proof.clone()).map_err(|msg| anyhow!(msg))?;
+    let mut proof_context = Vec::new();
+    proof.context.write_into(&mut proof_context);
+    let context = VerifierContext::new(DomainTag::default(), proof_context);
+    let risc_inputs = Fib128RiscInput {
+        result: example.result,
+        context: context.to_bytes(),
+        trace_segments: trace_segment_infos(proof.context.trace_layout()),
+        verifier_channel,
+    };
+    let air_input = FibAirInput {
+        trace_info: proof.get_trace_info(),
+        proof_options: proof.options().clone(),
+    };
+    let expected_statement_digest = statement_digest::<DefaultSha2>(&[
+        risc_inputs.context.as_slice(),
+        example.result.to_bytes().as_slice(),
+        trace_info_bytes(&air_input.trace_info).as_slice(),
+    ]);
+
+    let mut prover = Prover::new(
+        &std::fs::read(FIB128_VERIFY_PATH).unwrap(),
+        FIB128_VERIFY_ID,
+    )
+    .unwrap();
+    let fib_risc_inputs = rkyv::to_bytes::<_, 256>(&risc_inputs).unwrap();
+    prover.add_input_u8_slice_aux(&encode_aux_input(
+        &fib_risc_inputs,
+        AuxInputCompression::None,
+    ));
+    let policy = ProofPolicy::new();
+    prover.add_input(to_vec(&policy.to_bytes())?.as_slice())?;
+    prover.add_input(to_vec(&air_input)?.as_slice())?;
+    let receipt = run_prover(&mut prover)?;
+    receipt.verify(FIB128_VERIFY_ID).unwrap();
+
+    let journal = decode_journal(&receipt)?;
+    if let Some(error) = journal.error {
+        return Err(anyhow::Error::new(error).context("guest failed to verify the fib proof"));
+    }
+    if journal.statement_digest != expected_statement_digest {
+        return Err(anyhow!("journal is bound to a different statement"));
+    }
+    if !journal.result {
+        return Err(anyhow!("guest failed to verify the fib proof"));
+    }
+    Ok(())
+}
+
 #[allow(dead_code)]
 fn sha3() {
     let mut prover = Prover::new(&std::fs::read(SHA3_PATH).unwrap(), SHA3_ID).unwrap();
@@ -145,6 +450,57 @@ fn exp() {
     assert_eq!(res_felt, BaseElement::new(a) * BaseElement::new(b));
 }
 
+#[allow(dead_code)]
+fn field_diff() {
+    // compare the reference field against winter_math's f64 field inside the guest over all
+    // pairs of adversarial values
+    let values = ref_field::adversarial_values();
+    let pairs: Vec<(u64, u64)> = values
+        .iter()
+        .flat_map(|&a| values.iter().map(move |&b| (a, b)))
+        .collect();
+
+    let mut prover = Prover::new(&std::fs::read(FIELD_DIFF_PATH).unwrap(), FIELD_DIFF_ID).unwrap();
+    prover
+        .add_input(to_vec(&pairs).unwrap().as_slice())
+        .unwrap();
+    let receipt = prover.run().unwrap();
+    let mismatches: u32 = from_slice(&receipt.get_journal_vec().unwrap()).unwrap();
+    receipt.verify(FIELD_DIFF_ID).unwrap();
+    assert_eq!(
+        0, mismatches,
+        "guest field arithmetic diverged from the reference field"
+    );
+}
+
 pub fn get_proof_options_miden() -> ProofOptions {
     ProofOptions::with_sha2()
 }
+
+/// Returns the options of [get_proof_options_miden()] with the out-of-domain evaluations drawn
+/// from the quadratic extension of the base field.
+pub fn get_proof_options_miden_quad() -> ProofOptions {
+    ProofOptions::new(
+        1,
+        8,
+        16,
+        HashFunction::Sha2_256,
+        FieldExtension::Quadratic,
+        8,
+        256,
+    )
+}
+
+/// Returns the options of [get_proof_options_miden()] with the out-of-domain evaluations drawn
+/// from the cubic extension of the base field.
+pub fn get_proof_options_miden_cube() -> ProofOptions {
+    ProofOptions::new(
+        1,
+        8,
+        16,
+        HashFunction::Sha2_256,
+        FieldExtension::Cubic,
+        8,
+        256,
+    )
+}
@@ -0,0 +1,278 @@
+use anyhow::Error;
+use errors::ErrorChain;
+use std::fmt;
+
+// FAILURE KIND
+// ================================================================================================
+
+/// Class of a failure to verify a STARK proof, on the host or in a guest.
+///
+/// Failures reach the host as error messages: the winterfell verifier reports them through its
+/// error enum, while the guests report them as `anyhow` errors committed to the journal, or panic.
+/// The class of a failure is recovered from these messages, which all contain the description of
+/// the failed check given below in [FailureKind::patterns].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The query positions drawn by the verifier do not match the queried evaluations in the
+    /// proof, or could not be drawn from the public coin.
+    QueryPositions,
+    /// The constraints evaluated over the out-of-domain frame do not match the out-of-domain
+    /// evaluations of the constraint composition polynomial.
+    OodMismatch,
+    /// The queried trace states do not resolve to the trace commitments.
+    TraceCommitmentMismatch,
+    /// The queried constraint evaluations do not resolve to the constraint commitment.
+    ConstraintCommitmentMismatch,
+    /// The queried evaluations of a FRI layer or the FRI remainder do not resolve to their
+    /// commitment.
+    FriCommitmentMismatch,
+    /// The proof-of-work nonce does not satisfy the grinding factor.
+    ProofOfWork,
+    /// The DEEP composition polynomial is not of the expected degree.
+    LowDegree,
+}
+
+impl FailureKind {
+    /// All failure kinds, in the order in which they are matched against error messages.
+    const ALL: [Self; 7] = [
+        Self::QueryPositions,
+        Self::OodMismatch,
+        Self::TraceCommitmentMismatch,
+        Self::ConstraintCommitmentMismatch,
+        Self::FriCommitmentMismatch,
+        Self::ProofOfWork,
+        Self::LowDegree,
+    ];
+
+    /// Returns the lowercase fragments of the error messages reporting a failure of this kind.
+    fn patterns(&self) -> &'static [&'static str] {
+        match self {
+            Self::QueryPositions => &[
+                "number of query positions must be the same",
+                "failed to draw a random value",
+                "random coin error",
+            ],
+            Self::OodMismatch => &[
+                "constraint evaluations over the out-of-domain frame are inconsistent",
+                "inconsistent ood constraint evaluations",
+            ],
+            Self::TraceCommitmentMismatch => &["trace query did not match the commitment"],
+            Self::ConstraintCommitmentMismatch => {
+                &["constraint query did not match the commitment"]
+            }
+            Self::FriCommitmentMismatch => &[
+                "fri queries did not match layer commitment",
+                "fri remainder did not match the commitment",
+                "fri remainder merkle tree could not be constructed",
+            ],
+            Self::ProofOfWork => &[
+                "query seed proof-of-work verification failed",
+                "queryseedproofofworkverificationfailed",
+            ],
+            Self::LowDegree => &[
+                "degree-respecting projection",
+                "is not a valid degree",
+                "remainder expected degree",
+                "results in degree truncation",
+            ],
+        }
+    }
+
+    /// Returns the kind of the failure reported by the specified error message, if the message
+    /// reports a failure to verify a proof.
+    pub fn classify(message: &str) -> Option<Self> {
+        let message = message.to_lowercase();
+        Self::ALL.into_iter().find(|kind| {
+            kind.patterns()
+                .iter()
+                .any(|pattern| message.contains(pattern))
+        })
+    }
+
+    /// Returns the plain-language explanation of a failure of this kind.
+    pub fn explanation(&self) -> Explanation {
+        match self {
+            Self::QueryPositions => Explanation {
+                kind: *self,
+                description: "the verifier drew a different set of query positions than the \
+                    prover opened, so the queried evaluations in the proof cannot be matched to \
+                    the positions the verifier checks",
+                causes: &[
+                    "the public coin was seeded differently than by the prover, e.g., the \
+                     context or the public inputs were packaged differently than they were proven",
+                    "the number of FRI queries or the blowup factor of the proof options differs \
+                     from the options the proof was generated with",
+                    "a commitment absorbed by the public coin before the queries were drawn was \
+                     altered",
+                ],
+                fields: &[
+                    "context",
+                    "result",
+                    "verifier_channel.trace_roots",
+                    "verifier_channel.fri_layer_queries",
+                ],
+            },
+            Self::OodMismatch => Explanation {
+                kind: *self,
+                description: "the constraints evaluated over the out-of-domain frame do not \
+                    match the out-of-domain evaluations of the constraint composition polynomial \
+                    sent by the prover",
+                causes: &[
+                    "the proof attests to a different statement than the public inputs it is \
+                     verified against, e.g., a different fib result",
+                    "the AIR instantiated by the verifier differs from the AIR the proof was \
+                     generated for, e.g., the verification key or the trace segments are stale",
+                    "the out-of-domain frame or evaluations were altered or truncated during \
+                     packaging",
+                ],
+                fields: &[
+                    "result",
+                    "trace_segments",
+                    "verifier_channel.ood_trace_frame",
+                    "verifier_channel.ood_constraint_evaluations",
+                ],
+            },
+            Self::TraceCommitmentMismatch => Explanation {
+                kind: *self,
+                description: "the Merkle authentication paths of the queried trace states do \
+                    not resolve to the trace commitments in the proof",
+                causes: &[
+                    "the trace commitments were altered, reordered, or swapped with another \
+                     commitment during packaging",
+                    "the proof was generated with a different hash function than it is verified \
+                     with",
+                    "the queried trace states were opened at different positions than the \
+                     verifier drew",
+                ],
+                fields: &[
+                    "verifier_channel.trace_roots",
+                    "verifier_channel.trace_queries",
+                ],
+            },
+            Self::ConstraintCommitmentMismatch => Explanation {
+                kind: *self,
+                description: "the Merkle authentication paths of the queried constraint \
+                    evaluations do not resolve to the constraint commitment in the proof",
+                causes: &[
+                    "the constraint commitment was altered or swapped with another commitment \
+                     during packaging",
+                    "the proof was generated with a different hash function than it is verified \
+                     with",
+                    "the queried constraint evaluations were opened at different positions than \
+                     the verifier drew",
+                ],
+                fields: &[
+                    "verifier_channel.constraint_root",
+                    "verifier_channel.constraint_queries",
+                ],
+            },
+            Self::FriCommitmentMismatch => Explanation {
+                kind: *self,
+                description: "the queried evaluations of a FRI layer, or the FRI remainder, do \
+                    not resolve to the commitment made by the prover",
+                causes: &[
+                    "the evaluations or the Merkle proofs of a FRI layer were altered or \
+                     truncated during packaging",
+                    "the folding factor or the number of partitions of the proof options differs \
+                     from the options the proof was generated with",
+                ],
+                fields: &[
+                    "verifier_channel.fri_roots",
+                    "verifier_channel.fri_layer_proofs",
+                    "verifier_channel.fri_layer_queries",
+                    "verifier_channel.fri_remainder",
+                ],
+            },
+            Self::ProofOfWork => Explanation {
+                kind: *self,
+                description: "the proof-of-work nonce hashed with the state of the public coin \
+                    does not have as many leading zeros as the grinding factor requires",
+                causes: &[
+                    "the nonce was altered during packaging",
+                    "the proof is verified with a larger grinding factor than it was generated \
+                     with",
+                    "the public coin was seeded differently than by the prover",
+                ],
+                fields: &["verifier_channel.pow_nonce", "context"],
+            },
+            Self::LowDegree => Explanation {
+                kind: *self,
+                description: "the evaluations of the DEEP composition polynomial computed by \
+                    the verifier are not evaluations of a polynomial of the expected degree",
+                causes: &[
+                    "the queried trace states or constraint evaluations are inconsistent with \
+                     the out-of-domain frame",
+                    "the trace length or the blowup factor of the proof context differs from the \
+                     values the proof was generated with",
+                    "the FRI layers or the FRI remainder were altered during packaging",
+                ],
+                fields: &[
+                    "context",
+                    "verifier_channel.fri_layer_queries",
+                    "verifier_channel.fri_remainder",
+                ],
+            },
+        }
+    }
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QueryPositions => write!(f, "bad query positions"),
+            Self::OodMismatch => write!(f, "out-of-domain mismatch"),
+            Self::TraceCommitmentMismatch => write!(f, "trace commitment mismatch"),
+            Self::ConstraintCommitmentMismatch => write!(f, "constraint commitment mismatch"),
+            Self::FriCommitmentMismatch => write!(f, "FRI commitment mismatch"),
+            Self::ProofOfWork => write!(f, "proof-of-work mismatch"),
+            Self::LowDegree => write!(f, "low-degree test failure"),
+        }
+    }
+}
+
+// EXPLANATION
+// ================================================================================================
+
+/// Plain-language explanation of a verification failure, as printed in explain mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    /// Class of the failure.
+    pub kind: FailureKind,
+    /// Description of the check which failed.
+    pub description: &'static str,
+    /// Most common causes of the failure, from the most to the least likely.
+    pub causes: &'static [&'static str],
+    /// Fields of the packaged proof inputs involved in the failed check.
+    pub fields: &'static [&'static str],
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.description)?;
+        write!(f, "\ncommon causes:")?;
+        for cause in self.causes {
+            write!(f, "\n  - {}", cause)?;
+        }
+        write!(f, "\ninput fields involved: {}", self.fields.join(", "))
+    }
+}
+
+/// Returns the explanation of the verification failure reported by the provided error, if it
+/// reports one.
+///
+/// The causes of the error are searched from the root cause outwards, and failures committed by a
+/// guest are searched frame by frame, so that the innermost failed check is explained.
+pub fn explain(error: &Error) -> Option<Explanation> {
+    let mut messages = Vec::new();
+    for cause in error.chain() {
+        match cause.downcast_ref::<ErrorChain>() {
+            Some(chain) => messages.extend(chain.frames().iter().map(|f| f.message.clone())),
+            None => messages.push(cause.to_string()),
+        }
+    }
+    messages
+        .iter()
+        .rev()
+        .find_map(|message| FailureKind::classify(message))
+        .map(|kind| kind.explanation())
+}
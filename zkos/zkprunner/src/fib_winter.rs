@@ -1,70 +1,845 @@
+use crate::budget::{InputBudget, InputUsage};
+use crate::cache::{CacheKey, ProofCache};
+use crate::cost::{record_cost, CostRecorder};
+use crate::journal::{
+    decode_journal, decode_phase_journal, decode_profiled_journal, decode_transcript_journal,
+    decode_transcript_kat_journal,
+};
+use crate::receipt::{run_prover, ImageIdAllowList};
 use anyhow::{anyhow, Context, Result};
-use log::{debug, info};
-use methods::{FIB_VERIFY_ID, FIB_VERIFY_PATH};
+use log::{debug, info, warn};
+use methods::{
+    FIB_PHASE_ID, FIB_PHASE_PATH, FIB_TRANSCRIPT_ID, FIB_TRANSCRIPT_PATH, FIB_VERIFY_ID,
+    FIB_VERIFY_PATH, TRANSCRIPT_KAT_ID, TRANSCRIPT_KAT_PATH,
+};
 use risc0_zkvm::{host::Prover, serde::to_vec};
+use rkyv::ser::serializers::AllocSerializer;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use utils::aux_data::seed_pub_inputs;
+use utils::aux_input::{aux_input_heap_size, encode_aux_input, AuxInputCompression};
+use utils::context::{DomainTag, VerifierContext};
+use utils::estimate::{estimate, TraceShape};
 use utils::fib::example::{Example, FibExample};
-use utils::fib::fib_air::FibAir;
-use utils::inputs::{FibAirInput, FibRiscInput};
-use winter_air::{Air, ProofOptions};
-use winter_crypto::hashers::{DefaultSha2, Sha2_256};
+use utils::fib::fib_air::{FibAir, TRACE_WIDTH};
+use utils::fib::vk::FibVerificationKey;
+use utils::hasher::GuestHasher;
+use utils::inputs::{trace_segment_infos, FibAirInput, FibRiscInput};
+use utils::journal;
+use utils::kat::{TranscriptKat, KAT_SEQUENCE_LENGTHS, KAT_SUITE};
+use utils::mutation::ProofMutation;
+use utils::phase::{check_phase_journals, PhaseState, VerificationPhase};
+use utils::policy::ProofPolicy;
+use utils::poseidon::Poseidon64_256;
+use utils::profiler;
+use utils::release::{ReleaseFixture, RELEASE_CASE};
+use utils::transcript::{replay_transcript, TranscriptState};
+use winter_air::{Air, FieldExtension, HashFunction, ProofOptions};
+use winter_crypto::hashers::{
+    Blake3_256, DefaultSha2, DefaultSha384, DefaultSha512, Keccak256, Sha2_256, Sha2_384, Sha2_512,
+};
 use winter_math::fields::f64_risc0::{BaseElement, DefaultNativeMul, INV_NONDET, INV_NONDET_QUAD};
 use winter_math::fields::QuadExtension;
 use winter_verifier::{Serializable, StarkProof, VerifierChannel};
 
 type B = BaseElement;
 type E = QuadExtension<B>;
-type H = Sha2_256<B, DefaultSha2>;
+type H256 = Sha2_256<B, DefaultSha2>;
+type H384 = Sha2_384<B, DefaultSha384>;
+type H512 = Sha2_512<B, DefaultSha512>;
+type HBlake3 = Blake3_256<B>;
+type HKeccak = Keccak256<B>;
+type HPoseidon = Poseidon64_256<B>;
 
-pub fn fib_winter(proof_options: ProofOptions) -> Result<()> {
+/// Factor by which the cycle count committed by the fib verifier guest may exceed the estimated
+/// cycle count before the verification is reported as abnormally expensive.
+const ABNORMAL_CYCLES_FACTOR: u64 = 4;
+
+/// Verifies the fib proofs in the fib verifier guest under the provided policy, and appends the
+/// cost of the prove run to the cost history at the specified path. The packaged inputs are
+/// checked against the provided budget before the guest is launched.
+///
+/// If a proof cache is provided, and it holds a receipt of the guest for the same packaged
+/// inputs, the cached receipt is verified instead of proving the guest again; such runs are not
+/// appended to the cost history. Otherwise, the receipt of the run is added to the cache once it
+/// is verified.
+///
+/// If an aux data commitment is provided, it is bound to both packaged proofs, and the journal is
+/// checked to surface it for both statements.
+pub fn fib_winter(
+    proof_options: ProofOptions,
+    policy: &ProofPolicy,
+    budget: &InputBudget,
+    compression: AuxInputCompression,
+    flamegraph: Option<&Path>,
+    cost_history: &Path,
+    cache: Option<&ProofCache>,
+    aux_data_commitment: Option<[u8; 32]>,
+) -> Result<()> {
     info!("Generating winter fib proofs...");
+    let mut cost = CostRecorder::new("fib_verify");
 
     // Initialize Risc0 prover
     let mut prover = Prover::new(&std::fs::read(FIB_VERIFY_PATH).unwrap(), FIB_VERIFY_ID).unwrap();
+    let FibPackage {
+        statement_digest,
+        options_digest,
+        input_bytes,
+        input_digest,
+        estimated_cycles,
+        usage,
+        ..
+    } = cost.phase("package", || {
+        add_fib_proofs(
+            &mut prover,
+            proof_options,
+            Some(policy),
+            compression,
+            None,
+            aux_data_commitment,
+        )
+    })?;
+    budget.check(&usage)?;
+
+    // reuse the receipt of a previous run on the same inputs if there is one
+    let cache_key = CacheKey::new(FIB_VERIFY_ID, &input_digest);
+    let cached_receipt = match cache {
+        Some(cache) => cache.get(&cache_key)?,
+        None => None,
+    };
+    let is_cached = cached_receipt.is_some();
+
+    // Generate a proof of Winterfell verification using Risc0 prover
+    let receipt = match cached_receipt {
+        Some(receipt) => {
+            info!("Reusing cached receipt {}", cache_key.to_hex());
+            receipt
+        }
+        None => {
+            info!("Running risc0 prover...");
+            cost.phase("prove", || run_prover(&mut prover))?
+        }
+    };
+    info!("Verifying receipt of the two fib proofs in risc0");
+    let image_ids = fib_verify_image_ids();
+    let image_id = cost
+        .phase("verify_receipt", || image_ids.verify(&receipt))
+        .context("failed to verify receipt of the fib proofs")?;
+    debug!("Receipt verified against guest image {}", image_id.label);
+    if let (Some(cache), false) = (cache, is_cached) {
+        cache.put(&cache_key, &receipt)?;
+    }
+
+    // guests built with cycle attribution commit the folded stacks after the journal
+    let (journal, folded) = match decode_profiled_journal(&receipt) {
+        Ok((journal, folded)) => (journal, Some(folded)),
+        Err(_) if flamegraph.is_none() => (decode_journal(&receipt)?, None),
+        Err(e) => return Err(e),
+    };
+    if let (Some(path), Some(folded)) = (flamegraph, &folded) {
+        let mut file =
+            BufWriter::new(File::create(path).context("failed to create flamegraph file")?);
+        profiler::write_folded(&mut file, folded).context("failed to write flamegraph file")?;
+        info!("Wrote guest cycle attribution to {}", path.display());
+    }
+
+    if let Some(cycles) = journal.cycle_count {
+        info!(
+            "Guest executed {} cycles (estimated {} cycles)",
+            cycles, estimated_cycles
+        );
+        if cycles > estimated_cycles.saturating_mul(ABNORMAL_CYCLES_FACTOR) {
+            warn!(
+                "guest executed more than {} times the estimated cycles; the packaged inputs may be abnormal",
+                ABNORMAL_CYCLES_FACTOR
+            );
+        }
+    }
+
+    // the cost of a cached run is not the cost of proving the guest
+    if !is_cached {
+        let report = cost.finish(
+            &receipt,
+            input_bytes,
+            journal.cycle_count,
+            folded.as_deref(),
+        )?;
+        record_cost(&report, cost_history)?;
+    }
+
+    if let Some(failure) = &journal.heap_estimate_exceeded {
+        return Err(anyhow!("guest skipped the verification: {}", failure));
+    }
+    if let Some(error) = journal.error {
+        debug!(
+            "Guest failed with error of kind {}",
+            error.root_cause().kind
+        );
+        return Err(anyhow::Error::new(error).context("guest failed to verify the fib proofs"));
+    }
+    if journal.statement_digest != statement_digest {
+        return Err(anyhow!("journal is bound to a different statement"));
+    }
+    if journal.options_digest != options_digest {
+        return Err(anyhow!("journal is bound to different proof options"));
+    }
+    if journal.policy_digest != Some(policy.digest::<DefaultSha2>()) {
+        return Err(anyhow!("journal is bound to a different proof policy"));
+    }
+    if journal.aux_data_commitments != vec![aux_data_commitment; 2] {
+        return Err(anyhow!("journal surfaces different aux data commitments"));
+    }
+    if !journal.result {
+        return Err(anyhow!("guest failed to verify the fib proofs"));
+    }
+
+    Ok(())
+}
+
+/// Replays the Fiat–Shamir transcripts of the packaged fib proofs in the transcript-only guest,
+/// and checks that the final coin seeds match the seeds computed on the host from the original
+/// proofs. This is much cheaper than [fib_winter], and detects bugs in the packaging of the
+/// proofs before running the full verification.
+pub fn fib_transcript(
+    proof_options: ProofOptions,
+    budget: &InputBudget,
+    compression: AuxInputCompression,
+) -> Result<()> {
+    // the transcript-only guest reads proofs with 32-byte digests only
+    if proof_options.hash_fn() != HashFunction::Sha2_256 {
+        return Err(anyhow!(
+            "transcripts of fib proofs with hash function {:?} cannot be replayed in the guest",
+            proof_options.hash_fn()
+        ));
+    }
+    info!("Generating winter fib proofs...");
+
+    let mut prover = Prover::new(
+        &std::fs::read(FIB_TRANSCRIPT_PATH).unwrap(),
+        FIB_TRANSCRIPT_ID,
+    )
+    .unwrap();
+    // the transcript-only guest does not read a policy header
+    let FibPackage {
+        statement_digest,
+        transcripts,
+        usage,
+        ..
+    } = add_fib_proofs(&mut prover, proof_options, None, compression, None, None)?;
+    budget.check(&usage)?;
+
+    info!("Running risc0 prover on the transcript-only guest...");
+    let receipt = run_prover(&mut prover)?;
+    receipt
+        .verify(FIB_TRANSCRIPT_ID)
+        .context("failed to verify receipt of the fib transcripts")?;
+
+    let expected_seeds: Vec<[u8; 32]> = transcripts.iter().map(|state| state.seed).collect();
+    let journal = decode_transcript_journal(&receipt)?;
+    if let Some(error) = journal.error {
+        return Err(anyhow::Error::new(error).context("guest failed to replay the transcripts"));
+    }
+    if journal.statement_digest != statement_digest {
+        return Err(anyhow!("journal is bound to a different statement"));
+    }
+    if journal.final_seeds.len() != expected_seeds.len() {
+        return Err(anyhow!(
+            "expected {} transcripts, but the guest replayed {}",
+            expected_seeds.len(),
+            journal.final_seeds.len()
+        ));
+    }
+    for (i, (seed, expected)) in journal.final_seeds.iter().zip(&expected_seeds).enumerate() {
+        if seed != expected {
+            return Err(anyhow!(
+                "transcript of proof #{} diverged: expected final seed {}, but the guest computed {}",
+                i + 1,
+                hex::encode(expected),
+                hex::encode(seed)
+            ));
+        }
+    }
+    info!("Transcripts of the packaged fib proofs match the original proofs");
+
+    Ok(())
+}
+
+/// Verifies the fib proofs in the phase verifier guest under the provided policy, running every
+/// phase of the verification (see [utils::phase]) as a separate guest execution, and stitches
+/// the receipts of all phases.
+///
+/// Every execution is resumed from the states committed to the journal of the previous one. The
+/// fib proofs are generated deterministically, and thus, every execution verifies the same
+/// proofs; this is checked when the receipts are stitched, since all journals must be bound to
+/// the same statement, proof options, and policy.
+pub fn fib_phases(
+    proof_options: ProofOptions,
+    policy: &ProofPolicy,
+    budget: &InputBudget,
+    compression: AuxInputCompression,
+) -> Result<()> {
+    // the phase verifier guest reads proofs with 32-byte digests only
+    if proof_options.hash_fn() != HashFunction::Sha2_256 {
+        return Err(anyhow!(
+            "fib proofs with hash function {:?} cannot be verified in phases in the guest",
+            proof_options.hash_fn()
+        ));
+    }
+
+    let mut journals = Vec::with_capacity(VerificationPhase::ALL.len());
+    let mut prev_states: Vec<PhaseState> = Vec::new();
+    let mut statement_digest = [0; 32];
+    let mut options_digest = [0; 32];
+    for phase in VerificationPhase::ALL {
+        info!("Running the {} phase in the phase verifier guest...", phase);
+        let mut prover =
+            Prover::new(&std::fs::read(FIB_PHASE_PATH).unwrap(), FIB_PHASE_ID).unwrap();
+        let package = add_fib_proofs(
+            &mut prover,
+            proof_options.clone(),
+            Some(policy),
+            compression,
+            None,
+            None,
+        )?;
+        budget.check(&package.usage)?;
+        statement_digest = package.statement_digest;
+        options_digest = package.options_digest;
+
+        // the phase and the states it is resumed from are read after the AIR inputs
+        let phase_input = to_vec(&phase).context("failed to to_vec")?;
+        prover
+            .add_input(phase_input.as_slice())
+            .context("failed to add phase to prover")?;
+        let states_input = to_vec(&prev_states).context("failed to to_vec")?;
+        prover
+            .add_input(states_input.as_slice())
+            .context("failed to add phase states to prover")?;
+
+        let receipt = run_prover(&mut prover)?;
+        receipt
+            .verify(FIB_PHASE_ID)
+            .with_context(|| format!("failed to verify receipt of the {} phase", phase))?;
+        let journal = decode_phase_journal(&receipt)?;
+        if let Some(error) = &journal.error {
+            return Err(anyhow::Error::new(error.clone())
+                .context(format!("guest failed to run the {} phase", phase)));
+        }
+        prev_states = journal.states.clone();
+        journals.push(journal);
+    }
+
+    check_phase_journals::<DefaultSha2>(
+        &journals,
+        &statement_digest,
+        &options_digest,
+        &policy.digest::<DefaultSha2>(),
+    )
+    .map_err(|e| anyhow!("failed to stitch the receipts of the phases: {}", e))?;
+    info!(
+        "Stitched the receipts of all {} phases of the verification of the fib proofs",
+        journals.len()
+    );
+
+    Ok(())
+}
+
+/// Checks the Fiat–Shamir transcripts of the fixed fib proofs of every case of the KAT suite
+/// against the known answers in the specified directory, both on the host and in the transcript
+/// KAT guest. This pins the values drawn from the public coin, so that a dependency upgrade
+/// which changes them is detected.
+pub fn fib_transcript_kats(
+    kat_dir: &Path,
+    budget: &InputBudget,
+    compression: AuxInputCompression,
+) -> Result<()> {
+    for case in KAT_SUITE.iter() {
+        let kats = KAT_SEQUENCE_LENGTHS
+            .iter()
+            .map(|&sequence_length| read_kat(kat_dir, &case.kat_name(sequence_length)))
+            .collect::<Result<Vec<_>>>()?;
+        info!("Generating winter fib proofs of KAT case {}...", case.name);
+
+        let mut prover = Prover::new(
+            &std::fs::read(TRANSCRIPT_KAT_PATH).unwrap(),
+            TRANSCRIPT_KAT_ID,
+        )
+        .unwrap();
+        // the transcript KAT guest does not read a policy header
+        let FibPackage {
+            statement_digest,
+            transcripts,
+            usage,
+            ..
+        } = add_fib_proofs(
+            &mut prover,
+            case.proof_options(),
+            None,
+            compression,
+            None,
+            None,
+        )?;
+        for (kat, transcript) in kats.iter().zip(&transcripts) {
+            kat.check(transcript)
+                .map_err(|e| anyhow!("host transcript does not match: {}", e))?;
+        }
+        debug!("Host transcripts of KAT case {} match", case.name);
+
+        let kats_input = to_vec(&kats).context("failed to to_vec")?;
+        prover
+            .add_input(kats_input.as_slice())
+            .context("failed to add known answers to prover")?;
+        budget.check(&InputUsage {
+            input_bytes: usage.input_bytes + kats_input.len() * 4,
+            ..usage
+        })?;
+
+        info!("Running risc0 prover on the transcript KAT guest...");
+        let receipt = run_prover(&mut prover)?;
+        receipt
+            .verify(TRANSCRIPT_KAT_ID)
+            .context("failed to verify receipt of the transcript KATs")?;
+
+        let journal = decode_transcript_kat_journal(&receipt)?;
+        if let Some(error) = journal.error {
+            return Err(anyhow::Error::new(error).context(format!(
+                "guest transcripts of KAT case {} do not match",
+                case.name
+            )));
+        }
+        if journal.statement_digest != statement_digest {
+            return Err(anyhow!("journal is bound to a different statement"));
+        }
+        if journal.kats_digest != journal::kats_digest::<DefaultSha2>(&kats) {
+            return Err(anyhow!("journal is bound to different known answers"));
+        }
+        if !journal.result {
+            return Err(anyhow!(
+                "guest transcripts of KAT case {} do not match",
+                case.name
+            ));
+        }
+        info!("Transcripts of KAT case {} match in the guest", case.name);
+    }
+
+    Ok(())
+}
+
+/// Reads the known-answer transcript with the specified name from the specified directory.
+fn read_kat(kat_dir: &Path, name: &str) -> Result<TranscriptKat> {
+    let path = kat_dir.join(format!("{}.kat", name));
+    let kat: TranscriptKat = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?
+        .parse()
+        .map_err(|e| anyhow!("failed to parse {}: {}", path.display(), e))?;
+    if kat.name != name {
+        return Err(anyhow!(
+            "{} holds known answers '{}'",
+            path.display(),
+            kat.name
+        ));
+    }
+    Ok(kat)
+}
+
+/// Runs the fib verifier guest against packaged fib proofs tampered with by every mutation of
+/// the standard set, and checks that the guest rejects all of them. A proof is rejected if the
+/// guest either fails or commits a journal in which verification failed.
+pub fn fib_rejection(
+    proof_options: ProofOptions,
+    policy: &ProofPolicy,
+    budget: &InputBudget,
+    compression: AuxInputCompression,
+) -> Result<()> {
+    for mutation in ProofMutation::standard_set() {
+        info!(
+            "Running the fib verifier guest on a proof with mutation: {}",
+            mutation
+        );
+        let mut prover =
+            Prover::new(&std::fs::read(FIB_VERIFY_PATH).unwrap(), FIB_VERIFY_ID).unwrap();
+        let package = add_fib_proofs(
+            &mut prover,
+            proof_options.clone(),
+            Some(policy),
+            compression,
+            Some(&mutation),
+            None,
+        )?;
+        budget.check(&package.usage)?;
+
+        let accepted = match run_prover(&mut prover) {
+            Ok(receipt) => {
+                let journal = decode_journal(&receipt)?;
+                if let Some(error) = &journal.error {
+                    debug!("Guest rejected the proof: {}", error);
+                }
+                journal.result
+            }
+            Err(e) => {
+                debug!("Guest failed: {}", e);
+                false
+            }
+        };
+        if accepted {
+            return Err(anyhow!(
+                "guest accepted a fib proof with mutation: {}",
+                mutation
+            ));
+        }
+    }
+    info!("Guest rejected fib proofs with all mutations");
+
+    Ok(())
+}
+
+/// Verifies the fib proofs of the release case in the fib verifier guest, and checks the run of the
+/// guest against the golden release fixture at the specified path (see [utils::release]).
+///
+/// The fib proofs are generated and packaged anew, and the guest is always proven, so that any
+/// change of the guest, the packaging logic, or the dependencies which affects the inputs or the
+/// journal of the guest is detected. When ZKOS_WRITE_RELEASE_FIXTURE is set, the fixture is
+/// rewritten from the run instead; this should only be done when the change is intended, together
+/// with a bump of VERIFIER_VERSION if the journal changed.
+pub fn fib_validate_release(fixture_path: &Path, budget: &InputBudget) -> Result<()> {
+    info!("Generating winter fib proofs of the release case...");
+    let policy = ProofPolicy::new();
+    let mut prover = Prover::new(&std::fs::read(FIB_VERIFY_PATH).unwrap(), FIB_VERIFY_ID).unwrap();
+    let package = add_fib_proofs(
+        &mut prover,
+        RELEASE_CASE.proof_options(),
+        Some(&policy),
+        AuxInputCompression::None,
+        None,
+        None,
+    )?;
+    budget.check(&package.usage)?;
 
-    let (pub_inputs_1024, fib_air_input_1024) =
-        generate_winter_fib_proof(proof_options.clone(), 1024)?;
-    let (pub_inputs_2048, fib_air_input_2048) =
-        generate_winter_fib_proof(proof_options.clone(), 2048)?;
+    info!("Running risc0 prover on the release fixture...");
+    let receipt = run_prover(&mut prover)?;
+    receipt
+        .verify(FIB_VERIFY_ID)
+        .context("failed to verify receipt of the release fixture")?;
+    let journal = decode_journal(&receipt)?;
+    if let Some(error) = journal.error {
+        return Err(anyhow::Error::new(error).context("guest failed to verify the release case"));
+    }
+    if journal.statement_digest != package.statement_digest || !journal.result {
+        return Err(anyhow!(
+            "guest failed to verify the fib proofs of the release case"
+        ));
+    }
+
+    let actual = ReleaseFixture {
+        verifier_version: journal.verifier_version,
+        image_id: FIB_VERIFY_ID.to_vec(),
+        aux_input: package.aux_input,
+        inputs: package.inputs,
+        journal: receipt
+            .get_journal_vec()
+            .context("failed to read journal")?,
+    };
+    if std::env::var_os("ZKOS_WRITE_RELEASE_FIXTURE").is_some() {
+        if let Some(dir) = fixture_path.parent() {
+            std::fs::create_dir_all(dir).context("failed to create release fixture directory")?;
+        }
+        std::fs::write(fixture_path, actual.to_text()).with_context(|| {
+            format!("failed to write release fixture {}", fixture_path.display())
+        })?;
+        info!("Recorded the release fixture in {}", fixture_path.display());
+        return Ok(());
+    }
+
+    let expected: ReleaseFixture = std::fs::read_to_string(fixture_path)
+        .with_context(|| {
+            format!(
+                "failed to read release fixture {}; run with ZKOS_WRITE_RELEASE_FIXTURE set to record it",
+                fixture_path.display()
+            )
+        })?
+        .parse()
+        .map_err(|e| anyhow!("failed to parse release fixture: {}", e))?;
+    expected
+        .check(&actual)
+        .map_err(|e| anyhow!("release validation failed: {}", e))?;
+    info!("Run of the fib verifier guest matches the release fixture");
+
+    Ok(())
+}
+
+/// Values computed on the host while packaging the fib proofs for a guest.
+struct FibPackage {
+    /// Statement digest the guest is expected to commit to.
+    statement_digest: [u8; 32],
+    /// Options digest the guest is expected to commit to.
+    options_digest: [u8; 32],
+    /// Transcripts of the proofs replayed on the host, in the order of the proofs.
+    transcripts: Vec<TranscriptState>,
+    /// Total size of the inputs passed to the guest, in bytes.
+    input_bytes: usize,
+    /// Digest of the inputs passed to the guest, in the order in which they were added.
+    input_digest: [u8; 32],
+    /// Packaged proofs passed to the guest as its aux input.
+    aux_input: Vec<u8>,
+    /// Regular inputs passed to the guest, in the order in which they were added.
+    inputs: Vec<Vec<u32>>,
+    /// Estimated number of cycles the guest spends verifying the packaged proofs.
+    estimated_cycles: u64,
+    /// Resources the guest needs for the packaged inputs.
+    usage: InputUsage,
+}
+
+/// Generates the fib proofs, packages them as inputs of the provided guest prover, and returns
+/// the values the outputs of the guest are checked against.
+///
+/// If a policy is provided, it is passed to the guest as the header of the inputs. If a mutation
+/// is provided, it is applied to the first packaged proof. If an aux data commitment is provided,
+/// it is bound to both proofs.
+///
+/// # Errors
+/// Returns an error if the fib verifier guest does not support the hash function of the provided
+/// proof options, if the options violate the policy, or if the proofs cannot be generated or
+/// packaged.
+fn add_fib_proofs(
+    prover: &mut Prover,
+    proof_options: ProofOptions,
+    policy: Option<&ProofPolicy>,
+    compression: AuxInputCompression,
+    mutation: Option<&ProofMutation>,
+    aux_data_commitment: Option<[u8; 32]>,
+) -> Result<FibPackage> {
+    // the out-of-domain evaluations of the packaged proofs are in the quadratic extension only
+    if proof_options.field_extension() != FieldExtension::Quadratic {
+        return Err(anyhow!(
+            "fib proofs with field extension {:?} cannot be verified in the guest",
+            proof_options.field_extension()
+        ));
+    }
+    match proof_options.hash_fn() {
+        HashFunction::Sha2_256 => package_fib_proofs::<H256>(
+            prover,
+            proof_options,
+            policy,
+            compression,
+            mutation,
+            aux_data_commitment,
+        ),
+        HashFunction::Sha2_384 => package_fib_proofs::<H384>(
+            prover,
+            proof_options,
+            policy,
+            compression,
+            mutation,
+            aux_data_commitment,
+        ),
+        HashFunction::Sha2_512 => package_fib_proofs::<H512>(
+            prover,
+            proof_options,
+            policy,
+            compression,
+            mutation,
+            aux_data_commitment,
+        ),
+        HashFunction::Blake3_256 => package_fib_proofs::<HBlake3>(
+            prover,
+            proof_options,
+            policy,
+            compression,
+            mutation,
+            aux_data_commitment,
+        ),
+        HashFunction::Keccak256 => package_fib_proofs::<HKeccak>(
+            prover,
+            proof_options,
+            policy,
+            compression,
+            mutation,
+            aux_data_commitment,
+        ),
+        HashFunction::Poseidon64_256 => package_fib_proofs::<HPoseidon>(
+            prover,
+            proof_options,
+            policy,
+            compression,
+            mutation,
+            aux_data_commitment,
+        ),
+        hash_fn => Err(anyhow!(
+            "fib proofs with hash function {:?} cannot be verified in the guest",
+            hash_fn
+        )),
+    }
+}
+
+/// Packages the fib proofs with the specified hasher; the layout of the packaged proofs depends
+/// on the digest size of the hasher.
+fn package_fib_proofs<H>(
+    prover: &mut Prover,
+    proof_options: ProofOptions,
+    policy: Option<&ProofPolicy>,
+    compression: AuxInputCompression,
+    mutation: Option<&ProofMutation>,
+    aux_data_commitment: Option<[u8; 32]>,
+) -> Result<FibPackage>
+where
+    H: GuestHasher<BaseField = B>,
+    [FibRiscInput<E, H>; 2]: rkyv::Serialize<AllocSerializer<256>>,
+{
+    // proofs which the guest would reject under the policy are never generated
+    if let Some(policy) = policy {
+        policy
+            .check(&proof_options)
+            .map_err(|e| anyhow!("proof options violate the proof policy '{}': {}", policy, e))?;
+    }
+
+    let (mut pub_inputs_1024, fib_air_input_1024, transcript_1024) =
+        generate_winter_fib_proof::<H>(proof_options.clone(), 1024, aux_data_commitment)?;
+    if let Some(mutation) = mutation {
+        mutation
+            .apply(&mut pub_inputs_1024)
+            .map_err(|e| anyhow!("failed to apply mutation {}: {}", mutation, e))?;
+    }
+    let (pub_inputs_2048, fib_air_input_2048, transcript_2048) =
+        generate_winter_fib_proof::<H>(proof_options, 2048, aux_data_commitment)?;
+
+    // compute the digests the guest is expected to commit to
+    let seed_pub_inputs_1024 = seed_pub_inputs(
+        &pub_inputs_1024.result.to_bytes(),
+        pub_inputs_1024.aux_data_commitment.as_ref(),
+    );
+    let seed_pub_inputs_2048 = seed_pub_inputs(
+        &pub_inputs_2048.result.to_bytes(),
+        pub_inputs_2048.aux_data_commitment.as_ref(),
+    );
+    let statement_digest = journal::statement_digest::<DefaultSha2>(&[
+        pub_inputs_1024.context.as_slice(),
+        seed_pub_inputs_1024.as_slice(),
+        journal::trace_info_bytes(&fib_air_input_1024.trace_info).as_slice(),
+        pub_inputs_2048.context.as_slice(),
+        seed_pub_inputs_2048.as_slice(),
+        journal::trace_info_bytes(&fib_air_input_2048.trace_info).as_slice(),
+    ]);
+    let options_digest = journal::options_digest::<DefaultSha2>(&[
+        fib_air_input_1024.proof_options.clone(),
+        fib_air_input_2048.proof_options.clone(),
+    ]);
 
     let pub_inputs_aux = rkyv::to_bytes::<_, 256>(&[pub_inputs_1024, pub_inputs_2048]).unwrap();
-    prover.add_input_u8_slice_aux(&pub_inputs_aux);
+    let aux_input = encode_aux_input(&pub_inputs_aux, compression);
+    debug!(
+        "Guest aux input: {} bytes ({} bytes before {:?} compression)",
+        aux_input.len(),
+        pub_inputs_aux.len(),
+        compression
+    );
+    prover.add_input_u8_slice_aux(&aux_input);
+
+    // the policy header is read by the guest before any other input
+    let policy_header = match policy {
+        Some(policy) => {
+            let policy_header = to_vec(&policy.to_bytes()).context("failed to to_vec")?;
+            prover
+                .add_input(policy_header.as_slice())
+                .context("failed to add policy header to prover")?;
+            policy_header
+        }
+        None => Vec::new(),
+    };
 
+    let air_input_1024 = to_vec(&fib_air_input_1024).context("failed to_vec")?;
     prover
-        .add_input(
-            to_vec(&fib_air_input_1024)
-                .context("failed to_vec")?
-                .as_slice(),
-        )
+        .add_input(air_input_1024.as_slice())
         .context("failed to add fib_air_input_1024 to prover")?;
 
+    let air_input_2048 = to_vec(&fib_air_input_2048).context("failed to_vec")?;
     prover
-        .add_input(
-            to_vec(&fib_air_input_2048)
-                .context("failed to_vec")?
-                .as_slice(),
-        )
+        .add_input(air_input_2048.as_slice())
         .context("failed to add pub_inputs_2048 to prover")?;
 
-    // Generate a proof of Winterfell verification using Risc0 prover
-    info!("Running risc0 prover...");
-    let receipt = prover.run().unwrap();
-    info!("Verifying receipt of the two fib proofs in risc0");
-    receipt.verify(FIB_VERIFY_ID).unwrap();
+    // every input is prefixed with its length, so that inputs cannot be shifted between each other
+    let mut input_hasher = Sha256::new();
+    input_hasher.update((aux_input.len() as u64).to_le_bytes());
+    input_hasher.update(&aux_input);
+    for input in [&policy_header, &air_input_1024, &air_input_2048] {
+        input_hasher.update((input.len() as u64).to_le_bytes());
+        for word in input.iter() {
+            input_hasher.update(word.to_le_bytes());
+        }
+    }
 
-    Ok(())
+    let estimates = [&fib_air_input_1024, &fib_air_input_2048]
+        .iter()
+        .map(|air_input| {
+            let trace_info = &air_input.trace_info;
+            let shape = TraceShape::new(trace_info.length(), trace_info.width());
+            estimate(&shape, &air_input.proof_options)
+        })
+        .collect::<Vec<_>>();
+    let input_bytes =
+        aux_input.len() + (policy_header.len() + air_input_1024.len() + air_input_2048.len()) * 4;
+
+    // the guest reserves heap for the same estimates, and thus, the usage is exact
+    let usage = InputUsage {
+        input_bytes,
+        compression,
+        aux_input_heap_bytes: aux_input_heap_size(&aux_input)
+            .map_err(|e| anyhow!("failed to read the aux input header: {}", e))?,
+        verification_heap_bytes: estimates.iter().map(|estimate| estimate.heap_size).sum(),
+        num_proofs: estimates.len(),
+    };
+
+    Ok(FibPackage {
+        statement_digest,
+        options_digest,
+        transcripts: vec![transcript_1024, transcript_2048],
+        input_bytes,
+        input_digest: input_hasher.finalize().into(),
+        aux_input,
+        inputs: vec![policy_header, air_input_1024, air_input_2048],
+        estimated_cycles: estimates.iter().map(|estimate| estimate.guest_cycles).sum(),
+        usage,
+    })
+}
+
+/// Returns the image IDs of the fib verifier guest whose receipts are accepted. When the guest is
+/// updated, the image ID of the previous version can be added here with a deprecation date, so
+/// that receipts produced by it keep verifying until then.
+pub fn fib_verify_image_ids() -> ImageIdAllowList {
+    ImageIdAllowList::new().with_image_id("fib_verify", FIB_VERIFY_ID)
 }
 
-fn generate_winter_fib_proof(
+fn generate_winter_fib_proof<H: GuestHasher<BaseField = B>>(
     proof_options: ProofOptions,
     n: usize,
-) -> Result<(FibRiscInput<E, H>, FibAirInput)> {
-    // Generate a Fibonacci proof using Winterfell prover
-    let e = FibExample::new(n, proof_options);
-    let proof = e.prove();
+    aux_data_commitment: Option<[u8; 32]>,
+) -> Result<(FibRiscInput<E, H>, FibAirInput, TranscriptState)> {
+    let estimate = estimate(&TraceShape::new(n, TRACE_WIDTH), &proof_options);
+    debug!(
+        "Estimated proof size: {} bytes, guest input size: {} bytes, guest cycles: {}",
+        estimate.proof_size, estimate.input_size, estimate.guest_cycles
+    );
+    debug!("Estimated guest heap: {} bytes", estimate.heap_size);
+
+    // Generate a Fibonacci proof using Winterfell prover; the hasher is passed explicitly, since
+    // the generic prover cannot instantiate all hash functions the guest supports
+    let mut e = FibExample::new(n, proof_options);
+    if let Some(commitment) = aux_data_commitment {
+        e = e.with_aux_data_commitment(commitment);
+    }
+    let proof = e.prove_with_hasher::<H>();
     debug!("--------------------------------");
+    debug!("Proof size: {} bytes", proof.to_bytes().len());
     debug!("Trace length: {}", proof.context.trace_length());
     debug!("Trace queries length: {}", proof.trace_queries.len());
-    verify_with_winter(proof.clone(), e.result.clone())?;
+    let coin_pub_inputs = seed_pub_inputs(&e.result.to_bytes(), aux_data_commitment.as_ref());
+    verify_with_winter::<H>(proof.clone(), e.result.clone(), aux_data_commitment)?;
+
+    // make sure the guest is able to instantiate the AIR from its embedded verification key
+    FibVerificationKey::build::<DefaultNativeMul>()
+        .check_trace_info(&proof.get_trace_info(), proof.options())
+        .map_err(|e| anyhow!("fib proof is not covered by the verification key: {}", e))?;
 
     // Expose verification data as public inputs to Risc0 prover
     let air = FibAir::new(proof.get_trace_info(), e.result, proof.options().clone());
@@ -74,12 +849,29 @@ fn generate_winter_fib_proof(
 
     let mut proof_context = Vec::new();
     proof.context.write_into(&mut proof_context);
+
+    // replay the transcript from a separate channel built from the original proof, so that it
+    // can be compared against the transcript replayed by a guest from the packaged proof
+    let context = VerifierContext::new(DomainTag::default(), proof_context);
+    let public_coin_seed = context.coin_seed(&coin_pub_inputs);
+    let mut transcript_channel: VerifierChannel<E, H> =
+        VerifierChannel::new::<FibAir<DefaultNativeMul>>(&air, proof.clone())
+            .map_err(|msg| anyhow!(msg))?;
+    let transcript = replay_transcript(&air, &mut transcript_channel, &public_coin_seed)
+        .map_err(|e| anyhow!("failed to replay the transcript of the fib proof: {}", e))?;
     let pub_inputs = FibRiscInput {
         result: e.result,
-        context: proof_context,
+        context: context.to_bytes(),
+        trace_segments: trace_segment_infos(proof.context.trace_layout()),
+        query_positions: transcript
+            .query_positions
+            .iter()
+            .map(|&position| position as u64)
+            .collect(),
         verifier_channel,
         inv_nondet: INV_NONDET.lock().clone().into_iter().collect(),
         inv_nondet_quad: INV_NONDET_QUAD.lock().clone().into_iter().collect(),
+        aux_data_commitment,
     };
     // Expose FibAirInput as public input to Risc0 prover
     let fib_air_input = FibAirInput {
@@ -87,9 +879,21 @@ fn generate_winter_fib_proof(
         proof_options: proof.options().clone(),
     };
 
-    Ok((pub_inputs, fib_air_input))
+    Ok((pub_inputs, fib_air_input, transcript))
 }
 
-fn verify_with_winter(proof: StarkProof, result: B) -> Result<()> {
-    winter_verifier::verify::<FibAir<DefaultNativeMul>>(proof, result).map_err(|msg| anyhow!(msg))
+/// Verifies the provided fib proof with the native verifier, with the provided aux data
+/// commitment, if any, appended to the public inputs in the seed of the public coin.
+fn verify_with_winter<H: GuestHasher<BaseField = B>>(
+    proof: StarkProof,
+    result: B,
+    aux_data_commitment: Option<[u8; 32]>,
+) -> Result<()> {
+    let seed_extension = aux_data_commitment.map(Vec::from).unwrap_or_default();
+    winter_verifier::verify_with_seed_extension::<FibAir<DefaultNativeMul>, H>(
+        proof,
+        result,
+        &seed_extension,
+    )
+    .map_err(|msg| anyhow!(msg))
 }
@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Context, Result};
+use risc0_zkvm::{host::Receipt, serde::from_slice};
+use utils::journal::{
+    GuestJournal, Journal, MerkleBatchJournal, PhaseJournal, TranscriptJournal,
+    TranscriptKatJournal, VERIFIER_VERSION,
+};
+
+// JOURNAL DECODING
+// ================================================================================================
+
+/// Decodes the [Journal] committed by a verifier guest from the provided receipt.
+///
+/// # Errors
+/// Returns an error if the journal cannot be read from the receipt, if it does not start with a
+/// [Journal], or if the journal was produced by a different version of the verification logic.
+pub fn decode_journal(receipt: &Receipt) -> Result<Journal> {
+    let words = receipt
+        .get_journal_vec()
+        .context("failed to read journal")?;
+    let journal: Journal = from_slice(&words).context("failed to decode journal")?;
+    check_version(&journal)?;
+    Ok(journal)
+}
+
+/// Decodes the [Journal] committed by a verifier guest together with the folded stacks committed
+/// after it by guests built with cycle attribution enabled.
+///
+/// # Errors
+/// Returns an error if the journal cannot be decoded as in [decode_journal], or if it does not
+/// contain cycle attribution.
+pub fn decode_profiled_journal(receipt: &Receipt) -> Result<(Journal, Vec<(String, u64)>)> {
+    let words = receipt
+        .get_journal_vec()
+        .context("failed to read journal")?;
+    let (journal, folded): (Journal, Vec<(String, u64)>) = from_slice(&words).context(
+        "journal does not contain cycle attribution; build the guest with ZKOS_GUEST_PROFILE set",
+    )?;
+    check_version(&journal)?;
+    Ok((journal, folded))
+}
+
+/// Decodes the [TranscriptJournal] committed by a transcript-only guest from the provided
+/// receipt.
+///
+/// # Errors
+/// Returns an error if the journal cannot be read from the receipt, if it is not a
+/// [TranscriptJournal], or if it was produced by a different version of the verification logic.
+pub fn decode_transcript_journal(receipt: &Receipt) -> Result<TranscriptJournal> {
+    let words = receipt
+        .get_journal_vec()
+        .context("failed to read journal")?;
+    let journal: TranscriptJournal =
+        from_slice(&words).context("failed to decode transcript journal")?;
+    check_version(&journal)?;
+    Ok(journal)
+}
+
+/// Decodes the [TranscriptKatJournal] committed by the transcript KAT guest from the provided
+/// receipt.
+///
+/// # Errors
+/// Returns an error if the journal cannot be read from the receipt, if it is not a
+/// [TranscriptKatJournal], or if it was produced by a different version of the verification
+/// logic.
+pub fn decode_transcript_kat_journal(receipt: &Receipt) -> Result<TranscriptKatJournal> {
+    let words = receipt
+        .get_journal_vec()
+        .context("failed to read journal")?;
+    let journal: TranscriptKatJournal =
+        from_slice(&words).context("failed to decode transcript KAT journal")?;
+    check_version(&journal)?;
+    Ok(journal)
+}
+
+/// Decodes the [PhaseJournal] committed by the phase verifier guest from the provided receipt.
+///
+/// # Errors
+/// Returns an error if the journal cannot be read from the receipt, if it is not a
+/// [PhaseJournal], or if it was produced by a different version of the verification logic.
+pub fn decode_phase_journal(receipt: &Receipt) -> Result<PhaseJournal> {
+    let words = receipt
+        .get_journal_vec()
+        .context("failed to read journal")?;
+    let journal: PhaseJournal = from_slice(&words).context("failed to decode phase journal")?;
+    check_version(&journal)?;
+    Ok(journal)
+}
+
+/// Decodes the [MerkleBatchJournal] committed by the Merkle batch guest from the provided
+/// receipt.
+///
+/// # Errors
+/// Returns an error if the journal cannot be read from the receipt, if it is not a
+/// [MerkleBatchJournal], or if it was produced by a different version of the verification logic.
+pub fn decode_merkle_batch_journal(receipt: &Receipt) -> Result<MerkleBatchJournal> {
+    let words = receipt
+        .get_journal_vec()
+        .context("failed to read journal")?;
+    let journal: MerkleBatchJournal =
+        from_slice(&words).context("failed to decode Merkle batch journal")?;
+    check_version(&journal)?;
+    Ok(journal)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn check_version(journal: &impl GuestJournal) -> Result<()> {
+    if !journal.is_current_version() {
+        return Err(anyhow!(
+            "journal was produced by verifier version {}, but version {} is expected",
+            journal.verifier_version(),
+            VERIFIER_VERSION
+        ));
+    }
+    Ok(())
+}
@@ -1,12 +1,18 @@
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use env_logger::Env;
-use log::info;
+use log::{error, info};
 use methods::{EXP_ID, EXP_PATH, RECURSIVE_ID, RECURSIVE_PATH, SHA3_ID, SHA3_PATH};
 use risc0_zkvm::host::Prover;
 use risc0_zkvm::serde::{from_slice, to_vec};
 use sha3::{Digest, Sha3_256};
+use std::path::PathBuf;
+use std::time::Duration;
+use utils::aux_data::commit_aux_data;
+use utils::aux_input::AuxInputCompression;
 use utils::inputs::{MidenAirInput, MidenRiscInput};
+use utils::policy::ProofPolicy;
+use utils::profile::ParamProfile;
 use winter_air::proof::{Commitments, Context, OodFrame, Queries, StarkProof};
 use winter_air::{Air, FieldExtension, HashFunction, ProofOptions};
 use winter_crypto::hashers::DefaultSha2;
@@ -15,8 +21,17 @@ use winter_math::fields::f64::{BaseElement, INV_NONDET};
 use winter_math::log2;
 use winter_verifier::VerifierChannel;
 
+pub mod budget;
+pub mod cache;
+pub mod cost;
 pub mod examples;
+pub mod explain;
 pub mod fib_winter;
+pub mod journal;
+pub mod matrix;
+pub mod merkle_batch;
+pub mod receipt;
+pub mod retry;
 
 /// Choose security definitions for zkp-runner
 #[derive(Parser, Debug)]
@@ -33,21 +48,280 @@ struct ProofArgs {
     /// Grinding factor
     #[arg(short, long, default_value_t = 20)]
     grinding_factor: u32,
+
+    /// Hash function of the winter proofs, either sha2_256, sha2_384, sha2_512, blake3_256,
+    /// keccak256, or the experimental poseidon64_256
+    #[arg(long, default_value = "sha2_256", value_parser = parse_hash_fn)]
+    hash_fn: HashFunction,
+
+    /// Parameter profile of the winter proofs, either dev, recursion-fast, production-96bit, or
+    /// production-128bit; the profile sets the queries, blowup, grinding, and field extension of
+    /// the proofs, and the policy they are packaged and verified under
+    #[arg(
+        long,
+        value_parser = parse_profile,
+        conflicts_with_all = ["fri_queries", "blowup_factor", "grinding_factor", "policy"]
+    )]
+    profile: Option<ParamProfile>,
+
+    /// Policy on the proof options of the verified proofs, e.g. "queries>=27,blowup>=8,
+    /// grinding>=16,hash=sha2_256|sha2_512,field=quadratic"; proofs violating the policy are
+    /// neither packaged nor accepted by the guest
+    #[arg(long, default_value = "any", value_parser = parse_policy)]
+    policy: ProofPolicy,
+
+    /// Compress the packaged proofs passed to the guest with LZ4, trading guest cycles spent on
+    /// decompression for fewer input bytes
+    #[arg(long)]
+    compress_aux: bool,
+
+    /// Maximum number of input bytes passed to the guest; packaged proofs exceeding this budget,
+    /// or whose heap estimates exceed the cap the guests are built with (see
+    /// ZKOS_GUEST_HEAP_ESTIMATE_CAP), are rejected before the guest is launched
+    #[arg(long, default_value_t = budget::DEFAULT_MAX_INPUT_BYTES)]
+    max_input_bytes: usize,
+
+    /// Only replay the Fiat–Shamir transcripts of the packaged proofs in the guest instead of
+    /// verifying them; this is a cheap check for bugs in the packaging of the proofs
+    #[arg(long)]
+    transcript_only: bool,
+
+    /// Verify the fib proofs in the phase verifier guest, running the transcript, OOD, queries,
+    /// and FRI phases of the verification as separate guest executions and stitching their
+    /// receipts
+    #[arg(long)]
+    phased: bool,
+
+    /// Check the Fiat–Shamir transcripts of the fixed proofs of the KAT suite against the known
+    /// answers in this directory, on the host and in the transcript KAT guest, instead of
+    /// verifying the fib proofs
+    #[arg(long)]
+    transcript_kats: Option<PathBuf>,
+
+    /// Verify a batch of this many Merkle inclusion proofs in the Merkle batch guest instead of
+    /// the fib proofs, and report the guest hashing throughput
+    #[arg(long)]
+    merkle_batch: Option<usize>,
+
+    /// Depth of the Merkle tree the inclusion proofs of --merkle-batch are built for
+    #[arg(long, default_value_t = 16)]
+    merkle_depth: u32,
+
+    /// Bind the commitment to the lines of this file, as items of application data, to the fib
+    /// proofs verified in the fib verifier guest, and check that the journal surfaces it
+    #[arg(long)]
+    aux_data: Option<PathBuf>,
+
+    /// Verify the fib proofs of the release case in the fib verifier guest, and check the packaged
+    /// inputs, the guest image ID, and the journal against the golden release fixture at this path
+    #[arg(long)]
+    validate_release: Option<PathBuf>,
+
+    /// Check that the fib verifier guest rejects packaged proofs tampered with by each of a
+    /// standard set of mutations
+    #[arg(long)]
+    rejection_tests: bool,
+
+    /// If verification fails, on the host or in the guest, describe the failed check in plain
+    /// language, together with its most common causes and the packaged input fields involved
+    #[arg(long)]
+    explain: bool,
+
+    /// Build the guests with several optimization levels and codegen settings, and run the
+    /// verification fixture under each build, reporting builds under which it diverges
+    #[arg(long)]
+    ci_matrix: bool,
+
+    /// Write guest cycle attribution as folded stacks to this file (the guest must be built
+    /// with ZKOS_GUEST_PROFILE set)
+    #[arg(long)]
+    flamegraph: Option<PathBuf>,
+
+    /// Append the cost report of every prove run (cycles, wall-clock time, proof size) as a JSON
+    /// line to this file
+    #[arg(long, default_value = "cost-history.jsonl")]
+    cost_history: PathBuf,
+
+    /// Always prove the guest, even if the proof cache holds a receipt for the same inputs; the
+    /// cache is neither read nor written
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Directory of the proof cache, which holds the receipts of previous prove runs keyed by the
+    /// guest image ID and the packaged inputs
+    #[arg(long, default_value = ".zkprunner-cache")]
+    cache_dir: PathBuf,
+
+    /// Remove receipts written to the proof cache more than this many days ago
+    #[arg(long, default_value_t = 7)]
+    cache_max_age_days: u64,
+
+    /// Run the prove runs in a supervised child process, and retry them up to this many times,
+    /// each time with half as many prover threads, if proving runs out of memory; 0 runs them in
+    /// this process without retries
+    #[arg(long, default_value_t = 2)]
+    oom_retries: usize,
 }
 
 fn main() -> Result<()> {
     env_logger::Builder::from_env(Env::default().default_filter_or("debug")).init();
     let args = ProofArgs::parse();
-    fib_winter::fib_winter(get_proof_options(args))?;
+    if args.ci_matrix {
+        return matrix::ci_matrix(&matrix::DEFAULT_MATRIX, &fixture_args(&args));
+    }
+    if args.oom_retries > 0 && !retry::is_supervised() {
+        return retry::supervise(args.oom_retries);
+    }
+    let explain = args.explain;
+    let flamegraph = args.flamegraph.clone();
+    let cost_history = args.cost_history.clone();
+    let policy = match args.profile {
+        Some(profile) => profile.policy(),
+        None => args.policy,
+    };
+    let budget = budget::InputBudget::from_env(args.max_input_bytes)?;
+    let cache = match args.no_cache {
+        true => None,
+        false => {
+            let cache = cache::ProofCache::new(args.cache_dir.clone());
+            let num_removed =
+                cache.gc(Duration::from_secs(args.cache_max_age_days * 24 * 60 * 60))?;
+            if num_removed > 0 {
+                info!(
+                    "Removed {} expired receipts from the proof cache in {}",
+                    num_removed,
+                    cache.dir().display()
+                );
+            }
+            Some(cache)
+        }
+    };
+    let aux_data_commitment = match &args.aux_data {
+        Some(path) => Some(read_aux_data_commitment(path)?),
+        None => None,
+    };
+    let compression = match args.compress_aux {
+        true => AuxInputCompression::Lz4,
+        false => AuxInputCompression::None,
+    };
+    let result = if let Some(num_leaves) = args.merkle_batch {
+        merkle_batch::merkle_batch(num_leaves, args.merkle_depth, &budget, compression)
+    } else if let Some(kat_dir) = &args.transcript_kats {
+        fib_winter::fib_transcript_kats(kat_dir, &budget, compression)
+    } else if let Some(fixture_path) = &args.validate_release {
+        fib_winter::fib_validate_release(fixture_path, &budget)
+    } else if args.rejection_tests {
+        fib_winter::fib_rejection(get_proof_options(args), &policy, &budget, compression)
+    } else if args.transcript_only {
+        fib_winter::fib_transcript(get_proof_options(args), &budget, compression)
+    } else if args.phased {
+        fib_winter::fib_phases(get_proof_options(args), &policy, &budget, compression)
+    } else {
+        fib_winter::fib_winter(
+            get_proof_options(args),
+            &policy,
+            &budget,
+            compression,
+            flamegraph.as_deref(),
+            &cost_history,
+            cache.as_ref(),
+            aux_data_commitment,
+        )
+    };
+    if let (Err(e), true) = (&result, explain) {
+        match explain::explain(e) {
+            Some(explanation) => error!("{}", explanation),
+            None => info!("The failure is not a failed verification check; nothing to explain"),
+        }
+    }
+    retry::exit_on_out_of_memory(&result);
+    result?;
 
     // TODO - add proper cmd options
     // examples::recursive_miden()?;
+    // examples::recursive_miden_quad()?;
+    // examples::recursive_miden_cube()?;
+    // examples::recursive_miden_rpo()?;
+    // examples::recursive_miden_keccak()?;
+    // examples::recursive_miden_blinded()?;
+    // examples::fib128_verify()?;
     // examples::sha3();
     // examples::exp();
     Ok(())
 }
 
+/// Returns the command-line arguments with which the fixture is run by the CI matrix; these are
+/// the provided arguments without the matrix, flamegraph, cost history, and release validation
+/// options; the release fixture pins the image ID of the default build only. If a profile is
+/// provided, it is passed instead of the options and the policy it sets. The fixture never
+/// reads the proof cache, so that every build of the guest is actually proven.
+fn fixture_args(args: &ProofArgs) -> Vec<String> {
+    let mut result = match args.profile {
+        Some(profile) => vec![format!("--profile={}", profile)],
+        None => vec![
+            format!("--fri-queries={}", args.fri_queries),
+            format!("--blowup-factor={}", args.blowup_factor),
+            format!("--grinding-factor={}", args.grinding_factor),
+            format!("--policy={}", args.policy),
+        ],
+    };
+    result.extend([
+        format!("--hash-fn={}", hash_fn_name(args.hash_fn)),
+        format!("--max-input-bytes={}", args.max_input_bytes),
+        "--no-cache".to_string(),
+    ]);
+    if args.compress_aux {
+        result.push("--compress-aux".to_string());
+    }
+    if args.transcript_only {
+        result.push("--transcript-only".to_string());
+    }
+    if args.phased {
+        result.push("--phased".to_string());
+    }
+    if args.rejection_tests {
+        result.push("--rejection-tests".to_string());
+    }
+    if args.explain {
+        result.push("--explain".to_string());
+    }
+    if let Some(kat_dir) = &args.transcript_kats {
+        result.push(format!("--transcript-kats={}", kat_dir.display()));
+    }
+    if let Some(num_leaves) = args.merkle_batch {
+        result.push(format!("--merkle-batch={}", num_leaves));
+        result.push(format!("--merkle-depth={}", args.merkle_depth));
+    }
+    if let Some(path) = &args.aux_data {
+        result.push(format!("--aux-data={}", path.display()));
+    }
+    result
+}
+
+/// Reads the file at the provided path, and returns the commitment to its lines as the items of
+/// the aux data of the fib proofs.
+fn read_aux_data_commitment(path: &std::path::Path) -> Result<[u8; 32]> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to read aux data from {}: {}", path.display(), e))?;
+    let items: Vec<&[u8]> = data.lines().map(str::as_bytes).collect();
+    let commitment = commit_aux_data::<DefaultSha2>(&items);
+    info!(
+        "Binding commitment {} to {} aux data items",
+        hex::encode(commitment),
+        items.len()
+    );
+    Ok(commitment)
+}
+
 fn get_proof_options(args: ProofArgs) -> ProofOptions {
+    if let Some(profile) = args.profile {
+        info!(
+            "Generating winter proofs of the {} profile with {}bits of security",
+            profile,
+            profile.security_bits()
+        );
+        return profile.proof_options(args.hash_fn);
+    }
     let R = log2(args.blowup_factor as usize);
     // λ ≥ min{ζ + R · s, log2|K|} − 1 from ethSTARK paper
     // Since we are using extension field of degree 2, K = P^2.
@@ -61,9 +335,44 @@ fn get_proof_options(args: ProofArgs) -> ProofOptions {
         args.fri_queries as usize,
         args.blowup_factor as usize,
         args.grinding_factor,
-        HashFunction::Sha2_256,
+        args.hash_fn,
         FieldExtension::Quadratic,
         8,
         256,
     )
 }
+
+/// Parses the name of a hash function the fib verifier guest supports.
+fn parse_hash_fn(name: &str) -> Result<HashFunction, String> {
+    match name {
+        "sha2_256" => Ok(HashFunction::Sha2_256),
+        "sha2_384" => Ok(HashFunction::Sha2_384),
+        "sha2_512" => Ok(HashFunction::Sha2_512),
+        "blake3_256" => Ok(HashFunction::Blake3_256),
+        "keccak256" => Ok(HashFunction::Keccak256),
+        "poseidon64_256" => Ok(HashFunction::Poseidon64_256),
+        _ => Err(format!("'{}' is not a valid hash function option", name)),
+    }
+}
+
+/// Parses a proof policy written in the policy language of [utils::policy].
+fn parse_policy(source: &str) -> Result<ProofPolicy, String> {
+    source.parse::<ProofPolicy>().map_err(|e| e.to_string())
+}
+
+/// Parses the name of a parameter profile of [utils::profile].
+fn parse_profile(name: &str) -> Result<ParamProfile, String> {
+    name.parse::<ParamProfile>().map_err(|e| e.to_string())
+}
+
+/// Returns the name of the specified hash function as accepted by [parse_hash_fn].
+fn hash_fn_name(hash_fn: HashFunction) -> &'static str {
+    match hash_fn {
+        HashFunction::Sha2_384 => "sha2_384",
+        HashFunction::Sha2_512 => "sha2_512",
+        HashFunction::Blake3_256 => "blake3_256",
+        HashFunction::Keccak256 => "keccak256",
+        HashFunction::Poseidon64_256 => "poseidon64_256",
+        _ => "sha2_256",
+    }
+}
@@ -0,0 +1,179 @@
+use anyhow::{anyhow, Context, Result};
+use log::{error, info, warn};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// GUEST BUILDS
+// ================================================================================================
+
+/// Codegen settings with which the guests are built by one entry of the CI matrix.
+///
+/// The settings are passed to the build script of the `methods` crate, which applies them to the
+/// release profile of the guest build only; the host is always built with the same settings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuestBuild {
+    /// Name of the build, used in the report and as the name of its target directory.
+    pub name: &'static str,
+    /// Value of the `opt-level` profile setting.
+    pub opt_level: &'static str,
+    /// Value of the `codegen-units` profile setting.
+    pub codegen_units: u32,
+    /// Value of the `lto` profile setting.
+    pub lto: bool,
+    /// Field multiplication backend of the guests, either "default" or "schoolbook".
+    pub mul_backend: &'static str,
+}
+
+impl GuestBuild {
+    /// Returns the environment variables through which the settings are passed to the build
+    /// script of the `methods` crate.
+    fn env_vars(&self) -> [(&'static str, String); 4] {
+        [
+            ("ZKOS_GUEST_OPT_LEVEL", self.opt_level.to_string()),
+            ("ZKOS_GUEST_CODEGEN_UNITS", self.codegen_units.to_string()),
+            ("ZKOS_GUEST_LTO", self.lto.to_string()),
+            ("ZKOS_GUEST_MUL_BACKEND", self.mul_backend.to_string()),
+        ]
+    }
+}
+
+/// Guest builds run by the CI matrix. Field arithmetic which relies on undefined or
+/// implementation-specific behavior (e.g., inline multiplication hints) tends to break only at
+/// some optimization levels, so the matrix covers all levels used for release builds. The last
+/// build multiplies field elements with the schoolbook backend of winter-math, which does not
+/// depend on how the field arithmetic is inlined, and thus, cross-checks the optimized backend.
+pub const DEFAULT_MATRIX: [GuestBuild; 6] = [
+    GuestBuild {
+        name: "opt1",
+        opt_level: "1",
+        codegen_units: 16,
+        lto: false,
+        mul_backend: "default",
+    },
+    GuestBuild {
+        name: "opt2",
+        opt_level: "2",
+        codegen_units: 16,
+        lto: false,
+        mul_backend: "default",
+    },
+    GuestBuild {
+        name: "opt3",
+        opt_level: "3",
+        codegen_units: 16,
+        lto: false,
+        mul_backend: "default",
+    },
+    GuestBuild {
+        name: "opt3-lto",
+        opt_level: "3",
+        codegen_units: 1,
+        lto: true,
+        mul_backend: "default",
+    },
+    GuestBuild {
+        name: "opts",
+        opt_level: "s",
+        codegen_units: 16,
+        lto: false,
+        mul_backend: "default",
+    },
+    GuestBuild {
+        name: "opt3-schoolbook",
+        opt_level: "3",
+        codegen_units: 16,
+        lto: false,
+        mul_backend: "schoolbook",
+    },
+];
+
+// MATRIX RUNNER
+// ================================================================================================
+
+/// Outcome of the verification fixture under one guest build.
+struct MatrixResult {
+    /// Name of the guest build.
+    name: &'static str,
+    /// Last line of the error output of the fixture if it failed under this build.
+    error: Option<String>,
+}
+
+/// Builds the guests with every entry of the provided matrix and runs the verification fixture,
+/// i.e., zkprunner invoked with `fixture_args`, under each build.
+///
+/// Every build uses its own target directory under `target/ci-matrix`, so subsequent runs of the
+/// matrix only rebuild what changed.
+///
+/// # Errors
+/// Returns an error if the fixture fails under any of the builds; the builds under which the
+/// fixture failed are reported as divergences when it passed under others.
+pub fn ci_matrix(matrix: &[GuestBuild], fixture_args: &[String]) -> Result<()> {
+    let workspace_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .context("zkprunner is not in a workspace")?;
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+
+    let mut results = Vec::with_capacity(matrix.len());
+    for build in matrix {
+        info!("Running the fixture under guest build {}...", build.name);
+        let target_dir: PathBuf = workspace_dir
+            .join("target")
+            .join("ci-matrix")
+            .join(build.name);
+        let output = Command::new(&cargo)
+            .current_dir(workspace_dir)
+            .args(["run", "--release", "-p", "zkprunner", "--target-dir"])
+            .arg(&target_dir)
+            .arg("--")
+            .args(fixture_args)
+            .envs(build.env_vars())
+            .output()
+            .with_context(|| format!("failed to run the fixture under build {}", build.name))?;
+
+        let error = match output.status.success() {
+            true => None,
+            false => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let last_line = stderr.lines().rev().find(|line| !line.trim().is_empty());
+                Some(last_line.unwrap_or("no error output").to_string())
+            }
+        };
+        results.push(MatrixResult {
+            name: build.name,
+            error,
+        });
+    }
+
+    report(&results)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Logs the outcome of every build, and returns an error if any of the builds failed.
+fn report(results: &[MatrixResult]) -> Result<()> {
+    let (failed, passed): (Vec<_>, Vec<_>) = results.iter().partition(|r| r.error.is_some());
+    for result in passed.iter() {
+        info!("{}: passed", result.name);
+    }
+    for result in failed.iter() {
+        error!(
+            "{}: {}",
+            result.name,
+            result.error.as_deref().unwrap_or_default()
+        );
+    }
+
+    if failed.is_empty() {
+        return Ok(());
+    }
+    let failed_names = failed.iter().map(|r| r.name).collect::<Vec<_>>().join(", ");
+    if passed.is_empty() {
+        return Err(anyhow!("the fixture failed under all guest builds"));
+    }
+    warn!("Codegen-sensitive divergence: the fixture passed under some guest builds only");
+    Err(anyhow!(
+        "the fixture diverged under guest builds: {}",
+        failed_names
+    ))
+}
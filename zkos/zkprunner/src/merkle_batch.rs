@@ -0,0 +1,126 @@
+use crate::budget::{InputBudget, InputUsage};
+use crate::journal::decode_merkle_batch_journal;
+use crate::receipt::run_prover;
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info};
+use methods::{MERKLE_BATCH_ID, MERKLE_BATCH_PATH};
+use risc0_zkvm::host::Prover;
+use std::time::Instant;
+use utils::aux_input::{aux_input_heap_size, encode_aux_input, AuxInputCompression};
+use utils::merkle_batch::{verify_batch, MerkleBatch, MerkleTree, MAX_DEPTH};
+use winter_crypto::hashers::{DefaultSha2, ShaHasherT};
+
+/// Verifies the inclusion of the specified number of leaves of a deterministic tree of the
+/// specified depth in the Merkle batch guest, and checks that the guest commits the root of the
+/// tree and the digest of the included leaves. The packaged batch is checked against the provided
+/// budget before the guest is launched.
+pub fn merkle_batch(
+    num_leaves: usize,
+    depth: u32,
+    budget: &InputBudget,
+    compression: AuxInputCompression,
+) -> Result<()> {
+    info!(
+        "Building a Merkle tree of depth {} and a batch of {} inclusion proofs...",
+        depth, num_leaves
+    );
+    let tree = build_tree(depth)?;
+    let batch = MerkleBatch::from_tree(&tree, &batch_indices(num_leaves, depth));
+    let expected_digest = verify_batch::<DefaultSha2>(
+        &batch.root,
+        batch.depth,
+        &batch.indices,
+        &batch.leaves,
+        &batch.siblings,
+    )
+    .map_err(|e| anyhow!("host failed to verify the Merkle batch: {}", e))?;
+
+    let mut prover =
+        Prover::new(&std::fs::read(MERKLE_BATCH_PATH).unwrap(), MERKLE_BATCH_ID).unwrap();
+    let payload = rkyv::to_bytes::<_, 256>(&batch).unwrap();
+    let aux_input = encode_aux_input(&payload, compression);
+    debug!(
+        "Guest aux input: {} bytes ({} bytes before {:?} compression)",
+        aux_input.len(),
+        payload.len(),
+        compression
+    );
+    budget.check(&InputUsage {
+        input_bytes: aux_input.len(),
+        compression,
+        aux_input_heap_bytes: aux_input_heap_size(&aux_input)
+            .map_err(|e| anyhow!("failed to read the aux input header: {}", e))?,
+        verification_heap_bytes: 0,
+        num_proofs: num_leaves,
+    })?;
+    prover.add_input_u8_slice_aux(&aux_input);
+
+    info!("Running risc0 prover on the Merkle batch guest...");
+    let start = Instant::now();
+    let receipt = run_prover(&mut prover)?;
+    let elapsed = start.elapsed();
+    receipt
+        .verify(MERKLE_BATCH_ID)
+        .context("failed to verify receipt of the Merkle batch")?;
+
+    let journal = decode_merkle_batch_journal(&receipt)?;
+    if let Some(error) = journal.error {
+        return Err(anyhow::Error::new(error).context("guest failed to verify the Merkle batch"));
+    }
+    if journal.root != batch.root {
+        return Err(anyhow!(
+            "journal is bound to root {}, but the batch was built for root {}",
+            hex::encode(journal.root),
+            hex::encode(batch.root)
+        ));
+    }
+    if journal.num_leaves != num_leaves as u64 || journal.leaves_digest != expected_digest {
+        return Err(anyhow!("journal is bound to different leaves"));
+    }
+    if !journal.result {
+        return Err(anyhow!("guest failed to verify the Merkle batch"));
+    }
+
+    let num_hashes = num_leaves as f64 * depth as f64;
+    info!(
+        "Guest verified {} inclusion proofs ({} hashes) in {:.2?}: {:.0} hashes/s",
+        num_leaves,
+        num_hashes,
+        elapsed,
+        num_hashes / elapsed.as_secs_f64()
+    );
+
+    Ok(())
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a tree of the specified depth in which the leaf at index `i` is the digest of `i`.
+fn build_tree(depth: u32) -> Result<MerkleTree> {
+    if depth > MAX_DEPTH {
+        return Err(anyhow!(
+            "Merkle tree depth {} exceeds the maximum of {}",
+            depth,
+            MAX_DEPTH
+        ));
+    }
+    let num_leaves = 1u64 << depth;
+    let leaves = (0..num_leaves)
+        .map(|i| DefaultSha2::digest(&i.to_le_bytes()))
+        .collect();
+    Ok(MerkleTree::new::<DefaultSha2>(leaves))
+}
+
+/// Returns the indices of the leaves included in a batch of the specified size, spread evenly
+/// over the leaves of a tree of the specified depth; leaves are included more than once if the
+/// batch is larger than the tree.
+fn batch_indices(num_leaves: usize, depth: u32) -> Vec<u64> {
+    let tree_size = 1u64 << depth;
+    // an odd stride is coprime with the size of the tree, so that the indices do not repeat
+    // before every leaf has been included
+    let stride = (tree_size / num_leaves.max(1) as u64) | 1;
+    (0..num_leaves as u64)
+        .map(|i| i.wrapping_mul(stride) % tree_size)
+        .collect()
+}
@@ -0,0 +1,315 @@
+use anyhow::{anyhow, Context, Result};
+use errors::{ErrorChain, ErrorKind};
+use log::{debug, warn};
+use risc0_zkvm::host::{Prover, Receipt};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
+
+// IMAGE ID
+// ================================================================================================
+
+/// Image ID of a guest whose receipts are accepted by an [ImageIdAllowList].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageId {
+    /// Human-readable name of the guest version, e.g., "fib_verify-v1".
+    pub label: String,
+    /// Image ID of the guest.
+    pub id: Vec<u8>,
+    /// Point in time after which receipts of this guest are no longer accepted; if not set,
+    /// receipts of this guest are accepted indefinitely.
+    pub deprecated_after: Option<SystemTime>,
+}
+
+impl ImageId {
+    /// Returns true if receipts of this guest are no longer accepted at the specified time.
+    pub fn is_deprecated_at(&self, time: SystemTime) -> bool {
+        matches!(self.deprecated_after, Some(deprecated_after) if time > deprecated_after)
+    }
+}
+
+// IMAGE ID ALLOW-LIST
+// ================================================================================================
+
+/// A set of guest image IDs against which receipts are verified.
+///
+/// When a guest is updated, its image ID changes, and receipts produced by the previous version
+/// of the guest can no longer be verified against the new image ID. To rotate guest versions
+/// without breaking verification of older receipts, the previous image ID can be kept in the
+/// allow-list with a deprecation date, after which its receipts are rejected.
+#[derive(Clone, Debug, Default)]
+pub struct ImageIdAllowList {
+    image_ids: Vec<ImageId>,
+}
+
+impl ImageIdAllowList {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an empty allow-list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an image ID which is accepted indefinitely to this allow-list.
+    pub fn with_image_id(mut self, label: &str, id: &[u8]) -> Self {
+        self.image_ids.push(ImageId {
+            label: label.to_string(),
+            id: id.to_vec(),
+            deprecated_after: None,
+        });
+        self
+    }
+
+    /// Adds an image ID which is accepted until the specified time to this allow-list.
+    pub fn with_deprecated_image_id(
+        mut self,
+        label: &str,
+        id: &[u8],
+        deprecated_after: SystemTime,
+    ) -> Self {
+        self.image_ids.push(ImageId {
+            label: label.to_string(),
+            id: id.to_vec(),
+            deprecated_after: Some(deprecated_after),
+        });
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the image IDs in this allow-list in the order in which they were added.
+    pub fn image_ids(&self) -> &[ImageId] {
+        &self.image_ids
+    }
+
+    // VERIFICATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Verifies the provided receipt against the image IDs in this allow-list which are not
+    /// deprecated at the current time, and returns the image ID the receipt was verified
+    /// against.
+    ///
+    /// # Errors
+    /// Returns an error if the receipt cannot be verified against any of the image IDs which are
+    /// not deprecated.
+    pub fn verify(&self, receipt: &Receipt) -> Result<&ImageId> {
+        self.verify_at(receipt, SystemTime::now())
+    }
+
+    /// Verifies the provided receipt against the image IDs in this allow-list which are not
+    /// deprecated at the specified time, and returns the image ID the receipt was verified
+    /// against.
+    ///
+    /// Image IDs are tried in the order in which they were added to the allow-list, and thus,
+    /// the current version of a guest should be added first.
+    ///
+    /// # Errors
+    /// Returns an error if the receipt cannot be verified against any of the image IDs which are
+    /// not deprecated at the specified time.
+    pub fn verify_at(&self, receipt: &Receipt, time: SystemTime) -> Result<&ImageId> {
+        let (deprecated, active): (Vec<_>, Vec<_>) = self
+            .image_ids
+            .iter()
+            .partition(|image_id| image_id.is_deprecated_at(time));
+
+        if let Some(image_id) = find_image_id(receipt, &active) {
+            if image_id.deprecated_after.is_some() {
+                warn!(
+                    "receipt was verified against guest image {}, which is scheduled for deprecation",
+                    image_id.label
+                );
+            }
+            return Ok(image_id);
+        }
+
+        // deprecated image IDs are only checked to report a more informative error
+        match find_image_id(receipt, &deprecated) {
+            Some(image_id) => Err(anyhow!(
+                "receipt was produced by guest image {}, which is no longer accepted",
+                image_id.label
+            )),
+            None => Err(anyhow!(
+                "receipt cannot be verified against any of the {} allowed guest images",
+                active.len()
+            )),
+        }
+    }
+}
+
+// BATCH VERIFICATION
+// ================================================================================================
+
+/// Options of [verify_receipts_with].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchOptions {
+    /// Number of threads verifying receipts; if not set, the available parallelism of the
+    /// machine is used.
+    pub num_threads: Option<NonZeroUsize>,
+    /// If set, no further receipts are verified once a receipt fails verification; receipts which
+    /// were not verified are reported as skipped.
+    pub fail_fast: bool,
+}
+
+impl Default for BatchOptions {
+    /// Returns options which verify every receipt using all available threads.
+    fn default() -> Self {
+        Self {
+            num_threads: None,
+            fail_fast: false,
+        }
+    }
+}
+
+/// Verifies the provided receipts against the specified image ID in parallel, and returns the
+/// result of verifying every receipt, in the order of the receipts.
+///
+/// This is meant for services which ingest receipts from many provers; a receipt which fails
+/// verification does not affect the results of the other receipts.
+pub fn verify_receipts(receipts: &[Receipt], image_id: &[u8]) -> Vec<Result<()>> {
+    verify_receipts_with(receipts, image_id, BatchOptions::default())
+}
+
+/// Verifies the provided receipts against the specified image ID with the specified options, and
+/// returns the result of verifying every receipt, in the order of the receipts.
+///
+/// Receipts wrap handles owned by the prover library, which are not shared across threads;
+/// instead, the journal and the seal of every receipt are read on the calling thread, and every
+/// worker thread verifies receipts it rebuilds from them. A receipt whose journal or seal cannot
+/// be read fails verification.
+pub fn verify_receipts_with(
+    receipts: &[Receipt],
+    image_id: &[u8],
+    options: BatchOptions,
+) -> Vec<Result<()>> {
+    let parts = receipts
+        .iter()
+        .map(|receipt| {
+            let journal = receipt
+                .get_journal_vec()
+                .context("failed to read journal")?;
+            let seal = receipt.get_seal().context("failed to read seal")?.to_vec();
+            Ok((journal, seal))
+        })
+        .collect::<Vec<Result<(Vec<u32>, Vec<u32>)>>>();
+
+    let num_threads = options
+        .num_threads
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get)
+        .min(receipts.len().max(1));
+    debug!(
+        "Verifying {} receipts on {} threads",
+        receipts.len(),
+        num_threads
+    );
+
+    // workers claim receipts in order, so that with fail-fast, every receipt preceding the first
+    // failed one has been verified
+    let batch = Arc::new(Batch {
+        results: Mutex::new((0..parts.len()).map(|_| None).collect()),
+        parts,
+        image_id: image_id.to_vec(),
+        next_idx: AtomicUsize::new(0),
+        failed: AtomicBool::new(false),
+        fail_fast: options.fail_fast,
+    });
+    let workers = (0..num_threads)
+        .map(|_| {
+            let batch = Arc::clone(&batch);
+            thread::spawn(move || batch.run_worker())
+        })
+        .collect::<Vec<_>>();
+    for worker in workers {
+        worker.join().expect("receipt verification thread panicked");
+    }
+
+    let batch = Arc::try_unwrap(batch)
+        .ok()
+        .expect("receipt verification threads have not terminated");
+    batch
+        .results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, result)| {
+            result.unwrap_or_else(|| {
+                Err(anyhow!(
+                    "verification of receipt {} was skipped because a previous receipt failed \
+                     verification",
+                    idx
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Receipts verified by the worker threads of [verify_receipts_with].
+struct Batch {
+    parts: Vec<Result<(Vec<u32>, Vec<u32>)>>,
+    image_id: Vec<u8>,
+    next_idx: AtomicUsize,
+    failed: AtomicBool,
+    fail_fast: bool,
+    results: Mutex<Vec<Option<Result<()>>>>,
+}
+
+impl Batch {
+    /// Verifies receipts until every receipt has been claimed by a worker, or, with fail-fast,
+    /// until a receipt fails verification.
+    fn run_worker(&self) {
+        loop {
+            if self.fail_fast && self.failed.load(Ordering::Acquire) {
+                return;
+            }
+            let idx = self.next_idx.fetch_add(1, Ordering::AcqRel);
+            let result = match self.parts.get(idx) {
+                Some(Ok((journal, seal))) => verify_parts(journal, seal, &self.image_id),
+                Some(Err(err)) => Err(anyhow!("{:#}", err)),
+                None => return,
+            };
+            if result.is_err() {
+                self.failed.store(true, Ordering::Release);
+            }
+            self.results.lock().unwrap()[idx] = Some(result);
+        }
+    }
+}
+
+// PROVING
+// ================================================================================================
+
+/// Runs the provided prover and returns the receipt of the guest execution.
+///
+/// # Errors
+/// Returns an error chain of kind [ErrorKind::Prover] if the prover fails; exceptions thrown by
+/// the C++ prover are converted at the FFI boundary via [ErrorChain::from_ffi].
+pub fn run_prover(prover: &mut Prover) -> Result<Receipt, ErrorChain> {
+    prover
+        .run()
+        .map_err(|e| ErrorChain::from_ffi(ErrorKind::Prover, &e.to_string()))
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Rebuilds a receipt from the provided journal and seal, and verifies it against the specified
+/// image ID.
+fn verify_parts(journal: &[u32], seal: &[u32], image_id: &[u8]) -> Result<()> {
+    let receipt = Receipt::new(journal, seal).context("failed to rebuild receipt")?;
+    receipt
+        .verify(image_id)
+        .context("receipt cannot be verified against the image ID")
+}
+
+/// Returns the first of the provided image IDs the receipt can be verified against.
+fn find_image_id<'a>(receipt: &Receipt, image_ids: &[&'a ImageId]) -> Option<&'a ImageId> {
+    image_ids
+        .iter()
+        .find(|image_id| receipt.verify(&image_id.id).is_ok())
+        .copied()
+}
@@ -0,0 +1,251 @@
+use anyhow::{anyhow, Context, Result};
+use errors::ErrorChain;
+use log::{error, info, warn};
+use std::io::{BufRead, BufReader, Write};
+use std::num::NonZeroUsize;
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Exit code with which a supervised run reports that proving ran out of memory; this is
+/// `EX_TEMPFAIL`, i.e., a failure which may not occur again under different conditions.
+pub const OOM_EXIT_CODE: i32 = 75;
+
+/// Environment variable holding the number of the attempt a supervised run makes; runs with this
+/// variable set are never supervised themselves.
+pub const ATTEMPT_ENV: &str = "ZKOS_PROVE_ATTEMPT";
+
+/// Environment variables limiting the number of threads of the thread pools the provers use:
+/// the pool of the winterfell prover, and the one of the native zkVM prover.
+const THREADS_ENV: [&str; 2] = ["RAYON_NUM_THREADS", "OMP_NUM_THREADS"];
+
+/// Fragments of the messages with which allocation failures are reported: by the native prover,
+/// on `std::bad_alloc` or a failed `malloc`, and by the default allocation error handler of Rust,
+/// right before the process is aborted.
+const OOM_MESSAGES: [&str; 4] = [
+    "bad_alloc",
+    "out of memory",
+    "cannot allocate memory",
+    "memory allocation of",
+];
+
+// PROVE ATTEMPTS
+// ================================================================================================
+
+/// Settings under which one attempt to run zkprunner proves its guests.
+///
+/// The native prover proves a guest execution as a single segment and keeps the whole trace in
+/// memory, and neither can be changed by the host; the number of threads proving in parallel,
+/// each holding its own working buffers, is thus the only setting an attempt degrades.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProveAttempt {
+    /// Number of the attempt, starting at 1.
+    pub number: usize,
+    /// Number of threads of the provers; if not set, the provers use the available parallelism
+    /// of the machine.
+    pub num_threads: Option<NonZeroUsize>,
+}
+
+impl ProveAttempt {
+    /// Returns the first attempt, which proves with the default settings.
+    pub fn first() -> Self {
+        Self {
+            number: 1,
+            num_threads: None,
+        }
+    }
+
+    /// Returns the attempt made after this one ran out of memory, which proves with half as many
+    /// threads, or None if this attempt already proved on a single thread.
+    pub fn degrade(&self) -> Option<Self> {
+        let num_threads = self.num_threads().get();
+        if num_threads == 1 {
+            return None;
+        }
+        Some(Self {
+            number: self.number + 1,
+            num_threads: NonZeroUsize::new(num_threads / 2),
+        })
+    }
+
+    /// Returns the number of threads of the provers under this attempt.
+    pub fn num_threads(&self) -> NonZeroUsize {
+        self.num_threads
+            .or_else(|| thread::available_parallelism().ok())
+            .unwrap_or_else(|| NonZeroUsize::new(1).unwrap())
+    }
+
+    /// Returns the environment variables under which this attempt is run.
+    fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut result = vec![(ATTEMPT_ENV, self.number.to_string())];
+        if let Some(num_threads) = self.num_threads {
+            result.extend(THREADS_ENV.map(|name| (name, num_threads.to_string())));
+        }
+        result
+    }
+}
+
+// SUPERVISOR
+// ================================================================================================
+
+/// Returns true if this process is an attempt run by [supervise].
+pub fn is_supervised() -> bool {
+    std::env::var_os(ATTEMPT_ENV).is_some()
+}
+
+/// Runs zkprunner with the arguments of this process as a supervised child process, and retries
+/// with degraded settings, up to the specified number of times, whenever proving runs out of
+/// memory; the settings changed by every retry are logged.
+///
+/// Proving a large guest may allocate memory for hours before it fails; running every attempt
+/// in a fresh process reclaims the memory of the failed attempt, including memory the native
+/// prover leaks when an allocation fails, and survives attempts killed by the OOM killer of the
+/// system. An attempt is considered to have run out of memory if:
+/// * it exited with [OOM_EXIT_CODE], i.e., an allocation of the native prover failed, or
+/// * it was terminated by a signal after the allocation error handler of Rust reported a failed
+///   allocation, or
+/// * it was killed with `SIGKILL`, which is how the OOM killer terminates processes.
+///
+/// # Errors
+/// Returns an error if the child process cannot be run, if an attempt fails for any other reason
+/// than running out of memory, or if the last attempt runs out of memory.
+pub fn supervise(max_retries: usize) -> Result<()> {
+    let exe = std::env::current_exe().context("failed to locate the zkprunner executable")?;
+    let args: Vec<_> = std::env::args_os().skip(1).collect();
+
+    let first = ProveAttempt::first();
+    let mut attempt = first;
+    loop {
+        let (status, allocation_failed) = run_attempt(&exe, &args, &attempt)?;
+        if status.success() {
+            if attempt != first {
+                info!(
+                    "Proving succeeded on attempt {} with {} prover threads instead of {}",
+                    attempt.number,
+                    attempt.num_threads(),
+                    first.num_threads()
+                );
+            }
+            return Ok(());
+        }
+        if !ran_out_of_memory(&status, allocation_failed) {
+            return Err(anyhow!(
+                "zkprunner attempt {} failed: {}",
+                attempt.number,
+                status
+            ));
+        }
+
+        let next = match attempt.degrade() {
+            Some(next) if next.number <= max_retries + 1 => next,
+            Some(_) => {
+                return Err(anyhow!(
+                    "proving ran out of memory on attempt {} with {} prover threads, and no \
+                     retries are left (see --oom-retries)",
+                    attempt.number,
+                    attempt.num_threads()
+                ))
+            }
+            None => {
+                return Err(anyhow!(
+                    "proving ran out of memory on attempt {} on a single prover thread, and the \
+                     settings cannot be degraded any further",
+                    attempt.number
+                ))
+            }
+        };
+        warn!(
+            "Proving ran out of memory on attempt {} with {} prover threads; retrying with {} \
+             set to {}",
+            attempt.number,
+            attempt.num_threads(),
+            THREADS_ENV.join(" and "),
+            next.num_threads()
+        );
+        attempt = next;
+    }
+}
+
+/// Exits the process with [OOM_EXIT_CODE] if it is a supervised attempt and the provided result
+/// is an error caused by an allocation failure, so that the supervisor retries it.
+pub fn exit_on_out_of_memory<T>(result: &Result<T>) {
+    if let Err(e) = result {
+        if is_supervised() && is_out_of_memory(e) {
+            error!("Proving ran out of memory: {:#}", e);
+            std::process::exit(OOM_EXIT_CODE);
+        }
+    }
+}
+
+/// Returns true if the provided error, or any error in its chain, reports an allocation failure.
+pub fn is_out_of_memory(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|cause| match cause.downcast_ref::<ErrorChain>() {
+            Some(chain) => chain
+                .frames()
+                .iter()
+                .any(|frame| is_oom_message(&frame.message)),
+            None => is_oom_message(&cause.to_string()),
+        })
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Runs the specified attempt as a child process with the provided arguments, and returns its
+/// exit status, together with a flag indicating whether it reported a failed allocation.
+///
+/// The error output of the child is forwarded to the error output of this process line by line.
+fn run_attempt(
+    exe: &std::path::Path,
+    args: &[std::ffi::OsString],
+    attempt: &ProveAttempt,
+) -> Result<(ExitStatus, bool)> {
+    let mut child = Command::new(exe)
+        .args(args)
+        .envs(attempt.env_vars())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run zkprunner attempt {}", attempt.number))?;
+
+    let stderr = child.stderr.take().expect("stderr of the attempt is piped");
+    let mut allocation_failed = false;
+    for line in BufReader::new(stderr).lines() {
+        let line = line.context("failed to read the error output of the attempt")?;
+        allocation_failed |= is_oom_message(&line);
+        let _ = writeln!(std::io::stderr(), "{}", line);
+    }
+    let status = child
+        .wait()
+        .with_context(|| format!("failed to wait for zkprunner attempt {}", attempt.number))?;
+    Ok((status, allocation_failed))
+}
+
+/// Returns true if an attempt which exited with the provided status, and which did or did not
+/// report a failed allocation, ran out of memory.
+fn ran_out_of_memory(status: &ExitStatus, allocation_failed: bool) -> bool {
+    if status.code() == Some(OOM_EXIT_CODE) {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        const SIGKILL: i32 = 9;
+        if status.signal() == Some(SIGKILL) {
+            return true;
+        }
+    }
+    // an attempt aborted by the allocation error handler is terminated by a signal
+    allocation_failed && status.code().is_none()
+}
+
+/// Returns true if the provided message reports an allocation failure.
+fn is_oom_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    OOM_MESSAGES
+        .iter()
+        .any(|fragment| message.contains(fragment))
+}